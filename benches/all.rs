@@ -0,0 +1,345 @@
+//! Umbrella Benchmark Runner for StrataDB
+//!
+//! Runs every benchmark suite (or a tagged subset) from a single entry point,
+//! so "everything except the slow ones" is one invocation instead of a list
+//! of `cargo bench --bench ...` lines.
+//!
+//! Each suite owns its own `main()`, Criterion state, and `ResultRecorder`,
+//! so suites are dispatched sequentially as child `cargo bench` processes
+//! rather than linked into this binary. Results land in `results/` exactly as
+//! if each suite had been run by hand.
+//!
+//! Run:   `cargo bench --bench all`
+//! Only:  `cargo bench --bench all -- --only kv,vector,graph`
+//! Skip:  `cargo bench --bench all -- --skip ann`
+//! Fast:  `cargo bench --bench all -- --skip slow`
+//! List:  `cargo bench --bench all -- --list`
+//! Args:  `cargo bench --bench all -- --only redis -- -q --durability cache`
+//! RAM:   `cargo bench --bench all -- --only fill-level --memory-limit 512M`
+//!
+//! With `--memory-limit`, the selected suites are built first without a
+//! limit, then each one runs in a cgroup (or systemd scope) whose memory,
+//! page cache included, is capped at that size. See `harness/memlimit.rs`.
+//!
+//! Arguments after the second `--` go to every selected custom-harness
+//! suite, and a suite refuses flags it doesn't take, so forward the common
+//! flags (see `harness/cli.rs`) when more than one suite is selected.
+
+#[allow(unused)]
+#[path = "harness/memlimit.rs"]
+mod memlimit;
+
+use memlimit::Limiter;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+// ---------------------------------------------------------------------------
+// Suite registry
+// ---------------------------------------------------------------------------
+
+/// A benchmark suite that can be selected by name or by any of its tags.
+struct Suite {
+    /// Bench target name from Cargo.toml.
+    name: &'static str,
+    /// Selection tags (primitive, category, and `slow` for long-running suites).
+    tags: &'static [&'static str],
+    /// Whether the suite uses the custom-harness CLI (`-q`, `--csv`,
+    /// `--durability`) rather than Criterion's argument parser.
+    custom_harness: bool,
+}
+
+const SUITES: &[Suite] = &[
+    Suite { name: "kv", tags: &["latency", "kv"], custom_harness: false },
+    Suite { name: "state", tags: &["latency", "state"], custom_harness: false },
+    Suite { name: "event", tags: &["latency", "event"], custom_harness: false },
+    Suite { name: "json", tags: &["latency", "json"], custom_harness: false },
+    Suite { name: "vector", tags: &["latency", "vector"], custom_harness: false },
+    Suite { name: "branch", tags: &["latency", "branch"], custom_harness: false },
+    Suite { name: "concurrency", tags: &["concurrency", "kv", "slow"], custom_harness: true },
+    Suite { name: "fairness", tags: &["concurrency", "kv", "json", "vector"], custom_harness: true },
+    Suite { name: "multi_branch", tags: &["concurrency", "branch", "kv", "slow"], custom_harness: true },
+    Suite { name: "redis_compare", tags: &["redis", "kv", "state", "event"], custom_harness: true },
+    Suite { name: "memtier", tags: &["redis", "kv"], custom_harness: true },
+    Suite { name: "fill_level", tags: &["fill-level", "kv", "state", "event"], custom_harness: true },
+    Suite { name: "collection_count", tags: &["fill-level", "vector"], custom_harness: true },
+    Suite { name: "collection_delete", tags: &["fill-level", "vector", "slow"], custom_harness: true },
+    Suite { name: "json_pagination", tags: &["fill-level", "json"], custom_harness: true },
+    Suite { name: "branch_fork", tags: &["fill-level", "branch", "slow"], custom_harness: true },
+    Suite { name: "branch_delete", tags: &["fill-level", "branch", "kv", "slow"], custom_harness: true },
+    Suite { name: "insert_order", tags: &["fill-level", "kv"], custom_harness: true },
+    Suite { name: "overwrite_space", tags: &["fill-level", "kv", "slow"], custom_harness: true },
+    Suite { name: "full_scan", tags: &["fill-level", "kv", "slow"], custom_harness: true },
+    Suite { name: "wide_object", tags: &["fill-level", "json"], custom_harness: true },
+    Suite { name: "version_depth", tags: &["fill-level", "kv", "state"], custom_harness: true },
+    Suite { name: "event_retention", tags: &["fill-level", "event", "slow"], custom_harness: true },
+    Suite { name: "memory_pressure", tags: &["fill-level", "kv", "slow"], custom_harness: true },
+    Suite { name: "delete_reclaim", tags: &["space", "kv"], custom_harness: true },
+    Suite { name: "wal_replay", tags: &["recovery", "kv", "slow"], custom_harness: true },
+    Suite { name: "graph_bfs", tags: &["graph"], custom_harness: true },
+    Suite { name: "graph_pagerank", tags: &["graph"], custom_harness: true },
+    Suite { name: "graph_wcc", tags: &["graph"], custom_harness: true },
+    Suite { name: "graph_sssp", tags: &["graph"], custom_harness: true },
+    Suite { name: "graph_cdlp", tags: &["graph"], custom_harness: true },
+    Suite { name: "graph_lcc", tags: &["graph"], custom_harness: true },
+    Suite { name: "graph_load", tags: &["graph"], custom_harness: true },
+    Suite { name: "graph_khop", tags: &["graph"], custom_harness: true },
+    Suite { name: "graph_mutation", tags: &["graph"], custom_harness: true },
+    Suite { name: "graph_reload", tags: &["graph"], custom_harness: true },
+    Suite { name: "ycsb", tags: &["ycsb", "kv", "slow"], custom_harness: true },
+    Suite { name: "rw_sweep", tags: &["ycsb", "kv", "slow"], custom_harness: true },
+    Suite { name: "dbbench", tags: &["dbbench", "kv", "slow"], custom_harness: true },
+    Suite { name: "ann", tags: &["ann", "vector", "slow"], custom_harness: true },
+    Suite { name: "lmdb_compare", tags: &["kv-compare", "kv"], custom_harness: true },
+    Suite { name: "sqlite_compare", tags: &["kv-compare", "kv", "json"], custom_harness: true },
+    Suite { name: "rag", tags: &["scenario", "json", "vector"], custom_harness: true },
+    Suite { name: "chat_memory", tags: &["scenario", "event", "state", "kv", "branch"], custom_harness: true },
+    Suite { name: "ab", tags: &["experiment", "kv", "json"], custom_harness: true },
+    Suite { name: "config_matrix", tags: &["experiment", "kv", "json"], custom_harness: true },
+    Suite { name: "sdk_overhead", tags: &["sdk", "kv", "json"], custom_harness: true },
+];
+
+impl Suite {
+    /// True if `selector` names this suite or one of its tags.
+    fn matches(&self, selector: &str) -> bool {
+        let selector = selector.replace('-', "_");
+        self.name == selector
+            || self
+                .tags
+                .iter()
+                .any(|t| t.replace('-', "_") == selector)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    only: Option<Vec<String>>,
+    skip: Vec<String>,
+    list: bool,
+    dry_run: bool,
+    /// Arguments after a second `--`, forwarded to every custom-harness suite.
+    forward: Vec<String>,
+    /// Memory ceiling in bytes for each suite process.
+    memory_limit: Option<u64>,
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(1);
+}
+
+/// The value after the flag at `args[*i]`, moving `i` onto it.
+fn value<'a>(args: &'a [String], i: &mut usize) -> &'a str {
+    let flag = &args[*i];
+    *i += 1;
+    match args.get(*i) {
+        Some(v) if !v.starts_with("--") => v,
+        _ => usage_error(&format!("{} expects a value", flag)),
+    }
+}
+
+fn parse_args() -> Config {
+    let args: Vec<String> = std::env::args().collect();
+    let mut config = Config {
+        only: None,
+        skip: Vec::new(),
+        list: false,
+        dry_run: false,
+        forward: Vec::new(),
+        memory_limit: None,
+    };
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--only" => config.only = Some(split_list(value(&args, &mut i))),
+            "--skip" => config.skip.extend(split_list(value(&args, &mut i))),
+            "--memory-limit" => {
+                let v = value(&args, &mut i);
+                config.memory_limit = memlimit::parse_size(v);
+                if config.memory_limit.is_none() {
+                    usage_error(&format!("--memory-limit expects a size like 512M or 2G; got {}", v));
+                }
+            }
+            "--list" => config.list = true,
+            "--dry-run" => config.dry_run = true,
+            "--" => {
+                config.forward = args[i + 1..].to_vec();
+                break;
+            }
+            // `cargo bench` passes it to every custom-harness bench
+            "--bench" => {}
+            other => usage_error(&format!("Unknown argument {} (suite flags go after a second --)", other)),
+        }
+        i += 1;
+    }
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Selection
+// ---------------------------------------------------------------------------
+
+fn suite_is_selected(suite: &Suite, config: &Config) -> bool {
+    let included = match &config.only {
+        None => true,
+        Some(selectors) => selectors.iter().any(|s| suite.matches(s)),
+    };
+    included && !config.skip.iter().any(|s| suite.matches(s))
+}
+
+fn warn_unknown_selectors(config: &Config) {
+    let selectors = config.only.iter().flatten().chain(config.skip.iter());
+    for s in selectors {
+        if !SUITES.iter().any(|suite| suite.matches(s)) {
+            eprintln!("WARNING: '{}' matches no suite name or tag (see --list)", s);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Dispatch
+// ---------------------------------------------------------------------------
+
+fn cargo() -> String {
+    std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string())
+}
+
+/// Build every selected suite up front, so compiling doesn't run under a
+/// memory limit meant for the benchmark.
+fn build_suites(selected: &[&Suite]) -> bool {
+    let mut cmd = Command::new(cargo());
+    cmd.args(["bench", "--no-run"]);
+    for suite in selected {
+        cmd.args(["--bench", suite.name]);
+    }
+    matches!(cmd.status(), Ok(status) if status.success())
+}
+
+fn run_suite(suite: &Suite, forward: &[String], limiter: Option<&Limiter>) -> (bool, Duration) {
+    let mut args: Vec<String> = ["bench", "--bench", suite.name]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    if suite.custom_harness && !forward.is_empty() {
+        args.push("--".to_string());
+        args.extend_from_slice(forward);
+    }
+
+    let start = Instant::now();
+    let status = match limiter {
+        Some(limiter) => limiter.status(&cargo(), &args),
+        None => Command::new(cargo()).args(&args).status(),
+    };
+    let ok = match status {
+        Ok(status) => status.success(),
+        Err(e) => {
+            eprintln!("ERROR: failed to launch suite '{}': {}", suite.name, e);
+            false
+        }
+    };
+    (ok, start.elapsed())
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    warn_unknown_selectors(&config);
+
+    let selected: Vec<&Suite> = SUITES
+        .iter()
+        .filter(|s| suite_is_selected(s, &config))
+        .collect();
+
+    if config.list {
+        eprintln!("{:<16} {:<8} TAGS", "SUITE", "SELECTED");
+        for suite in SUITES {
+            let mark = if suite_is_selected(suite, &config) { "yes" } else { "-" };
+            eprintln!("{:<16} {:<8} {}", suite.name, mark, suite.tags.join(","));
+        }
+        return;
+    }
+
+    if selected.is_empty() {
+        eprintln!("No suites selected. Use --list to see suite names and tags.");
+        return;
+    }
+
+    eprintln!("=== StrataDB Benchmark Runner ===");
+    eprintln!(
+        "Suites: {}",
+        selected.iter().map(|s| s.name).collect::<Vec<_>>().join(", ")
+    );
+    if !config.forward.is_empty() {
+        eprintln!("Forwarded args: {}", config.forward.join(" "));
+    }
+    if let Some(limit) = config.memory_limit {
+        eprintln!("Memory limit: {}", memlimit::fmt_size(limit));
+    }
+    eprintln!();
+
+    if config.dry_run {
+        return;
+    }
+
+    let limiter = match config.memory_limit {
+        None => None,
+        Some(limit) => match Limiter::new(limit) {
+            Ok(limiter) => {
+                eprintln!("Enforcing {}", limiter.describe());
+                if !build_suites(&selected) {
+                    eprintln!("ERROR: building the selected suites failed");
+                    std::process::exit(1);
+                }
+                eprintln!();
+                Some(limiter)
+            }
+            Err(e) => {
+                eprintln!("ERROR: can't enforce a memory limit here ({})", e);
+                std::process::exit(1);
+            }
+        },
+    };
+
+    let mut summary = Vec::with_capacity(selected.len());
+    for suite in &selected {
+        eprintln!("### {} ###", suite.name);
+        let oom_before = limiter.as_ref().and_then(|l| l.oom_kills());
+        let (ok, elapsed) = run_suite(suite, &config.forward, limiter.as_ref());
+        let oom_after = limiter.as_ref().and_then(|l| l.oom_kills());
+        let oom_killed = matches!((oom_before, oom_after), (Some(b), Some(a)) if a > b);
+        summary.push((suite.name, ok, oom_killed, elapsed));
+        eprintln!();
+    }
+
+    eprintln!("=== Summary ===");
+    let mut failures = 0;
+    for (name, ok, oom_killed, elapsed) in &summary {
+        if !ok {
+            failures += 1;
+        }
+        let status = match (ok, oom_killed) {
+            (true, _) => "ok",
+            (false, true) => "OOM",
+            (false, false) => "FAILED",
+        };
+        eprintln!("  {:<16} {:<6} {:.1}s", name, status, elapsed.as_secs_f64());
+    }
+
+    if failures > 0 {
+        std::process::exit(1);
+    }
+}
@@ -1,26 +1,80 @@
 //! ANN (Approximate Nearest Neighbor) Benchmark for StrataDB
 //!
 //! Measures the standard ANN trade-off: Recall@k vs Queries Per Second (QPS),
-//! following ann-benchmarks.com methodology with synthetic clustered data.
+//! following ann-benchmarks.com methodology. Uses synthetic clustered data by
+//! default, or a standard dataset (SIFT1M, GloVe, Fashion-MNIST) with its
+//! provided ground truth via `--dataset`. Sweeping the HNSW knobs (`--m`,
+//! `--ef-construction`, `--ef-search`) traces the recall/QPS Pareto frontier.
+//! `--export ann-benchmarks` also writes every run in the ann-benchmarks.com
+//! results layout so Strata can be drawn on their standard plots. `--churn`
+//! deletes and re-upserts part of the corpus between query batches to show
+//! recall and QPS under index churn. `--filtered` measures search constrained
+//! by a metadata filter at several selectivities against unfiltered search.
+//! `--threads N` searches one index from 1 up to N threads and reports
+//! aggregate QPS scaling. `--streaming` searches while one writer upserts new
+//! vectors at several target rates. `--batch-sizes` measures index build
+//! throughput by vectors per insert call. `--compare` also runs the same dataset
+//! through the pure-Rust instant-distance HNSW in-process. `--holdout` and `--perturb` change where queries
+//! come from; every run checks its queries for copies of train vectors.
+//! `--gt-cache DIR` keeps synthetic ground truth on disk across runs.
+//! Recall is reported as a mean plus its p10 and minimum over queries;
+//! `--recall-csv FILE` dumps every query's recall and latency. The static
+//! benchmark also writes a Markdown report next to the results JSON, with a
+//! recommended operating point per k at `--target-recall` (default 0.95).
 //!
-//! Run:    `cargo bench --bench ann`
-//! Quick:  `cargo bench --bench ann -- -q`
-//! Custom: `cargo bench --bench ann -- --scales 10000,50000 --ks 1,10`
-//! CSV:    `cargo bench --bench ann -- --csv`
+//! Run:     `cargo bench --bench ann`
+//! Quick:   `cargo bench --bench ann -- -q`
+//! Custom:  `cargo bench --bench ann -- --scales 10000,50000 --ks 1,10`
+//! Dims:    `cargo bench --bench ann -- --scales 50000 --dims 128,384,768,1536`
+//! Dataset: `cargo bench --bench ann -- --dataset data/ann/sift --queries 10000`
+//! Sweep:   `cargo bench --bench ann -- --m 8,16,32 --ef-search 16,32,64,128,256`
+//! Export:  `cargo bench --bench ann --features ann-hdf5 -- --dataset data/ann/glove-100-angular.hdf5 --export ann-benchmarks`
+//! Churn:   `cargo bench --bench ann -- --scales 100000 --churn 0.1 --churn-rounds 10`
+//! Filter:  `cargo bench --bench ann -- --scales 100000 --ks 10 --filtered`
+//! Threads: `cargo bench --bench ann -- --scales 100000 --ks 10 --threads cores`
+//! Compare: `cargo bench --bench ann --features ann-compare -- --scales 100000 --compare`
+//! Stream:  `cargo bench --bench ann -- --scales 100000 --ks 10 --streaming --readers 4`
+//! Batch:   `cargo bench --bench ann -- --scales 100000 --batch-sizes 1,64,1024`
+//! Leakage: `cargo bench --bench ann -- --scales 100000 --perturb 0.01`
+//! Cached:  `cargo bench --bench ann -- --scales 1000000 --gt-cache data/ann/gt`
+//! Recall:  `cargo bench --bench ann -- --scales 100000 --ks 10 --recall-csv recall.csv`
+//! CSV:     `cargo bench --bench ann -- --csv`
 
 #[allow(unused)]
 #[path = "../harness/mod.rs"]
 mod harness;
 
+mod batch;
+mod churn;
 mod dataset;
+mod export;
+mod filtered;
+mod gt_cache;
+mod loader;
+mod operating_point;
+mod reference;
+mod report;
+mod streaming;
+mod sweep;
+mod threads;
 
-use dataset::{compute_ground_truth, compute_recall, generate_dataset};
+use dataset::{
+    compute_ground_truth, compute_recall, generate_dataset, holdout_queries, per_query_recall,
+    perturbed_queries, query_overlap, AnnDataset, GroundTruth, Metric, QuerySource,
+    RecallDistribution, NEAR_DUPLICATE,
+};
+use sweep::{pareto_frontier, IndexParams, SweepSpec};
+use harness::hdr::LatencyHistogram;
+use harness::perf::{self, PerfPerOp};
+use harness::profile;
 use harness::recorder::ResultRecorder;
-use harness::{create_db, print_hardware_info, DurabilityConfig};
+use harness::{create_db, print_hardware_info, read_rss_bytes, DurabilityConfig};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
-use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult};
-use stratadb::DistanceMetric;
+use strata_benchmarks::fmt::{fmt_duration, fmt_num, scale_label};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{DistanceMetric, Strata};
 
 // ---------------------------------------------------------------------------
 // Defaults
@@ -29,79 +83,87 @@ use stratadb::DistanceMetric;
 const DEFAULT_SCALES: &[usize] = &[10_000, 50_000, 100_000];
 const DEFAULT_KS: &[usize] = &[1, 10, 100];
 const DEFAULT_QUERIES: usize = 100;
-const DIM: usize = 128;
+const DEFAULT_CHURN_ROUNDS: usize = 5;
+/// Dimensions of the synthetic dataset. `--dims 384,768,1536` covers common
+/// sentence-transformer and OpenAI embedding sizes.
+const DEFAULT_DIMS: &[usize] = &[128];
 const SEED: u64 = 0xA00_2026;
+/// Under the results directory.
+const DEFAULT_EXPORT_DIR: &str = "ann-benchmarks";
+const COLLECTION: &str = "ann_bench";
 
 // ---------------------------------------------------------------------------
 // Result type
 // ---------------------------------------------------------------------------
 
 struct AnnResult {
+    /// Scale label for synthetic data, dataset name for `--dataset`.
+    name: String,
     scale: usize,
+    dim: usize,
+    index: IndexParams,
     k: usize,
+    build_secs: f64,
     build_qps: f64,
+    /// RSS growth across the build divided by the vector count.
+    bytes_per_vector: Option<f64>,
+    /// Hardware counters per inserted vector (`perf-counters` feature only).
+    build_perf: Option<PerfPerOp>,
+    /// Hardware counters per query (`perf-counters` feature only).
+    search_perf: Option<PerfPerOp>,
     search_qps: f64,
     recall: f64,
+    /// Per-query recall, in query order.
+    query_recall: Vec<f64>,
+    recall_dist: RecallDistribution,
+    /// Per-query latency, in query order.
     latencies: Vec<Duration>,
+    /// Keys returned for each query, in query order.
+    returned: Vec<Vec<String>>,
     p50: Duration,
     p95: Duration,
     p99: Duration,
+    /// On the recall/QPS frontier among points with the same k.
+    pareto: bool,
 }
 
 // ---------------------------------------------------------------------------
 // Output helpers
 // ---------------------------------------------------------------------------
 
-fn fmt_num(n: u64) -> String {
-    let s = n.to_string();
-    let mut result = String::new();
-    for (i, c) in s.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
-        }
-        result.push(c);
-    }
-    result.chars().rev().collect()
+fn fmt_knob(v: Option<usize>) -> String {
+    v.map(|v| v.to_string()).unwrap_or_else(|| "-".into())
 }
 
-fn fmt_duration(d: Duration) -> String {
-    let nanos = d.as_nanos();
-    if nanos < 1_000 {
-        format!("{:>7}ns", nanos)
-    } else if nanos < 1_000_000 {
-        format!("{:>6.1}us", nanos as f64 / 1_000.0)
-    } else if nanos < 1_000_000_000 {
-        format!("{:>6.1}ms", nanos as f64 / 1_000_000.0)
-    } else {
-        format!("{:>6.2}s ", nanos as f64 / 1_000_000_000.0)
-    }
-}
-
-fn scale_label(n: usize) -> String {
-    if n >= 1_000_000 {
-        format!("{}m", n / 1_000_000)
-    } else if n >= 1_000 {
-        format!("{}k", n / 1_000)
-    } else {
-        format!("{}", n)
+fn print_table_header(sweeping: bool) {
+    if sweeping {
+        eprint!("  {:>4}  {:>5}  {:>5}  {:>1}", "M", "efC", "ef", "");
     }
-}
-
-fn print_table_header() {
     eprintln!(
-        "  {:>10}  {:>5}  {:>10}  {:>10}  {:>8}  {:>10}  {:>10}  {:>10}",
-        "scale", "k", "build QPS", "search QPS", "recall", "p50", "p95", "p99"
+        "  {:>10}  {:>5}  {:>10}  {:>10}  {:>8}  {:>6}  {:>6}  {:>10}  {:>10}  {:>10}",
+        "scale", "k", "build QPS", "search QPS", "recall", "r p10", "r min", "p50", "p95", "p99"
     );
 }
 
-fn print_table_row(r: &AnnResult) {
+fn print_table_row(r: &AnnResult, sweeping: bool) {
+    if sweeping {
+        eprint!(
+            "  {:>4}  {:>5}  {:>5}  {:>1}",
+            fmt_knob(r.index.m),
+            fmt_knob(r.index.ef_construction),
+            fmt_knob(r.index.ef_search),
+            if r.pareto { "*" } else { "" },
+        );
+    }
     eprintln!(
-        "  {:>10}  {:>5}  {:>10}  {:>10}  {:>8.4}  {:>10}  {:>10}  {:>10}",
+        "  {:>10}  {:>5}  {:>10}  {:>10}  {:>8.4}  {:>6.2}  {:>6.2}  {:>10}  {:>10}  {:>10}",
         fmt_num(r.scale as u64),
         r.k,
         fmt_num(r.build_qps as u64),
         fmt_num(r.search_qps as u64),
         r.recall,
+        r.recall_dist.p10,
+        r.recall_dist.min,
         fmt_duration(r.p50),
         fmt_duration(r.p95),
         fmt_duration(r.p99),
@@ -110,37 +172,78 @@ fn print_table_row(r: &AnnResult) {
 
 fn print_quiet(r: &AnnResult) {
     eprintln!(
-        "ann {}@k={}: recall={:.4}, search={} QPS, build={} QPS, p50={}",
+        "ann {}@k={}{}: recall={:.4} (p10 {:.2}, min {:.2}), search={} QPS, build={} QPS, p50={}{}",
         fmt_num(r.scale as u64),
         r.k,
+        r.index.suffix(),
         r.recall,
+        r.recall_dist.p10,
+        r.recall_dist.min,
         fmt_num(r.search_qps as u64),
         fmt_num(r.build_qps as u64),
         fmt_duration(r.p50),
+        if r.pareto { " [pareto]" } else { "" },
     );
 }
 
 fn print_csv_header() {
     println!(
-        "\"scale\",\"k\",\"dim\",\"build_qps\",\"search_qps\",\"recall\",\"p50_us\",\"p95_us\",\"p99_us\""
+        "\"dataset\",\"scale\",\"k\",\"dim\",\"m\",\"ef_construction\",\"ef_search\",\"build_qps\",\"search_qps\",\"recall\",\"recall_min\",\"recall_p10\",\"recall_p50\",\"p50_us\",\"p95_us\",\"p99_us\",\"pareto\""
     );
 }
 
 fn print_csv_row(r: &AnnResult) {
     println!(
-        "{},{},{},{:.2},{:.2},{:.6},{:.1},{:.1},{:.1}",
+        "\"{}\",{},{},{},{},{},{},{:.2},{:.2},{:.6},{:.4},{:.4},{:.4},{:.1},{:.1},{:.1},{}",
+        r.name,
         r.scale,
         r.k,
-        DIM,
+        r.dim,
+        r.index.m.map(|v| v.to_string()).unwrap_or_default(),
+        r.index.ef_construction.map(|v| v.to_string()).unwrap_or_default(),
+        r.index.ef_search.map(|v| v.to_string()).unwrap_or_default(),
         r.build_qps,
         r.search_qps,
         r.recall,
+        r.recall_dist.min,
+        r.recall_dist.p10,
+        r.recall_dist.p50,
         r.p50.as_nanos() as f64 / 1_000.0,
         r.p95.as_nanos() as f64 / 1_000.0,
         r.p99.as_nanos() as f64 / 1_000.0,
+        r.pareto,
     );
 }
 
+const RECALL_CSV_HEADER: &str =
+    "\"dataset\",\"scale\",\"dim\",\"k\",\"m\",\"ef_construction\",\"ef_search\",\"query\",\"recall\",\"latency_us\"";
+
+/// Append one row per query of each result to the `--recall-csv` file.
+fn append_recall_csv(path: &std::path::Path, results: &[AnnResult]) -> std::io::Result<()> {
+    use std::io::Write;
+    let file = std::fs::OpenOptions::new().append(true).open(path)?;
+    let mut w = std::io::BufWriter::new(file);
+    for r in results {
+        for (q, (recall, latency)) in r.query_recall.iter().zip(&r.latencies).enumerate() {
+            writeln!(
+                w,
+                "\"{}\",{},{},{},{},{},{},{},{:.4},{:.1}",
+                r.name,
+                r.scale,
+                r.dim,
+                r.k,
+                r.index.m.map(|v| v.to_string()).unwrap_or_default(),
+                r.index.ef_construction.map(|v| v.to_string()).unwrap_or_default(),
+                r.index.ef_search.map(|v| v.to_string()).unwrap_or_default(),
+                q,
+                recall,
+                latency.as_nanos() as f64 / 1_000.0,
+            )?;
+        }
+    }
+    w.flush()
+}
+
 fn print_reference_points() {
     eprintln!();
     eprintln!("  Published reference points (ann-benchmarks.com, 128d cosine, ~1M vectors):");
@@ -154,19 +257,74 @@ fn print_reference_points() {
 // JSON recording
 // ---------------------------------------------------------------------------
 
-fn record_result(recorder: &mut ResultRecorder, r: &AnnResult, config: &Config) {
+/// Parameters shared by every mode: what was indexed, where the queries came
+/// from, and how many of them overlap the corpus.
+fn dataset_params(
+    name: &str,
+    dataset: &AnnDataset,
+    config: &Config,
+) -> HashMap<String, serde_json::Value> {
     let mut params = HashMap::new();
-    params.insert("scale".into(), serde_json::json!(r.scale));
+    params.insert(
+        "dataset".into(),
+        serde_json::json!(if config.dataset.is_some() { name } else { "synthetic" }),
+    );
+    params.insert("scale".into(), serde_json::json!(dataset.train_vectors.len()));
+    params.insert("dim".into(), serde_json::json!(dataset.dim));
+    params.insert("metric".into(), serde_json::json!(dataset.metric.label()));
+    params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    params.insert("query_source".into(), serde_json::json!(dataset.query_source.label()));
+    params.insert("seed".into(), serde_json::json!(config.seed));
+    match dataset.query_source {
+        QuerySource::Holdout(f) => {
+            params.insert("holdout_fraction".into(), serde_json::json!(f));
+        }
+        QuerySource::Perturbed(sigma) => {
+            params.insert("perturb_sigma".into(), serde_json::json!(sigma));
+        }
+        QuerySource::Generated | QuerySource::Provided => {}
+    }
+    params.insert("queries_exact_train".into(), serde_json::json!(dataset.overlap.exact_train));
+    params.insert("queries_near_train".into(), serde_json::json!(dataset.overlap.near_train));
+    params.insert("queries_repeated".into(), serde_json::json!(dataset.overlap.repeated));
+    params
+}
+
+fn record_result(recorder: &mut ResultRecorder, r: &AnnResult, dataset: &AnnDataset, config: &Config) {
+    let mut params = dataset_params(&r.name, dataset, config);
     params.insert("k".into(), serde_json::json!(r.k));
-    params.insert("dim".into(), serde_json::json!(DIM));
     params.insert("recall".into(), serde_json::json!(r.recall));
+    params.insert("recall_min".into(), serde_json::json!(r.recall_dist.min));
+    params.insert("recall_p10".into(), serde_json::json!(r.recall_dist.p10));
+    params.insert("recall_p50".into(), serde_json::json!(r.recall_dist.p50));
+    params.insert("zero_recall_queries".into(), serde_json::json!(r.recall_dist.zero));
     params.insert("build_qps".into(), serde_json::json!(r.build_qps));
-    params.insert("queries".into(), serde_json::json!(config.queries));
-    params.insert("durability".into(), serde_json::json!(config.durability.label()));
-    params.insert("metric".into(), serde_json::json!("cosine"));
+    if let Some(bpv) = r.bytes_per_vector {
+        params.insert("bytes_per_vector".into(), serde_json::json!(bpv));
+        params.insert("raw_bytes_per_vector".into(), serde_json::json!(r.dim * 4));
+    }
+    if let Some(c) = r.build_perf {
+        params.insert("build_instructions_per_vector".into(), serde_json::json!(c.instructions));
+        params.insert("build_cycles_per_vector".into(), serde_json::json!(c.cycles));
+        params.insert("build_branch_misses_per_vector".into(), serde_json::json!(c.branch_misses));
+        params.insert("build_llc_misses_per_vector".into(), serde_json::json!(c.llc_misses));
+    }
+    params.insert("queries".into(), serde_json::json!(r.latencies.len()));
+    if let Some(m) = r.index.m {
+        params.insert("m".into(), serde_json::json!(m));
+    }
+    if let Some(efc) = r.index.ef_construction {
+        params.insert("ef_construction".into(), serde_json::json!(efc));
+    }
+    if let Some(ef) = r.index.ef_search {
+        params.insert("ef_search".into(), serde_json::json!(ef));
+    }
+    if !config.sweep.is_empty() {
+        params.insert("pareto".into(), serde_json::json!(r.pareto));
+    }
 
     recorder.record(BenchmarkResult {
-        benchmark: format!("ann/{}/k{}/{}d", scale_label(r.scale), r.k, DIM),
+        benchmark: format!("ann/{}/k{}/{}d{}", r.name, r.k, r.dim, r.index.suffix()),
         category: "ann".to_string(),
         parameters: params,
         metrics: BenchmarkMetrics {
@@ -175,8 +333,15 @@ fn record_result(recorder: &mut ResultRecorder, r: &AnnResult, config: &Config)
             p95_ns: Some(r.p95.as_nanos() as u64),
             p99_ns: Some(r.p99.as_nanos() as u64),
             samples: Some(r.latencies.len() as u64),
+            instructions_per_op: r.search_perf.map(|c| c.instructions),
+            cycles_per_op: r.search_perf.map(|c| c.cycles),
+            branch_misses_per_op: r.search_perf.map(|c| c.branch_misses),
+            llc_misses_per_op: r.search_perf.map(|c| c.llc_misses),
             ..Default::default()
         },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
     });
 }
 
@@ -187,70 +352,565 @@ fn record_result(recorder: &mut ResultRecorder, r: &AnnResult, config: &Config)
 #[derive(Clone)]
 struct Config {
     scales: Vec<usize>,
+    dims: Vec<usize>,
     ks: Vec<usize>,
     queries: usize,
+    dataset: Option<PathBuf>,
+    sweep: SweepSpec,
+    /// Write ann-benchmarks.com result files under this directory.
+    export_dir: Option<PathBuf>,
+    /// Fraction of the corpus to delete and re-upsert per churn round.
+    churn: Option<f64>,
+    churn_rounds: usize,
+    /// Run filtered search at `selectivities` instead of the static benchmark.
+    filtered: bool,
+    selectivities: Vec<f64>,
+    overfetch: f64,
+    /// Thread counts for concurrent search; empty = single-threaded benchmark.
+    threads: Vec<usize>,
+    /// Search while upserting at `insert_rates` instead of the static benchmark.
+    streaming: bool,
+    insert_rates: Vec<Option<u64>>,
+    stream_secs: u64,
+    readers: usize,
+    /// Build throughput at these vectors-per-call; empty = off.
+    batch_sizes: Vec<usize>,
+    /// Also run the in-process reference engine (static benchmark only).
+    compare: bool,
+    /// `--holdout` / `--perturb`; `None` keeps the generator's or dataset's own queries.
+    query_source: Option<QuerySource>,
+    /// Load and store synthetic ground truth here instead of always recomputing.
+    gt_cache: Option<PathBuf>,
+    /// Write every query's recall and latency here (static benchmark only).
+    recall_csv: Option<PathBuf>,
+    /// Recall the report's recommended operating point must reach.
+    target_recall: f64,
     durability: DurabilityConfig,
+    /// Seed for synthetic data, query sources and churn/filter choices.
+    seed: u64,
     csv: bool,
     quiet: bool,
 }
 
+/// The current flag's value as a list of counts above zero.
+fn positive_list(args: &mut harness::cli::Args) -> Vec<usize> {
+    args.list_checked("a list of numbers above 0, like 1,64,1024", |&v| v > 0)
+}
+
 fn parse_args() -> Config {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args = harness::cli::Args::parse();
     let mut config = Config {
         scales: DEFAULT_SCALES.to_vec(),
+        dims: DEFAULT_DIMS.to_vec(),
         ks: DEFAULT_KS.to_vec(),
         queries: DEFAULT_QUERIES,
+        dataset: None,
+        sweep: SweepSpec::default(),
+        export_dir: None,
+        churn: None,
+        churn_rounds: DEFAULT_CHURN_ROUNDS,
+        filtered: false,
+        selectivities: filtered::DEFAULT_SELECTIVITIES.to_vec(),
+        overfetch: filtered::DEFAULT_OVERFETCH,
+        threads: Vec::new(),
+        streaming: false,
+        insert_rates: streaming::DEFAULT_INSERT_RATES.to_vec(),
+        stream_secs: streaming::DEFAULT_STREAM_SECS,
+        readers: streaming::DEFAULT_READERS,
+        batch_sizes: Vec::new(),
+        compare: false,
+        query_source: None,
+        gt_cache: None,
+        recall_csv: None,
+        target_recall: report::DEFAULT_TARGET_RECALL,
         durability: DurabilityConfig::Cache,
+        seed: SEED,
         csv: false,
         quiet: false,
     };
+    let mut export_dir = harness::cli::results_dir().join(DEFAULT_EXPORT_DIR);
+    let mut export_ann = false;
 
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
-            "--scales" => {
-                i += 1;
-                if i < args.len() {
-                    config.scales = args[i]
-                        .split(',')
-                        .filter_map(|s| s.trim().parse().ok())
-                        .collect();
-                }
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--scales" => config.scales = args.list(),
+            "--dims" => config.dims = positive_list(&mut args),
+            "--ks" => config.ks = args.list(),
+            "--queries" => config.queries = args.parse_value(),
+            "--dataset" => config.dataset = Some(PathBuf::from(args.value())),
+            "--m" => config.sweep.m = positive_list(&mut args),
+            "--ef-construction" => config.sweep.ef_construction = positive_list(&mut args),
+            "--ef-search" => config.sweep.ef_search = positive_list(&mut args),
+            "--export" => match args.value().as_str() {
+                "ann-benchmarks" => export_ann = true,
+                other => harness::cli::usage_error(&format!("--export expects ann-benchmarks; got {}", other)),
+            },
+            "--export-dir" => export_dir = PathBuf::from(args.value()),
+            "--churn" => {
+                config.churn = Some(args.parse_checked("a fraction in (0, 1]", |f: &f64| *f > 0.0 && *f <= 1.0));
             }
-            "--ks" => {
-                i += 1;
-                if i < args.len() {
-                    config.ks = args[i]
-                        .split(',')
-                        .filter_map(|s| s.trim().parse().ok())
-                        .collect();
+            "--churn-rounds" => config.churn_rounds = args.parse_value(),
+            "--filtered" => config.filtered = true,
+            "--selectivities" => {
+                config.filtered = true;
+                config.selectivities = args.list_checked("fractions in (0, 1)", |s: &f64| *s > 0.0 && *s < 1.0);
+            }
+            "--overfetch" => config.overfetch = args.parse_checked("a factor >= 1", |f: &f64| *f >= 1.0),
+            "--streaming" => config.streaming = true,
+            "--insert-rates" => {
+                config.streaming = true;
+                let v = args.value();
+                config.insert_rates = streaming::parse_rates(&v).unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--insert-rates expects a list like 0,1000,max; got {}", v))
+                });
+            }
+            "--stream-secs" => config.stream_secs = args.parse_checked("seconds above 0", |&s| s > 0),
+            "--readers" => config.readers = args.parse_checked("a reader count above 0", |&n| n > 0),
+            "--batch" => {
+                if config.batch_sizes.is_empty() {
+                    config.batch_sizes = batch::DEFAULT_BATCH_SIZES.to_vec();
                 }
             }
-            "--queries" => {
-                i += 1;
-                if i < args.len() {
-                    config.queries = args[i].parse().unwrap_or(DEFAULT_QUERIES);
+            "--batch-sizes" => config.batch_sizes = positive_list(&mut args),
+            "--compare" => config.compare = true,
+            "--holdout" => {
+                let f = args.parse_checked("a fraction in (0, 1)", |f: &f64| *f > 0.0 && *f < 1.0);
+                set_query_source(&mut config, QuerySource::Holdout(f));
+            }
+            "--perturb" => {
+                let sigma = args.parse_checked("a noise std-dev >= 0", |s: &f64| *s >= 0.0);
+                set_query_source(&mut config, QuerySource::Perturbed(sigma));
+            }
+            "--gt-cache" => config.gt_cache = Some(PathBuf::from(args.value())),
+            "--recall-csv" => config.recall_csv = Some(PathBuf::from(args.value())),
+            "--target-recall" => {
+                config.target_recall = args.parse_checked("a recall in (0, 1]", |r: &f64| *r > 0.0 && *r <= 1.0);
+            }
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.seed = args.seed_or(SEED);
+    // One count (or `cores`) means 1, 2, 4, ... up to it
+    config.threads = match args.threads.as_deref() {
+        Some(&[max]) => threads::thread_steps(max),
+        Some(list) => list.to_vec(),
+        None => Vec::new(),
+    };
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    if export_ann {
+        config.export_dir = Some(export_dir);
+    }
+    config
+}
+
+fn set_query_source(config: &mut Config, source: QuerySource) {
+    if config.query_source.is_some() {
+        eprintln!("--holdout and --perturb cannot be combined");
+        std::process::exit(1);
+    }
+    config.query_source = Some(source);
+}
+
+// ---------------------------------------------------------------------------
+// Build and search
+// ---------------------------------------------------------------------------
+
+fn distance_metric(metric: Metric) -> DistanceMetric {
+    match metric {
+        Metric::Angular => DistanceMetric::Cosine,
+        Metric::Euclidean => DistanceMetric::Euclidean,
+    }
+}
+
+/// Run the first `queries` query vectors at `k`. Returns per-query
+/// latencies and returned keys (both in query order) and the wall time.
+fn search_batch(
+    db: &Strata,
+    dataset: &AnnDataset,
+    queries: usize,
+    k: usize,
+) -> (Vec<Duration>, Vec<Vec<String>>, Duration) {
+    let mut latencies = Vec::with_capacity(queries);
+    let mut ann_results = Vec::with_capacity(queries);
+
+    let search_start = Instant::now();
+    for q in 0..queries {
+        let query = dataset.query_vectors[q].clone();
+        let op_start = Instant::now();
+        let results = db.vector_search(COLLECTION, query, k as u64).unwrap();
+        latencies.push(op_start.elapsed());
+
+        let keys: Vec<String> = results.iter().map(|m| m.key.clone()).collect();
+        ann_results.push(keys);
+    }
+    (latencies, ann_results, search_start.elapsed())
+}
+
+/// (p50, p95, p99) of unsorted latencies.
+fn percentiles(latencies: &[Duration]) -> (Duration, Duration, Duration) {
+    let hist: LatencyHistogram = latencies.iter().copied().collect();
+    (hist.p50(), hist.p95(), hist.p99())
+}
+
+/// Truncate ground truth to `k` neighbors per query.
+fn truncate_ground_truth(ground_truth: &GroundTruth, k: usize) -> GroundTruth {
+    GroundTruth {
+        neighbors: ground_truth
+            .neighbors
+            .iter()
+            .map(|nn| nn.iter().take(k).copied().collect())
+            .collect(),
+        k,
+    }
+}
+
+/// Create the benchmark collection and upsert every train vector.
+/// Returns the build wall time.
+fn build_index(db: &Strata, dataset: &AnnDataset) -> Duration {
+    db.vector_create_collection(COLLECTION, dataset.dim as u64, distance_metric(dataset.metric))
+        .unwrap();
+
+    let build_start = Instant::now();
+    for i in 0..dataset.train_vectors.len() {
+        db.vector_upsert(
+            COLLECTION,
+            &dataset.train_keys[i],
+            dataset.train_vectors[i].clone(),
+            None,
+        )
+        .unwrap();
+    }
+    build_start.elapsed()
+}
+
+/// Memory the index took per vector: RSS growth across the build over the
+/// vector count. The dataset is already resident before the build, so only
+/// the engine's copy and its graph count. Freed memory the allocator keeps
+/// from an earlier build point is reused first, so later points in a sweep
+/// can read low.
+fn bytes_per_vector(before: Option<u64>, after: Option<u64>, vectors: usize) -> Option<f64> {
+    let (before, after) = (before?, after?);
+    (vectors > 0).then(|| after.saturating_sub(before) as f64 / vectors as f64)
+}
+
+/// The requested ks that `ground_truth` covers; the rest are skipped with a
+/// warning.
+fn usable_ks(config: &Config, ground_truth: &GroundTruth) -> Vec<usize> {
+    config
+        .ks
+        .iter()
+        .copied()
+        .filter(|&k| {
+            let ok = k <= ground_truth.k;
+            if !ok && !config.csv {
+                eprintln!(
+                    "  k={} skipped: ground truth only has {} neighbors per query",
+                    k, ground_truth.k
+                );
+            }
+            ok
+        })
+        .collect()
+}
+
+/// Build an index over `dataset` for every (M, ef_construction) point and
+/// search it at every ef_search and k. `ground_truth` must cover at least
+/// the largest k; larger ks are skipped. Each point is also added to
+/// `report_rows`.
+fn run_dataset(
+    name: &str,
+    dataset: &AnnDataset,
+    ground_truth: &GroundTruth,
+    config: &Config,
+    recorder: &mut ResultRecorder,
+    report_rows: &mut Vec<report::Row>,
+) {
+    let scale = dataset.train_vectors.len();
+    let queries = config.queries.min(dataset.query_vectors.len());
+    let verbose = !config.csv && !config.quiet;
+    let sweeping = !config.sweep.is_empty();
+
+    let ks = usable_ks(config, ground_truth);
+
+    // ann-benchmarks names datasets <source>-<dim>-<distance>
+    let export_name = if config.dataset.is_some() {
+        name.to_string()
+    } else {
+        format!("synthetic{}-{}-{}", name, dataset.dim, export::distance_name(dataset.metric))
+    };
+
+    let mut results = Vec::new();
+
+    for build in config.sweep.build_points() {
+        // Build index (insert all vectors)
+        if verbose {
+            eprint!(
+                "  Building index ({} vectors{})...",
+                fmt_num(scale as u64),
+                if sweeping { format!(", M={} efC={}", fmt_knob(build.m), fmt_knob(build.ef_construction)) } else { String::new() }
+            );
+        }
+        let rss_before = read_rss_bytes();
+        let db = create_db(config.durability);
+        build.apply_build(&db.db).unwrap_or_else(|e| panic!("{}", e));
+        let perf_section = perf::start();
+        let profile_section = profile::start();
+        let build_elapsed = build_index(&db.db, dataset);
+        if let Some(section) = profile_section {
+            section.finish();
+        }
+        let build_perf = perf_section.and_then(perf::Section::finish).map(|c| c.per_op(scale));
+        let build_secs = build_elapsed.as_secs_f64();
+        let build_qps = scale as f64 / build_secs;
+        let bytes_per_vector = bytes_per_vector(rss_before, read_rss_bytes(), scale);
+
+        if verbose {
+            eprintln!(
+                " {:.2}s ({} inserts/s{})",
+                build_elapsed.as_secs_f64(),
+                fmt_num(build_qps as u64),
+                bytes_per_vector
+                    .map(|b| format!(", {:.0} B/vector vs {} B raw", b, dataset.dim * 4))
+                    .unwrap_or_default()
+            );
+            if let Some(c) = build_perf {
+                eprintln!(
+                    "    per vector: {:.0} instructions, {:.0} cycles (ipc {:.2}), {:.1} branch misses, {:.1} LLC misses",
+                    c.instructions, c.cycles, c.ipc(), c.branch_misses, c.llc_misses
+                );
+            }
+        }
+
+        for ef_search in config.sweep.search_values() {
+            let index = IndexParams { ef_search, ..build };
+            index.apply_search(&db.db).unwrap_or_else(|e| panic!("{}", e));
+
+            // Search for each k value
+            for &k in &ks {
+                let gt_k = truncate_ground_truth(ground_truth, k);
+
+                let perf_section = perf::start();
+                let profile_section = profile::start();
+                let (latencies, ann_results, search_elapsed) =
+                    search_batch(&db.db, dataset, queries, k);
+                if let Some(section) = profile_section {
+                    section.finish();
                 }
+                let search_perf = perf_section.and_then(perf::Section::finish).map(|c| c.per_op(queries));
+                let search_qps = queries as f64 / search_elapsed.as_secs_f64();
+
+                // Compute recall, per query and overall
+                let query_recall = per_query_recall(&ann_results, &gt_k, dataset);
+                let recall_dist = RecallDistribution::from_per_query(&query_recall);
+                let recall = compute_recall(&ann_results, &gt_k, dataset);
+
+                // Latencies stay in query order for export
+                let (p50, p95, p99) = percentiles(&latencies);
+
+                results.push(AnnResult {
+                    name: name.to_string(),
+                    scale,
+                    dim: dataset.dim,
+                    index,
+                    k,
+                    build_secs,
+                    build_qps,
+                    bytes_per_vector,
+                    build_perf,
+                    search_perf,
+                    search_qps,
+                    recall,
+                    query_recall,
+                    recall_dist,
+                    latencies,
+                    returned: ann_results,
+                    p50,
+                    p95,
+                    p99,
+                    pareto: false,
+                });
             }
-            "--durability" => {
-                i += 1;
-                if i < args.len() {
-                    config.durability = match args[i].as_str() {
-                        "cache" => DurabilityConfig::Cache,
-                        "standard" => DurabilityConfig::Standard,
-                        "always" => DurabilityConfig::Always,
-                        _ => DurabilityConfig::Cache,
-                    };
+        }
+    }
+
+    // Mark the recall/QPS frontier separately for each k
+    for &k in &ks {
+        let idx: Vec<usize> = (0..results.len()).filter(|&i| results[i].k == k).collect();
+        let points: Vec<(f64, f64)> = idx.iter().map(|&i| (results[i].recall, results[i].search_qps)).collect();
+        for (&i, on_frontier) in idx.iter().zip(pareto_frontier(&points)) {
+            results[i].pareto = on_frontier;
+        }
+    }
+
+    if verbose {
+        eprintln!();
+        eprintln!(
+            "--- {}: {} vectors, {}d, {} ---",
+            name,
+            fmt_num(scale as u64),
+            dataset.dim,
+            dataset.metric.label()
+        );
+        print_table_header(sweeping);
+    }
+
+    // Output, grouped by k and sorted by recall so the frontier reads top-down
+    results.sort_by(|a, b| {
+        a.k.cmp(&b.k)
+            .then(b.recall.partial_cmp(&a.recall).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    for result in &results {
+        if config.csv {
+            print_csv_row(result);
+        } else if config.quiet {
+            print_quiet(result);
+        } else {
+            print_table_row(result, sweeping);
+        }
+
+        record_result(recorder, result, dataset, config);
+        report_rows.push(report::Row::new(result, dataset.metric.label()));
+
+        if let Some(root) = &config.export_dir {
+            match export::write_run(root, &export_name, dataset, result) {
+                Ok(path) if verbose => eprintln!("  exported {}", path.display()),
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("Export failed: {}", e);
+                    std::process::exit(1);
                 }
             }
-            "--csv" => config.csv = true,
-            "-q" => config.quiet = true,
-            _ => {}
         }
-        i += 1;
     }
 
-    config
+    if verbose {
+        if sweeping {
+            eprintln!("  (* = on the recall/QPS Pareto frontier for that k)");
+        }
+        let zero: Vec<String> = results
+            .iter()
+            .filter(|r| r.recall_dist.zero > 0)
+            .map(|r| format!("k={}{}: {}", r.k, r.index.suffix(), r.recall_dist.zero))
+            .collect();
+        if !zero.is_empty() {
+            eprintln!("  Queries with recall 0: {}", zero.join(", "));
+        }
+        eprintln!();
+    }
+
+    if let Some(path) = &config.recall_csv {
+        if let Err(e) = append_recall_csv(path, &results) {
+            eprintln!("Failed to write {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+
+    if config.compare {
+        reference::compare(name, dataset, ground_truth, config, &results, recorder);
+    }
+}
+
+/// Generate the synthetic dataset for one (scale, dim) point, with queries
+/// from the configured source. Held-out queries come from a larger pool so
+/// the index still holds `scale` vectors.
+fn synthetic_dataset(scale: usize, dim: usize, config: &Config) -> AnnDataset {
+    match config.query_source {
+        Some(QuerySource::Holdout(f)) => {
+            let held = ((scale as f64 * f / (1.0 - f)).ceil() as usize).max(1);
+            let mut d = generate_dataset(scale + held, 0, dim, config.seed);
+            holdout_queries(&mut d, f, held, config.queries, config.seed);
+            d
+        }
+        Some(QuerySource::Perturbed(sigma)) => {
+            let mut d = generate_dataset(scale, 0, dim, config.seed);
+            perturbed_queries(&mut d, sigma, config.queries, config.seed);
+            d
+        }
+        _ => generate_dataset(scale, config.queries, dim, config.seed),
+    }
+}
+
+/// Brute-force ground truth (at max k) for a synthetic dataset, through the
+/// `--gt-cache` directory when one is set.
+fn synthetic_ground_truth(
+    dataset: &AnnDataset,
+    scale: usize,
+    dim: usize,
+    k: usize,
+    config: &Config,
+) -> GroundTruth {
+    let verbose = !config.csv && !config.quiet;
+    let cache = config
+        .gt_cache
+        .as_ref()
+        .map(|dir| gt_cache::path(dir, config.seed, scale, dim, config.queries, config.query_source, k));
+
+    if let Some(ground_truth) = cache.as_ref().and_then(|p| gt_cache::load(p, dataset, k)) {
+        if verbose {
+            eprintln!("  Ground truth (k={}) loaded from {}", k, cache.unwrap().display());
+        }
+        return ground_truth;
+    }
+
+    if verbose {
+        eprint!("  Computing ground truth (brute-force, k={})...", k);
+    }
+    let gt_start = Instant::now();
+    let ground_truth = compute_ground_truth(dataset, k);
+    if verbose {
+        eprintln!(" {:.2}s", gt_start.elapsed().as_secs_f64());
+    }
+    if let Some(path) = &cache {
+        if let Err(e) = gt_cache::store(path, dataset, &ground_truth) {
+            eprintln!("  Warning: could not cache ground truth: {}", e);
+        }
+    }
+    ground_truth
+}
+
+/// Warn when queries duplicate train vectors or each other; recall against
+/// such queries is inflated.
+fn warn_overlap(dataset: &AnnDataset, config: &Config) {
+    let o = dataset.overlap;
+    if config.csv || o.is_clean() {
+        return;
+    }
+    eprintln!(
+        "  Warning: of {} queries, {} are within {} (relative L2) of a train vector ({} exact copies) and {} repeat an earlier query; recall may be inflated",
+        o.queries, o.near_train, NEAR_DUPLICATE, o.exact_train, o.repeated
+    );
+}
+
+/// Run the static-index benchmark, or the churn / filtered / threads /
+/// streaming / batch benchmark. Only the static benchmark adds report rows.
+fn run(
+    name: &str,
+    dataset: &AnnDataset,
+    ground_truth: &GroundTruth,
+    config: &Config,
+    recorder: &mut ResultRecorder,
+    report_rows: &mut Vec<report::Row>,
+) {
+    let mut guard_params = HashMap::new();
+    guard_params.insert("dataset".into(), serde_json::json!(name));
+    guard_params.insert("dim".into(), serde_json::json!(dataset.dim));
+    guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    let guard_name = format!("ann/{}/{}d", name, dataset.dim);
+    recorder.guard(&guard_name, guard_params, |recorder| match config.churn {
+        Some(fraction) => churn::run_churn(name, dataset, ground_truth, config, fraction, recorder),
+        None if config.filtered => filtered::run_filtered(name, dataset, ground_truth, config, recorder),
+        None if !config.threads.is_empty() => {
+            threads::run_threads(name, dataset, ground_truth, config, recorder)
+        }
+        None if config.streaming => {
+            streaming::run_streaming(name, dataset, ground_truth, config, recorder)
+        }
+        None if !config.batch_sizes.is_empty() => batch::run_batch(name, dataset, config, recorder),
+        None => run_dataset(name, dataset, ground_truth, config, recorder, report_rows),
+    });
 }
 
 // ---------------------------------------------------------------------------
@@ -258,176 +918,226 @@ fn parse_args() -> Config {
 // ---------------------------------------------------------------------------
 
 fn main() {
-    let config = parse_args();
+    let mut config = parse_args();
     print_hardware_info();
+    let verbose = !config.csv && !config.quiet;
 
-    if !config.csv && !config.quiet {
+    if verbose {
         eprintln!("=== StrataDB ANN Benchmark ===");
         eprintln!("Measures Recall@k vs QPS (ann-benchmarks.com methodology)");
         eprintln!();
-        eprintln!(
-            "Parameters: {}d, cosine, {} queries, {} mode",
-            DIM, config.queries, config.durability.label()
-        );
-        eprintln!(
-            "Scales: {:?}",
-            config.scales
-        );
+        match &config.dataset {
+            Some(path) => eprintln!(
+                "Parameters: dataset {}, {} queries, {} mode",
+                path.display(),
+                config.queries,
+                config.durability.label()
+            ),
+            None => {
+                eprintln!(
+                    "Parameters: {:?}d, cosine, {} queries, {} mode",
+                    config.dims, config.queries, config.durability.label()
+                );
+                eprintln!(
+                    "Scales: {:?}",
+                    config.scales
+                );
+            }
+        }
         eprintln!(
             "k values: {:?}",
             config.ks
         );
+        match config.query_source {
+            Some(QuerySource::Holdout(f)) => eprintln!("Queries: {:.1}% of the corpus held out", f * 100.0),
+            Some(QuerySource::Perturbed(sigma)) => eprintln!("Queries: train vectors + N(0, {}) noise", sigma),
+            _ => {}
+        }
         eprintln!();
     }
 
     if config.csv {
-        print_csv_header();
+        if config.churn.is_some() {
+            churn::print_csv_header();
+        } else if config.filtered {
+            filtered::print_csv_header();
+        } else if !config.threads.is_empty() {
+            threads::print_csv_header();
+        } else if config.streaming {
+            streaming::print_csv_header();
+        } else if !config.batch_sizes.is_empty() {
+            batch::print_csv_header();
+        } else {
+            print_csv_header();
+        }
     }
 
-    let mut recorder = ResultRecorder::new("ann");
-    let max_k = *config.ks.iter().max().unwrap_or(&10);
+    let modes = [
+        config.churn.is_some(),
+        config.filtered,
+        !config.threads.is_empty(),
+        config.streaming,
+        !config.batch_sizes.is_empty(),
+        config.export_dir.is_some(),
+    ];
+    if modes.iter().filter(|&&m| m).count() > 1 {
+        eprintln!("--churn, --filtered, --threads, --streaming, --batch-sizes and --export cannot be combined");
+        std::process::exit(1);
+    }
 
-    for &scale in &config.scales {
-        // Phase 1: Generate dataset
-        if !config.csv && !config.quiet {
-            eprint!(
-                "  Generating {} vectors ({}d, {} clusters)...",
-                fmt_num(scale as u64),
-                DIM,
-                10
-            );
-        }
-        let gen_start = Instant::now();
-        let dataset = generate_dataset(scale, config.queries, DIM, SEED);
-        let gen_elapsed = gen_start.elapsed();
-        if !config.csv && !config.quiet {
-            eprintln!(" {:.2}s", gen_elapsed.as_secs_f64());
-        }
+    let static_run = !(config.churn.is_some()
+        || config.filtered
+        || !config.threads.is_empty()
+        || config.streaming
+        || !config.batch_sizes.is_empty());
+    if config.compare && !static_run {
+        eprintln!("--compare runs with the static benchmark only, not --churn, --filtered, --threads, --streaming or --batch-sizes");
+        std::process::exit(1);
+    }
 
-        // Phase 2: Compute brute-force ground truth (at max k)
-        if !config.csv && !config.quiet {
-            eprint!("  Computing ground truth (brute-force, k={})...", max_k);
-        }
-        let gt_start = Instant::now();
-        let ground_truth = compute_ground_truth(&dataset, max_k);
-        let gt_elapsed = gt_start.elapsed();
-        if !config.csv && !config.quiet {
-            eprintln!(" {:.2}s", gt_elapsed.as_secs_f64());
-        }
+    if config.recall_csv.is_some() && !static_run {
+        eprintln!("--recall-csv runs with the static benchmark only, not --churn, --filtered, --threads, --streaming or --batch-sizes");
+        std::process::exit(1);
+    }
 
-        // Phase 3: Build index (insert all vectors)
-        if !config.csv && !config.quiet {
-            eprint!("  Building index ({} vectors)...", fmt_num(scale as u64));
-        }
-        let db = create_db(config.durability);
-        db.db
-            .vector_create_collection("ann_bench", DIM as u64, DistanceMetric::Cosine)
-            .unwrap();
-
-        let build_start = Instant::now();
-        for i in 0..scale {
-            db.db
-                .vector_upsert(
-                    "ann_bench",
-                    &dataset.train_keys[i],
-                    dataset.train_vectors[i].clone(),
-                    None,
-                )
-                .unwrap();
+    if let Some(path) = &config.recall_csv {
+        if let Err(e) = std::fs::write(path, format!("{}\n", RECALL_CSV_HEADER)) {
+            eprintln!("Failed to create {}: {}", path.display(), e);
+            std::process::exit(1);
         }
-        let build_elapsed = build_start.elapsed();
-        let build_qps = scale as f64 / build_elapsed.as_secs_f64();
+    }
+
+    if config.compare && !reference::AVAILABLE {
+        eprintln!("--compare builds an instant-distance index; rebuild with `--features ann-compare`");
+        std::process::exit(1);
+    }
 
-        if !config.csv && !config.quiet {
+    if config.export_dir.is_some() && !export::AVAILABLE {
+        eprintln!("--export ann-benchmarks writes HDF5 files; rebuild with `--features ann-hdf5`");
+        std::process::exit(1);
+    }
+
+    if !config.sweep.is_empty() {
+        let probe = create_db(DurabilityConfig::Cache);
+        for key in config.sweep.retain_supported(&probe.db) {
+            eprintln!("  Warning: config_set(\"{}\") rejected by this Strata build; not sweeping it", key);
+        }
+        if verbose && !config.sweep.is_empty() {
             eprintln!(
-                " {:.2}s ({} inserts/s)",
-                build_elapsed.as_secs_f64(),
-                fmt_num(build_qps as u64)
+                "  Sweep: M={:?} ef_construction={:?} ef_search={:?}",
+                config.sweep.m, config.sweep.ef_construction, config.sweep.ef_search
             );
+            eprintln!();
         }
+    }
 
-        // Print scale header
-        if !config.csv && !config.quiet {
+    let mut recorder = ResultRecorder::new("ann");
+    let mut report_rows = Vec::new();
+    let max_k = *config.ks.iter().max().unwrap_or(&10);
+
+    if let Some(path) = &config.dataset {
+        // Real dataset: load it with its provided ground truth
+        if verbose {
+            eprint!("  Loading {}...", path.display());
+        }
+        let load_start = Instant::now();
+        let mut loaded = loader::load_dataset(path).unwrap_or_else(|e| {
             eprintln!();
+            eprintln!("Failed to load dataset: {}", e);
+            std::process::exit(1);
+        });
+        if verbose {
             eprintln!(
-                "--- {} vectors, {}d, cosine ---",
-                fmt_num(scale as u64),
-                DIM
+                " {:.2}s ({} train, {} queries, {}d, {}, ground truth k={})",
+                load_start.elapsed().as_secs_f64(),
+                fmt_num(loaded.dataset.train_vectors.len() as u64),
+                fmt_num(loaded.dataset.query_vectors.len() as u64),
+                loaded.dataset.dim,
+                loaded.dataset.metric.label(),
+                loaded.ground_truth.k,
             );
-            print_table_header();
         }
-
-        // Phase 4: Search for each k value
-        for &k in &config.ks {
-            // Truncate ground truth to this k
-            let gt_k = dataset::GroundTruth {
-                neighbors: ground_truth
-                    .neighbors
-                    .iter()
-                    .map(|nn| nn.iter().take(k).copied().collect())
-                    .collect(),
-                k,
-            };
-
-            let mut latencies = Vec::with_capacity(config.queries);
-            let mut ann_results = Vec::with_capacity(config.queries);
-
-            let search_start = Instant::now();
-            for q in 0..config.queries {
-                let query = dataset.query_vectors[q].clone();
-                let op_start = Instant::now();
-                let results = db.db.vector_search("ann_bench", query, k as u64).unwrap();
-                latencies.push(op_start.elapsed());
-
-                let keys: Vec<String> = results.iter().map(|m| m.key.clone()).collect();
-                ann_results.push(keys);
+        if let Some(source) = config.query_source {
+            // The shipped ground truth is for the shipped test set only
+            match source {
+                QuerySource::Holdout(f) => {
+                    let held = ((loaded.dataset.train_vectors.len() as f64 * f).round() as usize).max(1);
+                    holdout_queries(&mut loaded.dataset, f, held, config.queries, config.seed);
+                }
+                QuerySource::Perturbed(sigma) => {
+                    perturbed_queries(&mut loaded.dataset, sigma, config.queries, config.seed)
+                }
+                QuerySource::Generated | QuerySource::Provided => {}
             }
-            let search_elapsed = search_start.elapsed();
-            let search_qps = config.queries as f64 / search_elapsed.as_secs_f64();
-
-            // Compute recall
-            let recall = compute_recall(&ann_results, &gt_k, &dataset);
-
-            // Compute percentiles
-            latencies.sort_unstable();
-            let len = latencies.len();
-            let p50 = latencies[len * 50 / 100];
-            let p95 = latencies[(len * 95 / 100).min(len - 1)];
-            let p99 = latencies[(len * 99 / 100).min(len - 1)];
-
-            let result = AnnResult {
-                scale,
-                k,
-                build_qps,
-                search_qps,
-                recall,
-                latencies,
-                p50,
-                p95,
-                p99,
-            };
-
-            // Output
-            if config.csv {
-                print_csv_row(&result);
-            } else if config.quiet {
-                print_quiet(&result);
-            } else {
-                print_table_row(&result);
+            if verbose {
+                eprint!("  Recomputing ground truth for {} queries (brute-force, k={})...", source.label(), max_k);
+            }
+            let gt_start = Instant::now();
+            loaded.ground_truth = compute_ground_truth(&loaded.dataset, max_k);
+            if verbose {
+                eprintln!(" {:.2}s", gt_start.elapsed().as_secs_f64());
             }
-
-            record_result(&mut recorder, &result, &config);
         }
+        loaded.dataset.overlap = query_overlap(&loaded.dataset, &loaded.ground_truth, config.queries);
+        warn_overlap(&loaded.dataset, &config);
+        run(
+            &loaded.name,
+            &loaded.dataset,
+            &loaded.ground_truth,
+            &config,
+            &mut recorder,
+            &mut report_rows,
+        );
+    } else {
+        for &dim in &config.dims {
+            for &scale in &config.scales {
+                // Generate dataset
+                if verbose {
+                    eprint!(
+                        "  Generating {} vectors ({}d, {} clusters)...",
+                        fmt_num(scale as u64),
+                        dim,
+                        10
+                    );
+                }
+                let gen_start = Instant::now();
+                let mut dataset = synthetic_dataset(scale, dim, &config);
+                let gen_elapsed = gen_start.elapsed();
+                if verbose {
+                    eprintln!(" {:.2}s", gen_elapsed.as_secs_f64());
+                }
 
-        if !config.csv && !config.quiet {
-            eprintln!();
+                let ground_truth = synthetic_ground_truth(&dataset, scale, dim, max_k, &config);
+
+                dataset.overlap = query_overlap(&dataset, &ground_truth, config.queries);
+                warn_overlap(&dataset, &config);
+
+                run(
+                    &scale_label(scale as u64),
+                    &dataset,
+                    &ground_truth,
+                    &config,
+                    &mut recorder,
+                    &mut report_rows,
+                );
+            }
         }
     }
 
-    if !config.csv && !config.quiet {
+    if verbose {
         print_reference_points();
         eprintln!();
         eprintln!("=== ANN benchmark complete ===");
     }
-    let _ = recorder.save();
+    let failed = recorder.failure_count() > 0;
+    if let Ok(json_path) = recorder.save() {
+        if !config.csv && !report_rows.is_empty() {
+            if let Err(e) = report::write_markdown_report(&json_path, &report_rows, &config) {
+                eprintln!("Failed to write Markdown report: {}", e);
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
 }
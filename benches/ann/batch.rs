@@ -0,0 +1,211 @@
+//! Index build throughput by vectors per insert call.
+//!
+//! `--batch-sizes 1,64,1024` builds a fresh index once per batch size and
+//! reports vectors/s and the latency of each call. Strata's vector API
+//! inserts one vector per call and has no batch path, so a batch is one
+//! session transaction of `VectorUpsert` commands, as graph_load batches
+//! its inserts. Batch size 1 is a plain `vector_upsert` per vector, the
+//! baseline the speedups are measured against. When the engine gains a
+//! batch insert, `upsert_batch` is the one place to call it.
+
+use crate::dataset::AnnDataset;
+use crate::harness::create_db;
+use crate::harness::recorder::ResultRecorder;
+use crate::{dataset_params, distance_metric, percentiles, Config, COLLECTION};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_duration, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{Command, Session, Strata};
+
+pub const DEFAULT_BATCH_SIZES: &[usize] = &[1, 64, 1024];
+
+/// API the batches go through, recorded with each result.
+fn api(batch_size: usize) -> &'static str {
+    if batch_size == 1 {
+        "vector_upsert"
+    } else {
+        "txn(VectorUpsert)"
+    }
+}
+
+/// One batch size's build.
+struct BatchResult {
+    batch_size: usize,
+    build: Duration,
+    vectors_per_sec: f64,
+    /// Per-call latency, one entry per batch.
+    calls: Vec<Duration>,
+}
+
+/// Insert `dataset.train_vectors[range]`: one `vector_upsert` for a single
+/// vector, else one transaction holding every vector of the range.
+fn upsert_batch(db: &Strata, session: &mut Session, dataset: &AnnDataset, range: Range<usize>) {
+    if range.len() == 1 {
+        let i = range.start;
+        db.vector_upsert(
+            COLLECTION,
+            &dataset.train_keys[i],
+            dataset.train_vectors[i].clone(),
+            None,
+        )
+        .unwrap();
+        return;
+    }
+    session
+        .execute(Command::TxnBegin { branch: None, options: None })
+        .expect("TxnBegin failed");
+    for i in range {
+        session
+            .execute(Command::VectorUpsert {
+                branch: None,
+                space: None,
+                collection: COLLECTION.to_string(),
+                key: dataset.train_keys[i].clone(),
+                vector: dataset.train_vectors[i].clone(),
+                metadata: None,
+            })
+            .expect("vector upsert failed");
+    }
+    session.execute(Command::TxnCommit).expect("TxnCommit failed");
+}
+
+fn build_batched(dataset: &AnnDataset, config: &Config, batch_size: usize) -> BatchResult {
+    let db = create_db(config.durability);
+    config.sweep.build_points()[0]
+        .apply_build(&db.db)
+        .unwrap_or_else(|e| panic!("{}", e));
+    db.db
+        .vector_create_collection(COLLECTION, dataset.dim as u64, distance_metric(dataset.metric))
+        .unwrap();
+
+    let n = dataset.train_vectors.len();
+    let mut session = db.db.session();
+    let mut calls = Vec::with_capacity(n.div_ceil(batch_size));
+    let build_start = Instant::now();
+    for start in (0..n).step_by(batch_size) {
+        let call_start = Instant::now();
+        upsert_batch(&db.db, &mut session, dataset, start..(start + batch_size).min(n));
+        calls.push(call_start.elapsed());
+    }
+    let build = build_start.elapsed();
+
+    BatchResult {
+        batch_size,
+        build,
+        vectors_per_sec: n as f64 / build.as_secs_f64(),
+        calls,
+    }
+}
+
+pub fn print_csv_header() {
+    println!(
+        "\"dataset\",\"batch_size\",\"vectors\",\"build_secs\",\"vectors_per_sec\",\"speedup\",\"p50_us\",\"p95_us\",\"p99_us\""
+    );
+}
+
+/// Build the index at each batch size and report throughput relative to
+/// one vector per call.
+pub fn run_batch(name: &str, dataset: &AnnDataset, config: &Config, recorder: &mut ResultRecorder) {
+    let scale = dataset.train_vectors.len();
+    let verbose = !config.csv && !config.quiet;
+
+    let mut sizes: Vec<usize> = config.batch_sizes.iter().copied().filter(|&b| b > 0).collect();
+
+    // Speedups are relative to one vector per call, so that always runs
+    if !sizes.contains(&1) {
+        sizes.insert(0, 1);
+    }
+
+    let mut results = Vec::new();
+    for &batch_size in &sizes {
+        if verbose {
+            eprint!(
+                "  Building index ({} vectors, {} per call)...",
+                fmt_num(scale as u64),
+                batch_size
+            );
+        }
+        let r = build_batched(dataset, config, batch_size);
+        if verbose {
+            eprintln!(" {:.2}s", r.build.as_secs_f64());
+        }
+        results.push(r);
+    }
+    let baseline = results.iter().find(|r| r.batch_size == 1).unwrap().vectors_per_sec;
+
+    if verbose {
+        eprintln!();
+        eprintln!("--- {}: build throughput by batch size ({}d) ---", name, dataset.dim);
+        eprintln!(
+            "  {:>6}  {:>12}  {:>8}  {:>10}  {:>10}  {:>10}",
+            "batch", "vectors/s", "speedup", "call p50", "call p95", "call p99"
+        );
+    }
+
+    for r in &results {
+        let speedup = r.vectors_per_sec / baseline;
+        let (p50, p95, p99) = percentiles(&r.calls);
+
+        if config.csv {
+            println!(
+                "\"{}\",{},{},{:.3},{:.1},{:.2},{:.1},{:.1},{:.1}",
+                name,
+                r.batch_size,
+                scale,
+                r.build.as_secs_f64(),
+                r.vectors_per_sec,
+                speedup,
+                p50.as_nanos() as f64 / 1_000.0,
+                p95.as_nanos() as f64 / 1_000.0,
+                p99.as_nanos() as f64 / 1_000.0,
+            );
+        } else if config.quiet {
+            eprintln!(
+                "ann {} batch={}: {} vectors/s ({:.2}x), call p50={}",
+                name,
+                r.batch_size,
+                fmt_num(r.vectors_per_sec as u64),
+                speedup,
+                fmt_duration(p50),
+            );
+        } else {
+            eprintln!(
+                "  {:>6}  {:>12}  {:>7.2}x  {:>10}  {:>10}  {:>10}",
+                r.batch_size,
+                fmt_num(r.vectors_per_sec as u64),
+                speedup,
+                fmt_duration(p50),
+                fmt_duration(p95),
+                fmt_duration(p99),
+            );
+        }
+
+        let mut params = dataset_params(name, dataset, config);
+        params.insert("batch_size".into(), serde_json::json!(r.batch_size));
+        params.insert("api".into(), serde_json::json!(api(r.batch_size)));
+        params.insert("build_secs".into(), serde_json::json!(r.build.as_secs_f64()));
+        params.insert("speedup".into(), serde_json::json!(speedup));
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!("ann/{}/{}d/batch{}", name, dataset.dim, r.batch_size),
+            category: "ann".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(r.vectors_per_sec),
+                p50_ns: Some(p50.as_nanos() as u64),
+                p95_ns: Some(p95.as_nanos() as u64),
+                p99_ns: Some(p99.as_nanos() as u64),
+                samples: Some(r.calls.len() as u64),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::Success,
+        });
+    }
+
+    if verbose {
+        eprintln!();
+    }
+}
@@ -0,0 +1,244 @@
+//! Index churn: delete and re-upsert a fraction of the corpus between query
+//! batches.
+//!
+//! A static index hides tombstone and rebuild costs. Here every round deletes
+//! `--churn` of the vectors (a fresh random sample each round), re-upserts
+//! the same vectors under the same keys, then runs the query batch again.
+//! The corpus is identical after every round, so the original ground truth
+//! still holds and any recall change is the index's doing.
+
+use crate::dataset::{compute_recall, AnnDataset, FastRng, GroundTruth};
+use crate::harness::recorder::ResultRecorder;
+use crate::harness::create_db;
+use crate::sweep::IndexParams;
+use crate::{
+    build_index, dataset_params, percentiles, search_batch, truncate_ground_truth, usable_ks,
+    Config, COLLECTION,
+};
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_duration, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+/// One query batch after `round` rounds of churn (round 0 = freshly built).
+struct ChurnRound {
+    round: usize,
+    k: usize,
+    recall: f64,
+    search_qps: f64,
+    /// Deletes + re-upserts per second for this round; `None` for round 0.
+    churn_ops_per_sec: Option<f64>,
+    samples: usize,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+}
+
+/// Pick `n` distinct indices out of `0..len` (partial Fisher-Yates).
+fn sample_indices(rng: &mut FastRng, len: usize, n: usize) -> Vec<usize> {
+    let mut idx: Vec<usize> = (0..len).collect();
+    for i in 0..n.min(len) {
+        let j = i + rng.next_usize(len - i);
+        idx.swap(i, j);
+    }
+    idx.truncate(n.min(len));
+    idx
+}
+
+/// Delete then re-upsert `victims`. Returns the wall time of both phases.
+fn churn_once(db: &stratadb::Strata, dataset: &AnnDataset, victims: &[usize]) -> Duration {
+    let start = Instant::now();
+    for &i in victims {
+        db.vector_delete(COLLECTION, &dataset.train_keys[i]).unwrap();
+    }
+    for &i in victims {
+        db.vector_upsert(
+            COLLECTION,
+            &dataset.train_keys[i],
+            dataset.train_vectors[i].clone(),
+            None,
+        )
+        .unwrap();
+    }
+    start.elapsed()
+}
+
+pub fn print_csv_header() {
+    println!(
+        "\"dataset\",\"round\",\"k\",\"churn_fraction\",\"churn_ops_per_sec\",\"queries\",\"search_qps\",\"recall\",\"recall_delta\",\"p50_us\",\"p95_us\",\"p99_us\""
+    );
+}
+
+/// Build the index once, then alternate churn rounds and query batches.
+/// Uses the first value of each swept knob.
+pub fn run_churn(
+    name: &str,
+    dataset: &AnnDataset,
+    ground_truth: &GroundTruth,
+    config: &Config,
+    fraction: f64,
+    recorder: &mut ResultRecorder,
+) {
+    let scale = dataset.train_vectors.len();
+    let queries = config.queries.min(dataset.query_vectors.len());
+    let verbose = !config.csv && !config.quiet;
+    let ks = usable_ks(config, ground_truth);
+    let victims_per_round = ((scale as f64 * fraction).round() as usize).clamp(1, scale);
+
+    let index = IndexParams {
+        ef_search: config.sweep.search_values()[0],
+        ..config.sweep.build_points()[0]
+    };
+
+    if verbose {
+        eprint!("  Building index ({} vectors)...", fmt_num(scale as u64));
+    }
+    let db = create_db(config.durability);
+    index.apply_build(&db.db).unwrap_or_else(|e| panic!("{}", e));
+    index.apply_search(&db.db).unwrap_or_else(|e| panic!("{}", e));
+    let build_elapsed = build_index(&db.db, dataset);
+    if verbose {
+        eprintln!(" {:.2}s", build_elapsed.as_secs_f64());
+    }
+
+    let mut rng = FastRng::new(config.seed ^ 0xC4_0000);
+    let mut rounds = Vec::new();
+
+    for round in 0..=config.churn_rounds {
+        let churn_ops_per_sec = if round == 0 {
+            None
+        } else {
+            let victims = sample_indices(&mut rng, scale, victims_per_round);
+            let elapsed = churn_once(&db.db, dataset, &victims);
+            Some(2.0 * victims.len() as f64 / elapsed.as_secs_f64())
+        };
+
+        for &k in &ks {
+            let gt_k = truncate_ground_truth(ground_truth, k);
+            let (latencies, ann_results, elapsed) = search_batch(&db.db, dataset, queries, k);
+            let (p50, p95, p99) = percentiles(&latencies);
+            rounds.push(ChurnRound {
+                round,
+                k,
+                recall: compute_recall(&ann_results, &gt_k, dataset),
+                search_qps: queries as f64 / elapsed.as_secs_f64(),
+                churn_ops_per_sec,
+                samples: latencies.len(),
+                p50,
+                p95,
+                p99,
+            });
+        }
+    }
+
+    if verbose {
+        eprintln!();
+        eprintln!(
+            "--- {}: churn {:.1}% of {} vectors per round, {} rounds{} ---",
+            name,
+            fraction * 100.0,
+            fmt_num(scale as u64),
+            config.churn_rounds,
+            index.suffix()
+        );
+        eprintln!(
+            "  {:>5}  {:>5}  {:>12}  {:>10}  {:>8}  {:>8}  {:>10}  {:>10}",
+            "round", "k", "churn ops/s", "search QPS", "recall", "Δrecall", "p50", "p99"
+        );
+    }
+
+    let mut sorted: Vec<&ChurnRound> = rounds.iter().collect();
+    sorted.sort_by_key(|r| (r.k, r.round));
+    for r in sorted {
+        let baseline = rounds
+            .iter()
+            .find(|b| b.k == r.k && b.round == 0)
+            .map(|b| b.recall)
+            .unwrap_or(r.recall);
+        let recall_delta = r.recall - baseline;
+        let churn = r
+            .churn_ops_per_sec
+            .map(|v| fmt_num(v as u64))
+            .unwrap_or_else(|| "-".into());
+
+        if config.csv {
+            println!(
+                "\"{}\",{},{},{:.4},{},{},{:.2},{:.6},{:.6},{:.1},{:.1},{:.1}",
+                name,
+                r.round,
+                r.k,
+                fraction,
+                r.churn_ops_per_sec.map(|v| format!("{:.2}", v)).unwrap_or_default(),
+                r.samples,
+                r.search_qps,
+                r.recall,
+                recall_delta,
+                r.p50.as_nanos() as f64 / 1_000.0,
+                r.p95.as_nanos() as f64 / 1_000.0,
+                r.p99.as_nanos() as f64 / 1_000.0,
+            );
+        } else if config.quiet {
+            eprintln!(
+                "ann churn {}@k={} round {}: recall={:.4} ({:+.4}), search={} QPS, churn={} ops/s",
+                name,
+                r.k,
+                r.round,
+                r.recall,
+                recall_delta,
+                fmt_num(r.search_qps as u64),
+                churn,
+            );
+        } else {
+            eprintln!(
+                "  {:>5}  {:>5}  {:>12}  {:>10}  {:>8.4}  {:>+8.4}  {:>10}  {:>10}",
+                r.round,
+                r.k,
+                churn,
+                fmt_num(r.search_qps as u64),
+                r.recall,
+                recall_delta,
+                fmt_duration(r.p50),
+                fmt_duration(r.p99),
+            );
+        }
+
+        let mut params = dataset_params(name, dataset, config);
+        params.insert("k".into(), serde_json::json!(r.k));
+        params.insert("churn_fraction".into(), serde_json::json!(fraction));
+        params.insert("round".into(), serde_json::json!(r.round));
+        params.insert("recall".into(), serde_json::json!(r.recall));
+        params.insert("recall_delta".into(), serde_json::json!(recall_delta));
+        if let Some(v) = r.churn_ops_per_sec {
+            params.insert("churn_ops_per_sec".into(), serde_json::json!(v));
+        }
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!(
+                "ann/{}/k{}/{}d{}/churn{}/r{}",
+                name,
+                r.k,
+                dataset.dim,
+                index.suffix(),
+                (fraction * 100.0).round() as u64,
+                r.round
+            ),
+            category: "ann".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(r.search_qps),
+                p50_ns: Some(r.p50.as_nanos() as u64),
+                p95_ns: Some(r.p95.as_nanos() as u64),
+                p99_ns: Some(r.p99.as_nanos() as u64),
+                samples: Some(r.samples as u64),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::Success,
+        });
+    }
+
+    if verbose {
+        eprintln!("  (Δrecall is relative to round 0, the freshly built index)");
+        eprintln!();
+    }
+}
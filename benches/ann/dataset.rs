@@ -1,4 +1,4 @@
-//! Synthetic ANN dataset generation, brute-force ground truth, and recall computation.
+//! ANN dataset types, synthetic generation, brute-force ground truth, and recall computation.
 //!
 //! Uses a Gaussian Mixture Model to create clustered vectors that produce
 //! realistic search difficulty (unlike uniform random vectors which are
@@ -51,12 +51,33 @@ impl FastRng {
 // Dataset types
 // ---------------------------------------------------------------------------
 
+/// Similarity the dataset is defined under.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// Cosine similarity; vectors are stored L2-normalized.
+    Angular,
+    Euclidean,
+}
+
+impl Metric {
+    pub fn label(self) -> &'static str {
+        match self {
+            Metric::Angular => "cosine",
+            Metric::Euclidean => "euclidean",
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub struct AnnDataset {
     pub train_keys: Vec<String>,
     pub train_vectors: Vec<Vec<f32>>,
     pub query_vectors: Vec<Vec<f32>>,
     pub dim: usize,
+    pub metric: Metric,
+    pub query_source: QuerySource,
+    /// Filled in by [`query_overlap`] once ground truth is known.
+    pub overlap: QueryOverlap,
 }
 
 #[allow(dead_code)]
@@ -70,7 +91,7 @@ pub struct GroundTruth {
 // Data generation (Gaussian Mixture Model)
 // ---------------------------------------------------------------------------
 
-fn l2_normalize(v: &mut [f32]) {
+pub fn l2_normalize(v: &mut [f32]) {
     let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
     if norm > 1e-10 {
         for x in v.iter_mut() {
@@ -121,7 +142,157 @@ pub fn generate_dataset(n_train: usize, n_queries: usize, dim: usize, seed: u64)
         train_vectors,
         query_vectors,
         dim,
+        metric: Metric::Angular,
+        query_source: QuerySource::Generated,
+        overlap: QueryOverlap::default(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Query sets
+// ---------------------------------------------------------------------------
+
+/// Where the query vectors come from. A query sitting on top of a train
+/// vector finds itself and inflates recall, so the choice is recorded with
+/// every result.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuerySource {
+    /// Independent draws from the synthetic mixture.
+    Generated,
+    /// The test set shipped with a `--dataset`.
+    Provided,
+    /// This fraction of the corpus is held out of the index and searched for.
+    Holdout(f64),
+    /// Train vectors plus Gaussian noise with this standard deviation.
+    Perturbed(f64),
+}
+
+impl QuerySource {
+    pub fn label(self) -> &'static str {
+        match self {
+            QuerySource::Generated => "generated",
+            QuerySource::Provided => "provided",
+            QuerySource::Holdout(_) => "holdout",
+            QuerySource::Perturbed(_) => "perturbed",
+        }
+    }
+}
+
+/// Move `held` randomly chosen train vectors out of the corpus and use the
+/// first `n_queries` of them as queries. Remaining train vectors keep their keys.
+pub fn holdout_queries(dataset: &mut AnnDataset, fraction: f64, held: usize, n_queries: usize, seed: u64) {
+    let len = dataset.train_vectors.len();
+    let held = held.min(len.saturating_sub(1));
+    let mut rng = FastRng::new(seed ^ 0x401D);
+    let mut idx: Vec<usize> = (0..len).collect();
+    for i in 0..held {
+        let j = i + rng.next_usize(len - i);
+        idx.swap(i, j);
+    }
+
+    let mut is_held = vec![false; len];
+    for &i in &idx[..held] {
+        is_held[i] = true;
+    }
+    let mut vectors: Vec<Option<Vec<f32>>> =
+        std::mem::take(&mut dataset.train_vectors).into_iter().map(Some).collect();
+    dataset.query_vectors = idx[..held.min(n_queries)]
+        .iter()
+        .map(|&i| vectors[i].take().unwrap())
+        .collect();
+
+    let keys = std::mem::take(&mut dataset.train_keys);
+    for (i, (key, v)) in keys.into_iter().zip(vectors).enumerate() {
+        if !is_held[i] {
+            dataset.train_keys.push(key);
+            dataset.train_vectors.push(v.unwrap());
+        }
+    }
+    dataset.query_source = QuerySource::Holdout(fraction);
+}
+
+/// Replace the queries with `n_queries` randomly chosen train vectors plus
+/// Gaussian noise of standard deviation `sigma` per component. These queries
+/// leak the corpus on purpose: recall against them is an upper bound.
+pub fn perturbed_queries(dataset: &mut AnnDataset, sigma: f64, n_queries: usize, seed: u64) {
+    let mut rng = FastRng::new(seed ^ 0x9E27);
+    let len = dataset.train_vectors.len();
+    dataset.query_vectors = (0..n_queries)
+        .map(|_| {
+            let src = &dataset.train_vectors[rng.next_usize(len)];
+            let mut v: Vec<f32> = src
+                .iter()
+                .map(|&x| x + (rng.next_gaussian() * sigma) as f32)
+                .collect();
+            // sigma 0 keeps bit-exact copies; renormalizing would perturb the last bit
+            if dataset.metric == Metric::Angular && sigma > 0.0 {
+                l2_normalize(&mut v);
+            }
+            v
+        })
+        .collect();
+    dataset.query_source = QuerySource::Perturbed(sigma);
+}
+
+/// Relative L2 distance under which a query counts as a near-duplicate of
+/// its nearest train vector.
+pub const NEAR_DUPLICATE: f32 = 1e-3;
+
+/// How many queries coincide with the corpus or with each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryOverlap {
+    /// Queries inspected (the first `--queries`).
+    pub queries: usize,
+    /// Bit-identical to some train vector.
+    pub exact_train: usize,
+    /// Within [`NEAR_DUPLICATE`] of their nearest train vector; includes exact copies.
+    pub near_train: usize,
+    /// Bit-identical to an earlier query.
+    pub repeated: usize,
+}
+
+impl QueryOverlap {
+    pub fn is_clean(&self) -> bool {
+        self.near_train == 0 && self.repeated == 0
+    }
+}
+
+fn bits(v: &[f32]) -> Vec<u32> {
+    v.iter().map(|x| x.to_bits()).collect()
+}
+
+/// Check the first `queries` queries for copies of train vectors and of each
+/// other. Near-duplicates are judged against each query's first ground-truth
+/// neighbor.
+pub fn query_overlap(dataset: &AnnDataset, ground_truth: &GroundTruth, queries: usize) -> QueryOverlap {
+    let queries = queries.min(dataset.query_vectors.len());
+    let train: std::collections::HashSet<Vec<u32>> =
+        dataset.train_vectors.iter().map(|v| bits(v)).collect();
+    let mut seen = std::collections::HashSet::new();
+    let mut overlap = QueryOverlap {
+        queries,
+        ..Default::default()
+    };
+
+    for (q, query) in dataset.query_vectors[..queries].iter().enumerate() {
+        let key = bits(query);
+        if train.contains(&key) {
+            overlap.exact_train += 1;
+        }
+        if !seen.insert(key) {
+            overlap.repeated += 1;
+        }
+        let nearest = ground_truth.neighbors.get(q).and_then(|nn| nn.first());
+        if let Some(&i) = nearest {
+            let v = &dataset.train_vectors[i];
+            let dist = (-neg_l2_squared(query, v)).sqrt();
+            let norm = dot_product(v, v).sqrt().max(f32::MIN_POSITIVE);
+            if dist <= NEAR_DUPLICATE * norm {
+                overlap.near_train += 1;
+            }
+        }
     }
+    overlap
 }
 
 // ---------------------------------------------------------------------------
@@ -132,32 +303,162 @@ fn dot_product(a: &[f32], b: &[f32]) -> f32 {
     a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
 }
 
+fn neg_l2_squared(a: &[f32], b: &[f32]) -> f32 {
+    -a.iter().zip(b.iter()).map(|(x, y)| (x - y) * (x - y)).sum::<f32>()
+}
+
 pub fn compute_ground_truth(dataset: &AnnDataset, k: usize) -> GroundTruth {
-    let mut neighbors = Vec::with_capacity(dataset.query_vectors.len());
-
-    for query in &dataset.query_vectors {
-        // Cosine similarity = dot product for L2-normalized vectors
-        let mut scores: Vec<(usize, f32)> = dataset
-            .train_vectors
-            .iter()
-            .enumerate()
-            .map(|(i, v)| (i, dot_product(query, v)))
+    compute_filtered_ground_truth(dataset, k, |_| true)
+}
+
+/// Brute-force ground truth over only the train vectors `allowed` accepts.
+///
+/// Queries are split into contiguous chunks, one per core. Each query's
+/// result depends only on the query, so the output is the same at any
+/// thread count.
+pub fn compute_filtered_ground_truth(
+    dataset: &AnnDataset,
+    k: usize,
+    allowed: impl Fn(usize) -> bool + Sync,
+) -> GroundTruth {
+    let queries = &dataset.query_vectors;
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = queries.len().div_ceil(threads).max(1);
+    let allowed = &allowed;
+
+    let neighbors = std::thread::scope(|s| {
+        let handles: Vec<_> = queries
+            .chunks(chunk)
+            .map(|part| {
+                s.spawn(move || {
+                    part.iter()
+                        .map(|query| nearest(dataset, query, k, allowed))
+                        .collect::<Vec<_>>()
+                })
+            })
             .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("ground truth thread panicked"))
+            .collect()
+    });
+    GroundTruth { neighbors, k }
+}
 
-        // Sort descending by score
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+/// A scored train vector in the top-k heap. Ordered so that the heap's
+/// maximum is the worst candidate kept: lower score is worse, and on equal
+/// scores the higher index is worse (matching a stable sort by score).
+struct Candidate {
+    score: f32,
+    index: usize,
+}
 
-        let top_k: Vec<usize> = scores.iter().take(k).map(|(i, _)| *i).collect();
-        neighbors.push(top_k);
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
     }
+}
 
-    GroundTruth { neighbors, k }
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .score
+            .partial_cmp(&self.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// Indices of the `k` train vectors nearest to `query` among those
+/// `allowed` accepts, nearest first.
+///
+/// Keeps a bounded heap of the best k instead of sorting every score:
+/// O(n log k) time and O(k) memory per query.
+pub fn nearest(
+    dataset: &AnnDataset,
+    query: &[f32],
+    k: usize,
+    allowed: impl Fn(usize) -> bool,
+) -> Vec<usize> {
+    // Cosine similarity = dot product for L2-normalized vectors
+    let score = match dataset.metric {
+        Metric::Angular => dot_product,
+        Metric::Euclidean => neg_l2_squared,
+    };
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let mut heap = std::collections::BinaryHeap::with_capacity(k + 1);
+    for (index, v) in dataset.train_vectors.iter().enumerate() {
+        if !allowed(index) {
+            continue;
+        }
+        let candidate = Candidate {
+            score: score(query, v),
+            index,
+        };
+        if heap.len() < k {
+            heap.push(candidate);
+        } else if heap.peek().is_some_and(|worst| candidate < *worst) {
+            heap.pop();
+            heap.push(candidate);
+        }
+    }
+
+    // Ascending order is best first
+    heap.into_sorted_vec().into_iter().map(|c| c.index).collect()
 }
 
 // ---------------------------------------------------------------------------
 // Recall computation
 // ---------------------------------------------------------------------------
 
+/// Recall of one query: fraction of its true neighbors found in `keys`.
+/// Returns `None` when the query has no ground-truth neighbors.
+pub fn query_recall(keys: &[String], neighbors: &[usize], dataset: &AnnDataset) -> Option<f64> {
+    let k = neighbors.len();
+    if k == 0 {
+        return None;
+    }
+
+    // Convert ground truth indices to keys
+    let gt_keys: Vec<&str> = neighbors
+        .iter()
+        .map(|&idx| dataset.train_keys[idx].as_str())
+        .collect();
+
+    // Count how many ANN results are in the ground truth
+    let hits = keys
+        .iter()
+        .filter(|key| gt_keys.contains(&key.as_str()))
+        .count();
+
+    Some(hits as f64 / k as f64)
+}
+
+/// Recall of each query, in query order. A query with no ground-truth
+/// neighbors scores 0.
+pub fn per_query_recall(
+    ann_results: &[Vec<String>],
+    ground_truth: &GroundTruth,
+    dataset: &AnnDataset,
+) -> Vec<f64> {
+    ann_results
+        .iter()
+        .zip(&ground_truth.neighbors)
+        .map(|(keys, neighbors)| query_recall(keys, neighbors, dataset).unwrap_or(0.0))
+        .collect()
+}
+
 /// Compute recall@k: fraction of true top-k neighbors found by ANN results.
 /// `ann_results` is per-query list of keys returned by vector_search.
 pub fn compute_recall(
@@ -165,33 +466,85 @@ pub fn compute_recall(
     ground_truth: &GroundTruth,
     dataset: &AnnDataset,
 ) -> f64 {
-    let mut total_recall = 0.0;
-    let n = ann_results.len().min(ground_truth.neighbors.len());
+    let per_query = per_query_recall(ann_results, ground_truth, dataset);
+    if per_query.is_empty() {
+        0.0
+    } else {
+        per_query.iter().sum::<f64>() / per_query.len() as f64
+    }
+}
 
-    for i in 0..n {
-        let k = ground_truth.neighbors[i].len();
-        if k == 0 {
-            continue;
+/// The low end of per-query recall. A mean of 0.85 can hide queries that
+/// find nothing; those are the ones worth debugging.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RecallDistribution {
+    pub min: f64,
+    pub p10: f64,
+    pub p50: f64,
+    /// Queries that found none of their true neighbors.
+    pub zero: usize,
+}
+
+impl RecallDistribution {
+    pub fn from_per_query(per_query: &[f64]) -> Self {
+        if per_query.is_empty() {
+            return Self::default();
         }
+        let mut sorted = per_query.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let len = sorted.len();
+        Self {
+            min: sorted[0],
+            p10: sorted[len * 10 / 100],
+            p50: sorted[len * 50 / 100],
+            zero: sorted.iter().take_while(|&&r| r == 0.0).count(),
+        }
+    }
+}
 
-        // Convert ground truth indices to keys
-        let gt_keys: Vec<&str> = ground_truth.neighbors[i]
-            .iter()
-            .map(|&idx| dataset.train_keys[idx].as_str())
-            .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Count how many ANN results are in the ground truth
-        let hits = ann_results[i]
-            .iter()
-            .filter(|key| gt_keys.contains(&key.as_str()))
-            .count();
+    #[test]
+    fn holdout_queries_leave_the_corpus() {
+        let mut d = generate_dataset(200, 0, 16, 7);
+        holdout_queries(&mut d, 0.2, 50, 20, 7);
+        assert_eq!(d.train_vectors.len(), 150);
+        assert_eq!(d.train_keys.len(), 150);
+        assert_eq!(d.query_vectors.len(), 20);
+        assert_eq!(d.query_source, QuerySource::Holdout(0.2));
 
-        total_recall += hits as f64 / k as f64;
+        let gt = compute_ground_truth(&d, 1);
+        let overlap = query_overlap(&d, &gt, 20);
+        assert!(overlap.is_clean(), "{:?}", overlap);
     }
 
-    if n > 0 {
-        total_recall / n as f64
-    } else {
-        0.0
+    #[test]
+    fn unperturbed_copies_are_flagged() {
+        let mut d = generate_dataset(100, 0, 16, 7);
+        perturbed_queries(&mut d, 0.0, 30, 7);
+        let gt = compute_ground_truth(&d, 1);
+        let overlap = query_overlap(&d, &gt, 30);
+        assert_eq!(overlap.queries, 30);
+        assert_eq!(overlap.exact_train, 30);
+        assert_eq!(overlap.near_train, 30);
+        assert!(overlap.repeated > 0, "30 draws from 100 vectors should repeat");
+    }
+
+    #[test]
+    fn recall_distribution_exposes_the_low_tail() {
+        // 17 perfect queries and 3 that found nothing: mean 0.85, min 0
+        let mut per_query = vec![1.0; 17];
+        for i in [3, 8, 15] {
+            per_query.insert(i, 0.0);
+        }
+        let dist = RecallDistribution::from_per_query(&per_query);
+        assert_eq!(dist.min, 0.0);
+        assert_eq!(dist.p10, 0.0);
+        assert_eq!(dist.p50, 1.0);
+        assert_eq!(dist.zero, 3);
+
+        assert_eq!(RecallDistribution::from_per_query(&[]), RecallDistribution::default());
     }
 }
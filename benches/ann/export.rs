@@ -0,0 +1,218 @@
+//! Export results in the ann-benchmarks.com on-disk layout.
+//!
+//! ann-benchmarks' `plot.py` / `create_website.py` read one HDF5 file per run
+//! from `results/<dataset>/<count>/<algorithm>/<run>.hdf5`, with the run's
+//! metadata in root attributes and three datasets:
+//!
+//! - `times`: per-query search time in seconds, shape `(queries,)`
+//! - `neighbors`: returned train indices, shape `(queries, count)`, `-1` padded
+//! - `distances`: distance to each returned neighbor, `inf` padded
+//!
+//! The plotting scripts recompute recall themselves from `neighbors` and
+//! `distances` against the dataset file, so the dataset name must match an
+//! ann-benchmarks dataset for the plots to work. Needs `--features ann-hdf5`.
+
+use crate::dataset::{AnnDataset, Metric};
+use crate::AnnResult;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Algorithm name the runs are filed under.
+pub const ALGORITHM: &str = "strata";
+
+/// Whether this build can write HDF5.
+pub const AVAILABLE: bool = cfg!(feature = "ann-hdf5");
+
+/// ann-benchmarks' distance name.
+pub fn distance_name(metric: Metric) -> &'static str {
+    match metric {
+        Metric::Angular => "angular",
+        Metric::Euclidean => "euclidean",
+    }
+}
+
+/// Distance as ann-benchmarks defines it: `1 - cos` for angular, L2 for
+/// euclidean.
+fn distance(metric: Metric, a: &[f32], b: &[f32]) -> f32 {
+    match metric {
+        Metric::Angular => {
+            let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+            let na: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+            let nb: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if na > 0.0 && nb > 0.0 {
+                1.0 - dot / (na * nb)
+            } else {
+                1.0
+            }
+        }
+        Metric::Euclidean => a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| (x - y) * (x - y))
+            .sum::<f32>()
+            .sqrt(),
+    }
+}
+
+/// Run name, e.g. `M16_efc200_ef64`, or `default` when no knob is set.
+fn run_name(r: &AnnResult) -> String {
+    let suffix = r.index.suffix();
+    if suffix.is_empty() {
+        "default".into()
+    } else {
+        suffix.trim_start_matches('/').replace('/', "_")
+    }
+}
+
+/// Human-readable name shown in the plot legend.
+fn display_name(r: &AnnResult) -> String {
+    let knob = |label: &str, v: Option<usize>| v.map(|v| format!("{}={}", label, v));
+    let knobs: Vec<String> = [
+        knob("M", r.index.m),
+        knob("efC", r.index.ef_construction),
+        knob("ef", r.index.ef_search),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    format!("Strata({})", knobs.join(","))
+}
+
+/// Per-query `(neighbors, distances)`, padded to `k` with `-1` / `inf`.
+fn neighbor_rows(r: &AnnResult, dataset: &AnnDataset) -> (Vec<i32>, Vec<f32>) {
+    let index: HashMap<&str, usize> = dataset
+        .train_keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| (key.as_str(), i))
+        .collect();
+
+    let mut neighbors = Vec::with_capacity(r.returned.len() * r.k);
+    let mut distances = Vec::with_capacity(r.returned.len() * r.k);
+    for (q, keys) in r.returned.iter().enumerate() {
+        let query = &dataset.query_vectors[q];
+        for slot in 0..r.k {
+            match keys.get(slot).and_then(|key| index.get(key.as_str())) {
+                Some(&i) => {
+                    neighbors.push(i as i32);
+                    distances.push(distance(dataset.metric, query, &dataset.train_vectors[i]));
+                }
+                None => {
+                    neighbors.push(-1);
+                    distances.push(f32::INFINITY);
+                }
+            }
+        }
+    }
+    (neighbors, distances)
+}
+
+/// Write one run to `<root>/<dataset>/<k>/strata/<run>.hdf5`.
+pub fn write_run(
+    root: &Path,
+    dataset_name: &str,
+    dataset: &AnnDataset,
+    r: &AnnResult,
+) -> Result<PathBuf, String> {
+    let dir = root
+        .join(dataset_name)
+        .join(r.k.to_string())
+        .join(ALGORITHM);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}.hdf5", run_name(r)));
+
+    let times: Vec<f64> = r.latencies.iter().map(|d| d.as_secs_f64()).collect();
+    let (neighbors, distances) = neighbor_rows(r, dataset);
+    write_hdf5(&path, dataset_name, dataset.metric, r, &times, &neighbors, &distances)?;
+    Ok(path)
+}
+
+#[cfg(feature = "ann-hdf5")]
+fn write_hdf5(
+    path: &Path,
+    dataset_name: &str,
+    metric: Metric,
+    r: &AnnResult,
+    times: &[f64],
+    neighbors: &[i32],
+    distances: &[f32],
+) -> Result<(), String> {
+    use hdf5::types::VarLenUnicode;
+
+    let err = |e: hdf5::Error| format!("{}: {}", path.display(), e);
+    let file = hdf5::File::create(path).map_err(err)?;
+
+    for (key, value) in [
+        ("algo", ALGORITHM.to_string()),
+        ("dataset", dataset_name.to_string()),
+        ("distance", distance_name(metric).to_string()),
+        ("name", display_name(r)),
+    ] {
+        let value: VarLenUnicode = value
+            .parse()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        file.new_attr::<VarLenUnicode>()
+            .create(key)
+            .and_then(|a| a.write_scalar(&value))
+            .map_err(err)?;
+    }
+    let best_search_time = times.iter().sum::<f64>() / times.len().max(1) as f64;
+    for (key, value) in [
+        ("build_time", r.build_secs),
+        ("best_search_time", best_search_time),
+        // Strata does not report index size; the plots that use it show 0.
+        ("index_size", 0.0),
+        ("candidates", r.k as f64),
+    ] {
+        file.new_attr::<f64>()
+            .create(key)
+            .and_then(|a| a.write_scalar(&value))
+            .map_err(err)?;
+    }
+    for (key, value) in [("count", r.k as i64), ("run_count", 1)] {
+        file.new_attr::<i64>()
+            .create(key)
+            .and_then(|a| a.write_scalar(&value))
+            .map_err(err)?;
+    }
+    for (key, value) in [("batch_mode", false), ("expect_extra", false)] {
+        file.new_attr::<bool>()
+            .create(key)
+            .and_then(|a| a.write_scalar(&value))
+            .map_err(err)?;
+    }
+
+    let queries = times.len();
+    file.new_dataset::<f64>()
+        .shape(queries)
+        .create("times")
+        .and_then(|d| d.write_raw(times))
+        .map_err(err)?;
+    file.new_dataset::<i32>()
+        .shape((queries, r.k))
+        .create("neighbors")
+        .and_then(|d| d.write_raw(neighbors))
+        .map_err(err)?;
+    file.new_dataset::<f32>()
+        .shape((queries, r.k))
+        .create("distances")
+        .and_then(|d| d.write_raw(distances))
+        .map_err(err)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "ann-hdf5"))]
+fn write_hdf5(
+    path: &Path,
+    _dataset_name: &str,
+    _metric: Metric,
+    _r: &AnnResult,
+    _times: &[f64],
+    _neighbors: &[i32],
+    _distances: &[f32],
+) -> Result<(), String> {
+    Err(format!(
+        "{}: ann-benchmarks export needs `--features ann-hdf5`",
+        path.display()
+    ))
+}
@@ -0,0 +1,332 @@
+//! Filtered vector search: searches constrained by a metadata predicate.
+//!
+//! Every train vector gets a categorical `bucket` (0..100, uniform) stored as
+//! vector metadata. A filter at selectivity `s` accepts `bucket < s * 100`, so
+//! 1%, 10% and 50% of the corpus pass. Ground truth for each selectivity is
+//! brute-forced over the passing subset only.
+//!
+//! `vector_search` has no metadata-filter argument in the public API, so the
+//! filter runs the way an application would apply it today: over-fetch
+//! `k * overfetch / s` candidates, drop those outside the filter, keep the
+//! first k. The timed region covers search plus filtering. Queries left with
+//! fewer than k hits are counted as short.
+
+use crate::dataset::{compute_filtered_ground_truth, compute_recall, AnnDataset, FastRng, GroundTruth};
+use crate::harness::create_db;
+use crate::harness::recorder::ResultRecorder;
+use crate::sweep::IndexParams;
+use crate::{
+    dataset_params, distance_metric, percentiles, search_batch, truncate_ground_truth, usable_ks, Config,
+    COLLECTION,
+};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_duration, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{Strata, Value};
+
+pub const DEFAULT_SELECTIVITIES: &[f64] = &[0.01, 0.10, 0.50];
+pub const DEFAULT_OVERFETCH: f64 = 2.0;
+const BUCKETS: usize = 100;
+
+/// One (selectivity, k) measurement. Selectivity 1.0 is the unfiltered baseline.
+struct FilteredResult {
+    selectivity: f64,
+    k: usize,
+    candidates: usize,
+    recall: f64,
+    search_qps: f64,
+    short_rate: f64,
+    samples: usize,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+}
+
+fn bucket_metadata(bucket: usize) -> Value {
+    let mut map = HashMap::new();
+    map.insert("bucket".to_string(), Value::Int(bucket as i64));
+    Value::Object(map)
+}
+
+/// Buckets below this bound pass a filter at `selectivity`.
+fn bucket_bound(selectivity: f64) -> usize {
+    ((selectivity * BUCKETS as f64).round() as usize).clamp(1, BUCKETS)
+}
+
+/// Label used in benchmark names: `1`, `10`, `0.5`, ...
+fn pct_label(selectivity: f64) -> String {
+    let pct = (selectivity * 1000.0).round() / 10.0;
+    if pct.fract() == 0.0 {
+        format!("{}", pct as u64)
+    } else {
+        format!("{}", pct)
+    }
+}
+
+/// Search with a post-filter. Returns per-query latencies, the filtered keys,
+/// and the wall time.
+fn filtered_search(
+    db: &Strata,
+    dataset: &AnnDataset,
+    queries: usize,
+    k: usize,
+    candidates: usize,
+    passes: &HashMap<&str, bool>,
+) -> (Vec<Duration>, Vec<Vec<String>>, Duration) {
+    let mut latencies = Vec::with_capacity(queries);
+    let mut ann_results = Vec::with_capacity(queries);
+
+    let search_start = Instant::now();
+    for q in 0..queries {
+        let query = dataset.query_vectors[q].clone();
+        let op_start = Instant::now();
+        let results = db.vector_search(COLLECTION, query, candidates as u64).unwrap();
+        let keys: Vec<String> = results
+            .iter()
+            .filter(|m| passes.get(m.key.as_str()).copied().unwrap_or(false))
+            .take(k)
+            .map(|m| m.key.clone())
+            .collect();
+        latencies.push(op_start.elapsed());
+        ann_results.push(keys);
+    }
+    (latencies, ann_results, search_start.elapsed())
+}
+
+pub fn print_csv_header() {
+    println!(
+        "\"dataset\",\"selectivity\",\"k\",\"candidates\",\"search_qps\",\"qps_vs_unfiltered\",\"recall\",\"short_rate\",\"p50_us\",\"p95_us\",\"p99_us\""
+    );
+}
+
+/// Build the index once with bucket metadata, then search unfiltered and at
+/// each selectivity. Uses the first value of each swept knob.
+pub fn run_filtered(
+    name: &str,
+    dataset: &AnnDataset,
+    ground_truth: &GroundTruth,
+    config: &Config,
+    recorder: &mut ResultRecorder,
+) {
+    let scale = dataset.train_vectors.len();
+    let queries = config.queries.min(dataset.query_vectors.len());
+    let verbose = !config.csv && !config.quiet;
+    let ks = usable_ks(config, ground_truth);
+    let max_k = ks.iter().copied().max().unwrap_or(0);
+
+    let mut rng = FastRng::new(config.seed ^ 0xF1_7000);
+    let buckets: Vec<usize> = (0..scale).map(|_| rng.next_usize(BUCKETS)).collect();
+
+    let index = IndexParams {
+        ef_search: config.sweep.search_values()[0],
+        ..config.sweep.build_points()[0]
+    };
+
+    if verbose {
+        eprint!("  Building index with metadata ({} vectors)...", fmt_num(scale as u64));
+    }
+    let db = create_db(config.durability);
+    index.apply_build(&db.db).unwrap_or_else(|e| panic!("{}", e));
+    index.apply_search(&db.db).unwrap_or_else(|e| panic!("{}", e));
+    db.db
+        .vector_create_collection(COLLECTION, dataset.dim as u64, distance_metric(dataset.metric))
+        .unwrap();
+    let build_start = Instant::now();
+    for i in 0..scale {
+        db.db
+            .vector_upsert(
+                COLLECTION,
+                &dataset.train_keys[i],
+                dataset.train_vectors[i].clone(),
+                Some(bucket_metadata(buckets[i])),
+            )
+            .unwrap();
+    }
+    if verbose {
+        eprintln!(" {:.2}s", build_start.elapsed().as_secs_f64());
+    }
+
+    let mut results = Vec::new();
+
+    // Unfiltered baseline, same index and metadata
+    for &k in &ks {
+        let gt_k = truncate_ground_truth(ground_truth, k);
+        let (latencies, ann_results, elapsed) = search_batch(&db.db, dataset, queries, k);
+        let (p50, p95, p99) = percentiles(&latencies);
+        results.push(FilteredResult {
+            selectivity: 1.0,
+            k,
+            candidates: k,
+            recall: compute_recall(&ann_results, &gt_k, dataset),
+            search_qps: queries as f64 / elapsed.as_secs_f64(),
+            short_rate: ann_results.iter().filter(|r| r.len() < k).count() as f64 / queries as f64,
+            samples: latencies.len(),
+            p50,
+            p95,
+            p99,
+        });
+    }
+
+    for &selectivity in &config.selectivities {
+        let bound = bucket_bound(selectivity);
+        let passes: HashMap<&str, bool> = dataset
+            .train_keys
+            .iter()
+            .zip(&buckets)
+            .map(|(key, &b)| (key.as_str(), b < bound))
+            .collect();
+
+        if verbose {
+            eprint!(
+                "  Filtered ground truth ({}%, brute-force, k={})...",
+                pct_label(selectivity),
+                max_k
+            );
+        }
+        let gt_start = Instant::now();
+        let filtered_gt = compute_filtered_ground_truth(dataset, max_k, |i| buckets[i] < bound);
+        if verbose {
+            eprintln!(" {:.2}s", gt_start.elapsed().as_secs_f64());
+        }
+
+        for &k in &ks {
+            let gt_k = truncate_ground_truth(&filtered_gt, k);
+            let candidates = ((k as f64 * config.overfetch / selectivity).ceil() as usize).clamp(k, scale);
+            let (latencies, ann_results, elapsed) =
+                filtered_search(&db.db, dataset, queries, k, candidates, &passes);
+            let (p50, p95, p99) = percentiles(&latencies);
+            results.push(FilteredResult {
+                selectivity,
+                k,
+                candidates,
+                recall: compute_recall(&ann_results, &gt_k, dataset),
+                search_qps: queries as f64 / elapsed.as_secs_f64(),
+                short_rate: ann_results.iter().filter(|r| r.len() < k).count() as f64
+                    / queries as f64,
+                samples: latencies.len(),
+                p50,
+                p95,
+                p99,
+            });
+        }
+    }
+
+    if verbose {
+        eprintln!();
+        eprintln!(
+            "--- {}: filtered search, {} vectors, {}d, overfetch {}x{} ---",
+            name,
+            fmt_num(scale as u64),
+            dataset.dim,
+            config.overfetch,
+            index.suffix()
+        );
+        eprintln!(
+            "  {:>7}  {:>5}  {:>10}  {:>10}  {:>8}  {:>8}  {:>7}  {:>10}  {:>10}",
+            "filter", "k", "candidates", "search QPS", "vs none", "recall", "short", "p50", "p99"
+        );
+    }
+
+    results.sort_by(|a, b| {
+        a.k.cmp(&b.k)
+            .then(b.selectivity.partial_cmp(&a.selectivity).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    for r in &results {
+        let unfiltered_qps = results
+            .iter()
+            .find(|u| u.k == r.k && u.selectivity == 1.0)
+            .map(|u| u.search_qps)
+            .unwrap_or(r.search_qps);
+        let qps_ratio = r.search_qps / unfiltered_qps;
+        let filter = if r.selectivity == 1.0 {
+            "none".to_string()
+        } else {
+            format!("{}%", pct_label(r.selectivity))
+        };
+
+        if config.csv {
+            println!(
+                "\"{}\",{:.4},{},{},{:.2},{:.4},{:.6},{:.4},{:.1},{:.1},{:.1}",
+                name,
+                r.selectivity,
+                r.k,
+                r.candidates,
+                r.search_qps,
+                qps_ratio,
+                r.recall,
+                r.short_rate,
+                r.p50.as_nanos() as f64 / 1_000.0,
+                r.p95.as_nanos() as f64 / 1_000.0,
+                r.p99.as_nanos() as f64 / 1_000.0,
+            );
+        } else if config.quiet {
+            eprintln!(
+                "ann filtered {}@k={} filter={}: recall={:.4}, search={} QPS ({:.2}x unfiltered), short={:.1}%",
+                name,
+                r.k,
+                filter,
+                r.recall,
+                fmt_num(r.search_qps as u64),
+                qps_ratio,
+                r.short_rate * 100.0,
+            );
+        } else {
+            eprintln!(
+                "  {:>7}  {:>5}  {:>10}  {:>10}  {:>7.2}x  {:>8.4}  {:>6.1}%  {:>10}  {:>10}",
+                filter,
+                r.k,
+                fmt_num(r.candidates as u64),
+                fmt_num(r.search_qps as u64),
+                qps_ratio,
+                r.recall,
+                r.short_rate * 100.0,
+                fmt_duration(r.p50),
+                fmt_duration(r.p99),
+            );
+        }
+
+        let mut params = dataset_params(name, dataset, config);
+        params.insert("k".into(), serde_json::json!(r.k));
+        params.insert("selectivity".into(), serde_json::json!(r.selectivity));
+        params.insert("candidates".into(), serde_json::json!(r.candidates));
+        params.insert("overfetch".into(), serde_json::json!(config.overfetch));
+        params.insert("recall".into(), serde_json::json!(r.recall));
+        params.insert("short_rate".into(), serde_json::json!(r.short_rate));
+        params.insert("qps_vs_unfiltered".into(), serde_json::json!(qps_ratio));
+
+        let filter_suffix = if r.selectivity == 1.0 {
+            "/filter-none".to_string()
+        } else {
+            format!("/filter{}", pct_label(r.selectivity))
+        };
+        recorder.record(BenchmarkResult {
+            benchmark: format!(
+                "ann/{}/k{}/{}d{}{}",
+                name,
+                r.k,
+                dataset.dim,
+                index.suffix(),
+                filter_suffix
+            ),
+            category: "ann".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(r.search_qps),
+                p50_ns: Some(r.p50.as_nanos() as u64),
+                p95_ns: Some(r.p95.as_nanos() as u64),
+                p99_ns: Some(r.p99.as_nanos() as u64),
+                samples: Some(r.samples as u64),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::Success,
+        });
+    }
+
+    if verbose {
+        eprintln!("  (short = queries with fewer than k hits after filtering)");
+        eprintln!();
+    }
+}
@@ -0,0 +1,148 @@
+//! On-disk cache of brute-force ground truth.
+//!
+//! Brute force is O(queries × train × dim); at 1M+ vectors it takes longer
+//! than the benchmark itself. `--gt-cache <dir>` stores each synthetic
+//! dataset's ground truth under a name built from everything that decides
+//! it: seed, scale, dim, query count and source, and k. Repeated runs and
+//! parameter sweeps load it instead of recomputing.
+//!
+//! The file also carries a fingerprint of the query vectors. If the
+//! generator changes, the fingerprint stops matching and the entry is
+//! recomputed and overwritten rather than silently reused.
+//!
+//! Format, little-endian: magic `SGT1`, `k: u32`, `queries: u32`,
+//! `fingerprint: u64`, then `queries × k` train indices as `u32`. Rows
+//! shorter than k (corpus smaller than k) are padded with `u32::MAX`.
+
+use crate::dataset::{AnnDataset, GroundTruth, QuerySource};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"SGT1";
+const HEADER_LEN: usize = 4 + 4 + 4 + 8;
+
+/// Cache file for one synthetic dataset's ground truth at `k`.
+pub fn path(
+    dir: &Path,
+    seed: u64,
+    scale: usize,
+    dim: usize,
+    queries: usize,
+    source: Option<QuerySource>,
+    k: usize,
+) -> PathBuf {
+    let source = match source {
+        Some(QuerySource::Holdout(f)) => format!("holdout{}", f),
+        Some(QuerySource::Perturbed(sigma)) => format!("perturbed{}", sigma),
+        Some(s) => s.label().to_string(),
+        None => QuerySource::Generated.label().to_string(),
+    };
+    dir.join(format!(
+        "synthetic-{:x}-{}-{}d-q{}-{}-k{}.gt",
+        seed, scale, dim, queries, source, k
+    ))
+}
+
+/// FNV-1a over the query vectors' bits.
+fn fingerprint(dataset: &AnnDataset) -> u64 {
+    let mut h: u64 = 0xcbf29ce484222325;
+    for v in &dataset.query_vectors {
+        for x in v {
+            for b in x.to_bits().to_le_bytes() {
+                h ^= b as u64;
+                h = h.wrapping_mul(0x100000001b3);
+            }
+        }
+    }
+    h
+}
+
+/// Cached ground truth for `dataset` at `k`, or `None` if there is no entry
+/// or it doesn't match (other k, other queries, truncated file). `k` of 0
+/// has no entry.
+pub fn load(path: &Path, dataset: &AnnDataset, k: usize) -> Option<GroundTruth> {
+    if k == 0 {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+        return None;
+    }
+    let u32_at = |off: usize| u32::from_le_bytes(bytes[off..off + 4].try_into().unwrap()) as usize;
+    let stored_k = u32_at(4);
+    let queries = u32_at(8);
+    let stored_fp = u64::from_le_bytes(bytes[12..20].try_into().unwrap());
+    if stored_k != k || queries != dataset.query_vectors.len() || stored_fp != fingerprint(dataset) {
+        return None;
+    }
+
+    let train = dataset.train_vectors.len();
+    let body = &bytes[HEADER_LEN..];
+    if body.len() != queries * k * 4 {
+        return None;
+    }
+    let mut neighbors = Vec::with_capacity(queries);
+    for row in body.chunks_exact(k * 4) {
+        let nn: Vec<usize> = row
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .take_while(|&i| i != u32::MAX)
+            .map(|i| i as usize)
+            .collect();
+        if nn.iter().any(|&i| i >= train) {
+            return None;
+        }
+        neighbors.push(nn);
+    }
+    Some(GroundTruth { neighbors, k })
+}
+
+/// Write `ground_truth` to `path`, via a temporary file so an interrupted
+/// run never leaves a truncated entry behind.
+pub fn store(path: &Path, dataset: &AnnDataset, ground_truth: &GroundTruth) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    let k = ground_truth.k;
+    let tmp = path.with_extension("gt.tmp");
+    let write = || -> std::io::Result<()> {
+        let mut w = BufWriter::new(fs::File::create(&tmp)?);
+        w.write_all(MAGIC)?;
+        w.write_all(&(k as u32).to_le_bytes())?;
+        w.write_all(&(ground_truth.neighbors.len() as u32).to_le_bytes())?;
+        w.write_all(&fingerprint(dataset).to_le_bytes())?;
+        for nn in &ground_truth.neighbors {
+            for slot in 0..k {
+                let i = nn.get(slot).map_or(u32::MAX, |&i| i as u32);
+                w.write_all(&i.to_le_bytes())?;
+            }
+        }
+        w.into_inner()?.sync_all()
+    };
+    write().map_err(|e| format!("{}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{compute_ground_truth, generate_dataset};
+
+    #[test]
+    fn round_trips_and_rejects_other_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let d = generate_dataset(500, 20, 16, 7);
+        let gt = compute_ground_truth(&d, 10);
+        let p = path(dir.path(), 7, 500, 16, 20, None, 10);
+
+        assert!(load(&p, &d, 10).is_none());
+        store(&p, &d, &gt).unwrap();
+        assert_eq!(load(&p, &d, 10).unwrap().neighbors, gt.neighbors);
+        assert!(load(&p, &d, 5).is_none());
+        assert!(load(&p, &d, 0).is_none());
+
+        let other = generate_dataset(500, 20, 16, 8);
+        assert!(load(&p, &other, 10).is_none());
+    }
+}
@@ -0,0 +1,196 @@
+//! Loaders for the standard ann-benchmarks datasets.
+//!
+//! Two on-disk formats are supported:
+//!
+//! - **TEXMEX fvecs/ivecs** (SIFT1M, GIST1M): a directory holding
+//!   `<name>_base.fvecs`, `<name>_query.fvecs` and `<name>_groundtruth.ivecs`.
+//! - **ann-benchmarks HDF5** (GloVe, Fashion-MNIST, SIFT, ...): a single
+//!   `.hdf5` file with `train`, `test` and `neighbors` datasets and a
+//!   `distance` attribute. Needs `--features ann-hdf5` (links libhdf5).
+//!
+//! The provided ground truth is used as-is, so recall is comparable with
+//! published numbers.
+
+use crate::dataset::{l2_normalize, AnnDataset, GroundTruth, Metric, QueryOverlap, QuerySource};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// A real dataset with its provided ground truth.
+pub struct LoadedDataset {
+    pub name: String,
+    pub dataset: AnnDataset,
+    pub ground_truth: GroundTruth,
+}
+
+/// Load the dataset at `path`: a TEXMEX directory or an ann-benchmarks
+/// `.hdf5` file.
+pub fn load_dataset(path: &Path) -> Result<LoadedDataset, String> {
+    if path.is_dir() {
+        load_texmex(path)
+    } else if path.extension().is_some_and(|e| e == "hdf5" || e == "h5") {
+        load_hdf5(path)
+    } else {
+        Err(format!(
+            "{}: expected a TEXMEX directory (*_base.fvecs, *_query.fvecs, *_groundtruth.ivecs) or an .hdf5 file",
+            path.display()
+        ))
+    }
+}
+
+fn dataset_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "dataset".into())
+}
+
+/// Build the dataset from raw rows. Angular datasets are L2-normalized so
+/// cosine search and the dot-product ground truth agree.
+fn assemble(
+    name: String,
+    metric: Metric,
+    mut train: Vec<Vec<f32>>,
+    mut queries: Vec<Vec<f32>>,
+    neighbors: Vec<Vec<usize>>,
+) -> Result<LoadedDataset, String> {
+    let dim = train.first().map(|v| v.len()).ok_or("train set is empty")?;
+    if let Some(bad) = train.iter().chain(queries.iter()).find(|v| v.len() != dim) {
+        return Err(format!("mixed dimensions: expected {}, found {}", dim, bad.len()));
+    }
+    if neighbors.len() < queries.len() {
+        return Err(format!(
+            "ground truth covers {} queries, test set has {}",
+            neighbors.len(),
+            queries.len()
+        ));
+    }
+    if let Some(&idx) = neighbors.iter().flatten().find(|&&i| i >= train.len()) {
+        return Err(format!("ground truth index {} out of range ({} train vectors)", idx, train.len()));
+    }
+
+    if metric == Metric::Angular {
+        for v in train.iter_mut().chain(queries.iter_mut()) {
+            l2_normalize(v);
+        }
+    }
+
+    let k = neighbors.iter().map(|n| n.len()).min().unwrap_or(0);
+    let train_keys = (0..train.len()).map(|i| format!("vec_{}", i)).collect();
+    Ok(LoadedDataset {
+        name,
+        dataset: AnnDataset {
+            train_keys,
+            train_vectors: train,
+            query_vectors: queries,
+            dim,
+            metric,
+            query_source: QuerySource::Provided,
+            overlap: QueryOverlap::default(),
+        },
+        ground_truth: GroundTruth { neighbors, k },
+    })
+}
+
+// ---------------------------------------------------------------------------
+// TEXMEX fvecs / ivecs
+// ---------------------------------------------------------------------------
+
+/// Read a `.fvecs` / `.ivecs` file: each record is a little-endian `i32`
+/// dimension followed by that many 4-byte components.
+fn read_vecs<T>(path: &Path, decode: fn([u8; 4]) -> T) -> Result<Vec<Vec<T>>, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let mut rows = Vec::new();
+    let mut word = [0u8; 4];
+    loop {
+        match reader.read_exact(&mut word) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        }
+        let dim = i32::from_le_bytes(word);
+        if dim <= 0 {
+            return Err(format!("{}: bad record dimension {}", path.display(), dim));
+        }
+        let mut row = Vec::with_capacity(dim as usize);
+        for _ in 0..dim {
+            reader
+                .read_exact(&mut word)
+                .map_err(|e| format!("{}: truncated record: {}", path.display(), e))?;
+            row.push(decode(word));
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn find_with_suffix(dir: &Path, suffix: &str) -> Result<std::path::PathBuf, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .find(|p| {
+            p.file_name()
+                .is_some_and(|n| n.to_string_lossy().ends_with(suffix))
+        })
+        .ok_or_else(|| format!("{}: no *{} file", dir.display(), suffix))
+}
+
+/// TEXMEX datasets (SIFT1M, GIST1M) are Euclidean.
+fn load_texmex(dir: &Path) -> Result<LoadedDataset, String> {
+    let base = find_with_suffix(dir, "_base.fvecs")?;
+    let query = find_with_suffix(dir, "_query.fvecs")?;
+    let gt = find_with_suffix(dir, "_groundtruth.ivecs")?;
+
+    let train = read_vecs(&base, f32::from_le_bytes)?;
+    let queries = read_vecs(&query, f32::from_le_bytes)?;
+    let neighbors = read_vecs(&gt, i32::from_le_bytes)?
+        .into_iter()
+        .map(|row| row.into_iter().map(|i| i as usize).collect())
+        .collect();
+
+    assemble(dataset_name(dir), Metric::Euclidean, train, queries, neighbors)
+}
+
+// ---------------------------------------------------------------------------
+// ann-benchmarks HDF5
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "ann-hdf5")]
+fn load_hdf5(path: &Path) -> Result<LoadedDataset, String> {
+    let err = |e: hdf5::Error| format!("{}: {}", path.display(), e);
+    let file = hdf5::File::open(path).map_err(err)?;
+
+    let distance = file
+        .attr("distance")
+        .and_then(|a| a.read_scalar::<hdf5::types::VarLenUnicode>())
+        .map_err(err)?;
+    let metric = match distance.as_str() {
+        "angular" | "cosine" => Metric::Angular,
+        "euclidean" => Metric::Euclidean,
+        other => return Err(format!("{}: unsupported distance '{}'", path.display(), other)),
+    };
+
+    let rows_f32 = |name: &str| -> Result<Vec<Vec<f32>>, String> {
+        let arr = file.dataset(name).and_then(|d| d.read_2d::<f32>()).map_err(err)?;
+        Ok(arr.outer_iter().map(|r| r.to_vec()).collect())
+    };
+    let train = rows_f32("train")?;
+    let queries = rows_f32("test")?;
+    let neighbors = file
+        .dataset("neighbors")
+        .and_then(|d| d.read_2d::<i32>())
+        .map_err(err)?
+        .outer_iter()
+        .map(|r| r.iter().map(|&i| i as usize).collect())
+        .collect();
+
+    assemble(dataset_name(path), metric, train, queries, neighbors)
+}
+
+#[cfg(not(feature = "ann-hdf5"))]
+fn load_hdf5(path: &Path) -> Result<LoadedDataset, String> {
+    Err(format!(
+        "{}: HDF5 datasets need `--features ann-hdf5`",
+        path.display()
+    ))
+}
@@ -0,0 +1,56 @@
+//! Recommended operating point of a parameter sweep.
+//!
+//! Kept apart from `report` so tests/ann_modules.rs can include it without
+//! the rest of the bench.
+
+/// One swept configuration, as the recommendation sees it.
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    pub recall: f64,
+    pub search_qps: f64,
+}
+
+/// Index into `points` of the recommended point, and whether it reaches
+/// `target`. Fastest point at or above the target recall; otherwise the
+/// highest-recall point. `None` only for an empty slice.
+pub fn recommend(points: &[Point], target: f64) -> Option<(usize, bool)> {
+    let by = |a: f64, b: f64| a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal);
+    let fastest = points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.recall >= target)
+        .max_by(|(_, a), (_, b)| by(a.search_qps, b.search_qps));
+    match fastest {
+        Some((i, _)) => Some((i, true)),
+        None => points
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| by(a.recall, b.recall).then(by(a.search_qps, b.search_qps)))
+            .map(|(i, _)| (i, false)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(p: &[(f64, f64)]) -> Vec<Point> {
+        p.iter()
+            .map(|&(recall, search_qps)| Point { recall, search_qps })
+            .collect()
+    }
+
+    #[test]
+    fn recommend_picks_fastest_at_target() {
+        let p = points(&[(0.99, 1_000.0), (0.96, 4_000.0), (0.95, 5_000.0), (0.90, 9_000.0)]);
+        assert_eq!(recommend(&p, 0.95), Some((2, true)));
+        assert_eq!(recommend(&p, 0.97), Some((0, true)));
+    }
+
+    #[test]
+    fn recommend_falls_back_to_highest_recall() {
+        let p = points(&[(0.80, 1_000.0), (0.85, 500.0), (0.85, 700.0)]);
+        assert_eq!(recommend(&p, 0.95), Some((2, false)));
+        assert_eq!(recommend(&[], 0.95), None);
+    }
+}
@@ -0,0 +1,288 @@
+//! In-process reference: the same dataset in a pure-Rust HNSW crate.
+//!
+//! Mirrors graph_bfs's petgraph arm. `--compare` builds the train set into
+//! [instant-distance](https://crates.io/crates/instant-distance), searches the
+//! same queries at every k, and scores them against the same ground truth, so
+//! the two rows differ only in the engine. Needs `--features ann-compare`.
+//!
+//! instant-distance builds its graph on all cores and fixes M internally, so
+//! its build QPS is not a like-for-like number. Search is single-threaded on
+//! both sides.
+
+use crate::dataset::{compute_recall, AnnDataset, GroundTruth};
+use crate::harness::recorder::ResultRecorder;
+use crate::sweep::IndexParams;
+use crate::{dataset_params, percentiles, truncate_ground_truth, AnnResult, Config};
+use std::time::Duration;
+use strata_benchmarks::fmt::{fmt_duration, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+/// Engine name used in result names and tables.
+pub const ENGINE: &str = "instant-distance";
+
+/// Whether this build includes the reference engine.
+pub const AVAILABLE: bool = cfg!(feature = "ann-compare");
+
+/// instant-distance's own defaults, used for knobs the run doesn't set.
+const DEFAULT_EF_CONSTRUCTION: usize = 100;
+const DEFAULT_EF_SEARCH: usize = 100;
+
+/// Searches at one k: per-query latency and returned train indices, in
+/// query order, plus the wall time.
+struct KRun {
+    k: usize,
+    latencies: Vec<Duration>,
+    returned: Vec<Vec<usize>>,
+    wall: Duration,
+}
+
+#[cfg(feature = "ann-compare")]
+mod engine {
+    use super::KRun;
+    use crate::dataset::{AnnDataset, Metric};
+    use instant_distance::{Builder, Point, Search};
+    use std::time::{Duration, Instant};
+
+    /// `1 - cos` on L2-normalized vectors.
+    #[derive(Clone)]
+    struct Cosine(Vec<f32>);
+
+    impl Point for Cosine {
+        fn distance(&self, other: &Self) -> f32 {
+            1.0 - self.0.iter().zip(&other.0).map(|(a, b)| a * b).sum::<f32>()
+        }
+    }
+
+    #[derive(Clone)]
+    struct Euclidean(Vec<f32>);
+
+    impl Point for Euclidean {
+        fn distance(&self, other: &Self) -> f32 {
+            self.0
+                .iter()
+                .zip(&other.0)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f32>()
+                .sqrt()
+        }
+    }
+
+    fn run_with<P: Point>(
+        wrap: fn(Vec<f32>) -> P,
+        dataset: &AnnDataset,
+        queries: usize,
+        ks: &[usize],
+        ef_construction: usize,
+        ef_search: usize,
+        seed: u64,
+    ) -> (Duration, Vec<KRun>) {
+        let points: Vec<P> = dataset.train_vectors.iter().cloned().map(wrap).collect();
+        let values: Vec<usize> = (0..points.len()).collect();
+
+        let build_start = Instant::now();
+        let map = Builder::default()
+            .ef_construction(ef_construction)
+            .ef_search(ef_search)
+            .seed(seed)
+            .build(points, values);
+        let build = build_start.elapsed();
+
+        let query_points: Vec<P> = dataset.query_vectors[..queries].iter().cloned().map(wrap).collect();
+        let mut search = Search::default();
+        let runs = ks
+            .iter()
+            .map(|&k| {
+                let mut latencies = Vec::with_capacity(queries);
+                let mut returned = Vec::with_capacity(queries);
+                let wall_start = Instant::now();
+                for q in &query_points {
+                    let start = Instant::now();
+                    let hits: Vec<usize> = map.search(q, &mut search).take(k).map(|item| *item.value).collect();
+                    latencies.push(start.elapsed());
+                    returned.push(hits);
+                }
+                KRun {
+                    k,
+                    latencies,
+                    returned,
+                    wall: wall_start.elapsed(),
+                }
+            })
+            .collect();
+        (build, runs)
+    }
+
+    pub(super) fn run(
+        dataset: &AnnDataset,
+        queries: usize,
+        ks: &[usize],
+        ef_construction: usize,
+        ef_search: usize,
+        seed: u64,
+    ) -> (Duration, Vec<KRun>) {
+        match dataset.metric {
+            Metric::Angular => run_with(Cosine, dataset, queries, ks, ef_construction, ef_search, seed),
+            Metric::Euclidean => run_with(Euclidean, dataset, queries, ks, ef_construction, ef_search, seed),
+        }
+    }
+}
+
+#[cfg(not(feature = "ann-compare"))]
+mod engine {
+    use super::KRun;
+    use crate::dataset::AnnDataset;
+    use std::time::Duration;
+
+    pub(super) fn run(
+        _dataset: &AnnDataset,
+        _queries: usize,
+        _ks: &[usize],
+        _ef_construction: usize,
+        _ef_search: usize,
+        _seed: u64,
+    ) -> (Duration, Vec<KRun>) {
+        unreachable!("--compare needs `--features ann-compare`; checked at startup")
+    }
+}
+
+/// Build and search the reference index, then print it next to Strata's
+/// default-knob rows from `strata` and record one result per k.
+pub fn compare(
+    name: &str,
+    dataset: &AnnDataset,
+    ground_truth: &GroundTruth,
+    config: &Config,
+    strata: &[AnnResult],
+    recorder: &mut ResultRecorder,
+) {
+    let scale = dataset.train_vectors.len();
+    let queries = config.queries.min(dataset.query_vectors.len());
+    let verbose = !config.csv && !config.quiet;
+    let index = IndexParams {
+        ef_search: config.sweep.search_values()[0],
+        ..config.sweep.build_points()[0]
+    };
+    let ef_construction = index.ef_construction.unwrap_or(DEFAULT_EF_CONSTRUCTION);
+    let ef_search = index.ef_search.unwrap_or(DEFAULT_EF_SEARCH);
+    let mut ks: Vec<usize> = strata.iter().map(|r| r.k).collect();
+    ks.sort_unstable();
+    ks.dedup();
+
+    if verbose {
+        if index.m.is_some() {
+            eprintln!("  Note: {} fixes M internally; --m applies to Strata only", ENGINE);
+        }
+        eprint!("  Building {} index ({} vectors)...", ENGINE, fmt_num(scale as u64));
+    }
+    let (build, runs) = engine::run(dataset, queries, &ks, ef_construction, ef_search, config.seed);
+    let build_qps = scale as f64 / build.as_secs_f64();
+    if verbose {
+        eprintln!(" {:.2}s ({} inserts/s)", build.as_secs_f64(), fmt_num(build_qps as u64));
+        eprintln!();
+        eprintln!(
+            "--- {}: Strata vs {} (efC={} ef={} for {}) ---",
+            name, ENGINE, ef_construction, ef_search, ENGINE
+        );
+        eprintln!(
+            "  {:<16}  {:>5}  {:>10}  {:>10}  {:>8}  {:>10}  {:>10}",
+            "engine", "k", "build QPS", "search QPS", "recall", "p50", "p99"
+        );
+    }
+
+    for run in &runs {
+        let gt_k = truncate_ground_truth(ground_truth, run.k);
+        let keys: Vec<Vec<String>> = run
+            .returned
+            .iter()
+            .map(|hits| hits.iter().map(|&i| dataset.train_keys[i].clone()).collect())
+            .collect();
+        let recall = compute_recall(&keys, &gt_k, dataset);
+        let search_qps = queries as f64 / run.wall.as_secs_f64();
+        let (p50, p95, p99) = percentiles(&run.latencies);
+
+        if config.csv {
+            // Same columns as the static benchmark; the engine goes in the dataset field
+            println!(
+                "\"{}/{}\",{},{},{},,{},{},{:.2},{:.2},{:.6},{:.1},{:.1},{:.1},",
+                name,
+                ENGINE,
+                scale,
+                run.k,
+                dataset.dim,
+                ef_construction,
+                ef_search,
+                build_qps,
+                search_qps,
+                recall,
+                p50.as_nanos() as f64 / 1_000.0,
+                p95.as_nanos() as f64 / 1_000.0,
+                p99.as_nanos() as f64 / 1_000.0,
+            );
+        } else if config.quiet {
+            eprintln!(
+                "ann {} {}@k={}: recall={:.4}, search={} QPS, build={} QPS, p50={}",
+                ENGINE,
+                name,
+                run.k,
+                recall,
+                fmt_num(search_qps as u64),
+                fmt_num(build_qps as u64),
+                fmt_duration(p50),
+            );
+        } else {
+            if let Some(s) = strata.iter().find(|r| r.k == run.k && r.index == index) {
+                eprintln!(
+                    "  {:<16}  {:>5}  {:>10}  {:>10}  {:>8.4}  {:>10}  {:>10}",
+                    "strata",
+                    s.k,
+                    fmt_num(s.build_qps as u64),
+                    fmt_num(s.search_qps as u64),
+                    s.recall,
+                    fmt_duration(s.p50),
+                    fmt_duration(s.p99),
+                );
+            }
+            eprintln!(
+                "  {:<16}  {:>5}  {:>10}  {:>10}  {:>8.4}  {:>10}  {:>10}",
+                ENGINE,
+                run.k,
+                fmt_num(build_qps as u64),
+                fmt_num(search_qps as u64),
+                recall,
+                fmt_duration(p50),
+                fmt_duration(p99),
+            );
+        }
+
+        let mut params = dataset_params(name, dataset, config);
+        params.insert("engine".into(), serde_json::json!(ENGINE));
+        params.insert("k".into(), serde_json::json!(run.k));
+        params.insert("recall".into(), serde_json::json!(recall));
+        params.insert("build_qps".into(), serde_json::json!(build_qps));
+        params.insert("queries".into(), serde_json::json!(queries));
+        params.insert("ef_construction".into(), serde_json::json!(ef_construction));
+        params.insert("ef_search".into(), serde_json::json!(ef_search));
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!("ann/{}/k{}/{}d/{}", name, run.k, dataset.dim, ENGINE),
+            category: "ann".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(search_qps),
+                p50_ns: Some(p50.as_nanos() as u64),
+                p95_ns: Some(p95.as_nanos() as u64),
+                p99_ns: Some(p99.as_nanos() as u64),
+                samples: Some(run.latencies.len() as u64),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::Success,
+        });
+    }
+
+    if verbose {
+        eprintln!("  ({} builds on all cores; its build QPS is not like-for-like)", ENGINE);
+        eprintln!();
+    }
+}
@@ -0,0 +1,216 @@
+//! Markdown report for the static benchmark.
+//!
+//! Written next to the results JSON, like `graph_bfs`'s report. One table
+//! per dataset lists every swept configuration with its recall, QPS, and
+//! memory. A summary table then picks a recommended operating point for
+//! each k: the fastest configuration whose mean recall reaches
+//! `--target-recall`. If no configuration reaches it, the one with the
+//! highest recall is shown and marked as short of the target.
+
+use crate::operating_point::{recommend, Point};
+use crate::sweep::IndexParams;
+use crate::{fmt_knob, harness, AnnResult, Config};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use strata_benchmarks::fmt::{fmt_duration, fmt_num};
+
+pub const DEFAULT_TARGET_RECALL: f64 = 0.95;
+
+/// One configuration's numbers, kept after its `AnnResult` is dropped.
+pub struct Row {
+    pub name: String,
+    pub scale: usize,
+    pub dim: usize,
+    pub metric: &'static str,
+    pub index: IndexParams,
+    pub k: usize,
+    pub recall: f64,
+    pub recall_p10: f64,
+    pub recall_min: f64,
+    pub search_qps: f64,
+    pub build_qps: f64,
+    pub bytes_per_vector: Option<f64>,
+    pub p50: Duration,
+    pub p99: Duration,
+    pub pareto: bool,
+}
+
+impl Row {
+    pub fn new(r: &AnnResult, metric: &'static str) -> Self {
+        Self {
+            name: r.name.clone(),
+            scale: r.scale,
+            dim: r.dim,
+            metric,
+            index: r.index,
+            k: r.k,
+            recall: r.recall,
+            recall_p10: r.recall_dist.p10,
+            recall_min: r.recall_dist.min,
+            search_qps: r.search_qps,
+            build_qps: r.build_qps,
+            bytes_per_vector: r.bytes_per_vector,
+            p50: r.p50,
+            p99: r.p99,
+            pareto: r.pareto,
+        }
+    }
+
+    fn point(&self) -> Point {
+        Point {
+            recall: self.recall,
+            search_qps: self.search_qps,
+        }
+    }
+}
+
+fn fmt_bytes_per_vector(b: Option<f64>) -> String {
+    b.map(|b| format!("{:.0}", b)).unwrap_or_else(|| "—".to_string())
+}
+
+/// The (dataset, dim) groups in first-seen order.
+fn groups(rows: &[Row]) -> Vec<(&str, usize)> {
+    let mut groups: Vec<(&str, usize)> = Vec::new();
+    for r in rows {
+        if !groups.contains(&(r.name.as_str(), r.dim)) {
+            groups.push((r.name.as_str(), r.dim));
+        }
+    }
+    groups
+}
+
+pub fn write_markdown_report(json_path: &Path, rows: &[Row], config: &Config) -> std::io::Result<PathBuf> {
+    let json_name = json_path.file_name().unwrap_or_default().to_string_lossy();
+    let md_name = json_name.replacen("ann-", "ann-report-", 1).replace(".json", ".md");
+    let md_path = json_path.with_file_name(md_name);
+
+    let mut md = String::new();
+    md.push_str("# ANN Benchmark Report\n\n");
+
+    // Hardware
+    md.push_str("## Hardware\n\n");
+    md.push_str("| Field | Value |\n");
+    md.push_str("|-------|-------|\n");
+    md.push_str(&format!("| CPU | {} |\n", harness::read_cpu_model()));
+    md.push_str(&format!(
+        "| Cores | {} |\n",
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(0)
+    ));
+    md.push_str(&format!("| RAM | {} GB |\n", harness::read_total_ram_gb()));
+    md.push_str(&format!(
+        "| OS | {} ({}) |\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    md.push('\n');
+
+    // Configuration
+    md.push_str("## Configuration\n\n");
+    md.push_str("| Field | Value |\n");
+    md.push_str("|-------|-------|\n");
+    md.push_str(&format!("| Queries | {} |\n", config.queries));
+    md.push_str(&format!(
+        "| Query source | {} |\n",
+        config.query_source.map(|s| s.label()).unwrap_or("default")
+    ));
+    md.push_str(&format!("| Durability | {} |\n", config.durability.label()));
+    md.push_str(&format!("| Target recall | {} |\n", config.target_recall));
+    if !config.sweep.is_empty() {
+        md.push_str(&format!(
+            "| Sweep | M={:?} ef_construction={:?} ef_search={:?} |\n",
+            config.sweep.m, config.sweep.ef_construction, config.sweep.ef_search
+        ));
+    }
+    md.push('\n');
+
+    // Recommended operating points
+    md.push_str(&format!(
+        "## Recommended Operating Points (recall ≥ {})\n\n",
+        config.target_recall
+    ));
+    md.push_str("| Dataset | Dim | k | M | efC | ef | Recall | Search QPS | B/vector | Note |\n");
+    md.push_str("|---------|-----|---|---|-----|----|--------|------------|----------|------|\n");
+    for (name, dim) in groups(rows) {
+        let mut ks: Vec<usize> = rows
+            .iter()
+            .filter(|r| r.name == name && r.dim == dim)
+            .map(|r| r.k)
+            .collect();
+        ks.sort_unstable();
+        ks.dedup();
+        for k in ks {
+            let candidates: Vec<&Row> =
+                rows.iter().filter(|r| r.name == name && r.dim == dim && r.k == k).collect();
+            let points: Vec<Point> = candidates.iter().map(|r| r.point()).collect();
+            if let Some((i, reached)) = recommend(&points, config.target_recall) {
+                let r = candidates[i];
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} | {:.4} | {} | {} | {} |\n",
+                    name,
+                    dim,
+                    k,
+                    fmt_knob(r.index.m),
+                    fmt_knob(r.index.ef_construction),
+                    fmt_knob(r.index.ef_search),
+                    r.recall,
+                    fmt_num(r.search_qps as u64),
+                    fmt_bytes_per_vector(r.bytes_per_vector),
+                    if reached { "fastest at target" } else { "**target not reached**; highest recall" },
+                ));
+            }
+        }
+    }
+    md.push('\n');
+
+    // Every configuration, per dataset
+    for (name, dim) in groups(rows) {
+        let group: Vec<&Row> = rows.iter().filter(|r| r.name == name && r.dim == dim).collect();
+        md.push_str(&format!(
+            "## {} ({} vectors, {}d, {})\n\n",
+            name,
+            fmt_num(group[0].scale as u64),
+            dim,
+            group[0].metric
+        ));
+        md.push_str(
+            "| k | M | efC | ef | Recall | Recall p10 | Recall min | Search QPS | Build QPS | B/vector | p50 | p99 | Frontier |\n",
+        );
+        md.push_str(
+            "|---|---|-----|----|--------|------------|------------|------------|-----------|----------|-----|-----|----------|\n",
+        );
+        for r in group {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {:.4} | {:.2} | {:.2} | {} | {} | {} | {} | {} | {} |\n",
+                r.k,
+                fmt_knob(r.index.m),
+                fmt_knob(r.index.ef_construction),
+                fmt_knob(r.index.ef_search),
+                r.recall,
+                r.recall_p10,
+                r.recall_min,
+                fmt_num(r.search_qps as u64),
+                fmt_num(r.build_qps as u64),
+                fmt_bytes_per_vector(r.bytes_per_vector),
+                fmt_duration(r.p50),
+                fmt_duration(r.p99),
+                if r.pareto { "*" } else { "" },
+            ));
+        }
+        md.push('\n');
+    }
+    md.push_str(
+        "B/vector is RSS growth across the build divided by the vector count; the raw vectors take dim × 4 bytes. \
+         `*` marks the recall/QPS Pareto frontier for that k.\n\n",
+    );
+
+    // Raw JSON pointer
+    md.push_str("## Raw Data\n\n");
+    md.push_str(&format!(
+        "Machine-readable results: `{}`\n",
+        json_path.file_name().unwrap_or_default().to_string_lossy()
+    ));
+
+    std::fs::write(&md_path, &md)?;
+    eprintln!("Markdown report saved to {}", md_path.display());
+    Ok(md_path)
+}
@@ -0,0 +1,362 @@
+//! Streaming ingestion: searches served while new vectors are being indexed.
+//!
+//! RAG systems index documents while answering queries. Here the index is
+//! built from the first half of the corpus, then for `--stream-secs` one
+//! writer upserts the rest at a target rate while `--readers` threads search.
+//! Every target rate starts from a fresh half-built index. Rate 0 is the
+//! read-only baseline; `max` upserts as fast as the writer can.
+//!
+//! Recall is scored against the vectors visible when each search started:
+//! the writer publishes how many upserts have completed, and ground truth is
+//! brute-forced over that prefix of the corpus. A vector that lands mid-search
+//! and outranks a true neighbor counts as a miss, so recall under load is a
+//! slight underestimate. Each reader keeps a uniform random sample of its
+//! searches for scoring, `--queries` in total.
+
+use crate::dataset::{nearest, query_recall, AnnDataset, FastRng, GroundTruth};
+use crate::harness::clock::{run_paced, Clock, RealClock};
+use crate::harness::create_db;
+use crate::harness::recorder::ResultRecorder;
+use crate::sweep::IndexParams;
+use crate::{dataset_params, distance_metric, percentiles, usable_ks, Config, COLLECTION};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_duration, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Strata;
+
+/// Target upserts per second; `None` = unthrottled.
+pub const DEFAULT_INSERT_RATES: &[Option<u64>] = &[Some(0), Some(1_000), Some(10_000), None];
+pub const DEFAULT_STREAM_SECS: u64 = 10;
+pub const DEFAULT_READERS: usize = 1;
+/// Share of the corpus indexed before the window opens.
+const INITIAL_FRACTION: f64 = 0.5;
+
+/// One (insert rate, k) window.
+struct StreamResult {
+    rate: Option<u64>,
+    k: usize,
+    inserted: usize,
+    insert_rate: f64,
+    search_qps: f64,
+    recall: f64,
+    scored: usize,
+    samples: usize,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+}
+
+/// A search kept for recall scoring.
+struct Scored {
+    query: usize,
+    /// Train vectors visible when the search started.
+    visible: usize,
+    keys: Vec<String>,
+}
+
+/// `1000`, `max`, ...
+pub fn rate_label(rate: Option<u64>) -> String {
+    rate.map(|r| r.to_string()).unwrap_or_else(|| "max".into())
+}
+
+/// Parse `0,1000,max`. `None` if an entry is neither, or there are none.
+pub fn parse_rates(s: &str) -> Option<Vec<Option<u64>>> {
+    let rates: Option<Vec<_>> = s
+        .split(',')
+        .map(|v| match v.trim() {
+            "max" => Some(None),
+            v => v.parse().ok().map(Some),
+        })
+        .collect();
+    rates.filter(|r| !r.is_empty())
+}
+
+/// Upsert `dataset.train_vectors[range]` in order, pacing to `rate` per
+/// second, until the range or the window runs out. Publishes the number of
+/// completed upserts in `streamed`. Returns the count and the time taken.
+fn stream_writer(
+    db: &Strata,
+    dataset: &AnnDataset,
+    range: std::ops::Range<usize>,
+    rate: Option<u64>,
+    window: Duration,
+    streamed: &AtomicUsize,
+) -> (usize, Duration) {
+    let clock = RealClock::start();
+    let n = run_paced(&clock, rate, window, range.len(), |n| {
+        let i = range.start + n;
+        db.vector_upsert(
+            COLLECTION,
+            &dataset.train_keys[i],
+            dataset.train_vectors[i].clone(),
+            None,
+        )
+        .unwrap();
+        streamed.store(n + 1, Ordering::Release);
+    });
+    (n, clock.elapsed())
+}
+
+/// Index the first `initial` train vectors, then run one streaming window.
+#[allow(clippy::too_many_arguments)]
+fn run_window(
+    dataset: &AnnDataset,
+    config: &Config,
+    index: IndexParams,
+    initial: usize,
+    queries: usize,
+    k: usize,
+    rate: Option<u64>,
+    readers: usize,
+) -> StreamResult {
+    let db = create_db(config.durability);
+    index.apply_build(&db.db).unwrap_or_else(|e| panic!("{}", e));
+    index.apply_search(&db.db).unwrap_or_else(|e| panic!("{}", e));
+    db.db
+        .vector_create_collection(COLLECTION, dataset.dim as u64, distance_metric(dataset.metric))
+        .unwrap();
+    for i in 0..initial {
+        db.db
+            .vector_upsert(COLLECTION, &dataset.train_keys[i], dataset.train_vectors[i].clone(), None)
+            .unwrap();
+    }
+
+    let window = Duration::from_secs(config.stream_secs);
+    let keep = queries.div_ceil(readers);
+    let streamed = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let barrier = Barrier::new(readers + 1);
+
+    let (write, per_reader, wall) = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..readers)
+            .map(|tid| {
+                let handle = db.db.new_handle().expect("failed to create search handle");
+                let (barrier, stop, streamed) = (&barrier, &stop, &streamed);
+                scope.spawn(move || {
+                    let mut rng = FastRng::new(config.seed ^ 0x57_0000 ^ tid as u64);
+                    let mut latencies = Vec::new();
+                    let mut kept: Vec<Scored> = Vec::with_capacity(keep);
+                    let mut i = tid * queries / readers;
+                    barrier.wait();
+                    while !stop.load(Ordering::Acquire) {
+                        let q = i % queries;
+                        let visible = initial + streamed.load(Ordering::Acquire);
+                        let start = Instant::now();
+                        let results = handle
+                            .vector_search(COLLECTION, dataset.query_vectors[q].clone(), k as u64)
+                            .unwrap();
+                        latencies.push(start.elapsed());
+
+                        // Algorithm R over this reader's searches
+                        let slot = if kept.len() < keep {
+                            Some(kept.len())
+                        } else {
+                            Some(rng.next_usize(latencies.len())).filter(|&j| j < keep)
+                        };
+                        if let Some(slot) = slot {
+                            let scored = Scored {
+                                query: q,
+                                visible,
+                                keys: results.iter().map(|m| m.key.clone()).collect(),
+                            };
+                            if slot == kept.len() {
+                                kept.push(scored);
+                            } else {
+                                kept[slot] = scored;
+                            }
+                        }
+                        i += 1;
+                    }
+                    (latencies, kept)
+                })
+            })
+            .collect();
+
+        barrier.wait();
+        let start = Instant::now();
+        let write = stream_writer(&db.db, dataset, initial..dataset.train_vectors.len(), rate, window, &streamed);
+        stop.store(true, Ordering::Release);
+        let per_reader: Vec<_> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+        (write, per_reader, start.elapsed())
+    });
+
+    let (inserted, write_elapsed) = write;
+    let mut latencies = Vec::new();
+    let mut scored = Vec::new();
+    for (l, kept) in per_reader {
+        latencies.extend(l);
+        scored.extend(kept);
+    }
+
+    let recall_sum: f64 = scored
+        .iter()
+        .map(|s| {
+            let truth = nearest(dataset, &dataset.query_vectors[s.query], k, |i| i < s.visible);
+            query_recall(&s.keys, &truth, dataset).unwrap_or(0.0)
+        })
+        .sum();
+    let (p50, p95, p99) = percentiles(&latencies);
+
+    StreamResult {
+        rate,
+        k,
+        inserted,
+        insert_rate: inserted as f64 / write_elapsed.as_secs_f64(),
+        search_qps: latencies.len() as f64 / wall.as_secs_f64(),
+        recall: recall_sum / scored.len().max(1) as f64,
+        scored: scored.len(),
+        samples: latencies.len(),
+        p50,
+        p95,
+        p99,
+    }
+}
+
+pub fn print_csv_header() {
+    println!(
+        "\"dataset\",\"target_insert_rate\",\"k\",\"readers\",\"inserted\",\"insert_rate\",\"search_qps\",\"recall\",\"p50_us\",\"p95_us\",\"p99_us\""
+    );
+}
+
+/// Run one streaming window per (k, insert rate). Uses the first value of
+/// each swept knob.
+pub fn run_streaming(
+    name: &str,
+    dataset: &AnnDataset,
+    ground_truth: &GroundTruth,
+    config: &Config,
+    recorder: &mut ResultRecorder,
+) {
+    let scale = dataset.train_vectors.len();
+    let queries = config.queries.min(dataset.query_vectors.len());
+    let verbose = !config.csv && !config.quiet;
+    let ks = usable_ks(config, ground_truth);
+    let initial = ((scale as f64 * INITIAL_FRACTION) as usize).clamp(1, scale);
+    let readers = config.readers;
+
+    let index = IndexParams {
+        ef_search: config.sweep.search_values()[0],
+        ..config.sweep.build_points()[0]
+    };
+
+    let mut results = Vec::new();
+    for &k in &ks {
+        for &rate in &config.insert_rates {
+            if verbose {
+                eprint!(
+                    "  k={} insert rate {}: indexing {} vectors, then streaming for {}s...",
+                    k,
+                    rate_label(rate),
+                    fmt_num(initial as u64),
+                    config.stream_secs
+                );
+            }
+            let r = run_window(dataset, config, index, initial, queries, k, rate, readers);
+            if verbose {
+                eprintln!(" {} upserts", fmt_num(r.inserted as u64));
+            }
+            results.push(r);
+        }
+    }
+
+    if verbose {
+        eprintln!();
+        eprintln!(
+            "--- {}: streaming ingest, {} + {} vectors, {}d, {} reader(s){} ---",
+            name,
+            fmt_num(initial as u64),
+            fmt_num((scale - initial) as u64),
+            dataset.dim,
+            readers,
+            index.suffix()
+        );
+        eprintln!(
+            "  {:>8}  {:>5}  {:>10}  {:>10}  {:>10}  {:>8}  {:>10}  {:>10}",
+            "target/s", "k", "inserted", "inserts/s", "search QPS", "recall", "p50", "p99"
+        );
+    }
+
+    for r in &results {
+        if config.csv {
+            println!(
+                "\"{}\",\"{}\",{},{},{},{:.2},{:.2},{:.6},{:.1},{:.1},{:.1}",
+                name,
+                rate_label(r.rate),
+                r.k,
+                readers,
+                r.inserted,
+                r.insert_rate,
+                r.search_qps,
+                r.recall,
+                r.p50.as_nanos() as f64 / 1_000.0,
+                r.p95.as_nanos() as f64 / 1_000.0,
+                r.p99.as_nanos() as f64 / 1_000.0,
+            );
+        } else if config.quiet {
+            eprintln!(
+                "ann streaming {}@k={} rate={}: {} inserts/s, search={} QPS, recall={:.4}, p99={}",
+                name,
+                r.k,
+                rate_label(r.rate),
+                fmt_num(r.insert_rate as u64),
+                fmt_num(r.search_qps as u64),
+                r.recall,
+                fmt_duration(r.p99),
+            );
+        } else {
+            eprintln!(
+                "  {:>8}  {:>5}  {:>10}  {:>10}  {:>10}  {:>8.4}  {:>10}  {:>10}",
+                rate_label(r.rate),
+                r.k,
+                fmt_num(r.inserted as u64),
+                fmt_num(r.insert_rate as u64),
+                fmt_num(r.search_qps as u64),
+                r.recall,
+                fmt_duration(r.p50),
+                fmt_duration(r.p99),
+            );
+        }
+
+        let mut params = dataset_params(name, dataset, config);
+        params.insert("k".into(), serde_json::json!(r.k));
+        params.insert("initial".into(), serde_json::json!(initial));
+        params.insert("target_insert_rate".into(), serde_json::json!(r.rate));
+        params.insert("insert_rate".into(), serde_json::json!(r.insert_rate));
+        params.insert("inserted".into(), serde_json::json!(r.inserted));
+        params.insert("stream_secs".into(), serde_json::json!(config.stream_secs));
+        params.insert("recall".into(), serde_json::json!(r.recall));
+        params.insert("recall_samples".into(), serde_json::json!(r.scored));
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!(
+                "ann/{}/k{}/{}d{}/stream{}",
+                name,
+                r.k,
+                dataset.dim,
+                index.suffix(),
+                rate_label(r.rate)
+            ),
+            category: "ann".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(r.search_qps),
+                p50_ns: Some(r.p50.as_nanos() as u64),
+                p95_ns: Some(r.p95.as_nanos() as u64),
+                p99_ns: Some(r.p99.as_nanos() as u64),
+                samples: Some(r.samples as u64),
+                threads: Some(readers),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::Success,
+        });
+    }
+
+    if verbose {
+        eprintln!("  (recall is scored against the vectors visible when each search started)");
+        eprintln!();
+    }
+}
@@ -0,0 +1,158 @@
+//! HNSW parameter sweeps and the recall/QPS Pareto frontier.
+//!
+//! Index knobs are passed to the engine through `config_set`: build knobs
+//! (M, ef_construction) before the collection is created, the search knob
+//! (ef_search) before each batch of queries. Knobs the engine rejects are
+//! dropped from the sweep with a warning, so the benchmark still runs at
+//! the engine's defaults on builds that don't expose them.
+
+use stratadb::Strata;
+
+/// `config_set` keys for the HNSW knobs.
+pub const KEY_M: &str = "hnsw_m";
+pub const KEY_EF_CONSTRUCTION: &str = "hnsw_ef_construction";
+pub const KEY_EF_SEARCH: &str = "hnsw_ef_search";
+
+// ---------------------------------------------------------------------------
+// One operating point
+// ---------------------------------------------------------------------------
+
+/// Index parameters for one point. `None` means the engine default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IndexParams {
+    pub m: Option<usize>,
+    pub ef_construction: Option<usize>,
+    pub ef_search: Option<usize>,
+}
+
+impl IndexParams {
+    /// Benchmark-name suffix, e.g. `/M16/efc200/ef64`. Empty when every
+    /// knob is at its default, so unswept runs keep their historical names.
+    pub fn suffix(&self) -> String {
+        let mut s = String::new();
+        if let Some(m) = self.m {
+            s.push_str(&format!("/M{}", m));
+        }
+        if let Some(efc) = self.ef_construction {
+            s.push_str(&format!("/efc{}", efc));
+        }
+        if let Some(ef) = self.ef_search {
+            s.push_str(&format!("/ef{}", ef));
+        }
+        s
+    }
+
+    fn build_knobs(&self) -> Vec<(&'static str, usize)> {
+        let mut knobs = Vec::new();
+        if let Some(m) = self.m {
+            knobs.push((KEY_M, m));
+        }
+        if let Some(efc) = self.ef_construction {
+            knobs.push((KEY_EF_CONSTRUCTION, efc));
+        }
+        knobs
+    }
+
+    /// Apply the build knobs. Call before `vector_create_collection`.
+    pub fn apply_build(&self, db: &Strata) -> Result<(), String> {
+        for (key, value) in self.build_knobs() {
+            apply(db, key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Apply the search knob. Call before searching.
+    pub fn apply_search(&self, db: &Strata) -> Result<(), String> {
+        match self.ef_search {
+            Some(ef) => apply(db, KEY_EF_SEARCH, ef),
+            None => Ok(()),
+        }
+    }
+}
+
+fn apply(db: &Strata, key: &str, value: usize) -> Result<(), String> {
+    db.config_set(key, &value.to_string())
+        .map(|_| ())
+        .map_err(|e| format!("config_set({}, {}) failed: {:?}", key, value, e))
+}
+
+// ---------------------------------------------------------------------------
+// Sweep specification
+// ---------------------------------------------------------------------------
+
+/// Values to sweep per knob. An empty list leaves the knob at its default.
+#[derive(Clone, Debug, Default)]
+pub struct SweepSpec {
+    pub m: Vec<usize>,
+    pub ef_construction: Vec<usize>,
+    pub ef_search: Vec<usize>,
+}
+
+fn or_default(values: &[usize]) -> Vec<Option<usize>> {
+    if values.is_empty() {
+        vec![None]
+    } else {
+        values.iter().copied().map(Some).collect()
+    }
+}
+
+impl SweepSpec {
+    pub fn is_empty(&self) -> bool {
+        self.m.is_empty() && self.ef_construction.is_empty() && self.ef_search.is_empty()
+    }
+
+    /// Every (M, ef_construction) combination. Each needs its own index.
+    pub fn build_points(&self) -> Vec<IndexParams> {
+        let mut points = Vec::new();
+        for m in or_default(&self.m) {
+            for ef_construction in or_default(&self.ef_construction) {
+                points.push(IndexParams {
+                    m,
+                    ef_construction,
+                    ef_search: None,
+                });
+            }
+        }
+        points
+    }
+
+    /// ef_search values to try against each built index.
+    pub fn search_values(&self) -> Vec<Option<usize>> {
+        or_default(&self.ef_search)
+    }
+
+    /// Probe each swept knob on `db` and drop the ones the engine rejects.
+    /// Returns the rejected keys.
+    pub fn retain_supported(&mut self, db: &Strata) -> Vec<&'static str> {
+        let mut rejected = Vec::new();
+        for (key, values) in [
+            (KEY_M, &mut self.m),
+            (KEY_EF_CONSTRUCTION, &mut self.ef_construction),
+            (KEY_EF_SEARCH, &mut self.ef_search),
+        ] {
+            if let Some(&first) = values.first() {
+                if apply(db, key, first).is_err() {
+                    values.clear();
+                    rejected.push(key);
+                }
+            }
+        }
+        rejected
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Pareto frontier
+// ---------------------------------------------------------------------------
+
+/// For each `(recall, qps)` point, whether it is on the Pareto frontier:
+/// no other point has recall and QPS both at least as high and one of them
+/// strictly higher.
+pub fn pareto_frontier(points: &[(f64, f64)]) -> Vec<bool> {
+    points
+        .iter()
+        .map(|&(recall, qps)| {
+            !points.iter().any(|&(r, q)| r >= recall && q >= qps && (r > recall || q > qps))
+        })
+        .collect()
+}
@@ -0,0 +1,249 @@
+//! Concurrent query throughput: many threads searching one built index.
+//!
+//! Single-threaded QPS understates serving capacity. With `--threads N` the
+//! index is built once, then searched from 1, 2, 4, ... N threads (each with
+//! its own handle). Every thread issues `--queries` searches, starting at a
+//! different offset into the query set so threads don't move in lockstep.
+//! Aggregate QPS is total searches over the wall time from a shared barrier
+//! to the last thread finishing.
+
+use crate::dataset::{query_recall, AnnDataset, GroundTruth};
+use crate::harness::create_db;
+use crate::harness::recorder::ResultRecorder;
+use crate::sweep::IndexParams;
+use crate::{build_index, dataset_params, percentiles, truncate_ground_truth, usable_ks, Config, COLLECTION};
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_duration, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+/// One (threads, k) measurement.
+struct ThreadsResult {
+    threads: usize,
+    k: usize,
+    qps: f64,
+    recall: f64,
+    samples: usize,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+}
+
+/// 1, 2, 4, ... up to and including `max`.
+pub fn thread_steps(max: usize) -> Vec<usize> {
+    let mut steps = Vec::new();
+    let mut n = 1;
+    while n < max {
+        steps.push(n);
+        n *= 2;
+    }
+    steps.push(max.max(1));
+    steps
+}
+
+/// Search from `threads` threads at once. Returns aggregate QPS, mean recall
+/// and all per-search latencies.
+fn search_concurrent(
+    db: &stratadb::Strata,
+    dataset: &AnnDataset,
+    gt: &GroundTruth,
+    queries: usize,
+    k: usize,
+    threads: usize,
+) -> (f64, f64, Vec<Duration>) {
+    let barrier = Barrier::new(threads + 1);
+
+    let (per_thread, wall) = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|tid| {
+                let handle = db.new_handle().expect("failed to create search handle");
+                let barrier = &barrier;
+                scope.spawn(move || {
+                    let offset = tid * queries / threads;
+                    let mut latencies = Vec::with_capacity(queries);
+                    let mut recall_sum = 0.0;
+                    barrier.wait();
+                    for i in 0..queries {
+                        let q = (offset + i) % queries;
+                        let query = dataset.query_vectors[q].clone();
+                        let start = Instant::now();
+                        let results = handle.vector_search(COLLECTION, query, k as u64).unwrap();
+                        latencies.push(start.elapsed());
+
+                        let keys: Vec<String> = results.iter().map(|m| m.key.clone()).collect();
+                        recall_sum += query_recall(&keys, &gt.neighbors[q], dataset).unwrap_or(0.0);
+                    }
+                    (latencies, recall_sum)
+                })
+            })
+            .collect();
+
+        barrier.wait();
+        let start = Instant::now();
+        let per_thread: Vec<_> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+        (per_thread, start.elapsed())
+    });
+
+    let total = (threads * queries) as f64;
+    let recall = per_thread.iter().map(|(_, r)| r).sum::<f64>() / total;
+    let latencies = per_thread.into_iter().flat_map(|(l, _)| l).collect();
+    (total / wall.as_secs_f64(), recall, latencies)
+}
+
+pub fn print_csv_header() {
+    println!(
+        "\"dataset\",\"threads\",\"k\",\"qps\",\"speedup\",\"efficiency\",\"recall\",\"p50_us\",\"p95_us\",\"p99_us\""
+    );
+}
+
+/// Build the index once and measure aggregate search QPS at each thread
+/// count. Uses the first value of each swept knob.
+pub fn run_threads(
+    name: &str,
+    dataset: &AnnDataset,
+    ground_truth: &GroundTruth,
+    config: &Config,
+    recorder: &mut ResultRecorder,
+) {
+    let scale = dataset.train_vectors.len();
+    let queries = config.queries.min(dataset.query_vectors.len());
+    let verbose = !config.csv && !config.quiet;
+    let ks = usable_ks(config, ground_truth);
+
+    let index = IndexParams {
+        ef_search: config.sweep.search_values()[0],
+        ..config.sweep.build_points()[0]
+    };
+
+    if verbose {
+        eprint!("  Building index ({} vectors)...", fmt_num(scale as u64));
+    }
+    let db = create_db(config.durability);
+    index.apply_build(&db.db).unwrap_or_else(|e| panic!("{}", e));
+    index.apply_search(&db.db).unwrap_or_else(|e| panic!("{}", e));
+    let build_elapsed = build_index(&db.db, dataset);
+    if verbose {
+        eprintln!(" {:.2}s", build_elapsed.as_secs_f64());
+    }
+
+    let mut results = Vec::new();
+    for &k in &ks {
+        let gt_k = truncate_ground_truth(ground_truth, k);
+        for &threads in &config.threads {
+            let (qps, recall, latencies) =
+                search_concurrent(&db.db, dataset, &gt_k, queries, k, threads);
+            let (p50, p95, p99) = percentiles(&latencies);
+            results.push(ThreadsResult {
+                threads,
+                k,
+                qps,
+                recall,
+                samples: latencies.len(),
+                p50,
+                p95,
+                p99,
+            });
+        }
+    }
+
+    if verbose {
+        eprintln!();
+        eprintln!(
+            "--- {}: concurrent search, {} vectors, {}d, {} queries/thread{} ---",
+            name,
+            fmt_num(scale as u64),
+            dataset.dim,
+            queries,
+            index.suffix()
+        );
+        eprintln!(
+            "  {:>7}  {:>5}  {:>12}  {:>8}  {:>10}  {:>8}  {:>10}  {:>10}",
+            "threads", "k", "QPS", "speedup", "efficiency", "recall", "p50", "p99"
+        );
+    }
+
+    for r in &results {
+        let single = results
+            .iter()
+            .find(|b| b.k == r.k && b.threads == 1)
+            .map(|b| b.qps)
+            .unwrap_or(r.qps);
+        let speedup = r.qps / single;
+        let efficiency = speedup / r.threads as f64;
+
+        if config.csv {
+            println!(
+                "\"{}\",{},{},{:.2},{:.3},{:.3},{:.6},{:.1},{:.1},{:.1}",
+                name,
+                r.threads,
+                r.k,
+                r.qps,
+                speedup,
+                efficiency,
+                r.recall,
+                r.p50.as_nanos() as f64 / 1_000.0,
+                r.p95.as_nanos() as f64 / 1_000.0,
+                r.p99.as_nanos() as f64 / 1_000.0,
+            );
+        } else if config.quiet {
+            eprintln!(
+                "ann threads {}@k={} {}t: {} QPS ({:.2}x), recall={:.4}, p99={}",
+                name,
+                r.k,
+                r.threads,
+                fmt_num(r.qps as u64),
+                speedup,
+                r.recall,
+                fmt_duration(r.p99),
+            );
+        } else {
+            eprintln!(
+                "  {:>7}  {:>5}  {:>12}  {:>7.2}x  {:>9.0}%  {:>8.4}  {:>10}  {:>10}",
+                r.threads,
+                r.k,
+                fmt_num(r.qps as u64),
+                speedup,
+                efficiency * 100.0,
+                r.recall,
+                fmt_duration(r.p50),
+                fmt_duration(r.p99),
+            );
+        }
+
+        let mut params = dataset_params(name, dataset, config);
+        params.insert("k".into(), serde_json::json!(r.k));
+        params.insert("recall".into(), serde_json::json!(r.recall));
+        params.insert("queries_per_thread".into(), serde_json::json!(queries));
+        params.insert("speedup".into(), serde_json::json!(speedup));
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!(
+                "ann/{}/k{}/{}d{}/{}t",
+                name,
+                r.k,
+                dataset.dim,
+                index.suffix(),
+                r.threads
+            ),
+            category: "ann".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(r.qps),
+                p50_ns: Some(r.p50.as_nanos() as u64),
+                p95_ns: Some(r.p95.as_nanos() as u64),
+                p99_ns: Some(r.p99.as_nanos() as u64),
+                samples: Some(r.samples as u64),
+                threads: Some(r.threads),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::Success,
+        });
+    }
+
+    if verbose {
+        eprintln!("  (speedup and efficiency are relative to 1 thread at the same k)");
+        eprintln!();
+    }
+}
@@ -5,33 +5,45 @@
 //!
 //! Run: `cargo bench --bench concurrency`
 //! Quick: `cargo bench --bench concurrency -- --threads 1,2,4`
+//! Fixed warmup: `cargo bench --bench concurrency -- --warmup 2`
 
 #[allow(unused)]
 #[path = "../harness/mod.rs"]
 mod harness;
 
 use harness::recorder::ResultRecorder;
+use harness::warmup::Warmup;
 use harness::scaling::{
-    parse_thread_counts, physical_cores, print_table_header, print_table_row,
+    physical_cores, print_table_header, print_table_row,
     run_scaling_experiment, ReservoirSampler, ScalingResult, ThreadResult,
 };
+use strata_benchmarks::fmt::{fmt_duration, fmt_ops};
 use harness::{create_db, DurabilityConfig};
 use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 use std::time::Instant;
-use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult};
-use stratadb::Value;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{Command, Output, Session, Value};
 
 // ---------------------------------------------------------------------------
 // Constants
 // ---------------------------------------------------------------------------
 
-const WARMUP_SECS: u64 = 1;
 const MEASURE_SECS: u64 = 5;
 
 /// Number of keys to pre-populate for read-heavy workloads.
 const PREPOPULATE_KEYS: usize = 100_000;
 
+/// Overlap percentages swept by the transactional conflict workload
+/// (`--overlap`).
+const OVERLAP_PCTS: [usize; 5] = [0, 25, 50, 75, 100];
+
+/// Keys read and written by each conflict-workload transaction.
+const KEYS_PER_TXN: usize = 8;
+
+/// Concurrent sessions in the conflict workload (`--txn-sessions`).
+const CONFLICT_SESSIONS: usize = 2;
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -54,6 +66,7 @@ fn record_scaling_result(
     let mut params = HashMap::new();
     params.insert("durability".into(), serde_json::json!(mode.label()));
     params.insert("workload".into(), serde_json::json!(workload));
+    params.extend(result.warmup.parameters().into_iter().map(|(k, v)| (k.to_string(), v)));
 
     recorder.record(BenchmarkResult {
         benchmark: format!("concurrency/{}/{}/{}t", workload, mode.label(), result.threads),
@@ -73,9 +86,26 @@ fn record_scaling_result(
             },
             ..Default::default()
         },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
     });
 }
 
+/// Run one workload's sweep; if it panics, the panic is recorded against
+/// the workload and the remaining workloads still run.
+fn guard_workload(
+    recorder: &mut ResultRecorder,
+    workload: &str,
+    mode: DurabilityConfig,
+    run: impl FnOnce(&mut ResultRecorder),
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("workload".into(), serde_json::json!(workload));
+    recorder.guard(&format!("concurrency/{}/{}", workload, mode.label()), params, run);
+}
+
 // ---------------------------------------------------------------------------
 // Workload: KV GET (read-only, no contention)
 // ---------------------------------------------------------------------------
@@ -83,6 +113,7 @@ fn record_scaling_result(
 fn run_kv_get_scaling(
     thread_sweep: &[usize],
     mode: DurabilityConfig,
+    warmup: &Warmup,
     recorder: &mut ResultRecorder,
 ) {
     eprintln!(
@@ -106,7 +137,7 @@ fn run_kv_get_scaling(
 
     for &n in thread_sweep {
         let result =
-            run_scaling_experiment(&bench_db.db, n, WARMUP_SECS, MEASURE_SECS, move |tid, strata, stop| {
+            run_scaling_experiment(&bench_db.db, n, warmup, MEASURE_SECS, move |tid, strata, stop| {
                 let mut sampler = ReservoirSampler::with_seed(tid as u64);
                 let mut ops = 0u64;
                 let mut rng = tid as u64 ^ 0x12345678;
@@ -139,6 +170,7 @@ fn run_kv_get_scaling(
 fn run_kv_put_independent_scaling(
     thread_sweep: &[usize],
     mode: DurabilityConfig,
+    warmup: &Warmup,
     recorder: &mut ResultRecorder,
 ) {
     eprintln!(
@@ -152,7 +184,7 @@ fn run_kv_put_independent_scaling(
         // Fresh database per thread count to avoid accumulation effects
         let bench_db = create_db(mode);
         let result =
-            run_scaling_experiment(&bench_db.db, n, WARMUP_SECS, MEASURE_SECS, move |tid, strata, stop| {
+            run_scaling_experiment(&bench_db.db, n, warmup, MEASURE_SECS, move |tid, strata, stop| {
                 let mut sampler = ReservoirSampler::with_seed(tid as u64);
                 let mut ops = 0u64;
                 let mut seq = 0u64;
@@ -185,6 +217,7 @@ fn run_kv_put_independent_scaling(
 fn run_kv_put_hot_scaling(
     thread_sweep: &[usize],
     mode: DurabilityConfig,
+    warmup: &Warmup,
     recorder: &mut ResultRecorder,
 ) {
     eprintln!(
@@ -204,7 +237,7 @@ fn run_kv_put_hot_scaling(
             .expect("pre-populate hot key failed");
 
         let result =
-            run_scaling_experiment(&bench_db.db, n, WARMUP_SECS, MEASURE_SECS, move |tid, strata, stop| {
+            run_scaling_experiment(&bench_db.db, n, warmup, MEASURE_SECS, move |tid, strata, stop| {
                 let mut sampler = ReservoirSampler::with_seed(tid as u64);
                 let mut ops = 0u64;
                 let mut aborts = 0u64;
@@ -242,6 +275,7 @@ fn run_kv_put_hot_scaling(
 fn run_mixed_90_10_scaling(
     thread_sweep: &[usize],
     mode: DurabilityConfig,
+    warmup: &Warmup,
     recorder: &mut ResultRecorder,
 ) {
     eprintln!(
@@ -265,7 +299,7 @@ fn run_mixed_90_10_scaling(
 
     for &n in thread_sweep {
         let result =
-            run_scaling_experiment(&bench_db.db, n, WARMUP_SECS, MEASURE_SECS, move |tid, strata, stop| {
+            run_scaling_experiment(&bench_db.db, n, warmup, MEASURE_SECS, move |tid, strata, stop| {
                 let mut sampler = ReservoirSampler::with_seed(tid as u64);
                 let mut ops = 0u64;
                 let mut rng = tid as u64 ^ 0xfeedface;
@@ -303,11 +337,186 @@ fn run_mixed_90_10_scaling(
 }
 
 // ---------------------------------------------------------------------------
-// Durability modes to test
+// Workload: Transactional overlap (N sessions, 0-100% shared keys)
 // ---------------------------------------------------------------------------
 
-fn durability_modes() -> Vec<DurabilityConfig> {
-    DurabilityConfig::ALL.to_vec()
+/// Keys for one transaction of session `tid`: the first `shared` come from a
+/// pool every session draws from, the rest from the session's own pool.
+fn overlap_keys(tid: usize, shared: usize, rng: &mut u64) -> Vec<String> {
+    (0..KEYS_PER_TXN)
+        .map(|j| {
+            let slot = fast_rand(rng) % KEYS_PER_TXN as u64;
+            if j < shared {
+                format!("shared:{}", slot)
+            } else {
+                format!("own{}:{}", tid, slot)
+            }
+        })
+        .collect()
+}
+
+fn record_conflict_result(
+    recorder: &mut ResultRecorder,
+    mode: &DurabilityConfig,
+    overlap_pct: usize,
+    result: &ScalingResult,
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("workload".into(), serde_json::json!("txn_overlap"));
+    params.insert("overlap_pct".into(), serde_json::json!(overlap_pct));
+    params.insert("keys_per_txn".into(), serde_json::json!(KEYS_PER_TXN));
+    params.extend(result.warmup.parameters().into_iter().map(|(k, v)| (k.to_string(), v)));
+    params.insert("sessions".into(), serde_json::json!(result.threads));
+    params.insert(
+        "commit_success_pct".into(),
+        serde_json::json!(100.0 - result.abort_rate_pct),
+    );
+    params.insert(
+        "attempts_per_commit".into(),
+        serde_json::json!(result.retries_per_commit),
+    );
+    params.insert(
+        "goodput_keys_per_sec".into(),
+        serde_json::json!(result.ops_per_sec * KEYS_PER_TXN as f64),
+    );
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!(
+            "concurrency/txn_overlap/{}pct/{}/{}t",
+            overlap_pct,
+            mode.label(),
+            result.threads
+        ),
+        category: "concurrency".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(result.ops_per_sec),
+            p50_ns: Some(result.p50.as_nanos() as u64),
+            p95_ns: Some(result.p95.as_nanos() as u64),
+            p99_ns: Some(result.p99.as_nanos() as u64),
+            samples: Some(result.total_ops),
+            threads: Some(result.threads),
+            // Reported even at 0% so the overlap curve starts at its origin
+            abort_rate_pct: Some(result.abort_rate_pct),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+/// `sessions` sessions each run read-modify-write transactions over
+/// `KEYS_PER_TXN` keys, with `overlap_pct` of those keys drawn from a shared
+/// pool. A failed commit is retried on the same keys until it succeeds, so
+/// `ops/sec` is goodput (committed transactions) and latency includes the
+/// retries.
+fn run_txn_overlap(
+    overlaps: &[usize],
+    sessions: usize,
+    mode: DurabilityConfig,
+    warmup: &Warmup,
+    recorder: &mut ResultRecorder,
+) {
+    eprintln!(
+        "\n=== TXN OVERLAP ({} sessions, {} keys/txn, read-modify-write) | durability: {} ===",
+        sessions,
+        KEYS_PER_TXN,
+        mode.label()
+    );
+
+    eprintln!(
+        "{:<9}| {:<12}| {:<9}| {:<9}| {:<9}| {:<10}| {:<11}",
+        "overlap", "commits/s", "p50", "p95", "p99", "success%", "attempts/op"
+    );
+    eprintln!("{}", "-".repeat(80));
+
+    for &overlap_pct in overlaps {
+        let shared = KEYS_PER_TXN * overlap_pct / 100;
+        let bench_db = create_db(mode);
+
+        let result = run_scaling_experiment(
+            &bench_db.db,
+            sessions,
+            warmup,
+            MEASURE_SECS,
+            move |tid, strata, stop| {
+                let mut sampler = ReservoirSampler::with_seed(tid as u64);
+                let mut ops = 0u64;
+                let mut aborts = 0u64;
+                let mut rng = tid as u64 ^ 0xc0ff_ee00;
+                let mut session = strata.session();
+
+                while !stop.load(Ordering::Relaxed) {
+                    let keys = overlap_keys(tid, shared, &mut rng);
+                    let start = Instant::now();
+                    let mut committed = false;
+                    while !committed && !stop.load(Ordering::Relaxed) {
+                        committed = attempt_rmw_txn(&mut session, &keys);
+                        if !committed {
+                            aborts += 1;
+                        }
+                    }
+                    if committed {
+                        sampler.record(start.elapsed());
+                        ops += 1;
+                    }
+                }
+
+                ThreadResult {
+                    ops,
+                    aborts,
+                    latencies: sampler.into_samples(),
+                }
+            },
+        );
+
+        eprintln!(
+            "{:<9}| {:<12}| {:<9}| {:<9}| {:<9}| {:<10.2}| {:<11.2}",
+            format!("{}%", overlap_pct),
+            fmt_ops(result.ops_per_sec),
+            fmt_duration(result.p50),
+            fmt_duration(result.p95),
+            fmt_duration(result.p99),
+            100.0 - result.abort_rate_pct,
+            result.retries_per_commit,
+        );
+        record_conflict_result(recorder, &mode, overlap_pct, &result);
+    }
+}
+
+/// One read-modify-write transaction: read each key, write it back
+/// incremented. Returns whether the commit succeeded.
+fn attempt_rmw_txn(session: &mut Session, keys: &[String]) -> bool {
+    if session
+        .execute(Command::TxnBegin { branch: None, options: None })
+        .is_err()
+    {
+        return false;
+    }
+    for key in keys {
+        let current = match session.execute(Command::KvGet {
+            branch: None,
+            space: None,
+            key: key.clone(),
+            as_of: None,
+        }) {
+            Ok(Output::Maybe(Some(Value::Int(n)))) => n,
+            _ => 0,
+        };
+        let put = session.execute(Command::KvPut {
+            branch: None,
+            space: None,
+            key: key.clone(),
+            value: Value::Int(current + 1),
+        });
+        if put.is_err() {
+            let _ = session.execute(Command::TxnRollback);
+            return false;
+        }
+    }
+    session.execute(Command::TxnCommit).is_ok()
 }
 
 // ---------------------------------------------------------------------------
@@ -315,38 +524,59 @@ fn durability_modes() -> Vec<DurabilityConfig> {
 // ---------------------------------------------------------------------------
 
 fn main() {
-    // Parse --threads argument if provided
-    let args: Vec<String> = std::env::args().collect();
-    let thread_sweep = if let Some(pos) = args.iter().position(|a| a == "--threads") {
-        if let Some(val) = args.get(pos + 1) {
-            parse_thread_counts(val)
-        } else {
-            harness::scaling::thread_counts()
+    let mut args = harness::cli::Args::parse();
+    let mut overlaps: Vec<usize> = OVERLAP_PCTS.to_vec();
+    let mut txn_sessions = CONFLICT_SESSIONS;
+    let mut warmup = Warmup::default();
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--overlap" => overlaps = args.list_checked("percentages from 0 to 100", |&pct| pct <= 100),
+            "--txn-sessions" => txn_sessions = args.parse_value::<usize>().max(2),
+            "--warmup" => {
+                let v = args.value();
+                warmup = Warmup::parse(&v).unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--warmup expects auto or seconds; got {}", v))
+                });
+            }
+            _ => args.unknown_flag(),
         }
-    } else {
-        harness::scaling::thread_counts()
-    };
+    }
+    let thread_sweep = args.threads_or(harness::scaling::thread_counts());
+    let modes = args.durability_modes(&DurabilityConfig::ALL);
 
     // Hardware info
     let cores = physical_cores();
     eprintln!("=== Scaling & Concurrency Benchmark Suite ===");
     eprintln!("Physical cores (available_parallelism): {}", cores);
     eprintln!("Thread sweep: {:?}", thread_sweep);
+    eprintln!("Txn overlap: {:?}% with {} sessions", overlaps, txn_sessions);
     eprintln!(
-        "Measurement: {}s warmup + {}s measure per run",
-        WARMUP_SECS, MEASURE_SECS
+        "Measurement: {} + {}s measure per run",
+        warmup.describe(),
+        MEASURE_SECS
     );
     eprintln!();
 
     let mut recorder = ResultRecorder::new("concurrency");
 
-    for mode in durability_modes() {
-        run_kv_get_scaling(&thread_sweep, mode, &mut recorder);
-        run_kv_put_independent_scaling(&thread_sweep, mode, &mut recorder);
-        run_kv_put_hot_scaling(&thread_sweep, mode, &mut recorder);
-        run_mixed_90_10_scaling(&thread_sweep, mode, &mut recorder);
+    for mode in modes {
+        guard_workload(&mut recorder, "kv_get", mode, |recorder| {
+            run_kv_get_scaling(&thread_sweep, mode, &warmup, recorder)
+        });
+        guard_workload(&mut recorder, "kv_put_independent", mode, |recorder| {
+            run_kv_put_independent_scaling(&thread_sweep, mode, &warmup, recorder)
+        });
+        guard_workload(&mut recorder, "kv_put_hot", mode, |recorder| {
+            run_kv_put_hot_scaling(&thread_sweep, mode, &warmup, recorder)
+        });
+        guard_workload(&mut recorder, "mixed_90_10", mode, |recorder| {
+            run_mixed_90_10_scaling(&thread_sweep, mode, &warmup, recorder)
+        });
+        guard_workload(&mut recorder, "txn_overlap", mode, |recorder| {
+            run_txn_overlap(&overlaps, txn_sessions, mode, &warmup, recorder)
+        });
     }
 
     eprintln!("\n=== Benchmark complete ===");
-    let _ = recorder.save();
+    recorder.save_or_exit();
 }
@@ -0,0 +1,566 @@
+//! Scheduler-Fairness Benchmark for StrataDB
+//!
+//! Measures head-of-line blocking inside the engine: reader threads issue
+//! tiny `kv_get`s while one other thread periodically runs a huge operation,
+//! and the small-op latency is compared against a run with no interference.
+//!
+//! Interferers:
+//!
+//! - `none`: readers alone (baseline)
+//! - `json_large`: `json_set` of a multi-megabyte document (default 5 MB)
+//! - `vector_rebuild`: drop a vector collection, recreate it and re-upsert
+//!   every vector
+//!
+//! The heavy thread raises a flag while its operation is in flight, so
+//! reader latencies are also reported for just the gets that started during
+//! a heavy operation. That overlap p99 is the direct measure of blocking.
+//!
+//! Run:    `cargo bench --bench fairness`
+//! Quick:  `cargo bench --bench fairness -- --duration 2 --large-bytes 1048576 -q`
+//! Custom: `cargo bench --bench fairness -- --readers 8 --interferers json_large`
+//! CSV:    `cargo bench --bench fairness -- --csv`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::scaling::{physical_cores, ReservoirSampler};
+use harness::{create_db, print_hardware_info, DurabilityConfig};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_duration, fmt_ops};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{DistanceMetric, Strata, Value};
+
+// ---------------------------------------------------------------------------
+// Defaults
+// ---------------------------------------------------------------------------
+
+const DEFAULT_READERS: usize = 4;
+const DEFAULT_DURATION_SECS: u64 = 5;
+const DEFAULT_LARGE_BYTES: usize = 5 * 1024 * 1024;
+const DEFAULT_INTERVAL_MS: u64 = 100;
+const DEFAULT_REBUILD_VECTORS: usize = 5_000;
+const DEFAULT_DIM: usize = 128;
+
+/// Keys the readers draw from.
+const PREPOPULATE_KEYS: usize = 10_000;
+
+/// Bytes per string field in the large JSON document.
+const FIELD_BYTES: usize = 1_024;
+
+const REBUILD_COLLECTION: &str = "fairness_rebuild";
+
+// ---------------------------------------------------------------------------
+// Interferers
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Interferer {
+    None,
+    JsonLarge,
+    VectorRebuild,
+}
+
+impl Interferer {
+    const ALL: [Interferer; 3] = [Interferer::None, Interferer::JsonLarge, Interferer::VectorRebuild];
+
+    fn label(self) -> &'static str {
+        match self {
+            Interferer::None => "none",
+            Interferer::JsonLarge => "json_large",
+            Interferer::VectorRebuild => "vector_rebuild",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|i| i.label() == s)
+    }
+}
+
+/// A JSON object of roughly `bytes` bytes, built from 1 KB string fields.
+fn large_document(bytes: usize) -> Value {
+    let fields = bytes.div_ceil(FIELD_BYTES).max(1);
+    let filler = "x".repeat(FIELD_BYTES);
+    Value::Object(
+        (0..fields)
+            .map(|i| (format!("field_{:06}", i), Value::String(filler.clone())))
+            .collect(),
+    )
+}
+
+fn rebuild_vector(i: usize, dim: usize) -> Vec<f32> {
+    let mut state = (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    (0..dim)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % 2_000) as f32 / 1_000.0 - 1.0
+        })
+        .collect()
+}
+
+/// One heavy operation. Inputs are prepared by the caller so only the
+/// engine work happens while the `busy` flag is raised.
+fn heavy_op(db: &Strata, interferer: Interferer, n: u64, doc: &Value, vectors: &[Vec<f32>], dim: usize) {
+    match interferer {
+        Interferer::None => {}
+        Interferer::JsonLarge => {
+            db.json_set(&format!("large_doc_{}", n % 4), "$", doc.clone())
+                .unwrap();
+        }
+        Interferer::VectorRebuild => {
+            let _ = db.vector_delete_collection(REBUILD_COLLECTION);
+            db.vector_create_collection(REBUILD_COLLECTION, dim as u64, DistanceMetric::Cosine)
+                .unwrap();
+            for (i, v) in vectors.iter().enumerate() {
+                db.vector_upsert(REBUILD_COLLECTION, &format!("v{}", i), v.clone(), None)
+                    .unwrap();
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Run
+// ---------------------------------------------------------------------------
+
+struct SmallStats {
+    ops: u64,
+    ops_per_sec: f64,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    /// Exact maximum (not sampled).
+    max: Duration,
+}
+
+fn small_stats(mut samples: Vec<Duration>, ops: u64, max: Duration, wall: Duration) -> SmallStats {
+    samples.sort_unstable();
+    let len = samples.len().max(1);
+    let at = |pct: usize| samples.get((len * pct / 100).min(len - 1)).copied().unwrap_or_default();
+    SmallStats {
+        ops,
+        ops_per_sec: ops as f64 / wall.as_secs_f64(),
+        p50: at(50),
+        p95: at(95),
+        p99: at(99),
+        max,
+    }
+}
+
+struct FairnessResult {
+    /// Every small op.
+    all: SmallStats,
+    /// Small ops that started while a heavy op was in flight.
+    overlap: SmallStats,
+    heavy_ops: u64,
+    heavy_avg: Duration,
+    /// Fraction of the run a heavy op was in flight.
+    heavy_busy_pct: f64,
+}
+
+struct ReaderResult {
+    all: ReservoirSampler,
+    all_ops: u64,
+    all_max: Duration,
+    overlap: ReservoirSampler,
+    overlap_ops: u64,
+    overlap_max: Duration,
+}
+
+fn run_fairness(db: &Strata, config: &Config, interferer: Interferer) -> FairnessResult {
+    let stop = AtomicBool::new(false);
+    let busy = AtomicBool::new(false);
+    let barrier = Barrier::new(config.readers + 2);
+
+    let doc = if interferer == Interferer::JsonLarge {
+        large_document(config.large_bytes)
+    } else {
+        Value::Null
+    };
+    let vectors: Vec<Vec<f32>> = if interferer == Interferer::VectorRebuild {
+        (0..config.rebuild_vectors).map(|i| rebuild_vector(i, config.dim)).collect()
+    } else {
+        Vec::new()
+    };
+
+    std::thread::scope(|scope| {
+        let heavy = {
+            let handle = db.new_handle().expect("failed to create heavy handle");
+            let (stop, busy, barrier, doc, vectors) = (&stop, &busy, &barrier, &doc, &vectors);
+            scope.spawn(move || {
+                let mut durations = Vec::new();
+                barrier.wait();
+                if interferer == Interferer::None {
+                    return durations;
+                }
+                let mut n = 0u64;
+                while !stop.load(Ordering::Relaxed) {
+                    busy.store(true, Ordering::SeqCst);
+                    let start = Instant::now();
+                    heavy_op(&handle, interferer, n, doc, vectors, config.dim);
+                    durations.push(start.elapsed());
+                    busy.store(false, Ordering::SeqCst);
+                    n += 1;
+                    std::thread::sleep(config.interval);
+                }
+                durations
+            })
+        };
+
+        let readers: Vec<_> = (0..config.readers)
+            .map(|tid| {
+                let handle = db.new_handle().expect("failed to create reader handle");
+                let (stop, busy, barrier) = (&stop, &busy, &barrier);
+                scope.spawn(move || {
+                    let mut r = ReaderResult {
+                        all: ReservoirSampler::with_seed(tid as u64),
+                        all_ops: 0,
+                        all_max: Duration::ZERO,
+                        overlap: ReservoirSampler::with_seed(tid as u64 ^ 0xF00D),
+                        overlap_ops: 0,
+                        overlap_max: Duration::ZERO,
+                    };
+                    let mut rng = tid as u64 ^ 0x12345678;
+                    barrier.wait();
+                    while !stop.load(Ordering::Relaxed) {
+                        rng = rng
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        let key = format!("key{:06}", (rng >> 33) % PREPOPULATE_KEYS as u64);
+
+                        let during_heavy = busy.load(Ordering::Relaxed);
+                        let start = Instant::now();
+                        let _ = handle.kv_get(&key);
+                        let elapsed = start.elapsed();
+
+                        r.all.record(elapsed);
+                        r.all_ops += 1;
+                        r.all_max = r.all_max.max(elapsed);
+                        if during_heavy {
+                            r.overlap.record(elapsed);
+                            r.overlap_ops += 1;
+                            r.overlap_max = r.overlap_max.max(elapsed);
+                        }
+                    }
+                    r
+                })
+            })
+            .collect();
+
+        barrier.wait();
+        let start = Instant::now();
+        std::thread::sleep(config.duration);
+        stop.store(true, Ordering::Relaxed);
+
+        let reader_results: Vec<ReaderResult> = readers
+            .into_iter()
+            .map(|r| r.join().expect("reader thread panicked"))
+            .collect();
+        let wall = start.elapsed();
+        let heavy_durations = heavy.join().expect("heavy thread panicked");
+
+        let mut all = Vec::new();
+        let mut overlap = Vec::new();
+        let (mut all_ops, mut overlap_ops) = (0, 0);
+        let (mut all_max, mut overlap_max) = (Duration::ZERO, Duration::ZERO);
+        for r in reader_results {
+            all.extend(r.all.into_samples());
+            overlap.extend(r.overlap.into_samples());
+            all_ops += r.all_ops;
+            overlap_ops += r.overlap_ops;
+            all_max = all_max.max(r.all_max);
+            overlap_max = overlap_max.max(r.overlap_max);
+        }
+
+        let heavy_total: Duration = heavy_durations.iter().sum();
+        let heavy_ops = heavy_durations.len() as u64;
+        FairnessResult {
+            all: small_stats(all, all_ops, all_max, wall),
+            overlap: small_stats(overlap, overlap_ops, overlap_max, wall),
+            heavy_ops,
+            heavy_avg: heavy_total / heavy_ops.max(1) as u32,
+            heavy_busy_pct: 100.0 * heavy_total.as_secs_f64() / wall.as_secs_f64(),
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+fn print_header() {
+    eprintln!(
+        "  {:<15} {:>12} {:>10} {:>10} {:>10} {:>10}   {:>12} {:>10} {:>8} {:>12}",
+        "interferer", "gets/s", "p50", "p99", "max", "p99 ×base", "overlap p99", "overlap n", "heavy n", "heavy avg"
+    );
+    eprintln!("  {}", "-".repeat(118));
+}
+
+fn print_row(interferer: Interferer, r: &FairnessResult, inflation: f64) {
+    eprintln!(
+        "  {:<15} {:>12} {:>10} {:>10} {:>10} {:>9.2}x   {:>12} {:>10} {:>8} {:>12}",
+        interferer.label(),
+        fmt_ops(r.all.ops_per_sec),
+        fmt_duration(r.all.p50),
+        fmt_duration(r.all.p99),
+        fmt_duration(r.all.max),
+        inflation,
+        fmt_duration(r.overlap.p99),
+        r.overlap.ops,
+        r.heavy_ops,
+        fmt_duration(r.heavy_avg),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"interferer\",\"durability\",\"readers\",\"gets_per_sec\",\"p50_us\",\"p99_us\",\"max_us\",\"p99_inflation\",\"overlap_gets\",\"overlap_p99_us\",\"heavy_ops\",\"heavy_avg_ms\"");
+}
+
+fn print_csv_row(
+    interferer: Interferer,
+    mode: DurabilityConfig,
+    readers: usize,
+    r: &FairnessResult,
+    inflation: f64,
+) {
+    let us = |d: Duration| d.as_nanos() as f64 / 1_000.0;
+    println!(
+        "\"{}\",\"{}\",{},{:.2},{:.3},{:.3},{:.3},{:.3},{},{:.3},{},{:.3}",
+        interferer.label(),
+        mode.label(),
+        readers,
+        r.all.ops_per_sec,
+        us(r.all.p50),
+        us(r.all.p99),
+        us(r.all.max),
+        inflation,
+        r.overlap.ops,
+        us(r.overlap.p99),
+        r.heavy_ops,
+        r.heavy_avg.as_nanos() as f64 / 1_000_000.0,
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+fn result_name(interferer: Interferer, mode: DurabilityConfig, config: &Config) -> String {
+    format!(
+        "concurrency/fairness/{}/{}/{}t",
+        interferer.label(),
+        mode.label(),
+        config.readers
+    )
+}
+
+fn record_result(
+    recorder: &mut ResultRecorder,
+    interferer: Interferer,
+    mode: DurabilityConfig,
+    config: &Config,
+    r: &FairnessResult,
+    inflation: f64,
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("workload".into(), serde_json::json!("fairness"));
+    params.insert("interferer".into(), serde_json::json!(interferer.label()));
+    params.insert("p99_inflation".into(), serde_json::json!(inflation));
+    params.insert("overlap_gets".into(), serde_json::json!(r.overlap.ops));
+    params.insert("overlap_p50_ns".into(), serde_json::json!(r.overlap.p50.as_nanos() as u64));
+    params.insert("overlap_p99_ns".into(), serde_json::json!(r.overlap.p99.as_nanos() as u64));
+    params.insert("overlap_max_ns".into(), serde_json::json!(r.overlap.max.as_nanos() as u64));
+    params.insert("heavy_ops".into(), serde_json::json!(r.heavy_ops));
+    params.insert("heavy_avg_ns".into(), serde_json::json!(r.heavy_avg.as_nanos() as u64));
+    params.insert("heavy_busy_pct".into(), serde_json::json!(r.heavy_busy_pct));
+    params.insert("interval_ms".into(), serde_json::json!(config.interval.as_millis() as u64));
+    match interferer {
+        Interferer::None => {}
+        Interferer::JsonLarge => {
+            params.insert("large_bytes".into(), serde_json::json!(config.large_bytes));
+        }
+        Interferer::VectorRebuild => {
+            params.insert("rebuild_vectors".into(), serde_json::json!(config.rebuild_vectors));
+            params.insert("dim".into(), serde_json::json!(config.dim));
+        }
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(interferer, mode, config),
+        category: "concurrency".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.all.ops_per_sec),
+            p50_ns: Some(r.all.p50.as_nanos() as u64),
+            p95_ns: Some(r.all.p95.as_nanos() as u64),
+            p99_ns: Some(r.all.p99.as_nanos() as u64),
+            max_ns: Some(r.all.max.as_nanos() as u64),
+            samples: Some(r.all.ops),
+            threads: Some(config.readers),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    readers: usize,
+    duration: Duration,
+    large_bytes: usize,
+    interval: Duration,
+    rebuild_vectors: usize,
+    dim: usize,
+    interferers: Vec<Interferer>,
+    durability: Vec<DurabilityConfig>,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        readers: DEFAULT_READERS,
+        duration: Duration::from_secs(DEFAULT_DURATION_SECS),
+        large_bytes: DEFAULT_LARGE_BYTES,
+        interval: Duration::from_millis(DEFAULT_INTERVAL_MS),
+        rebuild_vectors: DEFAULT_REBUILD_VECTORS,
+        dim: DEFAULT_DIM,
+        interferers: Interferer::ALL.to_vec(),
+        durability: DurabilityConfig::ALL.to_vec(),
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--readers" => config.readers = args.parse_value::<usize>().max(1),
+            "--large-bytes" => config.large_bytes = args.parse_value(),
+            "--interval-ms" => config.interval = Duration::from_millis(args.parse_value()),
+            "--rebuild-vectors" => config.rebuild_vectors = args.parse_value::<usize>().max(1),
+            "--dim" => config.dim = args.parse_value::<usize>().max(1),
+            "--interferers" => {
+                let v = args.value();
+                config.interferers = v
+                    .split(',')
+                    .map(|s| Interferer::parse(s.trim()))
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|i| !i.is_empty())
+                    .unwrap_or_else(|| {
+                        let names: Vec<&str> = Interferer::ALL.iter().map(|i| i.label()).collect();
+                        harness::cli::usage_error(&format!("--interferers expects a list of {}; got {}", names.join(", "), v))
+                    });
+            }
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.duration = args.duration_or(Duration::from_secs(DEFAULT_DURATION_SECS));
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    // The baseline is what every other row is compared against
+    if !config.interferers.contains(&Interferer::None) {
+        config.interferers.insert(0, Interferer::None);
+    }
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let verbose = !config.csv && !config.quiet;
+    if verbose {
+        eprintln!("=== StrataDB Scheduler-Fairness Benchmark ===");
+        eprintln!(
+            "{} kv_get readers ({} cores) vs 1 heavy thread | {:.1}s per run | heavy ops every {}ms",
+            config.readers,
+            physical_cores(),
+            config.duration.as_secs_f64(),
+            config.interval.as_millis(),
+        );
+        eprintln!(
+            "json_large: {} bytes | vector_rebuild: {} x {}d",
+            config.large_bytes, config.rebuild_vectors, config.dim,
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("concurrency");
+
+    for &mode in &config.durability {
+        let bench_db = create_db(mode);
+        for i in 0..PREPOPULATE_KEYS {
+            bench_db
+                .db
+                .kv_put(&format!("key{:06}", i), Value::Int(i as i64))
+                .expect("pre-populate failed");
+        }
+
+        if verbose {
+            eprintln!("--- durability: {} ---", mode.label());
+            print_header();
+        }
+
+        let mut baseline_p99 = None;
+        for &interferer in &config.interferers {
+            let mut guard_params = HashMap::new();
+            guard_params.insert("durability".into(), serde_json::json!(mode.label()));
+            guard_params.insert("workload".into(), serde_json::json!("fairness"));
+            guard_params.insert("interferer".into(), serde_json::json!(interferer.label()));
+            recorder.guard(&result_name(interferer, mode, &config), guard_params, |recorder| {
+                let r = run_fairness(&bench_db.db, &config, interferer);
+                let base = *baseline_p99.get_or_insert(r.all.p99);
+                let inflation = r.all.p99.as_secs_f64() / base.as_secs_f64().max(1e-9);
+
+                if config.csv {
+                    print_csv_row(interferer, mode, config.readers, &r, inflation);
+                } else if config.quiet {
+                    eprintln!(
+                        "fairness/{}/{}: p99={} ({:.2}x baseline), overlap p99={}",
+                        interferer.label(),
+                        mode.label(),
+                        fmt_duration(r.all.p99),
+                        inflation,
+                        fmt_duration(r.overlap.p99),
+                    );
+                } else {
+                    print_row(interferer, &r, inflation);
+                }
+
+                record_result(recorder, interferer, mode, &config, &r, inflation);
+            });
+        }
+
+        if verbose {
+            eprintln!();
+        }
+    }
+
+    if verbose {
+        eprintln!("=== Fairness benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
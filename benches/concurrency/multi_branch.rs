@@ -0,0 +1,349 @@
+//! Multi-Branch Concurrency Benchmark
+//!
+//! N threads each work on their own branch at the same time, the pattern of
+//! multi-agent experiments where every agent forks its own branch. Reports
+//! aggregate and per-branch throughput as the thread count grows, to show
+//! whether adding branches (and threads) slows down the ones already there.
+//!
+//! Two comparisons separate the branch effect from plain concurrency:
+//!
+//! - `shared`: the same threads and workload on the default branch, each
+//!   thread on its own keys, so only the branch layout differs;
+//! - `--idle-branches`: extra branches that exist but see no traffic, so the
+//!   branch count grows without more load.
+//!
+//! Each operation is a `kv_get` or `kv_put` (`--write-pct`, default 50%) on
+//! one of the thread's own keys, so threads never contend on a key.
+//!
+//! Run:    `cargo bench --bench multi_branch`
+//! Quick:  `cargo bench --bench multi_branch -- --threads 1,4 --durability cache`
+//! Custom: `cargo bench --bench multi_branch -- --idle-branches 0,10000 --write-pct 20`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::scaling::{
+    physical_cores, run_scaling_experiment, thread_counts, ReservoirSampler,
+    ScalingResult, ThreadResult,
+};
+use harness::warmup::Warmup;
+use harness::{create_db, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+use strata_benchmarks::fmt::{fmt_duration, fmt_num, fmt_ops};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+const MEASURE_SECS: u64 = 5;
+
+/// Keys each thread reads and overwrites, pre-populated before the run.
+const KEYS_PER_THREAD: u64 = 1_000;
+const DEFAULT_WRITE_PCT: u64 = 50;
+const DEFAULT_IDLE_BRANCHES: &[usize] = &[0, 1_000];
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Simple LCG for key selection (fast, deterministic, no rand dependency).
+#[inline]
+fn fast_rand(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+#[derive(Clone, Copy)]
+enum Layout {
+    /// Each thread on its own branch.
+    OwnBranch,
+    /// All threads on the default branch, each on its own keys.
+    Shared,
+}
+
+impl Layout {
+    fn label(&self) -> &'static str {
+        match self {
+            Layout::OwnBranch => "own-branch",
+            Layout::Shared => "shared",
+        }
+    }
+}
+
+fn agent_branch(tid: usize) -> String {
+    format!("agent_{}", tid)
+}
+
+/// Key `i` of thread `tid`. With own branches the thread prefix is not
+/// needed, but keeping it means both layouts write identical keys.
+fn thread_key(tid: usize, i: u64) -> String {
+    format!("t{}:k{:06}", tid, i)
+}
+
+fn populate(db: &mut BenchDb, layout: Layout, threads: usize, idle: usize) {
+    for i in 0..idle {
+        db.db.create_branch(&format!("idle_{}", i)).unwrap();
+    }
+    for tid in 0..threads {
+        if let Layout::OwnBranch = layout {
+            db.db.create_branch(&agent_branch(tid)).unwrap();
+            db.db.set_branch(&agent_branch(tid)).unwrap();
+        }
+        for i in 0..KEYS_PER_THREAD {
+            db.db.kv_put(&thread_key(tid, i), Value::Int(i as i64)).unwrap();
+        }
+    }
+    db.db.set_branch("default").unwrap();
+}
+
+// ---------------------------------------------------------------------------
+// Run
+// ---------------------------------------------------------------------------
+
+fn run_multi_branch(
+    mode: DurabilityConfig,
+    layout: Layout,
+    threads: usize,
+    idle: usize,
+    write_pct: u64,
+    warmup: &Warmup,
+) -> ScalingResult {
+    let mut bench_db = create_db(mode);
+    populate(&mut bench_db, layout, threads, idle);
+
+    run_scaling_experiment(&bench_db.db, threads, warmup, MEASURE_SECS, move |tid, strata, stop| {
+        let mut strata = strata;
+        if let Layout::OwnBranch = layout {
+            strata.set_branch(&agent_branch(tid)).expect("set_branch failed");
+        }
+        let mut sampler = ReservoirSampler::with_seed(tid as u64);
+        let mut ops = 0u64;
+        let mut rng = tid as u64 ^ 0xa6e7_b4a1;
+        let mut seq = 0i64;
+
+        while !stop.load(Ordering::Relaxed) {
+            let key = thread_key(tid, fast_rand(&mut rng) % KEYS_PER_THREAD);
+            let write = fast_rand(&mut rng) % 100 < write_pct;
+
+            let start = Instant::now();
+            if write {
+                seq += 1;
+                let _ = strata.kv_put(&key, Value::Int(seq));
+            } else {
+                let _ = strata.kv_get(&key);
+            }
+            sampler.record(start.elapsed());
+            ops += 1;
+        }
+
+        ThreadResult {
+            ops,
+            aborts: 0,
+            latencies: sampler.into_samples(),
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Output and recording
+// ---------------------------------------------------------------------------
+
+fn print_header() {
+    eprintln!(
+        "{:<8}| {:<10}| {:<12}| {:<12}| {:<11}| {:<9}| {:<9}",
+        "threads", "branches", "ops/s", "ops/s/thread", "efficiency", "p50", "p99"
+    );
+    eprintln!("{}", "-".repeat(80));
+}
+
+fn print_row(result: &ScalingResult, branches: usize, per_thread: f64, efficiency: f64) {
+    eprintln!(
+        "{:<8}| {:<10}| {:<12}| {:<12}| {:<11}| {:<9}| {:<9}",
+        result.threads,
+        fmt_num(branches as u64),
+        fmt_ops(result.ops_per_sec),
+        fmt_ops(per_thread),
+        format!("{:.0}%", efficiency),
+        fmt_duration(result.p50),
+        fmt_duration(result.p99),
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn result_name(layout: Layout, idle: usize, mode: &DurabilityConfig, threads: usize) -> String {
+    format!(
+        "concurrency/multi_branch/{}/{}idle/{}/{}t",
+        layout.label(),
+        idle,
+        mode.label(),
+        threads
+    )
+}
+
+fn record_result(
+    recorder: &mut ResultRecorder,
+    mode: &DurabilityConfig,
+    layout: Layout,
+    idle: usize,
+    write_pct: u64,
+    result: &ScalingResult,
+    per_thread: f64,
+    efficiency: f64,
+) {
+    let active = match layout {
+        Layout::OwnBranch => result.threads,
+        Layout::Shared => 1,
+    };
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("workload".into(), serde_json::json!("multi_branch"));
+    params.insert("layout".into(), serde_json::json!(layout.label()));
+    params.insert("active_branches".into(), serde_json::json!(active));
+    params.insert("idle_branches".into(), serde_json::json!(idle));
+    params.insert("write_pct".into(), serde_json::json!(write_pct));
+    params.extend(result.warmup.parameters().into_iter().map(|(k, v)| (k.to_string(), v)));
+    params.insert("ops_per_sec_per_thread".into(), serde_json::json!(per_thread));
+    params.insert("scaling_efficiency_pct".into(), serde_json::json!(efficiency));
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(layout, idle, mode, result.threads),
+        category: "concurrency".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(result.ops_per_sec),
+            p50_ns: Some(result.p50.as_nanos() as u64),
+            p95_ns: Some(result.p95.as_nanos() as u64),
+            p99_ns: Some(result.p99.as_nanos() as u64),
+            samples: Some(result.total_ops),
+            threads: Some(result.threads),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+/// Sweep thread counts for one layout and idle-branch count. Efficiency is
+/// per-thread throughput relative to the sweep's first (smallest) run.
+fn run_sweep(
+    recorder: &mut ResultRecorder,
+    mode: DurabilityConfig,
+    layout: Layout,
+    idle: usize,
+    config: &Config,
+) {
+    eprintln!(
+        "\n=== MULTI-BRANCH {} ({} idle branches, {}% writes) | durability: {} ===",
+        layout.label(),
+        fmt_num(idle as u64),
+        config.write_pct,
+        mode.label()
+    );
+    print_header();
+
+    let mut baseline: Option<f64> = None;
+    for &n in &config.threads {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(mode.label()));
+        guard_params.insert("workload".into(), serde_json::json!("multi_branch"));
+        guard_params.insert("layout".into(), serde_json::json!(layout.label()));
+        guard_params.insert("idle_branches".into(), serde_json::json!(idle));
+        recorder.guard(&result_name(layout, idle, &mode, n), guard_params, |recorder| {
+            let result = run_multi_branch(mode, layout, n, idle, config.write_pct, &config.warmup);
+            let per_thread = result.ops_per_sec / n as f64;
+            let base = *baseline.get_or_insert(per_thread);
+            let efficiency = 100.0 * per_thread / base.max(f64::MIN_POSITIVE);
+            let branches = idle
+                + 1
+                + match layout {
+                    Layout::OwnBranch => n,
+                    Layout::Shared => 0,
+                };
+
+            print_row(&result, branches, per_thread, efficiency);
+            record_result(recorder, &mode, layout, idle, config.write_pct, &result, per_thread, efficiency);
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    threads: Vec<usize>,
+    idle_branches: Vec<usize>,
+    write_pct: u64,
+    warmup: Warmup,
+    durability: Vec<DurabilityConfig>,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        threads: thread_counts(),
+        idle_branches: DEFAULT_IDLE_BRANCHES.to_vec(),
+        write_pct: DEFAULT_WRITE_PCT,
+        warmup: Warmup::default(),
+        durability: DurabilityConfig::ALL.to_vec(),
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--idle-branches" => config.idle_branches = args.list(),
+            "--write-pct" => {
+                config.write_pct = args.parse_checked("a percentage from 0 to 100", |&pct| pct <= 100);
+            }
+            "--warmup" => {
+                let v = args.value();
+                config.warmup = Warmup::parse(&v).unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--warmup expects auto or seconds; got {}", v))
+                });
+            }
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.threads = args.threads_or(thread_counts());
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+
+    eprintln!("=== Multi-Branch Concurrency Benchmark ===");
+    eprintln!("Physical cores (available_parallelism): {}", physical_cores());
+    eprintln!("Thread sweep: {:?}", config.threads);
+    eprintln!("Idle branches: {:?}", config.idle_branches);
+    eprintln!(
+        "Measurement: {} + {}s measure per run",
+        config.warmup.describe(),
+        MEASURE_SECS
+    );
+
+    let mut recorder = ResultRecorder::new("concurrency");
+
+    for &mode in &config.durability {
+        run_sweep(&mut recorder, mode, Layout::Shared, 0, &config);
+        for &idle in &config.idle_branches {
+            run_sweep(&mut recorder, mode, Layout::OwnBranch, idle, &config);
+        }
+    }
+
+    eprintln!("\n=== Benchmark complete ===");
+    recorder.save_or_exit();
+}
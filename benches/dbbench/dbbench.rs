@@ -0,0 +1,593 @@
+//! db_bench-style Benchmark for StrataDB
+//!
+//! Implements the core of RocksDB's `db_bench` (fillseq, fillrandom,
+//! overwrite, readrandom, readseq) with db_bench's default parameters:
+//! 16-byte keys, 100-byte half-compressible values, 1M entries. Output lines
+//! use db_bench's `micros/op ops/sec MB/s` format so Strata's numbers can be
+//! quoted next to published LSM results.
+//!
+//! With the `rocksdb-compare` feature and `--rocksdb`, the same benchmarks
+//! also run against RocksDB itself, in the same process and on the same disk.
+//!
+//! Run:     `cargo bench --bench dbbench`
+//! Select:  `cargo bench --bench dbbench -- --benchmarks fillrandom,readrandom`
+//! Size:    `cargo bench --bench dbbench -- --num 100000 --value_size 1024`
+//! RocksDB: `cargo bench --bench dbbench --features rocksdb-compare -- --rocksdb`
+//! Quick:   `cargo bench --bench dbbench -- --durability cache -q`
+//! CSV:     `cargo bench --bench dbbench -- --csv`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[cfg(feature = "rocksdb-compare")]
+mod rocks;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters (matching db_bench defaults)
+// ---------------------------------------------------------------------------
+
+const DEFAULT_NUM: usize = 1_000_000;
+const DEFAULT_VALUE_SIZE: usize = 100;
+const KEY_SIZE: usize = 16;
+const COMPRESSION_RATIO: f64 = 0.5;
+
+const ALL_BENCHMARKS: &[&str] = &["fillseq", "fillrandom", "overwrite", "readrandom", "readseq"];
+
+// ---------------------------------------------------------------------------
+// Key and value generation
+// ---------------------------------------------------------------------------
+
+/// db_bench key: the entry index as a zero-padded 16-digit decimal string.
+fn dbbench_key(i: u64) -> String {
+    format!("{:0width$}", i, width = KEY_SIZE)
+}
+
+/// Simple LCG (fast, deterministic, no rand dependency).
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.state >> 33
+    }
+
+    #[inline]
+    fn uniform(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+/// db_bench's RandomGenerator: a 1MB pool of data that compresses to
+/// roughly `COMPRESSION_RATIO`, sliced at advancing offsets per value.
+struct ValueGenerator {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl ValueGenerator {
+    fn new() -> Self {
+        let mut rng = Rng::new(301);
+        let mut data = Vec::with_capacity(1 << 20);
+        while data.len() < 1 << 20 {
+            // 100-byte fragment: random printable prefix, repeated suffix
+            let random_len = (100.0 * COMPRESSION_RATIO) as usize;
+            let fragment: Vec<u8> = (0..random_len)
+                .map(|_| b' ' + rng.uniform(95) as u8)
+                .collect();
+            for j in 0..100 {
+                data.push(fragment[j % random_len]);
+            }
+        }
+        Self { data, pos: 0 }
+    }
+
+    fn next(&mut self, len: usize) -> &[u8] {
+        if self.pos + len > self.data.len() {
+            self.pos = 0;
+        }
+        self.pos += len;
+        &self.data[self.pos - len..self.pos]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Engines
+// ---------------------------------------------------------------------------
+
+/// A key-value store db_bench can drive.
+trait KvEngine {
+    fn put(&mut self, key: &str, value: &[u8]);
+    /// Returns whether the key was found.
+    fn get(&mut self, key: &str) -> bool;
+    /// Read up to `limit` entries (key and value) in key order, recording
+    /// one latency per entry. Returns the number of entries read.
+    fn read_seq(&mut self, limit: usize, latencies: &mut Vec<Duration>) -> usize;
+}
+
+struct StrataEngine {
+    bench_db: BenchDb,
+}
+
+impl KvEngine for StrataEngine {
+    fn put(&mut self, key: &str, value: &[u8]) {
+        self.bench_db
+            .db
+            .kv_put(key, Value::Bytes(value.to_vec()))
+            .unwrap();
+    }
+
+    fn get(&mut self, key: &str) -> bool {
+        self.bench_db.db.kv_get(key).unwrap().is_some()
+    }
+
+    /// Strata has no ordered iterator, so this lists keys, sorts them, and
+    /// reads each value. The listing cost is charged to the first entry.
+    fn read_seq(&mut self, limit: usize, latencies: &mut Vec<Duration>) -> usize {
+        let start = Instant::now();
+        let mut keys = self.bench_db.db.kv_list(None).unwrap();
+        keys.sort_unstable();
+        let mut list_cost = Some(start.elapsed());
+
+        let mut read = 0;
+        for key in keys.iter().take(limit) {
+            let op_start = Instant::now();
+            let _ = self.bench_db.db.kv_get(key).unwrap();
+            latencies.push(op_start.elapsed() + list_cost.take().unwrap_or_default());
+            read += 1;
+        }
+        read
+    }
+}
+
+/// Open a fresh engine. Returns `None` (with a warning) if the engine is
+/// unavailable in this build.
+fn open_engine(engine: &str, mode: DurabilityConfig) -> Result<Box<dyn KvEngine>, String> {
+    match engine {
+        "strata" => Ok(Box::new(StrataEngine {
+            bench_db: create_db(mode),
+        })),
+        #[cfg(feature = "rocksdb-compare")]
+        "rocksdb" => match rocks::RocksEngine::open(mode) {
+            Ok(e) => Ok(Box::new(e)),
+            Err(e) => Err(format!("failed to open RocksDB: {}", e)),
+        },
+        #[cfg(not(feature = "rocksdb-compare"))]
+        "rocksdb" => Err(
+            "--rocksdb requires the rocksdb-compare feature: \
+             cargo bench --bench dbbench --features rocksdb-compare -- --rocksdb"
+                .to_string(),
+        ),
+        other => Err(format!("unknown engine '{}'", other)),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Benchmarks
+// ---------------------------------------------------------------------------
+
+struct BenchResult {
+    name: String,
+    ops: usize,
+    /// For reads: entries found. Equal to `ops` for writes.
+    found: usize,
+    elapsed: Duration,
+    latencies: Vec<Duration>,
+    /// Bytes of key + value moved, for db_bench's MB/s column.
+    bytes: u64,
+}
+
+impl BenchResult {
+    fn micros_per_op(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1e6 / self.ops.max(1) as f64
+    }
+
+    fn ops_per_sec(&self) -> f64 {
+        self.ops as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn mb_per_sec(&self) -> f64 {
+        self.bytes as f64 / 1_048_576.0 / self.elapsed.as_secs_f64()
+    }
+
+    fn percentile(&self, pct: usize) -> Duration {
+        let len = self.latencies.len();
+        if len == 0 {
+            return Duration::ZERO;
+        }
+        self.latencies[(len * pct / 100).min(len - 1)]
+    }
+}
+
+fn run_writes(
+    name: &str,
+    engine: &mut dyn KvEngine,
+    config: &Config,
+    mut next_key: impl FnMut() -> u64,
+) -> BenchResult {
+    let mut values = ValueGenerator::new();
+    let mut latencies = Vec::with_capacity(config.num);
+    let wall_start = Instant::now();
+
+    for _ in 0..config.num {
+        let key = dbbench_key(next_key());
+        let value = values.next(config.value_size);
+        let start = Instant::now();
+        engine.put(&key, value);
+        latencies.push(start.elapsed());
+    }
+
+    let elapsed = wall_start.elapsed();
+    latencies.sort_unstable();
+    BenchResult {
+        name: name.to_string(),
+        ops: config.num,
+        found: config.num,
+        elapsed,
+        latencies,
+        bytes: (config.num * (KEY_SIZE + config.value_size)) as u64,
+    }
+}
+
+fn run_readrandom(engine: &mut dyn KvEngine, config: &Config) -> BenchResult {
+    let mut rng = Rng::new(config.seed ^ 0xdb_be7c);
+    let mut latencies = Vec::with_capacity(config.reads);
+    let mut found = 0;
+    let wall_start = Instant::now();
+
+    for _ in 0..config.reads {
+        let key = dbbench_key(rng.uniform(config.num as u64));
+        let start = Instant::now();
+        if engine.get(&key) {
+            found += 1;
+        }
+        latencies.push(start.elapsed());
+    }
+
+    let elapsed = wall_start.elapsed();
+    latencies.sort_unstable();
+    BenchResult {
+        name: "readrandom".to_string(),
+        ops: config.reads,
+        found,
+        elapsed,
+        latencies,
+        bytes: (found * (KEY_SIZE + config.value_size)) as u64,
+    }
+}
+
+fn run_readseq(engine: &mut dyn KvEngine, config: &Config) -> BenchResult {
+    let mut latencies = Vec::with_capacity(config.reads);
+    let wall_start = Instant::now();
+    let read = engine.read_seq(config.reads, &mut latencies);
+    let elapsed = wall_start.elapsed();
+    latencies.sort_unstable();
+    BenchResult {
+        name: "readseq".to_string(),
+        ops: read,
+        found: read,
+        elapsed,
+        latencies,
+        bytes: (read * (KEY_SIZE + config.value_size)) as u64,
+    }
+}
+
+/// Run one named benchmark. fillseq and fillrandom start from an empty
+/// database (db_bench's fresh_db); the others reuse whatever the previous
+/// benchmarks left behind, as in db_bench.
+fn run_named(
+    name: &str,
+    engine: &mut Box<dyn KvEngine>,
+    engine_name: &str,
+    mode: DurabilityConfig,
+    config: &Config,
+) -> Result<BenchResult, String> {
+    match name {
+        "fillseq" => {
+            *engine = open_engine(engine_name, mode)?;
+            let mut i = 0;
+            Ok(run_writes(name, engine.as_mut(), config, || {
+                i += 1;
+                i - 1
+            }))
+        }
+        "fillrandom" => {
+            *engine = open_engine(engine_name, mode)?;
+            let mut rng = Rng::new(config.seed ^ 0xf111);
+            let num = config.num as u64;
+            Ok(run_writes(name, engine.as_mut(), config, || rng.uniform(num)))
+        }
+        "overwrite" => {
+            let mut rng = Rng::new(config.seed ^ 0x0e7e);
+            let num = config.num as u64;
+            Ok(run_writes(name, engine.as_mut(), config, || rng.uniform(num)))
+        }
+        "readrandom" => Ok(run_readrandom(engine.as_mut(), config)),
+        "readseq" => Ok(run_readseq(engine.as_mut(), config)),
+        other => Err(format!("unknown benchmark '{}'", other)),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+/// db_bench's report line, e.g.
+/// `fillseq      :       2.345 micros/op 426439 ops/sec;   47.1 MB/s`
+fn print_report(r: &BenchResult, label: &str) {
+    let found = if r.name.starts_with("read") {
+        format!(" ({} of {} found)", r.found, r.ops)
+    } else {
+        String::new()
+    };
+    eprintln!(
+        "{:<12} : {:>11.3} micros/op {} ops/sec; {:>6.1} MB/s{}  [{}]",
+        r.name,
+        r.micros_per_op(),
+        r.ops_per_sec() as u64,
+        r.mb_per_sec(),
+        found,
+        label,
+    );
+}
+
+fn print_quiet(r: &BenchResult, label: &str) {
+    eprintln!(
+        "{} [{}]: {:.0} ops/sec, p50={:.3} us",
+        r.name,
+        label,
+        r.ops_per_sec(),
+        r.percentile(50).as_nanos() as f64 / 1000.0,
+    );
+}
+
+fn print_csv_header() {
+    println!(
+        "\"benchmark\",\"engine\",\"durability\",\"ops\",\"micros_per_op\",\"ops_sec\",\"mb_sec\",\"p50_us\",\"p99_us\",\"found\""
+    );
+}
+
+fn print_csv_row(r: &BenchResult, engine: &str, mode: DurabilityConfig) {
+    println!(
+        "\"{}\",\"{}\",\"{}\",{},{:.3},{:.2},{:.2},{:.3},{:.3},{}",
+        r.name,
+        engine,
+        mode.label(),
+        r.ops,
+        r.micros_per_op(),
+        r.ops_per_sec(),
+        r.mb_per_sec(),
+        r.percentile(50).as_nanos() as f64 / 1000.0,
+        r.percentile(99).as_nanos() as f64 / 1000.0,
+        r.found,
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+fn result_name(benchmark: &str, engine: &str, mode: DurabilityConfig) -> String {
+    format!("dbbench/{}/{}/{}", benchmark, engine, mode.label())
+}
+
+/// Parameters shared by a result and a failure of the same configuration.
+fn base_params(engine: &str, mode: DurabilityConfig, config: &Config) -> HashMap<String, serde_json::Value> {
+    let mut params = HashMap::new();
+    params.insert("engine".into(), serde_json::json!(engine));
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("num".into(), serde_json::json!(config.num));
+    params.insert("key_size".into(), serde_json::json!(KEY_SIZE));
+    params.insert("value_size".into(), serde_json::json!(config.value_size));
+    params.insert("compression_ratio".into(), serde_json::json!(COMPRESSION_RATIO));
+    params.insert("seed".into(), serde_json::json!(config.seed));
+    params
+}
+
+fn record_result(
+    recorder: &mut ResultRecorder,
+    r: &BenchResult,
+    engine: &str,
+    mode: DurabilityConfig,
+    config: &Config,
+) {
+    let mut params = base_params(engine, mode, config);
+    params.insert("micros_per_op".into(), serde_json::json!(r.micros_per_op()));
+    params.insert("mb_per_sec".into(), serde_json::json!(r.mb_per_sec()));
+    if r.name.starts_with("read") {
+        params.insert("found".into(), serde_json::json!(r.found));
+    }
+
+    let sum: Duration = r.latencies.iter().sum();
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(&r.name, engine, mode),
+        category: "dbbench".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec()),
+            p50_ns: Some(r.percentile(50).as_nanos() as u64),
+            p95_ns: Some(r.percentile(95).as_nanos() as u64),
+            p99_ns: Some(r.percentile(99).as_nanos() as u64),
+            min_ns: r.latencies.first().map(|d| d.as_nanos() as u64),
+            max_ns: r.latencies.last().map(|d| d.as_nanos() as u64),
+            avg_ns: Some((sum / r.latencies.len().max(1) as u32).as_nanos() as u64),
+            samples: Some(r.ops as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct Config {
+    benchmarks: Vec<String>,
+    num: usize,
+    /// Read operations for readrandom/readseq (db_bench `--reads`; defaults
+    /// to `num`).
+    reads: usize,
+    value_size: usize,
+    /// Mixed into the key choosers of fillrandom, overwrite and readrandom
+    /// (`--seed`). The value pool stays fixed, as in db_bench.
+    seed: u64,
+    durability: Vec<DurabilityConfig>,
+    rocksdb: bool,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    // db_bench's `--flag=value` form works too (see harness/cli.rs)
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        benchmarks: ALL_BENCHMARKS.iter().map(|s| s.to_string()).collect(),
+        num: DEFAULT_NUM,
+        reads: 0,
+        value_size: DEFAULT_VALUE_SIZE,
+        seed: 0,
+        durability: DurabilityConfig::ALL.to_vec(),
+        rocksdb: false,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--benchmarks" => {
+                let v = args.value();
+                config.benchmarks = v
+                    .split(',')
+                    .map(|s| s.trim().to_lowercase())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+            }
+            "--num" => config.num = args.parse_value::<usize>().max(1),
+            "--reads" => config.reads = args.parse_value(),
+            "--value_size" | "--value-size" => {
+                config.value_size = args.parse_value();
+            }
+            "--rocksdb" => config.rocksdb = true,
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.seed = args.seed_or(0);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    if config.reads == 0 {
+        config.reads = config.num;
+    }
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv && !config.quiet {
+        eprintln!("=== StrataDB db_bench-style Benchmark ===");
+        eprintln!("Keys:       {} bytes each", KEY_SIZE);
+        eprintln!(
+            "Values:     {} bytes each ({} bytes after compression)",
+            config.value_size,
+            (config.value_size as f64 * COMPRESSION_RATIO) as usize
+        );
+        eprintln!("Entries:    {}", config.num);
+        eprintln!("Reads:      {}", config.reads);
+        eprintln!("Benchmarks: {}", config.benchmarks.join(","));
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut engines = vec!["strata"];
+    if config.rocksdb {
+        engines.push("rocksdb");
+    }
+
+    let mut recorder = ResultRecorder::new("dbbench");
+
+    for &engine_name in &engines {
+        for &mode in &config.durability {
+            let label = format!("{}/{}", engine_name, mode.label());
+            if !config.csv && !config.quiet {
+                eprintln!("--- engine: {} | durability: {} ---", engine_name, mode.label());
+            }
+
+            let mut engine = match open_engine(engine_name, mode) {
+                Ok(engine) => engine,
+                Err(e) => {
+                    for name in &config.benchmarks {
+                        recorder.record_failure(
+                            &result_name(name, engine_name, mode),
+                            base_params(engine_name, mode, &config),
+                            ResultStatus::Error { message: e.clone() },
+                        );
+                    }
+                    continue;
+                }
+            };
+            for name in &config.benchmarks {
+                let result_name = result_name(name, engine_name, mode);
+                let params = base_params(engine_name, mode, &config);
+                let outcome = recorder.guard(&result_name, params.clone(), |_| {
+                    run_named(name, &mut engine, engine_name, mode, &config)
+                });
+                let result = match outcome {
+                    Some(Ok(result)) => result,
+                    Some(Err(e)) => {
+                        recorder.record_failure(&result_name, params, ResultStatus::Error { message: e });
+                        continue;
+                    }
+                    // Panicked; guard recorded it
+                    None => continue,
+                };
+                if config.csv {
+                    print_csv_row(&result, engine_name, mode);
+                } else if config.quiet {
+                    print_quiet(&result, &label);
+                } else {
+                    print_report(&result, &label);
+                }
+                record_result(&mut recorder, &result, engine_name, mode, &config);
+            }
+            if !config.csv && !config.quiet {
+                eprintln!();
+            }
+        }
+    }
+
+    if !config.csv && !config.quiet {
+        eprintln!("=== db_bench complete ===");
+    }
+    recorder.save_or_exit();
+}
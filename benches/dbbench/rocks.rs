@@ -0,0 +1,71 @@
+//! RocksDB engine for the db_bench comparison (`rocksdb-compare` feature).
+//!
+//! Opens RocksDB with default options in a temp directory (under `--db-path`
+//! if given), so the numbers are what an untuned `db_bench` run would report.
+//! Strata durability modes map to RocksDB write options:
+//!
+//! | Strata   | RocksDB                         |
+//! |----------|---------------------------------|
+//! | cache    | `disable_wal = true`            |
+//! | standard | WAL on, `sync = false` (default) |
+//! | always   | WAL on, `sync = true`           |
+
+use super::harness::{cli, DurabilityConfig};
+use super::KvEngine;
+use rocksdb::{IteratorMode, Options, WriteOptions, DB};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+pub struct RocksEngine {
+    db: DB,
+    write_opts: WriteOptions,
+    _dir: TempDir,
+}
+
+impl RocksEngine {
+    pub fn open(mode: DurabilityConfig) -> Result<Self, String> {
+        let dir = TempDir::new_in(cli::db_root()).map_err(|e| e.to_string())?;
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, dir.path()).map_err(|e| e.to_string())?;
+
+        let mut write_opts = WriteOptions::default();
+        match mode {
+            DurabilityConfig::Cache => write_opts.disable_wal(true),
+            DurabilityConfig::Standard | DurabilityConfig::StandardSync { .. } => write_opts.set_sync(false),
+            DurabilityConfig::Always => write_opts.set_sync(true),
+        }
+
+        Ok(Self {
+            db,
+            write_opts,
+            _dir: dir,
+        })
+    }
+}
+
+impl KvEngine for RocksEngine {
+    fn put(&mut self, key: &str, value: &[u8]) {
+        self.db.put_opt(key, value, &self.write_opts).unwrap();
+    }
+
+    fn get(&mut self, key: &str) -> bool {
+        self.db.get_pinned(key).unwrap().is_some()
+    }
+
+    fn read_seq(&mut self, limit: usize, latencies: &mut Vec<Duration>) -> usize {
+        let mut iter = self.db.iterator(IteratorMode::Start);
+        let mut read = 0;
+        while read < limit {
+            let start = Instant::now();
+            match iter.next() {
+                Some(Ok(_entry)) => {
+                    latencies.push(start.elapsed());
+                    read += 1;
+                }
+                _ => break,
+            }
+        }
+        read
+    }
+}
@@ -0,0 +1,463 @@
+//! A/B Experiment Benchmark for StrataDB
+//!
+//! Runs one fixed workload against two database profiles in the same process,
+//! interleaving repetitions (ABBA) and reporting paired-difference statistics.
+//! This is far more sensitive than comparing two separate runs with
+//! bench-compare, because drift that hits both profiles equally cancels out.
+//!
+//! A profile is `key=value,...`: `durability` picks the mode, every other key
+//! goes to `config_set`. A profile without `durability` runs in the
+//! `--durability` mode (cache by default); `--strata-config` applies to both.
+//!
+//! `--per-op` times every operation instead and pairs op i of A with op i of
+//! B. Every workload replays the same operation stream, so the distribution
+//! of per-op differences resolves shifts far below the run-to-run spread.
+//!
+//! Run:    `cargo bench --bench ab -- --a auto_embed=true --b auto_embed=false`
+//! Modes:  `cargo bench --bench ab -- --a durability=cache --b durability=standard`
+//! Disk:   `cargo bench --bench ab -- --durability standard`
+//! Custom: `cargo bench --bench ab -- --workload json_set --reps 20 --ops 20000`
+//! Per-op: `cargo bench --bench ab -- --workload kv_get --per-op`
+//! CSV:    `cargo bench --bench ab -- --csv`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::experiment::{
+    diff_distribution, paired_stats, run_ab, run_ab_per_op, DbProfile, DiffDistribution,
+    PairedStats, DIFF_PERCENTILES,
+};
+use harness::recorder::ResultRecorder;
+use harness::{json_document, kv_key, kv_value, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::Instant;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Defaults
+// ---------------------------------------------------------------------------
+
+const DEFAULT_REPS: usize = 10;
+const DEFAULT_OPS: usize = 10_000;
+const DEFAULT_A: &str = "auto_embed=true";
+const DEFAULT_B: &str = "auto_embed=false";
+
+const WORKLOADS: &[&str] = &["kv_put", "kv_get", "json_set", "mixed"];
+
+// ---------------------------------------------------------------------------
+// Workloads
+// ---------------------------------------------------------------------------
+
+/// Read workloads pre-populate outside the timed region.
+fn populate(workload: &str, bench_db: &BenchDb, ops: usize) {
+    if workload == "kv_get" || workload == "mixed" {
+        for i in 0..ops as u64 {
+            bench_db.db.kv_put(&kv_key(i), kv_value()).unwrap();
+        }
+    }
+}
+
+/// Operation `i` of `workload`. Depends only on `i`, so every repetition
+/// replays the same stream.
+fn run_op(workload: &str, bench_db: &BenchDb, i: u64, ops: u64) {
+    let db = &bench_db.db;
+    match workload {
+        "kv_put" => {
+            db.kv_put(&kv_key(i), kv_value()).unwrap();
+        }
+        "kv_get" => {
+            db.kv_get(&kv_key(i)).unwrap();
+        }
+        "json_set" => {
+            db.json_set(&format!("doc:{}", i), "$", json_document(i))
+                .unwrap();
+        }
+        "mixed" => {
+            // 90% reads, 10% overwrites over the pre-populated keyspace
+            let key = kv_key(i.wrapping_mul(7919) % ops);
+            if i.is_multiple_of(10) {
+                db.kv_put(&key, kv_value()).unwrap();
+            } else {
+                db.kv_get(&key).unwrap();
+            }
+        }
+        other => panic!("unknown workload '{}'", other),
+    }
+}
+
+/// Run one repetition of `workload` and return its throughput in ops/sec.
+fn run_workload(workload: &str, bench_db: &BenchDb, ops: usize) -> f64 {
+    populate(workload, bench_db, ops);
+    let start = Instant::now();
+    for i in 0..ops as u64 {
+        run_op(workload, bench_db, i, ops as u64);
+    }
+    ops as f64 / start.elapsed().as_secs_f64()
+}
+
+/// Run one repetition of `workload` and return each op's latency in ns.
+fn run_workload_per_op(workload: &str, bench_db: &BenchDb, ops: usize) -> Vec<f64> {
+    populate(workload, bench_db, ops);
+    (0..ops as u64)
+        .map(|i| {
+            let start = Instant::now();
+            run_op(workload, bench_db, i, ops as u64);
+            start.elapsed().as_nanos() as f64
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+fn print_csv_header() {
+    println!("workload,profile_a,profile_b,reps,mean_a_ops,mean_b_ops,mean_diff_ops,ci95_low,ci95_high,rel_diff_pct,significant");
+}
+
+fn print_csv_row(workload: &str, a: &DbProfile, b: &DbProfile, s: &PairedStats) {
+    println!(
+        "{},\"{}\",\"{}\",{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.2},{}",
+        workload,
+        a.describe(),
+        b.describe(),
+        s.reps,
+        s.mean_a,
+        s.mean_b,
+        s.mean_diff,
+        s.ci95_low,
+        s.ci95_high,
+        s.rel_diff_pct,
+        s.significant(),
+    );
+}
+
+fn print_per_op_csv_header() {
+    let cols: Vec<String> = DIFF_PERCENTILES.iter().map(|p| format!("diff_p{}_ns", p)).collect();
+    println!(
+        "workload,profile_a,profile_b,ops,{},median_ci95_low,median_ci95_high,b_lower_pct,marginal_p50_diff_ns,significant",
+        cols.join(",")
+    );
+}
+
+fn print_per_op_csv_row(workload: &str, a: &DbProfile, b: &DbProfile, d: &DiffDistribution) {
+    let cols: Vec<String> = d.percentiles.iter().map(|(_, v)| format!("{:.0}", v)).collect();
+    println!(
+        "{},\"{}\",\"{}\",{},{},{:.0},{:.0},{:.2},{:.0},{}",
+        workload,
+        a.describe(),
+        b.describe(),
+        d.ops,
+        cols.join(","),
+        d.median_ci95_low,
+        d.median_ci95_high,
+        d.b_lower_pct,
+        d.marginal_p50_diff,
+        d.significant(),
+    );
+}
+
+fn print_per_op_summary(workload: &str, a: &DbProfile, b: &DbProfile, d: &DiffDistribution) {
+    eprintln!("  workload:   {} (per-op, {} pairs)", workload, d.ops);
+    eprintln!("  A:          {}", a.describe());
+    eprintln!("  B:          {}", b.describe());
+    eprintln!("  B - A per op (ns):");
+    for (p, v) in &d.percentiles {
+        eprintln!("    p{:<4} {:>+10.0}", p, v);
+    }
+    eprintln!(
+        "  median:     {:+.0} ns  95% CI [{:+.0}, {:+.0}]  (unpaired p50 diff {:+.0} ns)",
+        d.at(50.0).unwrap_or(0.0),
+        d.median_ci95_low,
+        d.median_ci95_high,
+        d.marginal_p50_diff
+    );
+    eprintln!("  B lower on {:.1}% of ops", d.b_lower_pct);
+    eprintln!(
+        "  verdict:    {}",
+        if !d.significant() {
+            "no significant difference"
+        } else if d.median_ci95_high < 0.0 {
+            "B is faster"
+        } else {
+            "B is slower"
+        }
+    );
+    eprintln!();
+}
+
+fn print_summary(workload: &str, a: &DbProfile, b: &DbProfile, s: &PairedStats) {
+    eprintln!("  workload:   {}", workload);
+    eprintln!("  A:          {:.0} ops/s  ({})", s.mean_a, a.describe());
+    eprintln!("  B:          {:.0} ops/s  ({})", s.mean_b, b.describe());
+    eprintln!(
+        "  B - A:      {:+.0} ops/s ({:+.2}%)  95% CI [{:+.0}, {:+.0}]  stdev {:.0}  n={}",
+        s.mean_diff, s.rel_diff_pct, s.ci95_low, s.ci95_high, s.stdev_diff, s.reps
+    );
+    eprintln!(
+        "  verdict:    {}",
+        if !s.significant() {
+            "no significant difference"
+        } else if s.mean_diff > 0.0 {
+            "B is faster"
+        } else {
+            "B is slower"
+        }
+    );
+    eprintln!();
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+fn record_experiment(
+    recorder: &mut ResultRecorder,
+    workload: &str,
+    ops: usize,
+    a: &DbProfile,
+    b: &DbProfile,
+    s: &PairedStats,
+) {
+    for (profile, mean) in [(a, s.mean_a), (b, s.mean_b)] {
+        let mut params = HashMap::new();
+        params.insert("workload".into(), serde_json::json!(workload));
+        params.insert("profile".into(), serde_json::json!(profile.describe()));
+        params.insert("durability".into(), serde_json::json!(profile.durability.label()));
+        params.insert("ops_per_rep".into(), serde_json::json!(ops));
+        recorder.record(BenchmarkResult {
+            benchmark: format!("experiment/{}/{}", workload, profile.name),
+            category: "experiment".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(mean),
+                samples: Some(s.reps as u64),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::Success,
+        });
+    }
+
+    let mut params = HashMap::new();
+    params.insert("workload".into(), serde_json::json!(workload));
+    params.insert("profile_a".into(), serde_json::json!(a.describe()));
+    params.insert("profile_b".into(), serde_json::json!(b.describe()));
+    params.insert("ops_per_rep".into(), serde_json::json!(ops));
+    params.insert("mean_diff_ops_per_sec".into(), serde_json::json!(s.mean_diff));
+    params.insert("stdev_diff_ops_per_sec".into(), serde_json::json!(s.stdev_diff));
+    params.insert("ci95_low".into(), serde_json::json!(s.ci95_low));
+    params.insert("ci95_high".into(), serde_json::json!(s.ci95_high));
+    params.insert("rel_diff_pct".into(), serde_json::json!(s.rel_diff_pct));
+    params.insert("significant".into(), serde_json::json!(s.significant()));
+    recorder.record(BenchmarkResult {
+        benchmark: format!("experiment/{}/b-minus-a", workload),
+        category: "experiment".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            samples: Some(s.reps as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+fn record_per_op(
+    recorder: &mut ResultRecorder,
+    workload: &str,
+    config: &Config,
+    d: &DiffDistribution,
+) {
+    let mut params = HashMap::new();
+    params.insert("workload".into(), serde_json::json!(workload));
+    params.insert("profile_a".into(), serde_json::json!(config.a.describe()));
+    params.insert("profile_b".into(), serde_json::json!(config.b.describe()));
+    params.insert("ops_per_rep".into(), serde_json::json!(config.ops));
+    params.insert("reps".into(), serde_json::json!(config.reps));
+    for (p, v) in &d.percentiles {
+        params.insert(format!("diff_p{}_ns", p), serde_json::json!(v));
+    }
+    params.insert("median_ci95_low_ns".into(), serde_json::json!(d.median_ci95_low));
+    params.insert("median_ci95_high_ns".into(), serde_json::json!(d.median_ci95_high));
+    params.insert("b_lower_pct".into(), serde_json::json!(d.b_lower_pct));
+    params.insert("marginal_p50_diff_ns".into(), serde_json::json!(d.marginal_p50_diff));
+    params.insert("significant".into(), serde_json::json!(d.significant()));
+    recorder.record(BenchmarkResult {
+        benchmark: format!("experiment/{}/b-minus-a/per-op", workload),
+        category: "experiment".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            samples: Some(d.ops as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    a: DbProfile,
+    b: DbProfile,
+    workloads: Vec<String>,
+    reps: usize,
+    ops: usize,
+    /// Pair individual operations instead of whole repetitions.
+    per_op: bool,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_profile(name: &str, spec: &str, args: &harness::cli::Args) -> DbProfile {
+    let mut profile = DbProfile::parse(name, spec, args.durability_or(DurabilityConfig::Cache))
+        .unwrap_or_else(|e| {
+            eprintln!("Invalid profile --{} '{}': {}", name, spec, e);
+            std::process::exit(1);
+        });
+    profile.durability = args.strata_config.apply(profile.durability);
+    profile
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut a = DEFAULT_A.to_string();
+    let mut b = DEFAULT_B.to_string();
+    let mut config = Config {
+        a: DbProfile::new("a", DurabilityConfig::Cache),
+        b: DbProfile::new("b", DurabilityConfig::Cache),
+        workloads: vec!["kv_put".to_string()],
+        reps: DEFAULT_REPS,
+        ops: DEFAULT_OPS,
+        per_op: false,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--a" => a = args.value(),
+            "--b" => b = args.value(),
+            "--workload" | "-w" => {
+                let v = args.value();
+                config.workloads = if v == "all" {
+                    WORKLOADS.iter().map(|s| s.to_string()).collect()
+                } else {
+                    v.split(',').map(|s| s.trim().to_string()).collect()
+                };
+            }
+            "--reps" => config.reps = args.parse_value::<usize>().max(2),
+            "--ops" => config.ops = args.parse_value::<usize>().max(1),
+            "--per-op" => config.per_op = true,
+            _ => args.unknown_flag(),
+        }
+    }
+    config.a = parse_profile("a", &a, &args);
+    config.b = parse_profile("b", &b, &args);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    for w in &config.workloads {
+        if !WORKLOADS.contains(&w.as_str()) {
+            eprintln!("Unknown workload '{}'. Available: {}", w, WORKLOADS.join(", "));
+            std::process::exit(1);
+        }
+    }
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB A/B Experiment ===");
+        eprintln!("A: {}", config.a.describe());
+        eprintln!("B: {}", config.b.describe());
+        eprintln!(
+            "{} reps x {} ops per profile, interleaved ABBA, fresh database per rep{}",
+            config.reps,
+            config.ops,
+            if config.per_op { ", per-op pairing" } else { "" }
+        );
+        eprintln!();
+    } else if config.per_op {
+        print_per_op_csv_header();
+    } else {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("experiment");
+
+    for workload in &config.workloads {
+        let guard_name = format!(
+            "experiment/{}/b-minus-a{}",
+            workload,
+            if config.per_op { "/per-op" } else { "" }
+        );
+        let mut guard_params = HashMap::new();
+        guard_params.insert("workload".into(), serde_json::json!(workload));
+        guard_params.insert("profile_a".into(), serde_json::json!(config.a.describe()));
+        guard_params.insert("profile_b".into(), serde_json::json!(config.b.describe()));
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            if config.per_op {
+                let samples = run_ab_per_op(&config.a, &config.b, config.reps, |bench_db| {
+                    run_workload_per_op(workload, bench_db, config.ops)
+                });
+                let dist = diff_distribution(&samples.a, &samples.b);
+
+                if config.csv {
+                    print_per_op_csv_row(workload, &config.a, &config.b, &dist);
+                } else if config.quiet {
+                    eprintln!(
+                        "{}: B-A per-op median {:+.0} ns [{:+.0}, {:+.0}]",
+                        workload,
+                        dist.at(50.0).unwrap_or(0.0),
+                        dist.median_ci95_low,
+                        dist.median_ci95_high
+                    );
+                } else {
+                    print_per_op_summary(workload, &config.a, &config.b, &dist);
+                }
+
+                record_per_op(recorder, workload, &config, &dist);
+                return;
+            }
+
+            let samples = run_ab(&config.a, &config.b, config.reps, |bench_db| {
+                run_workload(workload, bench_db, config.ops)
+            });
+            let stats = paired_stats(&samples.a, &samples.b);
+
+            if config.csv {
+                print_csv_row(workload, &config.a, &config.b, &stats);
+            } else if config.quiet {
+                eprintln!(
+                    "{}: B-A {:+.2}% [{:+.0}, {:+.0}] ops/s",
+                    workload, stats.rel_diff_pct, stats.ci95_low, stats.ci95_high
+                );
+            } else {
+                print_summary(workload, &config.a, &config.b, &stats);
+            }
+
+            record_experiment(recorder, workload, config.ops, &config.a, &config.b, &stats);
+        });
+    }
+
+    if !config.csv {
+        eprintln!("=== Experiment complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,607 @@
+//! Configuration Matrix Benchmark for StrataDB
+//!
+//! Runs one fixed KV/JSON workload under every on/off combination of a set of
+//! `config_set` toggles (a full factorial design) and reports the marginal
+//! cost of each feature: mean throughput with the feature on minus mean
+//! throughput with it off, averaged over all settings of the other toggles.
+//!
+//! Like `ab`, every cell of the matrix runs in the same process with a fresh
+//! database per repetition, and the cell order is rotated between
+//! repetitions, so drift hits every profile alike and the per-repetition
+//! differences can be paired.
+//!
+//! A toggle is `key` (on=`true`, off=`false`) or `key=on/off`. Each toggle
+//! is probed with `config_set` at startup; keys this Strata build rejects are
+//! dropped with a warning.
+//!
+//! Run:    `cargo bench --bench config_matrix`
+//! Custom: `cargo bench --bench config_matrix -- --toggles auto_embed,cache_mb=256/0 --reps 10`
+//! Modes:  `cargo bench --bench config_matrix -- --durability standard`
+//! CSV:    `cargo bench --bench config_matrix -- --csv`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::experiment::{paired_stats, DbProfile, PairedStats};
+use harness::recorder::ResultRecorder;
+use harness::{
+    create_db, json_document, kv_key, kv_value, print_hardware_info, BenchDb, DurabilityConfig,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Defaults
+// ---------------------------------------------------------------------------
+
+const DEFAULT_REPS: usize = 6;
+const DEFAULT_OPS: usize = 10_000;
+const DEFAULT_TOGGLES: &str = "auto_embed";
+
+/// 2^6 = 64 profiles per repetition is already a long run.
+const MAX_TOGGLES: usize = 6;
+
+/// Operation kinds of the fixed workload, in the order they repeat.
+const OP_KINDS: [&str; 4] = ["kv_put", "kv_get", "json_set", "json_get"];
+
+// ---------------------------------------------------------------------------
+// Toggles and profiles
+// ---------------------------------------------------------------------------
+
+/// One `config_set` key with the values that switch the feature on and off.
+#[derive(Debug, Clone)]
+struct Toggle {
+    key: String,
+    on: String,
+    off: String,
+}
+
+impl Toggle {
+    /// Parse `key` or `key=on/off`.
+    fn parse(spec: &str) -> Result<Self, String> {
+        match spec.split_once('=') {
+            None => Ok(Self {
+                key: spec.to_string(),
+                on: "true".to_string(),
+                off: "false".to_string(),
+            }),
+            Some((key, values)) => {
+                let (on, off) = values
+                    .split_once('/')
+                    .ok_or_else(|| format!("expected key=on/off, got '{}'", spec))?;
+                Ok(Self {
+                    key: key.trim().to_string(),
+                    on: on.trim().to_string(),
+                    off: off.trim().to_string(),
+                })
+            }
+        }
+    }
+
+    /// True if this Strata build accepts both values for the key.
+    fn supported(&self, bench_db: &BenchDb) -> bool {
+        bench_db.db.config_set(&self.key, &self.on).is_ok()
+            && bench_db.db.config_set(&self.key, &self.off).is_ok()
+    }
+}
+
+/// Profile for one cell of the matrix. Bit `j` of `mask` switches toggle `j`
+/// on. Every toggle is set explicitly, so the baseline doesn't depend on
+/// engine defaults.
+fn cell_profile(toggles: &[Toggle], mask: usize, durability: DurabilityConfig) -> DbProfile {
+    let on: Vec<&str> = toggles
+        .iter()
+        .enumerate()
+        .filter(|(j, _)| mask & (1 << j) != 0)
+        .map(|(_, t)| t.key.as_str())
+        .collect();
+    let name = if on.is_empty() { "baseline".to_string() } else { on.join("+") };
+    toggles
+        .iter()
+        .enumerate()
+        .fold(DbProfile::new(&name, durability), |profile, (j, t)| {
+            let value = if mask & (1 << j) != 0 { &t.on } else { &t.off };
+            profile.with_setting(&t.key, value)
+        })
+}
+
+// ---------------------------------------------------------------------------
+// Workload
+// ---------------------------------------------------------------------------
+
+/// One repetition of one cell.
+#[derive(Debug, Clone, Copy, Default)]
+struct CellSample {
+    ops_per_sec: f64,
+    /// Mean latency per operation kind, indexed like `OP_KINDS`.
+    kind_ns: [f64; 4],
+}
+
+/// Operation `i` of the fixed workload. Cycles through `OP_KINDS`; each read
+/// targets the key written by the previous write of the same family.
+fn run_op(bench_db: &BenchDb, i: u64) {
+    let db = &bench_db.db;
+    let n = i / 4;
+    match i % 4 {
+        0 => {
+            db.kv_put(&kv_key(n), kv_value()).unwrap();
+        }
+        1 => {
+            assert!(db.kv_get(&kv_key(n)).unwrap().is_some(), "kv_get missed {}", n);
+        }
+        2 => {
+            db.json_set(&format!("doc:{}", n), "$", json_document(n))
+                .unwrap();
+        }
+        _ => {
+            assert!(
+                db.json_get(&format!("doc:{}", n), "$").unwrap().is_some(),
+                "json_get missed doc:{}",
+                n
+            );
+        }
+    }
+}
+
+fn run_workload(bench_db: &BenchDb, ops: usize) -> CellSample {
+    let mut kind_total = [0u128; 4];
+    let start = Instant::now();
+    for i in 0..ops as u64 {
+        let op_start = Instant::now();
+        run_op(bench_db, i);
+        kind_total[(i % 4) as usize] += op_start.elapsed().as_nanos();
+    }
+    let elapsed = start.elapsed();
+
+    let mut kind_ns = [0.0; 4];
+    for (k, total) in kind_total.iter().enumerate() {
+        let count = (ops + 3 - k) / 4;
+        if count > 0 {
+            kind_ns[k] = *total as f64 / count as f64;
+        }
+    }
+    CellSample {
+        ops_per_sec: ops as f64 / elapsed.as_secs_f64(),
+        kind_ns,
+    }
+}
+
+/// Run every cell `reps` times. Cell order is rotated by one each repetition
+/// and reversed on odd repetitions, so no profile always runs first or last.
+/// Returns `samples[rep][cell]`.
+fn run_matrix(profiles: &[DbProfile], reps: usize, ops: usize, verbose: bool) -> Vec<Vec<CellSample>> {
+    let cells = profiles.len();
+    let mut samples = Vec::with_capacity(reps);
+    for rep in 0..reps {
+        let mut order: Vec<usize> = (0..cells).map(|c| (c + rep) % cells).collect();
+        if rep % 2 == 1 {
+            order.reverse();
+        }
+        let mut row = vec![CellSample::default(); cells];
+        for cell in order {
+            let bench_db = profiles[cell].open();
+            row[cell] = run_workload(&bench_db, ops);
+        }
+        samples.push(row);
+        if verbose {
+            eprintln!("  rep {}/{} done", rep + 1, reps);
+        }
+    }
+    samples
+}
+
+// ---------------------------------------------------------------------------
+// Analysis
+// ---------------------------------------------------------------------------
+
+fn mean(v: &[f64]) -> f64 {
+    if v.is_empty() {
+        0.0
+    } else {
+        v.iter().sum::<f64>() / v.len() as f64
+    }
+}
+
+fn stdev(v: &[f64]) -> f64 {
+    if v.len() < 2 {
+        return 0.0;
+    }
+    let m = mean(v);
+    (v.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (v.len() - 1) as f64).sqrt()
+}
+
+struct CellSummary {
+    ops_per_sec: f64,
+    stdev_ops_per_sec: f64,
+    kind_ns: [f64; 4],
+}
+
+fn summarize_cell(samples: &[Vec<CellSample>], cell: usize) -> CellSummary {
+    let ops: Vec<f64> = samples.iter().map(|row| row[cell].ops_per_sec).collect();
+    let mut kind_ns = [0.0; 4];
+    for (k, slot) in kind_ns.iter_mut().enumerate() {
+        let v: Vec<f64> = samples.iter().map(|row| row[cell].kind_ns[k]).collect();
+        *slot = mean(&v);
+    }
+    CellSummary {
+        ops_per_sec: mean(&ops),
+        stdev_ops_per_sec: stdev(&ops),
+        kind_ns,
+    }
+}
+
+/// Marginal cost of one toggle.
+struct Effect {
+    /// Main effect: per repetition, mean over cells with the toggle on vs.
+    /// mean over cells with it off. `a` = off, `b` = on.
+    main: PairedStats,
+    /// The toggle alone against the baseline, all other toggles off.
+    isolated: PairedStats,
+    /// Mean latency added per operation kind (on - off), in ns.
+    kind_ns: [f64; 4],
+}
+
+impl Effect {
+    /// Throughput lost by switching the feature on, as a percentage.
+    fn cost_pct(&self) -> f64 {
+        -self.main.rel_diff_pct
+    }
+}
+
+fn toggle_effect(samples: &[Vec<CellSample>], j: usize) -> Effect {
+    let bit = 1 << j;
+    let reps = samples.len();
+    let group_mean = |rep: usize, on: bool, f: &dyn Fn(&CellSample) -> f64| {
+        let v: Vec<f64> = samples[rep]
+            .iter()
+            .enumerate()
+            .filter(|(mask, _)| (mask & bit != 0) == on)
+            .map(|(_, s)| f(s))
+            .collect();
+        mean(&v)
+    };
+
+    let off: Vec<f64> = (0..reps).map(|r| group_mean(r, false, &|s| s.ops_per_sec)).collect();
+    let on: Vec<f64> = (0..reps).map(|r| group_mean(r, true, &|s| s.ops_per_sec)).collect();
+    let base: Vec<f64> = samples.iter().map(|row| row[0].ops_per_sec).collect();
+    let alone: Vec<f64> = samples.iter().map(|row| row[bit].ops_per_sec).collect();
+
+    let mut kind_ns = [0.0; 4];
+    for (k, slot) in kind_ns.iter_mut().enumerate() {
+        let diffs: Vec<f64> = (0..reps)
+            .map(|r| group_mean(r, true, &|s| s.kind_ns[k]) - group_mean(r, false, &|s| s.kind_ns[k]))
+            .collect();
+        *slot = mean(&diffs);
+    }
+
+    Effect {
+        main: paired_stats(&off, &on),
+        isolated: paired_stats(&base, &alone),
+        kind_ns,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+fn print_csv_header() {
+    println!("row,name,settings,ops_per_sec,stdev_ops,diff_ops,ci95_low,ci95_high,cost_pct,isolated_cost_pct,significant");
+}
+
+fn print_csv_cell(profile: &DbProfile, s: &CellSummary) {
+    println!(
+        "cell,{},\"{}\",{:.1},{:.1},,,,,,",
+        profile.name,
+        profile.describe(),
+        s.ops_per_sec,
+        s.stdev_ops_per_sec
+    );
+}
+
+fn print_csv_effect(t: &Toggle, e: &Effect) {
+    println!(
+        "effect,{},\"{}={}/{}\",{:.1},{:.1},{:.1},{:.1},{:.1},{:.2},{:.2},{}",
+        t.key,
+        t.key,
+        t.on,
+        t.off,
+        e.main.mean_b,
+        e.main.stdev_diff,
+        e.main.mean_diff,
+        e.main.ci95_low,
+        e.main.ci95_high,
+        e.cost_pct(),
+        -e.isolated.rel_diff_pct,
+        e.main.significant(),
+    );
+}
+
+fn print_cells(profiles: &[DbProfile], cells: &[CellSummary]) {
+    eprintln!(
+        "  {:<32} {:>12} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "profile", "ops/s", "stdev", "kv_put", "kv_get", "json_set", "json_get"
+    );
+    eprintln!("  {}", "-".repeat(100));
+    for (p, s) in profiles.iter().zip(cells) {
+        eprintln!(
+            "  {:<32} {:>12.0} {:>10.0} {:>8.0}ns {:>8.0}ns {:>8.0}ns {:>8.0}ns",
+            p.name,
+            s.ops_per_sec,
+            s.stdev_ops_per_sec,
+            s.kind_ns[0],
+            s.kind_ns[1],
+            s.kind_ns[2],
+            s.kind_ns[3]
+        );
+    }
+    eprintln!();
+}
+
+fn print_effects(toggles: &[Toggle], effects: &[Effect]) {
+    eprintln!("  Marginal cost (on vs. off, averaged over the other toggles):");
+    for (t, e) in toggles.iter().zip(effects) {
+        eprintln!("  {} ({} vs {})", t.key, t.on, t.off);
+        eprintln!(
+            "    throughput: {:+.0} ops/s ({:+.2}%)  95% CI [{:+.0}, {:+.0}]  n={}",
+            e.main.mean_diff, e.main.rel_diff_pct, e.main.ci95_low, e.main.ci95_high, e.main.reps
+        );
+        eprintln!(
+            "    alone:      {:+.0} ops/s ({:+.2}%) vs baseline",
+            e.isolated.mean_diff, e.isolated.rel_diff_pct
+        );
+        let per_kind: Vec<String> = OP_KINDS
+            .iter()
+            .zip(e.kind_ns)
+            .map(|(kind, ns)| format!("{} {:+.0}ns", kind, ns))
+            .collect();
+        eprintln!("    per op:     {}", per_kind.join("  "));
+        eprintln!(
+            "    verdict:    {}",
+            if !e.main.significant() {
+                "no significant cost".to_string()
+            } else if e.main.mean_diff < 0.0 {
+                format!("costs {:.2}% throughput", e.cost_pct())
+            } else {
+                format!("faster by {:.2}%", -e.cost_pct())
+            }
+        );
+    }
+    eprintln!();
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+fn record_cell(
+    recorder: &mut ResultRecorder,
+    config: &Config,
+    profile: &DbProfile,
+    s: &CellSummary,
+) {
+    let mut params = HashMap::new();
+    params.insert("profile".into(), serde_json::json!(profile.describe()));
+    params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    params.insert("ops_per_rep".into(), serde_json::json!(config.ops));
+    params.insert("reps".into(), serde_json::json!(config.reps));
+    for (key, value) in &profile.settings {
+        params.insert(key.clone(), serde_json::json!(value));
+    }
+    params.insert("stdev_ops_per_sec".into(), serde_json::json!(s.stdev_ops_per_sec));
+    for (kind, ns) in OP_KINDS.iter().zip(s.kind_ns) {
+        params.insert(format!("{}_mean_ns", kind), serde_json::json!(ns));
+    }
+    recorder.record(BenchmarkResult {
+        benchmark: format!("experiment/config_matrix/{}", profile.name),
+        category: "experiment".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(s.ops_per_sec),
+            samples: Some(config.reps as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+fn record_effect(recorder: &mut ResultRecorder, config: &Config, t: &Toggle, e: &Effect) {
+    let mut params = HashMap::new();
+    params.insert("toggle".into(), serde_json::json!(t.key));
+    params.insert("on_value".into(), serde_json::json!(t.on));
+    params.insert("off_value".into(), serde_json::json!(t.off));
+    params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    params.insert("ops_per_rep".into(), serde_json::json!(config.ops));
+    params.insert("mean_off_ops_per_sec".into(), serde_json::json!(e.main.mean_a));
+    params.insert("mean_on_ops_per_sec".into(), serde_json::json!(e.main.mean_b));
+    params.insert("mean_diff_ops_per_sec".into(), serde_json::json!(e.main.mean_diff));
+    params.insert("stdev_diff_ops_per_sec".into(), serde_json::json!(e.main.stdev_diff));
+    params.insert("ci95_low".into(), serde_json::json!(e.main.ci95_low));
+    params.insert("ci95_high".into(), serde_json::json!(e.main.ci95_high));
+    params.insert("cost_pct".into(), serde_json::json!(e.cost_pct()));
+    params.insert("isolated_cost_pct".into(), serde_json::json!(-e.isolated.rel_diff_pct));
+    params.insert("significant".into(), serde_json::json!(e.main.significant()));
+    for (kind, ns) in OP_KINDS.iter().zip(e.kind_ns) {
+        params.insert(format!("{}_cost_ns", kind), serde_json::json!(ns));
+    }
+    recorder.record(BenchmarkResult {
+        benchmark: format!("experiment/config_matrix/effect/{}", t.key),
+        category: "experiment".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            samples: Some(e.main.reps as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    toggles: Vec<Toggle>,
+    durability: DurabilityConfig,
+    reps: usize,
+    ops: usize,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_toggles(spec: &str) -> Vec<Toggle> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            Toggle::parse(s).unwrap_or_else(|e| {
+                eprintln!("Invalid --toggles entry: {}", e);
+                std::process::exit(1);
+            })
+        })
+        .collect()
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        toggles: parse_toggles(DEFAULT_TOGGLES),
+        durability: DurabilityConfig::Cache,
+        reps: DEFAULT_REPS,
+        ops: DEFAULT_OPS,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--toggles" => config.toggles = parse_toggles(&args.value()),
+            "--reps" => config.reps = args.parse_value::<usize>().max(2),
+            "--ops" => config.ops = args.parse_value::<usize>().max(4),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    if config.toggles.len() > MAX_TOGGLES {
+        eprintln!(
+            "{} toggles would need {} profiles per rep; at most {} are supported",
+            config.toggles.len(),
+            1usize << config.toggles.len(),
+            MAX_TOGGLES
+        );
+        std::process::exit(1);
+    }
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let mut config = parse_args();
+    let verbose = !config.csv && !config.quiet;
+    print_hardware_info();
+
+    {
+        let probe = create_db(DurabilityConfig::Cache);
+        config.toggles.retain(|t| {
+            let ok = t.supported(&probe);
+            if !ok {
+                eprintln!("  Warning: config_set(\"{}\") rejected by this Strata build; dropping it", t.key);
+            }
+            ok
+        });
+    }
+    if config.toggles.is_empty() {
+        eprintln!("No supported toggles left; nothing to compare");
+        std::process::exit(1);
+    }
+
+    let profiles: Vec<DbProfile> = (0..1usize << config.toggles.len())
+        .map(|mask| cell_profile(&config.toggles, mask, config.durability))
+        .collect();
+
+    if config.csv {
+        print_csv_header();
+    } else {
+        eprintln!("=== StrataDB Configuration Matrix ===");
+        let names: Vec<String> = config
+            .toggles
+            .iter()
+            .map(|t| format!("{}={}/{}", t.key, t.on, t.off))
+            .collect();
+        eprintln!("Toggles: {}", names.join(", "));
+        eprintln!(
+            "{} profiles x {} reps x {} ops, durability {}, fresh database per cell",
+            profiles.len(),
+            config.reps,
+            config.ops,
+            config.durability.label()
+        );
+        eprintln!();
+    }
+
+    let mut recorder = ResultRecorder::new("experiment");
+
+    let toggle_keys: Vec<&str> = config.toggles.iter().map(|t| t.key.as_str()).collect();
+    let mut guard_params = HashMap::new();
+    guard_params.insert("toggles".into(), serde_json::json!(toggle_keys));
+    guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    guard_params.insert("ops_per_rep".into(), serde_json::json!(config.ops));
+    guard_params.insert("reps".into(), serde_json::json!(config.reps));
+    recorder.guard("experiment/config_matrix", guard_params, |recorder| {
+        let samples = run_matrix(&profiles, config.reps, config.ops, verbose);
+        let cells: Vec<CellSummary> = (0..profiles.len())
+            .map(|cell| summarize_cell(&samples, cell))
+            .collect();
+        let effects: Vec<Effect> = (0..config.toggles.len())
+            .map(|j| toggle_effect(&samples, j))
+            .collect();
+
+        if config.csv {
+            for (p, s) in profiles.iter().zip(&cells) {
+                print_csv_cell(p, s);
+            }
+            for (t, e) in config.toggles.iter().zip(&effects) {
+                print_csv_effect(t, e);
+            }
+        } else if config.quiet {
+            for (t, e) in config.toggles.iter().zip(&effects) {
+                eprintln!(
+                    "{}: cost {:+.2}% [{:+.0}, {:+.0}] ops/s",
+                    t.key,
+                    e.cost_pct(),
+                    e.main.ci95_low,
+                    e.main.ci95_high
+                );
+            }
+        } else {
+            eprintln!();
+            print_cells(&profiles, &cells);
+            print_effects(&config.toggles, &effects);
+        }
+
+        for (p, s) in profiles.iter().zip(&cells) {
+            record_cell(recorder, &config, p, s);
+        }
+        for (t, e) in config.toggles.iter().zip(&effects) {
+            record_effect(recorder, &config, t, e);
+        }
+    });
+
+    if !config.csv {
+        eprintln!("=== Configuration matrix complete ===");
+    }
+    recorder.save_or_exit();
+}
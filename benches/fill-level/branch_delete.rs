@@ -0,0 +1,571 @@
+//! Branch Delete Benchmark for StrataDB
+//!
+//! Measures what deleting a branch costs as the branch grows. The isolation
+//! tests show `delete_branch` wipes a branch's data, but not how long that
+//! takes or whether the cleanup slows down everything else. At each branch
+//! size (10K to 10M keys) it:
+//!
+//! - fills a fresh branch with that many keys
+//! - starts a foreground thread doing gets and puts on the default branch
+//! - after a baseline window, times `delete_branch` on the full branch
+//! - keeps the foreground running for a window after the delete returns
+//!
+//! Foreground latency is reported for three phases: `baseline` (before the
+//! delete), `during` (operations that started while `delete_branch` was in
+//! flight), and `after` (the window after it returned, where any deferred
+//! cleanup would show up). Each is compared with the baseline p99.
+//!
+//! On disk (`--durability standard` or `always`) the database directory is
+//! also sized before the delete, right after it, and at the end of the
+//! window, to show when the space comes back.
+//!
+//! After the last level the log-log slope of delete time against branch size
+//! is reported: near 0 is O(1), near 1 is proportional to data.
+//!
+//! Run:    `cargo bench --bench branch_delete`
+//! Quick:  `cargo bench --bench branch_delete -- -q`
+//! CSV:    `cargo bench --bench branch_delete -- --csv`
+//! Custom: `cargo bench --bench branch_delete -- --levels 10000,1000000 --window 5 --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::scaling::ReservoirSampler;
+use harness::{create_db, dir_size_bytes, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_duration, fmt_num, fmt_ops};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_LEVELS: &[usize] = &[10_000, 100_000, 1_000_000, 10_000_000];
+const DEFAULT_WINDOW_SECS: u64 = 3;
+const VALUE_SIZE: usize = 64; // small values so 10M keys fit in memory
+const PARENT: &str = "default";
+
+/// Keys on the default branch that the foreground thread reads and writes.
+const FOREGROUND_KEYS: u64 = 10_000;
+/// One foreground operation in this many is a put; the rest are gets.
+const FOREGROUND_PUT_EVERY: u64 = 10;
+
+/// Foreground phases, in order. The phase an operation is counted in is the
+/// one in effect when it started.
+const PHASES: &[&str] = &["baseline", "during", "after"];
+const PHASE_BASELINE: u8 = 0;
+const PHASE_DURING: u8 = 1;
+const PHASE_AFTER: u8 = 2;
+
+// ---------------------------------------------------------------------------
+// Result types
+// ---------------------------------------------------------------------------
+
+/// Foreground latency over one phase.
+struct PhaseStats {
+    ops: u64,
+    ops_per_sec: f64,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    /// Exact maximum (not sampled).
+    max: Duration,
+}
+
+impl PhaseStats {
+    fn new(mut samples: Vec<Duration>, ops: u64, max: Duration, wall: Duration) -> Self {
+        samples.sort_unstable();
+        let len = samples.len().max(1);
+        let at = |pct: usize| samples.get((len * pct / 100).min(len - 1)).copied().unwrap_or_default();
+        PhaseStats {
+            ops,
+            ops_per_sec: ops as f64 / wall.as_secs_f64().max(1e-9),
+            p50: at(50),
+            p95: at(95),
+            p99: at(99),
+            max,
+        }
+    }
+}
+
+struct LevelResult {
+    branch_keys: usize,
+    delete: Duration,
+    /// One entry per phase in `PHASES`.
+    phases: Vec<PhaseStats>,
+    /// Directory size before the delete, right after it, and at the end of
+    /// the window. `None` in cache mode.
+    disk: Option<[u64; 3]>,
+    failures: Vec<String>,
+}
+
+impl LevelResult {
+    /// Phase p99 over the baseline p99.
+    fn inflation(&self, phase: usize) -> f64 {
+        let base = self.phases[PHASE_BASELINE as usize].p99.as_secs_f64();
+        self.phases[phase].p99.as_secs_f64() / base.max(1e-9)
+    }
+}
+
+/// Log-log slope of delete time against branch size between the smallest and
+/// largest level: ~0 means constant time, ~1 means proportional to size.
+fn scaling_exponent(results: &[LevelResult]) -> Option<f64> {
+    let (first, last) = (results.first()?, results.last()?);
+    if last.branch_keys <= first.branch_keys {
+        return None;
+    }
+    let t = last.delete.as_secs_f64() / first.delete.as_secs_f64().max(1e-9);
+    let n = last.branch_keys as f64 / first.branch_keys as f64;
+    Some(t.ln() / n.ln())
+}
+
+fn scaling_verdict(exponent: f64) -> &'static str {
+    if exponent < 0.2 {
+        "O(1)"
+    } else if exponent > 0.8 {
+        "proportional to branch size"
+    } else {
+        "sublinear"
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Fill and measurement
+// ---------------------------------------------------------------------------
+
+fn foreground_key(i: u64) -> String {
+    format!("fg:{:06}", i)
+}
+
+fn branch_key(i: usize) -> String {
+    format!("fill:{:012}", i)
+}
+
+fn branch_name(level: usize) -> String {
+    format!("doomed_{}", level)
+}
+
+/// Create the branch for `level` and fill it with `level` keys, then switch
+/// back to the default branch.
+fn fill_branch(db: &mut BenchDb, level: usize) {
+    let branch = branch_name(level);
+    db.db.create_branch(&branch).unwrap();
+    db.db.set_branch(&branch).unwrap();
+    let value = Value::Bytes(vec![0x42; VALUE_SIZE]);
+    for i in 0..level {
+        db.db.kv_put(&branch_key(i), value.clone()).unwrap();
+        if level >= 1_000_000 && (i + 1).is_multiple_of(1_000_000) {
+            eprintln!("  filled {}/{} keys...", fmt_num((i + 1) as u64), fmt_num(level as u64));
+        }
+    }
+    db.db.set_branch(PARENT).unwrap();
+}
+
+struct Foreground {
+    samplers: Vec<ReservoirSampler>,
+    ops: Vec<u64>,
+    max: Vec<Duration>,
+    misses: u64,
+}
+
+/// Delete the filled branch for `level` while a foreground thread works on
+/// the default branch.
+fn measure_level(db: &BenchDb, level: usize, window: Duration) -> LevelResult {
+    let branch = branch_name(level);
+    let stop = AtomicBool::new(false);
+    let phase = AtomicU8::new(PHASE_BASELINE);
+    let barrier = Barrier::new(2);
+    let value = Value::Bytes(vec![0x46; VALUE_SIZE]);
+    let size = || db.data_dir().map(dir_size_bytes);
+
+    let (delete, walls, disk, fg) = std::thread::scope(|scope| {
+        let handle = db.db.new_handle().expect("failed to create foreground handle");
+        let (stop, phase, barrier, value) = (&stop, &phase, &barrier, &value);
+        let foreground = scope.spawn(move || {
+            let mut fg = Foreground {
+                samplers: (0..PHASES.len())
+                    .map(|p| ReservoirSampler::with_seed(level as u64 ^ p as u64))
+                    .collect(),
+                ops: vec![0; PHASES.len()],
+                max: vec![Duration::ZERO; PHASES.len()],
+                misses: 0,
+            };
+            let mut rng = level as u64 ^ 0x12345678;
+            let mut n = 0u64;
+            barrier.wait();
+            while !stop.load(Ordering::Relaxed) {
+                rng = rng
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let key = foreground_key((rng >> 33) % FOREGROUND_KEYS);
+
+                let p = phase.load(Ordering::Relaxed) as usize;
+                let start = Instant::now();
+                if n.is_multiple_of(FOREGROUND_PUT_EVERY) {
+                    handle.kv_put(&key, value.clone()).unwrap();
+                } else if handle.kv_get(&key).unwrap().is_none() {
+                    fg.misses += 1;
+                }
+                let elapsed = start.elapsed();
+                n += 1;
+
+                fg.samplers[p].record(elapsed);
+                fg.ops[p] += 1;
+                fg.max[p] = fg.max[p].max(elapsed);
+            }
+            fg
+        });
+
+        barrier.wait();
+        std::thread::sleep(window);
+        let before = size();
+
+        phase.store(PHASE_DURING, Ordering::SeqCst);
+        let start = Instant::now();
+        db.db.delete_branch(&branch).unwrap();
+        let delete = start.elapsed();
+        phase.store(PHASE_AFTER, Ordering::SeqCst);
+        let after_delete = size();
+
+        std::thread::sleep(window);
+        stop.store(true, Ordering::Relaxed);
+        let fg = foreground.join().expect("foreground thread panicked");
+        let disk = match (before, after_delete, size()) {
+            (Some(a), Some(b), Some(c)) => Some([a, b, c]),
+            _ => None,
+        };
+        (delete, [window, delete, window], disk, fg)
+    });
+
+    let mut failures = Vec::new();
+    if fg.misses > 0 {
+        failures.push(format!("{} foreground gets missed a default-branch key", fg.misses));
+    }
+    if db.db.list_branches().unwrap().contains(&branch) {
+        failures.push(format!("{} still listed after delete_branch", branch));
+    }
+
+    let phases = fg
+        .samplers
+        .into_iter()
+        .zip(fg.ops)
+        .zip(fg.max)
+        .zip(walls)
+        .map(|(((s, ops), max), wall)| PhaseStats::new(s.into_samples(), ops, max, wall))
+        .collect();
+    LevelResult {
+        branch_keys: level,
+        delete,
+        phases,
+        disk,
+        failures,
+    }
+}
+
+/// Recreate the deleted branch and check it starts out empty.
+fn check_recreated_empty(db: &mut BenchDb, level: usize) -> Option<String> {
+    let branch = branch_name(level);
+    db.db.create_branch(&branch).unwrap();
+    db.db.set_branch(&branch).unwrap();
+    let leftover = db.db.kv_get(&branch_key(level / 2)).unwrap().is_some();
+    db.db.set_branch(PARENT).unwrap();
+    db.db.delete_branch(&branch).unwrap();
+    leftover.then(|| format!("recreated {} still holds deleted data", branch))
+}
+
+// ---------------------------------------------------------------------------
+// Recording helpers
+// ---------------------------------------------------------------------------
+
+fn record_level(
+    recorder: &mut ResultRecorder,
+    r: &LevelResult,
+    mode: &DurabilityConfig,
+    exponent: Option<f64>,
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("branch_keys".into(), serde_json::json!(r.branch_keys));
+    params.insert("value_bytes".into(), serde_json::json!(VALUE_SIZE));
+    params.insert("during_p99_inflation".into(), serde_json::json!(r.inflation(PHASE_DURING as usize)));
+    params.insert("after_p99_inflation".into(), serde_json::json!(r.inflation(PHASE_AFTER as usize)));
+    if let Some([before, after_delete, after_window]) = r.disk {
+        params.insert("disk_bytes_before".into(), serde_json::json!(before));
+        params.insert("disk_bytes_after_delete".into(), serde_json::json!(after_delete));
+        params.insert("disk_bytes_after_window".into(), serde_json::json!(after_window));
+    }
+    if let Some(e) = exponent {
+        params.insert("scaling_exponent".into(), serde_json::json!(e));
+        params.insert("scaling".into(), serde_json::json!(scaling_verdict(e)));
+    }
+
+    let ns = r.delete.as_nanos() as u64;
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/branch_delete/{}keys", r.branch_keys),
+        category: "fill-level".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            p50_ns: Some(ns),
+            min_ns: Some(ns),
+            max_ns: Some(ns),
+            samples: Some(1),
+            fill_level: Some(r.branch_keys),
+            disk_bytes: r.disk.map(|d| d[2]),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(&r.failures),
+    });
+}
+
+fn record_phase(
+    recorder: &mut ResultRecorder,
+    r: &LevelResult,
+    phase: usize,
+    mode: &DurabilityConfig,
+    window: Duration,
+) {
+    let s = &r.phases[phase];
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("branch_keys".into(), serde_json::json!(r.branch_keys));
+    params.insert("phase".into(), serde_json::json!(PHASES[phase]));
+    params.insert("foreground_keys".into(), serde_json::json!(FOREGROUND_KEYS));
+    params.insert("put_every".into(), serde_json::json!(FOREGROUND_PUT_EVERY));
+    params.insert("window_secs".into(), serde_json::json!(window.as_secs_f64()));
+    params.insert("p99_inflation".into(), serde_json::json!(r.inflation(phase)));
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/branch_delete_foreground/{}/{}keys", PHASES[phase], r.branch_keys),
+        category: "fill-level".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(s.ops_per_sec),
+            p50_ns: Some(s.p50.as_nanos() as u64),
+            p95_ns: Some(s.p95.as_nanos() as u64),
+            p99_ns: Some(s.p99.as_nanos() as u64),
+            max_ns: Some(s.max.as_nanos() as u64),
+            samples: Some(s.ops),
+            fill_level: Some(r.branch_keys),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn duration_ms(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000_000.0
+}
+
+fn fmt_mb(bytes: u64) -> String {
+    format!("{:.1}MB", bytes as f64 / (1u64 << 20) as f64)
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>11}  {:>11}  {:>12}  {:>10}  {:>10}  {:>10}  {:>10}  {:>11}",
+        "branch_keys", "delete", "fg ops/s", "base p99", "during p99", "after p99", "after max", "after ×base"
+    );
+}
+
+fn print_table_row(r: &LevelResult) {
+    let [base, during, after] = [&r.phases[0], &r.phases[1], &r.phases[2]];
+    eprintln!(
+        "  {:>11}  {:>9.3}ms  {:>12}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10.2}x",
+        fmt_num(r.branch_keys as u64),
+        duration_ms(r.delete),
+        fmt_ops(base.ops_per_sec),
+        fmt_duration(base.p99),
+        if during.ops > 0 { fmt_duration(during.p99) } else { "-".to_string() },
+        fmt_duration(after.p99),
+        fmt_duration(after.max),
+        r.inflation(PHASE_AFTER as usize),
+    );
+    if let Some([before, after_delete, after_window]) = r.disk {
+        eprintln!(
+            "  {:>11}  disk {} -> {} after delete -> {} after window",
+            "",
+            fmt_mb(before),
+            fmt_mb(after_delete),
+            fmt_mb(after_window)
+        );
+    }
+}
+
+fn print_quiet(r: &LevelResult) {
+    eprintln!(
+        "branch_delete @ {} keys: {:.3}ms, foreground p99 {:.2}x after",
+        fmt_num(r.branch_keys as u64),
+        duration_ms(r.delete),
+        r.inflation(PHASE_AFTER as usize),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"branch_keys\",\"delete_ms\",\"baseline_p99_us\",\"during_ops\",\"during_p99_us\",\"after_p99_us\",\"after_max_us\",\"after_p99_inflation\",\"disk_before\",\"disk_after_window\"");
+}
+
+fn print_csv_row(r: &LevelResult) {
+    let us = |d: Duration| d.as_nanos() as f64 / 1_000.0;
+    let [base, during, after] = [&r.phases[0], &r.phases[1], &r.phases[2]];
+    let (before, end) = r.disk.map_or((String::new(), String::new()), |d| (d[0].to_string(), d[2].to_string()));
+    println!(
+        "{},{:.3},{:.3},{},{:.3},{:.3},{:.3},{:.3},{},{}",
+        r.branch_keys,
+        duration_ms(r.delete),
+        us(base.p99),
+        during.ops,
+        us(during.p99),
+        us(after.p99),
+        us(after.max),
+        r.inflation(PHASE_AFTER as usize),
+        before,
+        end,
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: DurabilityConfig,
+    levels: Vec<usize>,
+    window: Duration,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DurabilityConfig::Cache,
+        levels: DEFAULT_LEVELS.to_vec(),
+        window: Duration::from_secs(DEFAULT_WINDOW_SECS),
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--levels" => config.levels = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            "--window" => {
+                let secs: f64 = args.parse_checked("seconds above 0", |s: &f64| *s > 0.0);
+                config.window = Duration::from_secs_f64(secs.max(0.1));
+            }
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config.levels.sort_unstable();
+    config.levels.dedup();
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Branch Delete Benchmark ===");
+        eprintln!("Measures delete_branch cost and its effect on foreground work as the branch grows.");
+        eprintln!();
+        eprintln!(
+            "Parameters: {:.1}s windows, {} bytes values, {} mode",
+            config.window.as_secs_f64(),
+            VALUE_SIZE,
+            config.durability.label()
+        );
+        eprintln!("Branch sizes: {:?}", config.levels);
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+    let mut db = create_db(config.durability);
+    let value = Value::Bytes(vec![0x42; VALUE_SIZE]);
+    for i in 0..FOREGROUND_KEYS {
+        db.db.kv_put(&foreground_key(i), value.clone()).unwrap();
+    }
+
+    let mut results = Vec::new();
+    for &level in &config.levels {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        guard_params.insert("branch_keys".into(), serde_json::json!(level));
+        let guard_name = format!("fill-level/branch_delete/{}keys", level);
+        let measured = recorder.guard(&guard_name, guard_params, |_| {
+            if !config.csv && !config.quiet {
+                eprint!("  filling branch to {} keys...", fmt_num(level as u64));
+            }
+            fill_branch(&mut db, level);
+            if !config.csv && !config.quiet {
+                eprintln!(" done");
+            }
+
+            let mut r = measure_level(&db, level, config.window);
+            r.failures.extend(check_recreated_empty(&mut db, level));
+            for failure in &r.failures {
+                eprintln!("  Validation: FAIL ({})", failure);
+            }
+            r
+        });
+        results.extend(measured);
+    }
+
+    let exponent = scaling_exponent(&results);
+    for r in &results {
+        record_level(&mut recorder, r, &config.durability, exponent);
+        for phase in 0..PHASES.len() {
+            record_phase(&mut recorder, r, phase, &config.durability, config.window);
+        }
+    }
+
+    if config.csv {
+        for r in &results {
+            print_csv_row(r);
+        }
+    } else if config.quiet {
+        for r in &results {
+            print_quiet(r);
+        }
+    } else {
+        eprintln!();
+        print_table_header();
+        for r in &results {
+            print_table_row(r);
+        }
+        if let Some(e) = exponent {
+            eprintln!("  scaling exponent {:.2}: {}", e, scaling_verdict(e));
+        }
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,414 @@
+//! Branch Fork Benchmark for StrataDB
+//!
+//! Measures what branching costs as the parent branch grows. At each parent
+//! size (1K to 10M keys) it times:
+//!
+//! - `fork_branch`: new branch with a copy of the parent's data
+//! - `create_branch`: new empty branch (control; should not depend on size)
+//! - the first write on the fork, and the first read of a key it inherited
+//! - steady-state writes on the fork after the first one
+//!
+//! A copy-on-write fork stays flat as the parent grows; a copying fork grows
+//! with it. After the last level the log-log slope of fork time against
+//! parent size is reported: near 0 is O(1), near 1 is proportional to data.
+//!
+//! The parent is filled incrementally, one database for all levels, and each
+//! fork is deleted after it is measured.
+//!
+//! Run:    `cargo bench --bench branch_fork`
+//! Quick:  `cargo bench --bench branch_fork -- -q`
+//! CSV:    `cargo bench --bench branch_fork -- --csv`
+//! Custom: `cargo bench --bench branch_fork -- --levels 1000,100000 --forks 10`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_LEVELS: &[usize] = &[1_000, 10_000, 100_000, 1_000_000, 10_000_000];
+const DEFAULT_FORKS: usize = 5;
+/// Writes on each fork after the first, for the steady-state comparison.
+const WARM_WRITES: usize = 100;
+const VALUE_SIZE: usize = 64; // small values so 10M keys fit in memory
+const PARENT: &str = "default";
+
+/// Operations measured at each level, in output order.
+const OPS: &[&str] = &[
+    "branch_fork",
+    "branch_create",
+    "fork_first_write",
+    "fork_first_read",
+    "fork_warm_write",
+];
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct OpResult {
+    name: &'static str,
+    parent_keys: usize,
+    samples: usize,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl OpResult {
+    fn from_samples(name: &'static str, parent_keys: usize, mut latencies: Vec<Duration>) -> Self {
+        latencies.sort_unstable();
+        let len = latencies.len();
+        let sum: Duration = latencies.iter().sum();
+        OpResult {
+            name,
+            parent_keys,
+            samples: len,
+            avg: sum / len as u32,
+            p50: latencies[len * 50 / 100],
+            p95: latencies[(len * 95 / 100).min(len - 1)],
+            p99: latencies[(len * 99 / 100).min(len - 1)],
+            min: latencies[0],
+            max: latencies[len - 1],
+        }
+    }
+}
+
+/// Log-log slope of p50 against parent size between the smallest and largest
+/// level: ~0 means constant time, ~1 means proportional to parent size.
+fn scaling_exponent(results: &[OpResult]) -> Option<f64> {
+    let (first, last) = (results.first()?, results.last()?);
+    if last.parent_keys <= first.parent_keys {
+        return None;
+    }
+    let t = last.p50.as_secs_f64() / first.p50.as_secs_f64().max(1e-9);
+    let n = last.parent_keys as f64 / first.parent_keys as f64;
+    Some(t.ln() / n.ln())
+}
+
+fn scaling_verdict(exponent: f64) -> &'static str {
+    if exponent < 0.2 {
+        "O(1)"
+    } else if exponent > 0.8 {
+        "proportional to parent size"
+    } else {
+        "sublinear"
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Fill and measurement
+// ---------------------------------------------------------------------------
+
+fn parent_key(i: usize) -> String {
+    format!("fill:{:012}", i)
+}
+
+/// Grow the parent branch from `from` to `to` keys.
+fn fill_parent(db: &BenchDb, from: usize, to: usize) {
+    let value = Value::Bytes(vec![0x42; VALUE_SIZE]);
+    for i in from..to {
+        db.db.kv_put(&parent_key(i), value.clone()).unwrap();
+        if to >= 1_000_000 && (i + 1).is_multiple_of(1_000_000) {
+            eprintln!("  filled {}/{} keys...", fmt_num((i + 1) as u64), fmt_num(to as u64));
+        }
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let out = f();
+    (start.elapsed(), out)
+}
+
+/// Fork, create, and write/read on the fork `forks` times at the current
+/// parent size. Returns one result per entry in `OPS`, plus the number of
+/// forks that did not see an inherited key.
+fn measure_level(db: &mut BenchDb, level: usize, forks: usize) -> (Vec<OpResult>, usize) {
+    let value = Value::Bytes(vec![0x46; VALUE_SIZE]);
+    let mut samples: Vec<Vec<Duration>> = vec![Vec::with_capacity(forks); OPS.len()];
+    let mut missing = 0;
+
+    for r in 0..forks {
+        let fork = format!("fork_{}_{}", level, r);
+        let empty = format!("empty_{}_{}", level, r);
+
+        let (t, _) = timed(|| db.db.fork_branch(&fork).unwrap());
+        samples[0].push(t);
+        let (t, _) = timed(|| db.db.create_branch(&empty).unwrap());
+        samples[1].push(t);
+
+        db.db.set_branch(&fork).unwrap();
+        let (t, _) = timed(|| db.db.kv_put("fork:first", value.clone()).unwrap());
+        samples[2].push(t);
+        // A key from the middle of the parent's range, read for the first time on the fork
+        let (t, inherited) = timed(|| db.db.kv_get(&parent_key((level / 2 + r) % level)).unwrap());
+        samples[3].push(t);
+        if inherited.is_none() {
+            missing += 1;
+        }
+        for w in 0..WARM_WRITES {
+            let (t, _) = timed(|| db.db.kv_put(&format!("fork:{}", w), value.clone()).unwrap());
+            samples[4].push(t);
+        }
+
+        db.db.set_branch(PARENT).unwrap();
+        db.db.delete_branch(&fork).unwrap();
+        db.db.delete_branch(&empty).unwrap();
+    }
+
+    let results = OPS
+        .iter()
+        .zip(samples)
+        .map(|(&name, s)| OpResult::from_samples(name, level, s))
+        .collect();
+    (results, missing)
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_op_result(
+    recorder: &mut ResultRecorder,
+    r: &OpResult,
+    mode: &DurabilityConfig,
+    exponent: Option<f64>,
+    failures: &[String],
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("parent_keys".into(), serde_json::json!(r.parent_keys));
+    params.insert("value_bytes".into(), serde_json::json!(VALUE_SIZE));
+    if let Some(e) = exponent {
+        params.insert("scaling_exponent".into(), serde_json::json!(e));
+        params.insert("scaling".into(), serde_json::json!(scaling_verdict(e)));
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/{}/{}keys", r.name, r.parent_keys),
+        category: "fill-level".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg.as_nanos() as u64),
+            samples: Some(r.samples as u64),
+            fill_level: Some(r.parent_keys),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(failures),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn duration_ms(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000_000.0
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>12}  {:>9}  {:>9}  {:>9}  {:>9}",
+        "parent_keys", "avg", "p50", "p99", "max"
+    );
+}
+
+fn print_table_row(r: &OpResult) {
+    eprintln!(
+        "  {:>12}  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms",
+        fmt_num(r.parent_keys as u64),
+        duration_ms(r.avg),
+        duration_ms(r.p50),
+        duration_ms(r.p99),
+        duration_ms(r.max),
+    );
+}
+
+fn print_quiet(r: &OpResult) {
+    eprintln!(
+        "{} @ {} keys: p50={:.3}ms",
+        r.name,
+        fmt_num(r.parent_keys as u64),
+        duration_ms(r.p50),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"test\",\"parent_keys\",\"avg_ms\",\"p50_ms\",\"p95_ms\",\"p99_ms\",\"max_ms\"");
+}
+
+fn print_csv_row(r: &OpResult) {
+    println!(
+        "\"{}\",{},{:.3},{:.3},{:.3},{:.3},{:.3}",
+        r.name,
+        r.parent_keys,
+        duration_ms(r.avg),
+        duration_ms(r.p50),
+        duration_ms(r.p95),
+        duration_ms(r.p99),
+        duration_ms(r.max),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: DurabilityConfig,
+    levels: Vec<usize>,
+    forks: usize,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DurabilityConfig::Cache,
+        levels: DEFAULT_LEVELS.to_vec(),
+        forks: DEFAULT_FORKS,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--levels" => config.levels = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            "--forks" => config.forks = args.parse_value::<usize>().max(1),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    // Levels are filled incrementally
+    config.levels.sort_unstable();
+    config.levels.dedup();
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Branch Fork Benchmark ===");
+        eprintln!("Measures fork/create cost and first access on the fork as the parent grows.");
+        eprintln!();
+        eprintln!(
+            "Parameters: {} forks per level, {} bytes values, {} mode",
+            config.forks,
+            VALUE_SIZE,
+            config.durability.label()
+        );
+        eprintln!("Parent sizes: {:?}", config.levels);
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+    let mut db = create_db(config.durability);
+    let mut filled = 0;
+    let mut by_op: Vec<Vec<OpResult>> = OPS.iter().map(|_| Vec::new()).collect();
+    let mut failures: Vec<Vec<String>> = Vec::new();
+
+    for &level in &config.levels {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        guard_params.insert("parent_keys".into(), serde_json::json!(level));
+        let guard_name = format!("fill-level/branch_fork/{}keys", level);
+        let measured = recorder.guard(&guard_name, guard_params, |_| {
+            if !config.csv && !config.quiet {
+                eprint!("  filling parent to {} keys...", fmt_num(level as u64));
+            }
+            fill_parent(&db, filled, level);
+            filled = level;
+            if !config.csv && !config.quiet {
+                eprintln!(" done");
+            }
+
+            measure_level(&mut db, level, config.forks)
+        });
+        // Leave a panicked level out of `failures` too; they're zipped below
+        let Some((results, missing)) = measured else {
+            continue;
+        };
+        let mut level_failures = Vec::new();
+        if missing > 0 {
+            level_failures.push(format!(
+                "{} of {} forks did not see an inherited parent key",
+                missing, config.forks
+            ));
+            eprintln!("  Validation: FAIL ({})", level_failures[0]);
+        }
+        failures.push(level_failures);
+        for (op, r) in by_op.iter_mut().zip(results) {
+            op.push(r);
+        }
+    }
+
+    for results in &by_op {
+        let exponent = scaling_exponent(results);
+        for (r, level_failures) in results.iter().zip(&failures) {
+            // Inherited-key misses only invalidate the fork rows
+            let fails: &[String] = if r.name == "branch_create" { &[] } else { level_failures };
+            record_op_result(&mut recorder, r, &config.durability, exponent, fails);
+        }
+
+        if config.csv {
+            for r in results {
+                print_csv_row(r);
+            }
+        } else if config.quiet {
+            for r in results {
+                print_quiet(r);
+            }
+        } else if let Some(first) = results.first() {
+            eprintln!();
+            eprintln!("--- {} ---", first.name);
+            print_table_header();
+            for r in results {
+                print_table_row(r);
+            }
+            if let Some(e) = exponent {
+                eprintln!("  scaling exponent {:.2}: {}", e, scaling_verdict(e));
+            }
+        }
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
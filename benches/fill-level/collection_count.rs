@@ -0,0 +1,468 @@
+//! Collection-Count Benchmark for StrataDB
+//!
+//! Measures how vector operations behave as the number of collections grows.
+//! Multi-tenant deployments create a collection per user, so a database ends
+//! up with thousands of small collections rather than one large one. Tests run
+//! at 1, 100, and 10K collections holding the same number of vectors each.
+//!
+//! Each operation picks a collection at random, so per-collection caches don't
+//! hide lookup costs. `vector_list_collections` touches every collection and
+//! is also reported per collection; that figure should stay flat as the count
+//! grows.
+//!
+//! Run:    `cargo bench --bench collection_count`
+//! Quick:  `cargo bench --bench collection_count -- -q`
+//! CSV:    `cargo bench --bench collection_count -- --csv`
+//! Custom: `cargo bench --bench collection_count -- --collections 1,1000,50000 --vectors 20`
+//! Single: `cargo bench --bench collection_count -- -t vector_search`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, vector_128d, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::DistanceMetric;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_OPS: usize = 1_000;
+const DEFAULT_COUNTS: &[usize] = &[1, 100, 10_000];
+const DEFAULT_VECTORS: usize = 50; // per collection: "modest data" for a single tenant
+const DIM: u64 = 128;
+const SEARCH_K: u64 = 10;
+/// list_collections walks every collection, so it gets fewer samples.
+const LIST_SAMPLES: usize = 100;
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct CountResult {
+    name: String,
+    collections: usize,
+    total_ops: usize,
+    ops_per_sec: f64,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+// ---------------------------------------------------------------------------
+// Core measurement (same pattern as fill_level.rs)
+// ---------------------------------------------------------------------------
+
+fn run_bench(
+    name: &str,
+    collections: usize,
+    total_ops: usize,
+    mut bench_fn: impl FnMut(),
+) -> CountResult {
+    let mut latencies = Vec::with_capacity(total_ops);
+    let wall_start = Instant::now();
+
+    for _ in 0..total_ops {
+        let op_start = Instant::now();
+        bench_fn();
+        latencies.push(op_start.elapsed());
+    }
+
+    let elapsed = wall_start.elapsed();
+    latencies.sort_unstable();
+    let len = latencies.len();
+    let sum: Duration = latencies.iter().sum();
+
+    CountResult {
+        name: name.to_string(),
+        collections,
+        total_ops: len,
+        ops_per_sec: len as f64 / elapsed.as_secs_f64(),
+        avg: sum / len as u32,
+        p50: latencies[len * 50 / 100],
+        p95: latencies[(len * 95 / 100).min(len - 1)],
+        p99: latencies[(len * 99 / 100).min(len - 1)],
+        min: latencies[0],
+        max: latencies[len - 1],
+    }
+}
+
+/// Simple LCG for collection selection; high bits only.
+struct CollectionPicker {
+    state: u64,
+    count: usize,
+}
+
+impl CollectionPicker {
+    fn new(count: usize) -> Self {
+        Self {
+            state: 0xdeadbeef,
+            count,
+        }
+    }
+
+    fn next(&mut self) -> String {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        collection_name(((self.state >> 33) % self.count as u64) as usize)
+    }
+}
+
+fn collection_name(i: usize) -> String {
+    format!("tenant_{:06}", i)
+}
+
+// ---------------------------------------------------------------------------
+// Population
+// ---------------------------------------------------------------------------
+
+/// Create `count` collections with `vectors` vectors each; returns the time
+/// spent creating collections (upserts excluded).
+fn populate(db: &BenchDb, count: usize, vectors: usize) -> Duration {
+    let mut create_time = Duration::ZERO;
+    for c in 0..count {
+        let name = collection_name(c);
+        let start = Instant::now();
+        db.db
+            .vector_create_collection(&name, DIM, DistanceMetric::Cosine)
+            .unwrap();
+        create_time += start.elapsed();
+
+        for v in 0..vectors {
+            let i = (c * vectors + v) as u64;
+            db.db
+                .vector_upsert(&name, &format!("vec_{}", v), vector_128d(i), None)
+                .unwrap();
+        }
+        if count >= 1_000 && (c + 1).is_multiple_of(1_000) {
+            eprintln!("  created {}/{} collections...", c + 1, count);
+        }
+    }
+    create_time
+}
+
+// ---------------------------------------------------------------------------
+// Benchmark functions
+// ---------------------------------------------------------------------------
+
+fn bench_upsert(db: &BenchDb, n: usize, count: usize, vectors: usize) -> CountResult {
+    let mut picker = CollectionPicker::new(count);
+    let mut i = 0u64;
+    run_bench("vector_upsert", count, n, || {
+        let name = picker.next();
+        db.db
+            .vector_upsert(&name, &format!("new_{}", i), vector_128d((count * vectors) as u64 + i), None)
+            .unwrap();
+        i += 1;
+    })
+}
+
+fn bench_search(db: &BenchDb, n: usize, count: usize) -> CountResult {
+    let mut picker = CollectionPicker::new(count);
+    let mut i = 0u64;
+    run_bench("vector_search", count, n, || {
+        let name = picker.next();
+        let _ = db
+            .db
+            .vector_search(&name, vector_128d(u64::MAX / 2 + i), SEARCH_K)
+            .unwrap();
+        i += 1;
+    })
+}
+
+fn bench_list(db: &BenchDb, count: usize) -> CountResult {
+    run_bench("vector_list_collections", count, LIST_SAMPLES, || {
+        let listed = db.db.vector_list_collections().unwrap();
+        assert_eq!(listed.len(), count);
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_count_result(
+    recorder: &mut ResultRecorder,
+    r: &CountResult,
+    config: &Config,
+    create_time: Duration,
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    params.insert("collections".into(), serde_json::json!(r.collections));
+    params.insert("vectors_per_collection".into(), serde_json::json!(config.vectors));
+    params.insert("dimension".into(), serde_json::json!(DIM));
+    params.insert(
+        "create_collection_avg_us".into(),
+        serde_json::json!(create_time.as_secs_f64() * 1e6 / r.collections as f64),
+    );
+    if r.name == "vector_list_collections" {
+        params.insert(
+            "p50_ns_per_collection".into(),
+            serde_json::json!(r.p50.as_nanos() as f64 / r.collections as f64),
+        );
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/{}/{}collections", r.name, r.collections),
+        category: "fill-level".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec),
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg.as_nanos() as u64),
+            samples: Some(r.total_ops as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn duration_ms(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000_000.0
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>11}  {:>11}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}",
+        "collections", "ops/sec", "avg", "p50", "p95", "p99", "max"
+    );
+}
+
+fn print_table_row(r: &CountResult) {
+    eprintln!(
+        "  {:>11}  {:>11}  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms",
+        fmt_num(r.collections as u64),
+        fmt_num(r.ops_per_sec as u64),
+        duration_ms(r.avg),
+        duration_ms(r.p50),
+        duration_ms(r.p95),
+        duration_ms(r.p99),
+        duration_ms(r.max),
+    );
+}
+
+fn print_quiet(r: &CountResult) {
+    eprintln!(
+        "{} @ {} collections: {} ops/sec, p50={:.3}ms",
+        r.name,
+        fmt_num(r.collections as u64),
+        fmt_num(r.ops_per_sec as u64),
+        duration_ms(r.p50),
+    );
+}
+
+fn print_csv_header() {
+    println!(
+        "\"test\",\"collections\",\"ops_sec\",\"avg_ms\",\"p50_ms\",\"p95_ms\",\"p99_ms\",\"max_ms\""
+    );
+}
+
+fn print_csv_row(r: &CountResult) {
+    println!(
+        "\"{}\",{},{:.2},{:.3},{:.3},{:.3},{:.3},{:.3}",
+        r.name,
+        r.collections,
+        r.ops_per_sec,
+        duration_ms(r.avg),
+        duration_ms(r.p50),
+        duration_ms(r.p95),
+        duration_ms(r.p99),
+        duration_ms(r.max),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    ops: usize,
+    vectors: usize,
+    durability: DurabilityConfig,
+    tests: Option<Vec<String>>,
+    counts: Vec<usize>,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        ops: DEFAULT_OPS,
+        vectors: DEFAULT_VECTORS,
+        durability: DurabilityConfig::Cache,
+        tests: None,
+        counts: DEFAULT_COUNTS.to_vec(),
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "-n" => config.ops = args.parse_value(),
+            "--vectors" => config.vectors = args.parse_value(),
+            "-t" => {
+                let v = args.value();
+                let names: Vec<String> = v
+                    .split(',')
+                    .map(|s| s.trim().to_lowercase())
+                    .collect();
+                config.tests = Some(names);
+            }
+            "--collections" => config.counts = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+fn test_is_selected(name: &str, filter: &Option<Vec<String>>) -> bool {
+    match filter {
+        None => true,
+        Some(names) => names
+            .iter()
+            .any(|f| name.to_lowercase().starts_with(&f.to_lowercase())),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Test names
+// ---------------------------------------------------------------------------
+
+const ALL_TESTS: &[&str] = &["vector_upsert", "vector_search", "vector_list_collections"];
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Collection-Count Benchmark ===");
+        eprintln!("Measures vector operation latency as the number of collections grows.");
+        eprintln!();
+        eprintln!(
+            "Parameters: {} ops per measurement, {} vectors/collection, {}d, {} mode",
+            config.ops,
+            config.vectors,
+            DIM,
+            config.durability.label()
+        );
+        eprintln!("Collection counts: {:?}", config.counts);
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let tests: Vec<&str> = ALL_TESTS
+        .iter()
+        .copied()
+        .filter(|t| test_is_selected(t, &config.tests))
+        .collect();
+    let mut results: HashMap<&str, Vec<CountResult>> = HashMap::new();
+    let mut recorder = ResultRecorder::new("fill-level");
+
+    // One database per collection count, shared by the tests: population
+    // dominates setup time, and upserts add only a few vectors per collection.
+    for &count in &config.counts {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        guard_params.insert("collections".into(), serde_json::json!(count));
+        let guard_name = format!("fill-level/collection_count/{}collections", count);
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            if !config.csv && !config.quiet {
+                eprint!("  creating {} collections...", fmt_num(count as u64));
+            }
+            let db = create_db(config.durability);
+            let create_time = populate(&db, count, config.vectors);
+            if !config.csv && !config.quiet {
+                eprintln!(
+                    " done (create_collection avg {:.1}us, total {})",
+                    create_time.as_secs_f64() * 1e6 / count as f64,
+                    fmt_ms(create_time)
+                );
+            }
+
+            // Search and list first, so they see exactly `vectors` per collection
+            for &test_name in ["vector_search", "vector_list_collections", "vector_upsert"].iter() {
+                if !tests.contains(&test_name) {
+                    continue;
+                }
+                let result = match test_name {
+                    "vector_upsert" => bench_upsert(&db, config.ops, count, config.vectors),
+                    "vector_search" => bench_search(&db, config.ops, count),
+                    "vector_list_collections" => bench_list(&db, count),
+                    _ => unreachable!(),
+                };
+                record_count_result(recorder, &result, &config, create_time);
+                results.entry(test_name).or_default().push(result);
+            }
+        });
+    }
+
+    for test_name in &tests {
+        let Some(rs) = results.get(test_name) else {
+            continue;
+        };
+        if config.csv {
+            for r in rs {
+                print_csv_row(r);
+            }
+        } else if config.quiet {
+            for r in rs {
+                print_quiet(r);
+            }
+        } else {
+            eprintln!();
+            eprintln!("--- {} ---", test_name);
+            print_table_header();
+            for r in rs {
+                print_table_row(r);
+            }
+            if *test_name == "vector_list_collections" {
+                for r in rs {
+                    eprintln!(
+                        "  {:>11}  p50/collection={:.1}ns",
+                        fmt_num(r.collections as u64),
+                        r.p50.as_nanos() as f64 / r.collections as f64
+                    );
+                }
+            }
+            eprintln!();
+        }
+    }
+
+    if !config.csv {
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
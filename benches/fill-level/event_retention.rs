@@ -0,0 +1,719 @@
+//! Event Retention Benchmark for StrataDB
+//!
+//! Strata has no event truncation or retention policy: an event stream only
+//! grows. This measures what that costs readers. One stream is appended to
+//! 10M events (10K, 100K, 1M, 10M by default), spread over a few bulk types,
+//! and at each length it times:
+//!
+//! - `event_append`: one more bulk event
+//! - `event_get_oldest` / `event_get_newest`: by sequence number, within the
+//!   first and last 1,000 events
+//! - `read_by_type_old`: `event_get_by_type` of a 100-event type appended
+//!   before everything else
+//! - `read_by_type_new`: the same for a 100-event type appended just now
+//! - `read_by_type_bulk`: one bulk type, which holds a fixed share of the
+//!   stream and so grows with it
+//!
+//! The two small types never grow, so their reads should not care how long
+//! the stream is. After the last length the log-log slope of p50 against
+//! stream length is reported for every operation. A small-type read whose
+//! slope is well above 0 pays for events it never returns, which is the case
+//! for retention: capping the stream would cap that read.
+//!
+//! Run:    `cargo bench --bench event_retention`
+//! Quick:  `cargo bench --bench event_retention -- -q`
+//! CSV:    `cargo bench --bench event_retention -- --csv`
+//! Custom: `cargo bench --bench event_retention -- --levels 10000,1000000 --samples 200`
+//! Soak:   `cargo bench --bench event_retention -- --duration 2h`
+//!
+//! With `--duration`, the levels are replaced by one stream that keeps
+//! growing until the duration runs out: rounds of 100 appends, each followed
+//! by one `event_get_oldest`, `event_get_newest` and `read_by_type_old`.
+//! Per-minute throughput, percentiles, stream length, RSS and on-disk size
+//! are streamed to `results/soak-<bench>-<time>.jsonl` (see
+//! `harness/soak.rs`). `--soak-interval` and `--soak-out` work as in `ycsb`.
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::soak::{self, Gauges, Soak, Window};
+use harness::{create_db, dir_size_bytes, print_hardware_info, read_rss_bytes, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_LEVELS: &[usize] = &[10_000, 100_000, 1_000_000, 10_000_000];
+const DEFAULT_SAMPLES: usize = 1_000;
+/// Bulk event types; `read_by_type_bulk` returns 1 / BULK_TYPES of the stream.
+const BULK_TYPES: u64 = 10;
+/// Events in each small probe type.
+const PROBE_EVENTS: usize = 100;
+/// Sequence numbers at either end of the stream that `event_get_*` draws from.
+const SEQ_WINDOW: u64 = 1_000;
+/// Events returned per bulk-read sample budget: a 10M stream gets 10 samples.
+const BULK_EVENT_BUDGET: usize = 10_000_000;
+const MIN_BULK_SAMPLES: usize = 5;
+/// Small-type reads whose scaling exponent exceeds this degrade with the stream.
+const DEGRADED_EXPONENT: f64 = 0.2;
+
+const OLD_PROBE_TYPE: &str = "probe:old";
+
+/// Operations measured at each stream length, in output order.
+const OPS: &[&str] = &[
+    "event_append",
+    "event_get_oldest",
+    "event_get_newest",
+    "read_by_type_old",
+    "read_by_type_new",
+    "read_by_type_bulk",
+];
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct OpResult {
+    name: &'static str,
+    events: usize,
+    samples: usize,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl OpResult {
+    fn from_samples(name: &'static str, events: usize, mut latencies: Vec<Duration>) -> Self {
+        latencies.sort_unstable();
+        let len = latencies.len();
+        let sum: Duration = latencies.iter().sum();
+        OpResult {
+            name,
+            events,
+            samples: len,
+            avg: sum / len as u32,
+            p50: latencies[len * 50 / 100],
+            p95: latencies[(len * 95 / 100).min(len - 1)],
+            p99: latencies[(len * 99 / 100).min(len - 1)],
+            min: latencies[0],
+            max: latencies[len - 1],
+        }
+    }
+
+    fn ops_per_sec(&self) -> f64 {
+        1.0 / self.avg.as_secs_f64().max(1e-12)
+    }
+}
+
+/// Log-log slope of p50 against stream length between the shortest and
+/// longest stream: ~0 means independent of length, ~1 means proportional.
+fn scaling_exponent(results: &[OpResult]) -> Option<f64> {
+    let (first, last) = (results.first()?, results.last()?);
+    if last.events <= first.events {
+        return None;
+    }
+    let t = last.p50.as_secs_f64() / first.p50.as_secs_f64().max(1e-9);
+    let n = last.events as f64 / first.events as f64;
+    Some(t.ln() / n.ln())
+}
+
+fn scaling_verdict(exponent: f64) -> &'static str {
+    if exponent < 0.2 {
+        "O(1)"
+    } else if exponent > 0.8 {
+        "proportional to stream length"
+    } else {
+        "sublinear"
+    }
+}
+
+/// True for reads that return a fixed number of events.
+fn is_small_type_read(op: &str) -> bool {
+    op == "read_by_type_old" || op == "read_by_type_new"
+}
+
+// ---------------------------------------------------------------------------
+// Stream and measurement
+// ---------------------------------------------------------------------------
+
+fn payload(i: u64) -> Value {
+    let mut map = HashMap::new();
+    map.insert("i".to_string(), Value::Int(i as i64));
+    Value::Object(map)
+}
+
+fn bulk_type(i: u64) -> String {
+    format!("bulk:{}", i % BULK_TYPES)
+}
+
+/// The event stream under test. Sequence numbers start at 1 and every
+/// append goes through here, so the count doubles as the newest sequence.
+struct Stream<'a> {
+    db: &'a BenchDb,
+    appended: u64,
+    /// Bulk events so far; bulk event `b` has type `bulk:{b % BULK_TYPES}`.
+    bulk: u64,
+}
+
+impl Stream<'_> {
+    fn append(&mut self, event_type: &str) {
+        self.db.db.event_append(event_type, payload(self.appended)).unwrap();
+        self.appended += 1;
+    }
+
+    fn append_bulk(&mut self) {
+        let event_type = bulk_type(self.bulk);
+        self.append(&event_type);
+        self.bulk += 1;
+    }
+
+    /// Events of type `bulk:0` in the stream.
+    fn bulk_zero_count(&self) -> usize {
+        self.bulk.div_ceil(BULK_TYPES) as usize
+    }
+
+    /// Append bulk events until the stream holds at least `events`.
+    fn grow_to(&mut self, events: usize) {
+        while (self.appended as usize) < events {
+            self.append_bulk();
+        }
+    }
+}
+
+/// Simple LCG for sequence selection (fast, deterministic, no rand dependency).
+#[inline]
+fn fast_rand(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let out = f();
+    (start.elapsed(), out)
+}
+
+/// Run every entry in `OPS` against the stream, grown to `events`. Returns
+/// one result per op plus any validation failures.
+fn measure_level(stream: &mut Stream, events: usize, samples: usize) -> (Vec<OpResult>, Vec<String>) {
+    let db = stream.db;
+    let mut latencies: Vec<Vec<Duration>> = vec![Vec::new(); OPS.len()];
+    let mut failures = Vec::new();
+    let mut rng = events as u64;
+
+    // Oldest and newest windows are fixed before appending the samples
+    let window = SEQ_WINDOW.min(stream.appended);
+    let newest = stream.appended;
+
+    let mut missing = 0;
+    for _ in 0..samples {
+        let seq = fast_rand(&mut rng) % window + 1;
+        let (t, event) = timed(|| db.db.event_get(seq).unwrap());
+        latencies[1].push(t);
+        missing += event.is_none() as usize;
+
+        let seq = newest - fast_rand(&mut rng) % window;
+        let (t, event) = timed(|| db.db.event_get(seq).unwrap());
+        latencies[2].push(t);
+        missing += event.is_none() as usize;
+    }
+    if missing > 0 {
+        failures.push(format!("{} of {} event_get reads found nothing", missing, 2 * samples));
+    }
+
+    let new_probe_type = format!("probe:{}", events);
+    for _ in 0..PROBE_EVENTS {
+        stream.append(&new_probe_type);
+    }
+
+    for (slot, event_type) in [(3, OLD_PROBE_TYPE), (4, new_probe_type.as_str())] {
+        let mut wrong = 0;
+        for _ in 0..samples {
+            let (t, found) = timed(|| db.db.event_get_by_type(event_type).unwrap());
+            latencies[slot].push(t);
+            wrong += (found.len() != PROBE_EVENTS) as usize;
+        }
+        if wrong > 0 {
+            failures.push(format!(
+                "{} of {} reads of '{}' did not return {} events",
+                wrong, samples, event_type, PROBE_EVENTS
+            ));
+        }
+    }
+
+    let bulk_expected = stream.bulk_zero_count();
+    let bulk_samples = (BULK_EVENT_BUDGET / bulk_expected.max(1)).clamp(MIN_BULK_SAMPLES, samples);
+    let mut short = 0;
+    for _ in 0..bulk_samples {
+        let (t, found) = timed(|| db.db.event_get_by_type(&bulk_type(0)).unwrap());
+        latencies[5].push(t);
+        short += (found.len() < bulk_expected) as usize;
+    }
+    if short > 0 {
+        failures.push(format!(
+            "{} of {} bulk reads returned fewer than {} events",
+            short, bulk_samples, bulk_expected
+        ));
+    }
+
+    // Appends last, so the bulk reads above saw exactly `bulk_expected`
+    for _ in 0..samples {
+        let (t, _) = timed(|| stream.append_bulk());
+        latencies[0].push(t);
+    }
+
+    let results = OPS
+        .iter()
+        .zip(latencies)
+        .map(|(&name, l)| OpResult::from_samples(name, events, l))
+        .collect();
+    (results, failures)
+}
+
+// ---------------------------------------------------------------------------
+// Soak mode
+// ---------------------------------------------------------------------------
+
+/// Appends per soak round; each round then does one of each read.
+const SOAK_APPENDS_PER_ROUND: usize = 100;
+
+/// Operations timed in soak mode, in output order. The bulk read is left
+/// out: it grows with the stream and would soon be all the run measured.
+const SOAK_OPS: &[&str] = &[
+    "event_append",
+    "event_get_oldest",
+    "event_get_newest",
+    "read_by_type_old",
+];
+
+/// Grow the stream until the soak's duration runs out. Returns the
+/// windows and any validation failures.
+fn run_soak(stream: &mut Stream, config: &Config, mut soak: Soak) -> io::Result<(Vec<Window>, Vec<String>)> {
+    let db = stream.db;
+    let gauges = |appended: u64| Gauges {
+        entries: appended,
+        rss_bytes: read_rss_bytes(),
+        disk_bytes: db.data_dir().map(dir_size_bytes),
+    };
+    let mut rng = 1u64;
+    let (mut rounds, mut missing, mut wrong) = (0usize, 0usize, 0usize);
+
+    while soak.running() {
+        for _ in 0..SOAK_APPENDS_PER_ROUND {
+            let (t, _) = timed(|| stream.append_bulk());
+            soak.record("event_append", t);
+        }
+
+        let window = SEQ_WINDOW.min(stream.appended);
+        let seq = fast_rand(&mut rng) % window + 1;
+        let (t, event) = timed(|| db.db.event_get(seq).unwrap());
+        soak.record("event_get_oldest", t);
+        missing += event.is_none() as usize;
+
+        let seq = stream.appended - fast_rand(&mut rng) % window;
+        let (t, event) = timed(|| db.db.event_get(seq).unwrap());
+        soak.record("event_get_newest", t);
+        missing += event.is_none() as usize;
+
+        let (t, found) = timed(|| db.db.event_get_by_type(OLD_PROBE_TYPE).unwrap());
+        soak.record("read_by_type_old", t);
+        wrong += (found.len() != PROBE_EVENTS) as usize;
+        rounds += 1;
+
+        if let Some(w) = soak.tick(|| gauges(stream.appended))? {
+            if !config.csv && !config.quiet {
+                eprintln!("{}", w.progress_line());
+            }
+        }
+    }
+
+    let mut failures = Vec::new();
+    if missing > 0 {
+        failures.push(format!("{} of {} event_get reads found nothing", missing, 2 * rounds));
+    }
+    if wrong > 0 {
+        failures.push(format!(
+            "{} of {} reads of '{}' did not return {} events",
+            wrong, rounds, OLD_PROBE_TYPE, PROBE_EVENTS
+        ));
+    }
+    Ok((soak.finish(gauges(stream.appended))?, failures))
+}
+
+/// Record one result per soak operation. Throughput and percentiles are
+/// those of the last full window, where degradation would have led.
+fn record_soak_results(
+    recorder: &mut ResultRecorder,
+    mode: &DurabilityConfig,
+    interval: Duration,
+    series: &Path,
+    windows: &[Window],
+    failures: &[String],
+) {
+    let Some(last) = soak::last_full(windows) else {
+        return;
+    };
+    for &op in SOAK_OPS {
+        let Some(s) = last.op(op) else {
+            continue;
+        };
+        let name = format!("fill-level/event_retention/soak/{}", op);
+        let samples: u64 = windows.iter().filter_map(|w| w.op(op)).map(|s| s.count).sum();
+
+        let mut params = HashMap::new();
+        params.insert("durability".into(), serde_json::json!(mode.label()));
+        params.insert("stream_events".into(), serde_json::json!(last.gauges.entries));
+        params.insert("bulk_types".into(), serde_json::json!(BULK_TYPES));
+        params.insert("retention".into(), serde_json::json!("unbounded"));
+        for (key, value) in soak::summary_parameters(windows, interval, op) {
+            params.insert(key.into(), value);
+        }
+
+        recorder.record(BenchmarkResult {
+            benchmark: name.clone(),
+            category: "fill-level".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(s.ops_per_sec),
+                p50_ns: Some(s.p50_ns),
+                p95_ns: Some(s.p95_ns),
+                p99_ns: Some(s.p99_ns),
+                max_ns: Some(s.max_ns),
+                samples: Some(samples),
+                fill_level: Some(last.gauges.entries as usize),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::from_validation(failures),
+        });
+        recorder.attach_artifact(&name, "soak-series", &soak::artifact_path(series));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_op_result(
+    recorder: &mut ResultRecorder,
+    r: &OpResult,
+    mode: &DurabilityConfig,
+    exponent: Option<f64>,
+    failures: &[String],
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("stream_events".into(), serde_json::json!(r.events));
+    params.insert("bulk_types".into(), serde_json::json!(BULK_TYPES));
+    params.insert("retention".into(), serde_json::json!("unbounded"));
+    if let Some(e) = exponent {
+        params.insert("scaling_exponent".into(), serde_json::json!(e));
+        params.insert("scaling".into(), serde_json::json!(scaling_verdict(e)));
+        if is_small_type_read(r.name) {
+            params.insert("degraded".into(), serde_json::json!(e > DEGRADED_EXPONENT));
+        }
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/event_retention/{}/{}events", r.name, r.events),
+        category: "fill-level".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec()),
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg.as_nanos() as u64),
+            samples: Some(r.samples as u64),
+            fill_level: Some(r.events),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(failures),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn duration_us(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000.0
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>12}  {:>8}  {:>11}  {:>11}  {:>11}  {:>11}",
+        "events", "samples", "avg", "p50", "p99", "max"
+    );
+}
+
+fn print_table_row(r: &OpResult) {
+    eprintln!(
+        "  {:>12}  {:>8}  {:>9.2}us  {:>9.2}us  {:>9.2}us  {:>9.2}us",
+        fmt_num(r.events as u64),
+        r.samples,
+        duration_us(r.avg),
+        duration_us(r.p50),
+        duration_us(r.p99),
+        duration_us(r.max),
+    );
+}
+
+fn print_quiet(r: &OpResult) {
+    eprintln!(
+        "{} @ {} events: p50={:.2}us",
+        r.name,
+        fmt_num(r.events as u64),
+        duration_us(r.p50),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"test\",\"events\",\"avg_us\",\"p50_us\",\"p95_us\",\"p99_us\",\"max_us\"");
+}
+
+fn print_csv_row(r: &OpResult) {
+    println!(
+        "\"{}\",{},{:.3},{:.3},{:.3},{:.3},{:.3}",
+        r.name,
+        r.events,
+        duration_us(r.avg),
+        duration_us(r.p50),
+        duration_us(r.p95),
+        duration_us(r.p99),
+        duration_us(r.max),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: DurabilityConfig,
+    levels: Vec<usize>,
+    samples: usize,
+    /// Soak mode: grow one stream for this long (`--duration`).
+    duration: Option<Duration>,
+    soak_interval: Duration,
+    soak_out: Option<PathBuf>,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DurabilityConfig::Cache,
+        levels: DEFAULT_LEVELS.to_vec(),
+        samples: DEFAULT_SAMPLES,
+        duration: None,
+        soak_interval: soak::DEFAULT_INTERVAL,
+        soak_out: None,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--levels" => config.levels = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            "--samples" => config.samples = args.parse_value::<usize>().max(MIN_BULK_SAMPLES),
+            "--soak-interval" => {
+                let v = args.value();
+                config.soak_interval = soak::parse_duration(&v).unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--soak-interval expects a span like 30s or 5m; got {}", v))
+                });
+            }
+            "--soak-out" => config.soak_out = Some(PathBuf::from(args.value())),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.duration = args.duration;
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    // The stream only grows, so levels are visited shortest first
+    config.levels.sort_unstable();
+    config.levels.dedup();
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Event Retention Benchmark ===");
+        eprintln!("Measures event reads as an unbounded stream grows (Strata has no truncation).");
+        eprintln!();
+        match config.duration {
+            Some(d) => eprintln!(
+                "Soak: {} with {} windows, {} bulk types, {} mode",
+                soak::fmt_span(d),
+                soak::fmt_span(config.soak_interval),
+                BULK_TYPES,
+                config.durability.label()
+            ),
+            None => eprintln!(
+                "Levels: {:?} events, {} bulk types, {} samples, {} mode",
+                config.levels,
+                BULK_TYPES,
+                config.samples,
+                config.durability.label()
+            ),
+        }
+        eprintln!();
+    }
+
+    // A soak's numbers go to its series file, not stdout
+    if config.csv && config.duration.is_none() {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+    let db = create_db(config.durability);
+    let mut stream = Stream {
+        db: &db,
+        appended: 0,
+        bulk: 0,
+    };
+    for _ in 0..PROBE_EVENTS {
+        stream.append(OLD_PROBE_TYPE);
+    }
+
+    if let Some(duration) = config.duration {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        recorder.guard("fill-level/event_retention/soak", guard_params, |recorder| {
+            let soak = Soak::create(
+                "fill-level/event_retention/soak",
+                duration,
+                config.soak_interval,
+                config.soak_out.as_deref(),
+            )
+            .unwrap_or_else(|e| panic!("cannot create soak output: {}", e));
+            let series = soak.path().to_path_buf();
+            if !config.csv {
+                eprintln!("  soaking for {}, series in {}", soak::fmt_span(duration), series.display());
+            }
+            let (windows, failures) = run_soak(&mut stream, &config, soak)
+                .unwrap_or_else(|e| panic!("cannot write {}: {}", series.display(), e));
+            for failure in &failures {
+                eprintln!("  Validation: FAIL ({})", failure);
+            }
+            if let (true, Some(last)) = (config.quiet, windows.last()) {
+                eprintln!("soak: {}", last.progress_line().trim());
+            }
+            record_soak_results(
+                recorder,
+                &config.durability,
+                config.soak_interval,
+                &series,
+                &windows,
+                &failures,
+            );
+        });
+        if !config.csv {
+            eprintln!("=== Benchmark complete ===");
+        }
+        recorder.save_or_exit();
+        return;
+    }
+
+    let mut by_op: Vec<Vec<OpResult>> = OPS.iter().map(|_| Vec::new()).collect();
+    let mut failures: Vec<Vec<String>> = Vec::new();
+
+    for &level in &config.levels {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        guard_params.insert("events".into(), serde_json::json!(level));
+        let guard_name = format!("fill-level/event_retention/{}events", level);
+        let measured = recorder.guard(&guard_name, guard_params, |_| {
+            if !config.csv && !config.quiet {
+                eprintln!("  appending to {} events...", fmt_num(level as u64));
+            }
+            stream.grow_to(level);
+
+            measure_level(&mut stream, level, config.samples)
+        });
+        let Some((results, level_failures)) = measured else {
+            continue;
+        };
+        for failure in &level_failures {
+            eprintln!("  Validation: FAIL ({})", failure);
+        }
+        failures.push(level_failures);
+        for (op, r) in by_op.iter_mut().zip(results) {
+            op.push(r);
+        }
+    }
+
+    let mut degraded = Vec::new();
+    for results in &by_op {
+        let exponent = scaling_exponent(results);
+        for (r, level_failures) in results.iter().zip(&failures) {
+            record_op_result(&mut recorder, r, &config.durability, exponent, level_failures);
+        }
+        if let (Some(e), Some(first)) = (exponent, results.first()) {
+            if is_small_type_read(first.name) && e > DEGRADED_EXPONENT {
+                degraded.push((first.name, e));
+            }
+        }
+
+        if config.csv {
+            for r in results {
+                print_csv_row(r);
+            }
+        } else if config.quiet {
+            for r in results {
+                print_quiet(r);
+            }
+        } else if let Some(first) = results.first() {
+            eprintln!();
+            eprintln!("--- {} ---", first.name);
+            print_table_header();
+            for r in results {
+                print_table_row(r);
+            }
+            if let Some(e) = exponent {
+                eprintln!("  scaling exponent {:.2}: {}", e, scaling_verdict(e));
+            }
+        }
+    }
+
+    if !config.csv {
+        eprintln!();
+        for (name, e) in &degraded {
+            eprintln!(
+                "  {} returns {} events but scales with the stream (exponent {:.2}); retention would bound it",
+                name, PROBE_EVENTS, e
+            );
+        }
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -23,7 +23,8 @@ use harness::recorder::ResultRecorder;
 use harness::{create_db, kv_value, print_hardware_info, BenchDb, DurabilityConfig};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
-use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
 use stratadb::Value;
 
 // ---------------------------------------------------------------------------
@@ -101,7 +102,7 @@ fn fill_database(db: &BenchDb, count: usize) {
     for i in 0..count {
         let key = format!("fill:{:012}", i);
         db.db.kv_put(&key, fill_value.clone()).unwrap();
-        if count >= 50_000 && (i + 1) % 50_000 == 0 {
+        if count >= 50_000 && (i + 1).is_multiple_of(50_000) {
             eprintln!("  filled {}/{} keys...", i + 1, count);
         }
     }
@@ -136,6 +137,9 @@ fn record_fill_result(
             fill_level: Some(r.fill_level),
             ..Default::default()
         },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
     });
 }
 
@@ -169,6 +173,19 @@ fn bench_kv_get(db: &BenchDb, n: usize, fill_level: usize) -> FillResult {
     })
 }
 
+/// `kv_get` for keys that don't exist. Each key sorts between two fill keys,
+/// so the lookup has to reach the index rather than miss on a prefix; with
+/// no fill keys every lookup misses an empty store.
+fn bench_kv_get_miss(db: &BenchDb, n: usize, fill_level: usize) -> FillResult {
+    let mut i = 0u64;
+    run_bench("kv_get_miss", fill_level, n, || {
+        let key = format!("fill:{:012}~", i % fill_level.max(1) as u64);
+        let got = db.db.kv_get(&key).unwrap();
+        assert!(got.is_none(), "missing key {} was found", key);
+        i += 1;
+    })
+}
+
 fn bench_kv_delete(db: &BenchDb, n: usize, fill_level: usize) -> FillResult {
     // Delete from fill keys (they exist from fill_database)
     // If fill_level is 0, pre-populate some keys to delete
@@ -275,18 +292,6 @@ fn duration_ms(d: Duration) -> f64 {
     d.as_nanos() as f64 / 1_000_000.0
 }
 
-fn fmt_num(n: u64) -> String {
-    let s = n.to_string();
-    let mut result = String::new();
-    for (i, c) in s.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
-        }
-        result.push(c);
-    }
-    result.chars().rev().collect()
-}
-
 fn print_table_header() {
     eprintln!(
         "  {:>10}  {:>11}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}",
@@ -352,7 +357,7 @@ struct Config {
 }
 
 fn parse_args() -> Config {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args = harness::cli::Args::parse();
     let mut config = Config {
         ops: DEFAULT_OPS,
         durability: DurabilityConfig::Cache,
@@ -362,43 +367,24 @@ fn parse_args() -> Config {
         quiet: false,
     };
 
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
-            "-n" => {
-                i += 1;
-                config.ops = args[i].parse().unwrap_or(DEFAULT_OPS);
-            }
-            "--durability" => {
-                i += 1;
-                config.durability = match args[i].as_str() {
-                    "cache" => DurabilityConfig::Cache,
-                    "standard" => DurabilityConfig::Standard,
-                    "always" => DurabilityConfig::Always,
-                    _ => DurabilityConfig::Cache,
-                };
-            }
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "-n" => config.ops = args.parse_value(),
             "-t" => {
-                i += 1;
-                let names: Vec<String> = args[i]
+                let v = args.value();
+                let names: Vec<String> = v
                     .split(',')
                     .map(|s| s.trim().to_lowercase())
                     .collect();
                 config.tests = Some(names);
             }
-            "--levels" => {
-                i += 1;
-                config.levels = args[i]
-                    .split(',')
-                    .filter_map(|s| s.trim().parse().ok())
-                    .collect();
-            }
-            "--csv" => config.csv = true,
-            "-q" => config.quiet = true,
-            _ => {}
+            "--levels" => config.levels = args.list(),
+            _ => args.unknown_flag(),
         }
-        i += 1;
     }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
 
     config
 }
@@ -419,6 +405,7 @@ fn test_is_selected(name: &str, filter: &Option<Vec<String>>) -> bool {
 const ALL_TESTS: &[&str] = &[
     "kv_put",
     "kv_get",
+    "kv_get_miss",
     "kv_delete",
     "kv_list",
     "state_set",
@@ -464,41 +451,48 @@ fn main() {
         let mut results = Vec::new();
 
         for &level in &config.levels {
-            if !config.csv && !config.quiet {
-                eprint!("  populating {} fill keys for {}...", fmt_num(level as u64), test_name);
-            }
+            let mut guard_params = HashMap::new();
+            guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+            guard_params.insert("fill_level".into(), serde_json::json!(level));
+            let guard_name = format!("fill-level/{}/{}keys", test_name, level);
+            let measured = recorder.guard(&guard_name, guard_params, |recorder| {
+                if !config.csv && !config.quiet {
+                    eprint!("  populating {} fill keys for {}...", fmt_num(level as u64), test_name);
+                }
+
+                // kv_list uses a fresh database per fill level
+                if *test_name == "kv_list" {
+                    let result = bench_kv_list(config.durability, config.ops, level);
+                    if !config.csv && !config.quiet {
+                        eprintln!(" done");
+                    }
+                    record_fill_result(recorder, &result, &config.durability);
+                    return result;
+                }
+
+                let db = create_db(config.durability);
+                fill_database(&db, level);
 
-            // kv_list uses a fresh database per fill level
-            if *test_name == "kv_list" {
-                let result = bench_kv_list(config.durability, config.ops, level);
                 if !config.csv && !config.quiet {
                     eprintln!(" done");
                 }
-                record_fill_result(&mut recorder, &result, &config.durability);
-                results.push(result);
-                continue;
-            }
 
-            let db = create_db(config.durability);
-            fill_database(&db, level);
-
-            if !config.csv && !config.quiet {
-                eprintln!(" done");
-            }
+                let result = match *test_name {
+                    "kv_put" => bench_kv_put(&db, config.ops, level),
+                    "kv_get" => bench_kv_get(&db, config.ops, level),
+                    "kv_get_miss" => bench_kv_get_miss(&db, config.ops, level),
+                    "kv_delete" => bench_kv_delete(&db, config.ops, level),
+                    "state_set" => bench_state_set(&db, config.ops, level),
+                    "state_read" => bench_state_read(&db, config.ops, level),
+                    "event_append" => bench_event_append(&db, config.ops, level),
+                    "event_read" => bench_event_read(&db, config.ops, level),
+                    _ => unreachable!(),
+                };
 
-            let result = match *test_name {
-                "kv_put" => bench_kv_put(&db, config.ops, level),
-                "kv_get" => bench_kv_get(&db, config.ops, level),
-                "kv_delete" => bench_kv_delete(&db, config.ops, level),
-                "state_set" => bench_state_set(&db, config.ops, level),
-                "state_read" => bench_state_read(&db, config.ops, level),
-                "event_append" => bench_event_append(&db, config.ops, level),
-                "event_read" => bench_event_read(&db, config.ops, level),
-                _ => unreachable!(),
-            };
-
-            record_fill_result(&mut recorder, &result, &config.durability);
-            results.push(result);
+                record_fill_result(recorder, &result, &config.durability);
+                result
+            });
+            results.extend(measured);
         }
 
         // Output results
@@ -524,5 +518,5 @@ fn main() {
     if !config.csv {
         eprintln!("=== Benchmark complete ===");
     }
-    let _ = recorder.save();
+    recorder.save_or_exit();
 }
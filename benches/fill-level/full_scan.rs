@@ -0,0 +1,424 @@
+//! Full-Scan Benchmark for StrataDB
+//!
+//! Measures iterating the entire keyspace at 1M and 10M keys, the access
+//! pattern behind export and analytics jobs. Strata has no streaming
+//! iterator, so a full scan is `kv_list(None)`, which materializes every key
+//! at once. Two scans are timed:
+//!
+//! - `list`: `kv_list(None)` alone, the key listing;
+//! - `ordered_export`: list, sort, then `kv_get` every key in key order, which
+//!   is what reading the whole store out in order costs today.
+//!
+//! Reports keys/sec (and MB/s of values for the export) plus the peak
+//! resident memory the scan adds on top of the loaded database, sampled by a
+//! background thread. Because the listing is materialized, the peak grows
+//! with the key count; a streaming API would keep it flat.
+//!
+//! The database is filled incrementally, one database for all levels. Every
+//! scan must list exactly the keys written and read a value for each;
+//! anything else is recorded as a validation failure.
+//!
+//! Run:    `cargo bench --bench full_scan`
+//! Quick:  `cargo bench --bench full_scan -- --levels 100000 -q`
+//! CSV:    `cargo bench --bench full_scan -- --csv`
+//! Custom: `cargo bench --bench full_scan -- --levels 1000000,5000000 --reps 5`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, read_rss_bytes, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_LEVELS: &[usize] = &[1_000_000, 10_000_000];
+const DEFAULT_REPS: usize = 3;
+const VALUE_SIZE: usize = 64; // small values so 10M keys fit in memory
+/// How often the background thread samples RSS during a scan.
+const RSS_POLL: Duration = Duration::from_millis(5);
+
+/// Scans measured at each level, in output order.
+const SCANS: &[&str] = &["list", "ordered_export"];
+
+// ---------------------------------------------------------------------------
+// Peak memory sampling
+// ---------------------------------------------------------------------------
+
+/// Polls RSS on a background thread and keeps the highest value seen.
+struct PeakRss {
+    stop: Arc<AtomicBool>,
+    peak: Arc<AtomicU64>,
+    handle: JoinHandle<()>,
+}
+
+impl PeakRss {
+    fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let peak = Arc::new(AtomicU64::new(read_rss_bytes().unwrap_or(0)));
+        let handle = {
+            let (stop, peak) = (stop.clone(), peak.clone());
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    if let Some(rss) = read_rss_bytes() {
+                        peak.fetch_max(rss, Ordering::Relaxed);
+                    }
+                    std::thread::sleep(RSS_POLL);
+                }
+            })
+        };
+        PeakRss { stop, peak, handle }
+    }
+
+    /// Stop sampling and return the peak, including one last sample.
+    fn finish(self) -> u64 {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+        if let Some(rss) = read_rss_bytes() {
+            self.peak.fetch_max(rss, Ordering::Relaxed);
+        }
+        self.peak.load(Ordering::Relaxed)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct ScanResult {
+    name: &'static str,
+    keys: usize,
+    /// Wall time of each repetition.
+    reps: Vec<Duration>,
+    /// Value bytes read per repetition (export only).
+    value_bytes: u64,
+    /// Highest RSS during a repetition minus RSS before it, over all reps.
+    rss_delta_bytes: u64,
+    peak_rss_bytes: u64,
+}
+
+impl ScanResult {
+    fn sorted(&self) -> Vec<Duration> {
+        let mut reps = self.reps.clone();
+        reps.sort_unstable();
+        reps
+    }
+
+    fn p50(&self) -> Duration {
+        let reps = self.sorted();
+        reps[reps.len() / 2]
+    }
+
+    fn keys_per_sec(&self) -> f64 {
+        self.keys as f64 / self.p50().as_secs_f64()
+    }
+
+    fn mb_per_sec(&self) -> f64 {
+        self.value_bytes as f64 / 1_048_576.0 / self.p50().as_secs_f64()
+    }
+
+    fn rss_bytes_per_key(&self) -> f64 {
+        self.rss_delta_bytes as f64 / self.keys.max(1) as f64
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Fill and measurement
+// ---------------------------------------------------------------------------
+
+fn scan_key(i: usize) -> String {
+    format!("scan:{:012}", i)
+}
+
+/// Grow the keyspace from `from` to `to` keys.
+fn fill(db: &BenchDb, from: usize, to: usize) {
+    let value = Value::Bytes(vec![0x42; VALUE_SIZE]);
+    for i in from..to {
+        db.db.kv_put(&scan_key(i), value.clone()).unwrap();
+        if to >= 1_000_000 && (i + 1).is_multiple_of(1_000_000) {
+            eprintln!("  filled {}/{} keys...", fmt_num((i + 1) as u64), fmt_num(to as u64));
+        }
+    }
+}
+
+/// Run `scan` under a peak-RSS sampler. Returns its wall time, output, and
+/// the RSS it added over what was resident before it started.
+fn sampled<T>(scan: impl FnOnce() -> T) -> (Duration, T, u64, u64) {
+    let before = read_rss_bytes().unwrap_or(0);
+    let sampler = PeakRss::start();
+    let start = Instant::now();
+    let out = scan();
+    let elapsed = start.elapsed();
+    let peak = sampler.finish();
+    (elapsed, out, peak.saturating_sub(before), peak)
+}
+
+/// Scan the whole keyspace `reps` times each way. Returns one result per
+/// entry in `SCANS`, plus any validation failures.
+fn measure_level(db: &BenchDb, level: usize, reps: usize) -> (Vec<ScanResult>, Vec<String>) {
+    let mut failures = Vec::new();
+    let mut results: Vec<ScanResult> = SCANS
+        .iter()
+        .map(|&name| ScanResult {
+            name,
+            keys: level,
+            reps: Vec::with_capacity(reps),
+            value_bytes: 0,
+            rss_delta_bytes: 0,
+            peak_rss_bytes: 0,
+        })
+        .collect();
+
+    for _ in 0..reps {
+        let (t, keys, delta, peak) = sampled(|| db.db.kv_list(None).unwrap());
+        let r = &mut results[0];
+        r.reps.push(t);
+        r.rss_delta_bytes = r.rss_delta_bytes.max(delta);
+        r.peak_rss_bytes = r.peak_rss_bytes.max(peak);
+        if keys.len() != level {
+            failures.push(format!("list returned {} keys, expected {}", keys.len(), level));
+        }
+        drop(keys);
+
+        let (t, (bytes, missing), delta, peak) = sampled(|| {
+            let mut keys = db.db.kv_list(None).unwrap();
+            keys.sort_unstable();
+            let mut bytes = 0u64;
+            let mut missing = 0usize;
+            for key in &keys {
+                match db.db.kv_get(key).unwrap() {
+                    Some(Value::Bytes(b)) => bytes += b.len() as u64,
+                    Some(_) => {}
+                    None => missing += 1,
+                }
+            }
+            (bytes, missing)
+        });
+        let r = &mut results[1];
+        r.reps.push(t);
+        r.value_bytes = bytes;
+        r.rss_delta_bytes = r.rss_delta_bytes.max(delta);
+        r.peak_rss_bytes = r.peak_rss_bytes.max(peak);
+        if missing > 0 {
+            failures.push(format!("export found no value for {} listed keys", missing));
+        }
+    }
+
+    failures.dedup();
+    (results, failures)
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_scan(recorder: &mut ResultRecorder, r: &ScanResult, mode: &DurabilityConfig, failures: &[String]) {
+    let reps = r.sorted();
+    let sum: Duration = reps.iter().sum();
+
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("keys".into(), serde_json::json!(r.keys));
+    params.insert("value_bytes".into(), serde_json::json!(VALUE_SIZE));
+    params.insert("keys_per_sec".into(), serde_json::json!(r.keys_per_sec()));
+    params.insert("peak_rss_bytes".into(), serde_json::json!(r.peak_rss_bytes));
+    params.insert("scan_rss_bytes".into(), serde_json::json!(r.rss_delta_bytes));
+    params.insert("scan_rss_bytes_per_key".into(), serde_json::json!(r.rss_bytes_per_key()));
+    if r.value_bytes > 0 {
+        params.insert("mb_per_sec".into(), serde_json::json!(r.mb_per_sec()));
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/full_scan/{}/{}keys", r.name, r.keys),
+        category: "fill-level".to_string(),
+        parameters: params,
+        // Latency is the wall time of one full scan; ops/sec is keys/sec
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.keys_per_sec()),
+            p50_ns: Some(r.p50().as_nanos() as u64),
+            min_ns: Some(reps[0].as_nanos() as u64),
+            max_ns: Some(reps[reps.len() - 1].as_nanos() as u64),
+            avg_ns: Some((sum / reps.len() as u32).as_nanos() as u64),
+            samples: Some(reps.len() as u64),
+            fill_level: Some(r.keys),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(failures),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn fmt_mb(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:<16}  {:>10}  {:>11}  {:>9}  {:>12}  {:>10}",
+        "scan", "time", "keys/sec", "MB/s", "scan RSS", "B/key"
+    );
+}
+
+fn print_table_row(r: &ScanResult) {
+    eprintln!(
+        "  {:<16}  {:>8.1}ms  {:>11}  {:>9}  {:>12}  {:>10.1}",
+        r.name,
+        r.p50().as_secs_f64() * 1_000.0,
+        fmt_num(r.keys_per_sec() as u64),
+        if r.value_bytes > 0 { format!("{:.1}", r.mb_per_sec()) } else { "-".to_string() },
+        fmt_mb(r.rss_delta_bytes),
+        r.rss_bytes_per_key(),
+    );
+}
+
+fn print_quiet(r: &ScanResult) {
+    eprintln!(
+        "{} @ {} keys: {} keys/sec, +{} RSS",
+        r.name,
+        fmt_num(r.keys as u64),
+        fmt_num(r.keys_per_sec() as u64),
+        fmt_mb(r.rss_delta_bytes),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"scan\",\"keys\",\"p50_ms\",\"keys_sec\",\"mb_sec\",\"scan_rss_bytes\",\"peak_rss_bytes\"");
+}
+
+fn print_csv_row(r: &ScanResult) {
+    println!(
+        "{},{},{:.3},{:.2},{:.2},{},{}",
+        r.name,
+        r.keys,
+        r.p50().as_secs_f64() * 1_000.0,
+        r.keys_per_sec(),
+        r.mb_per_sec(),
+        r.rss_delta_bytes,
+        r.peak_rss_bytes,
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: DurabilityConfig,
+    levels: Vec<usize>,
+    reps: usize,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DurabilityConfig::Cache,
+        levels: DEFAULT_LEVELS.to_vec(),
+        reps: DEFAULT_REPS,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--levels" => {
+                let mut levels: Vec<usize> = args.list_checked("a list of numbers above 0", |&n| n > 0);
+                levels.sort_unstable();
+                levels.dedup();
+                config.levels = levels;
+            }
+            "--reps" => config.reps = args.parse_value::<usize>().max(1),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Full-Scan Benchmark ===");
+        eprintln!("Measures kv_list(None) and an ordered full export of the keyspace.");
+        eprintln!();
+        eprintln!(
+            "Levels: {:?}, {} reps, {}B values, {} mode",
+            config.levels,
+            config.reps,
+            VALUE_SIZE,
+            config.durability.label()
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+    let db = create_db(config.durability);
+    let mut filled = 0;
+
+    for &level in &config.levels {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        guard_params.insert("keys".into(), serde_json::json!(level));
+        let guard_name = format!("fill-level/full_scan/{}keys", level);
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            fill(&db, filled, level);
+            filled = level;
+
+            let (results, failures) = measure_level(&db, level, config.reps);
+            if !config.csv && !config.quiet {
+                eprintln!("--- full scan @ {} keys ---", fmt_num(level as u64));
+                print_table_header();
+            }
+            for r in &results {
+                record_scan(recorder, r, &config.durability, &failures);
+                if config.csv {
+                    print_csv_row(r);
+                } else if config.quiet {
+                    print_quiet(r);
+                } else {
+                    print_table_row(r);
+                }
+            }
+            for failure in &failures {
+                eprintln!("  Validation: FAIL ({})", failure);
+            }
+            if !config.csv && !config.quiet {
+                eprintln!();
+            }
+        });
+    }
+
+    if !config.csv {
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
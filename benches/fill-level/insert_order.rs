@@ -0,0 +1,469 @@
+//! Insert Order Benchmark for StrataDB
+//!
+//! Measures sustained `kv_put` insert throughput for the same key set written
+//! in three orders:
+//!
+//! - `sequential`: strictly ascending keys;
+//! - `random`: a uniform shuffle of the same keys;
+//! - `reverse`: strictly descending keys.
+//!
+//! An index that benefits from locality (appends at the right edge, hot pages)
+//! shows `sequential` well ahead of `random`; a structure that only handles
+//! one direction well shows `reverse` falling behind `sequential`.
+//!
+//! Each order starts from an empty database. The insert is split into
+//! windows (10 by default) and throughput is reported per window, so a run
+//! that starts fast and slows as the database grows is visible rather than
+//! averaged away. After the insert, a sample of keys is read back; a missing
+//! key is recorded as a validation failure.
+//!
+//! Run:    `cargo bench --bench insert_order`
+//! Quick:  `cargo bench --bench insert_order -- -q`
+//! CSV:    `cargo bench --bench insert_order -- --csv`
+//! Custom: `cargo bench --bench insert_order -- --keys 100000 --windows 5 --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_KEYS: usize = 1_000_000;
+const DEFAULT_WINDOWS: usize = 10;
+const VALUE_SIZE: usize = 64;
+/// Keys read back after each insert to check that the writes landed.
+const VERIFY_SAMPLE: usize = 1_000;
+/// Last/first window throughput ratio below which the insert is flagged as
+/// not sustained.
+const SUSTAINED_RATIO: f64 = 0.5;
+const SHUFFLE_SEED: u64 = 0x0bde_5eed;
+
+// ---------------------------------------------------------------------------
+// Key orders
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq)]
+enum Order {
+    Sequential,
+    Random,
+    Reverse,
+}
+
+impl Order {
+    const ALL: [Order; 3] = [Order::Sequential, Order::Random, Order::Reverse];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Order::Sequential => "sequential",
+            Order::Random => "random",
+            Order::Reverse => "reverse",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Order> {
+        Order::ALL.iter().copied().find(|o| o.label() == s)
+    }
+
+    /// Key indices `0..n` in this order. `random` is a permutation, so all
+    /// three orders insert exactly the same keys.
+    fn indices(&self, n: usize, seed: u64) -> Vec<u64> {
+        let mut indices: Vec<u64> = (0..n as u64).collect();
+        match self {
+            Order::Sequential => {}
+            Order::Reverse => indices.reverse(),
+            Order::Random => {
+                let mut rng = seed;
+                for i in (1..indices.len()).rev() {
+                    let j = (fast_rand(&mut rng) % (i as u64 + 1)) as usize;
+                    indices.swap(i, j);
+                }
+            }
+        }
+        indices
+    }
+}
+
+/// Simple LCG for the shuffle (fast, deterministic, no rand dependency).
+#[inline]
+fn fast_rand(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+/// Zero-padded so that key order matches index order.
+fn insert_key(i: u64) -> String {
+    format!("ins:{:012}", i)
+}
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct InsertResult {
+    order: Order,
+    keys: usize,
+    elapsed: Duration,
+    /// Insert throughput of each window, in order.
+    window_ops_per_sec: Vec<f64>,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    missing: usize,
+}
+
+impl InsertResult {
+    fn ops_per_sec(&self) -> f64 {
+        self.keys as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn first_window(&self) -> f64 {
+        self.window_ops_per_sec.first().copied().unwrap_or(0.0)
+    }
+
+    fn last_window(&self) -> f64 {
+        self.window_ops_per_sec.last().copied().unwrap_or(0.0)
+    }
+
+    /// Last-window throughput as a fraction of the first window's.
+    fn sustained_ratio(&self) -> f64 {
+        self.last_window() / self.first_window().max(f64::MIN_POSITIVE)
+    }
+
+    fn failures(&self) -> Vec<String> {
+        let mut failures = Vec::new();
+        if self.missing > 0 {
+            failures.push(format!(
+                "{} of {} sampled keys missing after insert",
+                self.missing,
+                VERIFY_SAMPLE.min(self.keys)
+            ));
+        }
+        failures
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Measurement
+// ---------------------------------------------------------------------------
+
+fn run_insert(db: &BenchDb, order: Order, keys: usize, windows: usize, seed: u64, quiet: bool) -> InsertResult {
+    let indices = order.indices(keys, seed);
+    let value = Value::Bytes(vec![0x42; VALUE_SIZE]);
+    let window_len = keys.div_ceil(windows).max(1);
+
+    let mut latencies = Vec::with_capacity(keys);
+    let mut window_ops_per_sec = Vec::with_capacity(windows);
+
+    let wall_start = Instant::now();
+    for chunk in indices.chunks(window_len) {
+        let window_start = Instant::now();
+        for &i in chunk {
+            let key = insert_key(i);
+            let start = Instant::now();
+            db.db.kv_put(&key, value.clone()).unwrap();
+            latencies.push(start.elapsed());
+        }
+        window_ops_per_sec.push(chunk.len() as f64 / window_start.elapsed().as_secs_f64());
+        if !quiet && keys >= 100_000 {
+            eprintln!("  {}: inserted {}/{} keys...", order.label(), latencies.len(), keys);
+        }
+    }
+    let elapsed = wall_start.elapsed();
+
+    let stride = (keys / VERIFY_SAMPLE).max(1);
+    let missing = (0..keys)
+        .step_by(stride)
+        .take(VERIFY_SAMPLE)
+        .filter(|&i| db.db.kv_get(&insert_key(i as u64)).unwrap().is_none())
+        .count();
+
+    latencies.sort_unstable();
+    let len = latencies.len();
+    let sum: Duration = latencies.iter().sum();
+
+    InsertResult {
+        order,
+        keys,
+        elapsed,
+        window_ops_per_sec,
+        avg: sum / len as u32,
+        p50: latencies[len * 50 / 100],
+        p95: latencies[(len * 95 / 100).min(len - 1)],
+        p99: latencies[(len * 99 / 100).min(len - 1)],
+        min: latencies[0],
+        max: latencies[len - 1],
+        missing,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_insert(
+    recorder: &mut ResultRecorder,
+    r: &InsertResult,
+    sequential_ops_per_sec: Option<f64>,
+    mode: &DurabilityConfig,
+    seed: u64,
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("order".into(), serde_json::json!(r.order.label()));
+    params.insert("keys".into(), serde_json::json!(r.keys));
+    if matches!(r.order, Order::Random) {
+        params.insert("seed".into(), serde_json::json!(seed));
+    }
+    params.insert("value_size".into(), serde_json::json!(VALUE_SIZE));
+    params.insert("window_ops_per_sec".into(), serde_json::json!(r.window_ops_per_sec));
+    params.insert("sustained_ratio".into(), serde_json::json!(r.sustained_ratio()));
+    params.insert(
+        "sustained".into(),
+        serde_json::json!(r.sustained_ratio() >= SUSTAINED_RATIO),
+    );
+    if let Some(seq) = sequential_ops_per_sec {
+        params.insert("vs_sequential".into(), serde_json::json!(r.ops_per_sec() / seq));
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/insert_order/{}/{}keys", r.order.label(), r.keys),
+        category: "fill-level".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec()),
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg.as_nanos() as u64),
+            samples: Some(r.keys as u64),
+            fill_level: Some(r.keys),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(&r.failures()),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn duration_us(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000.0
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:<12}  {:>10}  {:>12}  {:>12}  {:>9}  {:>9}  {:>9}  {:>8}",
+        "order", "ops/sec", "first window", "last window", "last/first", "p50", "p99", "vs seq"
+    );
+}
+
+fn print_table_row(r: &InsertResult, sequential_ops_per_sec: Option<f64>) {
+    eprintln!(
+        "  {:<12}  {:>10}  {:>12}  {:>12}  {:>8.2}x  {:>7.2}us  {:>7.2}us  {:>8}{}",
+        r.order.label(),
+        fmt_num(r.ops_per_sec() as u64),
+        fmt_num(r.first_window() as u64),
+        fmt_num(r.last_window() as u64),
+        r.sustained_ratio(),
+        duration_us(r.p50),
+        duration_us(r.p99),
+        sequential_ops_per_sec
+            .map(|seq| format!("{:.2}x", r.ops_per_sec() / seq))
+            .unwrap_or_else(|| "-".to_string()),
+        if r.sustained_ratio() < SUSTAINED_RATIO { "  <- slows as it grows" } else { "" },
+    );
+}
+
+fn print_windows(r: &InsertResult) {
+    let windows: Vec<String> = r
+        .window_ops_per_sec
+        .iter()
+        .map(|&ops| fmt_num(ops as u64))
+        .collect();
+    eprintln!("    {:<10} ops/sec per window: {}", r.order.label(), windows.join(", "));
+}
+
+fn print_quiet(r: &InsertResult) {
+    eprintln!(
+        "insert_order {} @ {} keys: {} ops/sec, last/first={:.2}x",
+        r.order.label(),
+        fmt_num(r.keys as u64),
+        fmt_num(r.ops_per_sec() as u64),
+        r.sustained_ratio(),
+    );
+}
+
+fn print_csv_header() {
+    println!(
+        "\"order\",\"keys\",\"ops_sec\",\"first_window_ops_sec\",\"last_window_ops_sec\",\"sustained_ratio\",\"p50_us\",\"p95_us\",\"p99_us\""
+    );
+}
+
+fn print_csv_row(r: &InsertResult) {
+    println!(
+        "{},{},{:.2},{:.2},{:.2},{:.3},{:.3},{:.3},{:.3}",
+        r.order.label(),
+        r.keys,
+        r.ops_per_sec(),
+        r.first_window(),
+        r.last_window(),
+        r.sustained_ratio(),
+        duration_us(r.p50),
+        duration_us(r.p95),
+        duration_us(r.p99),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: DurabilityConfig,
+    keys: usize,
+    windows: usize,
+    orders: Vec<Order>,
+    /// Seed for the `random` order's shuffle (`--seed`).
+    seed: u64,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DurabilityConfig::Cache,
+        keys: DEFAULT_KEYS,
+        windows: DEFAULT_WINDOWS,
+        orders: Order::ALL.to_vec(),
+        seed: SHUFFLE_SEED,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--keys" => config.keys = args.parse_value::<usize>().max(1),
+            "--windows" => config.windows = args.parse_value::<usize>().max(1),
+            "--orders" => {
+                let v = args.value();
+                config.orders = v
+                    .split(',')
+                    .map(|s| Order::parse(s.trim()))
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|o| !o.is_empty())
+                    .unwrap_or_else(|| {
+                        let names: Vec<&str> = Order::ALL.iter().map(|o| o.label()).collect();
+                        harness::cli::usage_error(&format!("--orders expects a list of {}; got {}", names.join(", "), v))
+                    });
+            }
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.seed = args.seed_or(SHUFFLE_SEED);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Insert Order Benchmark ===");
+        eprintln!("Measures sustained kv_put throughput for ascending, shuffled, and descending keys.");
+        eprintln!();
+        eprintln!(
+            "Keys: {}, {} windows, {}B values, {} mode",
+            fmt_num(config.keys as u64),
+            config.windows,
+            VALUE_SIZE,
+            config.durability.label()
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+    let mut results: Vec<InsertResult> = Vec::new();
+
+    for &order in &config.orders {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        guard_params.insert("order".into(), serde_json::json!(order.label()));
+        guard_params.insert("keys".into(), serde_json::json!(config.keys));
+        let guard_name = format!("fill-level/insert_order/{}/{}keys", order.label(), config.keys);
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            let db = create_db(config.durability);
+            let r = run_insert(&db, order, config.keys, config.windows, config.seed, config.csv || config.quiet);
+            let sequential = results
+                .iter()
+                .find(|s| s.order == Order::Sequential)
+                .map(|s| s.ops_per_sec());
+            record_insert(recorder, &r, sequential, &config.durability, config.seed);
+
+            if config.csv {
+                print_csv_row(&r);
+            } else if config.quiet {
+                print_quiet(&r);
+            }
+            for failure in r.failures() {
+                eprintln!("  Validation: FAIL ({}: {})", order.label(), failure);
+            }
+            results.push(r);
+        });
+    }
+
+    if !config.csv && !config.quiet {
+        let sequential = results
+            .iter()
+            .find(|s| s.order == Order::Sequential)
+            .map(|s| s.ops_per_sec());
+        eprintln!();
+        eprintln!("--- kv_put insert @ {} keys ---", fmt_num(config.keys as u64));
+        print_table_header();
+        for r in &results {
+            print_table_row(r, sequential);
+        }
+        eprintln!();
+        for r in &results {
+            print_windows(r);
+        }
+        eprintln!();
+    }
+
+    if !config.csv {
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,373 @@
+//! JSON Pagination Benchmark for StrataDB
+//!
+//! Measures `json_list` cursor pagination as the document count grows. For
+//! each document count (1K, 100K, 1M) and page size (10, 100, 1000), walks the
+//! whole listing from the first page to the last, timing every page.
+//!
+//! Besides the usual per-page percentiles, compares the median page latency
+//! in the first and last 10% of the walk. A cursor that resumes by seeking
+//! keeps that ratio near 1; one that re-scans from the start of the prefix
+//! makes late pages slower in proportion to their depth.
+//!
+//! Every walk must return each document exactly once; anything else is
+//! recorded as a validation failure.
+//!
+//! Run:    `cargo bench --bench json_pagination`
+//! Quick:  `cargo bench --bench json_pagination -- -q`
+//! CSV:    `cargo bench --bench json_pagination -- --csv`
+//! Custom: `cargo bench --bench json_pagination -- --docs 1000,10000 --page-sizes 10,50`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, json_document, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_DOCS: &[usize] = &[1_000, 100_000, 1_000_000];
+const DEFAULT_PAGE_SIZES: &[usize] = &[10, 100, 1_000];
+const PREFIX: &str = "doc:";
+/// Late/early page latency ratio above which deep pagination is flagged.
+const DEGRADATION_RATIO: f64 = 2.0;
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct PageWalk {
+    docs: usize,
+    page_size: usize,
+    pages: usize,
+    keys_seen: usize,
+    duplicates: usize,
+    elapsed: Duration,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    /// Median page latency over the first and last 10% of pages.
+    early_p50: Duration,
+    late_p50: Duration,
+}
+
+impl PageWalk {
+    fn pages_per_sec(&self) -> f64 {
+        self.pages as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn depth_ratio(&self) -> f64 {
+        self.late_p50.as_secs_f64() / self.early_p50.as_secs_f64().max(1e-9)
+    }
+
+    fn degraded(&self) -> bool {
+        self.depth_ratio() > DEGRADATION_RATIO
+    }
+
+    fn failures(&self) -> Vec<String> {
+        let mut failures = Vec::new();
+        if self.keys_seen - self.duplicates != self.docs {
+            failures.push(format!(
+                "walk returned {} distinct keys, expected {}",
+                self.keys_seen - self.duplicates,
+                self.docs
+            ));
+        }
+        if self.duplicates > 0 {
+            failures.push(format!("walk returned {} keys more than once", self.duplicates));
+        }
+        failures
+    }
+}
+
+fn median(sorted: &mut [Duration]) -> Duration {
+    sorted.sort_unstable();
+    sorted[sorted.len() / 2]
+}
+
+// ---------------------------------------------------------------------------
+// Population and measurement
+// ---------------------------------------------------------------------------
+
+fn populate(db: &BenchDb, count: usize) {
+    for i in 0..count {
+        db.db
+            .json_set(&format!("{}{:08}", PREFIX, i), "$", json_document(i as u64))
+            .unwrap();
+        if count >= 100_000 && (i + 1).is_multiple_of(100_000) {
+            eprintln!("  inserted {}/{} documents...", i + 1, count);
+        }
+    }
+}
+
+/// Walk the full listing with `page_size` pages, following cursors.
+fn walk(db: &BenchDb, docs: usize, page_size: usize) -> PageWalk {
+    let mut latencies = Vec::with_capacity(docs / page_size + 1);
+    let mut seen = HashSet::with_capacity(docs);
+    let mut keys_seen = 0;
+    let mut cursor: Option<String> = None;
+
+    let wall_start = Instant::now();
+    loop {
+        let op_start = Instant::now();
+        let (keys, next) = db
+            .db
+            .json_list(Some(PREFIX.to_string()), cursor.take(), page_size as u64)
+            .unwrap();
+        latencies.push(op_start.elapsed());
+
+        keys_seen += keys.len();
+        seen.extend(keys);
+        match next {
+            Some(c) => cursor = Some(c),
+            None => break,
+        }
+    }
+    let elapsed = wall_start.elapsed();
+
+    let pages = latencies.len();
+    let decile = (pages / 10).max(1);
+    let early_p50 = median(&mut latencies[..decile].to_vec());
+    let late_p50 = median(&mut latencies[pages - decile..].to_vec());
+
+    latencies.sort_unstable();
+    let sum: Duration = latencies.iter().sum();
+
+    PageWalk {
+        docs,
+        page_size,
+        pages,
+        keys_seen,
+        duplicates: keys_seen - seen.len(),
+        elapsed,
+        avg: sum / pages as u32,
+        p50: latencies[pages * 50 / 100],
+        p95: latencies[(pages * 95 / 100).min(pages - 1)],
+        p99: latencies[(pages * 99 / 100).min(pages - 1)],
+        min: latencies[0],
+        max: latencies[pages - 1],
+        early_p50,
+        late_p50,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_walk(recorder: &mut ResultRecorder, w: &PageWalk, mode: &DurabilityConfig) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("documents".into(), serde_json::json!(w.docs));
+    params.insert("page_size".into(), serde_json::json!(w.page_size));
+    params.insert("pages".into(), serde_json::json!(w.pages));
+    params.insert("early_p50_ns".into(), serde_json::json!(w.early_p50.as_nanos() as u64));
+    params.insert("late_p50_ns".into(), serde_json::json!(w.late_p50.as_nanos() as u64));
+    params.insert("depth_ratio".into(), serde_json::json!(w.depth_ratio()));
+    params.insert("deep_pages_degraded".into(), serde_json::json!(w.degraded()));
+    params.insert(
+        "keys_per_sec".into(),
+        serde_json::json!(w.keys_seen as f64 / w.elapsed.as_secs_f64()),
+    );
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/json_list/{}docs/page-{}", w.docs, w.page_size),
+        category: "fill-level".to_string(),
+        parameters: params,
+        // Latency per page; ops/sec is pages/sec
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(w.pages_per_sec()),
+            p50_ns: Some(w.p50.as_nanos() as u64),
+            p95_ns: Some(w.p95.as_nanos() as u64),
+            p99_ns: Some(w.p99.as_nanos() as u64),
+            min_ns: Some(w.min.as_nanos() as u64),
+            max_ns: Some(w.max.as_nanos() as u64),
+            avg_ns: Some(w.avg.as_nanos() as u64),
+            samples: Some(w.pages as u64),
+            fill_level: Some(w.docs),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(&w.failures()),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn duration_ms(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000_000.0
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>10}  {:>9}  {:>9}  {:>10}  {:>10}  {:>10}  {:>10}  {:>7}",
+        "page_size", "pages", "pages/sec", "p50", "p99", "first 10%", "last 10%", "ratio"
+    );
+}
+
+fn print_table_row(w: &PageWalk) {
+    eprintln!(
+        "  {:>10}  {:>9}  {:>9}  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>6.2}x{}",
+        w.page_size,
+        fmt_num(w.pages as u64),
+        fmt_num(w.pages_per_sec() as u64),
+        duration_ms(w.p50),
+        duration_ms(w.p99),
+        duration_ms(w.early_p50),
+        duration_ms(w.late_p50),
+        w.depth_ratio(),
+        if w.degraded() { "  <- deep pages slower" } else { "" },
+    );
+}
+
+fn print_quiet(w: &PageWalk) {
+    eprintln!(
+        "json_list @ {} docs, page {}: p50={:.3}ms, last/first={:.2}x",
+        fmt_num(w.docs as u64),
+        w.page_size,
+        duration_ms(w.p50),
+        w.depth_ratio(),
+    );
+}
+
+fn print_csv_header() {
+    println!(
+        "\"documents\",\"page_size\",\"pages\",\"pages_sec\",\"p50_ms\",\"p95_ms\",\"p99_ms\",\"early_p50_ms\",\"late_p50_ms\",\"depth_ratio\""
+    );
+}
+
+fn print_csv_row(w: &PageWalk) {
+    println!(
+        "{},{},{},{:.2},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3}",
+        w.docs,
+        w.page_size,
+        w.pages,
+        w.pages_per_sec(),
+        duration_ms(w.p50),
+        duration_ms(w.p95),
+        duration_ms(w.p99),
+        duration_ms(w.early_p50),
+        duration_ms(w.late_p50),
+        w.depth_ratio(),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: DurabilityConfig,
+    docs: Vec<usize>,
+    page_sizes: Vec<usize>,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DurabilityConfig::Cache,
+        docs: DEFAULT_DOCS.to_vec(),
+        page_sizes: DEFAULT_PAGE_SIZES.to_vec(),
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--docs" => config.docs = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            "--page-sizes" => config.page_sizes = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB JSON Pagination Benchmark ===");
+        eprintln!("Measures json_list per-page latency across a full cursor walk.");
+        eprintln!();
+        eprintln!(
+            "Documents: {:?}, page sizes: {:?}, {} mode",
+            config.docs,
+            config.page_sizes,
+            config.durability.label()
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+
+    for &docs in &config.docs {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        guard_params.insert("docs".into(), serde_json::json!(docs));
+        let guard_name = format!("fill-level/json_list/{}docs", docs);
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            if !config.csv && !config.quiet {
+                eprint!("  inserting {} documents...", fmt_num(docs as u64));
+            }
+            let db = create_db(config.durability);
+            populate(&db, docs);
+            if !config.csv && !config.quiet {
+                eprintln!(" done");
+                eprintln!();
+                eprintln!("--- json_list @ {} documents ---", fmt_num(docs as u64));
+                print_table_header();
+            }
+
+            for &page_size in &config.page_sizes {
+                let w = walk(&db, docs, page_size);
+                record_walk(recorder, &w, &config.durability);
+
+                if config.csv {
+                    print_csv_row(&w);
+                } else if config.quiet {
+                    print_quiet(&w);
+                } else {
+                    print_table_row(&w);
+                }
+                for failure in w.failures() {
+                    eprintln!("  Validation: FAIL ({})", failure);
+                }
+            }
+            if !config.csv && !config.quiet {
+                eprintln!();
+            }
+        });
+    }
+
+    if !config.csv {
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
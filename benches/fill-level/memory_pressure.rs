@@ -0,0 +1,678 @@
+//! Memory-Pressure Benchmark for StrataDB
+//!
+//! Every other suite runs with the whole dataset in RAM. This one runs under
+//! a memory ceiling (512 MiB by default) and grows the dataset past it, from
+//! a quarter of the ceiling to four times it, to show how throughput and
+//! tail latency degrade once reads have to go to the storage device.
+//!
+//! Each dataset size runs in its own child process inside a cgroup (or
+//! systemd scope) capped at the ceiling, page cache included; see
+//! `harness/memlimit.rs`. The child loads `ratio * ceiling` bytes of
+//! incompressible 1KB values, then times:
+//!
+//! - `kv_get`: reads of uniformly spread existing keys
+//! - `kv_put`: overwrites of uniformly spread existing keys
+//!
+//! Major page faults per operation are recorded next to the latencies. A
+//! child the kernel OOM-kills is recorded as an error for its ratio, and the
+//! remaining ratios still run. When the bench is itself started under a
+//! ceiling (`all --memory-limit`), that ceiling replaces `--memory-limit`.
+//! Where no ceiling can be enforced, the sizes are still computed from
+//! `--memory-limit`, but the run is marked `enforced: false` and only
+//! measures a larger dataset.
+//!
+//! Run:    `cargo bench --bench memory_pressure`
+//! Quick:  `cargo bench --bench memory_pressure -- -q`
+//! CSV:    `cargo bench --bench memory_pressure -- --csv`
+//! Custom: `cargo bench --bench memory_pressure -- --memory-limit 1G --ratios 0.5,1,2,8`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::memlimit::{self, Limiter};
+use harness::metrics::{delta_process_metrics, snapshot_process_metrics};
+use harness::recorder::ResultRecorder;
+use harness::{create_db, kv_key, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_num, fmt_ops};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_MEMORY_LIMIT: u64 = 512 << 20;
+const DEFAULT_RATIOS: &[f64] = &[0.25, 0.5, 1.0, 2.0, 4.0];
+const DEFAULT_OPS: usize = 20_000;
+const DEFAULT_VALUE_SIZE: usize = 1024;
+
+/// `kv_key` pads every key to 100 bytes.
+const KEY_BYTES: usize = 100;
+
+/// Operations measured at each ratio, in output order.
+const OPS: &[&str] = &["kv_get", "kv_put"];
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct OpResult {
+    name: &'static str,
+    ratio: f64,
+    keys: u64,
+    samples: usize,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    major_faults_per_op: f64,
+}
+
+impl OpResult {
+    fn from_samples(
+        name: &'static str,
+        ratio: f64,
+        keys: u64,
+        mut latencies: Vec<Duration>,
+        major_faults: u64,
+    ) -> Self {
+        latencies.sort_unstable();
+        let len = latencies.len();
+        let sum: Duration = latencies.iter().sum();
+        OpResult {
+            name,
+            ratio,
+            keys,
+            samples: len,
+            avg: sum / len as u32,
+            p50: latencies[len * 50 / 100],
+            p95: latencies[(len * 95 / 100).min(len - 1)],
+            p99: latencies[(len * 99 / 100).min(len - 1)],
+            min: latencies[0],
+            max: latencies[len - 1],
+            major_faults_per_op: major_faults as f64 / len as f64,
+        }
+    }
+
+    fn ops_per_sec(&self) -> f64 {
+        1.0 / self.avg.as_secs_f64().max(1e-12)
+    }
+
+    /// Serialized for the parent process (nanosecond integers).
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "ratio": self.ratio,
+            "keys": self.keys,
+            "samples": self.samples,
+            "avg_ns": self.avg.as_nanos() as u64,
+            "p50_ns": self.p50.as_nanos() as u64,
+            "p95_ns": self.p95.as_nanos() as u64,
+            "p99_ns": self.p99.as_nanos() as u64,
+            "min_ns": self.min.as_nanos() as u64,
+            "max_ns": self.max.as_nanos() as u64,
+            "major_faults_per_op": self.major_faults_per_op,
+        })
+    }
+
+    fn from_json(v: &serde_json::Value) -> Option<Self> {
+        let ns = |field: &str| v[field].as_u64().map(Duration::from_nanos);
+        let name = OPS.iter().find(|&&op| Some(op) == v["name"].as_str())?;
+        Some(OpResult {
+            name,
+            ratio: v["ratio"].as_f64()?,
+            keys: v["keys"].as_u64()?,
+            samples: v["samples"].as_u64()? as usize,
+            avg: ns("avg_ns")?,
+            p50: ns("p50_ns")?,
+            p95: ns("p95_ns")?,
+            p99: ns("p99_ns")?,
+            min: ns("min_ns")?,
+            max: ns("max_ns")?,
+            major_faults_per_op: v["major_faults_per_op"].as_f64()?,
+        })
+    }
+}
+
+/// Throughput and p99 at `r` relative to the smallest ratio measured.
+fn degradation(first: &OpResult, r: &OpResult) -> (f64, f64) {
+    let throughput = r.ops_per_sec() / first.ops_per_sec().max(1e-12);
+    let p99 = r.p99.as_secs_f64() / first.p99.as_secs_f64().max(1e-12);
+    (throughput, p99)
+}
+
+// ---------------------------------------------------------------------------
+// Dataset
+// ---------------------------------------------------------------------------
+
+fn key_count(ratio: f64, limit: u64, value_size: usize) -> u64 {
+    ((ratio * limit as f64) / (KEY_BYTES + value_size) as f64).max(1.0) as u64
+}
+
+/// A value that doesn't compress, so the bytes on disk match the bytes
+/// loaded. `generation` changes the contents for overwrites.
+fn incompressible_value(i: u64, generation: u64, size: usize) -> Value {
+    let mut x = i.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ generation.wrapping_add(1);
+    let mut bytes = Vec::with_capacity(size + 8);
+    while bytes.len() < size {
+        // xorshift64
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        bytes.extend_from_slice(&x.to_le_bytes());
+    }
+    bytes.truncate(size);
+    Value::Bytes(bytes)
+}
+
+/// The `s`-th key to touch: a fixed spread over `0..keys` that visits the
+/// whole keyspace rather than its recently written tail.
+fn spread_key(s: usize, keys: u64) -> u64 {
+    (s as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) % keys
+}
+
+fn load(db: &BenchDb, keys: u64, value_size: usize) -> f64 {
+    let start = Instant::now();
+    for i in 0..keys {
+        db.db
+            .kv_put(&kv_key(i), incompressible_value(i, 0, value_size))
+            .unwrap();
+    }
+    keys as f64 / start.elapsed().as_secs_f64().max(1e-9)
+}
+
+/// Runs in the child: load the dataset for one ratio and measure it.
+fn measure_ratio(config: &Config, ratio: f64) -> (f64, Vec<OpResult>, Vec<String>) {
+    let keys = key_count(ratio, config.memory_limit, config.value_size);
+    let db = create_db(config.durability);
+    let load_ops_per_sec = load(&db, keys, config.value_size);
+    let mut failures = Vec::new();
+
+    let mut latencies = Vec::with_capacity(config.ops);
+    let mut misses = 0;
+    let before = snapshot_process_metrics();
+    for s in 0..config.ops {
+        let key = kv_key(spread_key(s, keys));
+        let start = Instant::now();
+        let value = db.db.kv_get(&key).unwrap();
+        latencies.push(start.elapsed());
+        if value.is_none() {
+            misses += 1;
+        }
+    }
+    let faults = delta_process_metrics(&before, &snapshot_process_metrics()).major_faults;
+    if misses > 0 {
+        failures.push(format!("kv_get: {} of {} loaded keys missing", misses, config.ops));
+    }
+    let get = OpResult::from_samples("kv_get", ratio, keys, latencies, faults);
+
+    let mut latencies = Vec::with_capacity(config.ops);
+    let before = snapshot_process_metrics();
+    for s in 0..config.ops {
+        let i = spread_key(s, keys);
+        let value = incompressible_value(i, 1, config.value_size);
+        let key = kv_key(i);
+        let start = Instant::now();
+        db.db.kv_put(&key, value).unwrap();
+        latencies.push(start.elapsed());
+    }
+    let faults = delta_process_metrics(&before, &snapshot_process_metrics()).major_faults;
+    let put = OpResult::from_samples("kv_put", ratio, keys, latencies, faults);
+
+    (load_ops_per_sec, vec![get, put], failures)
+}
+
+// ---------------------------------------------------------------------------
+// Parent: one child process per ratio
+// ---------------------------------------------------------------------------
+
+/// What one child reported, or why it produced nothing.
+enum RatioOutcome {
+    Measured {
+        load_ops_per_sec: f64,
+        results: Vec<OpResult>,
+        failures: Vec<String>,
+    },
+    Failed(String),
+}
+
+fn emit_path(ratio: f64) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "strata-memory-pressure-{}-{}.json",
+        std::process::id(),
+        ratio
+    ))
+}
+
+fn run_child(config: &Config, ratio: f64, limiter: Option<&Limiter>) -> RatioOutcome {
+    let exe = std::env::current_exe().expect("current_exe");
+    let exe = exe.to_string_lossy().into_owned();
+    let emit = emit_path(ratio);
+    // A child the kernel kills leaves its database behind; giving each one
+    // its own --db-path lets the parent delete it either way
+    let db_path = harness::cli::db_root().join(format!(
+        "strata-memory-pressure-{}-{}",
+        std::process::id(),
+        ratio
+    ));
+    let mut args: Vec<String> = vec![
+        "--child".into(),
+        ratio.to_string(),
+        "--emit".into(),
+        emit.display().to_string(),
+        "--memory-limit".into(),
+        config.memory_limit.to_string(),
+        "--ops".into(),
+        config.ops.to_string(),
+        "--value-size".into(),
+        config.value_size.to_string(),
+        "--durability".into(),
+        config.durability.label(),
+        "--db-path".into(),
+        db_path.display().to_string(),
+    ];
+    if let Some(spec) = harness::cli::strata_config_spec() {
+        args.extend(["--strata-config".to_string(), spec]);
+    }
+
+    let oom_before = limiter.and_then(|l| l.oom_kills());
+    let status = match limiter {
+        Some(l) => l.status(&exe, &args),
+        None => Command::new(&exe).args(&args).status(),
+    };
+    let oom_after = limiter.and_then(|l| l.oom_kills());
+
+    let outcome = match status {
+        Ok(s) if s.success() => read_emitted(&emit),
+        Ok(_) if matches!((oom_before, oom_after), (Some(b), Some(a)) if a > b) => {
+            RatioOutcome::Failed("OOM-killed".to_string())
+        }
+        Ok(s) => RatioOutcome::Failed(format!("child exited with {}", s)),
+        Err(e) => RatioOutcome::Failed(format!("could not start child: {}", e)),
+    };
+    let _ = std::fs::remove_file(&emit);
+    let _ = std::fs::remove_dir_all(&db_path);
+    outcome
+}
+
+fn read_emitted(path: &Path) -> RatioOutcome {
+    let parsed = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok());
+    let Some(v) = parsed else {
+        return RatioOutcome::Failed(format!("no results in {}", path.display()));
+    };
+    let results = v["results"]
+        .as_array()
+        .map(|a| a.iter().filter_map(OpResult::from_json).collect())
+        .unwrap_or_default();
+    let failures = v["failures"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|f| f.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    RatioOutcome::Measured {
+        load_ops_per_sec: v["load_ops_per_sec"].as_f64().unwrap_or(0.0),
+        results,
+        failures,
+    }
+}
+
+fn child_main(config: &Config, ratio: f64, emit: &str) {
+    let (load_ops_per_sec, results, failures) = measure_ratio(config, ratio);
+    let report = serde_json::json!({
+        "load_ops_per_sec": load_ops_per_sec,
+        "results": results.iter().map(OpResult::to_json).collect::<Vec<_>>(),
+        "failures": failures,
+    });
+    std::fs::write(emit, report.to_string()).expect("failed to write child results");
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+struct RunInfo {
+    enforced: bool,
+    mechanism: String,
+}
+
+fn base_params(config: &Config, run: &RunInfo, ratio: f64) -> HashMap<String, serde_json::Value> {
+    let keys = key_count(ratio, config.memory_limit, config.value_size);
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    params.insert("ratio".into(), serde_json::json!(ratio));
+    params.insert("memory_limit_mb".into(), serde_json::json!(config.memory_limit >> 20));
+    params.insert(
+        "dataset_mb".into(),
+        serde_json::json!((keys * (KEY_BYTES + config.value_size) as u64) >> 20),
+    );
+    params.insert("keys".into(), serde_json::json!(keys));
+    params.insert("value_size".into(), serde_json::json!(config.value_size));
+    params.insert("enforced".into(), serde_json::json!(run.enforced));
+    params.insert("mechanism".into(), serde_json::json!(run.mechanism));
+    params
+}
+
+fn benchmark_name(op: &str, ratio: f64) -> String {
+    format!("fill-level/memory_pressure/{}/{}x", op, ratio)
+}
+
+fn record_op_result(
+    recorder: &mut ResultRecorder,
+    r: &OpResult,
+    first: &OpResult,
+    mut params: HashMap<String, serde_json::Value>,
+    load_ops_per_sec: f64,
+    failures: &[String],
+) {
+    let (throughput, p99) = degradation(first, r);
+    params.insert("major_faults_per_op".into(), serde_json::json!(r.major_faults_per_op));
+    params.insert("load_ops_per_sec".into(), serde_json::json!(load_ops_per_sec));
+    params.insert("throughput_vs_smallest".into(), serde_json::json!(throughput));
+    params.insert("p99_vs_smallest".into(), serde_json::json!(p99));
+
+    recorder.record(BenchmarkResult {
+        benchmark: benchmark_name(r.name, r.ratio),
+        category: "fill-level".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec()),
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg.as_nanos() as u64),
+            samples: Some(r.samples as u64),
+            fill_level: Some(r.keys as usize),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(failures),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn duration_us(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000.0
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>6}  {:>10}  {:>11}  {:>11}  {:>11}  {:>9}  {:>8}  {:>7}",
+        "ratio", "keys", "ops/s", "p50", "p99", "majflt/op", "vs 1st", "p99 x"
+    );
+}
+
+fn print_table_row(r: &OpResult, first: &OpResult) {
+    let (throughput, p99) = degradation(first, r);
+    eprintln!(
+        "  {:>5}x  {:>10}  {:>11}  {:>9.2}us  {:>9.2}us  {:>9.2}  {:>7.0}%  {:>6.1}x",
+        r.ratio,
+        fmt_num(r.keys),
+        fmt_ops(r.ops_per_sec()),
+        duration_us(r.p50),
+        duration_us(r.p99),
+        r.major_faults_per_op,
+        throughput * 100.0,
+        p99,
+    );
+}
+
+fn print_quiet(r: &OpResult, first: &OpResult) {
+    let (throughput, p99) = degradation(first, r);
+    eprintln!(
+        "{} @ {}x: {} ops/s ({:.0}% of {}x), p99={:.2}us ({:.1}x)",
+        r.name,
+        r.ratio,
+        fmt_ops(r.ops_per_sec()),
+        throughput * 100.0,
+        first.ratio,
+        duration_us(r.p99),
+        p99,
+    );
+}
+
+fn print_csv_header() {
+    println!("\"test\",\"ratio\",\"keys\",\"ops_per_sec\",\"p50_us\",\"p95_us\",\"p99_us\",\"major_faults_per_op\"");
+}
+
+fn print_csv_row(r: &OpResult) {
+    println!(
+        "\"{}\",{},{},{:.0},{:.3},{:.3},{:.3},{:.3}",
+        r.name,
+        r.ratio,
+        r.keys,
+        r.ops_per_sec(),
+        duration_us(r.p50),
+        duration_us(r.p95),
+        duration_us(r.p99),
+        r.major_faults_per_op,
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: DurabilityConfig,
+    memory_limit: u64,
+    ratios: Vec<f64>,
+    ops: usize,
+    value_size: usize,
+    csv: bool,
+    quiet: bool,
+    /// Set in child processes: measure this one ratio and write to `emit`.
+    child: Option<(f64, String)>,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DurabilityConfig::Standard,
+        memory_limit: DEFAULT_MEMORY_LIMIT,
+        ratios: DEFAULT_RATIOS.to_vec(),
+        ops: DEFAULT_OPS,
+        value_size: DEFAULT_VALUE_SIZE,
+        csv: false,
+        quiet: false,
+        child: None,
+    };
+    let mut child_ratio = None;
+    let mut emit = None;
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--memory-limit" => {
+                let v = args.value();
+                config.memory_limit = memlimit::parse_size(&v).unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--memory-limit expects a size like 512M or 2G; got {}", v))
+                });
+            }
+            "--ratios" => config.ratios = args.list_checked("a list of ratios above 0", |&r: &f64| r > 0.0),
+            "--ops" => config.ops = args.parse_value::<usize>().max(100),
+            "--value-size" => config.value_size = args.parse_value::<usize>().max(8),
+            "--child" => child_ratio = Some(args.parse_value()),
+            "--emit" => emit = Some(args.value()),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Standard);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config.child = child_ratio.zip(emit);
+    config.ratios.sort_by(|a, b| a.total_cmp(b));
+    config.ratios.dedup();
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let mut config = parse_args();
+    if let Some((ratio, emit)) = &config.child {
+        child_main(&config, *ratio, emit);
+        return;
+    }
+    print_hardware_info();
+
+    // Started under a ceiling already (`all --memory-limit`): children
+    // inherit it, and the dataset is sized against it
+    let inherited = memlimit::active_limit();
+    let limiter = match inherited {
+        Some(limit) => {
+            config.memory_limit = limit;
+            None
+        }
+        None => match Limiter::new(config.memory_limit) {
+            Ok(l) => Some(l),
+            Err(e) => {
+                eprintln!(
+                    "WARNING: can't enforce a memory limit here ({}). Running unconstrained; \
+                     results are marked enforced=false.",
+                    e
+                );
+                None
+            }
+        },
+    };
+    let run = RunInfo {
+        enforced: limiter.is_some() || inherited.is_some(),
+        mechanism: match (&limiter, inherited) {
+            (Some(l), _) => l.describe(),
+            (None, Some(_)) => "inherited".to_string(),
+            (None, None) => "none".to_string(),
+        },
+    };
+
+    if !config.csv {
+        eprintln!("=== StrataDB Memory-Pressure Benchmark ===");
+        eprintln!("Measures throughput and tail latency as the dataset outgrows a memory ceiling.");
+        eprintln!();
+        eprintln!(
+            "Ceiling: {} ({}), ratios: {:?}, {} ops, {}B values, {} mode",
+            memlimit::fmt_size(config.memory_limit),
+            run.mechanism,
+            config.ratios,
+            config.ops,
+            config.value_size,
+            config.durability.label()
+        );
+        if matches!(config.durability, DurabilityConfig::Cache) {
+            eprintln!("Cache mode keeps everything in memory; ratios of 1x and up will be OOM-killed.");
+        }
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+    let mut by_op: Vec<Vec<OpResult>> = OPS.iter().map(|_| Vec::new()).collect();
+    // Per ratio, in order: load throughput and validation failures
+    let mut measured: Vec<(f64, f64, Vec<String>)> = Vec::new();
+
+    for &ratio in &config.ratios {
+        let guard_name = format!("fill-level/memory_pressure/{}x", ratio);
+        recorder.guard(&guard_name, base_params(&config, &run, ratio), |recorder| {
+            let keys = key_count(ratio, config.memory_limit, config.value_size);
+            if !config.csv && !config.quiet {
+                eprintln!(
+                    "  {}x: loading {} keys ({})...",
+                    ratio,
+                    fmt_num(keys),
+                    memlimit::fmt_size(keys * (KEY_BYTES + config.value_size) as u64)
+                );
+            }
+            match run_child(&config, ratio, limiter.as_ref()) {
+                RatioOutcome::Measured {
+                    load_ops_per_sec,
+                    results,
+                    failures,
+                } => {
+                    for failure in &failures {
+                        eprintln!("  Validation: FAIL ({})", failure);
+                    }
+                    for r in results {
+                        if let Some(slot) = OPS.iter().position(|&op| op == r.name) {
+                            by_op[slot].push(r);
+                        }
+                    }
+                    measured.push((ratio, load_ops_per_sec, failures));
+                }
+                RatioOutcome::Failed(reason) => {
+                    let message = format!("{} at {}x of {}", reason, ratio, memlimit::fmt_size(config.memory_limit));
+                    for op in OPS {
+                        recorder.record_failure(
+                            &benchmark_name(op, ratio),
+                            base_params(&config, &run, ratio),
+                            ResultStatus::Error {
+                                message: message.clone(),
+                            },
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    for results in &by_op {
+        let Some(first) = results.first() else {
+            continue;
+        };
+        for r in results {
+            let (load_ops_per_sec, failures) = measured
+                .iter()
+                .find(|(ratio, _, _)| *ratio == r.ratio)
+                .map(|(_, load, f)| (*load, f.as_slice()))
+                .unwrap_or_default();
+            let params = base_params(&config, &run, r.ratio);
+            record_op_result(&mut recorder, r, first, params, load_ops_per_sec, failures);
+        }
+
+        if config.csv {
+            for r in results {
+                print_csv_row(r);
+            }
+        } else if config.quiet {
+            for r in results {
+                print_quiet(r, first);
+            }
+        } else {
+            eprintln!();
+            eprintln!("--- {} ---", first.name);
+            print_table_header();
+            for r in results {
+                print_table_row(r, first);
+            }
+            if let Some(last) = results.last().filter(|l| l.ratio > first.ratio) {
+                let (throughput, p99) = degradation(first, last);
+                eprintln!(
+                    "  at {}x the ceiling: {:.0}% of the {}x throughput, p99 {:.1}x",
+                    last.ratio,
+                    throughput * 100.0,
+                    first.ratio,
+                    p99
+                );
+            }
+        }
+    }
+
+    recorder.save_or_exit();
+}
@@ -0,0 +1,471 @@
+//! Overwrite Space-Growth Benchmark for StrataDB
+//!
+//! Loads a fixed key set, then overwrites it with a 100% update workload for
+//! millions of operations (uniformly random keys, 5M by default) while
+//! sampling the database's on-disk size. The live data never grows, so any
+//! growth on disk is old versions and log that haven't been reclaimed.
+//!
+//! Reports:
+//!
+//! - space amplification (bytes on disk / logical bytes of the live keys) at
+//!   every sample point;
+//! - the steady-state amplification, the median over the second half of the
+//!   run;
+//! - whether old versions are reclaimed: if the on-disk size still grows by
+//!   more than 25% over the second half of the run, it is reported as
+//!   unbounded growth.
+//!
+//! After the run, a sample of keys is read back and must return the value
+//! written last; anything else is recorded as a validation failure.
+//!
+//! Cache mode has nothing on disk and is not run.
+//!
+//! Run:    `cargo bench --bench overwrite_space`
+//! Quick:  `cargo bench --bench overwrite_space -- --ops 500000 -q`
+//! CSV:    `cargo bench --bench overwrite_space -- --csv`
+//! Custom: `cargo bench --bench overwrite_space -- --keys 10000 --ops 2000000 --samples 40`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::scaling::ReservoirSampler;
+use harness::{create_db, dir_size_bytes, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_KEYS: usize = 100_000;
+const DEFAULT_OPS: usize = 5_000_000;
+const DEFAULT_SAMPLES: usize = 20;
+const VALUE_SIZE: usize = 256;
+const DISK_MODES: &[DurabilityConfig] = &[DurabilityConfig::Standard, DurabilityConfig::Always];
+/// Keys read back after the run to check they hold the last write.
+const VERIFY_SAMPLE: usize = 1_000;
+/// Growth of the on-disk size over the second half of the run above which
+/// old versions are reported as not reclaimed.
+const UNBOUNDED_GROWTH: f64 = 1.25;
+
+// ---------------------------------------------------------------------------
+// Keys and values
+// ---------------------------------------------------------------------------
+
+/// Simple LCG for key selection (fast, deterministic, no rand dependency).
+#[inline]
+fn fast_rand(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+fn overwrite_key(i: usize) -> String {
+    format!("ow:{:010}", i)
+}
+
+/// A value whose first 8 bytes hold the write sequence number, so every
+/// overwrite stores different bytes and the last write can be checked.
+fn versioned_value(seq: u64) -> Value {
+    let mut bytes = vec![0x4f; VALUE_SIZE];
+    bytes[..8].copy_from_slice(&seq.to_le_bytes());
+    Value::Bytes(bytes)
+}
+
+fn value_seq(value: &Value) -> Option<u64> {
+    match value {
+        Value::Bytes(b) if b.len() >= 8 => Some(u64::from_le_bytes(b[..8].try_into().unwrap())),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct SpaceSample {
+    ops: usize,
+    disk_bytes: u64,
+    ops_per_sec: f64,
+}
+
+struct OverwriteResult {
+    keys: usize,
+    ops: usize,
+    logical_bytes: u64,
+    loaded_disk_bytes: u64,
+    samples: Vec<SpaceSample>,
+    elapsed: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    avg: Duration,
+    stale_reads: usize,
+}
+
+impl OverwriteResult {
+    fn ops_per_sec(&self) -> f64 {
+        self.ops as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn amplification(&self, disk_bytes: u64) -> f64 {
+        disk_bytes as f64 / self.logical_bytes.max(1) as f64
+    }
+
+    fn final_disk_bytes(&self) -> u64 {
+        self.samples.last().map(|s| s.disk_bytes).unwrap_or(self.loaded_disk_bytes)
+    }
+
+    /// Median amplification over the second half of the samples.
+    fn steady_state_amplification(&self) -> f64 {
+        let mut amps: Vec<f64> = self.samples[self.samples.len() / 2..]
+            .iter()
+            .map(|s| self.amplification(s.disk_bytes))
+            .collect();
+        if amps.is_empty() {
+            return self.amplification(self.loaded_disk_bytes);
+        }
+        amps.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        amps[amps.len() / 2]
+    }
+
+    /// Final on-disk size relative to the size halfway through the run.
+    fn second_half_growth(&self) -> f64 {
+        let midpoint = self
+            .samples
+            .get(self.samples.len().saturating_sub(1) / 2)
+            .map(|s| s.disk_bytes)
+            .unwrap_or(self.loaded_disk_bytes);
+        self.final_disk_bytes() as f64 / midpoint.max(1) as f64
+    }
+
+    fn reclaimed(&self) -> bool {
+        self.second_half_growth() <= UNBOUNDED_GROWTH
+    }
+
+    fn failures(&self) -> Vec<String> {
+        let mut failures = Vec::new();
+        if self.stale_reads > 0 {
+            failures.push(format!(
+                "{} of {} sampled keys did not return their last write",
+                self.stale_reads,
+                VERIFY_SAMPLE.min(self.keys)
+            ));
+        }
+        failures
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Measurement
+// ---------------------------------------------------------------------------
+
+fn disk_bytes(db: &BenchDb) -> u64 {
+    db.data_dir().map(dir_size_bytes).unwrap_or(0)
+}
+
+fn run_overwrite(db: &BenchDb, keys: usize, ops: usize, sample_points: usize, quiet: bool) -> OverwriteResult {
+    // Last sequence number written to each key; 0 is the initial load.
+    let mut last_seq = vec![0u64; keys];
+    for i in 0..keys {
+        db.db.kv_put(&overwrite_key(i), versioned_value(0)).unwrap();
+    }
+    let loaded_disk_bytes = disk_bytes(db);
+    let logical_bytes: u64 = (0..keys).map(|i| (overwrite_key(i).len() + VALUE_SIZE) as u64).sum();
+
+    let window = ops.div_ceil(sample_points).max(1);
+    let mut sampler = ReservoirSampler::with_seed(keys as u64);
+    let mut samples = Vec::with_capacity(sample_points);
+    let mut rng = 0x0f3e_a11c_u64;
+    let mut done = 0;
+    let mut elapsed = Duration::ZERO;
+
+    while done < ops {
+        let n = window.min(ops - done);
+        let window_start = Instant::now();
+        for _ in 0..n {
+            let k = (fast_rand(&mut rng) % keys as u64) as usize;
+            let seq = done as u64 + 1;
+            let start = Instant::now();
+            db.db.kv_put(&overwrite_key(k), versioned_value(seq)).unwrap();
+            sampler.record(start.elapsed());
+            last_seq[k] = seq;
+            done += 1;
+        }
+        let window_elapsed = window_start.elapsed();
+        elapsed += window_elapsed;
+
+        let sample = SpaceSample {
+            ops: done,
+            disk_bytes: disk_bytes(db),
+            ops_per_sec: n as f64 / window_elapsed.as_secs_f64(),
+        };
+        if !quiet {
+            eprintln!(
+                "  {:>10} overwrites: {:>10} on disk ({:.2}x), {} ops/sec",
+                fmt_num(done as u64),
+                fmt_mb(sample.disk_bytes),
+                sample.disk_bytes as f64 / logical_bytes.max(1) as f64,
+                fmt_num(sample.ops_per_sec as u64)
+            );
+        }
+        samples.push(sample);
+    }
+
+    let stride = (keys / VERIFY_SAMPLE).max(1);
+    let stale_reads = (0..keys)
+        .step_by(stride)
+        .take(VERIFY_SAMPLE)
+        .filter(|&i| {
+            let got = db.db.kv_get(&overwrite_key(i)).unwrap();
+            got.as_ref().and_then(value_seq) != Some(last_seq[i])
+        })
+        .count();
+
+    let mut latencies = sampler.into_samples();
+    latencies.sort_unstable();
+    let len = latencies.len().max(1);
+    let sum: Duration = latencies.iter().sum();
+    let pick = |pct: usize| latencies.get((len * pct / 100).min(len - 1)).copied().unwrap_or_default();
+
+    OverwriteResult {
+        keys,
+        ops,
+        logical_bytes,
+        loaded_disk_bytes,
+        samples,
+        elapsed,
+        p50: pick(50),
+        p95: pick(95),
+        p99: pick(99),
+        min: latencies.first().copied().unwrap_or_default(),
+        max: latencies.last().copied().unwrap_or_default(),
+        avg: sum / len as u32,
+        stale_reads,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_overwrite(recorder: &mut ResultRecorder, r: &OverwriteResult, mode: &DurabilityConfig) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("keys".into(), serde_json::json!(r.keys));
+    params.insert("overwrites".into(), serde_json::json!(r.ops));
+    params.insert("value_size".into(), serde_json::json!(VALUE_SIZE));
+    params.insert("logical_bytes".into(), serde_json::json!(r.logical_bytes));
+    params.insert("loaded_disk_bytes".into(), serde_json::json!(r.loaded_disk_bytes));
+    params.insert("final_disk_bytes".into(), serde_json::json!(r.final_disk_bytes()));
+    params.insert(
+        "steady_state_space_amplification".into(),
+        serde_json::json!(r.steady_state_amplification()),
+    );
+    params.insert("second_half_growth".into(), serde_json::json!(r.second_half_growth()));
+    params.insert("old_versions_reclaimed".into(), serde_json::json!(r.reclaimed()));
+    let sample_ops: Vec<usize> = r.samples.iter().map(|s| s.ops).collect();
+    let sample_disk: Vec<u64> = r.samples.iter().map(|s| s.disk_bytes).collect();
+    let sample_amp: Vec<f64> = r.samples.iter().map(|s| r.amplification(s.disk_bytes)).collect();
+    params.insert("sample_ops".into(), serde_json::json!(sample_ops));
+    params.insert("sample_disk_bytes".into(), serde_json::json!(sample_disk));
+    params.insert("sample_space_amplification".into(), serde_json::json!(sample_amp));
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/overwrite_space/{}keys/{}", r.keys, mode.label()),
+        category: "fill-level".to_string(),
+        parameters: params,
+        // Latency per overwrite, from a reservoir sample
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec()),
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg.as_nanos() as u64),
+            samples: Some(r.ops as u64),
+            fill_level: Some(r.keys),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(&r.failures()),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn fmt_mb(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+}
+
+fn print_summary(r: &OverwriteResult) {
+    eprintln!();
+    eprintln!("  Logical size:        {}", fmt_mb(r.logical_bytes));
+    eprintln!(
+        "  After load:          {} ({:.2}x)",
+        fmt_mb(r.loaded_disk_bytes),
+        r.amplification(r.loaded_disk_bytes)
+    );
+    eprintln!(
+        "  After overwrites:    {} ({:.2}x)",
+        fmt_mb(r.final_disk_bytes()),
+        r.amplification(r.final_disk_bytes())
+    );
+    eprintln!("  Steady-state amp:    {:.2}x", r.steady_state_amplification());
+    eprintln!(
+        "  Second-half growth:  {:.2}x{}",
+        r.second_half_growth(),
+        if r.reclaimed() { "" } else { "  <- old versions not reclaimed" }
+    );
+    eprintln!(
+        "  Overwrites:          {} ops/sec, p50 {:.2}us, p99 {:.2}us",
+        fmt_num(r.ops_per_sec() as u64),
+        r.p50.as_nanos() as f64 / 1_000.0,
+        r.p99.as_nanos() as f64 / 1_000.0
+    );
+}
+
+fn print_quiet(r: &OverwriteResult, mode: &DurabilityConfig) {
+    eprintln!(
+        "overwrite_space {} keys, {} ({}): steady-state amp={:.2}x, second-half growth={:.2}x",
+        fmt_num(r.keys as u64),
+        fmt_num(r.ops as u64),
+        mode.label(),
+        r.steady_state_amplification(),
+        r.second_half_growth(),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"durability\",\"keys\",\"ops\",\"disk_bytes\",\"space_amplification\",\"ops_sec\"");
+}
+
+fn print_csv_rows(r: &OverwriteResult, mode: &DurabilityConfig) {
+    for s in &r.samples {
+        println!(
+            "{},{},{},{},{:.3},{:.2}",
+            mode.label(),
+            r.keys,
+            s.ops,
+            s.disk_bytes,
+            r.amplification(s.disk_bytes),
+            s.ops_per_sec,
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: Vec<DurabilityConfig>,
+    keys: usize,
+    ops: usize,
+    samples: usize,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DISK_MODES.to_vec(),
+        keys: DEFAULT_KEYS,
+        ops: DEFAULT_OPS,
+        samples: DEFAULT_SAMPLES,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--keys" => config.keys = args.parse_value::<usize>().max(1),
+            "--ops" => config.ops = args.parse_value::<usize>().max(1),
+            "--samples" => config.samples = args.parse_value::<usize>().max(2),
+            _ => args.unknown_flag(),
+        }
+    }
+    if matches!(args.durability, Some(DurabilityConfig::Cache)) {
+        eprintln!("Cache mode keeps nothing on disk, so there is no space to measure");
+        std::process::exit(1);
+    }
+    config.durability = args.durability_modes(DISK_MODES);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Overwrite Space-Growth Benchmark ===");
+        eprintln!("Overwrites a fixed key set and samples on-disk size.");
+        eprintln!();
+        eprintln!(
+            "Keys: {}, overwrites: {}, {}B values, {} samples",
+            fmt_num(config.keys as u64),
+            fmt_num(config.ops as u64),
+            VALUE_SIZE,
+            config.samples
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+
+    for mode in &config.durability {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(mode.label()));
+        guard_params.insert("keys".into(), serde_json::json!(config.keys));
+        let guard_name = format!("fill-level/overwrite_space/{}keys/{}", config.keys, mode.label());
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            if !config.csv && !config.quiet {
+                eprintln!("--- durability: {} ---", mode.label());
+            }
+            let db = create_db(*mode);
+            let r = run_overwrite(&db, config.keys, config.ops, config.samples, config.csv || config.quiet);
+            record_overwrite(recorder, &r, mode);
+
+            if config.csv {
+                print_csv_rows(&r, mode);
+            } else if config.quiet {
+                print_quiet(&r, mode);
+            } else {
+                print_summary(&r);
+                eprintln!();
+            }
+            for failure in r.failures() {
+                eprintln!("  Validation: FAIL ({})", failure);
+            }
+        });
+    }
+
+    if !config.csv {
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,513 @@
+//! Version-Depth Benchmark for StrataDB
+//!
+//! Strata keeps every write as a version. This measures reads of heavily
+//! updated cells as their version chains grow (1 to 10K versions per cell by
+//! default). A small set of hot KV keys and state cells is overwritten up to
+//! each depth in turn, and at each depth it times:
+//!
+//! - `kv_get_latest` / `state_get_latest`: the current value
+//! - `kv_getv` / `state_getv`: the full version history
+//! - `kv_get_as_of_oldest` / `kv_get_as_of_middle`: a time-travel read
+//!   (`KvGet` with `as_of`) at the first version and at the middle of the chain
+//!
+//! Latest reads should not care how long the chain is. History reads are
+//! expected to grow with it; time-travel reads show whether an old version
+//! is found by seek or by walking the chain. After the last depth the
+//! log-log slope of p50 against depth is reported, near 0 for O(1) and near
+//! 1 when the read is proportional to the chain.
+//!
+//! Every read is checked against the value written at that version, and a
+//! history that is shorter than the number of writes is recorded as a
+//! validation failure.
+//!
+//! Run:    `cargo bench --bench version_depth`
+//! Quick:  `cargo bench --bench version_depth -- -q`
+//! CSV:    `cargo bench --bench version_depth -- --csv`
+//! Custom: `cargo bench --bench version_depth -- --depths 1,100,10000,100000 --cells 4`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{Command, Output, Session, Value, VersionedValue};
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_DEPTHS: &[usize] = &[1, 10, 100, 1_000, 10_000];
+const DEFAULT_CELLS: usize = 10;
+const DEFAULT_SAMPLES: usize = 1_000;
+/// Versions returned per history sample budget: a 10K-deep chain gets 100
+/// `getv` samples, a short one gets `--samples`.
+const HISTORY_VERSION_BUDGET: usize = 1_000_000;
+const MIN_HISTORY_SAMPLES: usize = 10;
+
+/// Operations measured at each depth, in output order.
+const OPS: &[&str] = &[
+    "kv_get_latest",
+    "kv_getv",
+    "kv_get_as_of_oldest",
+    "kv_get_as_of_middle",
+    "state_get_latest",
+    "state_getv",
+];
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct OpResult {
+    name: &'static str,
+    depth: usize,
+    samples: usize,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl OpResult {
+    fn from_samples(name: &'static str, depth: usize, mut latencies: Vec<Duration>) -> Self {
+        latencies.sort_unstable();
+        let len = latencies.len();
+        let sum: Duration = latencies.iter().sum();
+        OpResult {
+            name,
+            depth,
+            samples: len,
+            avg: sum / len as u32,
+            p50: latencies[len * 50 / 100],
+            p95: latencies[(len * 95 / 100).min(len - 1)],
+            p99: latencies[(len * 99 / 100).min(len - 1)],
+            min: latencies[0],
+            max: latencies[len - 1],
+        }
+    }
+
+    fn ops_per_sec(&self) -> f64 {
+        1.0 / self.avg.as_secs_f64().max(1e-12)
+    }
+}
+
+/// Log-log slope of p50 against depth between the shallowest and deepest
+/// chain: ~0 means independent of depth, ~1 means proportional to it.
+fn scaling_exponent(results: &[OpResult]) -> Option<f64> {
+    let (first, last) = (results.first()?, results.last()?);
+    if last.depth <= first.depth {
+        return None;
+    }
+    let t = last.p50.as_secs_f64() / first.p50.as_secs_f64().max(1e-9);
+    let n = last.depth as f64 / first.depth as f64;
+    Some(t.ln() / n.ln())
+}
+
+fn scaling_verdict(exponent: f64) -> &'static str {
+    if exponent < 0.2 {
+        "O(1)"
+    } else if exponent > 0.8 {
+        "proportional to depth"
+    } else {
+        "sublinear"
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Cells and measurement
+// ---------------------------------------------------------------------------
+
+fn kv_cell(c: usize) -> String {
+    format!("hist:{:04}", c)
+}
+
+fn state_cell(c: usize) -> String {
+    format!("hist_cell:{:04}", c)
+}
+
+/// Overwrite every cell until it holds `to` versions. Version `v` (0-based)
+/// of every cell holds `Value::Int(v)`.
+fn deepen(db: &BenchDb, cells: usize, from: usize, to: usize) {
+    for v in from..to {
+        for c in 0..cells {
+            db.db.kv_put(&kv_cell(c), Value::Int(v as i64)).unwrap();
+            db.db.state_set(&state_cell(c), Value::Int(v as i64)).unwrap();
+        }
+    }
+}
+
+/// Timestamp of the version that holds `Value::Int(v)`, looked up by value
+/// so the order `getv` returns versions in doesn't matter.
+fn version_timestamp(history: &[VersionedValue], v: usize) -> Option<u64> {
+    history
+        .iter()
+        .find(|h| h.value == Value::Int(v as i64))
+        .map(|h| h.timestamp)
+}
+
+/// Time-travel read of `key` as of `timestamp`.
+fn kv_get_as_of(session: &mut Session, key: &str, timestamp: u64) -> Option<Value> {
+    match session.execute(Command::KvGet {
+        branch: None,
+        space: None,
+        key: key.to_string(),
+        as_of: Some(timestamp),
+    }) {
+        Ok(Output::Maybe(value)) => value,
+        _ => None,
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let out = f();
+    (start.elapsed(), out)
+}
+
+/// Counts reads that did not return what was written, per check.
+#[derive(Default)]
+struct Mismatches(Vec<(&'static str, usize)>);
+
+impl Mismatches {
+    fn check(&mut self, what: &'static str, ok: bool) {
+        if ok {
+            return;
+        }
+        match self.0.iter_mut().find(|(w, _)| *w == what) {
+            Some((_, n)) => *n += 1,
+            None => self.0.push((what, 1)),
+        }
+    }
+
+    fn into_failures(self, depth: usize) -> Vec<String> {
+        self.0
+            .into_iter()
+            .map(|(what, n)| format!("{} {} reads at depth {} were wrong", n, what, depth))
+            .collect()
+    }
+}
+
+/// Run every entry in `OPS` against cells `depth` versions deep. Returns one
+/// result per op plus any validation failures.
+fn measure_depth(db: &BenchDb, cells: usize, depth: usize, samples: usize) -> (Vec<OpResult>, Vec<String>) {
+    let history_samples = (HISTORY_VERSION_BUDGET / depth).clamp(MIN_HISTORY_SAMPLES, samples);
+    let latest = Value::Int(depth as i64 - 1);
+    let middle = depth / 2;
+    let mut latencies: Vec<Vec<Duration>> = vec![Vec::new(); OPS.len()];
+    let mut mismatches = Mismatches::default();
+    let mut failures = Vec::new();
+
+    // Time-travel targets are looked up before timing
+    let mut targets = Vec::with_capacity(cells);
+    for c in 0..cells {
+        let history = db.db.kv_getv(&kv_cell(c)).unwrap().unwrap_or_default();
+        if history.len() < depth {
+            failures.push(format!(
+                "{} kept {} of {} versions",
+                kv_cell(c),
+                history.len(),
+                depth
+            ));
+        }
+        targets.push([version_timestamp(&history, 0), version_timestamp(&history, middle)]);
+    }
+
+    for s in 0..samples {
+        let key = kv_cell(s % cells);
+        let (t, value) = timed(|| db.db.kv_get(&key).unwrap());
+        latencies[0].push(t);
+        mismatches.check("kv_get_latest", value.as_ref() == Some(&latest));
+    }
+
+    for s in 0..history_samples {
+        let key = kv_cell(s % cells);
+        let (t, history) = timed(|| db.db.kv_getv(&key).unwrap());
+        latencies[1].push(t);
+        mismatches.check("kv_getv", history.map_or(0, |h| h.len()) >= depth);
+    }
+
+    let mut session = db.db.session();
+    for (k, (slot, version)) in [(2, 0), (3, middle)].into_iter().enumerate() {
+        for s in 0..samples {
+            let c = s % cells;
+            let Some(timestamp) = targets[c][k] else {
+                continue;
+            };
+            let key = kv_cell(c);
+            let (t, value) = timed(|| kv_get_as_of(&mut session, &key, timestamp));
+            latencies[slot].push(t);
+            mismatches.check(OPS[slot], value == Some(Value::Int(version as i64)));
+        }
+    }
+
+    for s in 0..samples {
+        let cell = state_cell(s % cells);
+        let (t, value) = timed(|| db.db.state_get(&cell).unwrap());
+        latencies[4].push(t);
+        mismatches.check("state_get_latest", value.as_ref() == Some(&latest));
+    }
+
+    for s in 0..history_samples {
+        let cell = state_cell(s % cells);
+        let (t, history) = timed(|| db.db.state_getv(&cell).unwrap());
+        latencies[5].push(t);
+        mismatches.check("state_getv", history.map_or(0, |h| h.len()) >= depth);
+    }
+
+    failures.extend(mismatches.into_failures(depth));
+    let results = OPS
+        .iter()
+        .zip(latencies)
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(&name, l)| OpResult::from_samples(name, depth, l))
+        .collect();
+    (results, failures)
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_op_result(
+    recorder: &mut ResultRecorder,
+    r: &OpResult,
+    config: &Config,
+    exponent: Option<f64>,
+    failures: &[String],
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    params.insert("depth".into(), serde_json::json!(r.depth));
+    params.insert("cells".into(), serde_json::json!(config.cells));
+    params.insert(
+        "ns_per_version".into(),
+        serde_json::json!(r.p50.as_nanos() as f64 / r.depth as f64),
+    );
+    if let Some(e) = exponent {
+        params.insert("scaling_exponent".into(), serde_json::json!(e));
+        params.insert("scaling".into(), serde_json::json!(scaling_verdict(e)));
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/version_depth/{}/{}versions", r.name, r.depth),
+        category: "fill-level".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec()),
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg.as_nanos() as u64),
+            samples: Some(r.samples as u64),
+            fill_level: Some(r.depth),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(failures),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn duration_us(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000.0
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>10}  {:>8}  {:>11}  {:>11}  {:>11}  {:>11}",
+        "versions", "samples", "avg", "p50", "p99", "max"
+    );
+}
+
+fn print_table_row(r: &OpResult) {
+    eprintln!(
+        "  {:>10}  {:>8}  {:>9.2}us  {:>9.2}us  {:>9.2}us  {:>9.2}us",
+        fmt_num(r.depth as u64),
+        r.samples,
+        duration_us(r.avg),
+        duration_us(r.p50),
+        duration_us(r.p99),
+        duration_us(r.max),
+    );
+}
+
+fn print_quiet(r: &OpResult) {
+    eprintln!(
+        "{} @ {} versions: p50={:.2}us",
+        r.name,
+        fmt_num(r.depth as u64),
+        duration_us(r.p50),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"test\",\"versions\",\"avg_us\",\"p50_us\",\"p95_us\",\"p99_us\",\"max_us\"");
+}
+
+fn print_csv_row(r: &OpResult) {
+    println!(
+        "\"{}\",{},{:.3},{:.3},{:.3},{:.3},{:.3}",
+        r.name,
+        r.depth,
+        duration_us(r.avg),
+        duration_us(r.p50),
+        duration_us(r.p95),
+        duration_us(r.p99),
+        duration_us(r.max),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: DurabilityConfig,
+    depths: Vec<usize>,
+    cells: usize,
+    samples: usize,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DurabilityConfig::Cache,
+        depths: DEFAULT_DEPTHS.to_vec(),
+        cells: DEFAULT_CELLS,
+        samples: DEFAULT_SAMPLES,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--depths" => config.depths = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            "--cells" => config.cells = args.parse_value::<usize>().max(1),
+            "--samples" => config.samples = args.parse_value::<usize>().max(MIN_HISTORY_SAMPLES),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    // Chains are deepened incrementally, shallowest first
+    config.depths.sort_unstable();
+    config.depths.dedup();
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Version-Depth Benchmark ===");
+        eprintln!("Measures latest, history, and time-travel reads as version chains grow.");
+        eprintln!();
+        eprintln!(
+            "Depths: {:?} versions, {} cells, {} samples, {} mode",
+            config.depths,
+            config.cells,
+            config.samples,
+            config.durability.label()
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+    let db = create_db(config.durability);
+    let mut by_op: Vec<Vec<OpResult>> = OPS.iter().map(|_| Vec::new()).collect();
+    let mut failures: Vec<Vec<String>> = Vec::new();
+    let mut current = 0;
+
+    for &depth in &config.depths {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        guard_params.insert("depth".into(), serde_json::json!(depth));
+        let guard_name = format!("fill-level/version_depth/{}versions", depth);
+        let measured = recorder.guard(&guard_name, guard_params, |_| {
+            if !config.csv && !config.quiet {
+                eprintln!("  deepening {} cells to {} versions...", config.cells, fmt_num(depth as u64));
+            }
+            deepen(&db, config.cells, current, depth);
+            current = depth;
+
+            measure_depth(&db, config.cells, depth, config.samples)
+        });
+        // Failures are looked up by depth index, so a depth that panicked
+        // still gets an (empty) entry
+        let (results, depth_failures) = measured.unwrap_or_default();
+        for failure in &depth_failures {
+            eprintln!("  Validation: FAIL ({})", failure);
+        }
+        for r in results {
+            if let Some(slot) = OPS.iter().position(|&op| op == r.name) {
+                by_op[slot].push(r);
+            }
+        }
+        failures.push(depth_failures);
+    }
+
+    for results in &by_op {
+        let exponent = scaling_exponent(results);
+        for r in results {
+            let depth_failures = config
+                .depths
+                .iter()
+                .position(|&d| d == r.depth)
+                .map(|i| failures[i].as_slice())
+                .unwrap_or_default();
+            record_op_result(&mut recorder, r, &config, exponent, depth_failures);
+        }
+
+        if config.csv {
+            for r in results {
+                print_csv_row(r);
+            }
+        } else if config.quiet {
+            for r in results {
+                print_quiet(r);
+            }
+        } else if let Some(first) = results.first() {
+            eprintln!();
+            eprintln!("--- {} ---", first.name);
+            print_table_header();
+            for r in results {
+                print_table_row(r);
+            }
+            if let Some(e) = exponent {
+                eprintln!("  scaling exponent {:.2}: {}", e, scaling_verdict(e));
+            }
+        }
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,444 @@
+//! Wide-Object Benchmark for StrataDB
+//!
+//! Measures JSON documents with many top-level fields (10, 1K, and 100K by
+//! default). `redis_compare` emulates HSET with one key per field; storing a
+//! hash as one wide document is the alternative, and it only works if field
+//! fan-out is cheap. At each width it times:
+//!
+//! - `json_set_doc` / `json_get_doc`: the whole document at `$`
+//! - `json_get_field`: one field (`$.f000123`)
+//! - `json_set_field`: update one existing field in place
+//! - `json_add_field`: add a new field to the document
+//!
+//! Whole-document operations are expected to grow with the width. Single-field
+//! operations should not: after the last width the log-log slope of p50
+//! against width is reported, near 0 for O(1) and near 1 when a field update
+//! rewrites the whole document.
+//!
+//! Every whole-document read must return all fields and every field read
+//! must find its field; anything else is recorded as a validation failure.
+//!
+//! Run:    `cargo bench --bench wide_object`
+//! Quick:  `cargo bench --bench wide_object -- -q`
+//! CSV:    `cargo bench --bench wide_object -- --csv`
+//! Custom: `cargo bench --bench wide_object -- --fields 10,1000,10000 --samples 500`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_FIELDS: &[usize] = &[10, 1_000, 100_000];
+/// Samples for single-field operations.
+const DEFAULT_SAMPLES: usize = 1_000;
+/// Fields touched per whole-document sample budget: a 100K-field document
+/// gets 10 samples, a 10-field one gets `--samples`.
+const DOC_FIELD_BUDGET: usize = 1_000_000;
+const MIN_DOC_SAMPLES: usize = 10;
+const DOC_KEY: &str = "wide:0";
+
+/// Operations measured at each width, in output order.
+const OPS: &[&str] = &[
+    "json_set_doc",
+    "json_get_doc",
+    "json_get_field",
+    "json_set_field",
+    "json_add_field",
+];
+
+// ---------------------------------------------------------------------------
+// Result type
+// ---------------------------------------------------------------------------
+
+struct OpResult {
+    name: &'static str,
+    fields: usize,
+    samples: usize,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl OpResult {
+    fn from_samples(name: &'static str, fields: usize, mut latencies: Vec<Duration>) -> Self {
+        latencies.sort_unstable();
+        let len = latencies.len();
+        let sum: Duration = latencies.iter().sum();
+        OpResult {
+            name,
+            fields,
+            samples: len,
+            avg: sum / len as u32,
+            p50: latencies[len * 50 / 100],
+            p95: latencies[(len * 95 / 100).min(len - 1)],
+            p99: latencies[(len * 99 / 100).min(len - 1)],
+            min: latencies[0],
+            max: latencies[len - 1],
+        }
+    }
+
+    fn ops_per_sec(&self) -> f64 {
+        1.0 / self.avg.as_secs_f64().max(1e-12)
+    }
+}
+
+/// Log-log slope of p50 against width between the narrowest and widest
+/// document: ~0 means independent of width, ~1 means proportional to it.
+fn scaling_exponent(results: &[OpResult]) -> Option<f64> {
+    let (first, last) = (results.first()?, results.last()?);
+    if last.fields <= first.fields {
+        return None;
+    }
+    let t = last.p50.as_secs_f64() / first.p50.as_secs_f64().max(1e-9);
+    let n = last.fields as f64 / first.fields as f64;
+    Some(t.ln() / n.ln())
+}
+
+fn scaling_verdict(exponent: f64) -> &'static str {
+    if exponent < 0.2 {
+        "O(1)"
+    } else if exponent > 0.8 {
+        "proportional to width"
+    } else {
+        "sublinear"
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Documents and measurement
+// ---------------------------------------------------------------------------
+
+fn field_name(i: usize) -> String {
+    format!("f{:06}", i)
+}
+
+/// A flat document with `fields` int fields, `f000000` to `f{fields-1}`.
+fn wide_document(fields: usize, seed: i64) -> Value {
+    Value::Object(
+        (0..fields)
+            .map(|i| (field_name(i), Value::Int(seed + i as i64)))
+            .collect(),
+    )
+}
+
+fn field_count(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => map.len(),
+        _ => 0,
+    }
+}
+
+/// Simple LCG for field selection (fast, deterministic, no rand dependency).
+#[inline]
+fn fast_rand(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let out = f();
+    (start.elapsed(), out)
+}
+
+/// Run every entry in `OPS` against a fresh `fields`-wide document. Returns
+/// one result per op plus any validation failures.
+fn measure_width(db: &BenchDb, fields: usize, samples: usize) -> (Vec<OpResult>, Vec<String>) {
+    let doc_samples = (DOC_FIELD_BUDGET / fields).clamp(MIN_DOC_SAMPLES, samples);
+    let mut latencies: Vec<Vec<Duration>> = vec![Vec::new(); OPS.len()];
+    let mut failures = Vec::new();
+    let mut rng = fields as u64;
+
+    // Documents are built before timing so only the write is measured
+    let docs: Vec<Value> = (0..doc_samples).map(|s| wide_document(fields, s as i64)).collect();
+    for doc in docs {
+        let (t, _) = timed(|| db.db.json_set(DOC_KEY, "$", doc).unwrap());
+        latencies[0].push(t);
+    }
+
+    let mut short_reads = 0;
+    for _ in 0..doc_samples {
+        let (t, doc) = timed(|| db.db.json_get(DOC_KEY, "$").unwrap());
+        latencies[1].push(t);
+        if doc.as_ref().map(field_count) != Some(fields) {
+            short_reads += 1;
+        }
+    }
+    if short_reads > 0 {
+        failures.push(format!("{} document reads did not return {} fields", short_reads, fields));
+    }
+
+    let mut missing = 0;
+    for _ in 0..samples {
+        let path = format!("$.{}", field_name(fast_rand(&mut rng) as usize % fields));
+        let (t, value) = timed(|| db.db.json_get(DOC_KEY, &path).unwrap());
+        latencies[2].push(t);
+        if value.is_none() {
+            missing += 1;
+        }
+    }
+    if missing > 0 {
+        failures.push(format!("{} of {} field reads found nothing", missing, samples));
+    }
+
+    for s in 0..samples {
+        let path = format!("$.{}", field_name(fast_rand(&mut rng) as usize % fields));
+        let (t, _) = timed(|| db.db.json_set(DOC_KEY, &path, Value::Int(-(s as i64))).unwrap());
+        latencies[3].push(t);
+    }
+
+    for s in 0..samples {
+        let path = format!("$.{}", field_name(fields + s));
+        let (t, _) = timed(|| db.db.json_set(DOC_KEY, &path, Value::Int(s as i64)).unwrap());
+        latencies[4].push(t);
+    }
+
+    let results = OPS
+        .iter()
+        .zip(latencies)
+        .map(|(&name, l)| OpResult::from_samples(name, fields, l))
+        .collect();
+    (results, failures)
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_op_result(
+    recorder: &mut ResultRecorder,
+    r: &OpResult,
+    mode: &DurabilityConfig,
+    exponent: Option<f64>,
+    failures: &[String],
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("fields".into(), serde_json::json!(r.fields));
+    params.insert(
+        "ns_per_field".into(),
+        serde_json::json!(r.p50.as_nanos() as f64 / r.fields as f64),
+    );
+    if let Some(e) = exponent {
+        params.insert("scaling_exponent".into(), serde_json::json!(e));
+        params.insert("scaling".into(), serde_json::json!(scaling_verdict(e)));
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("fill-level/wide_object/{}/{}fields", r.name, r.fields),
+        category: "fill-level".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec()),
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg.as_nanos() as u64),
+            samples: Some(r.samples as u64),
+            fill_level: Some(r.fields),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(failures),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn duration_us(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000.0
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>10}  {:>8}  {:>11}  {:>11}  {:>11}  {:>11}",
+        "fields", "samples", "avg", "p50", "p99", "max"
+    );
+}
+
+fn print_table_row(r: &OpResult) {
+    eprintln!(
+        "  {:>10}  {:>8}  {:>9.2}us  {:>9.2}us  {:>9.2}us  {:>9.2}us",
+        fmt_num(r.fields as u64),
+        r.samples,
+        duration_us(r.avg),
+        duration_us(r.p50),
+        duration_us(r.p99),
+        duration_us(r.max),
+    );
+}
+
+fn print_quiet(r: &OpResult) {
+    eprintln!(
+        "{} @ {} fields: p50={:.2}us",
+        r.name,
+        fmt_num(r.fields as u64),
+        duration_us(r.p50),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"test\",\"fields\",\"avg_us\",\"p50_us\",\"p95_us\",\"p99_us\",\"max_us\"");
+}
+
+fn print_csv_row(r: &OpResult) {
+    println!(
+        "\"{}\",{},{:.3},{:.3},{:.3},{:.3},{:.3}",
+        r.name,
+        r.fields,
+        duration_us(r.avg),
+        duration_us(r.p50),
+        duration_us(r.p95),
+        duration_us(r.p99),
+        duration_us(r.max),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: DurabilityConfig,
+    fields: Vec<usize>,
+    samples: usize,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DurabilityConfig::Cache,
+        fields: DEFAULT_FIELDS.to_vec(),
+        samples: DEFAULT_SAMPLES,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--fields" => config.fields = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            "--samples" => config.samples = args.parse_value::<usize>().max(MIN_DOC_SAMPLES),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    // The scaling exponent compares the narrowest and widest documents
+    config.fields.sort_unstable();
+    config.fields.dedup();
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Wide-Object Benchmark ===");
+        eprintln!("Measures whole-document and single-field JSON operations as documents get wider.");
+        eprintln!();
+        eprintln!(
+            "Widths: {:?} fields, {} field samples, {} mode",
+            config.fields,
+            config.samples,
+            config.durability.label()
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("fill-level");
+    let mut by_op: Vec<Vec<OpResult>> = OPS.iter().map(|_| Vec::new()).collect();
+    let mut failures: Vec<Vec<String>> = Vec::new();
+
+    for &fields in &config.fields {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        guard_params.insert("fields".into(), serde_json::json!(fields));
+        let guard_name = format!("fill-level/wide_object/{}fields", fields);
+        let measured = recorder.guard(&guard_name, guard_params, |_| {
+            if !config.csv && !config.quiet {
+                eprintln!("  measuring {}-field documents...", fmt_num(fields as u64));
+            }
+            let db = create_db(config.durability);
+            measure_width(&db, fields, config.samples)
+        });
+        // Failures are zipped with each op's results, so a panicked width
+        // is left out of both
+        let Some((results, width_failures)) = measured else {
+            continue;
+        };
+        for failure in &width_failures {
+            eprintln!("  Validation: FAIL ({})", failure);
+        }
+        failures.push(width_failures);
+        for (op, r) in by_op.iter_mut().zip(results) {
+            op.push(r);
+        }
+    }
+
+    for results in &by_op {
+        let exponent = scaling_exponent(results);
+        for (r, width_failures) in results.iter().zip(&failures) {
+            record_op_result(&mut recorder, r, &config.durability, exponent, width_failures);
+        }
+
+        if config.csv {
+            for r in results {
+                print_csv_row(r);
+            }
+        } else if config.quiet {
+            for r in results {
+                print_quiet(r);
+            }
+        } else if let Some(first) = results.first() {
+            eprintln!();
+            eprintln!("--- {} ---", first.name);
+            print_table_header();
+            for r in results {
+                print_table_row(r);
+            }
+            if let Some(e) = exponent {
+                eprintln!("  scaling exponent {:.2}: {}", e, scaling_verdict(e));
+            }
+        }
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
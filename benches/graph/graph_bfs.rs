@@ -13,6 +13,34 @@
 //! CSV:           `cargo bench --bench graph_bfs -- --csv`
 //! Custom data:   `cargo bench --bench graph_bfs -- --dataset path/to/ldbc/dir`
 //! Strata only:   `cargo bench --bench graph_bfs -- --strata-only`
+//! Directed:      `cargo bench --bench graph_bfs -- --direction out`
+//! Multi-source:  `cargo bench --bench graph_bfs -- --sources 1,5,9`
+//! Disk-backed:   `cargo bench --bench graph_bfs -- --durability standard`
+//!                `cargo bench --bench graph_bfs -- --sources path/to/sources.txt`
+//!
+//! As in the Graphalytics spec, BFS can be run from several sources. Each
+//! source gets `--runs` timed runs and its own result; an aggregate result
+//! (under the single-source name) reports the mean EVPS across sources, with
+//! its standard deviation as a parameter. The dataset's LDBC reference only
+//! covers its own source, so other sources are validated against petgraph.
+//!
+//! `--direction` picks which edges the traversal follows: `both` (default)
+//! treats every edge as undirected, `out` and `in` follow edges as written in
+//! the `.e` file or in reverse, and need a directed dataset. petgraph runs
+//! the same traversal on an undirected or directed graph to match. The LDBC
+//! reference follows outgoing edges on a directed dataset and all edges on an
+//! undirected one, so it is only checked when `--direction` matches.
+//!
+//! The load phase also records each engine's footprint: RSS growth across
+//! the load, and for disk-backed `--durability` modes the size of Strata's
+//! database directory, each divided by |E| as bytes per edge. The dataset is
+//! resident before either load, so only the engine's copy counts. Strata
+//! loads first; petgraph can reuse memory the allocator kept from it, so its
+//! RSS growth can read low.
+//!
+//! Repeated edges in the `.e` file are dropped before either engine loads
+//! the graph, and self-loops are kept; see `ldbc.rs` for the full edge
+//! semantics. |E| in EVPS is the edge count after that step.
 
 #[allow(unused)]
 #[path = "../harness/mod.rs"]
@@ -22,12 +50,15 @@ mod harness;
 mod ldbc;
 
 use harness::recorder::ResultRecorder;
-use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
-use ldbc::{petgraph_bfs, BfsReference, LdbcDataset, UNREACHABLE};
-use std::collections::HashMap;
+use harness::{create_db, dir_size_bytes, print_hardware_info, read_rss_bytes, BenchDb, DurabilityConfig};
+use ldbc::{petgraph_bfs, petgraph_bfs_directed, BfsReference, LdbcDataset, UNREACHABLE};
+use petgraph::graph::{DiGraph, NodeIndex, UnGraph};
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::time::Instant;
-use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult};
+use strata_benchmarks::fmt::{fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
 
 // ---------------------------------------------------------------------------
 // Default parameters
@@ -43,10 +74,49 @@ fn default_dataset_dir() -> PathBuf {
 // CLI configuration
 // ---------------------------------------------------------------------------
 
+/// Which edges a BFS follows.
+#[derive(Clone, Copy, PartialEq)]
+enum TraversalDirection {
+    Out,
+    In,
+    Both,
+}
+
+impl TraversalDirection {
+    fn label(self) -> &'static str {
+        match self {
+            TraversalDirection::Out => "out",
+            TraversalDirection::In => "in",
+            TraversalDirection::Both => "both",
+        }
+    }
+
+    /// Direction argument to `graph_bfs`.
+    fn strata_direction(self) -> &'static str {
+        match self {
+            TraversalDirection::Out => "outgoing",
+            TraversalDirection::In => "incoming",
+            TraversalDirection::Both => "both",
+        }
+    }
+
+    /// The traversal the LDBC reference output was computed with.
+    fn ldbc(dataset: &LdbcDataset) -> Self {
+        if dataset.directed {
+            TraversalDirection::Out
+        } else {
+            TraversalDirection::Both
+        }
+    }
+}
+
 struct Config {
     dataset: PathBuf,
-    source: Option<u64>,
+    /// Source vertices; empty = the dataset's BFS source.
+    sources: Vec<u64>,
     runs: usize,
+    direction: TraversalDirection,
+    durability: DurabilityConfig,
     validate_only: bool,
     no_validate: bool,
     csv: bool,
@@ -55,11 +125,13 @@ struct Config {
 }
 
 fn parse_args() -> Config {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args = harness::cli::Args::parse();
     let mut config = Config {
         dataset: default_dataset_dir(),
-        source: None,
+        sources: Vec::new(),
         runs: DEFAULT_RUNS,
+        direction: TraversalDirection::Both,
+        durability: DurabilityConfig::Cache,
         validate_only: false,
         no_validate: false,
         csv: false,
@@ -67,40 +139,78 @@ fn parse_args() -> Config {
         strata_only: false,
     };
 
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
-            "--dataset" => {
-                i += 1;
-                if i < args.len() {
-                    config.dataset = PathBuf::from(&args[i]);
-                }
-            }
-            "--source" => {
-                i += 1;
-                if i < args.len() {
-                    config.source = args[i].parse().ok();
-                }
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--dataset" => config.dataset = PathBuf::from(args.value()),
+            "--source" => config.sources = vec![args.parse_value()],
+            "--sources" => {
+                let v = args.value();
+                config.sources = parse_sources(&v).unwrap_or_else(|e| {
+                    eprintln!("Invalid --sources: {}", e);
+                    std::process::exit(1);
+                });
             }
-            "--runs" => {
-                i += 1;
-                if i < args.len() {
-                    config.runs = args[i].parse::<usize>().unwrap_or(DEFAULT_RUNS).max(1);
-                }
+            "--runs" => config.runs = args.parse_value::<usize>().max(1),
+            "--direction" => {
+                let v = args.value();
+                config.direction = match v.as_str() {
+                    "out" => TraversalDirection::Out,
+                    "in" => TraversalDirection::In,
+                    "both" => TraversalDirection::Both,
+                    other => {
+                        eprintln!("Invalid --direction '{}': expected out, in, or both", other);
+                        std::process::exit(1);
+                    }
+                };
             }
             "--validate-only" => config.validate_only = true,
             "--no-validate" => config.no_validate = true,
-            "--csv" => config.csv = true,
-            "-q" => config.quiet = true,
             "--strata-only" => config.strata_only = true,
-            _ => {}
+            _ => args.unknown_flag(),
         }
-        i += 1;
     }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
 
     config
 }
 
+/// Parse `--sources`: a comma-separated list of vertex IDs, or a file with
+/// one vertex ID per line (`#` starts a comment). Repeats are dropped.
+fn parse_sources(arg: &str) -> Result<Vec<u64>, String> {
+    let parsed = match arg
+        .split(',')
+        .map(|s| s.trim().parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(list) => list,
+        Err(_) => {
+            let content = std::fs::read_to_string(arg)
+                .map_err(|e| format!("failed to read {}: {}", arg, e))?;
+            content
+                .lines()
+                .filter_map(|l| l.split('#').next()?.split_whitespace().next())
+                .map(|id| {
+                    id.parse::<u64>()
+                        .map_err(|e| format!("bad source vertex '{}': {}", id, e))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    let mut sources = Vec::with_capacity(parsed.len());
+    for source in parsed {
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+    if sources.is_empty() {
+        return Err(format!("no source vertices in '{}'", arg));
+    }
+    Ok(sources)
+}
+
 // ---------------------------------------------------------------------------
 // Graph loading (Strata)
 // ---------------------------------------------------------------------------
@@ -132,6 +242,49 @@ fn load_graph(db: &BenchDb, dataset: &LdbcDataset) -> std::time::Duration {
     start.elapsed()
 }
 
+/// Load time and footprint of one engine's copy of the graph.
+struct LoadPhase {
+    time: std::time::Duration,
+    /// RSS growth across the load; `None` where RSS can't be read.
+    rss_bytes: Option<u64>,
+    /// Database directory size after the load; `None` for cache mode and
+    /// petgraph.
+    disk_bytes: Option<u64>,
+}
+
+impl LoadPhase {
+    /// Run `load`, measuring its time and RSS growth.
+    fn measure<T>(load: impl FnOnce() -> T) -> (T, Self) {
+        let rss_before = read_rss_bytes();
+        let start = Instant::now();
+        let loaded = load();
+        let time = start.elapsed();
+        let rss_bytes = rss_before
+            .zip(read_rss_bytes())
+            .map(|(before, after)| after.saturating_sub(before));
+        let phase = LoadPhase {
+            time,
+            rss_bytes,
+            disk_bytes: None,
+        };
+        (loaded, phase)
+    }
+}
+
+/// `bytes` over the edge count, as bytes per edge.
+fn per_edge(bytes: Option<u64>, edges: usize) -> Option<f64> {
+    bytes.filter(|_| edges > 0).map(|b| b as f64 / edges as f64)
+}
+
+/// `1,234,567 (72.6 B/edge)`, or `-` when not measured.
+fn fmt_footprint(bytes: Option<u64>, edges: usize) -> String {
+    match (bytes, per_edge(bytes, edges)) {
+        (Some(b), Some(pe)) => format!("{} ({:.1} B/edge)", fmt_num(b), pe),
+        (Some(b), None) => fmt_num(b),
+        _ => "-".to_string(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // BFS execution (Strata)
 // ---------------------------------------------------------------------------
@@ -141,7 +294,7 @@ struct BfsRun {
     depths: HashMap<String, usize>,
 }
 
-fn run_bfs(db: &BenchDb, source: u64) -> BfsRun {
+fn run_bfs(db: &BenchDb, source: u64, direction: TraversalDirection) -> BfsRun {
     let start = Instant::now();
     let result = db
         .db
@@ -151,7 +304,7 @@ fn run_bfs(db: &BenchDb, source: u64) -> BfsRun {
             usize::MAX,
             None,
             None,
-            Some("both"),
+            Some(direction.strata_direction()),
         )
         .expect("graph_bfs failed");
     let elapsed = start.elapsed();
@@ -297,32 +450,258 @@ fn compute_stats(times: &mut Vec<std::time::Duration>, total_elements: f64) -> R
     }
 }
 
+/// Mean and sample standard deviation of per-source EVPS.
+fn evps_spread(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, var.sqrt())
+}
+
 // ---------------------------------------------------------------------------
-// Output formatters
+// Per-source runs
 // ---------------------------------------------------------------------------
 
-fn fmt_num(n: u64) -> String {
-    let s = n.to_string();
-    let mut result = String::new();
-    for (i, c) in s.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
+/// petgraph baseline graph for the chosen `--direction`.
+enum PetgraphGraph {
+    Undirected(UnGraph<(), ()>),
+    Directed(DiGraph<(), ()>, Direction),
+}
+
+impl PetgraphGraph {
+    fn build(dataset: &LdbcDataset, direction: TraversalDirection) -> (Self, HashMap<u64, NodeIndex>) {
+        let directed = |dir| {
+            let (graph, id_map) = dataset.to_petgraph_directed();
+            (PetgraphGraph::Directed(graph, dir), id_map)
+        };
+        match direction {
+            TraversalDirection::Out => directed(Direction::Outgoing),
+            TraversalDirection::In => directed(Direction::Incoming),
+            TraversalDirection::Both => {
+                let (graph, id_map) = dataset.to_petgraph();
+                (PetgraphGraph::Undirected(graph), id_map)
+            }
         }
-        result.push(c);
     }
-    result.chars().rev().collect()
+
+    fn bfs(&self, source: NodeIndex) -> HashMap<NodeIndex, usize> {
+        match self {
+            PetgraphGraph::Undirected(graph) => petgraph_bfs(graph, source),
+            PetgraphGraph::Directed(graph, dir) => petgraph_bfs_directed(graph, source, *dir),
+        }
+    }
+}
+
+/// petgraph's graph and vertex-ID map, when the comparison runs.
+type PetgraphRefs<'a> = (&'a PetgraphGraph, &'a HashMap<u64, NodeIndex>);
+
+/// Timed runs and validation outcome for one BFS source.
+struct SourceRun {
+    source: u64,
+    strata_times: Vec<std::time::Duration>,
+    strata: RunStats,
+    petgraph_times: Vec<std::time::Duration>,
+    petgraph: Option<RunStats>,
+    /// `None` when the LDBC reference is for a different source.
+    ldbc_validation: Option<bool>,
+    cross_validation: Option<bool>,
 }
 
-fn fmt_ms(d: std::time::Duration) -> String {
-    format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+impl SourceRun {
+    fn failures(&self) -> Vec<String> {
+        let mut failures = Vec::new();
+        if self.ldbc_validation == Some(false) {
+            failures.push("BFS depths differ from the LDBC reference".to_string());
+        }
+        if self.cross_validation == Some(false) {
+            failures.push("BFS depths differ from petgraph".to_string());
+        }
+        failures
+    }
 }
 
+/// Run BFS from `source` `runs` times on each engine, validating the first
+/// Strata run. In `--validate-only` mode, runs once and skips petgraph timing.
+fn run_source(
+    db: &BenchDb,
+    dataset: &LdbcDataset,
+    config: &Config,
+    source: u64,
+    reference: Option<&BfsReference>,
+    petgraph_state: Option<PetgraphRefs>,
+    total_elements: f64,
+) -> SourceRun {
+    let runs = if config.validate_only { 1 } else { config.runs };
+    let mut strata_times = Vec::with_capacity(runs);
+    let mut ldbc_validation = None;
+    let mut cross_validation = None;
+
+    for run in 0..runs {
+        let bfs_run = run_bfs(db, source, config.direction);
+        let bfs_ms = bfs_run.elapsed.as_secs_f64() * 1000.0;
+        let evps = total_elements / bfs_run.elapsed.as_secs_f64();
+        strata_times.push(bfs_run.elapsed);
+
+        // Validate first run against LDBC reference
+        if let Some(reference) = reference.filter(|r| {
+            run == 0 && r.source == source && config.direction == TraversalDirection::ldbc(dataset)
+        }) {
+            let validation = validate_bfs(dataset, &bfs_run.depths, reference);
+            ldbc_validation = Some(validation.pass);
+            if !config.csv {
+                if validation.pass {
+                    eprintln!(
+                        "LDBC Validation (source {}): PASS ({} vertices checked)",
+                        source,
+                        dataset.vertices.len()
+                    );
+                } else {
+                    eprintln!(
+                        "LDBC Validation (source {}): FAIL ({} mismatches out of {} vertices)",
+                        source,
+                        validation.mismatches,
+                        dataset.vertices.len()
+                    );
+                    for detail in &validation.details {
+                        eprintln!("  {}", detail);
+                    }
+                }
+            }
+        }
+
+        // Cross-validate first run against petgraph
+        if run == 0 {
+            if let Some((pg_graph, id_map)) = petgraph_state {
+                let pg_depths = pg_graph.bfs(id_map[&source]);
+                let (pass, checked, mismatches) =
+                    cross_validate(dataset, &bfs_run.depths, &pg_depths, id_map);
+                cross_validation = Some(pass);
+                if !config.csv {
+                    if pass {
+                        eprintln!(
+                            "Cross-validation (source {}): PASS (depths match on all {} vertices)",
+                            source,
+                            fmt_num(checked as u64)
+                        );
+                    } else {
+                        eprintln!(
+                            "Cross-validation (source {}): FAIL ({} mismatches out of {} vertices)",
+                            source, mismatches, checked
+                        );
+                    }
+                }
+            }
+        }
+
+        if config.validate_only {
+            break;
+        }
+
+        if config.csv {
+            print_csv_row(
+                "strata",
+                source,
+                run + 1,
+                bfs_ms,
+                evps,
+                dataset.vertices.len(),
+                dataset.edges.len(),
+            );
+        } else if config.quiet && run == 0 {
+            eprintln!(
+                "Strata BFS (source {}): {:.3}ms, EVPS: {:.0}, |V|={}, |E|={}",
+                source, bfs_ms, evps, dataset.vertices.len(), dataset.edges.len()
+            );
+        }
+    }
+
+    let strata = compute_stats(&mut strata_times, total_elements);
+
+    let mut petgraph_times = Vec::new();
+    let petgraph = match petgraph_state {
+        Some((pg_graph, id_map)) if !config.validate_only => {
+            let pg_source = id_map[&source];
+            for run in 0..runs {
+                let start = Instant::now();
+                let _ = pg_graph.bfs(pg_source);
+                let elapsed = start.elapsed();
+                petgraph_times.push(elapsed);
+
+                if config.csv {
+                    let bfs_ms = elapsed.as_secs_f64() * 1000.0;
+                    let evps = total_elements / elapsed.as_secs_f64();
+                    print_csv_row(
+                        "petgraph",
+                        source,
+                        run + 1,
+                        bfs_ms,
+                        evps,
+                        dataset.vertices.len(),
+                        dataset.edges.len(),
+                    );
+                } else if config.quiet && run == 0 {
+                    let bfs_ms = elapsed.as_secs_f64() * 1000.0;
+                    let evps = total_elements / elapsed.as_secs_f64();
+                    eprintln!(
+                        "petgraph BFS (source {}): {:.3}ms, EVPS: {:.0}, |V|={}, |E|={}",
+                        source, bfs_ms, evps, dataset.vertices.len(), dataset.edges.len()
+                    );
+                }
+            }
+            Some(compute_stats(&mut petgraph_times, total_elements))
+        }
+        _ => None,
+    };
+
+    SourceRun {
+        source,
+        strata_times,
+        strata,
+        petgraph_times,
+        petgraph,
+        ldbc_validation,
+        cross_validation,
+    }
+}
+
+/// Stats over every run from every source. `avg_evps` is the mean of the
+/// per-source EVPS, as the Graphalytics spec aggregates it.
+fn aggregate_stats(times: Vec<std::time::Duration>, per_source_evps: &[f64], total_elements: f64) -> RunStats {
+    let mut times = times;
+    let mut stats = compute_stats(&mut times, total_elements);
+    stats.avg_evps = evps_spread(per_source_evps).0;
+    stats
+}
+
+/// `Some(false)` if any source failed, `Some(true)` if any ran and none failed.
+fn combine_validation(outcomes: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    outcomes.flatten().fold(None, |acc, pass| Some(acc.unwrap_or(true) && pass))
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
 fn print_csv_header() {
-    println!("\"engine\",\"run\",\"bfs_time_ms\",\"evps\",\"vertices\",\"edges\"");
+    println!("\"engine\",\"source\",\"run\",\"bfs_time_ms\",\"evps\",\"vertices\",\"edges\"");
 }
 
-fn print_csv_row(engine: &str, run: usize, bfs_ms: f64, evps: f64, vertices: usize, edges: usize) {
-    println!("\"{}\",{},{:.3},{:.0},{},{}", engine, run, bfs_ms, evps, vertices, edges);
+fn print_csv_row(
+    engine: &str,
+    source: u64,
+    run: usize,
+    bfs_ms: f64,
+    evps: f64,
+    vertices: usize,
+    edges: usize,
+) {
+    println!(
+        "\"{}\",{},{},{:.3},{:.0},{},{}",
+        engine, source, run, bfs_ms, evps, vertices, edges
+    );
 }
 
 fn print_published_references(strata_evps: f64, dataset_name: &str) {
@@ -350,6 +729,123 @@ fn print_published_references(strata_evps: f64, dataset_name: &str) {
     );
 }
 
+// ---------------------------------------------------------------------------
+// Result recording
+// ---------------------------------------------------------------------------
+
+/// Result name for `engine`'s BFS from `sources` (just `sources[0]`'s with
+/// `per_source`). Directed runs get an `/out` or `/in` segment, and
+/// disk-backed runs a durability segment, so neither compares against the
+/// default run.
+fn result_name(
+    dataset: &LdbcDataset,
+    engine: &str,
+    sources: &[u64],
+    per_source: bool,
+    direction: TraversalDirection,
+    durability: DurabilityConfig,
+) -> String {
+    let mut name = format!(
+        "graph-bfs/{}/{}/{}V-{}E",
+        engine,
+        dataset.name,
+        dataset.vertices.len(),
+        dataset.edges.len()
+    );
+    if direction != TraversalDirection::Both {
+        name.push_str(&format!("/{}", direction.label()));
+    }
+    if !matches!(durability, DurabilityConfig::Cache) {
+        name.push_str(&format!("/{}", durability.label()));
+    }
+    if per_source {
+        name.push_str(&format!("/source-{}", sources[0]));
+    }
+    name
+}
+
+/// Parameters every BFS result carries.
+fn base_params(
+    dataset: &LdbcDataset,
+    engine: &str,
+    sources: &[u64],
+    direction: TraversalDirection,
+    durability: DurabilityConfig,
+) -> HashMap<String, serde_json::Value> {
+    let mut params = HashMap::new();
+    params.insert("dataset".into(), serde_json::json!(dataset.name));
+    params.insert("engine".into(), serde_json::json!(engine));
+    if let [only] = sources {
+        params.insert("source".into(), serde_json::json!(only));
+    } else {
+        params.insert("sources".into(), serde_json::json!(sources));
+    }
+    params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
+    params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
+    params.insert("duplicate_edges_dropped".into(), serde_json::json!(dataset.duplicate_edges));
+    params.insert("self_loops".into(), serde_json::json!(dataset.self_loops));
+    params.insert("direction".into(), serde_json::json!(direction.label()));
+    params.insert("durability".into(), serde_json::json!(durability.label()));
+    params
+}
+
+/// Record one engine's BFS result. A per-source result has one entry in
+/// `sources` and a `/source-<id>` suffix; an aggregate over several sources
+/// carries the mean and stdev of their EVPS in `spread`, and the load
+/// footprint when `load` is set.
+#[allow(clippy::too_many_arguments)]
+fn record_bfs(
+    recorder: &mut ResultRecorder,
+    dataset: &LdbcDataset,
+    engine: &str,
+    sources: &[u64],
+    per_source: bool,
+    direction: TraversalDirection,
+    durability: DurabilityConfig,
+    load: Option<&LoadPhase>,
+    stats: &RunStats,
+    spread: Option<(f64, f64)>,
+    status: ResultStatus,
+) {
+    let mut params = base_params(dataset, engine, sources, direction, durability);
+    if let Some((mean, stdev)) = spread {
+        params.insert("evps_mean".into(), serde_json::json!(mean));
+        params.insert("evps_stdev".into(), serde_json::json!(stdev));
+    }
+    if let Some(load) = load {
+        let edges = dataset.edges.len();
+        params.insert("load_ms".into(), serde_json::json!(load.time.as_secs_f64() * 1000.0));
+        if let Some(rss) = load.rss_bytes {
+            params.insert("rss_bytes".into(), serde_json::json!(rss));
+            params.insert("rss_bytes_per_edge".into(), serde_json::json!(per_edge(Some(rss), edges)));
+        }
+        if let Some(disk) = load.disk_bytes {
+            params.insert("disk_bytes".into(), serde_json::json!(disk));
+            params.insert("disk_bytes_per_edge".into(), serde_json::json!(per_edge(Some(disk), edges)));
+        }
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(dataset, engine, sources, per_source, direction, durability),
+        category: "graph-bfs".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(stats.avg_evps),
+            p50_ns: Some(stats.p50.as_nanos() as u64),
+            p95_ns: Some(stats.p95.as_nanos() as u64),
+            p99_ns: Some(stats.p99.as_nanos() as u64),
+            min_ns: Some(stats.min.as_nanos() as u64),
+            max_ns: Some(stats.max.as_nanos() as u64),
+            avg_ns: Some(stats.avg.as_nanos() as u64),
+            samples: Some(stats.count as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status,
+    });
+}
+
 // ---------------------------------------------------------------------------
 // Markdown report generation
 // ---------------------------------------------------------------------------
@@ -357,11 +853,13 @@ fn print_published_references(strata_evps: f64, dataset_name: &str) {
 fn write_markdown_report(
     json_path: &std::path::Path,
     dataset: &LdbcDataset,
-    source: u64,
+    per_source: &[SourceRun],
     runs: usize,
+    direction: TraversalDirection,
+    durability: DurabilityConfig,
     strata_only: bool,
-    strata_load_time: std::time::Duration,
-    petgraph_load_time: Option<std::time::Duration>,
+    strata_load: &LoadPhase,
+    petgraph_load: Option<&LoadPhase>,
     strata_stats: &RunStats,
     petgraph_stats: Option<&RunStats>,
     ldbc_validation: Option<bool>,
@@ -412,6 +910,14 @@ fn write_markdown_report(
         "| Edges | {} |\n",
         fmt_num(dataset.edges.len() as u64)
     ));
+    md.push_str(&format!(
+        "| Repeated edges dropped | {} |\n",
+        fmt_num(dataset.duplicate_edges as u64)
+    ));
+    md.push_str(&format!(
+        "| Self-loops | {} |\n",
+        fmt_num(dataset.self_loops as u64)
+    ));
     md.push_str(&format!(
         "| Type | {} |\n",
         if dataset.directed {
@@ -420,7 +926,11 @@ fn write_markdown_report(
             "undirected"
         }
     ));
-    md.push_str(&format!("| BFS Source | {} |\n", source));
+    if let [only] = per_source {
+        md.push_str(&format!("| BFS Source | {} |\n", only.source));
+    } else {
+        md.push_str(&format!("| BFS Sources | {} |\n", per_source.len()));
+    }
     md.push_str("\n");
 
     // Configuration
@@ -428,7 +938,8 @@ fn write_markdown_report(
     md.push_str("| Field | Value |\n");
     md.push_str("|-------|-------|\n");
     md.push_str(&format!("| Runs | {} |\n", runs));
-    md.push_str("| Direction | both |\n");
+    md.push_str(&format!("| Direction | {} |\n", direction.label()));
+    md.push_str(&format!("| Durability | {} |\n", durability.label()));
     if strata_only {
         md.push_str("| Engine(s) | Strata |\n");
     } else {
@@ -441,34 +952,60 @@ fn write_markdown_report(
     if strata_only {
         md.push_str("| Engine | Load Time |\n");
         md.push_str("|--------|-----------|\n");
-        md.push_str(&format!("| Strata | {} |\n", fmt_ms(strata_load_time)));
+        md.push_str(&format!("| Strata | {} |\n", fmt_ms(strata_load.time)));
     } else {
         md.push_str("| Engine | Load Time | Ratio |\n");
         md.push_str("|--------|-----------|-------|\n");
         md.push_str(&format!(
             "| Strata | {} | — |\n",
-            fmt_ms(strata_load_time)
+            fmt_ms(strata_load.time)
         ));
-        if let Some(pg_load) = petgraph_load_time {
-            let ratio = if pg_load.as_secs_f64() > 0.0 {
+        if let Some(pg_load) = petgraph_load {
+            let ratio = if pg_load.time.as_secs_f64() > 0.0 {
                 format!(
                     "{:.2}x",
-                    strata_load_time.as_secs_f64() / pg_load.as_secs_f64()
+                    strata_load.time.as_secs_f64() / pg_load.time.as_secs_f64()
                 )
             } else {
                 "—".to_string()
             };
             md.push_str(&format!(
                 "| petgraph | {} | {} |\n",
-                fmt_ms(pg_load),
+                fmt_ms(pg_load.time),
                 ratio
             ));
         }
     }
     md.push_str("\n");
 
+    // Footprint
+    let edges = dataset.edges.len();
+    md.push_str("## Footprint\n\n");
+    md.push_str("| Engine | RSS Growth (bytes) | On Disk (bytes) |\n");
+    md.push_str("|--------|--------------------|-----------------|\n");
+    md.push_str(&format!(
+        "| Strata | {} | {} |\n",
+        fmt_footprint(strata_load.rss_bytes, edges),
+        fmt_footprint(strata_load.disk_bytes, edges),
+    ));
+    if let Some(pg_load) = petgraph_load {
+        md.push_str(&format!(
+            "| petgraph | {} | — |\n",
+            fmt_footprint(pg_load.rss_bytes, edges),
+        ));
+    }
+    md.push('\n');
+
     // BFS Phase
-    md.push_str(&format!("## BFS Phase ({} runs)\n\n", runs));
+    if per_source.len() > 1 {
+        md.push_str(&format!(
+            "## BFS Phase ({} runs × {} sources, EVPS is the mean across sources)\n\n",
+            runs,
+            per_source.len()
+        ));
+    } else {
+        md.push_str(&format!("## BFS Phase ({} runs)\n\n", runs));
+    }
     if strata_only {
         md.push_str("| Metric | Strata |\n");
         md.push_str("|--------|--------|\n");
@@ -543,6 +1080,37 @@ fn write_markdown_report(
     }
     md.push_str("\n");
 
+    // Per-source breakdown
+    if per_source.len() > 1 {
+        let strata_evps: Vec<f64> = per_source.iter().map(|r| r.strata.avg_evps).collect();
+        let (mean, stdev) = evps_spread(&strata_evps);
+        md.push_str("## Per-source BFS\n\n");
+        md.push_str("| Source | Strata avg | Strata EVPS | petgraph EVPS | LDBC Reference |\n");
+        md.push_str("|--------|------------|-------------|---------------|----------------|\n");
+        for r in per_source {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                r.source,
+                fmt_ms(r.strata.avg),
+                fmt_num(r.strata.avg_evps as u64),
+                r.petgraph
+                    .as_ref()
+                    .map(|pg| fmt_num(pg.avg_evps as u64))
+                    .unwrap_or_else(|| "—".to_string()),
+                match r.ldbc_validation {
+                    Some(true) => "PASS",
+                    Some(false) => "**FAIL**",
+                    None => "—",
+                },
+            ));
+        }
+        md.push_str(&format!(
+            "\nStrata EVPS across sources: mean {}, stdev {}\n\n",
+            fmt_num(mean as u64),
+            fmt_num(stdev as u64)
+        ));
+    }
+
     // Validation
     md.push_str("## Validation\n\n");
     md.push_str("| Check | Result |\n");
@@ -599,12 +1167,25 @@ fn main() {
         std::process::exit(1);
     });
 
-    let source = config
-        .source
-        .or(dataset.bfs_source)
-        .unwrap_or(dataset.vertices[0]);
+    let sources = if config.sources.is_empty() {
+        vec![dataset.bfs_source.unwrap_or(dataset.vertices[0])]
+    } else {
+        config.sources.clone()
+    };
+    if config.direction != TraversalDirection::Both && !dataset.directed {
+        eprintln!(
+            "--direction {} needs a directed dataset; {} is undirected",
+            config.direction.label(),
+            dataset.name
+        );
+        std::process::exit(1);
+    }
 
-    let total_elements = (dataset.vertices.len() + dataset.edges.len()) as f64;
+    let known: HashSet<u64> = dataset.vertices.iter().copied().collect();
+    if let Some(missing) = sources.iter().find(|s| !known.contains(s)) {
+        eprintln!("Source vertex {} is not in dataset {}", missing, dataset.name);
+        std::process::exit(1);
+    }
 
     if !config.csv {
         eprintln!("=== LDBC Graphalytics BFS Benchmark ===");
@@ -615,28 +1196,110 @@ fn main() {
             fmt_num(dataset.edges.len() as u64),
             if dataset.directed { "directed" } else { "undirected" },
         );
-        eprintln!("Source:   {}", source);
+        if dataset.duplicate_edges > 0 || dataset.self_loops > 0 {
+            eprintln!(
+                "Edges:    {} repeated edges dropped, {} self-loops kept",
+                fmt_num(dataset.duplicate_edges as u64),
+                fmt_num(dataset.self_loops as u64),
+            );
+        }
+        if let [only] = sources.as_slice() {
+            eprintln!("Source:   {}", only);
+        } else if sources.len() <= 10 {
+            let list: Vec<String> = sources.iter().map(|s| s.to_string()).collect();
+            eprintln!("Sources:  {} ({})", sources.len(), list.join(", "));
+        } else {
+            eprintln!("Sources:  {}", sources.len());
+        }
         eprintln!("Runs:     {}", config.runs);
-        eprintln!("Direction: both (LDBC BFS treats edges as undirected)");
+        eprintln!("Direction: {}", config.direction.label());
+        eprintln!("Durability: {}", config.durability.label());
         if config.strata_only {
             eprintln!("Mode:     strata-only (petgraph comparison skipped)");
         }
         eprintln!();
     }
 
+    let mut recorder = ResultRecorder::new("graph-bfs");
+    let summary = recorder
+        .guard(
+            &result_name(&dataset, "strata", &sources, false, config.direction, config.durability),
+            base_params(&dataset, "strata", &sources, config.direction, config.durability),
+            |recorder| run_benchmark(&config, &dataset, &sources, recorder),
+        )
+        .flatten();
+    if config.validate_only && recorder.failure_count() == 0 {
+        return;
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    let failed = recorder.failure_count() > 0;
+    if let Ok(json_path) = recorder.save() {
+        if let (false, Some(summary)) = (config.csv, &summary) {
+            let _ = write_markdown_report(
+                &json_path,
+                &dataset,
+                &summary.per_source,
+                config.runs,
+                config.direction,
+                config.durability,
+                config.strata_only,
+                &summary.strata_load,
+                summary.petgraph_load.as_ref(),
+                &summary.strata_stats,
+                summary.petgraph_stats.as_ref(),
+                summary.ldbc_validation,
+                summary.cross_validation,
+            );
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// What the Markdown report needs from a finished run.
+struct BfsSummary {
+    per_source: Vec<SourceRun>,
+    strata_load: LoadPhase,
+    petgraph_load: Option<LoadPhase>,
+    strata_stats: RunStats,
+    petgraph_stats: Option<RunStats>,
+    ldbc_validation: Option<bool>,
+    cross_validation: Option<bool>,
+}
+
+/// Load the graph into Strata (and petgraph unless `--strata-only`), time
+/// BFS from every source, and record the results. Returns what the
+/// Markdown report needs; `None` with `--validate-only`, which stops after
+/// validating and records only a validation failure.
+fn run_benchmark(
+    config: &Config,
+    dataset: &LdbcDataset,
+    sources: &[u64],
+    recorder: &mut ResultRecorder,
+) -> Option<BfsSummary> {
+    let total_elements = (dataset.vertices.len() + dataset.edges.len()) as f64;
+
     // -----------------------------------------------------------------------
     // Load phase
     // -----------------------------------------------------------------------
 
     // Strata
-    let db = create_db(DurabilityConfig::Cache);
+    let db = create_db(config.durability);
 
     if !config.csv && !config.quiet {
         eprint!("Loading graph into Strata...");
     }
-    let strata_load_time = load_graph(&db, &dataset);
+    let ((), mut strata_load) = LoadPhase::measure(|| {
+        load_graph(&db, dataset);
+    });
+    strata_load.disk_bytes = db.data_dir().map(dir_size_bytes);
     if !config.csv && !config.quiet {
-        eprintln!(" done ({:.1}ms)", strata_load_time.as_secs_f64() * 1000.0);
+        eprintln!(" done ({:.1}ms)", strata_load.time.as_secs_f64() * 1000.0);
     }
 
     // petgraph (unless --strata-only)
@@ -644,44 +1307,62 @@ fn main() {
         if !config.csv && !config.quiet {
             eprint!("Loading graph into petgraph...");
         }
-        let pg_start = Instant::now();
-        let (pg_graph, id_map) = dataset.to_petgraph();
-        let pg_load_time = pg_start.elapsed();
+        let ((pg_graph, id_map), pg_load) =
+            LoadPhase::measure(|| PetgraphGraph::build(dataset, config.direction));
         if !config.csv && !config.quiet {
-            eprintln!(" done ({:.1}ms)", pg_load_time.as_secs_f64() * 1000.0);
+            eprintln!(" done ({:.1}ms)", pg_load.time.as_secs_f64() * 1000.0);
         }
-        Some((pg_graph, id_map, pg_load_time))
+        Some((pg_graph, id_map, pg_load))
     } else {
         None
     };
 
     // Print load comparison
     if !config.csv && !config.quiet {
+        let edges = dataset.edges.len();
         eprintln!();
         eprintln!("--- Load Phase ---");
-        eprintln!("  {:12} {}", "Strata:", fmt_ms(strata_load_time));
-        if let Some((_, _, pg_load_time)) = &petgraph_state {
-            let pg_secs = pg_load_time.as_secs_f64();
+        eprintln!("  {:12} {}", "Strata:", fmt_ms(strata_load.time));
+        if let Some((_, _, pg_load)) = &petgraph_state {
+            let pg_secs = pg_load.time.as_secs_f64();
             if pg_secs > 0.0 {
-                let ratio = strata_load_time.as_secs_f64() / pg_secs;
+                let ratio = strata_load.time.as_secs_f64() / pg_secs;
                 eprintln!(
                     "  {:12} {}  ({:.2}x faster)",
-                    "petgraph:", fmt_ms(*pg_load_time), ratio
+                    "petgraph:", fmt_ms(pg_load.time), ratio
                 );
             } else {
-                eprintln!("  {:12} {}", "petgraph:", fmt_ms(*pg_load_time));
+                eprintln!("  {:12} {}", "petgraph:", fmt_ms(pg_load.time));
             }
         }
+
+        eprintln!();
+        eprintln!("--- Footprint (bytes, |E|={}) ---", fmt_num(edges as u64));
+        eprintln!("  {:12} {:>28} {:>28}", "", "RSS growth", "on disk");
+        eprintln!(
+            "  {:12} {:>28} {:>28}",
+            "Strata:",
+            fmt_footprint(strata_load.rss_bytes, edges),
+            fmt_footprint(strata_load.disk_bytes, edges),
+        );
+        if let Some((_, _, pg_load)) = &petgraph_state {
+            eprintln!(
+                "  {:12} {:>28} {:>28}",
+                "petgraph:",
+                fmt_footprint(pg_load.rss_bytes, edges),
+                "-",
+            );
+        }
     }
 
     // Load BFS reference for validation
     let reference = if !config.no_validate {
         let bfs_path = config.dataset.join(format!("{}-BFS", dataset.name));
         if bfs_path.exists() {
-            Some(BfsReference::load(&bfs_path).unwrap_or_else(|e| {
-                eprintln!("Failed to load BFS reference: {}", e);
-                std::process::exit(1);
-            }))
+            Some(
+                BfsReference::load(&bfs_path)
+                    .unwrap_or_else(|e| panic!("failed to load BFS reference: {}", e)),
+            )
         } else {
             if !config.csv && !config.quiet {
                 eprintln!("No BFS reference file found, skipping LDBC validation.");
@@ -692,153 +1373,134 @@ fn main() {
         None
     };
 
-    // -----------------------------------------------------------------------
-    // BFS phase — Strata
-    // -----------------------------------------------------------------------
-
-    let mut strata_times = Vec::with_capacity(config.runs);
-    let mut ldbc_validation_pass: Option<bool> = None;
-    let mut cross_validation_pass: Option<bool> = None;
-
-    if config.csv {
-        print_csv_header();
-    }
-
-    for run in 0..config.runs {
-        let bfs_run = run_bfs(&db, source);
-        let bfs_ms = bfs_run.elapsed.as_secs_f64() * 1000.0;
-        let evps = total_elements / bfs_run.elapsed.as_secs_f64();
-        strata_times.push(bfs_run.elapsed);
-
-        // Validate first run against LDBC reference
-        if let Some(ref reference) = reference {
-            if run == 0 || config.validate_only {
-                let validation = validate_bfs(&dataset, &bfs_run.depths, reference);
-                if run == 0 {
-                    ldbc_validation_pass = Some(validation.pass);
-                }
-                if !config.csv {
-                    if validation.pass {
-                        eprintln!(
-                            "LDBC Validation: PASS ({} vertices checked)",
-                            dataset.vertices.len()
-                        );
-                    } else {
-                        eprintln!(
-                            "LDBC Validation: FAIL ({} mismatches out of {} vertices)",
-                            validation.mismatches,
-                            dataset.vertices.len()
-                        );
-                        for detail in &validation.details {
-                            eprintln!("  {}", detail);
-                        }
-                    }
-                }
-                if !validation.pass && config.validate_only {
-                    std::process::exit(1);
-                }
-            }
-        }
-
-        // Cross-validate first run against petgraph
-        if run == 0 {
-            if let Some((ref pg_graph, ref id_map, _)) = petgraph_state {
-                let pg_source = id_map[&source];
-                let pg_depths = petgraph_bfs(pg_graph, pg_source);
-                let (pass, checked, mismatches) =
-                    cross_validate(&dataset, &bfs_run.depths, &pg_depths, id_map);
-                cross_validation_pass = Some(pass);
-                if !config.csv {
-                    if pass {
-                        eprintln!(
-                            "Cross-validation: PASS (depths match on all {} vertices)",
-                            fmt_num(checked as u64)
-                        );
-                    } else {
-                        eprintln!(
-                            "Cross-validation: FAIL ({} mismatches out of {} vertices)",
-                            mismatches, checked
-                        );
-                    }
-                }
-            }
-        }
-
-        if config.validate_only {
-            if !config.csv {
-                eprintln!("Validate-only mode, skipping remaining runs.");
-            }
-            return;
-        }
-
-        if config.csv {
-            print_csv_row(
-                "strata",
-                run + 1,
-                bfs_ms,
-                evps,
-                dataset.vertices.len(),
-                dataset.edges.len(),
+    if let Some(reference) = &reference {
+        let ldbc_direction = TraversalDirection::ldbc(dataset);
+        if config.direction != ldbc_direction && !config.csv && !config.quiet {
+            eprintln!(
+                "LDBC reference follows direction {}; validating against petgraph only.",
+                ldbc_direction.label()
             );
-        } else if config.quiet && run == 0 {
+        } else if !sources.contains(&reference.source) && !config.csv && !config.quiet {
             eprintln!(
-                "Strata BFS: {:.3}ms, EVPS: {:.0}, |V|={}, |E|={}",
-                bfs_ms, evps, dataset.vertices.len(), dataset.edges.len()
+                "LDBC reference is for source {}; validating against petgraph only.",
+                reference.source
             );
         }
     }
 
-    let strata_stats = compute_stats(&mut strata_times, total_elements);
-
     // -----------------------------------------------------------------------
-    // BFS phase — petgraph
+    // BFS phase
     // -----------------------------------------------------------------------
 
-    let petgraph_stats = if let Some((ref pg_graph, ref id_map, _)) = petgraph_state {
-        let pg_source = id_map[&source];
-        let mut pg_times = Vec::with_capacity(config.runs);
+    if config.csv {
+        print_csv_header();
+    }
 
-        for run in 0..config.runs {
-            let start = Instant::now();
-            let _ = petgraph_bfs(pg_graph, pg_source);
-            let elapsed = start.elapsed();
-            pg_times.push(elapsed);
+    let petgraph_refs = petgraph_state.as_ref().map(|(g, m, _)| (g, m));
+    let per_source: Vec<SourceRun> = sources
+        .iter()
+        .map(|&source| {
+            run_source(
+                &db,
+                dataset,
+                config,
+                source,
+                reference.as_ref(),
+                petgraph_refs,
+                total_elements,
+            )
+        })
+        .collect();
+
+    let ldbc_validation_pass = combine_validation(per_source.iter().map(|r| r.ldbc_validation));
+    let cross_validation_pass = combine_validation(per_source.iter().map(|r| r.cross_validation));
+
+    let multi = per_source.len() > 1;
+    let failures: Vec<String> = if multi {
+        per_source
+            .iter()
+            .flat_map(|r| {
+                r.failures()
+                    .into_iter()
+                    .map(move |f| format!("source {}: {}", r.source, f))
+            })
+            .collect()
+    } else {
+        per_source[0].failures()
+    };
 
-            if config.csv {
-                let bfs_ms = elapsed.as_secs_f64() * 1000.0;
-                let evps = total_elements / elapsed.as_secs_f64();
-                print_csv_row(
-                    "petgraph",
-                    run + 1,
-                    bfs_ms,
-                    evps,
-                    dataset.vertices.len(),
-                    dataset.edges.len(),
-                );
-            } else if config.quiet && run == 0 {
-                let bfs_ms = elapsed.as_secs_f64() * 1000.0;
-                let evps = total_elements / elapsed.as_secs_f64();
-                eprintln!(
-                    "petgraph BFS: {:.3}ms, EVPS: {:.0}, |V|={}, |E|={}",
-                    bfs_ms, evps, dataset.vertices.len(), dataset.edges.len()
-                );
-            }
+    if config.validate_only {
+        if !failures.is_empty() {
+            recorder.record_failure(
+                &result_name(dataset, "strata", sources, false, config.direction, config.durability),
+                base_params(dataset, "strata", sources, config.direction, config.durability),
+                ResultStatus::from_validation(&failures),
+            );
+        } else if !config.csv {
+            eprintln!("Validate-only mode, skipping remaining runs.");
         }
+        return None;
+    }
 
-        Some(compute_stats(&mut pg_times, total_elements))
-    } else {
-        None
-    };
+    let strata_evps: Vec<f64> = per_source.iter().map(|r| r.strata.avg_evps).collect();
+    let strata_stats = aggregate_stats(
+        per_source.iter().flat_map(|r| r.strata_times.iter().copied()).collect(),
+        &strata_evps,
+        total_elements,
+    );
+    let petgraph_evps: Vec<f64> = per_source
+        .iter()
+        .filter_map(|r| r.petgraph.as_ref().map(|pg| pg.avg_evps))
+        .collect();
+    let petgraph_stats = (!petgraph_evps.is_empty()).then(|| {
+        aggregate_stats(
+            per_source.iter().flat_map(|r| r.petgraph_times.iter().copied()).collect(),
+            &petgraph_evps,
+            total_elements,
+        )
+    });
 
     // -----------------------------------------------------------------------
     // Output comparison table
     // -----------------------------------------------------------------------
 
+    if !config.csv && !config.quiet && per_source.len() > 1 {
+        eprintln!();
+        eprintln!(
+            "--- Per-source BFS ({} sources, {} runs each) ---",
+            per_source.len(),
+            config.runs
+        );
+        eprintln!(
+            "  {:16} {:>10} {:>14} {:>14}",
+            "source", "avg", "EVPS", "petgraph EVPS"
+        );
+        for r in &per_source {
+            eprintln!(
+                "  {:16} {:>10} {:>14} {:>14}",
+                r.source,
+                fmt_ms(r.strata.avg),
+                fmt_num(r.strata.avg_evps as u64),
+                r.petgraph
+                    .as_ref()
+                    .map(|pg| fmt_num(pg.avg_evps as u64))
+                    .unwrap_or_else(|| "-".to_string()),
+            );
+        }
+        let (mean, stdev) = evps_spread(&strata_evps);
+        eprintln!(
+            "  EVPS across sources: mean {}, stdev {}",
+            fmt_num(mean as u64),
+            fmt_num(stdev as u64)
+        );
+    }
+
     if !config.csv && !config.quiet {
         eprintln!();
         eprintln!(
-            "--- BFS Phase ({} runs, direction=both) ---",
-            strata_stats.count
+            "--- BFS Phase ({} runs, direction={}) ---",
+            strata_stats.count,
+            config.direction.label()
         );
         eprintln!(
             "  {:16} {:>10} {:>10} {:>14}",
@@ -901,93 +1563,75 @@ fn main() {
     // Record results
     // -----------------------------------------------------------------------
 
-    let mut recorder = ResultRecorder::new("graph-bfs");
+    if multi {
+        for r in &per_source {
+            record_bfs(
+                recorder,
+                dataset,
+                "strata",
+                &[r.source],
+                true,
+                config.direction,
+                config.durability,
+                None,
+                &r.strata,
+                None,
+                ResultStatus::from_validation(&r.failures()),
+            );
+            if let Some(ref pg) = r.petgraph {
+                record_bfs(
+                    recorder,
+                    dataset,
+                    "petgraph",
+                    &[r.source],
+                    true,
+                    config.direction,
+                    config.durability,
+                    None,
+                    pg,
+                    None,
+                    ResultStatus::Success,
+                );
+            }
+        }
+    }
 
-    // Strata result
-    {
-        let mut params = HashMap::new();
-        params.insert("dataset".into(), serde_json::json!(dataset.name));
-        params.insert("engine".into(), serde_json::json!("strata"));
-        params.insert("source".into(), serde_json::json!(source));
-        params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
-        params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
-        params.insert("direction".into(), serde_json::json!("both"));
-
-        recorder.record(BenchmarkResult {
-            benchmark: format!(
-                "graph-bfs/strata/{}/{}V-{}E",
-                dataset.name,
-                dataset.vertices.len(),
-                dataset.edges.len()
-            ),
-            category: "graph-bfs".to_string(),
-            parameters: params,
-            metrics: BenchmarkMetrics {
-                ops_per_sec: Some(strata_stats.avg_evps),
-                p50_ns: Some(strata_stats.p50.as_nanos() as u64),
-                p95_ns: Some(strata_stats.p95.as_nanos() as u64),
-                p99_ns: Some(strata_stats.p99.as_nanos() as u64),
-                min_ns: Some(strata_stats.min.as_nanos() as u64),
-                max_ns: Some(strata_stats.max.as_nanos() as u64),
-                avg_ns: Some(strata_stats.avg.as_nanos() as u64),
-                samples: Some(strata_stats.count as u64),
-                ..Default::default()
-            },
-        });
-    }
-
-    // petgraph result
+    record_bfs(
+        recorder,
+        dataset,
+        "strata",
+        sources,
+        false,
+        config.direction,
+        config.durability,
+        Some(&strata_load),
+        &strata_stats,
+        multi.then(|| evps_spread(&strata_evps)),
+        ResultStatus::from_validation(&failures),
+    );
     if let Some(ref pg) = petgraph_stats {
-        let mut params = HashMap::new();
-        params.insert("dataset".into(), serde_json::json!(dataset.name));
-        params.insert("engine".into(), serde_json::json!("petgraph"));
-        params.insert("source".into(), serde_json::json!(source));
-        params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
-        params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
-        params.insert("direction".into(), serde_json::json!("both"));
-
-        recorder.record(BenchmarkResult {
-            benchmark: format!(
-                "graph-bfs/petgraph/{}/{}V-{}E",
-                dataset.name,
-                dataset.vertices.len(),
-                dataset.edges.len()
-            ),
-            category: "graph-bfs".to_string(),
-            parameters: params,
-            metrics: BenchmarkMetrics {
-                ops_per_sec: Some(pg.avg_evps),
-                p50_ns: Some(pg.p50.as_nanos() as u64),
-                p95_ns: Some(pg.p95.as_nanos() as u64),
-                p99_ns: Some(pg.p99.as_nanos() as u64),
-                min_ns: Some(pg.min.as_nanos() as u64),
-                max_ns: Some(pg.max.as_nanos() as u64),
-                avg_ns: Some(pg.avg.as_nanos() as u64),
-                samples: Some(pg.count as u64),
-                ..Default::default()
-            },
-        });
+        record_bfs(
+            recorder,
+            dataset,
+            "petgraph",
+            sources,
+            false,
+            config.direction,
+            config.durability,
+            petgraph_state.as_ref().map(|(_, _, load)| load),
+            pg,
+            multi.then(|| evps_spread(&petgraph_evps)),
+            ResultStatus::Success,
+        );
     }
 
-    if !config.csv {
-        eprintln!();
-        eprintln!("=== Benchmark complete ===");
-    }
-    if let Ok(json_path) = recorder.save() {
-        if !config.csv {
-            let _ = write_markdown_report(
-                &json_path,
-                &dataset,
-                source,
-                config.runs,
-                config.strata_only,
-                strata_load_time,
-                petgraph_state.as_ref().map(|(_, _, t)| *t),
-                &strata_stats,
-                petgraph_stats.as_ref(),
-                ldbc_validation_pass,
-                cross_validation_pass,
-            );
-        }
-    }
+    Some(BfsSummary {
+        per_source,
+        strata_load,
+        petgraph_load: petgraph_state.map(|(_, _, load)| load),
+        strata_stats,
+        petgraph_stats,
+        ldbc_validation: ldbc_validation_pass,
+        cross_validation: cross_validation_pass,
+    })
 }
@@ -0,0 +1,651 @@
+//! LDBC Graphalytics CDLP Benchmark — Strata vs petgraph head-to-head
+//!
+//! Runs community detection by label propagation (synchronous, fixed
+//! iteration count, smallest label wins a tie) and validates the labels
+//! against the dataset's `-CDLP` reference output. Labels must match
+//! exactly, as Graphalytics requires. Throughput is reported as EVPS
+//! (Edges + Vertices processed per second), as in graph_bfs.
+//!
+//! Strata has no label propagation primitive. Each Strata run reads every
+//! vertex's out-neighbors from the engine with a depth-1 `graph_bfs`, then
+//! propagates in memory with the same kernel the petgraph run uses, so the
+//! two differ only in where the adjacency comes from. The adjacency read is
+//! reported separately.
+//!
+//! Run:           `cargo bench --bench graph_cdlp`
+//! Quick:         `cargo bench --bench graph_cdlp -- -q`
+//! Validate only: `cargo bench --bench graph_cdlp -- --validate-only`
+//! CSV:           `cargo bench --bench graph_cdlp -- --csv`
+//! Custom data:   `cargo bench --bench graph_cdlp -- --dataset path/to/ldbc/dir`
+//! Iterations:    `cargo bench --bench graph_cdlp -- --iterations 10`
+//! Strata only:   `cargo bench --bench graph_cdlp -- --strata-only`
+//! Disk-backed:   `cargo bench --bench graph_cdlp -- --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[allow(unused)]
+mod ldbc;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use ldbc::{cdlp, petgraph_adjacency, CdlpReference, LdbcDataset};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_RUNS: usize = 10;
+
+fn default_dataset_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-directed")
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    dataset: PathBuf,
+    durability: DurabilityConfig,
+    /// Override the dataset's `algorithms.cdlp.max-iterations` property.
+    iterations: Option<usize>,
+    runs: usize,
+    validate_only: bool,
+    no_validate: bool,
+    csv: bool,
+    quiet: bool,
+    strata_only: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        dataset: default_dataset_dir(),
+        durability: DurabilityConfig::Cache,
+        iterations: None,
+        runs: DEFAULT_RUNS,
+        validate_only: false,
+        no_validate: false,
+        csv: false,
+        quiet: false,
+        strata_only: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--dataset" => config.dataset = PathBuf::from(args.value()),
+            "--iterations" => config.iterations = Some(args.parse_value()),
+            "--runs" => config.runs = args.parse_value::<usize>().max(1),
+            "--validate-only" => config.validate_only = true,
+            "--no-validate" => config.no_validate = true,
+            "--strata-only" => config.strata_only = true,
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Graph loading (Strata)
+// ---------------------------------------------------------------------------
+
+fn load_graph(db: &BenchDb, dataset: &LdbcDataset) -> Duration {
+    let start = Instant::now();
+
+    db.db.graph_create("ldbc").expect("graph_create failed");
+
+    for &vid in &dataset.vertices {
+        db.db
+            .graph_add_node("ldbc", &vid.to_string(), None, None)
+            .expect("graph_add_node failed");
+    }
+
+    for &(src, dst) in &dataset.edges {
+        db.db
+            .graph_add_edge(
+                "ldbc",
+                &src.to_string(),
+                &dst.to_string(),
+                "E",
+                None,
+                None,
+            )
+            .expect("graph_add_edge failed");
+    }
+
+    start.elapsed()
+}
+
+// ---------------------------------------------------------------------------
+// CDLP execution (Strata)
+// ---------------------------------------------------------------------------
+
+struct CdlpRun {
+    elapsed: Duration,
+    /// Part of `elapsed` spent reading adjacency from Strata.
+    adjacency: Duration,
+    /// Labels indexed like `dataset.vertices`.
+    labels: Vec<u64>,
+}
+
+/// Read every vertex's out-neighbors from Strata, then propagate in memory.
+/// Self-loops don't show up in a BFS (the vertex is its own depth 0), which
+/// matches the kernel's rule of skipping them.
+fn run_cdlp(db: &BenchDb, dataset: &LdbcDataset, iterations: usize) -> CdlpRun {
+    let index: HashMap<String, usize> = dataset
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(i, vid)| (vid.to_string(), i))
+        .collect();
+    let direction = if dataset.directed { "outgoing" } else { "both" };
+
+    let start = Instant::now();
+    let mut out: Vec<Vec<usize>> = vec![Vec::new(); dataset.vertices.len()];
+    for (u, vid) in dataset.vertices.iter().enumerate() {
+        let result = db
+            .db
+            .graph_bfs("ldbc", &vid.to_string(), 1, None, None, Some(direction))
+            .expect("graph_bfs failed");
+        for (node, depth) in &result.depths {
+            if *depth == 1 {
+                if let Some(&v) = index.get(node) {
+                    out[u].push(v);
+                }
+            }
+        }
+    }
+    let adjacency = start.elapsed();
+
+    let labels = cdlp(&dataset.vertices, &out, dataset.directed, iterations);
+
+    CdlpRun {
+        elapsed: start.elapsed(),
+        adjacency,
+        labels,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Check `labels` (indexed like `dataset.vertices`) against `expected`.
+/// Returns (mismatches, first few mismatch details).
+fn validate_labels(
+    dataset: &LdbcDataset,
+    labels: &[u64],
+    expected: &HashMap<u64, u64>,
+) -> (usize, Vec<String>) {
+    let mut mismatches = 0;
+    let mut details = Vec::new();
+
+    for (&vid, &actual) in dataset.vertices.iter().zip(labels) {
+        match expected.get(&vid) {
+            Some(&e) if e == actual => {}
+            Some(&e) => {
+                mismatches += 1;
+                if details.len() < 10 {
+                    details.push(format!("vertex {}: expected label {}, got {}", vid, e, actual));
+                }
+            }
+            None => {
+                mismatches += 1;
+                if details.len() < 10 {
+                    details.push(format!("vertex {}: missing from reference", vid));
+                }
+            }
+        }
+    }
+
+    (mismatches, details)
+}
+
+fn report_validation(label: &str, checked: usize, mismatches: usize, details: &[String]) {
+    if mismatches == 0 {
+        eprintln!(
+            "{}: PASS ({} vertex labels match exactly)",
+            label,
+            fmt_num(checked as u64)
+        );
+    } else {
+        eprintln!(
+            "{}: FAIL ({} mismatches out of {} vertices)",
+            label, mismatches, checked
+        );
+        for detail in details {
+            eprintln!("  {}", detail);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Run statistics
+// ---------------------------------------------------------------------------
+
+struct RunStats {
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    avg_evps: f64,
+    count: usize,
+}
+
+fn compute_stats(times: &mut [Duration], total_elements: f64) -> RunStats {
+    assert!(!times.is_empty(), "compute_stats requires at least one run");
+    times.sort_unstable();
+    let len = times.len();
+    let sum: Duration = times.iter().sum();
+    let avg = sum / len as u32;
+    let avg_secs = avg.as_secs_f64();
+    let avg_evps = if avg_secs > 0.0 {
+        total_elements / avg_secs
+    } else {
+        0.0
+    };
+    RunStats {
+        avg,
+        p50: times[len * 50 / 100],
+        p95: times[(len * 95 / 100).min(len - 1)],
+        p99: times[(len * 99 / 100).min(len - 1)],
+        min: times[0],
+        max: times[len - 1],
+        avg_evps,
+        count: len,
+    }
+}
+
+fn print_csv_row(engine: &str, run: usize, elapsed: Duration, total_elements: f64, dataset: &LdbcDataset) {
+    println!(
+        "\"{}\",{},{:.3},{:.0},{},{}",
+        engine,
+        run,
+        elapsed.as_secs_f64() * 1000.0,
+        total_elements / elapsed.as_secs_f64(),
+        dataset.vertices.len(),
+        dataset.edges.len()
+    );
+}
+
+fn result_name(engine: &str, dataset: &LdbcDataset, durability: DurabilityConfig) -> String {
+    let mut name = format!(
+        "graph-cdlp/{}/{}/{}V-{}E",
+        engine,
+        dataset.name,
+        dataset.vertices.len(),
+        dataset.edges.len()
+    );
+    if !matches!(durability, DurabilityConfig::Cache) {
+        name.push_str(&format!("/{}", durability.label()));
+    }
+    name
+}
+
+fn base_params(
+    engine: &str,
+    dataset: &LdbcDataset,
+    iterations: usize,
+    durability: DurabilityConfig,
+) -> HashMap<String, serde_json::Value> {
+    let mut params = HashMap::new();
+    params.insert("dataset".into(), serde_json::json!(dataset.name));
+    params.insert("engine".into(), serde_json::json!(engine));
+    params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
+    params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
+    params.insert("iterations".into(), serde_json::json!(iterations));
+    params.insert("durability".into(), serde_json::json!(durability.label()));
+    params
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record(
+    recorder: &mut ResultRecorder,
+    engine: &str,
+    dataset: &LdbcDataset,
+    durability: DurabilityConfig,
+    iterations: usize,
+    communities: usize,
+    stats: &RunStats,
+    status: ResultStatus,
+) {
+    let mut params = base_params(engine, dataset, iterations, durability);
+    params.insert("communities".into(), serde_json::json!(communities));
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(engine, dataset, durability),
+        category: "graph-cdlp".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(stats.avg_evps),
+            p50_ns: Some(stats.p50.as_nanos() as u64),
+            p95_ns: Some(stats.p95.as_nanos() as u64),
+            p99_ns: Some(stats.p99.as_nanos() as u64),
+            min_ns: Some(stats.min.as_nanos() as u64),
+            max_ns: Some(stats.max.as_nanos() as u64),
+            avg_ns: Some(stats.avg.as_nanos() as u64),
+            samples: Some(stats.count as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let dataset = LdbcDataset::load(&config.dataset).unwrap_or_else(|e| {
+        eprintln!("Failed to load dataset from {}: {}", config.dataset.display(), e);
+        std::process::exit(1);
+    });
+    let default_iterations = dataset.cdlp_iterations();
+    let iterations = config.iterations.unwrap_or(default_iterations);
+
+    if !config.csv {
+        eprintln!("=== LDBC Graphalytics CDLP Benchmark ===");
+        eprintln!(
+            "Dataset:    {} ({} vertices, {} edges, {})",
+            dataset.name,
+            fmt_num(dataset.vertices.len() as u64),
+            fmt_num(dataset.edges.len() as u64),
+            if dataset.directed { "directed" } else { "undirected" },
+        );
+        eprintln!("Iterations: {}", iterations);
+        eprintln!("Runs:       {}", config.runs);
+        eprintln!("Durability: {}", config.durability.label());
+        if config.strata_only {
+            eprintln!("Mode:       strata-only (petgraph comparison skipped)");
+        }
+        eprintln!();
+    }
+
+    let mut recorder = ResultRecorder::new("graph-cdlp");
+    recorder.guard(
+        &result_name("strata", &dataset, config.durability),
+        base_params("strata", &dataset, iterations, config.durability),
+        |recorder| {
+            let reference = load_reference(&config, &dataset, iterations);
+            run_benchmark(&config, &dataset, iterations, reference.as_ref(), recorder)
+        },
+    );
+    if config.validate_only && recorder.failure_count() == 0 {
+        return;
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
+
+/// The LDBC CDLP reference for `dataset`, or `None` when validation
+/// is off or doesn't apply. A reference that exists but can't be read
+/// panics, so the run's guard records it as an error.
+fn load_reference(
+    config: &Config,
+    dataset: &LdbcDataset,
+    iterations: usize,
+) -> Option<CdlpReference> {
+    let default_iterations = dataset.cdlp_iterations();
+
+    // Reference labels only hold for the dataset's own iteration count
+    if config.no_validate {
+        None
+    } else if iterations != default_iterations {
+        if !config.csv && !config.quiet {
+            eprintln!("CDLP iterations overridden, skipping LDBC validation.");
+        }
+        None
+    } else {
+        let cdlp_path = config.dataset.join(format!("{}-CDLP", dataset.name));
+        if cdlp_path.exists() {
+            Some(CdlpReference::load(&cdlp_path).unwrap_or_else(|e| {
+                panic!("failed to load CDLP reference: {}", e)
+            }))
+        } else {
+            if !config.csv && !config.quiet {
+                eprintln!("No CDLP reference file found, skipping LDBC validation.");
+            }
+            None
+        }
+    }
+}
+
+/// Load the graph, time CDLP on Strata and (unless `--strata-only`)
+/// petgraph, and record the results. With `--validate-only` it stops after
+/// the first Strata run, recording only a validation failure.
+fn run_benchmark(
+    config: &Config,
+    dataset: &LdbcDataset,
+    iterations: usize,
+    reference: Option<&CdlpReference>,
+    recorder: &mut ResultRecorder,
+) {
+    let total_elements = (dataset.vertices.len() + dataset.edges.len()) as f64;
+
+    // -----------------------------------------------------------------------
+    // Load phase
+    // -----------------------------------------------------------------------
+
+    let db = create_db(config.durability);
+    let strata_load_time = load_graph(&db, dataset);
+
+    let petgraph_state = if !config.strata_only {
+        let pg_start = Instant::now();
+        let (pg_graph, id_map) = dataset.to_petgraph_directed();
+        Some((pg_graph, id_map, pg_start.elapsed()))
+    } else {
+        None
+    };
+
+    if !config.csv && !config.quiet {
+        eprintln!("--- Load Phase ---");
+        eprintln!("  {:12} {}", "Strata:", fmt_ms(strata_load_time));
+        if let Some((_, _, pg_load_time)) = &petgraph_state {
+            eprintln!("  {:12} {}", "petgraph:", fmt_ms(*pg_load_time));
+        }
+        eprintln!();
+    }
+
+    // -----------------------------------------------------------------------
+    // CDLP phase — Strata
+    // -----------------------------------------------------------------------
+
+    if config.csv {
+        println!("\"engine\",\"run\",\"cdlp_time_ms\",\"evps\",\"vertices\",\"edges\"");
+    }
+
+    let mut strata_times = Vec::with_capacity(config.runs);
+    let mut adjacency_times = Vec::with_capacity(config.runs);
+    let mut communities = 0;
+    let mut failures: Vec<String> = Vec::new();
+    for run in 0..config.runs {
+        let cdlp_run = run_cdlp(&db, dataset, iterations);
+        strata_times.push(cdlp_run.elapsed);
+        adjacency_times.push(cdlp_run.adjacency);
+
+        if run == 0 {
+            communities = cdlp_run.labels.iter().collect::<HashSet<_>>().len();
+            if let Some(reference) = reference {
+                let (mismatches, details) =
+                    validate_labels(dataset, &cdlp_run.labels, &reference.labels);
+                if mismatches > 0 {
+                    failures.push(format!("{} labels differ from the LDBC reference", mismatches));
+                }
+                if !config.csv {
+                    report_validation("LDBC Validation", dataset.vertices.len(), mismatches, &details);
+                }
+            }
+            if let Some((ref pg_graph, ref id_map, _)) = petgraph_state {
+                let pg_labels = cdlp(
+                    &dataset.vertices,
+                    &petgraph_adjacency(pg_graph),
+                    dataset.directed,
+                    iterations,
+                );
+                let expected: HashMap<u64, u64> = id_map
+                    .iter()
+                    .map(|(&vid, idx)| (vid, pg_labels[idx.index()]))
+                    .collect();
+                let (mismatches, details) = validate_labels(dataset, &cdlp_run.labels, &expected);
+                if mismatches > 0 {
+                    failures.push(format!("{} labels differ from petgraph", mismatches));
+                }
+                if !config.csv {
+                    report_validation("Cross-validation", dataset.vertices.len(), mismatches, &details);
+                }
+            }
+            if !config.csv && !config.quiet {
+                eprintln!("Communities: {}", fmt_num(communities as u64));
+            }
+            if config.validate_only {
+                if !failures.is_empty() {
+                    recorder.record_failure(
+                        &result_name("strata", dataset, config.durability),
+                        base_params("strata", dataset, iterations, config.durability),
+                        ResultStatus::from_validation(&failures),
+                    );
+                } else if !config.csv {
+                    eprintln!("Validate-only mode, skipping remaining runs.");
+                }
+                return;
+            }
+        }
+
+        if config.csv {
+            print_csv_row("strata", run + 1, cdlp_run.elapsed, total_elements, dataset);
+        }
+    }
+    let strata_stats = compute_stats(&mut strata_times, total_elements);
+    let adjacency_avg = adjacency_times.iter().sum::<Duration>() / adjacency_times.len() as u32;
+
+    // -----------------------------------------------------------------------
+    // CDLP phase — petgraph
+    // -----------------------------------------------------------------------
+
+    let petgraph_stats = petgraph_state.as_ref().map(|(pg_graph, _, _)| {
+        let mut pg_times = Vec::with_capacity(config.runs);
+        for run in 0..config.runs {
+            let start = Instant::now();
+            let _ = cdlp(
+                &dataset.vertices,
+                &petgraph_adjacency(pg_graph),
+                dataset.directed,
+                iterations,
+            );
+            let elapsed = start.elapsed();
+            pg_times.push(elapsed);
+            if config.csv {
+                print_csv_row("petgraph", run + 1, elapsed, total_elements, dataset);
+            }
+        }
+        compute_stats(&mut pg_times, total_elements)
+    });
+
+    // -----------------------------------------------------------------------
+    // Output
+    // -----------------------------------------------------------------------
+
+    if config.quiet && !config.csv {
+        eprintln!(
+            "Strata CDLP: {}, EVPS: {}, adjacency read {}, communities: {}",
+            fmt_ms(strata_stats.avg),
+            fmt_num(strata_stats.avg_evps as u64),
+            fmt_ms(adjacency_avg),
+            communities
+        );
+        if let Some(ref pg) = petgraph_stats {
+            eprintln!(
+                "petgraph CDLP: {}, EVPS: {}",
+                fmt_ms(pg.avg),
+                fmt_num(pg.avg_evps as u64)
+            );
+        }
+    } else if !config.csv {
+        eprintln!();
+        eprintln!(
+            "--- CDLP Phase ({} runs, {} iterations) ---",
+            strata_stats.count, iterations
+        );
+        eprintln!(
+            "  {:16} {:>10} {:>10} {:>10} {:>14}",
+            "", "avg", "p50", "max", "EVPS"
+        );
+        eprintln!(
+            "  {:16} {:>10} {:>10} {:>10} {:>14}",
+            "Strata:",
+            fmt_ms(strata_stats.avg),
+            fmt_ms(strata_stats.p50),
+            fmt_ms(strata_stats.max),
+            fmt_num(strata_stats.avg_evps as u64),
+        );
+        if let Some(ref pg) = petgraph_stats {
+            eprintln!(
+                "  {:16} {:>10} {:>10} {:>10} {:>14}",
+                "petgraph:",
+                fmt_ms(pg.avg),
+                fmt_ms(pg.p50),
+                fmt_ms(pg.max),
+                fmt_num(pg.avg_evps as u64),
+            );
+            eprintln!(
+                "  {:16} {:>10}",
+                "Ratio:",
+                format!("{:.1}x", strata_stats.avg.as_secs_f64() / pg.avg.as_secs_f64()),
+            );
+        }
+        eprintln!(
+            "  Strata adjacency read: {} of {} avg",
+            fmt_ms(adjacency_avg),
+            fmt_ms(strata_stats.avg)
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Record results
+    // -----------------------------------------------------------------------
+
+    record(
+        recorder,
+        "strata",
+        dataset,
+        config.durability,
+        iterations,
+        communities,
+        &strata_stats,
+        ResultStatus::from_validation(&failures),
+    );
+    if let Some(ref pg) = petgraph_stats {
+        record(
+            recorder,
+            "petgraph",
+            dataset,
+            config.durability,
+            iterations,
+            communities,
+            pg,
+            ResultStatus::Success,
+        );
+    }
+}
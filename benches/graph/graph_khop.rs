@@ -0,0 +1,427 @@
+//! Graph k-hop Neighborhood Benchmark — interactive traversal latency
+//!
+//! Measures the latency of 1-, 2- and 3-hop neighborhood expansions
+//! (`graph_bfs` with a depth limit) from vertices sampled at different
+//! degrees. BFS to completion in graph_bfs reports throughput over the
+//! whole graph; this reports what a single bounded query costs, which is
+//! what an interactive caller waits on.
+//!
+//! Vertices are bucketed by degree in the traversal direction: `low` (below
+//! the median), `mid` (median to p90) and `high` (p90 and up). Each bucket
+//! contributes up to `--samples` vertices, evenly spaced through its degree
+//! range, and the timed queries cycle through them. Before timing, every
+//! sampled expansion is checked against an in-memory BFS over the edge list.
+//!
+//! Run:          `cargo bench --bench graph_khop`
+//! Quick:        `cargo bench --bench graph_khop -- -q`
+//! Custom data:  `cargo bench --bench graph_khop -- --dataset path/to/ldbc/dir`
+//! Hops:         `cargo bench --bench graph_khop -- --hops 1,2,3,4`
+//! Direction:    `cargo bench --bench graph_khop -- --direction both`
+//! Queries:      `cargo bench --bench graph_khop -- --queries 10000 --samples 500`
+//! Fixed time:   `cargo bench --bench graph_khop -- --duration 10`
+//! Disk-backed:  `cargo bench --bench graph_khop -- --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[allow(unused)]
+mod ldbc;
+
+use harness::recorder::ResultRecorder;
+use harness::{
+    create_db, measure_percentiles_or, print_hardware_info, report_percentiles, BenchDb,
+    DurabilityConfig, Percentiles,
+};
+use ldbc::LdbcDataset;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_duration, fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_HOPS: [usize; 3] = [1, 2, 3];
+const DEFAULT_SAMPLES: usize = 100;
+const DEFAULT_QUERIES: usize = 1_000;
+
+fn default_dataset_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-directed")
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    dataset: PathBuf,
+    durability: DurabilityConfig,
+    hops: Vec<usize>,
+    /// Sampled start vertices per degree bucket.
+    samples: usize,
+    /// Timed queries per bucket and hop count.
+    queries: usize,
+    /// Time each bucket and hop count for this long instead of `queries`.
+    duration: Option<Duration>,
+    /// `outgoing`, `incoming` or `both`; defaults to `outgoing` for a
+    /// directed dataset and `both` otherwise.
+    direction: Option<String>,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        dataset: default_dataset_dir(),
+        durability: DurabilityConfig::Cache,
+        hops: DEFAULT_HOPS.to_vec(),
+        samples: DEFAULT_SAMPLES,
+        queries: DEFAULT_QUERIES,
+        duration: None,
+        direction: None,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--dataset" => config.dataset = PathBuf::from(args.value()),
+            "--hops" => config.hops = args.list_checked("a list like 1,2,3", |&h| h > 0),
+            "--samples" => config.samples = args.parse_value::<usize>().max(1),
+            "--queries" => config.queries = args.parse_value::<usize>().max(1),
+            "--direction" => match args.value().as_str() {
+                d @ ("outgoing" | "incoming" | "both") => config.direction = Some(d.to_string()),
+                other => harness::cli::usage_error(&format!(
+                    "--direction expects outgoing, incoming or both; got {}",
+                    other
+                )),
+            },
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.quiet = args.quiet;
+    config.duration = args.duration;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Graph loading (Strata)
+// ---------------------------------------------------------------------------
+
+fn load_graph(db: &BenchDb, dataset: &LdbcDataset) -> Duration {
+    let start = Instant::now();
+
+    db.db.graph_create("ldbc").expect("graph_create failed");
+
+    for &vid in &dataset.vertices {
+        db.db
+            .graph_add_node("ldbc", &vid.to_string(), None, None)
+            .expect("graph_add_node failed");
+    }
+
+    for &(src, dst) in &dataset.edges {
+        db.db
+            .graph_add_edge(
+                "ldbc",
+                &src.to_string(),
+                &dst.to_string(),
+                "E",
+                None,
+                None,
+            )
+            .expect("graph_add_edge failed");
+    }
+
+    start.elapsed()
+}
+
+// ---------------------------------------------------------------------------
+// Degree buckets
+// ---------------------------------------------------------------------------
+
+/// Neighbors of every vertex in the traversal direction, without
+/// self-loops or repeats.
+fn adjacency(dataset: &LdbcDataset, direction: &str) -> HashMap<u64, Vec<u64>> {
+    let mut adj: HashMap<u64, Vec<u64>> = dataset.vertices.iter().map(|&v| (v, Vec::new())).collect();
+    for &(src, dst) in &dataset.edges {
+        if src == dst {
+            continue;
+        }
+        if direction != "incoming" {
+            adj.entry(src).or_default().push(dst);
+        }
+        if direction != "outgoing" {
+            adj.entry(dst).or_default().push(src);
+        }
+    }
+    for neighbors in adj.values_mut() {
+        neighbors.sort_unstable();
+        neighbors.dedup();
+    }
+    adj
+}
+
+struct Bucket {
+    label: &'static str,
+    /// Sampled start vertices, lowest degree first.
+    vertices: Vec<u64>,
+    min_degree: usize,
+    max_degree: usize,
+}
+
+/// Split vertices into low/mid/high degree buckets at the median and p90,
+/// and take up to `samples` evenly spaced vertices from each.
+fn degree_buckets(dataset: &LdbcDataset, adj: &HashMap<u64, Vec<u64>>, samples: usize) -> Vec<Bucket> {
+    let mut by_degree: Vec<(usize, u64)> = dataset.vertices.iter().map(|&v| (adj[&v].len(), v)).collect();
+    by_degree.sort_unstable();
+    let n = by_degree.len();
+    let bounds = [("low", 0, n / 2), ("mid", n / 2, n * 9 / 10), ("high", n * 9 / 10, n)];
+
+    bounds
+        .iter()
+        .filter(|(_, lo, hi)| hi > lo)
+        .map(|&(label, lo, hi)| {
+            let range = &by_degree[lo..hi];
+            let take = samples.min(range.len());
+            let vertices = (0..take).map(|i| range[i * range.len() / take].1).collect();
+            Bucket {
+                label,
+                vertices,
+                min_degree: range[0].0,
+                max_degree: range[range.len() - 1].0,
+            }
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Vertices within `hops` of `start` (excluding `start`), by in-memory BFS.
+fn reference_reached(adj: &HashMap<u64, Vec<u64>>, start: u64, hops: usize) -> usize {
+    let mut depth: HashMap<u64, usize> = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(u) = queue.pop_front() {
+        let d = depth[&u];
+        if d == hops {
+            continue;
+        }
+        for &v in &adj[&u] {
+            depth.entry(v).or_insert_with(|| {
+                queue.push_back(v);
+                d + 1
+            });
+        }
+    }
+    depth.len() - 1
+}
+
+/// Vertices a `hops`-deep Strata BFS from `start` reaches, excluding `start`.
+fn strata_reached(db: &BenchDb, start: u64, hops: usize, direction: &str) -> usize {
+    let result = db
+        .db
+        .graph_bfs("ldbc", &start.to_string(), hops, None, None, Some(direction))
+        .expect("graph_bfs failed");
+    result.depths.values().filter(|&&d| d > 0 && d <= hops).count()
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+/// Disk-backed runs end in their durability label, so they don't compare
+/// against the default cache run.
+fn result_name(
+    dataset: &LdbcDataset,
+    bucket: &Bucket,
+    hops: usize,
+    durability: DurabilityConfig,
+) -> String {
+    let name = format!("graph-khop/{}/{}-degree/{}-hop", dataset.name, bucket.label, hops);
+    match durability {
+        DurabilityConfig::Cache => name,
+        other => format!("{}/{}", name, other.label()),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record(
+    recorder: &mut ResultRecorder,
+    dataset: &LdbcDataset,
+    bucket: &Bucket,
+    hops: usize,
+    durability: DurabilityConfig,
+    direction: &str,
+    avg_reached: f64,
+    p: &Percentiles,
+    status: ResultStatus,
+) {
+    let mut params = HashMap::new();
+    params.insert("dataset".into(), serde_json::json!(dataset.name));
+    params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
+    params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
+    params.insert("hops".into(), serde_json::json!(hops));
+    params.insert("direction".into(), serde_json::json!(direction));
+    params.insert("durability".into(), serde_json::json!(durability.label()));
+    params.insert("degree_bucket".into(), serde_json::json!(bucket.label));
+    params.insert("min_degree".into(), serde_json::json!(bucket.min_degree));
+    params.insert("max_degree".into(), serde_json::json!(bucket.max_degree));
+    params.insert("start_vertices".into(), serde_json::json!(bucket.vertices.len()));
+    params.insert("avg_reached".into(), serde_json::json!(avg_reached));
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(dataset, bucket, hops, durability),
+        category: "graph-khop".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(p.ops_per_sec()),
+            p50_ns: Some(p.p50.as_nanos() as u64),
+            p95_ns: Some(p.p95.as_nanos() as u64),
+            p99_ns: Some(p.p99.as_nanos() as u64),
+            min_ns: Some(p.min.as_nanos() as u64),
+            max_ns: Some(p.max.as_nanos() as u64),
+            samples: Some(p.samples as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let dataset = LdbcDataset::load(&config.dataset).unwrap_or_else(|e| {
+        eprintln!("Failed to load dataset from {}: {}", config.dataset.display(), e);
+        std::process::exit(1);
+    });
+    let direction = config
+        .direction
+        .clone()
+        .unwrap_or_else(|| if dataset.directed { "outgoing" } else { "both" }.to_string());
+    let adj = adjacency(&dataset, &direction);
+    let buckets = degree_buckets(&dataset, &adj, config.samples);
+
+    eprintln!("=== Graph k-hop Neighborhood Benchmark ===");
+    eprintln!(
+        "Dataset:   {} ({} vertices, {} edges)",
+        dataset.name,
+        fmt_num(dataset.vertices.len() as u64),
+        fmt_num(dataset.edges.len() as u64),
+    );
+    eprintln!("Direction: {}", direction);
+    eprintln!("Hops:      {:?}", config.hops);
+    eprintln!("Durability: {}", config.durability.label());
+    match config.duration {
+        Some(d) => eprintln!("Queries:   for {:.1}s per bucket and hop count", d.as_secs_f64()),
+        None => eprintln!("Queries:   {} per bucket and hop count", fmt_num(config.queries as u64)),
+    }
+    for bucket in &buckets {
+        eprintln!(
+            "  {:<5} degree {}..={}, {} start vertices",
+            bucket.label,
+            bucket.min_degree,
+            bucket.max_degree,
+            bucket.vertices.len()
+        );
+    }
+    eprintln!();
+
+    let db = create_db(config.durability);
+    let load_time = load_graph(&db, &dataset);
+    if !config.quiet {
+        eprintln!("Load: {}", fmt_ms(load_time));
+        eprintln!();
+    }
+
+    let mut recorder = ResultRecorder::new("graph-khop");
+
+    for &hops in &config.hops {
+        if !config.quiet {
+            eprintln!("--- {}-hop ---", hops);
+        }
+        for bucket in &buckets {
+            let mut guard_params = HashMap::new();
+            guard_params.insert("dataset".into(), serde_json::json!(dataset.name));
+            guard_params.insert("hops".into(), serde_json::json!(hops));
+            guard_params.insert("direction".into(), serde_json::json!(direction));
+            guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+            guard_params.insert("degree_bucket".into(), serde_json::json!(bucket.label));
+            let name = result_name(&dataset, bucket, hops, config.durability);
+            recorder.guard(&name, guard_params, |recorder| {
+                // Validate every start vertex once; this also warms the engine
+                let mut mismatches = 0;
+                let mut reached_total = 0;
+                for &v in &bucket.vertices {
+                    let reached = strata_reached(&db, v, hops, &direction);
+                    if reached != reference_reached(&adj, v, hops) {
+                        mismatches += 1;
+                    }
+                    reached_total += reached;
+                }
+                let avg_reached = reached_total as f64 / bucket.vertices.len() as f64;
+
+                let mut next = 0;
+                let p = measure_percentiles_or(config.duration, config.queries, || {
+                    let start = bucket.vertices[next % bucket.vertices.len()].to_string();
+                    next += 1;
+                    let _ = db
+                        .db
+                        .graph_bfs("ldbc", &start, hops, None, None, Some(&direction))
+                        .expect("graph_bfs failed");
+                });
+
+                let label = format!("{}-degree {}-hop (avg {:.1} reached)", bucket.label, hops, avg_reached);
+                if config.quiet {
+                    eprintln!("{}: p50={} p99={}", label, fmt_duration(p.p50), fmt_duration(p.p99));
+                } else {
+                    report_percentiles(&label, &p);
+                }
+
+                let status = if mismatches == 0 {
+                    ResultStatus::Success
+                } else {
+                    eprintln!(
+                        "  Validation: FAIL ({} of {} start vertices reached a different neighborhood)",
+                        mismatches,
+                        bucket.vertices.len()
+                    );
+                    ResultStatus::ValidationFailed {
+                        message: format!(
+                            "{} start vertices reached a different {}-hop neighborhood than the edge list",
+                            mismatches, hops
+                        ),
+                    }
+                };
+                record(
+                    recorder,
+                    &dataset,
+                    bucket,
+                    hops,
+                    config.durability,
+                    &direction,
+                    avg_reached,
+                    &p,
+                    status,
+                );
+            });
+        }
+        if !config.quiet {
+            eprintln!();
+        }
+    }
+
+    eprintln!("=== Benchmark complete ===");
+    recorder.save_or_exit();
+}
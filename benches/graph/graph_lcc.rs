@@ -0,0 +1,602 @@
+//! LDBC Graphalytics LCC Benchmark — Strata vs petgraph head-to-head
+//!
+//! Computes the local clustering coefficient of every vertex (links among
+//! its in- and out-neighbors over the number possible) and validates the
+//! coefficients against the dataset's `-LCC` reference output with the
+//! Graphalytics epsilon rule. Throughput is reported as EVPS (Edges +
+//! Vertices processed per second), as in graph_bfs.
+//!
+//! Strata has no triangle-counting primitive. Each Strata run reads every
+//! vertex's out-neighbors from the engine with a depth-1 `graph_bfs`, then
+//! counts links in memory with the same kernel the petgraph run uses, so the
+//! two differ only in where the adjacency comes from. The adjacency read is
+//! reported separately.
+//!
+//! Run:           `cargo bench --bench graph_lcc`
+//! Quick:         `cargo bench --bench graph_lcc -- -q`
+//! Validate only: `cargo bench --bench graph_lcc -- --validate-only`
+//! CSV:           `cargo bench --bench graph_lcc -- --csv`
+//! Custom data:   `cargo bench --bench graph_lcc -- --dataset path/to/ldbc/dir`
+//! Strata only:   `cargo bench --bench graph_lcc -- --strata-only`
+//! Disk-backed:   `cargo bench --bench graph_lcc -- --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[allow(unused)]
+mod ldbc;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use ldbc::{epsilon_eq, lcc, petgraph_adjacency, LccReference, LdbcDataset, EPSILON};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_RUNS: usize = 10;
+
+fn default_dataset_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-directed")
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    dataset: PathBuf,
+    durability: DurabilityConfig,
+    runs: usize,
+    validate_only: bool,
+    no_validate: bool,
+    csv: bool,
+    quiet: bool,
+    strata_only: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        dataset: default_dataset_dir(),
+        durability: DurabilityConfig::Cache,
+        runs: DEFAULT_RUNS,
+        validate_only: false,
+        no_validate: false,
+        csv: false,
+        quiet: false,
+        strata_only: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--dataset" => config.dataset = PathBuf::from(args.value()),
+            "--runs" => config.runs = args.parse_value::<usize>().max(1),
+            "--validate-only" => config.validate_only = true,
+            "--no-validate" => config.no_validate = true,
+            "--strata-only" => config.strata_only = true,
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Graph loading (Strata)
+// ---------------------------------------------------------------------------
+
+fn load_graph(db: &BenchDb, dataset: &LdbcDataset) -> Duration {
+    let start = Instant::now();
+
+    db.db.graph_create("ldbc").expect("graph_create failed");
+
+    for &vid in &dataset.vertices {
+        db.db
+            .graph_add_node("ldbc", &vid.to_string(), None, None)
+            .expect("graph_add_node failed");
+    }
+
+    for &(src, dst) in &dataset.edges {
+        db.db
+            .graph_add_edge(
+                "ldbc",
+                &src.to_string(),
+                &dst.to_string(),
+                "E",
+                None,
+                None,
+            )
+            .expect("graph_add_edge failed");
+    }
+
+    start.elapsed()
+}
+
+// ---------------------------------------------------------------------------
+// LCC execution (Strata)
+// ---------------------------------------------------------------------------
+
+struct LccRun {
+    elapsed: Duration,
+    /// Part of `elapsed` spent reading adjacency from Strata.
+    adjacency: Duration,
+    /// Coefficients indexed like `dataset.vertices`.
+    coefficients: Vec<f64>,
+}
+
+/// Read every vertex's out-neighbors from Strata, then count links in memory.
+/// Self-loops don't show up in a BFS (the vertex is its own depth 0), which
+/// matches the kernel's rule of skipping them.
+fn run_lcc(db: &BenchDb, dataset: &LdbcDataset) -> LccRun {
+    let index: HashMap<String, usize> = dataset
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(i, vid)| (vid.to_string(), i))
+        .collect();
+    let direction = if dataset.directed { "outgoing" } else { "both" };
+
+    let start = Instant::now();
+    let mut out: Vec<Vec<usize>> = vec![Vec::new(); dataset.vertices.len()];
+    for (u, vid) in dataset.vertices.iter().enumerate() {
+        let result = db
+            .db
+            .graph_bfs("ldbc", &vid.to_string(), 1, None, None, Some(direction))
+            .expect("graph_bfs failed");
+        for (node, depth) in &result.depths {
+            if *depth == 1 {
+                if let Some(&v) = index.get(node) {
+                    out[u].push(v);
+                }
+            }
+        }
+    }
+    let adjacency = start.elapsed();
+
+    let coefficients = lcc(&out, dataset.directed);
+
+    LccRun {
+        elapsed: start.elapsed(),
+        adjacency,
+        coefficients,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Check `coefficients` (indexed like `dataset.vertices`) against
+/// `expected`. Returns (mismatches, first few mismatch details).
+fn validate_coefficients(
+    dataset: &LdbcDataset,
+    coefficients: &[f64],
+    expected: &HashMap<u64, f64>,
+) -> (usize, Vec<String>) {
+    let mut mismatches = 0;
+    let mut details = Vec::new();
+
+    for (&vid, &actual) in dataset.vertices.iter().zip(coefficients) {
+        match expected.get(&vid) {
+            Some(&e) if epsilon_eq(actual, e) => {}
+            Some(&e) => {
+                mismatches += 1;
+                if details.len() < 10 {
+                    details.push(format!("vertex {}: expected {:.10e}, got {:.10e}", vid, e, actual));
+                }
+            }
+            None => {
+                mismatches += 1;
+                if details.len() < 10 {
+                    details.push(format!("vertex {}: missing from reference", vid));
+                }
+            }
+        }
+    }
+
+    (mismatches, details)
+}
+
+fn report_validation(label: &str, checked: usize, mismatches: usize, details: &[String]) {
+    if mismatches == 0 {
+        eprintln!(
+            "{}: PASS ({} vertices within epsilon {})",
+            label,
+            fmt_num(checked as u64),
+            EPSILON
+        );
+    } else {
+        eprintln!(
+            "{}: FAIL ({} mismatches out of {} vertices)",
+            label, mismatches, checked
+        );
+        for detail in details {
+            eprintln!("  {}", detail);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Run statistics
+// ---------------------------------------------------------------------------
+
+struct RunStats {
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    avg_evps: f64,
+    count: usize,
+}
+
+fn compute_stats(times: &mut [Duration], total_elements: f64) -> RunStats {
+    assert!(!times.is_empty(), "compute_stats requires at least one run");
+    times.sort_unstable();
+    let len = times.len();
+    let sum: Duration = times.iter().sum();
+    let avg = sum / len as u32;
+    let avg_secs = avg.as_secs_f64();
+    let avg_evps = if avg_secs > 0.0 {
+        total_elements / avg_secs
+    } else {
+        0.0
+    };
+    RunStats {
+        avg,
+        p50: times[len * 50 / 100],
+        p95: times[(len * 95 / 100).min(len - 1)],
+        p99: times[(len * 99 / 100).min(len - 1)],
+        min: times[0],
+        max: times[len - 1],
+        avg_evps,
+        count: len,
+    }
+}
+
+fn print_csv_row(engine: &str, run: usize, elapsed: Duration, total_elements: f64, dataset: &LdbcDataset) {
+    println!(
+        "\"{}\",{},{:.3},{:.0},{},{}",
+        engine,
+        run,
+        elapsed.as_secs_f64() * 1000.0,
+        total_elements / elapsed.as_secs_f64(),
+        dataset.vertices.len(),
+        dataset.edges.len()
+    );
+}
+
+fn result_name(engine: &str, dataset: &LdbcDataset, durability: DurabilityConfig) -> String {
+    let mut name = format!(
+        "graph-lcc/{}/{}/{}V-{}E",
+        engine,
+        dataset.name,
+        dataset.vertices.len(),
+        dataset.edges.len()
+    );
+    if !matches!(durability, DurabilityConfig::Cache) {
+        name.push_str(&format!("/{}", durability.label()));
+    }
+    name
+}
+
+fn base_params(engine: &str, dataset: &LdbcDataset, durability: DurabilityConfig) -> HashMap<String, serde_json::Value> {
+    let mut params = HashMap::new();
+    params.insert("dataset".into(), serde_json::json!(dataset.name));
+    params.insert("engine".into(), serde_json::json!(engine));
+    params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
+    params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
+    params.insert("durability".into(), serde_json::json!(durability.label()));
+    params
+}
+
+fn record(
+    recorder: &mut ResultRecorder,
+    engine: &str,
+    dataset: &LdbcDataset,
+    durability: DurabilityConfig,
+    mean_coefficient: f64,
+    stats: &RunStats,
+    status: ResultStatus,
+) {
+    let mut params = base_params(engine, dataset, durability);
+    params.insert("mean_coefficient".into(), serde_json::json!(mean_coefficient));
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(engine, dataset, durability),
+        category: "graph-lcc".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(stats.avg_evps),
+            p50_ns: Some(stats.p50.as_nanos() as u64),
+            p95_ns: Some(stats.p95.as_nanos() as u64),
+            p99_ns: Some(stats.p99.as_nanos() as u64),
+            min_ns: Some(stats.min.as_nanos() as u64),
+            max_ns: Some(stats.max.as_nanos() as u64),
+            avg_ns: Some(stats.avg.as_nanos() as u64),
+            samples: Some(stats.count as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let dataset = LdbcDataset::load(&config.dataset).unwrap_or_else(|e| {
+        eprintln!("Failed to load dataset from {}: {}", config.dataset.display(), e);
+        std::process::exit(1);
+    });
+
+    if !config.csv {
+        eprintln!("=== LDBC Graphalytics LCC Benchmark ===");
+        eprintln!(
+            "Dataset:    {} ({} vertices, {} edges, {})",
+            dataset.name,
+            fmt_num(dataset.vertices.len() as u64),
+            fmt_num(dataset.edges.len() as u64),
+            if dataset.directed { "directed" } else { "undirected" },
+        );
+        eprintln!("Runs:       {}", config.runs);
+        eprintln!("Durability: {}", config.durability.label());
+        if config.strata_only {
+            eprintln!("Mode:       strata-only (petgraph comparison skipped)");
+        }
+        eprintln!();
+    }
+
+    let mut recorder = ResultRecorder::new("graph-lcc");
+    recorder.guard(
+        &result_name("strata", &dataset, config.durability),
+        base_params("strata", &dataset, config.durability),
+        |recorder| {
+            let reference = load_reference(&config, &dataset);
+            run_benchmark(&config, &dataset, reference.as_ref(), recorder)
+        },
+    );
+    if config.validate_only && recorder.failure_count() == 0 {
+        return;
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
+
+/// The LDBC LCC reference for `dataset`, or `None` when validation
+/// is off or doesn't apply. A reference that exists but can't be read
+/// panics, so the run's guard records it as an error.
+fn load_reference(config: &Config, dataset: &LdbcDataset) -> Option<LccReference> {
+    if config.no_validate {
+        None
+    } else {
+        let lcc_path = config.dataset.join(format!("{}-LCC", dataset.name));
+        if lcc_path.exists() {
+            Some(LccReference::load(&lcc_path).unwrap_or_else(|e| {
+                panic!("failed to load LCC reference: {}", e)
+            }))
+        } else {
+            if !config.csv && !config.quiet {
+                eprintln!("No LCC reference file found, skipping LDBC validation.");
+            }
+            None
+        }
+    }
+}
+
+/// Load the graph, time LCC on Strata and (unless `--strata-only`)
+/// petgraph, and record the results. With `--validate-only` it stops after
+/// the first Strata run, recording only a validation failure.
+fn run_benchmark(
+    config: &Config,
+    dataset: &LdbcDataset,
+    reference: Option<&LccReference>,
+    recorder: &mut ResultRecorder,
+) {
+    let total_elements = (dataset.vertices.len() + dataset.edges.len()) as f64;
+
+    // -----------------------------------------------------------------------
+    // Load phase
+    // -----------------------------------------------------------------------
+
+    let db = create_db(config.durability);
+    let strata_load_time = load_graph(&db, dataset);
+
+    let petgraph_state = if !config.strata_only {
+        let pg_start = Instant::now();
+        let (pg_graph, id_map) = dataset.to_petgraph_directed();
+        Some((pg_graph, id_map, pg_start.elapsed()))
+    } else {
+        None
+    };
+
+    if !config.csv && !config.quiet {
+        eprintln!("--- Load Phase ---");
+        eprintln!("  {:12} {}", "Strata:", fmt_ms(strata_load_time));
+        if let Some((_, _, pg_load_time)) = &petgraph_state {
+            eprintln!("  {:12} {}", "petgraph:", fmt_ms(*pg_load_time));
+        }
+        eprintln!();
+    }
+
+    // -----------------------------------------------------------------------
+    // LCC phase — Strata
+    // -----------------------------------------------------------------------
+
+    if config.csv {
+        println!("\"engine\",\"run\",\"lcc_time_ms\",\"evps\",\"vertices\",\"edges\"");
+    }
+
+    let mut strata_times = Vec::with_capacity(config.runs);
+    let mut adjacency_times = Vec::with_capacity(config.runs);
+    let mut mean_coefficient = 0.0;
+    let mut failures: Vec<String> = Vec::new();
+    for run in 0..config.runs {
+        let lcc_run = run_lcc(&db, dataset);
+        strata_times.push(lcc_run.elapsed);
+        adjacency_times.push(lcc_run.adjacency);
+
+        if run == 0 {
+            mean_coefficient = lcc_run.coefficients.iter().sum::<f64>()
+                / lcc_run.coefficients.len().max(1) as f64;
+            if let Some(reference) = reference {
+                let (mismatches, details) =
+                    validate_coefficients(dataset, &lcc_run.coefficients, &reference.coefficients);
+                if mismatches > 0 {
+                    failures.push(format!("{} coefficients differ from the LDBC reference", mismatches));
+                }
+                if !config.csv {
+                    report_validation("LDBC Validation", dataset.vertices.len(), mismatches, &details);
+                }
+            }
+            if let Some((ref pg_graph, ref id_map, _)) = petgraph_state {
+                let pg_coefficients = lcc(&petgraph_adjacency(pg_graph), dataset.directed);
+                let expected: HashMap<u64, f64> = id_map
+                    .iter()
+                    .map(|(&vid, idx)| (vid, pg_coefficients[idx.index()]))
+                    .collect();
+                let (mismatches, details) =
+                    validate_coefficients(dataset, &lcc_run.coefficients, &expected);
+                if mismatches > 0 {
+                    failures.push(format!("{} coefficients differ from petgraph", mismatches));
+                }
+                if !config.csv {
+                    report_validation("Cross-validation", dataset.vertices.len(), mismatches, &details);
+                }
+            }
+            if !config.csv && !config.quiet {
+                eprintln!("Mean coefficient: {:.6}", mean_coefficient);
+            }
+            if config.validate_only {
+                if !failures.is_empty() {
+                    recorder.record_failure(
+                        &result_name("strata", dataset, config.durability),
+                        base_params("strata", dataset, config.durability),
+                        ResultStatus::from_validation(&failures),
+                    );
+                } else if !config.csv {
+                    eprintln!("Validate-only mode, skipping remaining runs.");
+                }
+                return;
+            }
+        }
+
+        if config.csv {
+            print_csv_row("strata", run + 1, lcc_run.elapsed, total_elements, dataset);
+        }
+    }
+    let strata_stats = compute_stats(&mut strata_times, total_elements);
+    let adjacency_avg = adjacency_times.iter().sum::<Duration>() / adjacency_times.len() as u32;
+
+    // -----------------------------------------------------------------------
+    // LCC phase — petgraph
+    // -----------------------------------------------------------------------
+
+    let petgraph_stats = petgraph_state.as_ref().map(|(pg_graph, _, _)| {
+        let mut pg_times = Vec::with_capacity(config.runs);
+        for run in 0..config.runs {
+            let start = Instant::now();
+            let _ = lcc(&petgraph_adjacency(pg_graph), dataset.directed);
+            let elapsed = start.elapsed();
+            pg_times.push(elapsed);
+            if config.csv {
+                print_csv_row("petgraph", run + 1, elapsed, total_elements, dataset);
+            }
+        }
+        compute_stats(&mut pg_times, total_elements)
+    });
+
+    // -----------------------------------------------------------------------
+    // Output
+    // -----------------------------------------------------------------------
+
+    if config.quiet && !config.csv {
+        eprintln!(
+            "Strata LCC: {}, EVPS: {}, adjacency read {}, mean coefficient: {:.6}",
+            fmt_ms(strata_stats.avg),
+            fmt_num(strata_stats.avg_evps as u64),
+            fmt_ms(adjacency_avg),
+            mean_coefficient
+        );
+        if let Some(ref pg) = petgraph_stats {
+            eprintln!(
+                "petgraph LCC: {}, EVPS: {}",
+                fmt_ms(pg.avg),
+                fmt_num(pg.avg_evps as u64)
+            );
+        }
+    } else if !config.csv {
+        eprintln!();
+        eprintln!("--- LCC Phase ({} runs) ---", strata_stats.count);
+        eprintln!(
+            "  {:16} {:>10} {:>10} {:>10} {:>14}",
+            "", "avg", "p50", "max", "EVPS"
+        );
+        eprintln!(
+            "  {:16} {:>10} {:>10} {:>10} {:>14}",
+            "Strata:",
+            fmt_ms(strata_stats.avg),
+            fmt_ms(strata_stats.p50),
+            fmt_ms(strata_stats.max),
+            fmt_num(strata_stats.avg_evps as u64),
+        );
+        if let Some(ref pg) = petgraph_stats {
+            eprintln!(
+                "  {:16} {:>10} {:>10} {:>10} {:>14}",
+                "petgraph:",
+                fmt_ms(pg.avg),
+                fmt_ms(pg.p50),
+                fmt_ms(pg.max),
+                fmt_num(pg.avg_evps as u64),
+            );
+            eprintln!(
+                "  {:16} {:>10}",
+                "Ratio:",
+                format!("{:.1}x", strata_stats.avg.as_secs_f64() / pg.avg.as_secs_f64()),
+            );
+        }
+        eprintln!(
+            "  Strata adjacency read: {} of {} avg",
+            fmt_ms(adjacency_avg),
+            fmt_ms(strata_stats.avg)
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Record results
+    // -----------------------------------------------------------------------
+
+    record(
+        recorder,
+        "strata",
+        dataset,
+        config.durability,
+        mean_coefficient,
+        &strata_stats,
+        ResultStatus::from_validation(&failures),
+    );
+    if let Some(ref pg) = petgraph_stats {
+        record(recorder, "petgraph", dataset, config.durability, mean_coefficient, pg, ResultStatus::Success);
+    }
+}
@@ -0,0 +1,438 @@
+//! Graph Bulk-Load Benchmark — batched vertex and edge insertion
+//!
+//! Loads an LDBC dataset into a fresh graph once per durability mode and
+//! batch size, and reports vertices/sec and edges/sec. Each batch is one
+//! transaction (`TxnBegin`, N `GraphAddNode` or `GraphAddEdge` commands,
+//! `TxnCommit`), so a batch of 1 is a commit per element and larger batches
+//! show how much of the load phase in the other graph benches is per-commit
+//! overhead. Vertices load first, then edges, as in graph_bfs.
+//!
+//! After the first run of each configuration, every vertex's out-neighbors
+//! are read back with a depth-1 `graph_bfs` and checked against the edge
+//! list, so a batch that didn't commit shows up as a failed result.
+//!
+//! The bundled fixture has only a handful of edges, so every batch above
+//! its size is a single transaction. Use `--dataset` with a real LDBC graph
+//! to see the sweep.
+//!
+//! Run:          `cargo bench --bench graph_load`
+//! Quick:        `cargo bench --bench graph_load -- -q`
+//! CSV:          `cargo bench --bench graph_load -- --csv`
+//! Custom data:  `cargo bench --bench graph_load -- --dataset path/to/ldbc/dir`
+//! Batch sizes:  `cargo bench --bench graph_load -- --batch-sizes 1,1000,100000`
+//! Durability:   `cargo bench --bench graph_load -- --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[allow(unused)]
+mod ldbc;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use ldbc::LdbcDataset;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_ms, fmt_num, fmt_us};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{Command, Session};
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_RUNS: usize = 3;
+const DEFAULT_BATCH_SIZES: [usize; 3] = [1, 100, 10_000];
+const GRAPH: &str = "ldbc";
+
+fn default_dataset_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-directed")
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    dataset: PathBuf,
+    batch_sizes: Vec<usize>,
+    durability: Vec<DurabilityConfig>,
+    runs: usize,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        dataset: default_dataset_dir(),
+        batch_sizes: DEFAULT_BATCH_SIZES.to_vec(),
+        durability: DurabilityConfig::ALL.to_vec(),
+        runs: DEFAULT_RUNS,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--dataset" => config.dataset = PathBuf::from(args.value()),
+            "--batch-sizes" => {
+                config.batch_sizes = args.list_checked("a list like 1,100,10000", |&b| b > 0);
+            }
+            "--runs" => config.runs = args.parse_value::<usize>().max(1),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Batched load
+// ---------------------------------------------------------------------------
+
+struct LoadRun {
+    vertex_time: Duration,
+    edge_time: Duration,
+    /// Duration of each committed batch, vertices then edges.
+    batch_times: Vec<Duration>,
+}
+
+/// Run `commands` as one transaction.
+fn commit_batch(session: &mut Session, commands: impl Iterator<Item = Command>) {
+    session
+        .execute(Command::TxnBegin { branch: None, options: None })
+        .expect("TxnBegin failed");
+    for command in commands {
+        session.execute(command).expect("graph insert failed");
+    }
+    session.execute(Command::TxnCommit).expect("TxnCommit failed");
+}
+
+fn add_node(vid: u64) -> Command {
+    Command::GraphAddNode {
+        branch: None,
+        space: None,
+        graph: GRAPH.to_string(),
+        node_id: vid.to_string(),
+        entity_ref: None,
+        properties: None,
+    }
+}
+
+fn add_edge(src: u64, dst: u64) -> Command {
+    Command::GraphAddEdge {
+        branch: None,
+        space: None,
+        graph: GRAPH.to_string(),
+        src: src.to_string(),
+        dst: dst.to_string(),
+        edge_type: "E".to_string(),
+        weight: None,
+        properties: None,
+    }
+}
+
+/// Load every vertex, then every edge, `batch` elements per transaction.
+fn load_batched(db: &BenchDb, dataset: &LdbcDataset, batch: usize) -> LoadRun {
+    db.db.graph_create(GRAPH).expect("graph_create failed");
+    let mut session = db.db.session();
+    let mut batch_times = Vec::new();
+
+    let start = Instant::now();
+    for chunk in dataset.vertices.chunks(batch) {
+        let t = Instant::now();
+        commit_batch(&mut session, chunk.iter().map(|&vid| add_node(vid)));
+        batch_times.push(t.elapsed());
+    }
+    let vertex_time = start.elapsed();
+
+    let start = Instant::now();
+    for chunk in dataset.edges.chunks(batch) {
+        let t = Instant::now();
+        commit_batch(&mut session, chunk.iter().map(|&(src, dst)| add_edge(src, dst)));
+        batch_times.push(t.elapsed());
+    }
+    let edge_time = start.elapsed();
+
+    LoadRun {
+        vertex_time,
+        edge_time,
+        batch_times,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Vertices whose out-neighbors in Strata differ from the edge list.
+/// Self-loops are left out of both sides; a BFS never returns them.
+fn adjacency_mismatches(db: &BenchDb, dataset: &LdbcDataset) -> Vec<u64> {
+    let mut expected: HashMap<u64, HashSet<String>> = HashMap::new();
+    for &(src, dst) in &dataset.edges {
+        if src != dst {
+            expected.entry(src).or_default().insert(dst.to_string());
+        }
+    }
+
+    let mut mismatches = Vec::new();
+    for &vid in &dataset.vertices {
+        let result = db
+            .db
+            .graph_bfs(GRAPH, &vid.to_string(), 1, None, None, Some("outgoing"))
+            .expect("graph_bfs failed");
+        let actual: HashSet<String> = result
+            .depths
+            .into_iter()
+            .filter(|(_, depth)| *depth == 1)
+            .map(|(node, _)| node)
+            .collect();
+        if actual != expected.remove(&vid).unwrap_or_default() {
+            mismatches.push(vid);
+        }
+    }
+    mismatches
+}
+
+// ---------------------------------------------------------------------------
+// Run statistics
+// ---------------------------------------------------------------------------
+
+struct LoadStats {
+    vertices_per_sec: f64,
+    edges_per_sec: f64,
+    vertex_time: Duration,
+    edge_time: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    avg: Duration,
+    /// Batches committed per run.
+    commits: usize,
+    batches: usize,
+}
+
+fn per_sec(count: usize, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        count as f64 / secs
+    } else {
+        0.0
+    }
+}
+
+fn compute_stats(runs: &[LoadRun], dataset: &LdbcDataset) -> LoadStats {
+    assert!(!runs.is_empty(), "compute_stats requires at least one run");
+    let n = runs.len() as u32;
+    let vertex_time = runs.iter().map(|r| r.vertex_time).sum::<Duration>() / n;
+    let edge_time = runs.iter().map(|r| r.edge_time).sum::<Duration>() / n;
+
+    let mut times: Vec<Duration> = runs.iter().flat_map(|r| r.batch_times.iter().copied()).collect();
+    times.sort_unstable();
+    let len = times.len().max(1);
+    let at = |pct: usize| times.get((len * pct / 100).min(len - 1)).copied().unwrap_or_default();
+
+    LoadStats {
+        vertices_per_sec: per_sec(dataset.vertices.len(), vertex_time),
+        edges_per_sec: per_sec(dataset.edges.len(), edge_time),
+        vertex_time,
+        edge_time,
+        p50: at(50),
+        p95: at(95),
+        p99: at(99),
+        min: times.first().copied().unwrap_or_default(),
+        max: times.last().copied().unwrap_or_default(),
+        avg: times.iter().sum::<Duration>() / len as u32,
+        commits: runs[0].batch_times.len(),
+        batches: times.len(),
+    }
+}
+
+fn print_row(batch: usize, s: &LoadStats) {
+    eprintln!(
+        "  {:>8} {:>14} {:>14} {:>10} {:>10} {:>10} {:>10}",
+        fmt_num(batch as u64),
+        fmt_num(s.vertices_per_sec as u64),
+        fmt_num(s.edges_per_sec as u64),
+        fmt_ms(s.edge_time),
+        fmt_us(s.p50),
+        fmt_us(s.p99),
+        fmt_num(s.commits as u64),
+    );
+}
+
+fn print_csv_row(mode: DurabilityConfig, batch: usize, run: usize, r: &LoadRun, dataset: &LdbcDataset) {
+    println!(
+        "\"{}\",{},{},{:.3},{:.3},{:.0},{:.0}",
+        mode.label(),
+        batch,
+        run,
+        r.vertex_time.as_secs_f64() * 1000.0,
+        r.edge_time.as_secs_f64() * 1000.0,
+        per_sec(dataset.vertices.len(), r.vertex_time),
+        per_sec(dataset.edges.len(), r.edge_time),
+    );
+}
+
+fn result_name(dataset: &LdbcDataset, batch: usize, mode: DurabilityConfig) -> String {
+    format!("graph-load/{}/batch-{}/{}", dataset.name, batch, mode.label())
+}
+
+fn base_params(dataset: &LdbcDataset, batch: usize, mode: DurabilityConfig) -> HashMap<String, serde_json::Value> {
+    let mut params = HashMap::new();
+    params.insert("dataset".into(), serde_json::json!(dataset.name));
+    params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
+    params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
+    params.insert("batch_size".into(), serde_json::json!(batch));
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params
+}
+
+fn record(
+    recorder: &mut ResultRecorder,
+    dataset: &LdbcDataset,
+    batch: usize,
+    mode: DurabilityConfig,
+    stats: &LoadStats,
+    status: ResultStatus,
+) {
+    let mut params = base_params(dataset, batch, mode);
+    params.insert("vertices_per_sec".into(), serde_json::json!(stats.vertices_per_sec));
+    params.insert("edges_per_sec".into(), serde_json::json!(stats.edges_per_sec));
+    params.insert("vertex_load_ms".into(), serde_json::json!(stats.vertex_time.as_secs_f64() * 1000.0));
+    params.insert("edge_load_ms".into(), serde_json::json!(stats.edge_time.as_secs_f64() * 1000.0));
+    params.insert("commits".into(), serde_json::json!(stats.commits));
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(dataset, batch, mode),
+        category: "graph-load".to_string(),
+        parameters: params,
+        // Throughput is edges/sec; latency is per committed batch
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(stats.edges_per_sec),
+            p50_ns: Some(stats.p50.as_nanos() as u64),
+            p95_ns: Some(stats.p95.as_nanos() as u64),
+            p99_ns: Some(stats.p99.as_nanos() as u64),
+            min_ns: Some(stats.min.as_nanos() as u64),
+            max_ns: Some(stats.max.as_nanos() as u64),
+            avg_ns: Some(stats.avg.as_nanos() as u64),
+            samples: Some(stats.batches as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let dataset = LdbcDataset::load(&config.dataset).unwrap_or_else(|e| {
+        eprintln!("Failed to load dataset from {}: {}", config.dataset.display(), e);
+        std::process::exit(1);
+    });
+
+    if !config.csv {
+        eprintln!("=== Graph Bulk-Load Benchmark ===");
+        eprintln!(
+            "Dataset:     {} ({} vertices, {} edges)",
+            dataset.name,
+            fmt_num(dataset.vertices.len() as u64),
+            fmt_num(dataset.edges.len() as u64),
+        );
+        eprintln!("Batch sizes: {:?}", config.batch_sizes);
+        eprintln!("Runs:        {}", config.runs);
+        eprintln!();
+    }
+
+    if config.csv {
+        println!("\"durability\",\"batch_size\",\"run\",\"vertex_load_ms\",\"edge_load_ms\",\"vertices_per_sec\",\"edges_per_sec\"");
+    }
+
+    let mut recorder = ResultRecorder::new("graph-load");
+
+    for &mode in &config.durability {
+        if !config.csv && !config.quiet {
+            eprintln!("--- durability: {} ---", mode.label());
+            eprintln!(
+                "  {:>8} {:>14} {:>14} {:>10} {:>10} {:>10} {:>10}",
+                "batch", "vertices/s", "edges/s", "edge load", "p50 txn", "p99 txn", "commits"
+            );
+        }
+
+        for &batch in &config.batch_sizes {
+            let name = result_name(&dataset, batch, mode);
+            recorder.guard(&name, base_params(&dataset, batch, mode), |recorder| {
+                let mut runs = Vec::with_capacity(config.runs);
+                let mut mismatches = Vec::new();
+                for run in 0..config.runs {
+                    let db = create_db(mode);
+                    let load = load_batched(&db, &dataset, batch);
+                    if run == 0 {
+                        mismatches = adjacency_mismatches(&db, &dataset);
+                    }
+                    if config.csv {
+                        print_csv_row(mode, batch, run + 1, &load, &dataset);
+                    }
+                    runs.push(load);
+                }
+                let stats = compute_stats(&runs, &dataset);
+
+                if config.quiet && !config.csv {
+                    eprintln!(
+                        "{}/batch-{}: {} vertices/s, {} edges/s",
+                        mode.label(),
+                        batch,
+                        fmt_num(stats.vertices_per_sec as u64),
+                        fmt_num(stats.edges_per_sec as u64)
+                    );
+                } else if !config.csv {
+                    print_row(batch, &stats);
+                }
+
+                let status = if mismatches.is_empty() {
+                    ResultStatus::Success
+                } else {
+                    if !config.csv {
+                        eprintln!(
+                            "  Validation: FAIL ({} of {} vertices have the wrong out-neighbors, e.g. {:?})",
+                            mismatches.len(),
+                            dataset.vertices.len(),
+                            &mismatches[..mismatches.len().min(5)]
+                        );
+                    }
+                    ResultStatus::ValidationFailed {
+                        message: format!(
+                            "{} vertices have out-neighbors that differ from the edge list",
+                            mismatches.len()
+                        ),
+                    }
+                };
+                record(recorder, &dataset, batch, mode, &stats, status);
+            });
+        }
+
+        if !config.csv && !config.quiet {
+            eprintln!();
+        }
+    }
+
+    if !config.csv {
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,578 @@
+//! Graph Mutation-Under-Read Benchmark — traversal latency while edges change
+//!
+//! Agent knowledge graphs are written to while they're queried. This loads
+//! an LDBC dataset, then for `--secs` per window one writer adds and removes
+//! edges at a target rate while `--readers` threads run neighbor lookups
+//! (depth-1 `graph_bfs`) or full BFS traversals. Rate 0 is the read-only
+//! baseline; `max` mutates as fast as the writer can. Each window reports
+//! the mutation rate achieved, read throughput, and read latency against
+//! the baseline.
+//!
+//! The writer alternates between adding an edge of type `M` between two
+//! dataset vertices and removing the edge it just added, so the graph stays
+//! the size of the dataset plus at most one edge. After each window, every
+//! vertex the writer touched must have its original out-neighbors again.
+//!
+//! Run:          `cargo bench --bench graph_mutation`
+//! Quick:        `cargo bench --bench graph_mutation -- -q --secs 1`
+//! Custom data:  `cargo bench --bench graph_mutation -- --dataset path/to/ldbc/dir`
+//! Rates:        `cargo bench --bench graph_mutation -- --rates 0,1000,max`
+//! Queries:      `cargo bench --bench graph_mutation -- --queries neighbors --readers 4`
+//! Durability:   `cargo bench --bench graph_mutation -- --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[allow(unused)]
+mod ldbc;
+
+use harness::clock::{run_paced, Clock, RealClock};
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use ldbc::LdbcDataset;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_duration, fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+/// Target mutations per second; `None` = unthrottled.
+const DEFAULT_RATES: &[Option<u64>] = &[Some(0), Some(100), Some(1_000), None];
+const DEFAULT_SECS: u64 = 5;
+const DEFAULT_READERS: usize = 1;
+const GRAPH: &str = "ldbc";
+/// Edge type of the writer's edges, so removals never touch dataset edges.
+const MUTATION_EDGE_TYPE: &str = "M";
+
+fn default_dataset_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-directed")
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq)]
+enum Query {
+    /// Depth-1 `graph_bfs`.
+    Neighbors,
+    /// `graph_bfs` to completion.
+    Bfs,
+}
+
+impl Query {
+    fn label(self) -> &'static str {
+        match self {
+            Query::Neighbors => "neighbors",
+            Query::Bfs => "bfs",
+        }
+    }
+
+    fn depth(self) -> usize {
+        match self {
+            Query::Neighbors => 1,
+            Query::Bfs => usize::MAX,
+        }
+    }
+}
+
+struct Config {
+    dataset: PathBuf,
+    rates: Vec<Option<u64>>,
+    queries: Vec<Query>,
+    readers: usize,
+    secs: u64,
+    durability: DurabilityConfig,
+    csv: bool,
+    quiet: bool,
+}
+
+fn rate_label(rate: Option<u64>) -> String {
+    rate.map(|r| r.to_string()).unwrap_or_else(|| "max".into())
+}
+
+/// Parse `0,1000,max`. `None` if an entry is neither, or there are none.
+fn parse_rates(s: &str) -> Option<Vec<Option<u64>>> {
+    let rates: Option<Vec<_>> = s
+        .split(',')
+        .map(|v| match v.trim() {
+            "max" => Some(None),
+            v => v.parse().ok().map(Some),
+        })
+        .collect();
+    rates.filter(|r| !r.is_empty())
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        dataset: default_dataset_dir(),
+        rates: DEFAULT_RATES.to_vec(),
+        queries: vec![Query::Neighbors, Query::Bfs],
+        readers: DEFAULT_READERS,
+        secs: DEFAULT_SECS,
+        durability: DurabilityConfig::Cache,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--dataset" => config.dataset = PathBuf::from(args.value()),
+            "--rates" => {
+                let v = args.value();
+                config.rates = parse_rates(&v).unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--rates expects a list like 0,1000,max; got {}", v))
+                });
+            }
+            "--queries" => {
+                let v = args.value();
+                config.queries = match v.as_str() {
+                    "neighbors" => vec![Query::Neighbors],
+                    "bfs" => vec![Query::Bfs],
+                    "all" => vec![Query::Neighbors, Query::Bfs],
+                    other => harness::cli::usage_error(&format!(
+                        "--queries expects neighbors, bfs or all; got {}",
+                        other
+                    )),
+                };
+            }
+            "--readers" => config.readers = args.parse_value::<usize>().max(1),
+            "--secs" => config.secs = args.parse_value::<u64>().max(1),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Graph loading (Strata)
+// ---------------------------------------------------------------------------
+
+fn load_graph(db: &BenchDb, dataset: &LdbcDataset) -> Duration {
+    let start = Instant::now();
+
+    db.db.graph_create(GRAPH).expect("graph_create failed");
+
+    for &vid in &dataset.vertices {
+        db.db
+            .graph_add_node(GRAPH, &vid.to_string(), None, None)
+            .expect("graph_add_node failed");
+    }
+
+    for &(src, dst) in &dataset.edges {
+        db.db
+            .graph_add_edge(
+                GRAPH,
+                &src.to_string(),
+                &dst.to_string(),
+                "E",
+                None,
+                None,
+            )
+            .expect("graph_add_edge failed");
+    }
+
+    start.elapsed()
+}
+
+// ---------------------------------------------------------------------------
+// Mutation window
+// ---------------------------------------------------------------------------
+
+/// Spread `n` over `0..len` without an RNG, so every run touches the same
+/// vertices. The multiplier is the 32-bit golden-ratio constant.
+fn spread(n: usize, len: usize) -> usize {
+    (n as u64).wrapping_mul(2_654_435_761) as usize % len
+}
+
+/// Endpoints of the writer's `pair`-th edge.
+fn mutation_edge(dataset: &LdbcDataset, pair: usize) -> (u64, u64) {
+    let v = &dataset.vertices;
+    (v[spread(2 * pair, v.len())], v[spread(2 * pair + 1, v.len())])
+}
+
+struct WindowResult {
+    query: Query,
+    rate: Option<u64>,
+    mutations: usize,
+    mutation_rate: f64,
+    read_qps: f64,
+    samples: usize,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    avg: Duration,
+    /// Vertices whose out-neighbors differ from the dataset afterwards.
+    mismatches: usize,
+}
+
+/// Latencies must be non-empty.
+fn percentiles(latencies: &mut [Duration]) -> (Duration, Duration, Duration) {
+    latencies.sort_unstable();
+    let len = latencies.len();
+    (
+        latencies[len * 50 / 100],
+        latencies[(len * 95 / 100).min(len - 1)],
+        latencies[(len * 99 / 100).min(len - 1)],
+    )
+}
+
+/// Run one window: readers query until the writer's window closes.
+/// `first_pair` numbers the writer's edges so windows use different ones.
+fn run_window(
+    db: &BenchDb,
+    dataset: &LdbcDataset,
+    config: &Config,
+    query: Query,
+    rate: Option<u64>,
+    first_pair: usize,
+) -> WindowResult {
+    let window = Duration::from_secs(config.secs);
+    let stop = AtomicBool::new(false);
+    let barrier = Barrier::new(config.readers + 1);
+    let direction = if dataset.directed { "outgoing" } else { "both" };
+    let mut touched: HashSet<u64> = HashSet::new();
+
+    let (mutations, write_elapsed, per_reader, wall) = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..config.readers)
+            .map(|tid| {
+                let handle = db.db.new_handle().expect("failed to create reader handle");
+                let (barrier, stop) = (&barrier, &stop);
+                scope.spawn(move || {
+                    let mut latencies = Vec::new();
+                    let mut i = tid;
+                    barrier.wait();
+                    while !stop.load(Ordering::Acquire) {
+                        let start = dataset.vertices[spread(i, dataset.vertices.len())].to_string();
+                        let t = Instant::now();
+                        handle
+                            .graph_bfs(GRAPH, &start, query.depth(), None, None, Some(direction))
+                            .expect("graph_bfs failed");
+                        latencies.push(t.elapsed());
+                        i += config.readers;
+                    }
+                    latencies
+                })
+            })
+            .collect();
+
+        barrier.wait();
+        let start = Instant::now();
+        let clock = RealClock::start();
+        // Even ops add the next edge, odd ops remove it again
+        let n = run_paced(&clock, rate, window, usize::MAX, |n| {
+            let (src, dst) = mutation_edge(dataset, first_pair + n / 2);
+            let (src_id, dst_id) = (src.to_string(), dst.to_string());
+            if n.is_multiple_of(2) {
+                touched.insert(src);
+                db.db
+                    .graph_add_edge(GRAPH, &src_id, &dst_id, MUTATION_EDGE_TYPE, None, None)
+                    .expect("graph_add_edge failed");
+            } else {
+                db.db
+                    .graph_remove_edge(GRAPH, &src_id, &dst_id, MUTATION_EDGE_TYPE)
+                    .expect("graph_remove_edge failed");
+            }
+        });
+        let write_elapsed = clock.elapsed();
+        stop.store(true, Ordering::Release);
+        let per_reader: Vec<Vec<Duration>> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+        (n, write_elapsed, per_reader, start.elapsed())
+    });
+
+    // Undo a trailing add so the next window starts from the dataset
+    if mutations % 2 == 1 {
+        let (src, dst) = mutation_edge(dataset, first_pair + mutations / 2);
+        db.db
+            .graph_remove_edge(GRAPH, &src.to_string(), &dst.to_string(), MUTATION_EDGE_TYPE)
+            .expect("graph_remove_edge failed");
+    }
+    let mismatches = adjacency_mismatches(db, dataset, &touched);
+
+    let mut latencies: Vec<Duration> = per_reader.into_iter().flatten().collect();
+    if latencies.is_empty() {
+        latencies.push(wall);
+    }
+    let samples = latencies.len();
+    let avg = latencies.iter().sum::<Duration>() / samples as u32;
+    let (p50, p95, p99) = percentiles(&mut latencies);
+
+    WindowResult {
+        query,
+        rate,
+        mutations,
+        mutation_rate: mutations as f64 / write_elapsed.as_secs_f64(),
+        read_qps: samples as f64 / wall.as_secs_f64(),
+        samples,
+        p50,
+        p95,
+        p99,
+        min: latencies[0],
+        max: latencies[samples - 1],
+        avg,
+        mismatches,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// How many of `vertices` have out-neighbors in Strata that differ from
+/// the edge list. Self-loops are left out of both sides; a BFS never
+/// returns them.
+fn adjacency_mismatches(db: &BenchDb, dataset: &LdbcDataset, vertices: &HashSet<u64>) -> usize {
+    let mut expected: HashMap<u64, HashSet<String>> = HashMap::new();
+    for &(src, dst) in &dataset.edges {
+        if src != dst && vertices.contains(&src) {
+            expected.entry(src).or_default().insert(dst.to_string());
+        }
+    }
+
+    vertices
+        .iter()
+        .filter(|&&vid| {
+            let result = db
+                .db
+                .graph_bfs(GRAPH, &vid.to_string(), 1, None, None, Some("outgoing"))
+                .expect("graph_bfs failed");
+            let actual: HashSet<String> = result
+                .depths
+                .into_iter()
+                .filter(|(_, depth)| *depth == 1)
+                .map(|(node, _)| node)
+                .collect();
+            actual != expected.remove(&vid).unwrap_or_default()
+        })
+        .count()
+}
+
+// ---------------------------------------------------------------------------
+// Output and recording
+// ---------------------------------------------------------------------------
+
+fn p99_ratio(r: &WindowResult, baseline: Option<&WindowResult>) -> Option<f64> {
+    baseline
+        .filter(|b| !b.p99.is_zero())
+        .map(|b| r.p99.as_secs_f64() / b.p99.as_secs_f64())
+}
+
+fn print_row(r: &WindowResult, baseline: Option<&WindowResult>) {
+    eprintln!(
+        "  {:>10} {:>12} {:>12} {:>10} {:>10} {:>10} {:>10}",
+        rate_label(r.rate),
+        fmt_num(r.mutation_rate as u64),
+        fmt_num(r.read_qps as u64),
+        fmt_duration(r.p50),
+        fmt_duration(r.p95),
+        fmt_duration(r.p99),
+        p99_ratio(r, baseline)
+            .map(|x| format!("{:.2}x", x))
+            .unwrap_or_else(|| "-".into()),
+    );
+}
+
+fn print_csv_row(dataset: &LdbcDataset, config: &Config, r: &WindowResult) {
+    println!(
+        "\"{}\",\"{}\",\"{}\",{},{},{:.0},{:.0},{:.3},{:.3},{:.3}",
+        dataset.name,
+        r.query.label(),
+        rate_label(r.rate),
+        config.readers,
+        r.mutations,
+        r.mutation_rate,
+        r.read_qps,
+        r.p50.as_secs_f64() * 1e6,
+        r.p95.as_secs_f64() * 1e6,
+        r.p99.as_secs_f64() * 1e6,
+    );
+}
+
+fn result_name(dataset: &LdbcDataset, config: &Config, query: Query, rate: Option<u64>) -> String {
+    format!(
+        "graph-mutation/{}/{}/rate-{}/{}",
+        dataset.name,
+        query.label(),
+        rate_label(rate),
+        config.durability.label()
+    )
+}
+
+fn record(
+    recorder: &mut ResultRecorder,
+    dataset: &LdbcDataset,
+    config: &Config,
+    r: &WindowResult,
+    baseline: Option<&WindowResult>,
+) {
+    let mut params = HashMap::new();
+    params.insert("dataset".into(), serde_json::json!(dataset.name));
+    params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
+    params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
+    params.insert("query".into(), serde_json::json!(r.query.label()));
+    params.insert("target_mutation_rate".into(), serde_json::json!(rate_label(r.rate)));
+    params.insert("mutations".into(), serde_json::json!(r.mutations));
+    params.insert("mutations_per_sec".into(), serde_json::json!(r.mutation_rate));
+    params.insert("readers".into(), serde_json::json!(config.readers));
+    params.insert("window_secs".into(), serde_json::json!(config.secs));
+    params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    if let Some(ratio) = p99_ratio(r, baseline) {
+        params.insert("read_p99_vs_baseline".into(), serde_json::json!(ratio));
+    }
+
+    let status = if r.mismatches == 0 {
+        ResultStatus::Success
+    } else {
+        ResultStatus::ValidationFailed {
+            message: format!(
+                "{} vertices kept mutated out-neighbors after the window",
+                r.mismatches
+            ),
+        }
+    };
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(dataset, config, r.query, r.rate),
+        category: "graph-mutation".to_string(),
+        parameters: params,
+        // Throughput and latency are the readers'; mutation rate is a parameter
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.read_qps),
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg.as_nanos() as u64),
+            samples: Some(r.samples as u64),
+            threads: Some(config.readers),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let dataset = LdbcDataset::load(&config.dataset).unwrap_or_else(|e| {
+        eprintln!("Failed to load dataset from {}: {}", config.dataset.display(), e);
+        std::process::exit(1);
+    });
+    if dataset.vertices.is_empty() {
+        eprintln!("Dataset {} has no vertices", dataset.name);
+        std::process::exit(1);
+    }
+
+    if !config.csv {
+        eprintln!("=== Graph Mutation-Under-Read Benchmark ===");
+        eprintln!(
+            "Dataset:    {} ({} vertices, {} edges)",
+            dataset.name,
+            fmt_num(dataset.vertices.len() as u64),
+            fmt_num(dataset.edges.len() as u64),
+        );
+        eprintln!(
+            "Rates:      {} mutations/s",
+            config.rates.iter().map(|&r| rate_label(r)).collect::<Vec<_>>().join(", ")
+        );
+        eprintln!("Readers:    {}", config.readers);
+        eprintln!("Window:     {}s", config.secs);
+        eprintln!("Durability: {}", config.durability.label());
+        eprintln!();
+    }
+
+    let db = create_db(config.durability);
+    let load_time = load_graph(&db, &dataset);
+    if !config.csv && !config.quiet {
+        eprintln!("Load: {}", fmt_ms(load_time));
+        eprintln!();
+    }
+
+    if config.csv {
+        println!(
+            "\"dataset\",\"query\",\"target_rate\",\"readers\",\"mutations\",\"mutation_rate\",\"read_qps\",\"p50_us\",\"p95_us\",\"p99_us\""
+        );
+    }
+
+    let mut recorder = ResultRecorder::new("graph-mutation");
+    let mut next_pair = 0;
+
+    for &query in &config.queries {
+        if !config.csv && !config.quiet {
+            eprintln!("--- {} queries ---", query.label());
+            eprintln!(
+                "  {:>10} {:>12} {:>12} {:>10} {:>10} {:>10} {:>10}",
+                "rate", "mutations/s", "reads/s", "p50", "p95", "p99", "p99 vs 0"
+            );
+        }
+
+        let mut baseline: Option<WindowResult> = None;
+        for &rate in &config.rates {
+            let mut guard_params = HashMap::new();
+            guard_params.insert("dataset".into(), serde_json::json!(dataset.name));
+            guard_params.insert("query".into(), serde_json::json!(query.label()));
+            guard_params.insert("target_mutation_rate".into(), serde_json::json!(rate_label(rate)));
+            guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+            recorder.guard(&result_name(&dataset, &config, query, rate), guard_params, |recorder| {
+                let r = run_window(&db, &dataset, &config, query, rate, next_pair);
+                next_pair += r.mutations.div_ceil(2);
+
+                if config.csv {
+                    print_csv_row(&dataset, &config, &r);
+                } else if config.quiet {
+                    eprintln!(
+                        "{}/rate-{}: {} mutations/s, read p99 {}",
+                        query.label(),
+                        rate_label(rate),
+                        fmt_num(r.mutation_rate as u64),
+                        fmt_duration(r.p99)
+                    );
+                } else {
+                    print_row(&r, baseline.as_ref());
+                }
+                if r.mismatches > 0 && !config.csv {
+                    eprintln!(
+                        "  Validation: FAIL ({} vertices kept mutated out-neighbors)",
+                        r.mismatches
+                    );
+                }
+
+                record(recorder, &dataset, &config, &r, baseline.as_ref());
+                if rate == Some(0) && baseline.is_none() {
+                    baseline = Some(r);
+                }
+            });
+        }
+
+        if !config.csv && !config.quiet {
+            eprintln!();
+        }
+    }
+
+    if !config.csv {
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
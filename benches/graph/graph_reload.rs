@@ -0,0 +1,388 @@
+//! Graph Reload Benchmark — time from reopen to the first completed BFS
+//!
+//! Loads an LDBC dataset into a disk-backed database, then closes and reopens
+//! it `--cycles` times. Each cycle times the reopen (WAL replay and whatever
+//! the engine rebuilds before `open` returns) and the first BFS afterwards
+//! (anything rebuilt lazily on first access), then a second, warm BFS for
+//! comparison. The reported latency is reopen + first BFS: how long a
+//! restarted process waits before it can answer a graph query.
+//!
+//! Every first BFS after a reopen must return the same depths as a BFS run
+//! before the first close, so a graph that didn't survive the reopen shows
+//! up as a failed result. Cache mode has nothing on disk and is not run.
+//!
+//! Run:          `cargo bench --bench graph_reload`
+//! Quick:        `cargo bench --bench graph_reload -- -q`
+//! CSV:          `cargo bench --bench graph_reload -- --csv`
+//! Custom data:  `cargo bench --bench graph_reload -- --dataset path/to/ldbc/dir`
+//! Cycles:       `cargo bench --bench graph_reload -- --cycles 10`
+//! Durability:   `cargo bench --bench graph_reload -- --durability always`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[allow(unused)]
+mod ldbc;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, dir_size_bytes, print_hardware_info, BenchDb, DurabilityConfig};
+use ldbc::LdbcDataset;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_CYCLES: usize = 3;
+const DISK_MODES: [DurabilityConfig; 2] = [DurabilityConfig::Standard, DurabilityConfig::Always];
+const GRAPH: &str = "ldbc";
+
+fn default_dataset_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-directed")
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    dataset: PathBuf,
+    source: Option<u64>,
+    durability: Vec<DurabilityConfig>,
+    cycles: usize,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        dataset: default_dataset_dir(),
+        source: None,
+        durability: DISK_MODES.to_vec(),
+        cycles: DEFAULT_CYCLES,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--dataset" => config.dataset = PathBuf::from(args.value()),
+            "--source" => config.source = Some(args.parse_value()),
+            "--cycles" => config.cycles = args.parse_value::<usize>().max(1),
+            _ => args.unknown_flag(),
+        }
+    }
+    if matches!(args.durability, Some(DurabilityConfig::Cache)) {
+        harness::cli::usage_error("Cache mode keeps nothing on disk, so there is nothing to reload");
+    }
+    config.durability = args.durability_modes(&DISK_MODES);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Graph loading and BFS (Strata)
+// ---------------------------------------------------------------------------
+
+fn load_graph(db: &BenchDb, dataset: &LdbcDataset) -> Duration {
+    let start = Instant::now();
+
+    db.db.graph_create(GRAPH).expect("graph_create failed");
+
+    for &vid in &dataset.vertices {
+        db.db
+            .graph_add_node(GRAPH, &vid.to_string(), None, None)
+            .expect("graph_add_node failed");
+    }
+
+    for &(src, dst) in &dataset.edges {
+        db.db
+            .graph_add_edge(
+                GRAPH,
+                &src.to_string(),
+                &dst.to_string(),
+                "E",
+                None,
+                None,
+            )
+            .expect("graph_add_edge failed");
+    }
+
+    start.elapsed()
+}
+
+fn timed_bfs(db: &BenchDb, source: u64) -> (Duration, HashMap<String, usize>) {
+    let start = Instant::now();
+    let result = db
+        .db
+        .graph_bfs(GRAPH, &source.to_string(), usize::MAX, None, None, Some("both"))
+        .expect("graph_bfs failed");
+    (start.elapsed(), result.depths)
+}
+
+/// Vertices whose depth differs between two BFS results, including
+/// vertices only one of them reached.
+fn depth_mismatches(expected: &HashMap<String, usize>, actual: &HashMap<String, usize>) -> usize {
+    let missing = actual.keys().filter(|v| !expected.contains_key(*v)).count();
+    expected
+        .iter()
+        .filter(|(v, d)| actual.get(*v) != Some(d))
+        .count()
+        + missing
+}
+
+// ---------------------------------------------------------------------------
+// Reload cycles
+// ---------------------------------------------------------------------------
+
+struct Cycle {
+    reopen: Duration,
+    first_bfs: Duration,
+    warm_bfs: Duration,
+    mismatches: usize,
+}
+
+impl Cycle {
+    fn time_to_first_bfs(&self) -> Duration {
+        self.reopen + self.first_bfs
+    }
+}
+
+struct ReloadRun {
+    load: Duration,
+    disk_bytes: u64,
+    /// Warm BFS before the first close, the baseline for `warm_bfs`.
+    before_close_bfs: Duration,
+    cycles: Vec<Cycle>,
+}
+
+fn run_reload(config: &Config, dataset: &LdbcDataset, mode: DurabilityConfig, source: u64) -> ReloadRun {
+    let mut db = create_db(mode);
+    let load = load_graph(&db, dataset);
+    let disk_bytes = db.data_dir().map(dir_size_bytes).unwrap_or(0);
+
+    // Run once to warm up, then keep the second run's depths as the baseline
+    timed_bfs(&db, source);
+    let (before_close_bfs, baseline) = timed_bfs(&db, source);
+
+    let mut cycles = Vec::with_capacity(config.cycles);
+    for _ in 0..config.cycles {
+        let start = Instant::now();
+        db = db.reopen();
+        let reopen = start.elapsed();
+
+        let (first_bfs, depths) = timed_bfs(&db, source);
+        let (warm_bfs, _) = timed_bfs(&db, source);
+        cycles.push(Cycle {
+            reopen,
+            first_bfs,
+            warm_bfs,
+            mismatches: depth_mismatches(&baseline, &depths),
+        });
+    }
+
+    ReloadRun {
+        load,
+        disk_bytes,
+        before_close_bfs,
+        cycles,
+    }
+}
+
+fn avg(durations: impl Iterator<Item = Duration>) -> Duration {
+    let (sum, n) = durations.fold((Duration::ZERO, 0u32), |(s, n), d| (s + d, n + 1));
+    if n == 0 {
+        Duration::ZERO
+    } else {
+        sum / n
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Output and recording
+// ---------------------------------------------------------------------------
+
+fn print_row(mode: DurabilityConfig, r: &ReloadRun) {
+    let reopen = avg(r.cycles.iter().map(|c| c.reopen));
+    let first = avg(r.cycles.iter().map(|c| c.first_bfs));
+    let warm = avg(r.cycles.iter().map(|c| c.warm_bfs));
+    eprintln!(
+        "  {:>10} {:>10} {:>14} {:>10} {:>10} {:>10} {:>10} {:>12}",
+        mode.label(),
+        fmt_ms(r.load),
+        fmt_num(r.disk_bytes),
+        fmt_ms(reopen),
+        fmt_ms(first),
+        fmt_ms(warm),
+        fmt_ms(r.before_close_bfs),
+        fmt_ms(reopen + first),
+    );
+}
+
+fn print_csv_rows(dataset: &LdbcDataset, mode: DurabilityConfig, r: &ReloadRun) {
+    for (i, c) in r.cycles.iter().enumerate() {
+        println!(
+            "\"{}\",\"{}\",{},{:.3},{:.3},{:.3},{:.3},{}",
+            dataset.name,
+            mode.label(),
+            i + 1,
+            c.reopen.as_secs_f64() * 1000.0,
+            c.first_bfs.as_secs_f64() * 1000.0,
+            c.warm_bfs.as_secs_f64() * 1000.0,
+            c.time_to_first_bfs().as_secs_f64() * 1000.0,
+            c.mismatches,
+        );
+    }
+}
+
+fn result_name(dataset: &LdbcDataset, mode: DurabilityConfig) -> String {
+    format!("graph-reload/{}/{}", dataset.name, mode.label())
+}
+
+fn base_params(dataset: &LdbcDataset, mode: DurabilityConfig, source: u64, cycles: usize) -> HashMap<String, serde_json::Value> {
+    let mut params = HashMap::new();
+    params.insert("dataset".into(), serde_json::json!(dataset.name));
+    params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
+    params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("source".into(), serde_json::json!(source));
+    params.insert("cycles".into(), serde_json::json!(cycles));
+    params
+}
+
+fn record(
+    recorder: &mut ResultRecorder,
+    dataset: &LdbcDataset,
+    mode: DurabilityConfig,
+    source: u64,
+    r: &ReloadRun,
+) {
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let mut params = base_params(dataset, mode, source, r.cycles.len());
+    params.insert("load_ms".into(), serde_json::json!(ms(r.load)));
+    params.insert("disk_bytes".into(), serde_json::json!(r.disk_bytes));
+    params.insert("reopen_ms".into(), serde_json::json!(ms(avg(r.cycles.iter().map(|c| c.reopen)))));
+    params.insert("first_bfs_ms".into(), serde_json::json!(ms(avg(r.cycles.iter().map(|c| c.first_bfs)))));
+    params.insert("warm_bfs_ms".into(), serde_json::json!(ms(avg(r.cycles.iter().map(|c| c.warm_bfs)))));
+    params.insert("before_close_bfs_ms".into(), serde_json::json!(ms(r.before_close_bfs)));
+
+    let mut totals: Vec<Duration> = r.cycles.iter().map(Cycle::time_to_first_bfs).collect();
+    totals.sort_unstable();
+    let len = totals.len();
+
+    let mismatches: usize = r.cycles.iter().map(|c| c.mismatches).sum();
+    let status = if mismatches == 0 {
+        ResultStatus::Success
+    } else {
+        ResultStatus::ValidationFailed {
+            message: format!(
+                "BFS after reopen differs from before close at {} vertex depths across {} cycles",
+                mismatches, len
+            ),
+        }
+    };
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(dataset, mode),
+        category: "graph-reload".to_string(),
+        parameters: params,
+        // Latency is reopen + first BFS, one sample per cycle
+        metrics: BenchmarkMetrics {
+            p50_ns: Some(totals[len * 50 / 100].as_nanos() as u64),
+            p95_ns: Some(totals[(len * 95 / 100).min(len - 1)].as_nanos() as u64),
+            p99_ns: Some(totals[(len * 99 / 100).min(len - 1)].as_nanos() as u64),
+            min_ns: Some(totals[0].as_nanos() as u64),
+            max_ns: Some(totals[len - 1].as_nanos() as u64),
+            avg_ns: Some(avg(totals.iter().copied()).as_nanos() as u64),
+            samples: Some(len as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let dataset = LdbcDataset::load(&config.dataset).unwrap_or_else(|e| {
+        eprintln!("Failed to load dataset from {}: {}", config.dataset.display(), e);
+        std::process::exit(1);
+    });
+    let source = config
+        .source
+        .or(dataset.bfs_source)
+        .unwrap_or(dataset.vertices[0]);
+
+    if !config.csv {
+        eprintln!("=== Graph Reload Benchmark ===");
+        eprintln!(
+            "Dataset:  {} ({} vertices, {} edges)",
+            dataset.name,
+            fmt_num(dataset.vertices.len() as u64),
+            fmt_num(dataset.edges.len() as u64),
+        );
+        eprintln!("Source:   {}", source);
+        eprintln!("Cycles:   {}", config.cycles);
+        eprintln!();
+    }
+
+    if config.csv {
+        println!("\"dataset\",\"durability\",\"cycle\",\"reopen_ms\",\"first_bfs_ms\",\"warm_bfs_ms\",\"time_to_first_bfs_ms\",\"mismatches\"");
+    } else if !config.quiet {
+        eprintln!(
+            "  {:>10} {:>10} {:>14} {:>10} {:>10} {:>10} {:>10} {:>12}",
+            "mode", "load", "disk bytes", "reopen", "first BFS", "warm BFS", "pre-close", "reopen+BFS"
+        );
+    }
+
+    let mut recorder = ResultRecorder::new("graph-reload");
+
+    for &mode in &config.durability {
+        let name = result_name(&dataset, mode);
+        recorder.guard(&name, base_params(&dataset, mode, source, config.cycles), |recorder| {
+            let r = run_reload(&config, &dataset, mode, source);
+
+            if config.csv {
+                print_csv_rows(&dataset, mode, &r);
+            } else if config.quiet {
+                eprintln!(
+                    "{}: reopen + first BFS {}",
+                    mode.label(),
+                    fmt_ms(avg(r.cycles.iter().map(Cycle::time_to_first_bfs)))
+                );
+            } else {
+                print_row(mode, &r);
+            }
+            let mismatches: usize = r.cycles.iter().map(|c| c.mismatches).sum();
+            if mismatches > 0 && !config.csv {
+                eprintln!(
+                    "  Validation: FAIL ({} vertex depths differ after reopen)",
+                    mismatches
+                );
+            }
+
+            record(recorder, &dataset, mode, source, &r);
+        });
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,669 @@
+//! LDBC Graphalytics SSSP Benchmark — Strata vs petgraph head-to-head
+//!
+//! Computes single-source shortest path distances over the weights in the
+//! `.e` file's third column (unit weights if it has none) and validates them
+//! against the dataset's `-SSSP` reference output with the Graphalytics
+//! epsilon rule. Throughput is reported as EVPS (Edges + Vertices processed
+//! per second), as in graph_bfs.
+//!
+//! Strata has no weighted-path query, and the graph API used here doesn't
+//! read edge data back, so weights aren't stored in the engine. Each Strata
+//! run is a Dijkstra that fetches every settled vertex's out-neighbors from
+//! Strata with a depth-1 `graph_bfs` and looks their weights up in the
+//! dataset. The adjacency read is reported separately and usually
+//! dominates. Replace this with the engine's own query once it has one.
+//!
+//! Run:           `cargo bench --bench graph_sssp`
+//! Quick:         `cargo bench --bench graph_sssp -- -q`
+//! Validate only: `cargo bench --bench graph_sssp -- --validate-only`
+//! CSV:           `cargo bench --bench graph_sssp -- --csv`
+//! Custom data:   `cargo bench --bench graph_sssp -- --dataset path/to/ldbc/dir`
+//! Source vertex: `cargo bench --bench graph_sssp -- --source 1`
+//! Strata only:   `cargo bench --bench graph_sssp -- --strata-only`
+//! Disk-backed:   `cargo bench --bench graph_sssp -- --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[allow(unused)]
+mod ldbc;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use ldbc::{dijkstra, epsilon_eq, LdbcDataset, SsspReference, EPSILON};
+use petgraph::graph::{DiGraph, NodeIndex};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_RUNS: usize = 10;
+
+fn default_dataset_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-directed")
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    dataset: PathBuf,
+    durability: DurabilityConfig,
+    runs: usize,
+    validate_only: bool,
+    no_validate: bool,
+    csv: bool,
+    quiet: bool,
+    strata_only: bool,
+    source: Option<u64>,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        dataset: default_dataset_dir(),
+        durability: DurabilityConfig::Cache,
+        runs: DEFAULT_RUNS,
+        validate_only: false,
+        no_validate: false,
+        csv: false,
+        quiet: false,
+        strata_only: false,
+        source: None,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--dataset" => config.dataset = PathBuf::from(args.value()),
+            "--runs" => config.runs = args.parse_value::<usize>().max(1),
+            "--source" => config.source = Some(args.parse_value()),
+            "--validate-only" => config.validate_only = true,
+            "--no-validate" => config.no_validate = true,
+            "--strata-only" => config.strata_only = true,
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Graph loading (Strata)
+// ---------------------------------------------------------------------------
+
+fn load_graph(db: &BenchDb, dataset: &LdbcDataset) -> Duration {
+    let start = Instant::now();
+
+    db.db.graph_create("ldbc").expect("graph_create failed");
+
+    for &vid in &dataset.vertices {
+        db.db
+            .graph_add_node("ldbc", &vid.to_string(), None, None)
+            .expect("graph_add_node failed");
+    }
+
+    for &(src, dst) in &dataset.edges {
+        db.db
+            .graph_add_edge(
+                "ldbc",
+                &src.to_string(),
+                &dst.to_string(),
+                "E",
+                None,
+                None,
+            )
+            .expect("graph_add_edge failed");
+    }
+
+    start.elapsed()
+}
+
+// ---------------------------------------------------------------------------
+// SSSP execution (Strata)
+// ---------------------------------------------------------------------------
+
+struct SsspRun {
+    elapsed: Duration,
+    /// Part of `elapsed` spent reading adjacency from Strata.
+    adjacency: Duration,
+    /// Distances indexed like `dataset.vertices`.
+    distances: Vec<f64>,
+}
+
+/// Dijkstra from `source`, reading each settled vertex's out-neighbors from
+/// Strata and their weights from `weights`.
+fn run_sssp(
+    db: &BenchDb,
+    dataset: &LdbcDataset,
+    index: &HashMap<u64, usize>,
+    weights: &HashMap<(u64, u64), f64>,
+    source: u64,
+) -> SsspRun {
+    let direction = if dataset.directed { "outgoing" } else { "both" };
+    let mut adjacency = Duration::ZERO;
+
+    let start = Instant::now();
+    let distances = dijkstra(dataset.vertices.len(), index[&source], |u| {
+        let vid = dataset.vertices[u];
+        let fetch = Instant::now();
+        let result = db
+            .db
+            .graph_bfs("ldbc", &vid.to_string(), 1, None, None, Some(direction))
+            .expect("graph_bfs failed");
+        adjacency += fetch.elapsed();
+        result
+            .depths
+            .into_iter()
+            .filter(|(_, depth)| *depth == 1)
+            .filter_map(|(node, _)| node.parse::<u64>().ok())
+            .filter_map(|v| Some((*index.get(&v)?, *weights.get(&(vid, v))?)))
+            .collect::<Vec<_>>()
+    });
+
+    SsspRun {
+        elapsed: start.elapsed(),
+        adjacency,
+        distances,
+    }
+}
+
+/// Distances from petgraph's Dijkstra, indexed like `dataset.vertices`.
+fn petgraph_sssp(
+    graph: &DiGraph<(), f64>,
+    id_map: &HashMap<u64, NodeIndex>,
+    dataset: &LdbcDataset,
+    source: u64,
+) -> Vec<f64> {
+    let dist = petgraph::algo::dijkstra(graph, id_map[&source], None, |e| *e.weight());
+    dataset
+        .vertices
+        .iter()
+        .map(|vid| dist.get(&id_map[vid]).copied().unwrap_or(f64::INFINITY))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Check `distances` (indexed like `dataset.vertices`) against `expected`.
+/// Returns (mismatches, first few mismatch details).
+fn validate_distances(
+    dataset: &LdbcDataset,
+    distances: &[f64],
+    expected: &HashMap<u64, f64>,
+) -> (usize, Vec<String>) {
+    let mut mismatches = 0;
+    let mut details = Vec::new();
+
+    for (&vid, &actual) in dataset.vertices.iter().zip(distances) {
+        match expected.get(&vid) {
+            Some(&e) if epsilon_eq(actual, e) => {}
+            Some(&e) => {
+                mismatches += 1;
+                if details.len() < 10 {
+                    details.push(format!("vertex {}: expected {}, got {}", vid, e, actual));
+                }
+            }
+            None => {
+                mismatches += 1;
+                if details.len() < 10 {
+                    details.push(format!("vertex {}: missing from reference", vid));
+                }
+            }
+        }
+    }
+
+    (mismatches, details)
+}
+
+fn report_validation(label: &str, checked: usize, mismatches: usize, details: &[String]) {
+    if mismatches == 0 {
+        eprintln!(
+            "{}: PASS ({} vertices within epsilon {})",
+            label,
+            fmt_num(checked as u64),
+            EPSILON
+        );
+    } else {
+        eprintln!(
+            "{}: FAIL ({} mismatches out of {} vertices)",
+            label, mismatches, checked
+        );
+        for detail in details {
+            eprintln!("  {}", detail);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Run statistics
+// ---------------------------------------------------------------------------
+
+struct RunStats {
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    avg_evps: f64,
+    count: usize,
+}
+
+fn compute_stats(times: &mut [Duration], total_elements: f64) -> RunStats {
+    assert!(!times.is_empty(), "compute_stats requires at least one run");
+    times.sort_unstable();
+    let len = times.len();
+    let sum: Duration = times.iter().sum();
+    let avg = sum / len as u32;
+    let avg_secs = avg.as_secs_f64();
+    let avg_evps = if avg_secs > 0.0 {
+        total_elements / avg_secs
+    } else {
+        0.0
+    };
+    RunStats {
+        avg,
+        p50: times[len * 50 / 100],
+        p95: times[(len * 95 / 100).min(len - 1)],
+        p99: times[(len * 99 / 100).min(len - 1)],
+        min: times[0],
+        max: times[len - 1],
+        avg_evps,
+        count: len,
+    }
+}
+
+fn print_csv_row(engine: &str, run: usize, elapsed: Duration, total_elements: f64, dataset: &LdbcDataset) {
+    println!(
+        "\"{}\",{},{:.3},{:.0},{},{}",
+        engine,
+        run,
+        elapsed.as_secs_f64() * 1000.0,
+        total_elements / elapsed.as_secs_f64(),
+        dataset.vertices.len(),
+        dataset.edges.len()
+    );
+}
+
+fn result_name(engine: &str, dataset: &LdbcDataset, durability: DurabilityConfig) -> String {
+    let mut name = format!(
+        "graph-sssp/{}/{}/{}V-{}E",
+        engine,
+        dataset.name,
+        dataset.vertices.len(),
+        dataset.edges.len()
+    );
+    if !matches!(durability, DurabilityConfig::Cache) {
+        name.push_str(&format!("/{}", durability.label()));
+    }
+    name
+}
+
+fn base_params(
+    engine: &str,
+    dataset: &LdbcDataset,
+    source: u64,
+    durability: DurabilityConfig,
+) -> HashMap<String, serde_json::Value> {
+    let mut params = HashMap::new();
+    params.insert("dataset".into(), serde_json::json!(dataset.name));
+    params.insert("engine".into(), serde_json::json!(engine));
+    params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
+    params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
+    params.insert("source".into(), serde_json::json!(source));
+    params.insert("weighted".into(), serde_json::json!(dataset.weights.is_some()));
+    params.insert("durability".into(), serde_json::json!(durability.label()));
+    params
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record(
+    recorder: &mut ResultRecorder,
+    engine: &str,
+    dataset: &LdbcDataset,
+    durability: DurabilityConfig,
+    source: u64,
+    reachable: usize,
+    stats: &RunStats,
+    status: ResultStatus,
+) {
+    let mut params = base_params(engine, dataset, source, durability);
+    params.insert("reachable".into(), serde_json::json!(reachable));
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(engine, dataset, durability),
+        category: "graph-sssp".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(stats.avg_evps),
+            p50_ns: Some(stats.p50.as_nanos() as u64),
+            p95_ns: Some(stats.p95.as_nanos() as u64),
+            p99_ns: Some(stats.p99.as_nanos() as u64),
+            min_ns: Some(stats.min.as_nanos() as u64),
+            max_ns: Some(stats.max.as_nanos() as u64),
+            avg_ns: Some(stats.avg.as_nanos() as u64),
+            samples: Some(stats.count as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let dataset = LdbcDataset::load(&config.dataset).unwrap_or_else(|e| {
+        eprintln!("Failed to load dataset from {}: {}", config.dataset.display(), e);
+        std::process::exit(1);
+    });
+    let source = config
+        .source
+        .or(dataset.sssp_source)
+        .or_else(|| dataset.vertices.first().copied())
+        .unwrap_or_else(|| {
+            eprintln!("Dataset has no vertices");
+            std::process::exit(1);
+        });
+    let index: HashMap<u64, usize> = dataset
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(i, &vid)| (vid, i))
+        .collect();
+    if !index.contains_key(&source) {
+        eprintln!("Source vertex {} is not in the dataset", source);
+        std::process::exit(1);
+    }
+
+    if !config.csv {
+        eprintln!("=== LDBC Graphalytics SSSP Benchmark ===");
+        eprintln!(
+            "Dataset:  {} ({} vertices, {} edges, {}, {})",
+            dataset.name,
+            fmt_num(dataset.vertices.len() as u64),
+            fmt_num(dataset.edges.len() as u64),
+            if dataset.directed { "directed" } else { "undirected" },
+            if dataset.weights.is_some() { "weighted" } else { "unit weights" },
+        );
+        eprintln!("Source:   {}", source);
+        eprintln!("Runs:     {}", config.runs);
+        eprintln!("Durability: {}", config.durability.label());
+        if config.strata_only {
+            eprintln!("Mode:     strata-only (petgraph comparison skipped)");
+        }
+        eprintln!();
+    }
+
+    let mut recorder = ResultRecorder::new("graph-sssp");
+    recorder.guard(
+        &result_name("strata", &dataset, config.durability),
+        base_params("strata", &dataset, source, config.durability),
+        |recorder| {
+            let reference = load_reference(&config, &dataset, source);
+            run_benchmark(&config, &dataset, source, &index, reference.as_ref(), recorder)
+        },
+    );
+    if config.validate_only && recorder.failure_count() == 0 {
+        return;
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
+
+/// The LDBC SSSP reference for `dataset`, or `None` when validation
+/// is off or doesn't apply. A reference that exists but can't be read
+/// panics, so the run's guard records it as an error.
+fn load_reference(config: &Config, dataset: &LdbcDataset, source: u64) -> Option<SsspReference> {
+    // Reference distances only hold for the dataset's own source
+    if config.no_validate {
+        None
+    } else if dataset.sssp_source != Some(source) {
+        if !config.csv && !config.quiet {
+            eprintln!("Source differs from the dataset's SSSP source, skipping LDBC validation.");
+        }
+        None
+    } else {
+        let sssp_path = config.dataset.join(format!("{}-SSSP", dataset.name));
+        if sssp_path.exists() {
+            Some(SsspReference::load(&sssp_path).unwrap_or_else(|e| {
+                panic!("failed to load SSSP reference: {}", e)
+            }))
+        } else {
+            if !config.csv && !config.quiet {
+                eprintln!("No SSSP reference file found, skipping LDBC validation.");
+            }
+            None
+        }
+    }
+}
+
+/// Load the graph, time SSSP from `source` on Strata and (unless
+/// `--strata-only`) petgraph, and record the results. With
+/// `--validate-only` it stops after the first Strata run, recording only a
+/// validation failure.
+fn run_benchmark(
+    config: &Config,
+    dataset: &LdbcDataset,
+    source: u64,
+    index: &HashMap<u64, usize>,
+    reference: Option<&SsspReference>,
+    recorder: &mut ResultRecorder,
+) {
+    let weights = dataset.weight_map();
+    let total_elements = (dataset.vertices.len() + dataset.edges.len()) as f64;
+
+    // -----------------------------------------------------------------------
+    // Load phase
+    // -----------------------------------------------------------------------
+
+    let db = create_db(config.durability);
+    let strata_load_time = load_graph(&db, dataset);
+
+    let petgraph_state = if !config.strata_only {
+        let pg_start = Instant::now();
+        let (pg_graph, id_map) = dataset.to_petgraph_weighted();
+        Some((pg_graph, id_map, pg_start.elapsed()))
+    } else {
+        None
+    };
+
+    if !config.csv && !config.quiet {
+        eprintln!("--- Load Phase ---");
+        eprintln!("  {:12} {}", "Strata:", fmt_ms(strata_load_time));
+        if let Some((_, _, pg_load_time)) = &petgraph_state {
+            eprintln!("  {:12} {}", "petgraph:", fmt_ms(*pg_load_time));
+        }
+        eprintln!();
+    }
+
+    // -----------------------------------------------------------------------
+    // SSSP phase — Strata
+    // -----------------------------------------------------------------------
+
+    if config.csv {
+        println!("\"engine\",\"run\",\"sssp_time_ms\",\"evps\",\"vertices\",\"edges\"");
+    }
+
+    let mut strata_times = Vec::with_capacity(config.runs);
+    let mut adjacency_total = Duration::ZERO;
+    let mut reachable = 0;
+    let mut failures: Vec<String> = Vec::new();
+    for run in 0..config.runs {
+        let sssp_run = run_sssp(&db, dataset, index, &weights, source);
+        strata_times.push(sssp_run.elapsed);
+        adjacency_total += sssp_run.adjacency;
+
+        if run == 0 {
+            reachable = sssp_run.distances.iter().filter(|d| d.is_finite()).count();
+            if let Some(reference) = reference {
+                let (mismatches, details) =
+                    validate_distances(dataset, &sssp_run.distances, &reference.distances);
+                if mismatches > 0 {
+                    failures.push(format!("{} distances differ from the LDBC reference", mismatches));
+                }
+                if !config.csv {
+                    report_validation("LDBC Validation", dataset.vertices.len(), mismatches, &details);
+                }
+            }
+            if let Some((ref pg_graph, ref id_map, _)) = petgraph_state {
+                let pg_distances = petgraph_sssp(pg_graph, id_map, dataset, source);
+                let expected: HashMap<u64, f64> =
+                    dataset.vertices.iter().copied().zip(pg_distances).collect();
+                let (mismatches, details) =
+                    validate_distances(dataset, &sssp_run.distances, &expected);
+                if mismatches > 0 {
+                    failures.push(format!("{} distances differ from petgraph", mismatches));
+                }
+                if !config.csv {
+                    report_validation(
+                        "Cross-validation (petgraph)",
+                        dataset.vertices.len(),
+                        mismatches,
+                        &details,
+                    );
+                }
+            }
+            if !config.csv && !config.quiet {
+                eprintln!(
+                    "Reachable: {} of {} vertices",
+                    fmt_num(reachable as u64),
+                    fmt_num(dataset.vertices.len() as u64)
+                );
+            }
+            if config.validate_only {
+                if !failures.is_empty() {
+                    recorder.record_failure(
+                        &result_name("strata", dataset, config.durability),
+                        base_params("strata", dataset, source, config.durability),
+                        ResultStatus::from_validation(&failures),
+                    );
+                } else if !config.csv {
+                    eprintln!("Validate-only mode, skipping remaining runs.");
+                }
+                return;
+            }
+        }
+
+        if config.csv {
+            print_csv_row("strata", run + 1, sssp_run.elapsed, total_elements, dataset);
+        }
+    }
+    let strata_stats = compute_stats(&mut strata_times, total_elements);
+    let adjacency_avg = adjacency_total / config.runs as u32;
+
+    // -----------------------------------------------------------------------
+    // SSSP phase — petgraph
+    // -----------------------------------------------------------------------
+
+    let petgraph_stats = if let Some((ref pg_graph, ref id_map, _)) = petgraph_state {
+        let mut pg_times = Vec::with_capacity(config.runs);
+        for run in 0..config.runs {
+            let start = Instant::now();
+            let _ = petgraph_sssp(pg_graph, id_map, dataset, source);
+            let elapsed = start.elapsed();
+            pg_times.push(elapsed);
+            if config.csv {
+                print_csv_row("petgraph", run + 1, elapsed, total_elements, dataset);
+            }
+        }
+        Some(compute_stats(&mut pg_times, total_elements))
+    } else {
+        None
+    };
+
+    // -----------------------------------------------------------------------
+    // Output
+    // -----------------------------------------------------------------------
+
+    if config.quiet && !config.csv {
+        eprintln!(
+            "Strata SSSP: {}, EVPS: {}, adjacency read {}",
+            fmt_ms(strata_stats.avg),
+            fmt_num(strata_stats.avg_evps as u64),
+            fmt_ms(adjacency_avg),
+        );
+        if let Some(ref pg) = petgraph_stats {
+            eprintln!(
+                "petgraph SSSP: {}, EVPS: {}",
+                fmt_ms(pg.avg),
+                fmt_num(pg.avg_evps as u64)
+            );
+        }
+    } else if !config.csv {
+        eprintln!();
+        eprintln!("--- SSSP Phase ({} runs) ---", strata_stats.count);
+        eprintln!(
+            "  {:16} {:>10} {:>10} {:>10} {:>14}",
+            "", "avg", "p50", "max", "EVPS"
+        );
+        eprintln!(
+            "  {:16} {:>10} {:>10} {:>10} {:>14}",
+            "Strata:",
+            fmt_ms(strata_stats.avg),
+            fmt_ms(strata_stats.p50),
+            fmt_ms(strata_stats.max),
+            fmt_num(strata_stats.avg_evps as u64),
+        );
+        if let Some(ref pg) = petgraph_stats {
+            eprintln!(
+                "  {:16} {:>10} {:>10} {:>10} {:>14}",
+                "petgraph:",
+                fmt_ms(pg.avg),
+                fmt_ms(pg.p50),
+                fmt_ms(pg.max),
+                fmt_num(pg.avg_evps as u64),
+            );
+            eprintln!(
+                "  {:16} {:>10}",
+                "Ratio:",
+                format!("{:.1}x", strata_stats.avg.as_secs_f64() / pg.avg.as_secs_f64()),
+            );
+        }
+        eprintln!(
+            "  Strata adjacency read: {} of {} avg",
+            fmt_ms(adjacency_avg),
+            fmt_ms(strata_stats.avg)
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Record results
+    // -----------------------------------------------------------------------
+
+    record(
+        recorder,
+        "strata",
+        dataset,
+        config.durability,
+        source,
+        reachable,
+        &strata_stats,
+        ResultStatus::from_validation(&failures),
+    );
+    if let Some(ref pg) = petgraph_stats {
+        record(recorder, "petgraph", dataset, config.durability, source, reachable, pg, ResultStatus::Success);
+    }
+}
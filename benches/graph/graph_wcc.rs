@@ -0,0 +1,558 @@
+//! LDBC Graphalytics WCC Benchmark — Strata vs in-memory union-find
+//!
+//! Computes weakly connected components (edges treated as undirected) and
+//! validates the assignment against the dataset's `-WCC` reference output.
+//! Component IDs are compared as groupings, not values, as Graphalytics
+//! does. Throughput is reported as EVPS (Edges + Vertices processed per
+//! second), as in graph_bfs.
+//!
+//! Strata has no components primitive. Each Strata run walks the vertex
+//! list and starts an undirected `graph_bfs` from every vertex no earlier
+//! traversal reached, so the engine does the traversal. The baseline is a
+//! union-find over the dataset's edge list.
+//!
+//! Run:           `cargo bench --bench graph_wcc`
+//! Quick:         `cargo bench --bench graph_wcc -- -q`
+//! Validate only: `cargo bench --bench graph_wcc -- --validate-only`
+//! CSV:           `cargo bench --bench graph_wcc -- --csv`
+//! Custom data:   `cargo bench --bench graph_wcc -- --dataset path/to/ldbc/dir`
+//! Strata only:   `cargo bench --bench graph_wcc -- --strata-only`
+//! Disk-backed:   `cargo bench --bench graph_wcc -- --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[allow(unused)]
+mod ldbc;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use ldbc::{union_find_wcc, wcc_mismatches, LdbcDataset, WccReference};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_RUNS: usize = 10;
+
+fn default_dataset_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-directed")
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    dataset: PathBuf,
+    durability: DurabilityConfig,
+    runs: usize,
+    validate_only: bool,
+    no_validate: bool,
+    csv: bool,
+    quiet: bool,
+    strata_only: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        dataset: default_dataset_dir(),
+        durability: DurabilityConfig::Cache,
+        runs: DEFAULT_RUNS,
+        validate_only: false,
+        no_validate: false,
+        csv: false,
+        quiet: false,
+        strata_only: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--dataset" => config.dataset = PathBuf::from(args.value()),
+            "--runs" => config.runs = args.parse_value::<usize>().max(1),
+            "--validate-only" => config.validate_only = true,
+            "--no-validate" => config.no_validate = true,
+            "--strata-only" => config.strata_only = true,
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Cache);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Graph loading (Strata)
+// ---------------------------------------------------------------------------
+
+fn load_graph(db: &BenchDb, dataset: &LdbcDataset) -> Duration {
+    let start = Instant::now();
+
+    db.db.graph_create("ldbc").expect("graph_create failed");
+
+    for &vid in &dataset.vertices {
+        db.db
+            .graph_add_node("ldbc", &vid.to_string(), None, None)
+            .expect("graph_add_node failed");
+    }
+
+    for &(src, dst) in &dataset.edges {
+        db.db
+            .graph_add_edge(
+                "ldbc",
+                &src.to_string(),
+                &dst.to_string(),
+                "E",
+                None,
+                None,
+            )
+            .expect("graph_add_edge failed");
+    }
+
+    start.elapsed()
+}
+
+// ---------------------------------------------------------------------------
+// WCC execution (Strata)
+// ---------------------------------------------------------------------------
+
+struct WccRun {
+    elapsed: Duration,
+    components: HashMap<u64, u64>,
+    /// Number of components found, one `graph_bfs` each.
+    traversals: usize,
+}
+
+/// Label every vertex with the smallest vertex ID its undirected BFS reaches.
+fn run_wcc(db: &BenchDb, dataset: &LdbcDataset) -> WccRun {
+    let start = Instant::now();
+    let mut components: HashMap<u64, u64> = HashMap::with_capacity(dataset.vertices.len());
+    let mut traversals = 0;
+
+    for &vid in &dataset.vertices {
+        if components.contains_key(&vid) {
+            continue;
+        }
+        let result = db
+            .db
+            .graph_bfs(
+                "ldbc",
+                &vid.to_string(),
+                usize::MAX,
+                None,
+                None,
+                Some("both"),
+            )
+            .expect("graph_bfs failed");
+        traversals += 1;
+
+        let members: Vec<u64> = result
+            .depths
+            .keys()
+            .filter_map(|k| k.parse().ok())
+            .chain(std::iter::once(vid))
+            .collect::<HashSet<u64>>()
+            .into_iter()
+            .collect();
+        let label = members.iter().copied().min().unwrap_or(vid);
+        for m in members {
+            components.insert(m, label);
+        }
+    }
+
+    WccRun {
+        elapsed: start.elapsed(),
+        components,
+        traversals,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+fn report_validation(label: &str, dataset: &LdbcDataset, mismatches: &[u64]) {
+    if mismatches.is_empty() {
+        eprintln!(
+            "{}: PASS ({} vertices checked)",
+            label,
+            fmt_num(dataset.vertices.len() as u64)
+        );
+    } else {
+        eprintln!(
+            "{}: FAIL ({} mismatches out of {} vertices)",
+            label,
+            mismatches.len(),
+            dataset.vertices.len()
+        );
+        for vid in mismatches.iter().take(10) {
+            eprintln!("  vertex {}: component assignment differs", vid);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Run statistics
+// ---------------------------------------------------------------------------
+
+struct RunStats {
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    avg_evps: f64,
+    count: usize,
+}
+
+fn compute_stats(times: &mut [Duration], total_elements: f64) -> RunStats {
+    assert!(!times.is_empty(), "compute_stats requires at least one run");
+    times.sort_unstable();
+    let len = times.len();
+    let sum: Duration = times.iter().sum();
+    let avg = sum / len as u32;
+    let avg_secs = avg.as_secs_f64();
+    let avg_evps = if avg_secs > 0.0 {
+        total_elements / avg_secs
+    } else {
+        0.0
+    };
+    RunStats {
+        avg,
+        p50: times[len * 50 / 100],
+        p95: times[(len * 95 / 100).min(len - 1)],
+        p99: times[(len * 99 / 100).min(len - 1)],
+        min: times[0],
+        max: times[len - 1],
+        avg_evps,
+        count: len,
+    }
+}
+
+fn print_csv_row(engine: &str, run: usize, elapsed: Duration, total_elements: f64, dataset: &LdbcDataset) {
+    println!(
+        "\"{}\",{},{:.3},{:.0},{},{}",
+        engine,
+        run,
+        elapsed.as_secs_f64() * 1000.0,
+        total_elements / elapsed.as_secs_f64(),
+        dataset.vertices.len(),
+        dataset.edges.len()
+    );
+}
+
+fn result_name(engine: &str, dataset: &LdbcDataset, durability: DurabilityConfig) -> String {
+    let mut name = format!(
+        "graph-wcc/{}/{}/{}V-{}E",
+        engine,
+        dataset.name,
+        dataset.vertices.len(),
+        dataset.edges.len()
+    );
+    if !matches!(durability, DurabilityConfig::Cache) {
+        name.push_str(&format!("/{}", durability.label()));
+    }
+    name
+}
+
+fn base_params(engine: &str, dataset: &LdbcDataset, durability: DurabilityConfig) -> HashMap<String, serde_json::Value> {
+    let mut params = HashMap::new();
+    params.insert("dataset".into(), serde_json::json!(dataset.name));
+    params.insert("engine".into(), serde_json::json!(engine));
+    params.insert("vertices".into(), serde_json::json!(dataset.vertices.len()));
+    params.insert("edges".into(), serde_json::json!(dataset.edges.len()));
+    params.insert("durability".into(), serde_json::json!(durability.label()));
+    params
+}
+
+fn record(
+    recorder: &mut ResultRecorder,
+    engine: &str,
+    dataset: &LdbcDataset,
+    durability: DurabilityConfig,
+    components: usize,
+    stats: &RunStats,
+    status: ResultStatus,
+) {
+    let mut params = base_params(engine, dataset, durability);
+    params.insert("components".into(), serde_json::json!(components));
+
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(engine, dataset, durability),
+        category: "graph-wcc".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(stats.avg_evps),
+            p50_ns: Some(stats.p50.as_nanos() as u64),
+            p95_ns: Some(stats.p95.as_nanos() as u64),
+            p99_ns: Some(stats.p99.as_nanos() as u64),
+            min_ns: Some(stats.min.as_nanos() as u64),
+            max_ns: Some(stats.max.as_nanos() as u64),
+            avg_ns: Some(stats.avg.as_nanos() as u64),
+            samples: Some(stats.count as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let dataset = LdbcDataset::load(&config.dataset).unwrap_or_else(|e| {
+        eprintln!("Failed to load dataset from {}: {}", config.dataset.display(), e);
+        std::process::exit(1);
+    });
+
+    if !config.csv {
+        eprintln!("=== LDBC Graphalytics WCC Benchmark ===");
+        eprintln!(
+            "Dataset:  {} ({} vertices, {} edges, {})",
+            dataset.name,
+            fmt_num(dataset.vertices.len() as u64),
+            fmt_num(dataset.edges.len() as u64),
+            if dataset.directed { "directed" } else { "undirected" },
+        );
+        eprintln!("Runs:     {}", config.runs);
+        eprintln!("Durability: {}", config.durability.label());
+        eprintln!("Direction: both (WCC ignores edge direction)");
+        if config.strata_only {
+            eprintln!("Mode:     strata-only (union-find baseline skipped)");
+        }
+        eprintln!();
+    }
+
+    let mut recorder = ResultRecorder::new("graph-wcc");
+    recorder.guard(
+        &result_name("strata", &dataset, config.durability),
+        base_params("strata", &dataset, config.durability),
+        |recorder| {
+            let reference = load_reference(&config, &dataset);
+            run_benchmark(&config, &dataset, reference.as_ref(), recorder)
+        },
+    );
+    if config.validate_only && recorder.failure_count() == 0 {
+        return;
+    }
+
+    if !config.csv {
+        eprintln!();
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
+
+/// The LDBC WCC reference for `dataset`, or `None` when validation
+/// is off or doesn't apply. A reference that exists but can't be read
+/// panics, so the run's guard records it as an error.
+fn load_reference(config: &Config, dataset: &LdbcDataset) -> Option<WccReference> {
+    if config.no_validate {
+        None
+    } else {
+        let wcc_path = config.dataset.join(format!("{}-WCC", dataset.name));
+        if wcc_path.exists() {
+            Some(WccReference::load(&wcc_path).unwrap_or_else(|e| {
+                panic!("failed to load WCC reference: {}", e)
+            }))
+        } else {
+            if !config.csv && !config.quiet {
+                eprintln!("No WCC reference file found, skipping LDBC validation.");
+            }
+            None
+        }
+    }
+}
+
+/// Load the graph, time WCC on Strata and (unless `--strata-only`) the
+/// union-find baseline, and record the results. With `--validate-only` it
+/// stops after the first Strata run, recording only a validation failure.
+fn run_benchmark(
+    config: &Config,
+    dataset: &LdbcDataset,
+    reference: Option<&WccReference>,
+    recorder: &mut ResultRecorder,
+) {
+    let total_elements = (dataset.vertices.len() + dataset.edges.len()) as f64;
+
+    // -----------------------------------------------------------------------
+    // Load phase
+    // -----------------------------------------------------------------------
+
+    let db = create_db(config.durability);
+    let strata_load_time = load_graph(&db, dataset);
+    if !config.csv && !config.quiet {
+        eprintln!("--- Load Phase ---");
+        eprintln!("  {:12} {}", "Strata:", fmt_ms(strata_load_time));
+        eprintln!();
+    }
+
+    // -----------------------------------------------------------------------
+    // WCC phase — Strata
+    // -----------------------------------------------------------------------
+
+    if config.csv {
+        println!("\"engine\",\"run\",\"wcc_time_ms\",\"evps\",\"vertices\",\"edges\"");
+    }
+
+    let mut strata_times = Vec::with_capacity(config.runs);
+    let mut components = 0;
+    let mut failures: Vec<String> = Vec::new();
+    for run in 0..config.runs {
+        let wcc_run = run_wcc(&db, dataset);
+        strata_times.push(wcc_run.elapsed);
+
+        if run == 0 {
+            components = wcc_run.traversals;
+            if let Some(reference) = reference {
+                let mismatches =
+                    wcc_mismatches(&dataset.vertices, &wcc_run.components, &reference.components);
+                if !mismatches.is_empty() {
+                    failures.push(format!(
+                        "{} component assignments differ from the LDBC reference",
+                        mismatches.len()
+                    ));
+                }
+                if !config.csv {
+                    report_validation("LDBC Validation", dataset, &mismatches);
+                }
+            }
+            if !config.strata_only {
+                let baseline = union_find_wcc(dataset);
+                let mismatches = wcc_mismatches(&dataset.vertices, &wcc_run.components, &baseline);
+                if !mismatches.is_empty() {
+                    failures.push(format!(
+                        "{} component assignments differ from union-find",
+                        mismatches.len()
+                    ));
+                }
+                if !config.csv {
+                    report_validation("Cross-validation (union-find)", dataset, &mismatches);
+                }
+            }
+            if !config.csv && !config.quiet {
+                eprintln!("Components: {}", fmt_num(components as u64));
+            }
+            if config.validate_only {
+                if !failures.is_empty() {
+                    recorder.record_failure(
+                        &result_name("strata", dataset, config.durability),
+                        base_params("strata", dataset, config.durability),
+                        ResultStatus::from_validation(&failures),
+                    );
+                } else if !config.csv {
+                    eprintln!("Validate-only mode, skipping remaining runs.");
+                }
+                return;
+            }
+        }
+
+        if config.csv {
+            print_csv_row("strata", run + 1, wcc_run.elapsed, total_elements, dataset);
+        }
+    }
+    let strata_stats = compute_stats(&mut strata_times, total_elements);
+
+    // -----------------------------------------------------------------------
+    // WCC phase — union-find
+    // -----------------------------------------------------------------------
+
+    let baseline_stats = if !config.strata_only {
+        let mut uf_times = Vec::with_capacity(config.runs);
+        for run in 0..config.runs {
+            let start = Instant::now();
+            let _ = union_find_wcc(dataset);
+            let elapsed = start.elapsed();
+            uf_times.push(elapsed);
+            if config.csv {
+                print_csv_row("union-find", run + 1, elapsed, total_elements, dataset);
+            }
+        }
+        Some(compute_stats(&mut uf_times, total_elements))
+    } else {
+        None
+    };
+
+    // -----------------------------------------------------------------------
+    // Output
+    // -----------------------------------------------------------------------
+
+    if config.quiet && !config.csv {
+        eprintln!(
+            "Strata WCC: {}, EVPS: {}, components: {}",
+            fmt_ms(strata_stats.avg),
+            fmt_num(strata_stats.avg_evps as u64),
+            components
+        );
+        if let Some(ref uf) = baseline_stats {
+            eprintln!(
+                "union-find WCC: {}, EVPS: {}",
+                fmt_ms(uf.avg),
+                fmt_num(uf.avg_evps as u64)
+            );
+        }
+    } else if !config.csv {
+        eprintln!();
+        eprintln!("--- WCC Phase ({} runs) ---", strata_stats.count);
+        eprintln!(
+            "  {:16} {:>10} {:>10} {:>10} {:>14}",
+            "", "avg", "p50", "max", "EVPS"
+        );
+        eprintln!(
+            "  {:16} {:>10} {:>10} {:>10} {:>14}",
+            "Strata:",
+            fmt_ms(strata_stats.avg),
+            fmt_ms(strata_stats.p50),
+            fmt_ms(strata_stats.max),
+            fmt_num(strata_stats.avg_evps as u64),
+        );
+        if let Some(ref uf) = baseline_stats {
+            eprintln!(
+                "  {:16} {:>10} {:>10} {:>10} {:>14}",
+                "union-find:",
+                fmt_ms(uf.avg),
+                fmt_ms(uf.p50),
+                fmt_ms(uf.max),
+                fmt_num(uf.avg_evps as u64),
+            );
+            eprintln!(
+                "  {:16} {:>10}",
+                "Ratio:",
+                format!("{:.1}x", strata_stats.avg.as_secs_f64() / uf.avg.as_secs_f64()),
+            );
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Record results
+    // -----------------------------------------------------------------------
+
+    record(
+        recorder,
+        "strata",
+        dataset,
+        config.durability,
+        components,
+        &strata_stats,
+        ResultStatus::from_validation(&failures),
+    );
+    if let Some(ref uf) = baseline_stats {
+        record(recorder, "union-find", dataset, config.durability, components, uf, ResultStatus::Success);
+    }
+}
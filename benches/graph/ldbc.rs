@@ -2,25 +2,73 @@
 //!
 //! Parses the standard LDBC file formats:
 //! - `.v`  — one vertex ID (u64) per line
-//! - `.e`  — `src dst` per line (space-separated u64 pair)
+//! - `.e`  — `src dst` or `src dst weight` per line (space-separated)
 //! - `.properties` — Java properties format with graph metadata
 //! - BFS reference — `vertex_id depth` per line
+//! - PageRank reference — `vertex_id rank` per line
+//! - WCC reference — `vertex_id component_id` per line
+//! - SSSP reference — `vertex_id distance` per line (`infinity` if unreachable)
+//! - CDLP reference — `vertex_id label` per line
+//! - LCC reference — `vertex_id coefficient` per line
+//!
+//! # Edge semantics
+//!
+//! Both engines are loaded from [`LdbcDataset::edges`], so they must agree on
+//! what an edge list means or EVPS is computed over different graphs:
+//!
+//! - **Repeated edges** — a `src dst` line that appears more than once is kept
+//!   once. Strata treats a repeated add of the same edge as an update, while
+//!   petgraph would add a parallel edge; dropping repeats at load gives both
+//!   the same edge count.
+//! - **Reverse pairs** — `1 2` and `2 1` are distinct directed edges and are
+//!   both kept. Traversed undirected, they are two parallel edges between the
+//!   same vertices in both engines.
+//! - **Self-loops** — kept, one edge each. They count towards |E| but never
+//!   change a BFS depth.
+//!
+//! Edge weights are optional, but a `.e` file has them on every line or on
+//! none. A repeated edge keeps the weight of its first occurrence.
 
-use std::collections::{HashMap, VecDeque};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::path::Path;
+use std::str::FromStr;
 
-use petgraph::graph::{NodeIndex, UnGraph};
+use petgraph::graph::{DiGraph, NodeIndex, UnGraph};
+use petgraph::Direction;
 
 /// Sentinel value for unreachable vertices in LDBC BFS output.
 pub const UNREACHABLE: i64 = 9223372036854775807; // i64::MAX
 
+/// Relative tolerance for floating-point outputs, per the Graphalytics
+/// validation rules: `|actual - expected| <= EPSILON * |expected|`.
+pub const EPSILON: f64 = 1e-4;
+
+/// PageRank defaults when the properties file doesn't set them.
+pub const DEFAULT_DAMPING_FACTOR: f64 = 0.85;
+pub const DEFAULT_PR_ITERATIONS: usize = 10;
+
+/// CDLP iteration count when the properties file doesn't set it.
+pub const DEFAULT_CDLP_ITERATIONS: usize = 10;
+
 /// An LDBC Graphalytics dataset (vertices + edges + metadata).
 pub struct LdbcDataset {
     pub vertices: Vec<u64>,
+    /// Edges in file order, with repeated edges removed (see module docs).
     pub edges: Vec<(u64, u64)>,
+    /// Weight of each entry in `edges`, when the `.e` file has a third column.
+    pub weights: Option<Vec<f64>>,
+    /// Repeated edges dropped from the `.e` file.
+    pub duplicate_edges: usize,
+    /// Edges from a vertex to itself, included in `edges`.
+    pub self_loops: usize,
     pub directed: bool,
     pub name: String,
     pub bfs_source: Option<u64>,
+    pub pr_damping_factor: Option<f64>,
+    pub pr_iterations: Option<usize>,
+    pub sssp_source: Option<u64>,
+    pub cdlp_iterations: Option<usize>,
 }
 
 /// BFS reference output for validation.
@@ -29,6 +77,34 @@ pub struct BfsReference {
     pub depths: HashMap<u64, i64>, // i64 to hold UNREACHABLE sentinel
 }
 
+/// PageRank reference output for validation.
+pub struct PageRankReference {
+    pub ranks: HashMap<u64, f64>,
+}
+
+/// SSSP reference output for validation. Unreachable vertices have an
+/// infinite distance.
+pub struct SsspReference {
+    pub distances: HashMap<u64, f64>,
+}
+
+/// CDLP reference output for validation. Labels are vertex IDs and must
+/// match exactly.
+pub struct CdlpReference {
+    pub labels: HashMap<u64, u64>,
+}
+
+/// LCC reference output for validation.
+pub struct LccReference {
+    pub coefficients: HashMap<u64, f64>,
+}
+
+/// WCC reference output for validation. Component IDs are labels only:
+/// two outputs agree if they group the vertices the same way.
+pub struct WccReference {
+    pub components: HashMap<u64, u64>,
+}
+
 impl LdbcDataset {
     /// Load an LDBC dataset from a directory.
     ///
@@ -61,12 +137,12 @@ impl LdbcDataset {
         // Parse edges
         let e_content = std::fs::read_to_string(&e_path)
             .map_err(|e| format!("failed to read {}: {}", e_path.display(), e))?;
-        let edges: Vec<(u64, u64)> = e_content
+        let parsed: Vec<(u64, u64, Option<f64>)> = e_content
             .lines()
             .filter(|l| !l.trim().is_empty())
             .map(|l| {
                 let parts: Vec<&str> = l.trim().split_whitespace().collect();
-                if parts.len() != 2 {
+                if parts.len() != 2 && parts.len() != 3 {
                     return Err(format!("bad edge line: '{}'", l.trim()));
                 }
                 let src = parts[0]
@@ -75,13 +151,41 @@ impl LdbcDataset {
                 let dst = parts[1]
                     .parse::<u64>()
                     .map_err(|e| format!("bad edge dst '{}': {}", parts[1], e))?;
-                Ok((src, dst))
+                let weight = match parts.get(2) {
+                    Some(w) => Some(
+                        w.parse::<f64>()
+                            .ok()
+                            .filter(|w| w.is_finite() && *w >= 0.0)
+                            .ok_or_else(|| format!("bad edge weight '{}'", w))?,
+                    ),
+                    None => None,
+                };
+                Ok((src, dst, weight))
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        let weighted = parsed.iter().filter(|(_, _, w)| w.is_some()).count();
+        if weighted != 0 && weighted != parsed.len() {
+            return Err(format!(
+                "{} of {} edges have a weight; expected all or none",
+                weighted,
+                parsed.len()
+            ));
+        }
+        let edges: Vec<(u64, u64)> = parsed.iter().map(|&(src, dst, _)| (src, dst)).collect();
+        let weights: Option<Vec<f64>> = if weighted > 0 {
+            Some(parsed.iter().filter_map(|&(_, _, w)| w).collect())
+        } else {
+            None
+        };
+
         // Parse properties (optional)
         let mut directed = true;
         let mut bfs_source = None;
+        let mut pr_damping_factor = None;
+        let mut pr_iterations = None;
+        let mut sssp_source = None;
+        let mut cdlp_iterations = None;
         let mut expected_vertices: Option<usize> = None;
         let mut expected_edges: Option<usize> = None;
 
@@ -102,13 +206,18 @@ impl LdbcDataset {
                         "meta.vertices" => expected_vertices = value.parse().ok(),
                         "meta.edges" => expected_edges = value.parse().ok(),
                         "algorithms.bfs.source-vertex" => bfs_source = value.parse().ok(),
+                        "algorithms.pr.damping-factor" => pr_damping_factor = value.parse().ok(),
+                        "algorithms.pr.num-iterations" => pr_iterations = value.parse().ok(),
+                        "algorithms.sssp.source-vertex" => sssp_source = value.parse().ok(),
+                        "algorithms.cdlp.max-iterations" => cdlp_iterations = value.parse().ok(),
                         _ => {}
                     }
                 }
             }
         }
 
-        // Validate counts if properties file provided them
+        // Validate counts if properties file provided them. meta.edges counts
+        // lines in the .e file, so this runs before repeated edges are dropped.
         if let Some(ev) = expected_vertices {
             if vertices.len() != ev {
                 return Err(format!(
@@ -128,12 +237,22 @@ impl LdbcDataset {
             }
         }
 
+        let (edges, weights, duplicate_edges) = dedup_edges(edges, weights);
+        let self_loops = edges.iter().filter(|(src, dst)| src == dst).count();
+
         Ok(LdbcDataset {
             vertices,
             edges,
+            weights,
+            duplicate_edges,
+            self_loops,
             directed,
             name,
             bfs_source,
+            pr_damping_factor,
+            pr_iterations,
+            sssp_source,
+            cdlp_iterations,
         })
     }
 
@@ -157,6 +276,121 @@ impl LdbcDataset {
 
         (graph, id_map)
     }
+
+    /// Build a petgraph directed graph from this dataset. An undirected
+    /// dataset gets both directions of every edge.
+    ///
+    /// Returns the graph and a mapping from LDBC vertex ID to petgraph NodeIndex.
+    pub fn to_petgraph_directed(&self) -> (DiGraph<(), ()>, HashMap<u64, NodeIndex>) {
+        let mut graph = DiGraph::new();
+        let mut id_map: HashMap<u64, NodeIndex> = HashMap::with_capacity(self.vertices.len());
+
+        for &vid in &self.vertices {
+            let idx = graph.add_node(());
+            id_map.insert(vid, idx);
+        }
+
+        for &(src, dst) in &self.edges {
+            if let (Some(&si), Some(&di)) = (id_map.get(&src), id_map.get(&dst)) {
+                graph.add_edge(si, di, ());
+                if !self.directed && si != di {
+                    graph.add_edge(di, si, ());
+                }
+            }
+        }
+
+        (graph, id_map)
+    }
+
+    /// Build a weighted petgraph directed graph for SSSP. An undirected
+    /// dataset gets both directions of every edge. Unweighted datasets use
+    /// weight 1.0, so distances are hop counts.
+    ///
+    /// Returns the graph and a mapping from LDBC vertex ID to petgraph NodeIndex.
+    pub fn to_petgraph_weighted(&self) -> (DiGraph<(), f64>, HashMap<u64, NodeIndex>) {
+        let mut graph = DiGraph::new();
+        let mut id_map: HashMap<u64, NodeIndex> = HashMap::with_capacity(self.vertices.len());
+
+        for &vid in &self.vertices {
+            let idx = graph.add_node(());
+            id_map.insert(vid, idx);
+        }
+
+        for (i, &(src, dst)) in self.edges.iter().enumerate() {
+            if let (Some(&si), Some(&di)) = (id_map.get(&src), id_map.get(&dst)) {
+                let w = self.edge_weight(i);
+                graph.add_edge(si, di, w);
+                if !self.directed && si != di {
+                    graph.add_edge(di, si, w);
+                }
+            }
+        }
+
+        (graph, id_map)
+    }
+
+    /// Weight of `edges[i]`, or 1.0 for an unweighted dataset.
+    pub fn edge_weight(&self, i: usize) -> f64 {
+        self.weights.as_ref().map_or(1.0, |w| w[i])
+    }
+
+    /// Lightest edge weight for each traversable `(from, to)` pair. An
+    /// undirected dataset has both directions; reverse pairs with
+    /// different weights keep the lighter one.
+    pub fn weight_map(&self) -> HashMap<(u64, u64), f64> {
+        let mut map: HashMap<(u64, u64), f64> = HashMap::with_capacity(self.edges.len());
+        let mut insert = |key: (u64, u64), w: f64| {
+            let entry = map.entry(key).or_insert(w);
+            *entry = entry.min(w);
+        };
+        for (i, &(src, dst)) in self.edges.iter().enumerate() {
+            let w = self.edge_weight(i);
+            insert((src, dst), w);
+            if !self.directed {
+                insert((dst, src), w);
+            }
+        }
+        map
+    }
+
+    /// PageRank damping factor and iteration count from the properties
+    /// file, or the defaults.
+    pub fn pagerank_params(&self) -> (f64, usize) {
+        (
+            self.pr_damping_factor.unwrap_or(DEFAULT_DAMPING_FACTOR),
+            self.pr_iterations.unwrap_or(DEFAULT_PR_ITERATIONS),
+        )
+    }
+
+    /// CDLP iteration count from the properties file, or the default.
+    pub fn cdlp_iterations(&self) -> usize {
+        self.cdlp_iterations.unwrap_or(DEFAULT_CDLP_ITERATIONS)
+    }
+}
+
+/// Drop repeated `(src, dst)` pairs, keeping the first occurrence of each
+/// and its weight. Returns the remaining edges and weights, and how many
+/// edges were dropped.
+fn dedup_edges(
+    edges: Vec<(u64, u64)>,
+    weights: Option<Vec<f64>>,
+) -> (Vec<(u64, u64)>, Option<Vec<f64>>, usize) {
+    let total = edges.len();
+    let mut seen = HashSet::with_capacity(total);
+    let keep: Vec<bool> = edges.iter().map(|&e| seen.insert(e)).collect();
+    let weights = weights.map(|w| {
+        w.into_iter()
+            .zip(&keep)
+            .filter_map(|(w, &k)| k.then_some(w))
+            .collect()
+    });
+    let edges: Vec<(u64, u64)> = edges
+        .into_iter()
+        .zip(&keep)
+        .filter_map(|(e, &k)| k.then_some(e))
+        .collect();
+    let dropped = total - edges.len();
+    (edges, weights, dropped)
 }
 
 /// Run BFS on a petgraph graph using a manual VecDeque-based traversal.
@@ -182,6 +416,319 @@ pub fn petgraph_bfs(graph: &UnGraph<(), ()>, source: NodeIndex) -> HashMap<NodeI
     depths
 }
 
+/// Run BFS on a directed petgraph graph, following edges in `direction`
+/// only: `Outgoing` is the Graphalytics traversal for directed graphs,
+/// `Incoming` walks the edges in reverse.
+///
+/// Returns a map from NodeIndex to BFS depth (0 for source).
+pub fn petgraph_bfs_directed(
+    graph: &DiGraph<(), ()>,
+    source: NodeIndex,
+    direction: Direction,
+) -> HashMap<NodeIndex, usize> {
+    let mut depths: HashMap<NodeIndex, usize> = HashMap::with_capacity(graph.node_count());
+    let mut queue = VecDeque::new();
+
+    depths.insert(source, 0);
+    queue.push_back(source);
+
+    while let Some(node) = queue.pop_front() {
+        let d = depths[&node];
+        for neighbor in graph.neighbors_directed(node, direction) {
+            if let std::collections::hash_map::Entry::Vacant(e) = depths.entry(neighbor) {
+                e.insert(d + 1);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    depths
+}
+
+/// One PageRank iteration as specified by LDBC Graphalytics:
+///
+/// `PR(v) = (1 - d) / |V| + d * Σ_{u → v} PR(u) / outdeg(u) + d / |V| * Σ_{w dangling} PR(w)`
+///
+/// `in_neighbors[v]` lists the sources of v's incoming edges and
+/// `out_degree[u]` counts u's outgoing edges, both without self-loops.
+/// Graphalytics graphs have none, and skipping them lets the in-memory and
+/// Strata implementations agree on graphs that do.
+pub fn pagerank_step(
+    ranks: &[f64],
+    in_neighbors: &[Vec<usize>],
+    out_degree: &[usize],
+    damping: f64,
+) -> Vec<f64> {
+    let n = ranks.len() as f64;
+    let dangling: f64 = ranks
+        .iter()
+        .zip(out_degree)
+        .filter(|(_, &deg)| deg == 0)
+        .map(|(&r, _)| r)
+        .sum();
+    let base = (1.0 - damping) / n + damping * dangling / n;
+    in_neighbors
+        .iter()
+        .map(|sources| {
+            base + damping
+                * sources
+                    .iter()
+                    .map(|&u| ranks[u] / out_degree[u] as f64)
+                    .sum::<f64>()
+        })
+        .collect()
+}
+
+/// Run PageRank on a petgraph graph for a fixed number of iterations.
+///
+/// Returns ranks indexed by NodeIndex.
+pub fn petgraph_pagerank(graph: &DiGraph<(), ()>, damping: f64, iterations: usize) -> Vec<f64> {
+    let n = graph.node_count();
+    if n == 0 {
+        return Vec::new();
+    }
+    let in_neighbors: Vec<Vec<usize>> = graph
+        .node_indices()
+        .map(|v| {
+            graph
+                .neighbors_directed(v, Direction::Incoming)
+                .filter(|&u| u != v)
+                .map(|u| u.index())
+                .collect()
+        })
+        .collect();
+    let out_degree: Vec<usize> = graph
+        .node_indices()
+        .map(|u| {
+            graph
+                .neighbors_directed(u, Direction::Outgoing)
+                .filter(|&v| v != u)
+                .count()
+        })
+        .collect();
+
+    let mut ranks = vec![1.0 / n as f64; n];
+    for _ in 0..iterations {
+        ranks = pagerank_step(&ranks, &in_neighbors, &out_degree, damping);
+    }
+    ranks
+}
+
+/// Weakly connected components by union-find over the edge list, ignoring
+/// direction. Each vertex is labelled with the smallest vertex ID in its
+/// component, as in the LDBC reference outputs.
+pub fn union_find_wcc(dataset: &LdbcDataset) -> HashMap<u64, u64> {
+    let index: HashMap<u64, usize> = dataset
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(i, &vid)| (vid, i))
+        .collect();
+    let mut parent: Vec<usize> = (0..dataset.vertices.len()).collect();
+
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+
+    for &(src, dst) in &dataset.edges {
+        if let (Some(&a), Some(&b)) = (index.get(&src), index.get(&dst)) {
+            let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+            if ra != rb {
+                parent[ra.max(rb)] = ra.min(rb);
+            }
+        }
+    }
+
+    let mut label: HashMap<usize, u64> = HashMap::new();
+    for (i, &vid) in dataset.vertices.iter().enumerate() {
+        let root = find(&mut parent, i);
+        let l = label.entry(root).or_insert(vid);
+        *l = (*l).min(vid);
+    }
+    dataset
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(i, &vid)| (vid, label[&find(&mut parent, i)]))
+        .collect()
+}
+
+/// Vertices whose component assignment disagrees between `actual` and
+/// `expected`, comparing groupings rather than label values. A vertex
+/// disagrees if it's missing from `actual`, or if its actual label is
+/// already paired with a different expected label (or the reverse).
+pub fn wcc_mismatches(
+    vertices: &[u64],
+    actual: &HashMap<u64, u64>,
+    expected: &HashMap<u64, u64>,
+) -> Vec<u64> {
+    let mut forward: HashMap<u64, u64> = HashMap::new();
+    let mut backward: HashMap<u64, u64> = HashMap::new();
+    let mut mismatches = Vec::new();
+
+    for &vid in vertices {
+        let (a, e) = match (actual.get(&vid), expected.get(&vid)) {
+            (Some(&a), Some(&e)) => (a, e),
+            _ => {
+                mismatches.push(vid);
+                continue;
+            }
+        };
+        let f = *forward.entry(a).or_insert(e);
+        let b = *backward.entry(e).or_insert(a);
+        if f != e || b != a {
+            mismatches.push(vid);
+        }
+    }
+
+    mismatches
+}
+
+/// Out-neighbors of every vertex of a graph built by
+/// [`LdbcDataset::to_petgraph_directed`], indexed by NodeIndex (which
+/// follows the order of `vertices`). Self-loops are skipped and each
+/// neighbor is listed once, as a depth-1 Strata BFS returns them.
+pub fn petgraph_adjacency(graph: &DiGraph<(), ()>) -> Vec<Vec<usize>> {
+    graph
+        .node_indices()
+        .map(|u| {
+            let mut out: Vec<usize> = graph
+                .neighbors_directed(u, Direction::Outgoing)
+                .filter(|&v| v != u)
+                .map(|v| v.index())
+                .collect();
+            out.sort_unstable();
+            out.dedup();
+            out
+        })
+        .collect()
+}
+
+/// Reverse an out-adjacency list into in-neighbors.
+fn transpose(out: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut incoming = vec![Vec::new(); out.len()];
+    for (u, targets) in out.iter().enumerate() {
+        for &v in targets {
+            incoming[v].push(u);
+        }
+    }
+    incoming
+}
+
+/// Community detection by synchronous label propagation, per the
+/// Graphalytics CDLP definition.
+///
+/// Every vertex starts labelled with its own ID (`vertices[i]`). Each
+/// iteration, a vertex takes the label most frequent among its neighbors,
+/// the smallest on a tie, and keeps its label if it has none. In a directed
+/// graph both in- and out-neighbors count, so a neighbor linked in both
+/// directions counts twice. `out` is the out-adjacency (both directions for
+/// an undirected graph) without self-loops. Returns labels indexed like
+/// `vertices`.
+pub fn cdlp(vertices: &[u64], out: &[Vec<usize>], directed: bool, iterations: usize) -> Vec<u64> {
+    let incoming = if directed { transpose(out) } else { vec![Vec::new(); out.len()] };
+    let mut labels = vertices.to_vec();
+    let mut counts: HashMap<u64, usize> = HashMap::new();
+
+    for _ in 0..iterations {
+        labels = (0..labels.len())
+            .map(|v| {
+                counts.clear();
+                for &u in out[v].iter().chain(&incoming[v]) {
+                    *counts.entry(labels[u]).or_insert(0) += 1;
+                }
+                counts
+                    .iter()
+                    .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+                    .map_or(labels[v], |(&label, _)| label)
+            })
+            .collect();
+    }
+
+    labels
+}
+
+/// Local clustering coefficient of every vertex, per the Graphalytics LCC
+/// definition.
+///
+/// A vertex's neighborhood is the set of its in- and out-neighbors. Its
+/// coefficient is the number of edges between neighborhood members divided
+/// by the number possible, `k * (k - 1)` for `k` neighbors, and 0 when
+/// `k < 2`. `out` is the out-adjacency (both directions for an undirected
+/// graph, where each edge is then counted once per direction) without
+/// self-loops. Returns coefficients indexed like `out`.
+pub fn lcc(out: &[Vec<usize>], directed: bool) -> Vec<f64> {
+    let incoming = if directed { transpose(out) } else { vec![Vec::new(); out.len()] };
+    // mark[w] == v + 1 while w is in v's neighborhood
+    let mut mark = vec![0usize; out.len()];
+
+    (0..out.len())
+        .map(|v| {
+            let mut neighborhood = Vec::new();
+            for &u in out[v].iter().chain(&incoming[v]) {
+                if mark[u] != v + 1 {
+                    mark[u] = v + 1;
+                    neighborhood.push(u);
+                }
+            }
+            let k = neighborhood.len();
+            if k < 2 {
+                return 0.0;
+            }
+            let links = neighborhood
+                .iter()
+                .map(|&u| out[u].iter().filter(|&&w| w != v && mark[w] == v + 1).count())
+                .sum::<usize>();
+            links as f64 / (k * (k - 1)) as f64
+        })
+        .collect()
+}
+
+/// Dijkstra from `source` over vertices `0..n`. `neighbors(u)` yields u's
+/// outgoing `(v, weight)` pairs; it's called once per settled vertex, so a
+/// caller can fetch adjacency lazily. Returns distances indexed by vertex,
+/// `f64::INFINITY` where unreachable. Weights must be non-negative.
+pub fn dijkstra<F, I>(n: usize, source: usize, mut neighbors: F) -> Vec<f64>
+where
+    F: FnMut(usize) -> I,
+    I: IntoIterator<Item = (usize, f64)>,
+{
+    let mut dist = vec![f64::INFINITY; n];
+    let mut settled = vec![false; n];
+    // Non-negative f64s order the same as their bit patterns, which gives
+    // the heap a total order without a wrapper type.
+    let mut heap = BinaryHeap::new();
+    dist[source] = 0.0;
+    heap.push(Reverse((0f64.to_bits(), source)));
+
+    while let Some(Reverse((bits, u))) = heap.pop() {
+        if settled[u] {
+            continue;
+        }
+        settled[u] = true;
+        let d = f64::from_bits(bits);
+        for (v, w) in neighbors(u) {
+            let nd = d + w;
+            if nd < dist[v] {
+                dist[v] = nd;
+                heap.push(Reverse((nd.to_bits(), v)));
+            }
+        }
+    }
+
+    dist
+}
+
+/// Graphalytics epsilon match for floating-point outputs. Equal values
+/// always match, so two infinite SSSP distances agree.
+pub fn epsilon_eq(actual: f64, expected: f64) -> bool {
+    actual == expected || (actual - expected).abs() <= EPSILON * expected.abs()
+}
+
 impl BfsReference {
     /// Load a BFS reference output file.
     ///
@@ -222,6 +769,93 @@ impl BfsReference {
     }
 }
 
+/// Parse a `vertex_id value` per line output file. `what` names the value
+/// in error messages.
+fn read_vertex_values<T: FromStr>(path: &Path, what: &str) -> Result<HashMap<u64, T>, String>
+where
+    T::Err: std::fmt::Display,
+{
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+    let mut values = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() != 2 {
+            return Err(format!("bad {} reference line: '{}'", what, line));
+        }
+        let vid = parts[0]
+            .parse::<u64>()
+            .map_err(|e| format!("bad vertex id '{}': {}", parts[0], e))?;
+        let value = parts[1]
+            .parse::<T>()
+            .map_err(|e| format!("bad {} '{}': {}", what, parts[1], e))?;
+        values.insert(vid, value);
+    }
+
+    Ok(values)
+}
+
+impl PageRankReference {
+    /// Load a PageRank reference output file.
+    ///
+    /// Format: `vertex_id rank` per line, space-separated.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Ok(PageRankReference {
+            ranks: read_vertex_values(path, "rank")?,
+        })
+    }
+}
+
+impl SsspReference {
+    /// Load an SSSP reference output file.
+    ///
+    /// Format: `vertex_id distance` per line, space-separated. Unreachable
+    /// vertices have distance `infinity`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Ok(SsspReference {
+            distances: read_vertex_values(path, "distance")?,
+        })
+    }
+}
+
+impl CdlpReference {
+    /// Load a CDLP reference output file.
+    ///
+    /// Format: `vertex_id label` per line, space-separated.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Ok(CdlpReference {
+            labels: read_vertex_values(path, "label")?,
+        })
+    }
+}
+
+impl LccReference {
+    /// Load an LCC reference output file.
+    ///
+    /// Format: `vertex_id coefficient` per line, space-separated.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Ok(LccReference {
+            coefficients: read_vertex_values(path, "coefficient")?,
+        })
+    }
+}
+
+impl WccReference {
+    /// Load a WCC reference output file.
+    ///
+    /// Format: `vertex_id component_id` per line, space-separated.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Ok(WccReference {
+            components: read_vertex_values(path, "component")?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -235,6 +869,14 @@ mod tests {
         LdbcDataset::load(&example_dir()).unwrap()
     }
 
+    fn multi_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-multi")
+    }
+
+    fn multi_dataset() -> LdbcDataset {
+        LdbcDataset::load(&multi_dir()).unwrap()
+    }
+
     // -----------------------------------------------------------------------
     // Dataset loading tests
     // -----------------------------------------------------------------------
@@ -270,6 +912,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn example_dataset_has_no_repeats_or_self_loops() {
+        let ds = example_dataset();
+        assert_eq!(ds.duplicate_edges, 0);
+        assert_eq!(ds.self_loops, 0);
+    }
+
+    // -----------------------------------------------------------------------
+    // Edge semantics: repeated edges, reverse pairs, self-loops
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn load_drops_repeated_edges() {
+        // 11 lines in the .e file (matching meta.edges), 3 of them repeats
+        let ds = multi_dataset();
+        assert_eq!(ds.duplicate_edges, 3);
+        assert_eq!(ds.edges.len(), 8);
+        let unique: HashSet<_> = ds.edges.iter().collect();
+        assert_eq!(unique.len(), ds.edges.len());
+    }
+
+    #[test]
+    fn load_keeps_reverse_pairs_and_self_loops() {
+        let ds = multi_dataset();
+        assert!(ds.edges.contains(&(1, 2)));
+        assert!(ds.edges.contains(&(2, 1)));
+        assert_eq!(ds.self_loops, 3);
+        for vid in [3, 5, 6] {
+            assert!(ds.edges.contains(&(vid, vid)), "self-loop on {} missing", vid);
+        }
+    }
+
+    #[test]
+    fn load_keeps_first_occurrence_order() {
+        let ds = multi_dataset();
+        assert_eq!(
+            ds.edges,
+            vec![(1, 2), (2, 1), (2, 3), (3, 3), (3, 4), (4, 5), (5, 5), (6, 6)]
+        );
+    }
+
+    #[test]
+    fn dedup_edges_treats_direction_as_distinct() {
+        let (edges, weights, dropped) =
+            dedup_edges(vec![(1, 2), (2, 1), (1, 2), (2, 1), (7, 7), (7, 7)], None);
+        assert_eq!(edges, vec![(1, 2), (2, 1), (7, 7)]);
+        assert!(weights.is_none());
+        assert_eq!(dropped, 3);
+    }
+
+    #[test]
+    fn to_petgraph_edge_count_matches_dataset_with_repeats() {
+        // petgraph gets exactly the deduplicated list: one edge per reverse
+        // pair direction, one per self-loop, none for repeats
+        let ds = multi_dataset();
+        let (graph, _) = ds.to_petgraph();
+        assert_eq!(graph.edge_count(), ds.edges.len());
+        assert_eq!(graph.edge_count(), 8);
+    }
+
+    #[test]
+    fn petgraph_bfs_ignores_self_loops() {
+        let ds = multi_dataset();
+        let (graph, id_map) = ds.to_petgraph();
+        let depths = petgraph_bfs(&graph, id_map[&1]);
+        let reference = BfsReference::load(&multi_dir().join("example-multi-BFS")).unwrap();
+
+        for &vid in &ds.vertices {
+            let expected = reference.depths[&vid];
+            let actual = depths.get(&id_map[&vid]).map(|&d| d as i64).unwrap_or(UNREACHABLE);
+            assert_eq!(actual, expected, "vertex {}", vid);
+        }
+        // Vertex 6 has only a self-loop, so it stays unreachable
+        assert!(!depths.contains_key(&id_map[&6]));
+    }
+
     // -----------------------------------------------------------------------
     // to_petgraph tests
     // -----------------------------------------------------------------------
@@ -449,4 +1167,348 @@ mod tests {
             assert_eq!(depths[node], i, "node {} should be at depth {}", i, i);
         }
     }
+
+    #[test]
+    fn petgraph_bfs_directed_outgoing_matches_ldbc_reference() {
+        let ds = example_dataset();
+        let (graph, id_map) = ds.to_petgraph_directed();
+        let depths = petgraph_bfs_directed(&graph, id_map[&1], Direction::Outgoing);
+        let reference = BfsReference::load(&example_dir().join("example-directed-BFS")).unwrap();
+
+        for &vid in &ds.vertices {
+            let expected = reference.depths[&vid];
+            let actual = depths.get(&id_map[&vid]).map(|&d| d as i64).unwrap_or(UNREACHABLE);
+            assert_eq!(actual, expected, "vertex {} depth mismatch", vid);
+        }
+    }
+
+    #[test]
+    fn petgraph_bfs_directed_incoming_follows_reverse_edges() {
+        // Only 2 -> 1 and 3 -> 1 lead into vertex 1, and only 1 leads into
+        // 2 and 3, so walking edges backwards from 1 reaches just {1, 2, 3}
+        let ds = example_dataset();
+        let (graph, id_map) = ds.to_petgraph_directed();
+        let depths = petgraph_bfs_directed(&graph, id_map[&1], Direction::Incoming);
+
+        assert_eq!(depths.len(), 3);
+        assert_eq!(depths[&id_map[&1]], 0);
+        assert_eq!(depths[&id_map[&2]], 1);
+        assert_eq!(depths[&id_map[&3]], 1);
+    }
+
+    #[test]
+    fn petgraph_bfs_directed_stops_at_sinks() {
+        // 0 -> 1 -> 2, 3 -> 2: nothing leaves 2, and 3 isn't reachable from 0
+        let mut graph = DiGraph::new();
+        let n: Vec<_> = (0..4).map(|_| graph.add_node(())).collect();
+        graph.add_edge(n[0], n[1], ());
+        graph.add_edge(n[1], n[2], ());
+        graph.add_edge(n[3], n[2], ());
+
+        let out = petgraph_bfs_directed(&graph, n[0], Direction::Outgoing);
+        assert_eq!(out.len(), 3);
+        assert_eq!(out[&n[2]], 2);
+        assert!(!out.contains_key(&n[3]));
+
+        let back = petgraph_bfs_directed(&graph, n[2], Direction::Incoming);
+        assert_eq!(back.len(), 4);
+        assert_eq!(back[&n[0]], 2);
+        assert_eq!(back[&n[3]], 1);
+    }
+
+    // -----------------------------------------------------------------------
+    // PageRank tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn load_pagerank_params() {
+        let ds = example_dataset();
+        assert_eq!(ds.pr_damping_factor, Some(0.85));
+        assert_eq!(ds.pr_iterations, Some(2));
+        assert_eq!(ds.pagerank_params(), (0.85, 2));
+        assert_eq!(
+            multi_dataset().pagerank_params(),
+            (DEFAULT_DAMPING_FACTOR, DEFAULT_PR_ITERATIONS)
+        );
+    }
+
+    #[test]
+    fn to_petgraph_directed_keeps_direction() {
+        let ds = example_dataset();
+        let (graph, id_map) = ds.to_petgraph_directed();
+        assert_eq!(graph.node_count(), 10);
+        assert_eq!(graph.edge_count(), 17);
+        // 4 -> 5 exists, 5 -> 4 doesn't
+        let out4: Vec<_> = graph.neighbors_directed(id_map[&4], Direction::Outgoing).collect();
+        let out5: Vec<_> = graph.neighbors_directed(id_map[&5], Direction::Outgoing).collect();
+        assert!(out4.contains(&id_map[&5]));
+        assert!(!out5.contains(&id_map[&4]));
+    }
+
+    #[test]
+    fn petgraph_pagerank_matches_ldbc_reference() {
+        let ds = example_dataset();
+        let (graph, id_map) = ds.to_petgraph_directed();
+        let (damping, iterations) = ds.pagerank_params();
+        let ranks = petgraph_pagerank(&graph, damping, iterations);
+        let reference = PageRankReference::load(&example_dir().join("example-directed-PR")).unwrap();
+        assert_eq!(reference.ranks.len(), 10);
+
+        for &vid in &ds.vertices {
+            let actual = ranks[id_map[&vid].index()];
+            let expected = reference.ranks[&vid];
+            assert!(
+                epsilon_eq(actual, expected),
+                "vertex {}: expected {}, got {}",
+                vid,
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn petgraph_pagerank_conserves_mass_with_dangling_vertices() {
+        // 0 -> 1 -> 2, 2 dangling: the dangling mass is spread over every vertex
+        let mut graph = DiGraph::new();
+        let n: Vec<_> = (0..3).map(|_| graph.add_node(())).collect();
+        graph.add_edge(n[0], n[1], ());
+        graph.add_edge(n[1], n[2], ());
+
+        let ranks = petgraph_pagerank(&graph, 0.85, 20);
+        let total: f64 = ranks.iter().sum();
+        assert!((total - 1.0).abs() < 1e-12, "ranks sum to {}", total);
+        assert!(ranks[2] > ranks[1] && ranks[1] > ranks[0]);
+    }
+
+    #[test]
+    fn petgraph_pagerank_skips_self_loops() {
+        // A self-loop neither adds to out-degree nor feeds rank back
+        let mut with_loop = DiGraph::new();
+        let a = with_loop.add_node(());
+        let b = with_loop.add_node(());
+        with_loop.add_edge(a, b, ());
+        with_loop.add_edge(b, a, ());
+        with_loop.add_edge(a, a, ());
+
+        let mut without = DiGraph::new();
+        let a2 = without.add_node(());
+        let b2 = without.add_node(());
+        without.add_edge(a2, b2, ());
+        without.add_edge(b2, a2, ());
+
+        assert_eq!(petgraph_pagerank(&with_loop, 0.85, 5), petgraph_pagerank(&without, 0.85, 5));
+    }
+
+    #[test]
+    fn epsilon_eq_is_relative() {
+        assert!(epsilon_eq(1.00005, 1.0));
+        assert!(!epsilon_eq(1.0002, 1.0));
+        assert!(epsilon_eq(1.00005e-6, 1.0e-6));
+        assert!(!epsilon_eq(1.0002e-6, 1.0e-6));
+    }
+
+    // -----------------------------------------------------------------------
+    // WCC tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn union_find_wcc_matches_ldbc_reference() {
+        for (ds, dir) in [(example_dataset(), example_dir()), (multi_dataset(), multi_dir())] {
+            let reference = WccReference::load(&dir.join(format!("{}-WCC", ds.name))).unwrap();
+            let components = union_find_wcc(&ds);
+            assert_eq!(components, reference.components, "{}", ds.name);
+        }
+    }
+
+    #[test]
+    fn union_find_wcc_ignores_direction() {
+        // In the multi fixture 6 has only a self-loop and stays on its own
+        let components = union_find_wcc(&multi_dataset());
+        assert_eq!(components[&5], 1);
+        assert_eq!(components[&6], 6);
+    }
+
+    #[test]
+    fn wcc_mismatches_compares_groupings_not_labels() {
+        let vertices = [1, 2, 3, 4];
+        let expected: HashMap<u64, u64> = [(1, 1), (2, 1), (3, 3), (4, 3)].into_iter().collect();
+
+        // Same grouping, different labels
+        let relabelled: HashMap<u64, u64> = [(1, 7), (2, 7), (3, 9), (4, 9)].into_iter().collect();
+        assert!(wcc_mismatches(&vertices, &relabelled, &expected).is_empty());
+
+        // 3 and 4 merged into 1's component
+        let merged: HashMap<u64, u64> = [(1, 7), (2, 7), (3, 7), (4, 7)].into_iter().collect();
+        assert_eq!(wcc_mismatches(&vertices, &merged, &expected), vec![3, 4]);
+
+        // 4 split off, and missing vertices count
+        let split: HashMap<u64, u64> = [(1, 7), (2, 7), (4, 8)].into_iter().collect();
+        assert_eq!(wcc_mismatches(&vertices, &split, &expected), vec![3]);
+        let split: HashMap<u64, u64> = [(1, 7), (2, 7), (3, 9), (4, 8)].into_iter().collect();
+        assert_eq!(wcc_mismatches(&vertices, &split, &expected), vec![4]);
+    }
+
+    // -----------------------------------------------------------------------
+    // SSSP tests
+    // -----------------------------------------------------------------------
+
+    /// Dijkstra over the dataset's own edge list, keyed by vertex ID.
+    fn sssp(ds: &LdbcDataset, source: u64) -> HashMap<u64, f64> {
+        let index: HashMap<u64, usize> =
+            ds.vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        let mut adjacency: Vec<Vec<(usize, f64)>> = vec![Vec::new(); ds.vertices.len()];
+        for (&(src, dst), &w) in ds.weight_map().iter() {
+            adjacency[index[&src]].push((index[&dst], w));
+        }
+        let dist = dijkstra(ds.vertices.len(), index[&source], |u| adjacency[u].clone());
+        ds.vertices.iter().copied().zip(dist).collect()
+    }
+
+    #[test]
+    fn load_weighted_edges() {
+        let ds = example_dataset();
+        let weights = ds.weights.as_ref().expect("example-directed is weighted");
+        assert_eq!(weights.len(), ds.edges.len());
+        assert_eq!(ds.edges[0], (1, 2));
+        assert_eq!(ds.edge_weight(0), 0.5);
+        assert_eq!(ds.sssp_source, Some(1));
+        // Unweighted datasets get unit weights
+        let multi = multi_dataset();
+        assert!(multi.weights.is_none());
+        assert_eq!(multi.edge_weight(0), 1.0);
+    }
+
+    #[test]
+    fn dedup_edges_keeps_first_weight() {
+        let (edges, weights, dropped) =
+            dedup_edges(vec![(1, 2), (1, 2), (2, 1)], Some(vec![3.0, 1.0, 2.0]));
+        assert_eq!(edges, vec![(1, 2), (2, 1)]);
+        assert_eq!(weights, Some(vec![3.0, 2.0]));
+        assert_eq!(dropped, 1);
+    }
+
+    #[test]
+    fn load_rejects_partially_weighted_edges() {
+        let dir = tempfile::tempdir().unwrap();
+        let ds_dir = dir.path().join("partial");
+        std::fs::create_dir(&ds_dir).unwrap();
+        std::fs::write(ds_dir.join("partial.v"), "1\n2\n3\n").unwrap();
+        std::fs::write(ds_dir.join("partial.e"), "1 2 0.5\n2 3\n").unwrap();
+        let err = LdbcDataset::load(&ds_dir).err().expect("mixed weights should fail");
+        assert!(err.contains("all or none"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn dijkstra_matches_ldbc_reference() {
+        let ds = example_dataset();
+        let reference =
+            SsspReference::load(&example_dir().join("example-directed-SSSP")).unwrap();
+        let dist = sssp(&ds, ds.sssp_source.unwrap());
+        for (vid, expected) in &reference.distances {
+            assert!(
+                epsilon_eq(dist[vid], *expected),
+                "vertex {}: expected {}, got {}",
+                vid,
+                expected,
+                dist[vid]
+            );
+        }
+    }
+
+    #[test]
+    fn dijkstra_unreachable_is_infinite() {
+        // Unit weights on the unweighted fixture; 6 only has a self-loop
+        let ds = multi_dataset();
+        let reference = SsspReference::load(&multi_dir().join("example-multi-SSSP")).unwrap();
+        assert!(reference.distances[&6].is_infinite());
+        let dist = sssp(&ds, 1);
+        assert!(dist[&6].is_infinite());
+        for (vid, expected) in &reference.distances {
+            assert!(epsilon_eq(dist[vid], *expected), "vertex {}", vid);
+        }
+    }
+
+    #[test]
+    fn petgraph_dijkstra_matches_ldbc_reference() {
+        let ds = example_dataset();
+        let reference =
+            SsspReference::load(&example_dir().join("example-directed-SSSP")).unwrap();
+        let (graph, id_map) = ds.to_petgraph_weighted();
+        let dist = petgraph::algo::dijkstra(&graph, id_map[&1], None, |e| *e.weight());
+        for (vid, expected) in &reference.distances {
+            let actual = dist.get(&id_map[vid]).copied().unwrap_or(f64::INFINITY);
+            assert!(epsilon_eq(actual, *expected), "vertex {}", vid);
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // CDLP and LCC tests
+    // -----------------------------------------------------------------------
+
+    fn petgraph_out(ds: &LdbcDataset) -> Vec<Vec<usize>> {
+        let (graph, _) = ds.to_petgraph_directed();
+        petgraph_adjacency(&graph)
+    }
+
+    #[test]
+    fn petgraph_adjacency_skips_self_loops_and_repeats() {
+        // multi: 3 -> 3 and 3 -> 4 twice in the file; 6 only has a self-loop
+        let out = petgraph_out(&multi_dataset());
+        assert_eq!(out[2], vec![3]);
+        assert!(out[5].is_empty());
+    }
+
+    #[test]
+    fn cdlp_matches_ldbc_reference() {
+        for (ds, dir) in [(example_dataset(), example_dir()), (multi_dataset(), multi_dir())] {
+            assert_eq!(ds.cdlp_iterations(), 2);
+            let reference =
+                CdlpReference::load(&dir.join(format!("{}-CDLP", ds.name))).unwrap();
+            let labels = cdlp(&ds.vertices, &petgraph_out(&ds), ds.directed, ds.cdlp_iterations());
+            for (vid, label) in ds.vertices.iter().zip(&labels) {
+                assert_eq!(reference.labels[vid], *label, "{} vertex {}", ds.name, vid);
+            }
+        }
+    }
+
+    #[test]
+    fn cdlp_breaks_ties_by_smallest_label() {
+        // 1 - 2 - 3 path: 2 sees labels 1 and 3 once each
+        let labels = cdlp(&[1, 2, 3], &[vec![1], vec![0, 2], vec![1]], false, 1);
+        assert_eq!(labels, vec![2, 1, 2]);
+    }
+
+    #[test]
+    fn cdlp_counts_reverse_pairs_twice() {
+        // 0 <-> 1 and 2 -> 0: vertex 0 sees label 20 twice and 30 once
+        let labels = cdlp(&[10, 20, 30], &[vec![1], vec![0], vec![0]], true, 1);
+        assert_eq!(labels[0], 20);
+    }
+
+    #[test]
+    fn lcc_matches_ldbc_reference() {
+        for (ds, dir) in [(example_dataset(), example_dir()), (multi_dataset(), multi_dir())] {
+            let reference = LccReference::load(&dir.join(format!("{}-LCC", ds.name))).unwrap();
+            let coefficients = lcc(&petgraph_out(&ds), ds.directed);
+            for (vid, c) in ds.vertices.iter().zip(&coefficients) {
+                assert!(
+                    epsilon_eq(*c, reference.coefficients[vid]),
+                    "{} vertex {}: expected {}, got {}",
+                    ds.name,
+                    vid,
+                    reference.coefficients[vid],
+                    c
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn lcc_of_undirected_triangle_is_one() {
+        let out = [vec![1, 2], vec![0, 2], vec![0, 1]];
+        assert_eq!(lcc(&out, false), vec![1.0, 1.0, 1.0]);
+        // Directed 0 -> 1 -> 2 -> 0: each neighborhood has one of two links
+        assert_eq!(lcc(&[vec![1], vec![2], vec![0]], true), vec![0.5, 0.5, 0.5]);
+    }
 }
@@ -0,0 +1,144 @@
+//! CPU pinning and scheduling priority.
+//!
+//! On a shared machine the scheduler moves bench threads between cores and
+//! lets other processes run on the same ones, which shows up as run-to-run
+//! noise. `--cpus 2-5` restricts the bench process to those cores, and the
+//! multi-threaded runners ([`super::load`], [`super::scaling`]) pin worker
+//! `i` to the `i`-th of them. `--nice N` changes the scheduling priority;
+//! raising it (a negative N) needs root or `CAP_SYS_NICE`.
+//!
+//! Both are applied to the thread that parses the arguments, before the
+//! bench spawns any others, and every thread spawned afterwards inherits
+//! them. Pinning is Linux only; elsewhere `--cpus` is reported and ignored.
+
+use std::sync::OnceLock;
+
+static PINNED: OnceLock<Vec<usize>> = OnceLock::new();
+
+/// Restrict this thread, and the threads it spawns from here on, to `cpus`.
+/// Workers are then pinned one per core with [`pin_worker`].
+pub fn pin_process(cpus: &[usize]) -> Result<(), String> {
+    set_affinity(cpus)?;
+    let _ = PINNED.set(cpus.to_vec());
+    Ok(())
+}
+
+/// Pin the calling worker thread to one core of the `--cpus` set, round
+/// robin by `worker` index. Does nothing without `--cpus`.
+pub fn pin_worker(worker: usize) {
+    if let Some(cpus) = PINNED.get().filter(|c| !c.is_empty()) {
+        let _ = set_affinity(&[cpus[worker % cpus.len()]]);
+    }
+}
+
+/// The `--cpus` set, as a CPU list like `2-5,8`, when workers are pinned.
+pub fn pinned_cpus() -> Option<String> {
+    PINNED.get().map(|cpus| format_cpu_list(cpus))
+}
+
+/// Set the nice value of this thread and the threads it spawns from here on.
+pub fn set_nice(nice: i32) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        const PRIO_PROCESS: i32 = 0;
+        unsafe extern "C" {
+            fn setpriority(which: i32, who: u32, prio: i32) -> i32;
+        }
+        // SAFETY: setpriority only reads its integer arguments. On Linux,
+        // who = 0 is the calling thread.
+        if unsafe { setpriority(PRIO_PROCESS, 0, nice) } != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = nice;
+        Err("not supported on this platform".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(cpus: &[usize]) -> Result<(), String> {
+    /// `cpu_set_t`: a 1024-bit mask.
+    const MASK_WORDS: usize = 1024 / 64;
+    unsafe extern "C" {
+        fn sched_setaffinity(pid: i32, cpusetsize: usize, mask: *const u64) -> i32;
+    }
+
+    let mut mask = [0u64; MASK_WORDS];
+    for &cpu in cpus {
+        if cpu >= MASK_WORDS * 64 {
+            return Err(format!("CPU {} is out of range", cpu));
+        }
+        mask[cpu / 64] |= 1 << (cpu % 64);
+    }
+    // SAFETY: `mask` is a valid cpu_set_t of the size passed; pid 0 is the
+    // calling thread.
+    if unsafe { sched_setaffinity(0, std::mem::size_of_val(&mask), mask.as_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_affinity(_: &[usize]) -> Result<(), String> {
+    Err("not supported on this platform".to_string())
+}
+
+/// Parse a CPU list as `taskset -c` takes it: `3`, `0,2,4`, `2-5`, `0-3,8`.
+/// The result is sorted and without duplicates; `None` if any part is
+/// malformed.
+pub fn parse_cpu_list(s: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+    for part in s.split(',').map(str::trim) {
+        match part.split_once('-') {
+            Some((lo, hi)) => {
+                let (lo, hi): (usize, usize) = (lo.trim().parse().ok()?, hi.trim().parse().ok()?);
+                if lo > hi {
+                    return None;
+                }
+                cpus.extend(lo..=hi);
+            }
+            None => cpus.push(part.parse().ok()?),
+        }
+    }
+    cpus.sort_unstable();
+    cpus.dedup();
+    Some(cpus)
+}
+
+/// Inverse of [`parse_cpu_list`], with runs collapsed into ranges.
+pub fn format_cpu_list(cpus: &[usize]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < cpus.len() {
+        let mut j = i;
+        while j + 1 < cpus.len() && cpus[j + 1] == cpus[j] + 1 {
+            j += 1;
+        }
+        parts.push(if j > i {
+            format!("{}-{}", cpus[i], cpus[j])
+        } else {
+            cpus[i].to_string()
+        });
+        i = j + 1;
+    }
+    parts.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_list_round_trip() {
+        assert_eq!(parse_cpu_list("3"), Some(vec![3]));
+        assert_eq!(parse_cpu_list("0-3, 8,2"), Some(vec![0, 1, 2, 3, 8]));
+        assert_eq!(parse_cpu_list("4-2"), None);
+        assert_eq!(parse_cpu_list("1,x"), None);
+        assert_eq!(parse_cpu_list(""), None);
+        assert_eq!(format_cpu_list(&[0, 1, 2, 3, 8, 10, 11]), "0-3,8,10-11");
+        assert_eq!(format_cpu_list(&[5]), "5");
+    }
+}
@@ -0,0 +1,103 @@
+//! Counting global allocator for allocation-per-operation metrics.
+//!
+//! Enabled with the `alloc-count` feature. When enabled, every bench binary
+//! that includes the harness routes allocations through `CountingAllocator`,
+//! a thin wrapper over the system allocator that increments two relaxed
+//! atomics per allocation. Without the feature nothing is installed and
+//! `snapshot()` returns `None`, so call sites need no cfg of their own.
+//!
+//! Counts are process-wide: allocations made by Strata's background threads
+//! during a measured window are attributed to the window.
+
+#[cfg(feature = "alloc-count")]
+use std::alloc::{GlobalAlloc, Layout, System};
+#[cfg(feature = "alloc-count")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Cumulative allocation counters at a point in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocSnapshot {
+    /// Number of `alloc`/`alloc_zeroed`/`realloc` calls.
+    pub allocs: u64,
+    /// Total bytes requested by those calls.
+    pub bytes: u64,
+}
+
+impl AllocSnapshot {
+    /// Allocations between `self` (earlier) and `later`.
+    pub fn delta(&self, later: &AllocSnapshot) -> AllocSnapshot {
+        AllocSnapshot {
+            allocs: later.allocs.saturating_sub(self.allocs),
+            bytes: later.bytes.saturating_sub(self.bytes),
+        }
+    }
+}
+
+#[cfg(feature = "alloc-count")]
+static ALLOCS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "alloc-count")]
+static ALLOC_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// System allocator wrapper that counts allocations and bytes.
+#[cfg(feature = "alloc-count")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "alloc-count")]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        ALLOC_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        ALLOC_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        ALLOC_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[cfg(feature = "alloc-count")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Current cumulative counters, or `None` when the feature is disabled.
+pub fn snapshot() -> Option<AllocSnapshot> {
+    #[cfg(feature = "alloc-count")]
+    {
+        Some(AllocSnapshot {
+            allocs: ALLOCS.load(Ordering::Relaxed),
+            bytes: ALLOC_BYTES.load(Ordering::Relaxed),
+        })
+    }
+    #[cfg(not(feature = "alloc-count"))]
+    {
+        None
+    }
+}
+
+#[cfg(all(test, feature = "alloc-count"))]
+mod tests {
+    use super::snapshot;
+
+    #[test]
+    fn test_counts_heap_allocation() {
+        let before = snapshot().unwrap();
+        let v: Vec<u8> = Vec::with_capacity(4096);
+        std::hint::black_box(&v);
+        let after = snapshot().unwrap();
+        let d = before.delta(&after);
+        assert!(d.allocs >= 1);
+        assert!(d.bytes >= 4096);
+    }
+}
@@ -0,0 +1,553 @@
+//! Command-line parsing shared by the bench binaries.
+//!
+//! Every bench takes the same common flags:
+//!
+//! - `--csv`: machine-readable output on stdout
+//! - `-q`: less progress output
+//! - `--durability cache|standard|always`: run one mode (`all`, or leaving
+//!   it out, means the bench's default). A label like `standard-10ms-100`
+//!   (see [`DurabilityConfig::label`]) is taken too
+//! - `--seed N`: seed for benches with a seeded workload (decimal or `0x` hex)
+//! - `--threads N|1,2,4|cores`: thread counts, for benches that sweep them
+//! - `--out-dir DIR`: where result files go, instead of `results/`
+//! - `--db-path DIR`: where databases are created, instead of the system
+//!   temp directory (see [`db_root`])
+//! - `--strata-config KEY=VALUE,...`: standard mode's group commit, as
+//!   `sync_interval_ms` and `sync_batch_size` (see [`strata_config`])
+//! - `--cpus LIST`: run on these cores only, one worker per core (see
+//!   [`super::affinity`])
+//! - `--nice N`: scheduling priority; negative raises it
+//! - `--duration SPAN`: run for a fixed wall time instead of a fixed
+//!   operation count, for benches that support it (`30`, `0.5`, `90s`,
+//!   `1h30m`)
+//! - `--profile`: sample the CPU during measured phases and write a
+//!   flamegraph next to the results (needs the `profile` feature; see
+//!   [`super::profile`])
+//!
+//! [`Args::parse`] takes those out and hands the bench only its own flags:
+//!
+//! ```ignore
+//! let mut args = cli::Args::parse();
+//! while let Some(flag) = args.next_flag() {
+//!     match flag.as_str() {
+//!         "--levels" => config.levels = args.list(),
+//!         "--runs" => config.runs = args.parse_value::<usize>().max(1),
+//!         _ => args.unknown_flag(),
+//!     }
+//! }
+//! config.durability = args.durability_or(DurabilityConfig::Cache);
+//! ```
+//!
+//! Both `--flag value` and `--flag=value` are accepted. A flag the bench
+//! doesn't know, a missing value or one that doesn't parse is a usage error,
+//! so a typo can't quietly run the defaults. The `--bench` that `cargo bench`
+//! passes is dropped.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::affinity;
+use super::scaling::{parse_thread_counts, physical_cores};
+use super::soak;
+use super::{DurabilityConfig, SyncSettings, SYNC_BATCH_KEY, SYNC_INTERVAL_KEY};
+
+/// Where result files go when `--out-dir` isn't given.
+const DEFAULT_RESULTS_DIR: &str = "results";
+
+static OUT_DIR: OnceLock<PathBuf> = OnceLock::new();
+static DB_PATH: OnceLock<PathBuf> = OnceLock::new();
+static STRATA_CONFIG: OnceLock<SyncSettings> = OnceLock::new();
+static PROFILE: OnceLock<bool> = OnceLock::new();
+
+/// Directory for result files: `--out-dir` if it was given, else `results/`.
+pub fn results_dir() -> PathBuf {
+    OUT_DIR
+        .get()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_RESULTS_DIR))
+}
+
+/// Directory databases are created in: `--db-path` if it was given, else the
+/// system temp directory. Each database gets its own subdirectory, deleted
+/// when the database is dropped, so `--db-path` can point at the filesystem
+/// or device under test.
+pub fn db_root() -> PathBuf {
+    DB_PATH.get().cloned().unwrap_or_else(std::env::temp_dir)
+}
+
+/// `--strata-config` settings. [`Args::durability_or`] and
+/// [`Args::durability_modes`] turn standard mode into
+/// [`DurabilityConfig::StandardSync`] with them, so the group commit shows
+/// up in every result's durability label.
+pub fn strata_config() -> SyncSettings {
+    STRATA_CONFIG.get().copied().unwrap_or_default()
+}
+
+/// [`strata_config`] as `key=value,key=value`, the form `--strata-config`
+/// takes, e.g. to pass on to a child process. `None` without settings.
+pub fn strata_config_spec() -> Option<String> {
+    strata_config().spec()
+}
+
+/// Whether `--profile` was given in a build with the `profile` feature.
+pub fn profiling() -> bool {
+    PROFILE.get().copied().unwrap_or(false)
+}
+
+/// This process's arguments, with the common flags already parsed.
+pub struct Args {
+    rest: std::iter::Peekable<std::vec::IntoIter<String>>,
+    /// The flag [`Args::next_flag`] returned last, for error messages.
+    flag: String,
+    pub csv: bool,
+    pub quiet: bool,
+    /// `None` when not given, or `all`.
+    pub durability: Option<DurabilityConfig>,
+    pub seed: Option<u64>,
+    pub threads: Option<Vec<usize>>,
+    pub out_dir: Option<PathBuf>,
+    pub db_path: Option<PathBuf>,
+    pub strata_config: SyncSettings,
+    pub cpus: Option<Vec<usize>>,
+    pub nice: Option<i32>,
+    pub duration: Option<Duration>,
+    pub profile: bool,
+}
+
+impl Args {
+    /// Parse this process's arguments. `--out-dir` takes effect for
+    /// [`results_dir`], `--db-path` for [`db_root`], `--strata-config`
+    /// for [`strata_config`] and `--profile` for [`profiling`] from here on, and
+    /// `--cpus` and `--nice` for this thread and every thread spawned after
+    /// it. A `--db-path` that holds anything is refused.
+    pub fn parse() -> Self {
+        let args = Self::from_vec(std::env::args().skip(1).collect());
+        if let Some(dir) = &args.out_dir {
+            let _ = OUT_DIR.set(dir.clone());
+        }
+        if let Some(dir) = &args.db_path {
+            if let Err(e) = prepare_db_path(dir) {
+                usage_error(&format!("--db-path {}: {}", dir.display(), e));
+            }
+            let _ = DB_PATH.set(dir.clone());
+        }
+        if !args.strata_config.is_empty() {
+            let _ = STRATA_CONFIG.set(args.strata_config);
+        }
+        if args.profile {
+            if cfg!(feature = "profile") {
+                let _ = PROFILE.set(true);
+            } else {
+                eprintln!("Warning: --profile ignored: build with `--features profile`");
+            }
+        }
+        if let Some(cpus) = &args.cpus {
+            if let Err(e) = affinity::pin_process(cpus) {
+                eprintln!("Warning: --cpus {} not applied: {}", affinity::format_cpu_list(cpus), e);
+            }
+        }
+        if let Some(nice) = args.nice {
+            if let Err(e) = affinity::set_nice(nice) {
+                eprintln!("Warning: --nice {} not applied: {}", nice, e);
+            }
+        }
+        args
+    }
+
+    fn from_vec(args: Vec<String>) -> Self {
+        let mut parsed = Args {
+            rest: Vec::new().into_iter().peekable(),
+            flag: String::new(),
+            csv: false,
+            quiet: false,
+            durability: None,
+            seed: None,
+            threads: None,
+            out_dir: None,
+            db_path: None,
+            strata_config: SyncSettings::default(),
+            cpus: None,
+            nice: None,
+            duration: None,
+            profile: false,
+        };
+        let mut rest = Vec::new();
+        let mut args = args.into_iter().flat_map(|a| match a.split_once('=') {
+            Some((flag, value)) if a.starts_with("--") => vec![flag.to_string(), value.to_string()],
+            _ => vec![a],
+        });
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--csv" => parsed.csv = true,
+                "-q" => parsed.quiet = true,
+                "--durability" => {
+                    if let Some(m) = args.next() {
+                        parsed.durability = parse_durability(&m).unwrap_or_else(|| {
+                            usage_error(&format!("--durability expects cache, standard, always, standard-<ms>ms-<writes> or all; got {}", m))
+                        });
+                    }
+                }
+                "--seed" => {
+                    if let Some(s) = args.next() {
+                        parsed.seed = Some(parse_seed(&s).unwrap_or_else(|| {
+                            usage_error(&format!("--seed expects a number; got {}", s))
+                        }));
+                    }
+                }
+                "--threads" => {
+                    if let Some(s) = args.next() {
+                        parsed.threads = Some(parse_threads(&s).unwrap_or_else(|| {
+                            usage_error(&format!("--threads expects N, a list like 1,2,4, or `cores`; got {}", s))
+                        }));
+                    }
+                }
+                "--out-dir" => parsed.out_dir = args.next().map(PathBuf::from),
+                "--db-path" => parsed.db_path = args.next().map(PathBuf::from),
+                "--strata-config" => {
+                    if let Some(s) = args.next() {
+                        if let Err(e) = parse_settings(&s, &mut parsed.strata_config) {
+                            usage_error(&format!("--strata-config {}: {}", s, e));
+                        }
+                    }
+                }
+                "--cpus" => {
+                    if let Some(s) = args.next() {
+                        parsed.cpus = Some(affinity::parse_cpu_list(&s).unwrap_or_else(|| {
+                            usage_error(&format!("--cpus expects a CPU list like 2-5 or 0,2,4; got {}", s))
+                        }));
+                    }
+                }
+                "--nice" => {
+                    if let Some(s) = args.next() {
+                        parsed.nice = Some(s.trim().parse().unwrap_or_else(|_| {
+                            usage_error(&format!("--nice expects a number from -20 to 19; got {}", s))
+                        }));
+                    }
+                }
+                "--duration" => {
+                    if let Some(s) = args.next() {
+                        parsed.duration = Some(parse_duration(&s).unwrap_or_else(|| {
+                            usage_error(&format!("--duration expects seconds or a span like 90s, 30m or 2h; got {}", s))
+                        }));
+                    }
+                }
+                "--profile" => parsed.profile = true,
+                "--bench" => {}
+                _ => rest.push(arg),
+            }
+        }
+        parsed.rest = rest.into_iter().peekable();
+        parsed
+    }
+
+    /// The next argument the common flags didn't take, or `None` at the end.
+    pub fn next_flag(&mut self) -> Option<String> {
+        let flag = self.rest.next()?;
+        self.flag.clone_from(&flag);
+        Some(flag)
+    }
+
+    /// The value following the current flag. A missing value, or another
+    /// flag in its place, is a usage error.
+    pub fn value(&mut self) -> String {
+        self.rest
+            .next_if(|v| !v.starts_with("--"))
+            .unwrap_or_else(|| usage_error(&format!("{} expects a value", self.flag)))
+    }
+
+    /// The value following the current flag if `accept` takes it, for flags
+    /// whose value is optional. Otherwise it's left for [`Args::next_flag`].
+    pub fn value_if(&mut self, accept: impl FnOnce(&str) -> bool) -> Option<String> {
+        self.rest.next_if(|v| accept(v))
+    }
+
+    /// The value following the current flag, parsed. A value that doesn't
+    /// parse is a usage error.
+    pub fn parse_value<T: FromStr>(&mut self) -> T {
+        let v = self.value();
+        v.trim()
+            .parse()
+            .unwrap_or_else(|_| usage_error(&format!("{}: invalid value {}", self.flag, v)))
+    }
+
+    /// Like [`Args::parse_value`], but a value `accept` refuses is a usage
+    /// error too, saying the flag `expects` something else.
+    pub fn parse_checked<T: FromStr>(&mut self, expects: &str, accept: impl FnOnce(&T) -> bool) -> T {
+        let v = self.value();
+        v.trim()
+            .parse()
+            .ok()
+            .filter(accept)
+            .unwrap_or_else(|| usage_error(&format!("{} expects {}; got {}", self.flag, expects, v)))
+    }
+
+    /// The value following the current flag as a comma-separated list.
+    /// Empty entries are skipped; one that doesn't parse, or no entries at
+    /// all, is a usage error.
+    pub fn list<T: FromStr>(&mut self) -> Vec<T> {
+        self.list_checked("a comma-separated list", |_| true)
+    }
+
+    /// Like [`Args::list`], but an entry `accept` refuses is a usage error
+    /// too, saying the flag `expects` something else.
+    pub fn list_checked<T: FromStr>(&mut self, expects: &str, accept: impl Fn(&T) -> bool) -> Vec<T> {
+        let v = self.value();
+        let list: Option<Vec<T>> = v
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().ok().filter(&accept))
+            .collect();
+        match list {
+            Some(list) if !list.is_empty() => list,
+            _ => usage_error(&format!("{} expects {}; got {}", self.flag, expects, v)),
+        }
+    }
+
+    /// Exit with a usage error for the current flag, which the bench
+    /// doesn't take.
+    pub fn unknown_flag(&self) -> ! {
+        usage_error(&format!("Unknown argument {}", self.flag))
+    }
+
+    /// The `--durability` mode, or `default`, with the `--strata-config`
+    /// settings applied.
+    pub fn durability_or(&self, default: DurabilityConfig) -> DurabilityConfig {
+        self.strata_config.apply(self.durability.unwrap_or(default))
+    }
+
+    /// The `--durability` mode on its own, or every mode in `default`, with
+    /// the `--strata-config` settings applied.
+    pub fn durability_modes(&self, default: &[DurabilityConfig]) -> Vec<DurabilityConfig> {
+        let modes = match self.durability {
+            Some(d) => vec![d],
+            None => default.to_vec(),
+        };
+        modes.into_iter().map(|d| self.strata_config.apply(d)).collect()
+    }
+
+    /// The `--threads` counts, or `default`.
+    pub fn threads_or(&self, default: Vec<usize>) -> Vec<usize> {
+        self.threads.clone().unwrap_or(default)
+    }
+
+    /// The largest `--threads` count, or `default`, for benches that run at
+    /// one thread count.
+    pub fn thread_count_or(&self, default: usize) -> usize {
+        self.threads
+            .as_ref()
+            .and_then(|t| t.iter().copied().max())
+            .unwrap_or(default)
+    }
+
+    /// The `--seed` value, or `default`.
+    pub fn seed_or(&self, default: u64) -> u64 {
+        self.seed.unwrap_or(default)
+    }
+
+    /// The `--duration` value, or `default`.
+    pub fn duration_or(&self, default: Duration) -> Duration {
+        self.duration.unwrap_or(default)
+    }
+}
+
+/// Create `dir` if it doesn't exist, and refuse it if it holds anything.
+/// Benches create and delete databases under it, so a mistyped path must
+/// not point them at a directory with someone's data in it.
+fn prepare_db_path(dir: &Path) -> Result<(), String> {
+    match std::fs::read_dir(dir) {
+        Ok(mut entries) => match entries.next() {
+            None => Ok(()),
+            Some(_) => Err("directory is not empty; pass an empty or new directory".to_string()),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            std::fs::create_dir_all(dir).map_err(|e| format!("cannot create: {}", e))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// `key=value,key=value` into `settings`. Only the group-commit keys are
+/// taken: anything else isn't a durability setting Strata has, and the mode
+/// itself comes from `--durability`.
+fn parse_settings(s: &str, settings: &mut SyncSettings) -> Result<(), String> {
+    for part in s.split(',') {
+        let (key, value) = part
+            .split_once('=')
+            .ok_or_else(|| format!("expected key=value, got `{}`", part))?;
+        let (key, value) = (key.trim(), value.trim());
+        let count = || {
+            value
+                .parse::<u64>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!("{} expects a positive integer, got `{}`", key, value))
+        };
+        match key {
+            SYNC_INTERVAL_KEY => settings.interval_ms = Some(count()?),
+            SYNC_BATCH_KEY => settings.batch_size = Some(count()?),
+            "durability" => return Err("set the mode with --durability".to_string()),
+            _ => {
+                return Err(format!(
+                    "unknown setting `{}`; expected {} or {}",
+                    key, SYNC_INTERVAL_KEY, SYNC_BATCH_KEY
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A mode label (see [`DurabilityConfig::parse`]), or `all` for `None`.
+fn parse_durability(s: &str) -> Option<Option<DurabilityConfig>> {
+    match s {
+        "all" => Some(None),
+        label => DurabilityConfig::parse(label).map(Some),
+    }
+}
+
+fn parse_seed(s: &str) -> Option<u64> {
+    let s = s.trim().replace('_', "");
+    match s.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+/// Seconds, fractions allowed, or a span as [`soak::parse_duration`] takes
+/// it. Must be positive.
+fn parse_duration(s: &str) -> Option<Duration> {
+    match s.trim().parse::<f64>() {
+        Ok(secs) => Some(secs)
+            .filter(|s| s.is_finite() && *s > 0.0)
+            .map(Duration::from_secs_f64),
+        Err(_) => soak::parse_duration(s),
+    }
+}
+
+/// `cores` is the physical core count on its own.
+fn parse_threads(s: &str) -> Option<Vec<usize>> {
+    let counts = match s.trim() {
+        "cores" => vec![physical_cores()],
+        list => parse_thread_counts(list),
+    };
+    Some(counts).filter(|c| !c.is_empty())
+}
+
+/// Print `msg` and exit with status 1, for a flag the bench can't run with.
+pub fn usage_error(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Args {
+        Args::from_vec(s.split_whitespace().map(String::from).collect())
+    }
+
+    #[test]
+    fn test_common_flags_are_taken_out() {
+        let mut a = args("--levels=1,2 --csv --durability always -q --bench --out-dir /tmp/r --seed=0x2a --cpus 2-3,6 --nice=-5 --profile");
+        assert!(a.csv && a.quiet && a.profile);
+        assert_eq!(a.cpus, Some(vec![2, 3, 6]));
+        assert_eq!(a.nice, Some(-5));
+        assert!(matches!(a.durability, Some(DurabilityConfig::Always)));
+        assert_eq!(a.seed, Some(42));
+        assert_eq!(a.out_dir, Some(PathBuf::from("/tmp/r")));
+        assert_eq!(a.next_flag().as_deref(), Some("--levels"));
+        assert_eq!(a.list::<usize>(), vec![1, 2]);
+        assert_eq!(a.next_flag(), None);
+    }
+
+    #[test]
+    fn test_durability_defaults() {
+        let a = args("--durability all");
+        assert!(a.durability.is_none());
+        assert_eq!(a.durability_modes(&DurabilityConfig::ALL).len(), 3);
+        assert!(matches!(a.durability_or(DurabilityConfig::Standard), DurabilityConfig::Standard));
+        let a = args("--durability cache");
+        assert!(matches!(a.durability_modes(&DurabilityConfig::ALL)[..], [DurabilityConfig::Cache]));
+        assert_eq!(parse_durability("alwyas"), None);
+    }
+
+    #[test]
+    fn test_values() {
+        let mut a = args("--runs 5 --sizes 1,,3, --damping 0.5 --dataset=a=b --fast --threads 1,2,8");
+        assert_eq!(a.threads, Some(vec![1, 2, 8]));
+        a.next_flag();
+        assert_eq!(a.parse_value::<usize>(), 5);
+        a.next_flag();
+        assert_eq!(a.list_checked::<u64>("sizes above 0", |&n| n > 0), vec![1, 3]);
+        a.next_flag();
+        assert_eq!(a.parse_checked("a factor in [0, 1)", |d: &f64| (0.0..1.0).contains(d)), 0.5);
+        assert_eq!(a.next_flag().as_deref(), Some("--dataset"));
+        assert_eq!(a.value(), "a=b");
+        assert_eq!(a.value_if(|v| v.parse::<u32>().is_ok()), None);
+        assert_eq!(a.next_flag().as_deref(), Some("--fast"));
+        assert_eq!(parse_threads("cores"), Some(vec![physical_cores()]));
+        assert_eq!(parse_threads("0"), None);
+    }
+
+    #[test]
+    fn test_duration() {
+        let a = args("--duration 0.5");
+        assert_eq!(a.duration, Some(Duration::from_millis(500)));
+        let a = args("--duration=1h30m");
+        assert_eq!(a.duration_or(Duration::ZERO), Duration::from_secs(5400));
+        assert_eq!(args("").duration_or(Duration::from_secs(5)), Duration::from_secs(5));
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    #[test]
+    fn test_strata_config() {
+        let a = args("--strata-config sync_interval_ms=10 --strata-config=sync_batch_size=1");
+        let tuned = DurabilityConfig::StandardSync {
+            interval_ms: 10,
+            batch_size: 1,
+        };
+        assert_eq!(a.durability_modes(&DurabilityConfig::ALL), [DurabilityConfig::Cache, tuned, DurabilityConfig::Always]);
+        assert_eq!(a.durability_or(DurabilityConfig::Standard).label(), "standard-10ms-1");
+        assert_eq!(a.strata_config.spec().as_deref(), Some("sync_interval_ms=10,sync_batch_size=1"));
+
+        let a = args("--strata-config sync_batch_size=8");
+        assert_eq!(a.durability_or(DurabilityConfig::Standard).label(), "standard-100ms-8");
+        assert_eq!(args("").durability_or(DurabilityConfig::Standard), DurabilityConfig::Standard);
+
+        let mut settings = SyncSettings::default();
+        assert!(parse_settings("durability=always", &mut settings).is_err());
+        assert!(parse_settings("cache_mb=64", &mut settings).is_err());
+        assert!(parse_settings("sync_interval_ms=0", &mut settings).is_err());
+        assert!(parse_settings("sync_batch_size=x", &mut settings).is_err());
+        assert!(parse_settings("sync_batch_size", &mut settings).is_err());
+    }
+
+    #[test]
+    fn test_durability_labels_round_trip() {
+        let tuned = DurabilityConfig::StandardSync {
+            interval_ms: 5,
+            batch_size: 64,
+        };
+        for mode in DurabilityConfig::ALL.into_iter().chain([tuned]) {
+            assert_eq!(DurabilityConfig::parse(&mode.label()), Some(mode));
+        }
+        assert_eq!(DurabilityConfig::parse("standard-0ms-1"), None);
+        assert_eq!(DurabilityConfig::parse("standard-5ms"), None);
+    }
+
+    #[test]
+    fn test_db_path_must_be_empty() {
+        let root = tempfile::tempdir().unwrap();
+        let fresh = root.path().join("a/b");
+        assert!(prepare_db_path(&fresh).is_ok());
+        assert!(fresh.is_dir());
+        assert!(prepare_db_path(&fresh).is_ok());
+        std::fs::write(fresh.join("data"), b"x").unwrap();
+        assert!(prepare_db_path(&fresh).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+//! Clock abstraction for scheduling logic.
+//!
+//! Pacing and time windows read time and sleep through [`Clock`], so the
+//! same code runs on the wall clock in benchmarks and on [`MockClock`] in
+//! unit tests. A mock sleep advances virtual time instantly, so a test of a
+//! ten-second paced window finishes in microseconds and gets the same
+//! answer every run. Warm-up, soak windows, `--duration` loops and the
+//! resource sampler's timestamps all go through it.
+//!
+//! Latency measurement stays on `Instant`: the clock is for deciding *when*
+//! to issue work, not for timing it.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A source of elapsed time that can also wait.
+pub trait Clock: Sync {
+    /// Time since the clock started.
+    fn elapsed(&self) -> Duration;
+
+    /// Block for `d`.
+    fn sleep(&self, d: Duration);
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn elapsed(&self) -> Duration {
+        (**self).elapsed()
+    }
+
+    fn sleep(&self, d: Duration) {
+        (**self).sleep(d)
+    }
+}
+
+/// Shared with a background thread, e.g. the resource sampler's.
+impl<C: Clock + Send + ?Sized> Clock for Arc<C> {
+    fn elapsed(&self) -> Duration {
+        (**self).elapsed()
+    }
+
+    fn sleep(&self, d: Duration) {
+        (**self).sleep(d)
+    }
+}
+
+/// The wall clock, measured from when it was started.
+pub struct RealClock {
+    start: Instant,
+}
+
+impl RealClock {
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for RealClock {
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&self, d: Duration) {
+        std::thread::sleep(d);
+    }
+}
+
+/// Virtual time for tests. Starts at zero and moves only when something
+/// sleeps on it or calls [`MockClock::advance`].
+#[derive(Default)]
+pub struct MockClock {
+    nanos: AtomicU64,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move virtual time forward by `d`, e.g. to stand in for an operation's cost.
+    pub fn advance(&self, d: Duration) {
+        self.nanos.fetch_add(d.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+
+    fn sleep(&self, d: Duration) {
+        self.advance(d);
+    }
+}
+
+// =============================================================================
+// Pacing
+// =============================================================================
+
+/// Open-loop schedule at a fixed rate: operation n is due `n / rate` seconds
+/// after the schedule starts. An operation that runs late doesn't push the
+/// schedule back, so the next ones start immediately until it catches up.
+/// `None` means no pacing. The rate needn't be whole, e.g. one worker's
+/// share of a total rate.
+pub struct Pacer {
+    rate: Option<f64>,
+    start: Duration,
+    issued: u64,
+}
+
+impl Pacer {
+    /// A schedule starting at the clock's zero.
+    pub fn new(rate: Option<f64>) -> Self {
+        Self::starting_at(rate, Duration::ZERO)
+    }
+
+    /// A schedule whose first operation is due at `start` on the clock.
+    pub fn starting_at(rate: Option<f64>, start: Duration) -> Self {
+        assert!(rate.is_none_or(|r| r > 0.0), "a pacer needs a positive rate or None");
+        Self {
+            rate,
+            start,
+            issued: 0,
+        }
+    }
+
+    /// When the next operation is due on the clock.
+    pub fn next_due(&self) -> Duration {
+        match self.rate {
+            Some(rate) => {
+                self.start + Duration::from_nanos((self.issued as f64 * 1e9 / rate) as u64)
+            }
+            None => self.start,
+        }
+    }
+
+    /// Sleep until the next operation is due and count it as issued.
+    /// Returns how late it starts: zero unless it's paced and an earlier
+    /// operation ran past its slot.
+    pub fn wait(&mut self, clock: &impl Clock) -> Duration {
+        let due = self.next_due();
+        let now = clock.elapsed();
+        self.issued += 1;
+        if self.rate.is_none() {
+            return Duration::ZERO;
+        }
+        match due.checked_sub(now) {
+            Some(wait) if !wait.is_zero() => {
+                clock.sleep(wait);
+                Duration::ZERO
+            }
+            _ => now - due,
+        }
+    }
+}
+
+/// Run `op(0)`, `op(1)`, … up to `count` operations, paced to `rate` per
+/// second, stopping at the first one that would start at or after `window`.
+/// `Some(0)` issues nothing and just waits out the window. Returns the number
+/// of operations run.
+pub fn run_paced(
+    clock: &impl Clock,
+    rate: Option<u64>,
+    window: Duration,
+    count: usize,
+    mut op: impl FnMut(usize),
+) -> usize {
+    if rate == Some(0) {
+        clock.sleep(window);
+        return 0;
+    }
+
+    let mut pacer = Pacer::new(rate.map(|r| r as f64));
+    for n in 0..count {
+        pacer.wait(clock);
+        if clock.elapsed() >= window {
+            return n;
+        }
+        op(n);
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn test_mock_clock_moves_only_when_told() {
+        let clock = MockClock::new();
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+        clock.sleep(5 * MS);
+        clock.advance(MS);
+        assert_eq!(clock.elapsed(), 6 * MS);
+    }
+
+    #[test]
+    fn test_pacer_schedule_is_exact() {
+        let mut pacer = Pacer::new(Some(3.0));
+        let clock = MockClock::new();
+        let mut starts = Vec::new();
+        for _ in 0..4 {
+            pacer.wait(&clock);
+            starts.push(clock.elapsed());
+        }
+        assert_eq!(
+            starts,
+            [0, 333_333_333, 666_666_666, 1_000_000_000].map(Duration::from_nanos)
+        );
+    }
+
+    #[test]
+    fn test_pacer_reports_lateness() {
+        let clock = MockClock::new();
+        let mut pacer = Pacer::starting_at(Some(1_000.0), 10 * MS);
+        assert_eq!(pacer.wait(&clock), Duration::ZERO);
+        assert_eq!(clock.elapsed(), 10 * MS);
+        clock.advance(5 * MS);
+        // Due at 11 ms, starting at 15 ms
+        assert_eq!(pacer.wait(&clock), 4 * MS);
+        assert_eq!(Pacer::new(None).wait(&clock), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_run_paced_fills_window_at_rate() {
+        let clock = MockClock::new();
+        let n = run_paced(&clock, Some(100), Duration::from_secs(10), usize::MAX, |_| {});
+        assert_eq!(n, 1_000);
+        assert_eq!(clock.elapsed(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_run_paced_slow_ops_fall_behind_without_waiting() {
+        // 20 ms per op can't sustain 100/s: ops run back to back and 50 fit in 1 s
+        let clock = MockClock::new();
+        let n = run_paced(&clock, Some(100), Duration::from_secs(1), usize::MAX, |_| {
+            clock.advance(20 * MS)
+        });
+        assert_eq!(n, 50);
+    }
+
+    #[test]
+    fn test_run_paced_catches_up_after_a_stall() {
+        // Op 0 stalls for 50 ms; ops 1-5 were due by then and start immediately
+        let clock = MockClock::new();
+        let mut starts = Vec::new();
+        run_paced(&clock, Some(100), Duration::from_secs(1), 8, |i| {
+            starts.push(clock.elapsed());
+            if i == 0 {
+                clock.advance(50 * MS);
+            }
+        });
+        let expected: Vec<Duration> = [0, 50, 50, 50, 50, 50, 60, 70].iter().map(|&ms| ms * MS).collect();
+        assert_eq!(starts, expected);
+    }
+
+    #[test]
+    fn test_run_paced_unpaced_and_idle() {
+        let clock = MockClock::new();
+        assert_eq!(run_paced(&clock, None, Duration::from_secs(1), 500, |_| {}), 500);
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+
+        let mut ran = false;
+        assert_eq!(run_paced(&clock, Some(0), Duration::from_secs(2), 500, |_| ran = true), 0);
+        assert!(!ran);
+        assert_eq!(clock.elapsed(), Duration::from_secs(2));
+    }
+}
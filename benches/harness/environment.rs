@@ -0,0 +1,201 @@
+//! OS settings that change results on identical hardware.
+//!
+//! Transparent huge pages, swap, a cgroup memory limit, CPU affinity and
+//! priority, and the mount options of the filesystem the databases live on
+//! are the first things to check when numbers don't reproduce. They're read from `/sys` and `/proc`
+//! on Linux; elsewhere, or when a file can't be read, the field is left
+//! empty.
+
+use std::path::{Path, PathBuf};
+use strata_benchmarks::schema::EnvironmentInfo;
+
+/// Capture the environment for databases created under `db_dir`.
+pub fn capture_environment(db_dir: &Path) -> EnvironmentInfo {
+    let mut env = EnvironmentInfo {
+        db_dir: Some(db_dir.display().to_string()),
+        ..Default::default()
+    };
+
+    #[cfg(target_os = "linux")]
+    {
+        let read = |path: &str| std::fs::read_to_string(path).ok();
+        env.thp_enabled = read("/sys/kernel/mm/transparent_hugepage/enabled")
+            .and_then(|s| selected_mode(&s));
+        env.thp_defrag = read("/sys/kernel/mm/transparent_hugepage/defrag")
+            .and_then(|s| selected_mode(&s));
+        env.swappiness = read("/proc/sys/vm/swappiness").and_then(|s| s.trim().parse().ok());
+        env.swap_total_mb = read("/proc/meminfo").and_then(|s| swap_total_mb(&s));
+        let dir = std::fs::canonicalize(db_dir).unwrap_or_else(|_| db_dir.to_path_buf());
+        if let Some(m) = read("/proc/mounts").and_then(|s| mount_for(&s, &dir)) {
+            env.db_mount_point = Some(m.mount_point.display().to_string());
+            env.db_fs_type = Some(m.fs_type);
+            env.db_mount_options = Some(m.options);
+        }
+        env.memory_limit_mb = super::memlimit::cgroup_memory_max().map(|b| b >> 20);
+        env.cpu_affinity = read("/proc/self/status").and_then(|s| cpus_allowed(&s));
+        env.nice = read("/proc/self/stat").and_then(|s| nice_from_stat(&s));
+    }
+    env.pinned_cpus = super::affinity::pinned_cpus();
+    env.strata_config = super::cli::strata_config_spec();
+
+    env
+}
+
+/// One-line summary for the hardware banner.
+pub fn describe(env: &EnvironmentInfo) -> String {
+    let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "?".to_string());
+    let limit = env
+        .memory_limit_mb
+        .map_or_else(|| "none".to_string(), |mb| format!("{} MB", mb));
+    let pinned = if env.pinned_cpus.is_some() { " (workers pinned)" } else { "" };
+    let strata = env
+        .strata_config
+        .as_ref()
+        .map_or_else(String::new, |c| format!(", group commit {}", c));
+    format!(
+        "THP {} (defrag {}), swappiness {}, swap {} MB, memory limit {}, CPUs {}{}, nice {}, {} on {} ({}){}",
+        show(&env.thp_enabled),
+        show(&env.thp_defrag),
+        show(&env.swappiness.map(|v| v.to_string())),
+        show(&env.swap_total_mb.map(|v| v.to_string())),
+        limit,
+        show(&env.cpu_affinity),
+        pinned,
+        show(&env.nice.map(|v| v.to_string())),
+        show(&env.db_dir),
+        show(&env.db_fs_type),
+        show(&env.db_mount_options),
+        strata,
+    )
+}
+
+/// The bracketed entry of a sysfs mode file, e.g. `always [madvise] never`.
+fn selected_mode(contents: &str) -> Option<String> {
+    contents
+        .split_whitespace()
+        .find_map(|w| w.strip_prefix('[')?.strip_suffix(']'))
+        .map(str::to_string)
+}
+
+fn swap_total_mb(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("SwapTotal:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb / 1024)
+}
+
+/// `Cpus_allowed_list` from `/proc/<pid>/status`, e.g. `0-7`.
+fn cpus_allowed(status: &str) -> Option<String> {
+    let line = status.lines().find(|l| l.starts_with("Cpus_allowed_list:"))?;
+    Some(line.split_once(':')?.1.trim().to_string())
+}
+
+/// Field 19 (nice) of `/proc/<pid>/stat`. The command name (field 2) may
+/// contain spaces, so fields are counted from the `)` closing it.
+fn nice_from_stat(stat: &str) -> Option<i32> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(16)?.parse().ok()
+}
+
+struct Mount {
+    mount_point: PathBuf,
+    fs_type: String,
+    options: String,
+}
+
+/// The `/proc/mounts` entry whose mount point is the longest prefix of `dir`.
+/// Later entries win ties, as they shadow earlier mounts on the same point.
+fn mount_for(mounts: &str, dir: &Path) -> Option<Mount> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = PathBuf::from(unescape_octal(fields.next()?));
+            let fs_type = fields.next()?.to_string();
+            let options = fields.next()?.to_string();
+            Some(Mount {
+                mount_point,
+                fs_type,
+                options,
+            })
+        })
+        .filter(|m| dir.starts_with(&m.mount_point))
+        .max_by_key(|m| m.mount_point.components().count())
+}
+
+/// `/proc/mounts` writes space, tab, newline and backslash as `\ooo`.
+fn unescape_octal(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let code = bytes
+            .get(i + 1..i + 4)
+            .filter(|_| bytes[i] == b'\\')
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u8::from_str_radix(d, 8).ok());
+        match code {
+            Some(c) => {
+                out.push(c);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selected_mode() {
+        assert_eq!(selected_mode("always [madvise] never\n").as_deref(), Some("madvise"));
+        assert_eq!(
+            selected_mode("always defer defer+madvise [madvise] never").as_deref(),
+            Some("madvise")
+        );
+        assert_eq!(selected_mode("always madvise never"), None);
+    }
+
+    #[test]
+    fn test_swap_total_mb() {
+        let meminfo = "MemTotal:       16318536 kB\nSwapTotal:       2097148 kB\nSwapFree:        2097148 kB\n";
+        assert_eq!(swap_total_mb(meminfo), Some(2047));
+        assert_eq!(swap_total_mb("MemTotal: 1 kB\n"), None);
+    }
+
+    #[test]
+    fn test_cpus_and_nice() {
+        let status = "Name:\tycsb\nCpus_allowed:\tff\nCpus_allowed_list:\t0-7\nMems_allowed_list:\t0\n";
+        assert_eq!(cpus_allowed(status).as_deref(), Some("0-7"));
+        assert_eq!(cpus_allowed("Name:\tycsb\n"), None);
+        let stat = "4242 (bench (x) y) R 1 4242 4242 0 -1 4194304 120 0 0 0 3 1 0 0 25 5 1 0 812 1000 200";
+        assert_eq!(nice_from_stat(stat), Some(5));
+    }
+
+    #[test]
+    fn test_mount_for_picks_longest_prefix() {
+        let mounts = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+tmpfs /tmp tmpfs rw,nosuid,nodev 0 0
+/dev/sdb1 /mnt/fast\\040disk xfs rw,noatime 0 0
+/dev/sdc1 /tmpdata ext4 rw 0 0
+";
+        let m = mount_for(mounts, Path::new("/tmp/.tmpAbc")).unwrap();
+        assert_eq!(m.mount_point, Path::new("/tmp"));
+        assert_eq!(m.fs_type, "tmpfs");
+        assert_eq!(m.options, "rw,nosuid,nodev");
+
+        let m = mount_for(mounts, Path::new("/mnt/fast disk/db")).unwrap();
+        assert_eq!(m.fs_type, "xfs");
+
+        // A string prefix that isn't a path prefix doesn't match
+        let m = mount_for(mounts, Path::new("/tmpfoo")).unwrap();
+        assert_eq!(m.mount_point, Path::new("/"));
+    }
+}
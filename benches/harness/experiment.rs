@@ -0,0 +1,397 @@
+//! A/B experiment runner: one workload, two database profiles, one process.
+//!
+//! Comparing two separate benchmark runs mixes the effect under test with
+//! machine drift (thermal state, page cache, background load). This module
+//! runs both profiles inside the same process and interleaves repetitions in
+//! ABBA order. The statistics are computed on the per-repetition paired
+//! differences, so drift that affects A and B equally cancels out.
+//!
+//! With a deterministic operation stream, the pairing can go down to single
+//! operations: op i in run A against op i in run B. [`diff_distribution`]
+//! summarizes those per-op differences.
+
+use super::{create_db, BenchDb, DurabilityConfig};
+
+// =============================================================================
+// DbProfile
+// =============================================================================
+
+/// A named database configuration: durability mode plus `config_set` overrides.
+#[derive(Debug, Clone)]
+pub struct DbProfile {
+    pub name: String,
+    pub durability: DurabilityConfig,
+    /// Applied in order via `Strata::config_set` after the database opens.
+    pub settings: Vec<(String, String)>,
+}
+
+impl DbProfile {
+    pub fn new(name: &str, durability: DurabilityConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            durability,
+            settings: Vec::new(),
+        }
+    }
+
+    pub fn with_setting(mut self, key: &str, value: &str) -> Self {
+        self.settings.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Parse a profile from a `key=value,key=value` spec. `durability` selects
+    /// the DurabilityConfig, `mode` when it isn't given; every other key is
+    /// passed to `config_set`.
+    ///
+    /// Example: `durability=standard,auto_embed=false`
+    pub fn parse(name: &str, spec: &str, mode: DurabilityConfig) -> Result<Self, String> {
+        let mut profile = Self::new(name, mode);
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got '{}'", part))?;
+            let (key, value) = (key.trim(), value.trim());
+            if key == "durability" {
+                profile.durability = DurabilityConfig::parse(value)
+                    .ok_or_else(|| format!("unknown durability '{}'", value))?;
+            } else {
+                profile = profile.with_setting(key, value);
+            }
+        }
+        Ok(profile)
+    }
+
+    /// Open a fresh database with this profile applied.
+    pub fn open(&self) -> BenchDb {
+        let bench_db = create_db(self.durability);
+        for (key, value) in &self.settings {
+            bench_db
+                .db
+                .config_set(key, value)
+                .unwrap_or_else(|e| panic!("config_set({}, {}) failed: {:?}", key, value, e));
+        }
+        bench_db
+    }
+
+    /// One-line description, e.g. `standard, auto_embed=false`.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![self.durability.label()];
+        parts.extend(self.settings.iter().map(|(k, v)| format!("{}={}", k, v)));
+        parts.join(", ")
+    }
+}
+
+// =============================================================================
+// Interleaved runner
+// =============================================================================
+
+/// Per-repetition measurements for both profiles, index-aligned.
+pub struct AbSamples {
+    pub a: Vec<f64>,
+    pub b: Vec<f64>,
+}
+
+/// Run `workload` `reps` times against each profile, interleaved ABBA so that
+/// neither profile systematically runs first. Each repetition gets a fresh
+/// database so state from one run can't leak into the next.
+///
+/// `workload` returns the metric for one repetition (e.g. ops/sec).
+pub fn run_ab<F>(a: &DbProfile, b: &DbProfile, reps: usize, mut workload: F) -> AbSamples
+where
+    F: FnMut(&BenchDb) -> f64,
+{
+    run_ab_per_op(a, b, reps, |bench_db| vec![workload(bench_db)])
+}
+
+/// Like [`run_ab`], but `workload` returns one value per operation (e.g.
+/// latency in ns) and must replay the same operation stream every time.
+/// The returned samples pair op i of A with op i of B, repetition after
+/// repetition.
+pub fn run_ab_per_op<F>(a: &DbProfile, b: &DbProfile, reps: usize, mut workload: F) -> AbSamples
+where
+    F: FnMut(&BenchDb) -> Vec<f64>,
+{
+    let mut samples = AbSamples {
+        a: Vec::new(),
+        b: Vec::new(),
+    };
+
+    for rep in 0..reps {
+        let a_first = rep.is_multiple_of(2);
+        let order = if a_first { [a, b] } else { [b, a] };
+        let mut values = [Vec::new(), Vec::new()];
+        for (slot, profile) in order.iter().enumerate() {
+            let bench_db = profile.open();
+            values[slot] = workload(&bench_db);
+        }
+        let [first, second] = values;
+        let (va, vb) = if a_first { (first, second) } else { (second, first) };
+        assert_eq!(va.len(), vb.len(), "per-op runs must replay the same operations");
+        samples.a.extend(va);
+        samples.b.extend(vb);
+    }
+
+    samples
+}
+
+// =============================================================================
+// Paired-difference statistics
+// =============================================================================
+
+/// Statistics on the paired differences `b[i] - a[i]`.
+#[derive(Debug, Clone)]
+pub struct PairedStats {
+    pub reps: usize,
+    pub mean_a: f64,
+    pub mean_b: f64,
+    /// Mean of `b[i] - a[i]`.
+    pub mean_diff: f64,
+    /// Sample standard deviation of the differences.
+    pub stdev_diff: f64,
+    /// 95% confidence interval for the mean difference (Student's t).
+    pub ci95_low: f64,
+    pub ci95_high: f64,
+    /// `mean_diff / mean_a` as a percentage.
+    pub rel_diff_pct: f64,
+}
+
+impl PairedStats {
+    /// True if the 95% confidence interval excludes zero.
+    pub fn significant(&self) -> bool {
+        self.ci95_low > 0.0 || self.ci95_high < 0.0
+    }
+}
+
+/// Two-sided 95% critical value of Student's t for `df` degrees of freedom.
+fn t_critical_95(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179,
+        2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064,
+        2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => TABLE[df - 1],
+        31..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
+}
+
+/// Compute paired-difference statistics. `a` and `b` must be index-aligned
+/// and of equal length.
+pub fn paired_stats(a: &[f64], b: &[f64]) -> PairedStats {
+    assert_eq!(a.len(), b.len(), "paired samples must have equal length");
+    let n = a.len();
+    let mean = |v: &[f64]| if v.is_empty() { 0.0 } else { v.iter().sum::<f64>() / v.len() as f64 };
+
+    let diffs: Vec<f64> = a.iter().zip(b).map(|(x, y)| y - x).collect();
+    let mean_a = mean(a);
+    let mean_b = mean(b);
+    let mean_diff = mean(&diffs);
+
+    let stdev_diff = if n > 1 {
+        let var = diffs.iter().map(|d| (d - mean_diff).powi(2)).sum::<f64>() / (n - 1) as f64;
+        var.sqrt()
+    } else {
+        0.0
+    };
+
+    let half_width = if n > 1 {
+        t_critical_95(n - 1) * stdev_diff / (n as f64).sqrt()
+    } else {
+        f64::INFINITY
+    };
+
+    PairedStats {
+        reps: n,
+        mean_a,
+        mean_b,
+        mean_diff,
+        stdev_diff,
+        ci95_low: mean_diff - half_width,
+        ci95_high: mean_diff + half_width,
+        rel_diff_pct: if mean_a != 0.0 {
+            mean_diff / mean_a * 100.0
+        } else {
+            0.0
+        },
+    }
+}
+
+// =============================================================================
+// Per-op difference distribution
+// =============================================================================
+
+/// Percentiles reported for the per-op differences.
+pub const DIFF_PERCENTILES: [f64; 7] = [1.0, 5.0, 25.0, 50.0, 75.0, 95.0, 99.0];
+
+/// Distribution of the per-op differences `b[i] - a[i]`.
+///
+/// Comparing p50(B) with p50(A) throws the pairing away. The median of the
+/// differences keeps it, so a shift far smaller than the spread of either
+/// run still shows up.
+#[derive(Debug, Clone)]
+pub struct DiffDistribution {
+    pub ops: usize,
+    /// `(percentile, difference)` for each of [`DIFF_PERCENTILES`].
+    pub percentiles: Vec<(f64, f64)>,
+    /// Distribution-free 95% confidence interval for the median difference
+    /// (order statistics of the sign test).
+    pub median_ci95_low: f64,
+    pub median_ci95_high: f64,
+    /// Percentage of ops where `b[i] < a[i]`.
+    pub b_lower_pct: f64,
+    /// `p50(b) - p50(a)`: the unpaired comparison, for contrast.
+    pub marginal_p50_diff: f64,
+}
+
+impl DiffDistribution {
+    /// Value of the difference at percentile `pct`, if it was computed.
+    pub fn at(&self, pct: f64) -> Option<f64> {
+        self.percentiles.iter().find(|(p, _)| *p == pct).map(|&(_, d)| d)
+    }
+
+    /// True if the median difference's 95% confidence interval excludes zero.
+    pub fn significant(&self) -> bool {
+        self.median_ci95_low > 0.0 || self.median_ci95_high < 0.0
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile_sorted(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let idx = ((pct / 100.0 * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1;
+    sorted[idx]
+}
+
+/// Summarize the per-op differences `b[i] - a[i]`. `a` and `b` must be
+/// index-aligned and of equal length.
+pub fn diff_distribution(a: &[f64], b: &[f64]) -> DiffDistribution {
+    assert_eq!(a.len(), b.len(), "paired samples must have equal length");
+    let n = a.len();
+    let by_value = |x: &f64, y: &f64| x.total_cmp(y);
+
+    let mut diffs: Vec<f64> = a.iter().zip(b).map(|(x, y)| y - x).collect();
+    diffs.sort_by(by_value);
+    let b_lower = diffs.iter().filter(|&&d| d < 0.0).count();
+
+    // Ranks n/2 ± 1.96·√n/2 bound the median at 95% (normal approximation
+    // to the binomial); too few ops leaves the interval unbounded.
+    let half_width = 1.96 * (n as f64).sqrt() / 2.0;
+    let lo = (n as f64 / 2.0 - half_width).floor();
+    let hi = (n as f64 / 2.0 + half_width).ceil();
+    let (median_ci95_low, median_ci95_high) = if lo >= 1.0 && (hi as usize) <= n {
+        (diffs[lo as usize - 1], diffs[hi as usize - 1])
+    } else {
+        (f64::NEG_INFINITY, f64::INFINITY)
+    };
+
+    let mut sorted_a = a.to_vec();
+    let mut sorted_b = b.to_vec();
+    sorted_a.sort_by(by_value);
+    sorted_b.sort_by(by_value);
+
+    DiffDistribution {
+        ops: n,
+        percentiles: DIFF_PERCENTILES
+            .iter()
+            .map(|&p| (p, percentile_sorted(&diffs, p)))
+            .collect(),
+        median_ci95_low,
+        median_ci95_high,
+        b_lower_pct: if n > 0 { b_lower as f64 / n as f64 * 100.0 } else { 0.0 },
+        marginal_p50_diff: percentile_sorted(&sorted_b, 50.0) - percentile_sorted(&sorted_a, 50.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_distribution, paired_stats, t_critical_95, DbProfile, DurabilityConfig};
+
+    #[test]
+    fn test_paired_stats_constant_shift_is_significant() {
+        let a = [100.0, 110.0, 90.0, 105.0];
+        let b = [110.0, 120.0, 100.0, 115.0];
+        let s = paired_stats(&a, &b);
+        assert_eq!(s.reps, 4);
+        assert!((s.mean_diff - 10.0).abs() < 1e-9);
+        assert!(s.stdev_diff.abs() < 1e-9);
+        assert!(s.significant());
+        assert!((s.rel_diff_pct - 10.0 / 101.25 * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_paired_stats_noise_is_not_significant() {
+        let a = [100.0, 100.0, 100.0, 100.0];
+        let b = [105.0, 95.0, 104.0, 96.0];
+        let s = paired_stats(&a, &b);
+        assert!(s.mean_diff.abs() < 1e-9);
+        assert!(!s.significant());
+    }
+
+    #[test]
+    fn test_paired_stats_single_rep_has_unbounded_ci() {
+        let s = paired_stats(&[1.0], &[2.0]);
+        assert!(!s.significant());
+        assert!(s.ci95_high.is_infinite());
+    }
+
+    #[test]
+    fn test_t_critical_decreases_toward_normal() {
+        assert!(t_critical_95(1) > t_critical_95(10));
+        assert!(t_critical_95(10) > t_critical_95(1000));
+        assert!((t_critical_95(1000) - 1.96).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_diff_distribution_finds_shift_hidden_by_spread() {
+        // A spans 1000..2000 ns; B is every op 5 ns slower. The marginal
+        // medians differ by 5 ns out of a 1000 ns spread, but every paired
+        // difference is exactly +5.
+        let a: Vec<f64> = (0..1000).map(|i| 1000.0 + ((i * 7919) % 1000) as f64).collect();
+        let b: Vec<f64> = a.iter().map(|x| x + 5.0).collect();
+        let d = diff_distribution(&a, &b);
+        assert_eq!(d.ops, 1000);
+        assert_eq!(d.at(1.0), Some(5.0));
+        assert_eq!(d.at(99.0), Some(5.0));
+        assert!(d.significant());
+        assert_eq!(d.b_lower_pct, 0.0);
+        assert!((d.marginal_p50_diff - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_diff_distribution_symmetric_noise_is_not_significant() {
+        let a = vec![100.0; 1000];
+        let b: Vec<f64> = (0..1000).map(|i| if i % 2 == 0 { 103.0 } else { 97.0 }).collect();
+        let d = diff_distribution(&a, &b);
+        assert!(!d.significant());
+        assert_eq!(d.b_lower_pct, 50.0);
+        assert_eq!(d.at(5.0), Some(-3.0));
+        assert_eq!(d.at(95.0), Some(3.0));
+    }
+
+    #[test]
+    fn test_diff_distribution_few_ops_has_unbounded_ci() {
+        let d = diff_distribution(&[1.0, 2.0], &[2.0, 3.0]);
+        assert!(!d.significant());
+        assert!(d.median_ci95_high.is_infinite());
+    }
+
+    #[test]
+    fn test_profile_parse() {
+        let p = DbProfile::parse("b", "durability=standard, auto_embed=false", DurabilityConfig::Cache).unwrap();
+        assert!(matches!(p.durability, DurabilityConfig::Standard));
+        assert_eq!(p.settings, vec![("auto_embed".to_string(), "false".to_string())]);
+        assert_eq!(p.describe(), "standard, auto_embed=false");
+
+        let p = DbProfile::parse("a", "auto_embed=true", DurabilityConfig::Always).unwrap();
+        assert!(matches!(p.durability, DurabilityConfig::Always));
+
+        assert!(DbProfile::parse("x", "durability=fast", DurabilityConfig::Cache).is_err());
+        assert!(DbProfile::parse("x", "auto_embed", DurabilityConfig::Cache).is_err());
+        assert!(DbProfile::parse("x", "", DurabilityConfig::Cache).unwrap().settings.is_empty());
+    }
+}
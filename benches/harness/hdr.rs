@@ -0,0 +1,191 @@
+//! Latency histograms with bounded memory.
+//!
+//! Keeping every latency and sorting them costs 16 bytes per operation,
+//! which a billion-operation run can't afford, and it's too coarse to say
+//! anything about p99.99 on a short one anyway. An HDR histogram keeps
+//! three significant digits per value instead: any percentile is within
+//! 0.1% of the true value, and the histogram stays a few hundred KB at most
+//! however many operations it holds.
+//!
+//! Minimum, maximum and mean are tracked exactly alongside it.
+
+use std::time::Duration;
+
+use hdrhistogram::Histogram;
+
+/// Significant decimal digits kept per value; 3 bounds the error at 0.1%.
+const SIGNIFICANT_DIGITS: u8 = 3;
+
+/// Recorded latencies, in nanoseconds.
+pub struct LatencyHistogram {
+    hist: Histogram<u64>,
+    min: Duration,
+    max: Duration,
+    sum_ns: u128,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        LatencyHistogram {
+            // Auto-resizing, so no operation is too slow to record
+            hist: Histogram::new(SIGNIFICANT_DIGITS).expect("valid histogram precision"),
+            min: Duration::MAX,
+            max: Duration::ZERO,
+            sum_ns: 0,
+        }
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        let ns = latency.as_nanos();
+        // `saturating_record` would clamp to the current range instead of
+        // growing it; half of u64 is the most an HDR histogram can track
+        self.hist
+            .record(ns.min(u64::MAX as u128 / 2) as u64)
+            .expect("auto-resizing histogram records any value up to u64::MAX / 2");
+        self.min = self.min.min(latency);
+        self.max = self.max.max(latency);
+        self.sum_ns += ns;
+    }
+
+    /// Add every latency recorded in `other`, e.g. from another thread.
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        if other.is_empty() {
+            return;
+        }
+        self.hist.add(&other.hist).expect("auto-resizing histograms always merge");
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sum_ns += other.sum_ns;
+    }
+
+    /// Number of recorded latencies.
+    pub fn len(&self) -> u64 {
+        self.hist.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hist.is_empty()
+    }
+
+    /// Latency at `quantile` (0.99 for p99). Zero when empty.
+    pub fn quantile(&self, quantile: f64) -> Duration {
+        if self.is_empty() {
+            return Duration::ZERO;
+        }
+        // The histogram answers with the top of the value's bucket, which
+        // can overshoot the largest value actually seen
+        Duration::from_nanos(self.hist.value_at_quantile(quantile)).clamp(self.min, self.max)
+    }
+
+    pub fn p50(&self) -> Duration {
+        self.quantile(0.50)
+    }
+
+    pub fn p95(&self) -> Duration {
+        self.quantile(0.95)
+    }
+
+    pub fn p99(&self) -> Duration {
+        self.quantile(0.99)
+    }
+
+    pub fn p999(&self) -> Duration {
+        self.quantile(0.999)
+    }
+
+    pub fn p9999(&self) -> Duration {
+        self.quantile(0.9999)
+    }
+
+    /// Exact minimum. Zero when empty.
+    pub fn min(&self) -> Duration {
+        if self.is_empty() {
+            Duration::ZERO
+        } else {
+            self.min
+        }
+    }
+
+    /// Exact maximum.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Exact sum of every recorded latency.
+    pub fn total(&self) -> Duration {
+        Duration::from_nanos(self.sum_ns.min(u64::MAX as u128) as u64)
+    }
+
+    /// Exact mean. Zero when empty.
+    pub fn mean(&self) -> Duration {
+        let n = self.len() as u128;
+        if n == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos((self.sum_ns / n).min(u64::MAX as u128) as u64)
+    }
+}
+
+impl FromIterator<Duration> for LatencyHistogram {
+    fn from_iter<I: IntoIterator<Item = Duration>>(iter: I) -> Self {
+        let mut h = LatencyHistogram::new();
+        for latency in iter {
+            h.record(latency);
+        }
+        h
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn us(n: u64) -> Duration {
+        Duration::from_micros(n)
+    }
+
+    #[test]
+    fn test_quantiles_within_precision() {
+        let h: LatencyHistogram = (1..=100_000).map(us).collect();
+        for (q, exact) in [(0.5, 50_000), (0.99, 99_000), (0.999, 99_900), (0.9999, 99_990)] {
+            let got = h.quantile(q).as_micros() as f64;
+            assert!((got - exact as f64).abs() / exact as f64 <= 0.001, "q={} got={}", q, got);
+        }
+        assert_eq!(h.min(), us(1));
+        assert_eq!(h.max(), us(100_000));
+        assert_eq!(h.mean(), Duration::from_nanos(50_000_500));
+        assert_eq!(h.len(), 100_000);
+    }
+
+    #[test]
+    fn test_merge_matches_single_histogram() {
+        let mut a: LatencyHistogram = (1..=500).map(us).collect();
+        let b: LatencyHistogram = (501..=1_000).map(us).collect();
+        a.merge(&b);
+        let all: LatencyHistogram = (1..=1_000).map(us).collect();
+        assert_eq!(a.len(), all.len());
+        assert_eq!(a.p99(), all.p99());
+        assert_eq!((a.min(), a.max(), a.mean()), (all.min(), all.max(), all.mean()));
+    }
+
+    #[test]
+    fn test_empty_is_zero() {
+        let mut h = LatencyHistogram::new();
+        h.merge(&LatencyHistogram::new());
+        assert!(h.is_empty());
+        assert_eq!(h.p99(), Duration::ZERO);
+        assert_eq!((h.min(), h.max(), h.mean()), (Duration::ZERO, Duration::ZERO, Duration::ZERO));
+    }
+
+    #[test]
+    fn test_quantile_never_exceeds_max() {
+        let h: LatencyHistogram = [us(1), Duration::from_nanos(1_234_567)].into_iter().collect();
+        assert_eq!(h.quantile(1.0), Duration::from_nanos(1_234_567));
+    }
+}
@@ -0,0 +1,407 @@
+//! Multi-threaded load generation.
+//!
+//! [`run`] drives one worker per context — usually a Strata handle from
+//! `new_handle()`, or a server connection — and hands back every worker's
+//! latencies merged into one [`Recorder`]. The bench supplies a factory
+//! that turns each context into a closure issuing one operation per call;
+//! the load generator does the splitting, pacing and timing:
+//!
+//! ```ignore
+//! let spec = load::Load { ops: 100_000, duration: None, rate: None };
+//! let run: load::LoadResult<LatencyHistogram> = load::run(&spec, handles, |worker, db| {
+//!     let mut keys = KeyGen::for_worker(worker);
+//!     move || {
+//!         db.kv_put(&keys.next(), value.clone()).unwrap();
+//!     }
+//! });
+//! println!("{:.0} ops/s, p99 {:?}", run.ops_per_sec(), run.recorder.p99());
+//! ```
+//!
+//! With `rate` set each worker runs an open loop: operation `k` is due at
+//! `k` intervals after the worker started, and its latency is measured from
+//! when it was due, not when it was issued. A stall then shows up in the
+//! latency of every operation that queued behind it, as it would for
+//! clients that don't wait for each other (no coordinated omission).
+//!
+//! The schedule and `duration` are kept on a [`Clock`] (see
+//! [`super::clock`]); each operation itself is timed on `Instant`.
+
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+
+use super::affinity;
+use super::clock::{Clock, Pacer, RealClock};
+use super::hdr::LatencyHistogram;
+use super::spikes::SpikeTracker;
+
+/// How much work to generate.
+#[derive(Debug, Clone, Copy)]
+pub struct Load {
+    /// Total operations across all workers, spread as evenly as possible.
+    /// Ignored when `duration` is set.
+    pub ops: usize,
+    /// Run every worker until this much wall-clock time has passed instead
+    /// of stopping after `ops`.
+    pub duration: Option<Duration>,
+    /// Target throughput across all workers, in operations per second.
+    /// `None` runs every worker flat out.
+    pub rate: Option<f64>,
+}
+
+/// Collects one worker's operation latencies; merged across workers at the
+/// end of a run.
+pub trait Recorder: Default + Send {
+    /// What one operation reports back, e.g. which command it was or whether
+    /// a read hit.
+    type Outcome;
+
+    /// The recorder worker `worker` starts with.
+    fn for_worker(_worker: usize) -> Self {
+        Self::default()
+    }
+
+    fn record(&mut self, outcome: Self::Outcome, latency: Duration);
+
+    fn merge(&mut self, other: Self);
+}
+
+impl Recorder for LatencyHistogram {
+    type Outcome = ();
+
+    fn record(&mut self, _: (), latency: Duration) {
+        LatencyHistogram::record(self, latency);
+    }
+
+    fn merge(&mut self, other: Self) {
+        LatencyHistogram::merge(self, &other);
+    }
+}
+
+/// Latencies plus the slowest operations (see [`super::spikes`]). A
+/// spike's `op_index` counts within the worker that issued it, which the
+/// spike records.
+#[derive(Default)]
+pub struct Timings {
+    pub latencies: LatencyHistogram,
+    pub spikes: SpikeTracker,
+    ops: usize,
+}
+
+impl Timings {
+    /// The slowest operations, slowest first, each labeled with what it
+    /// worked on given the worker that issued it and the operation's index
+    /// within that worker (e.g. the key it used).
+    pub fn spikes_with_context<C: Fn(usize, usize) -> String>(
+        self,
+        context: C,
+    ) -> Vec<strata_benchmarks::schema::LatencySpike> {
+        let mut spikes = self.spikes.finish();
+        for spike in &mut spikes {
+            let worker = spike.worker.unwrap_or(0) as usize;
+            spike.context = Some(context(worker, spike.op_index as usize));
+        }
+        spikes
+    }
+}
+
+impl Recorder for Timings {
+    type Outcome = ();
+
+    fn for_worker(worker: usize) -> Self {
+        Timings {
+            spikes: SpikeTracker::default().with_worker(worker),
+            ..Timings::default()
+        }
+    }
+
+    fn record(&mut self, _: (), latency: Duration) {
+        self.latencies.record(latency);
+        let now = Instant::now();
+        self.spikes.observe(self.ops, now.checked_sub(latency).unwrap_or(now), latency);
+        self.ops += 1;
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.latencies.merge(&other.latencies);
+        self.spikes.merge(other.spikes);
+        self.ops += other.ops;
+    }
+}
+
+/// Everything the workers recorded, merged.
+pub struct LoadResult<R> {
+    pub recorder: R,
+    /// Operations completed across all workers.
+    pub ops: u64,
+    /// Wall time from releasing the workers to the last one finishing.
+    pub elapsed: Duration,
+}
+
+impl<R> LoadResult<R> {
+    pub fn ops_per_sec(&self) -> f64 {
+        self.ops as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Run `load` across one worker thread per context.
+///
+/// `worker` is called once per context, on that context's thread, with the
+/// worker's index; the closure it returns issues one operation per call.
+/// Workers start together behind a barrier so none gets a head start while
+/// the others are still spawning. With `--cpus`, worker `i` runs on the
+/// `i`-th core of the set.
+///
+/// With a single context the operations run on the calling thread, so a
+/// one-client run measures no thread hand-off.
+pub fn run<C, R, W, F>(load: &Load, contexts: Vec<C>, worker: W) -> LoadResult<R>
+where
+    C: Send,
+    R: Recorder,
+    W: Fn(usize, C) -> F + Sync,
+    F: FnMut() -> R::Outcome,
+{
+    run_with_clock(&RealClock::start(), load, contexts, worker)
+}
+
+/// [`run`], paced and timed on `clock`.
+fn run_with_clock<K, C, R, W, F>(
+    clock: &K,
+    load: &Load,
+    mut contexts: Vec<C>,
+    worker: W,
+) -> LoadResult<R>
+where
+    K: Clock,
+    C: Send,
+    R: Recorder,
+    W: Fn(usize, C) -> F + Sync,
+    F: FnMut() -> R::Outcome,
+{
+    let workers = contexts.len();
+    assert!(workers > 0, "load::run needs at least one context");
+    // Each worker paces its share of the total rate
+    let rate = load.rate.filter(|r| *r > 0.0).map(|r| r / workers as f64);
+    let quota = |w: usize| load.ops / workers + usize::from(w < load.ops % workers);
+
+    if workers == 1 {
+        let op = worker(0, contexts.pop().unwrap());
+        let start = clock.elapsed();
+        let (recorder, ops) = drive::<R, _>(clock, 0, op, quota(0), load.duration, rate);
+        return LoadResult {
+            recorder,
+            ops,
+            elapsed: clock.elapsed() - start,
+        };
+    }
+
+    let barrier = Barrier::new(workers + 1);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = contexts
+            .into_iter()
+            .enumerate()
+            .map(|(w, ctx)| {
+                let (barrier, worker, quota) = (&barrier, &worker, &quota);
+                scope.spawn(move || {
+                    affinity::pin_worker(w);
+                    let op = worker(w, ctx);
+                    barrier.wait();
+                    drive::<R, _>(clock, w, op, quota(w), load.duration, rate)
+                })
+            })
+            .collect();
+
+        barrier.wait();
+        let start = clock.elapsed();
+        let mut result = LoadResult {
+            recorder: R::default(),
+            ops: 0,
+            elapsed: Duration::ZERO,
+        };
+        for h in handles {
+            let (recorder, ops) = h.join().expect("load worker panicked");
+            result.recorder.merge(recorder);
+            result.ops += ops;
+        }
+        result.elapsed = clock.elapsed() - start;
+        result
+    })
+}
+
+/// Worker `worker`'s loop: `quota` operations, or until `duration` has
+/// passed on `clock`, paced to `rate` per second if set. A late operation's
+/// latency includes how late it started.
+fn drive<R: Recorder, F: FnMut() -> R::Outcome>(
+    clock: &impl Clock,
+    worker: usize,
+    mut op: F,
+    quota: usize,
+    duration: Option<Duration>,
+    rate: Option<f64>,
+) -> (R, u64) {
+    let mut recorder = R::for_worker(worker);
+    let begin = clock.elapsed();
+    let deadline = duration.map(|d| begin + d);
+    let mut pacer = Pacer::starting_at(rate, begin);
+    let mut done = 0u64;
+
+    loop {
+        if deadline.is_none() && done >= quota as u64 {
+            break;
+        }
+        if deadline.is_some_and(|d| clock.elapsed().max(pacer.next_due()) >= d) {
+            break;
+        }
+        let late = pacer.wait(clock);
+        let start = Instant::now();
+        let outcome = op();
+        recorder.record(outcome, late + start.elapsed());
+        done += 1;
+    }
+    (recorder, done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::clock::MockClock;
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const MS: Duration = Duration::from_millis(1);
+
+    /// Every latency in order.
+    #[derive(Default)]
+    struct Latencies(Vec<Duration>);
+
+    impl Recorder for Latencies {
+        type Outcome = ();
+        fn record(&mut self, _: (), latency: Duration) {
+            self.0.push(latency);
+        }
+        fn merge(&mut self, other: Self) {
+            self.0.extend(other.0);
+        }
+    }
+
+    #[test]
+    fn test_ops_split_across_workers() {
+        let issued: Vec<AtomicUsize> = (0..3).map(|_| AtomicUsize::new(0)).collect();
+        let load = Load {
+            ops: 10,
+            duration: None,
+            rate: None,
+        };
+        let run: LoadResult<LatencyHistogram> = run(&load, vec![(); 3], |w, ()| {
+            let issued = &issued[w];
+            move || {
+                issued.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        assert_eq!(run.ops, 10);
+        assert_eq!(run.recorder.len(), 10);
+        let per_worker: Vec<usize> = issued.iter().map(|n| n.load(Ordering::Relaxed)).collect();
+        assert_eq!(per_worker, vec![4, 3, 3]);
+    }
+
+    #[test]
+    fn test_spikes_name_their_worker() {
+        // Fewer operations than tracked spikes, so every one is kept
+        let load = Load {
+            ops: 6,
+            duration: None,
+            rate: None,
+        };
+        let run: LoadResult<Timings> = run(&load, vec![(); 2], |_, ()| || ());
+        let mut labels: Vec<String> = run
+            .recorder
+            .spikes_with_context(|worker, i| format!("{}/{}", worker, i))
+            .into_iter()
+            .filter_map(|s| s.context)
+            .collect();
+        labels.sort();
+        assert_eq!(labels, vec!["0/0", "0/1", "0/2", "1/0", "1/1", "1/2"]);
+    }
+
+    #[test]
+    fn test_rate_paces_workers() {
+        let load = Load {
+            ops: 10,
+            duration: None,
+            rate: Some(500.0),
+        };
+        let clock = MockClock::new();
+        let run: LoadResult<LatencyHistogram> =
+            run_with_clock(&clock, &load, vec![()], |_, ()| || ());
+        // The last operation is due 9 intervals of 2ms in
+        assert_eq!(run.elapsed, 18 * MS);
+        assert_eq!(run.ops, 10);
+    }
+
+    #[test]
+    fn test_late_operations_count_their_wait() {
+        // 1000/s, and the first operation stalls for 5ms: the next four were
+        // due at 1-4ms, start at 5ms, and are 4, 3, 2 and 1ms late
+        let load = Load {
+            ops: 6,
+            duration: None,
+            rate: Some(1_000.0),
+        };
+        let clock = MockClock::new();
+        let run: LoadResult<Latencies> = run_with_clock(&clock, &load, vec![()], |_, ()| {
+            let mut first = true;
+            let clock = &clock;
+            move || {
+                if std::mem::take(&mut first) {
+                    clock.advance(5 * MS);
+                }
+            }
+        });
+        let late: Vec<Duration> = [0, 4, 3, 2, 1, 0].iter().map(|&ms| ms * MS).collect();
+        for (latency, late) in run.recorder.0.iter().zip(&late) {
+            // Plus the real time the (empty) operation took
+            assert!(*latency >= *late && *latency - *late < MS, "{:?} vs {:?}", latency, late);
+        }
+        assert_eq!(run.ops, 6);
+        assert_eq!(run.elapsed, 5 * MS);
+    }
+
+    #[test]
+    fn test_duration_and_outcomes() {
+        #[derive(Default)]
+        struct Split {
+            even: u64,
+            odd: u64,
+        }
+        impl Recorder for Split {
+            type Outcome = bool;
+            fn record(&mut self, even: bool, _: Duration) {
+                if even {
+                    self.even += 1;
+                } else {
+                    self.odd += 1;
+                }
+            }
+            fn merge(&mut self, other: Self) {
+                self.even += other.even;
+                self.odd += other.odd;
+            }
+        }
+
+        let load = Load {
+            ops: 0,
+            duration: Some(Duration::from_millis(20)),
+            rate: Some(500.0),
+        };
+        let clock = MockClock::new();
+        let run: LoadResult<Split> = run_with_clock(&clock, &load, vec![()], |_, ()| {
+            let mut even = true;
+            move || {
+                even = !even;
+                even
+            }
+        });
+        // One operation every 2ms for 20ms
+        assert_eq!(run.ops, 10);
+        assert_eq!((run.recorder.even, run.recorder.odd), (5, 5));
+        assert_eq!(run.elapsed, 18 * MS);
+    }
+}
@@ -0,0 +1,391 @@
+//! Memory ceilings for constrained-memory runs (Linux).
+//!
+//! A ceiling is applied to a child process, never to the running one, so a
+//! suite can be built and set up without it. The mechanisms are tried in
+//! this order:
+//!
+//! 1. **cgroup v2**: a `strata-bench` child of this process's cgroup, with
+//!    `memory.max` set and swap disabled. Needs write access to the cgroup
+//!    tree, and the memory controller enabled for children of a cgroup this
+//!    process is in, which the kernel only allows for the root cgroup: root
+//!    on a host or VM. In a container or a session scope, enabling it fails
+//!    with EBUSY and the next mechanism is tried.
+//! 2. **cgroup v1**: the same, in the `memory` hierarchy of a legacy or
+//!    hybrid host (`memory.limit_in_bytes`).
+//! 3. **systemd**: `systemd-run --user --scope -p MemoryMax=...`, for a
+//!    desktop or server session with a user manager.
+//!
+//! Page cache counts against the ceiling in all three, so a dataset larger
+//! than the ceiling is read from the storage device, not from cache.
+//!
+//! The child gets `STRATA_BENCH_MEMORY_LIMIT` set to the ceiling in bytes.
+//! This module doesn't depend on the rest of the harness, so the umbrella
+//! runner includes it on its own.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+/// Environment variable holding the ceiling (bytes) a process runs under.
+pub const LIMIT_ENV: &str = "STRATA_BENCH_MEMORY_LIMIT";
+
+/// Name of the cgroup created under this process's own cgroup.
+const CGROUP_NAME: &str = "strata-bench";
+
+/// cgroup v1 reports "no limit" as a page-aligned `i64::MAX`.
+const V1_UNLIMITED: u64 = 1 << 62;
+
+/// Parse a size such as `512M`, `2G`, `1.5GiB` or `1048576` into bytes.
+/// Suffixes are binary (K = 1024).
+pub fn parse_size(s: &str) -> Option<u64> {
+    let lower = s.trim().to_ascii_lowercase();
+    let digits = lower.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let multiplier: u64 = match &lower[digits.len()..] {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        "t" | "tb" | "tib" => 1 << 40,
+        _ => return None,
+    };
+    let value: f64 = digits.parse().ok()?;
+    if !value.is_finite() || value <= 0.0 {
+        return None;
+    }
+    Some((value * multiplier as f64) as u64)
+}
+
+/// Format a byte count with a binary unit: `"512 MiB"`, `"1.50 GiB"`.
+pub fn fmt_size(bytes: u64) -> String {
+    const GIB: f64 = (1u64 << 30) as f64;
+    const MIB: f64 = (1u64 << 20) as f64;
+    let b = bytes as f64;
+    if b >= GIB {
+        format!("{:.2} GiB", b / GIB)
+    } else {
+        format!("{:.0} MiB", b / MIB)
+    }
+}
+
+/// The ceiling this process was started under by a [`Limiter`], if any.
+pub fn active_limit() -> Option<u64> {
+    std::env::var(LIMIT_ENV).ok()?.parse().ok()
+}
+
+/// Memory limit of this process's own cgroup (v2 `memory.max`, else v1
+/// `memory.limit_in_bytes`), or `None` if it is unlimited or can't be read.
+/// Limits set on an ancestor cgroup are not seen.
+pub fn cgroup_memory_max() -> Option<u64> {
+    let read = |dir: PathBuf, file: &str| -> Option<u64> {
+        std::fs::read_to_string(dir.join(file)).ok()?.trim().parse().ok()
+    };
+    own_cgroup_dir(Version::V2)
+        .and_then(|dir| read(dir, "memory.max"))
+        .or_else(|| own_cgroup_dir(Version::V1).and_then(|dir| read(dir, "memory.limit_in_bytes")))
+        .filter(|&limit| limit < V1_UNLIMITED)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Version {
+    V1,
+    V2,
+}
+
+impl Version {
+    fn label(self) -> &'static str {
+        match self {
+            Version::V1 => "cgroup v1",
+            Version::V2 => "cgroup v2",
+        }
+    }
+}
+
+/// Directory of this process's cgroup in the unified (v2) or v1 `memory`
+/// hierarchy, under wherever that hierarchy is mounted.
+fn own_cgroup_dir(version: Version) -> Option<PathBuf> {
+    let cgroups = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+    let mounts = std::fs::read_to_string("/proc/mounts").ok()?;
+    let (rel, root) = match version {
+        Version::V2 => (unified_cgroup_path(&cgroups)?, cgroup2_mount(&mounts)?),
+        Version::V1 => (memory_cgroup_path(&cgroups)?, memory_v1_mount(&mounts)?),
+    };
+    Some(Path::new(root).join(rel.trim_start_matches('/')))
+}
+
+/// The cgroup v2 entry (`0::/path`) of a `/proc/<pid>/cgroup` file.
+fn unified_cgroup_path(contents: &str) -> Option<&str> {
+    contents.lines().find_map(|l| l.strip_prefix("0::"))
+}
+
+/// The v1 entry whose controller list includes `memory`.
+fn memory_cgroup_path(contents: &str) -> Option<&str> {
+    contents.lines().find_map(|l| {
+        let mut fields = l.splitn(3, ':');
+        let _id = fields.next()?;
+        let controllers = fields.next()?;
+        let path = fields.next()?;
+        controllers.split(',').any(|c| c == "memory").then_some(path)
+    })
+}
+
+/// Mount point of the first `cgroup2` filesystem in `/proc/mounts`.
+fn cgroup2_mount(mounts: &str) -> Option<&str> {
+    mounts.lines().find_map(|l| {
+        let mut fields = l.split_whitespace();
+        let mount_point = fields.nth(1)?;
+        (fields.next()? == "cgroup2").then_some(mount_point)
+    })
+}
+
+/// Mount point of the v1 `cgroup` filesystem carrying the memory controller.
+fn memory_v1_mount(mounts: &str) -> Option<&str> {
+    mounts.lines().find_map(|l| {
+        let mut fields = l.split_whitespace();
+        let mount_point = fields.nth(1)?;
+        let fs_type = fields.next()?;
+        let options = fields.next()?;
+        (fs_type == "cgroup" && options.split(',').any(|o| o == "memory")).then_some(mount_point)
+    })
+}
+
+/// How a [`Limiter`] enforces its ceiling.
+enum Mechanism {
+    /// The child joins this cgroup directory before it execs.
+    Cgroup(PathBuf, Version),
+    /// The child is started through `systemd-run --scope`.
+    Systemd,
+}
+
+/// Runs child processes under a memory ceiling.
+pub struct Limiter {
+    limit: u64,
+    mechanism: Mechanism,
+}
+
+impl Limiter {
+    /// Set up a ceiling of `limit` bytes. The error lists why each
+    /// mechanism was unavailable.
+    pub fn new(limit: u64) -> Result<Limiter, String> {
+        let mut reasons = Vec::new();
+        for version in [Version::V2, Version::V1] {
+            match create_cgroup(version, limit) {
+                Ok(dir) => {
+                    return Ok(Limiter {
+                        limit,
+                        mechanism: Mechanism::Cgroup(dir, version),
+                    })
+                }
+                Err(e) => reasons.push(format!("{}: {}", version.label(), e)),
+            }
+        }
+        match probe_systemd(limit) {
+            Ok(()) => Ok(Limiter {
+                limit,
+                mechanism: Mechanism::Systemd,
+            }),
+            Err(e) => {
+                reasons.push(format!("systemd-run: {}", e));
+                Err(reasons.join("; "))
+            }
+        }
+    }
+
+    /// The ceiling in bytes.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// One-line description for banners.
+    pub fn describe(&self) -> String {
+        match &self.mechanism {
+            Mechanism::Cgroup(dir, version) => format!(
+                "{} via {} {}",
+                fmt_size(self.limit),
+                version.label(),
+                dir.display()
+            ),
+            Mechanism::Systemd => format!("{} via systemd-run scope", fmt_size(self.limit)),
+        }
+    }
+
+    /// Run `program args...` under the ceiling and wait for it.
+    pub fn status(&self, program: &str, args: &[String]) -> io::Result<ExitStatus> {
+        match &self.mechanism {
+            // The shell joins the cgroup and then execs the program under the
+            // same pid, so nothing runs before the ceiling applies
+            Mechanism::Cgroup(dir, _) => Command::new("sh")
+                .arg("-c")
+                .arg(r#"echo $$ > "$0/cgroup.procs" && exec "$@""#)
+                .arg(dir)
+                .arg(program)
+                .args(args)
+                .env(LIMIT_ENV, self.limit.to_string())
+                .status(),
+            Mechanism::Systemd => Command::new("systemd-run")
+                .args(systemd_args(self.limit))
+                .arg("--")
+                .arg(program)
+                .args(args)
+                .env(LIMIT_ENV, self.limit.to_string())
+                .status(),
+        }
+    }
+
+    /// Times the kernel has OOM-killed a process under this ceiling, where
+    /// the mechanism exposes it (cgroups only; v1 needs Linux 4.13+).
+    pub fn oom_kills(&self) -> Option<u64> {
+        let Mechanism::Cgroup(dir, version) = &self.mechanism else {
+            return None;
+        };
+        let file = match version {
+            Version::V2 => "memory.events",
+            Version::V1 => "memory.oom_control",
+        };
+        let events = std::fs::read_to_string(dir.join(file)).ok()?;
+        events
+            .lines()
+            .find_map(|l| l.strip_prefix("oom_kill "))
+            .and_then(|v| v.trim().parse().ok())
+    }
+}
+
+impl Drop for Limiter {
+    fn drop(&mut self) {
+        // Empty once every child has exited; otherwise left for the next run
+        if let Mechanism::Cgroup(dir, _) = &self.mechanism {
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+}
+
+fn systemd_args(limit: u64) -> Vec<String> {
+    vec![
+        "--user".into(),
+        "--scope".into(),
+        "--quiet".into(),
+        "-p".into(),
+        format!("MemoryMax={}", limit),
+        "-p".into(),
+        "MemorySwapMax=0".into(),
+    ]
+}
+
+fn create_cgroup(version: Version, limit: u64) -> Result<PathBuf, String> {
+    if !cfg!(target_os = "linux") {
+        return Err("not Linux".into());
+    }
+    let parent = own_cgroup_dir(version).ok_or("hierarchy not mounted")?;
+    if version == Version::V2 {
+        let control = parent.join("cgroup.subtree_control");
+        let controllers = std::fs::read_to_string(&control)
+            .map_err(|e| format!("{}: {}", control.display(), e))?;
+        if !controllers.split_whitespace().any(|c| c == "memory") {
+            // Fails with EBUSY unless the parent is the root cgroup, since
+            // this process is in it
+            std::fs::write(&control, "+memory")
+                .map_err(|e| format!("enabling memory in {}: {}", control.display(), e))?;
+        }
+    }
+
+    let dir = parent.join(CGROUP_NAME);
+    match std::fs::create_dir(&dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(format!("{}: {}", dir.display(), e)),
+    }
+    // v2 caps swap alone, v1 caps memory plus swap
+    let (limit_file, swap_file, swap) = match version {
+        Version::V2 => ("memory.max", "memory.swap.max", 0),
+        Version::V1 => ("memory.limit_in_bytes", "memory.memsw.limit_in_bytes", limit),
+    };
+    let write = |file: &str, value: u64| {
+        std::fs::write(dir.join(file), value.to_string())
+            .map_err(|e| format!("{}/{}: {}", dir.display(), file, e))
+    };
+    // v1 rejects a memory limit above the memory+swap one (EINVAL), so when
+    // a cgroup left by an earlier run is raised, memory+swap goes first
+    let current: Option<u64> = std::fs::read_to_string(dir.join(limit_file))
+        .ok()
+        .and_then(|s| s.trim().parse().ok());
+    let swap_first = version == Version::V1 && current.is_some_and(|c| c < limit);
+    // The swap file is absent without swap accounting; then there is no
+    // swap to fall back on either
+    if swap_first {
+        let _ = write(swap_file, swap);
+    }
+    write(limit_file, limit)?;
+    if !swap_first {
+        let _ = write(swap_file, swap);
+    }
+    Ok(dir)
+}
+
+fn probe_systemd(limit: u64) -> Result<(), String> {
+    let status = Command::new("systemd-run")
+        .args(systemd_args(limit))
+        .arg("--")
+        .arg("true")
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("probe exited with {}", status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1048576"), Some(1 << 20));
+        assert_eq!(parse_size("512M"), Some(512 << 20));
+        assert_eq!(parse_size("512mib"), Some(512 << 20));
+        assert_eq!(parse_size("2G"), Some(2 << 30));
+        assert_eq!(parse_size("1.5GiB"), Some(3 << 29));
+        assert_eq!(parse_size("64k"), Some(64 << 10));
+        assert_eq!(parse_size("0"), None);
+        assert_eq!(parse_size("12X"), None);
+        assert_eq!(parse_size("M"), None);
+    }
+
+    #[test]
+    fn test_cgroup_paths() {
+        let v2 = "0::/user.slice/user-1000.slice/session-2.scope\n";
+        assert_eq!(
+            unified_cgroup_path(v2),
+            Some("/user.slice/user-1000.slice/session-2.scope")
+        );
+        assert_eq!(memory_cgroup_path(v2), None);
+
+        let hybrid = "9:name=systemd:/\n4:memory:/docker/abc\n2:cpu,cpuacct:/\n0::/\n";
+        assert_eq!(unified_cgroup_path(hybrid), Some("/"));
+        assert_eq!(memory_cgroup_path(hybrid), Some("/docker/abc"));
+        assert_eq!(memory_cgroup_path("5:cpu,memory:/a\n"), Some("/a"));
+    }
+
+    #[test]
+    fn test_cgroup_mounts() {
+        let unified = "sysfs /sys sysfs rw 0 0\ncgroup2 /sys/fs/cgroup cgroup2 rw,nsdelegate 0 0\n";
+        assert_eq!(cgroup2_mount(unified), Some("/sys/fs/cgroup"));
+        assert_eq!(memory_v1_mount(unified), None);
+
+        let hybrid = "\
+tmpfs /sys/fs/cgroup tmpfs ro 0 0
+cgroup2 /sys/fs/cgroup/unified cgroup2 rw 0 0
+cgroup /sys/fs/cgroup/cpu cgroup rw,cpu 0 0
+cgroup /sys/fs/cgroup/memory cgroup rw,nosuid,memory 0 0
+";
+        assert_eq!(cgroup2_mount(hybrid), Some("/sys/fs/cgroup/unified"));
+        assert_eq!(memory_v1_mount(hybrid), Some("/sys/fs/cgroup/memory"));
+    }
+
+    #[test]
+    fn test_fmt_size() {
+        assert_eq!(fmt_size(512 << 20), "512 MiB");
+        assert_eq!(fmt_size(3 << 29), "1.50 GiB");
+    }
+}
@@ -13,6 +13,8 @@ pub struct ProcessMetrics {
     pub voluntary_ctx: u64,
     /// Involuntary context switches (from /proc/self/status)
     pub involuntary_ctx: u64,
+    /// Page faults that needed I/O (from /proc/self/stat field 12: majflt)
+    pub major_faults: u64,
 }
 
 /// Take a snapshot of current process metrics.
@@ -22,13 +24,14 @@ pub struct ProcessMetrics {
 pub fn snapshot_process_metrics() -> ProcessMetrics {
     #[cfg(target_os = "linux")]
     {
-        let (user_time_ms, system_time_ms) = read_proc_stat();
+        let (user_time_ms, system_time_ms, major_faults) = read_proc_stat();
         let (voluntary_ctx, involuntary_ctx) = read_proc_status();
         ProcessMetrics {
             user_time_ms,
             system_time_ms,
             voluntary_ctx,
             involuntary_ctx,
+            major_faults,
         }
     }
     #[cfg(not(target_os = "linux"))]
@@ -44,33 +47,37 @@ pub fn delta_process_metrics(before: &ProcessMetrics, after: &ProcessMetrics) ->
         system_time_ms: after.system_time_ms.saturating_sub(before.system_time_ms),
         voluntary_ctx: after.voluntary_ctx.saturating_sub(before.voluntary_ctx),
         involuntary_ctx: after.involuntary_ctx.saturating_sub(before.involuntary_ctx),
+        major_faults: after.major_faults.saturating_sub(before.major_faults),
     }
 }
 
-/// Parse /proc/self/stat for user and system CPU time.
+/// Parse /proc/self/stat for user and system CPU time and major faults.
 ///
-/// Fields are space-separated. Field 14 (0-indexed 13) = utime, field 15 (0-indexed 14) = stime.
-/// Values are in clock ticks; we convert to milliseconds using sysconf(_SC_CLK_TCK).
+/// Fields are space-separated. Field 12 = majflt, field 14 (0-indexed 13) = utime,
+/// field 15 (0-indexed 14) = stime. Times are in clock ticks; we convert to
+/// milliseconds using sysconf(_SC_CLK_TCK).
 #[cfg(target_os = "linux")]
-fn read_proc_stat() -> (u64, u64) {
+fn read_proc_stat() -> (u64, u64, u64) {
     let Ok(contents) = std::fs::read_to_string("/proc/self/stat") else {
-        return (0, 0);
+        return (0, 0, 0);
     };
 
     // The comm field (field 2) is wrapped in parentheses and may contain spaces,
     // so we find the closing ')' and parse fields after it.
     let Some(close_paren) = contents.rfind(')') else {
-        return (0, 0);
+        return (0, 0, 0);
     };
     let rest = &contents[close_paren + 2..]; // skip ") "
     let fields: Vec<&str> = rest.split_whitespace().collect();
 
     // After the comm field, field indices shift:
-    // field 3 (state) is fields[0], field 14 (utime) is fields[11], field 15 (stime) is fields[12]
+    // field 3 (state) is fields[0], field 12 (majflt) is fields[9],
+    // field 14 (utime) is fields[11], field 15 (stime) is fields[12]
     if fields.len() < 13 {
-        return (0, 0);
+        return (0, 0, 0);
     }
 
+    let majflt: u64 = fields[9].parse().unwrap_or(0);
     let utime: u64 = fields[11].parse().unwrap_or(0);
     let stime: u64 = fields[12].parse().unwrap_or(0);
 
@@ -78,7 +85,7 @@ fn read_proc_stat() -> (u64, u64) {
     let user_ms = utime * 1000 / ticks_per_sec;
     let sys_ms = stime * 1000 / ticks_per_sec;
 
-    (user_ms, sys_ms)
+    (user_ms, sys_ms, majflt)
 }
 
 /// Get clock ticks per second via libc sysconf.
@@ -160,18 +167,21 @@ mod tests {
             system_time_ms: 20,
             voluntary_ctx: 50,
             involuntary_ctx: 10,
+            major_faults: 3,
         };
         let after = ProcessMetrics {
             user_time_ms: 250,
             system_time_ms: 35,
             voluntary_ctx: 120,
             involuntary_ctx: 25,
+            major_faults: 8,
         };
         let d = delta_process_metrics(&before, &after);
         assert_eq!(d.user_time_ms, 150);
         assert_eq!(d.system_time_ms, 15);
         assert_eq!(d.voluntary_ctx, 70);
         assert_eq!(d.involuntary_ctx, 15);
+        assert_eq!(d.major_faults, 5);
     }
 
     #[test]
@@ -3,14 +3,30 @@
 //! Provides database factory, data generators, latency percentile reporting,
 //! and configuration types used across all primitive benchmark files.
 
+pub mod affinity;
+pub mod alloc;
+pub mod cli;
+pub mod clock;
+pub mod environment;
+pub mod experiment;
+pub mod hdr;
+pub mod load;
+pub mod memlimit;
 pub mod metrics;
+pub mod perf;
+pub mod profile;
 pub mod recorder;
+pub mod resources;
 pub mod scaling;
+pub mod soak;
+pub mod spikes;
+pub mod warmup;
 
 use std::collections::HashMap;
 use std::fmt;
 use std::time::{Duration, Instant};
 
+use strata_benchmarks::fmt::fmt_duration;
 use stratadb::{Strata, Value, WalCounters};
 use tempfile::TempDir;
 
@@ -46,6 +62,10 @@ pub fn print_hardware_info() {
         eprintln!("Cores:  {}", cores);
         eprintln!("RAM:    {} GB", ram_gb);
         eprintln!("OS:     {} ({})", os, arch);
+        eprintln!(
+            "Env:    {}",
+            environment::describe(&environment::capture_environment(&cli::db_root()))
+        );
         eprintln!("================");
     });
 }
@@ -114,6 +134,28 @@ pub fn read_total_ram_gb() -> u64 {
     0
 }
 
+/// Resident set size of this process in bytes, or `None` where it can't be read.
+pub fn read_rss_bytes() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+        let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        return Some(kb * 1024);
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+            .output()
+            .ok()?;
+        let kb: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        return Some(kb * 1024);
+    }
+    #[allow(unreachable_code)]
+    None
+}
+
 // =============================================================================
 // Latency Percentiles
 // =============================================================================
@@ -123,55 +165,155 @@ pub struct Percentiles {
     pub p50: Duration,
     pub p95: Duration,
     pub p99: Duration,
+    pub p999: Duration,
+    pub p9999: Duration,
     pub min: Duration,
     pub max: Duration,
     pub samples: usize,
+    /// Wall time of the measured loop.
+    pub elapsed: Duration,
+    /// Allocations during the measured loop (`alloc-count` feature only).
+    pub allocs: Option<alloc::AllocSnapshot>,
+    /// Hardware counters over the measured loop (`perf-counters` feature only).
+    pub perf: Option<perf::PerfCounts>,
+    /// The slowest operations, slowest first (see [`spikes`]).
+    pub spikes: Vec<strata_benchmarks::schema::LatencySpike>,
+}
+
+impl Percentiles {
+    /// Operations per second over the measured loop.
+    pub fn ops_per_sec(&self) -> f64 {
+        self.samples as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Allocations per measured operation, if allocation counting is enabled.
+    pub fn allocs_per_op(&self) -> Option<f64> {
+        self.allocs
+            .map(|a| a.allocs as f64 / self.samples.max(1) as f64)
+    }
+
+    /// Bytes allocated per measured operation, if allocation counting is enabled.
+    pub fn alloc_bytes_per_op(&self) -> Option<f64> {
+        self.allocs
+            .map(|a| a.bytes as f64 / self.samples.max(1) as f64)
+    }
+
+    /// Hardware counters per measured operation, if perf counters are enabled.
+    pub fn perf_per_op(&self) -> Option<perf::PerfPerOp> {
+        self.perf.map(|c| c.per_op(self.samples))
+    }
+
+    /// Label each spike with what its operation worked on, given the
+    /// operation's index in the measured loop (e.g. the key it used).
+    pub fn with_context<C: Fn(usize) -> String>(mut self, context: C) -> Self {
+        for spike in &mut self.spikes {
+            spike.context = Some(context(spike.op_index as usize));
+        }
+        self
+    }
 }
 
 /// Run `f` for `n` iterations, time each call individually, return percentiles.
-pub fn measure_percentiles<F: FnMut()>(n: usize, mut f: F) -> Percentiles {
-    let mut timings = Vec::with_capacity(n);
-    for _ in 0..n {
+/// Timings go into an HDR histogram (see [`hdr`]), so memory doesn't grow with `n`.
+pub fn measure_percentiles<F: FnMut()>(n: usize, f: F) -> Percentiles {
+    measure(&clock::RealClock::start(), Some(n), None, f)
+}
+
+/// Run `f` repeatedly until `duration` has passed and return percentiles, as
+/// [`measure_percentiles`] does for a fixed count. Engines with very
+/// different throughput then get the same wall time each, rather than one
+/// finishing a fixed count in milliseconds and the other in minutes. `f` may
+/// be called any number of times, so it should cycle through its keys.
+pub fn measure_percentiles_for<F: FnMut()>(duration: Duration, f: F) -> Percentiles {
+    measure(&clock::RealClock::start(), None, Some(duration), f)
+}
+
+/// [`measure_percentiles_for`] `duration` if it's set (usually `--duration`),
+/// else [`measure_percentiles`] for `n` iterations.
+pub fn measure_percentiles_or<F: FnMut()>(duration: Option<Duration>, n: usize, f: F) -> Percentiles {
+    match duration {
+        Some(d) => measure_percentiles_for(d, f),
+        None => measure_percentiles(n, f),
+    }
+}
+
+/// The measured loop: `n` iterations, or until `duration` has passed on
+/// `clock`. Each call is still timed on `Instant`.
+fn measure<F: FnMut()>(
+    clock: &impl clock::Clock,
+    n: Option<usize>,
+    duration: Option<Duration>,
+    mut f: F,
+) -> Percentiles {
+    let mut timings = hdr::LatencyHistogram::new();
+    let mut tracker = spikes::SpikeTracker::new(spikes::TRACKED_SPIKES);
+    let alloc_before = alloc::snapshot();
+    let perf_section = perf::start();
+    let profile_section = profile::start();
+    let begin = Instant::now();
+    let deadline = duration.map(|d| clock.elapsed() + d);
+    let mut i = 0;
+    loop {
+        if n.is_some_and(|n| i >= n) {
+            break;
+        }
+        if deadline.is_some_and(|d| clock.elapsed() >= d) {
+            break;
+        }
         let start = Instant::now();
         f();
-        timings.push(start.elapsed());
+        let elapsed = start.elapsed();
+        timings.record(elapsed);
+        tracker.observe(i, start, elapsed);
+        i += 1;
     }
-    timings.sort();
-    let len = timings.len();
+    let total = begin.elapsed();
+    if let Some(section) = profile_section {
+        section.finish();
+    }
+    let perf = perf_section.and_then(perf::Section::finish);
+    let alloc_after = alloc::snapshot();
     Percentiles {
-        p50: timings[len * 50 / 100],
-        p95: timings[len * 95 / 100],
-        p99: timings[len * 99 / 100],
-        min: timings[0],
-        max: timings[len - 1],
-        samples: len,
-    }
-}
-
-/// Format a Duration for human-readable latency display.
-fn fmt_duration(d: Duration) -> String {
-    let nanos = d.as_nanos();
-    if nanos < 1_000 {
-        format!("{} ns", nanos)
-    } else if nanos < 1_000_000 {
-        format!("{:.2} µs", nanos as f64 / 1_000.0)
-    } else if nanos < 1_000_000_000 {
-        format!("{:.2} ms", nanos as f64 / 1_000_000.0)
-    } else {
-        format!("{:.2} s", nanos as f64 / 1_000_000_000.0)
+        p50: timings.p50(),
+        p95: timings.p95(),
+        p99: timings.p99(),
+        p999: timings.p999(),
+        p9999: timings.p9999(),
+        min: timings.min(),
+        max: timings.max(),
+        samples: timings.len() as usize,
+        elapsed: total,
+        allocs: alloc_before
+            .zip(alloc_after)
+            .map(|(before, after)| before.delta(&after)),
+        perf,
+        spikes: tracker.finish(),
     }
 }
 
 /// Print percentiles to stderr in a compact table.
 pub fn report_percentiles(label: &str, p: &Percentiles) {
     eprintln!(
-        "  {:<45} p50={:<12} p95={:<12} p99={:<12} (n={})",
+        "  {:<45} p50={:<12} p95={:<12} p99={:<12} p99.9={:<12} (n={})",
         label,
         fmt_duration(p.p50),
         fmt_duration(p.p95),
         fmt_duration(p.p99),
+        fmt_duration(p.p999),
         p.samples,
     );
+    if let (Some(allocs), Some(bytes)) = (p.allocs_per_op(), p.alloc_bytes_per_op()) {
+        eprintln!(
+            "  {:<45} allocs/op={:.1}  alloc_bytes/op={:.0}",
+            label, allocs, bytes
+        );
+    }
+    if let Some(c) = p.perf_per_op() {
+        eprintln!(
+            "  {:<45} instr/op={:.0}  cycles/op={:.0}  ipc={:.2}  br_miss/op={:.1}  llc_miss/op={:.1}",
+            label, c.instructions, c.cycles, c.ipc(), c.branch_misses, c.llc_misses
+        );
+    }
 }
 
 // =============================================================================
@@ -179,30 +321,104 @@ pub fn report_percentiles(label: &str, p: &Percentiles) {
 // =============================================================================
 
 /// Durability mode for benchmark parameterization.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DurabilityConfig {
     Cache,
+    /// Standard mode with the engine's default group commit.
     Standard,
+    /// Standard mode with its group commit set: the WAL is synced every
+    /// `interval_ms` or every `batch_size` writes, whichever comes first.
+    StandardSync { interval_ms: u64, batch_size: u64 },
     Always,
 }
 
+/// `strata.toml` key for [`DurabilityConfig::StandardSync`]'s `interval_ms`.
+pub const SYNC_INTERVAL_KEY: &str = "sync_interval_ms";
+/// `strata.toml` key for [`DurabilityConfig::StandardSync`]'s `batch_size`.
+pub const SYNC_BATCH_KEY: &str = "sync_batch_size";
+
+/// Standard mode's group commit when nothing sets it (the engine's
+/// `DurabilityMode::default()`).
+pub const DEFAULT_SYNC_INTERVAL_MS: u64 = 100;
+pub const DEFAULT_SYNC_BATCH_SIZE: u64 = 1000;
+
 impl DurabilityConfig {
-    /// All three modes for iteration in parameterized benchmarks.
+    /// The three base modes for iteration in parameterized benchmarks.
     pub const ALL: [Self; 3] = [Self::Cache, Self::Standard, Self::Always];
 
-    /// Short label for `BenchmarkId`.
-    pub fn label(&self) -> &'static str {
+    /// Label for `BenchmarkId`s and result names: the mode name, or
+    /// `standard-<interval>ms-<batch>` for [`Self::StandardSync`], so results
+    /// say which group commit they ran with.
+    pub fn label(&self) -> String {
         match self {
-            Self::Cache => "cache",
-            Self::Standard => "standard",
-            Self::Always => "always",
+            Self::Cache => "cache".to_string(),
+            Self::Standard => "standard".to_string(),
+            Self::StandardSync {
+                interval_ms,
+                batch_size,
+            } => format!("standard-{}ms-{}", interval_ms, batch_size),
+            Self::Always => "always".to_string(),
+        }
+    }
+
+    /// The mode a [`label`](Self::label) names.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "cache" => Some(Self::Cache),
+            "standard" => Some(Self::Standard),
+            "always" => Some(Self::Always),
+            _ => {
+                let (interval, batch) = s.strip_prefix("standard-")?.split_once("ms-")?;
+                Some(Self::StandardSync {
+                    interval_ms: interval.parse().ok().filter(|&n| n > 0)?,
+                    batch_size: batch.parse().ok().filter(|&n| n > 0)?,
+                })
+            }
         }
     }
 }
 
 impl fmt::Display for DurabilityConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(self.label())
+        f.write_str(&self.label())
+    }
+}
+
+/// Group-commit settings from `--strata-config` (see [`cli::strata_config`]).
+/// They apply to standard mode only; cache mode has no WAL and always mode
+/// syncs every write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncSettings {
+    pub interval_ms: Option<u64>,
+    pub batch_size: Option<u64>,
+}
+
+impl SyncSettings {
+    pub fn is_empty(&self) -> bool {
+        self.interval_ms.is_none() && self.batch_size.is_none()
+    }
+
+    /// `mode` with these settings: standard becomes
+    /// [`DurabilityConfig::StandardSync`], with the engine default for a
+    /// setting that isn't given. Other modes are returned as they are.
+    pub fn apply(&self, mode: DurabilityConfig) -> DurabilityConfig {
+        match mode {
+            DurabilityConfig::Standard if !self.is_empty() => DurabilityConfig::StandardSync {
+                interval_ms: self.interval_ms.unwrap_or(DEFAULT_SYNC_INTERVAL_MS),
+                batch_size: self.batch_size.unwrap_or(DEFAULT_SYNC_BATCH_SIZE),
+            },
+            other => other,
+        }
+    }
+
+    /// The settings as `key=value,key=value`, the form `--strata-config`
+    /// takes. `None` when empty.
+    pub fn spec(&self) -> Option<String> {
+        let settings: Vec<String> = [(SYNC_INTERVAL_KEY, self.interval_ms), (SYNC_BATCH_KEY, self.batch_size)]
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|v| format!("{}={}", key, v)))
+            .collect();
+        (!settings.is_empty()).then(|| settings.join(","))
     }
 }
 
@@ -259,7 +475,50 @@ pub fn kv_value_sized(size: ValueSize) -> Value {
 /// Database wrapper that keeps temp directories alive for disk-backed modes.
 pub struct BenchDb {
     pub db: Strata,
-    _temp_dir: Option<TempDir>,
+    temp_dir: Option<TempDir>,
+}
+
+impl BenchDb {
+    /// Directory holding a disk-backed database's files; `None` in cache mode.
+    pub fn data_dir(&self) -> Option<&std::path::Path> {
+        self.temp_dir.as_ref().map(|d| d.path())
+    }
+
+    /// Close the database and open it again from the same directory, as a
+    /// restarted process would. The directory (and its `strata.toml`) is kept
+    /// across the reopen. Panics in cache mode, which has nothing on disk.
+    pub fn reopen(self) -> BenchDb {
+        let BenchDb { db, temp_dir } = self;
+        let temp_dir = temp_dir.expect("cache-mode database has nothing on disk to reopen");
+        drop(db);
+        let strata = Strata::open(temp_dir.path()).expect("failed to reopen database");
+        BenchDb {
+            db: strata,
+            temp_dir: Some(temp_dir),
+        }
+    }
+}
+
+/// Total size in bytes of the files under `path`, recursively. Entries that
+/// can't be read count as zero.
+pub fn dir_size_bytes(path: &std::path::Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| match e.metadata() {
+            Ok(m) if m.is_dir() => dir_size_bytes(&e.path()),
+            Ok(m) => m.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// A new, empty directory for one database under [`cli::db_root`]
+/// (`--db-path`, or the system temp directory). Deleted when dropped.
+pub fn db_temp_dir() -> TempDir {
+    TempDir::new_in(cli::db_root()).expect("failed to create database directory")
 }
 
 /// Create a database configured for the given durability mode.
@@ -271,35 +530,50 @@ pub fn create_db(config: DurabilityConfig) -> BenchDb {
             let strata = Strata::cache().expect("failed to create cache database");
             BenchDb {
                 db: strata,
-                _temp_dir: None,
-            }
-        }
-        DurabilityConfig::Standard => {
-            let temp_dir = TempDir::new().expect("failed to create temp dir");
-            let strata = Strata::open(temp_dir.path())
-                .expect("failed to open standard database");
-            BenchDb {
-                db: strata,
-                _temp_dir: Some(temp_dir),
+                temp_dir: None,
             }
         }
-        DurabilityConfig::Always => {
-            let temp_dir = TempDir::new().expect("failed to create temp dir");
-            std::fs::write(
-                temp_dir.path().join("strata.toml"),
-                "durability = \"always\"\n",
-            )
-            .expect("failed to write always config");
+        DurabilityConfig::Standard | DurabilityConfig::StandardSync { .. } | DurabilityConfig::Always => {
+            let temp_dir = db_temp_dir();
+            write_strata_toml(temp_dir.path(), config);
             let strata = Strata::open(temp_dir.path())
-                .expect("failed to open always database");
+                .unwrap_or_else(|e| panic!("failed to open {} database: {:?}", config, e));
             BenchDb {
                 db: strata,
-                _temp_dir: Some(temp_dir),
+                temp_dir: Some(temp_dir),
             }
         }
     }
 }
 
+/// Open a disk-backed database in `dir`, which the caller owns. Unlike
+/// [`create_db`], nothing is deleted when the handle is dropped, so the
+/// directory can be reopened by another process. Panics in cache mode.
+pub fn open_db_in(dir: &std::path::Path, config: DurabilityConfig) -> Strata {
+    if let DurabilityConfig::Cache = config {
+        panic!("cache-mode databases have no directory");
+    }
+    write_strata_toml(dir, config);
+    Strata::open(dir).expect("failed to open database")
+}
+
+/// Write `dir/strata.toml` for a database in `config` mode. Standard mode
+/// with the engine defaults writes nothing.
+fn write_strata_toml(dir: &std::path::Path, config: DurabilityConfig) {
+    let toml = match config {
+        DurabilityConfig::Cache | DurabilityConfig::Standard => return,
+        DurabilityConfig::StandardSync {
+            interval_ms,
+            batch_size,
+        } => format!(
+            "durability = \"standard\"\n{} = {}\n{} = {}\n",
+            SYNC_INTERVAL_KEY, interval_ms, SYNC_BATCH_KEY, batch_size
+        ),
+        DurabilityConfig::Always => "durability = \"always\"\n".to_string(),
+    };
+    std::fs::write(dir.join("strata.toml"), toml).expect("failed to write strata.toml");
+}
+
 // =============================================================================
 // Data Generators
 // =============================================================================
@@ -346,7 +620,7 @@ pub fn json_document(i: u64) -> Value {
     map.insert("id".to_string(), Value::Int(i as i64));
     map.insert("name".to_string(), Value::String(format!("doc_{}", i)));
     map.insert("score".to_string(), Value::Float((i as f64) * 0.1));
-    map.insert("active".to_string(), Value::Bool(i % 2 == 0));
+    map.insert("active".to_string(), Value::Bool(i.is_multiple_of(2)));
     map.insert(
         "tags".to_string(),
         Value::Array(vec![
@@ -442,3 +716,28 @@ pub fn measure_with_counters<F: FnMut()>(
     let delta = counter_delta(&before, &after);
     (percentiles, delta)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::clock::MockClock;
+    use super::*;
+
+    #[test]
+    fn test_measure_for_stops_at_the_deadline() {
+        // 3 ms per call: calls start at 0, 3, ..., 9 ms, and the one due at 12 ms is skipped
+        let clock = MockClock::new();
+        let p = measure(&clock, None, Some(Duration::from_millis(10)), || {
+            clock.advance(Duration::from_millis(3))
+        });
+        assert_eq!(p.samples, 4);
+        assert_eq!(clock.elapsed(), Duration::from_millis(12));
+    }
+
+    #[test]
+    fn test_measure_count_ignores_the_clock() {
+        let clock = MockClock::new();
+        let p = measure(&clock, Some(25), None, || {});
+        assert_eq!(p.samples, 25);
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,227 @@
+//! Hardware performance counters for measured sections.
+//!
+//! Enabled with the `perf-counters` feature, Linux only. A [`Section`] opens
+//! four `perf_event_open` counters — instructions, cycles, branch misses and
+//! last-level cache misses — when it starts and reads them when it finishes.
+//! Without the feature, on other platforms, or when the kernel refuses the
+//! counters (`perf_event_paranoid`, a VM without a PMU), [`start`] returns
+//! `None`, so call sites need no cfg of their own.
+//!
+//! Counters follow the calling thread only and exclude kernel time, so they
+//! work unprivileged at `perf_event_paranoid` 2. Work Strata hands to its
+//! background threads isn't counted, unlike the process-wide `alloc-count`
+//! figures. Counts are scaled up if the kernel had to multiplex the group.
+
+/// Counter totals for one section.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerfCounts {
+    pub instructions: u64,
+    pub cycles: u64,
+    pub branch_misses: u64,
+    /// `PERF_COUNT_HW_CACHE_MISSES`, which the kernel maps to last-level
+    /// cache misses on most CPUs.
+    pub llc_misses: u64,
+}
+
+/// [`PerfCounts`] divided by the number of operations in the section.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PerfPerOp {
+    pub instructions: f64,
+    pub cycles: f64,
+    pub branch_misses: f64,
+    pub llc_misses: f64,
+}
+
+impl PerfCounts {
+    /// Each counter over `ops` (at least 1).
+    pub fn per_op(&self, ops: usize) -> PerfPerOp {
+        let ops = ops.max(1) as f64;
+        PerfPerOp {
+            instructions: self.instructions as f64 / ops,
+            cycles: self.cycles as f64 / ops,
+            branch_misses: self.branch_misses as f64 / ops,
+            llc_misses: self.llc_misses as f64 / ops,
+        }
+    }
+}
+
+impl PerfPerOp {
+    /// Instructions per cycle.
+    pub fn ipc(&self) -> f64 {
+        if self.cycles > 0.0 {
+            self.instructions / self.cycles
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Open counters on the calling thread. Finish on the same thread.
+pub struct Section {
+    #[cfg(all(feature = "perf-counters", target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    counters: sys::Group,
+}
+
+/// Start counting, or `None` if counters aren't available. The first
+/// failure is printed once to stderr.
+pub fn start() -> Option<Section> {
+    #[cfg(all(feature = "perf-counters", target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        match sys::Group::open() {
+            Ok(counters) => Some(Section { counters }),
+            Err(e) => {
+                static WARNED: std::sync::Once = std::sync::Once::new();
+                WARNED.call_once(|| eprintln!("  perf counters unavailable: {}", e));
+                None
+            }
+        }
+    }
+    #[cfg(not(all(feature = "perf-counters", target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+    {
+        None
+    }
+}
+
+impl Section {
+    /// Stop counting and return the totals since [`start`], or `None` if a
+    /// counter couldn't be read.
+    pub fn finish(self) -> Option<PerfCounts> {
+        #[cfg(all(feature = "perf-counters", target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+        {
+            self.counters.read().ok()
+        }
+        #[cfg(not(all(feature = "perf-counters", target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+        {
+            None
+        }
+    }
+}
+
+#[cfg(all(feature = "perf-counters", target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod sys {
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::fd::FromRawFd;
+
+    use super::PerfCounts;
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_PERF_EVENT_OPEN: i64 = 298;
+    #[cfg(target_arch = "aarch64")]
+    const SYS_PERF_EVENT_OPEN: i64 = 241;
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+
+    const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+    const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+    const PERF_FLAG_FD_CLOEXEC: u64 = 1 << 3;
+
+    /// `struct perf_event_attr` up to `config1` (`PERF_ATTR_SIZE_VER0`);
+    /// the kernel zero-fills the rest.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+    }
+
+    unsafe extern "C" {
+        fn syscall(num: i64, ...) -> i64;
+    }
+
+    /// Cycles leads the group, so all four are scheduled together.
+    pub struct Group {
+        cycles: File,
+        instructions: File,
+        branch_misses: File,
+        llc_misses: File,
+    }
+
+    impl Group {
+        pub fn open() -> io::Result<Group> {
+            let cycles = open_counter(PERF_COUNT_HW_CPU_CYCLES, -1)?;
+            let leader = std::os::fd::AsRawFd::as_raw_fd(&cycles);
+            Ok(Group {
+                instructions: open_counter(PERF_COUNT_HW_INSTRUCTIONS, leader)?,
+                branch_misses: open_counter(PERF_COUNT_HW_BRANCH_MISSES, leader)?,
+                llc_misses: open_counter(PERF_COUNT_HW_CACHE_MISSES, leader)?,
+                cycles,
+            })
+        }
+
+        pub fn read(mut self) -> io::Result<PerfCounts> {
+            Ok(PerfCounts {
+                instructions: read_scaled(&mut self.instructions)?,
+                cycles: read_scaled(&mut self.cycles)?,
+                branch_misses: read_scaled(&mut self.branch_misses)?,
+                llc_misses: read_scaled(&mut self.llc_misses)?,
+            })
+        }
+    }
+
+    fn open_counter(config: u64, group_fd: i32) -> io::Result<File> {
+        let attr = PerfEventAttr {
+            kind: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+            flags: EXCLUDE_KERNEL | EXCLUDE_HV,
+            ..Default::default()
+        };
+        // SAFETY: `attr` is a valid perf_event_attr of the size it declares;
+        // pid 0 and cpu -1 count the calling thread on any CPU.
+        let fd = unsafe {
+            syscall(SYS_PERF_EVENT_OPEN, &attr as *const PerfEventAttr, 0i32, -1i32, group_fd, PERF_FLAG_FD_CLOEXEC)
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the kernel just returned this fd and nothing else owns it.
+        Ok(unsafe { File::from_raw_fd(fd as i32) })
+    }
+
+    /// Read one counter, scaled by enabled/running time in case it was
+    /// multiplexed off the PMU for part of the section.
+    fn read_scaled(file: &mut File) -> io::Result<u64> {
+        let mut buf = [0u8; 24];
+        file.read_exact(&mut buf)?;
+        let word = |i: usize| u64::from_ne_bytes(buf[i * 8..i * 8 + 8].try_into().unwrap());
+        let (value, enabled, running) = (word(0), word(1), word(2));
+        if running == 0 {
+            return Ok(0);
+        }
+        Ok((value as u128 * enabled as u128 / running as u128) as u64)
+    }
+}
+
+#[cfg(all(test, feature = "perf-counters", target_os = "linux"))]
+mod tests {
+    use super::start;
+
+    #[test]
+    fn test_counts_instructions() {
+        // Hosts without a PMU (most CI VMs) can't open counters at all
+        let Some(section) = start() else { return };
+        let mut x = 0u64;
+        for i in 0..100_000u64 {
+            x = std::hint::black_box(x.wrapping_add(i));
+        }
+        let counts = section.finish().unwrap();
+        assert!(counts.instructions >= 100_000);
+        assert!(counts.cycles > 0);
+    }
+}
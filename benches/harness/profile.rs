@@ -0,0 +1,111 @@
+//! CPU profiling of measured sections, written out as a flamegraph.
+//!
+//! Enabled with the `profile` feature and turned on per run with
+//! `--profile` (see [`super::cli`]). A [`Section`] samples every thread of
+//! the process with pprof while it's open; the stacks from all sections are
+//! folded together, and [`super::recorder::ResultRecorder::save`] writes them
+//! as an SVG next to the results JSON. Setup, population and validation
+//! between sections don't appear in the graph.
+//!
+//! Without the feature or the flag, [`start`] returns `None`, so call sites
+//! need no cfg of their own. Only one section samples at a time: one opened
+//! while another is running (a nested or concurrent measurement) returns
+//! `None` and its time lands in the outer one.
+
+#[cfg(feature = "profile")]
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+#[cfg(feature = "profile")]
+use std::sync::Mutex;
+
+/// Sampling rate. Prime, so it doesn't run in lockstep with periodic work.
+#[cfg(feature = "profile")]
+const FREQUENCY_HZ: i32 = 997;
+
+/// Folded stacks (`thread;outer;...;inner`) and their sample counts, summed
+/// over every finished section.
+#[cfg(feature = "profile")]
+static FOLDED: Mutex<BTreeMap<String, isize>> = Mutex::new(BTreeMap::new());
+
+/// A running profile. Finish it when the measured phase ends.
+pub struct Section {
+    #[cfg(feature = "profile")]
+    guard: pprof::ProfilerGuard<'static>,
+}
+
+/// Start sampling if `--profile` was given, else `None`.
+pub fn start() -> Option<Section> {
+    #[cfg(feature = "profile")]
+    {
+        if !super::cli::profiling() {
+            return None;
+        }
+        pprof::ProfilerGuardBuilder::default()
+            .frequency(FREQUENCY_HZ)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()
+            .ok()
+            .map(|guard| Section { guard })
+    }
+    #[cfg(not(feature = "profile"))]
+    {
+        None
+    }
+}
+
+impl Section {
+    /// Stop sampling and add this section's stacks to the run's flamegraph.
+    pub fn finish(self) {
+        #[cfg(feature = "profile")]
+        {
+            let report = match self.guard.report().build() {
+                Ok(report) => report,
+                Err(e) => {
+                    eprintln!("  profile dropped: {}", e);
+                    return;
+                }
+            };
+            let mut folded = FOLDED.lock().unwrap_or_else(|e| e.into_inner());
+            for (frames, count) in &report.data {
+                let thread = if frames.thread_name.is_empty() {
+                    frames.thread_id.to_string()
+                } else {
+                    frames.thread_name.clone()
+                };
+                // Frames are innermost first; a flamegraph wants the root first
+                let stack = std::iter::once(thread)
+                    .chain(frames.frames.iter().rev().flat_map(|f| f.iter().rev().map(|s| s.to_string())))
+                    .collect::<Vec<_>>()
+                    .join(";");
+                *folded.entry(stack).or_insert(0) += count;
+            }
+        }
+    }
+}
+
+/// Write the stacks collected so far to `path` as a flamegraph SVG.
+/// Returns `false`, writing nothing, if no section collected any.
+pub fn write_flamegraph(path: &Path) -> io::Result<bool> {
+    #[cfg(feature = "profile")]
+    {
+        let folded = FOLDED.lock().unwrap_or_else(|e| e.into_inner());
+        if folded.is_empty() {
+            return Ok(false);
+        }
+        let lines: Vec<String> = folded
+            .iter()
+            .map(|(stack, count)| format!("{} {}", stack, count))
+            .collect();
+        let file = std::fs::File::create(path)?;
+        let mut options = pprof::flamegraph::Options::default();
+        pprof::flamegraph::from_lines(&mut options, lines.iter().map(String::as_str), file)
+            .map_err(io::Error::other)?;
+        Ok(true)
+    }
+    #[cfg(not(feature = "profile"))]
+    {
+        let _ = path;
+        Ok(false)
+    }
+}
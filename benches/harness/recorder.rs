@@ -1,15 +1,24 @@
 //! Result recorder for saving benchmark results to JSON files.
 //!
-//! Creates JSON files in the `results/` directory following the shared schema
-//! defined in `strata_benchmarks::schema`.
+//! Creates JSON files in the `results/` directory (or `--out-dir`, see
+//! [`super::cli`]) following the shared schema
+//! defined in `strata_benchmarks::schema`. Resource usage is sampled in the
+//! background from creation to `save()` (see [`super::resources`]).
 
+use super::cli::{db_root, results_dir};
+use super::environment::capture_environment;
+use super::profile;
+use super::resources::{ResourceSampler, DEFAULT_INTERVAL};
 use super::{read_cpu_model, read_total_ram_gb, Percentiles};
 use strata_benchmarks::schema::*;
 use stratadb::WalCounters;
 
+use std::any::Any;
 use std::collections::HashMap;
 use std::io;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
 use std::time::SystemTime;
 
 /// Accumulates benchmark results and writes them to a JSON file.
@@ -17,12 +26,14 @@ pub struct ResultRecorder {
     category: String,
     metadata: RunMetadata,
     results: Vec<BenchmarkResult>,
+    sampler: ResourceSampler,
 }
 
 impl ResultRecorder {
     /// Create a new recorder for the given category.
     ///
-    /// Captures metadata (hardware, git, timestamp) at construction time.
+    /// Captures metadata (hardware, git, timestamp) at construction time
+    /// and starts sampling resource usage.
     pub fn new(category: &str) -> Self {
         Self {
             category: category.to_string(),
@@ -34,8 +45,11 @@ impl ResultRecorder {
                 sdk: "rust".to_string(),
                 sdk_version: env!("CARGO_PKG_VERSION").to_string(),
                 hardware: capture_hardware(),
+                // Standard/Always databases live in TempDirs under here
+                environment: Some(capture_environment(&db_root())),
             },
             results: Vec::new(),
+            sampler: ResourceSampler::start(DEFAULT_INTERVAL),
         }
     }
 
@@ -61,32 +75,122 @@ impl ResultRecorder {
             _ => (None, None),
         };
 
+        let perf = p.perf_per_op();
+
         self.results.push(BenchmarkResult {
             benchmark: name.to_string(),
             category: self.category.clone(),
             parameters,
             metrics: BenchmarkMetrics {
+                ops_per_sec: Some(p.ops_per_sec()),
                 p50_ns: Some(p.p50.as_nanos() as u64),
                 p95_ns: Some(p.p95.as_nanos() as u64),
                 p99_ns: Some(p.p99.as_nanos() as u64),
+                p999_ns: Some(p.p999.as_nanos() as u64),
+                p9999_ns: Some(p.p9999.as_nanos() as u64),
                 min_ns: Some(p.min.as_nanos() as u64),
                 max_ns: Some(p.max.as_nanos() as u64),
                 samples: Some(p.samples as u64),
                 wal_appends_per_op,
                 wal_syncs_per_op,
+                allocs_per_op: p.allocs_per_op(),
+                alloc_bytes_per_op: p.alloc_bytes_per_op(),
+                instructions_per_op: perf.map(|c| c.instructions),
+                cycles_per_op: perf.map(|c| c.cycles),
+                branch_misses_per_op: perf.map(|c| c.branch_misses),
+                llc_misses_per_op: perf.map(|c| c.llc_misses),
                 ..Default::default()
             },
+            artifacts: Vec::new(),
+            spikes: p.spikes.clone(),
+            status: ResultStatus::Success,
         });
     }
 
-    /// Write all accumulated results to a JSON file in `results/`.
+    /// Record a configuration that produced no measurements. It's saved
+    /// with its status and empty metrics, so reports show the failure.
+    pub fn record_failure(
+        &mut self,
+        name: &str,
+        parameters: HashMap<String, serde_json::Value>,
+        status: ResultStatus,
+    ) {
+        eprintln!("  FAILED {}: {}", name, status);
+        self.results.push(BenchmarkResult::failed(
+            name.to_string(),
+            self.category.clone(),
+            parameters,
+            status,
+        ));
+    }
+
+    /// Run one configuration. If it panics, the panic is recorded as an
+    /// `error` result named `name` and `None` is returned, so the remaining
+    /// configurations still run and everything recorded so far is saved.
+    pub fn guard<T>(
+        &mut self,
+        name: &str,
+        parameters: HashMap<String, serde_json::Value>,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> Option<T> {
+        match panic::catch_unwind(AssertUnwindSafe(|| f(&mut *self))) {
+            Ok(value) => Some(value),
+            Err(payload) => {
+                let message = panic_message(&*payload);
+                self.record_failure(name, parameters, ResultStatus::Error { message });
+                None
+            }
+        }
+    }
+
+    /// [`guard`](Self::guard) for criterion benches, which keep their
+    /// recorder in a static so the bench functions can record into it. The
+    /// lock is free while `f` runs and is only taken to record a panic.
+    pub fn guard_shared(
+        shared: &Mutex<Option<Self>>,
+        name: &str,
+        parameters: HashMap<String, serde_json::Value>,
+        f: impl FnOnce(),
+    ) {
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(f)) {
+            let message = panic_message(&*payload);
+            let mut shared = shared.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(recorder) = shared.as_mut() {
+                recorder.record_failure(name, parameters, ResultStatus::Error { message });
+            }
+        }
+    }
+
+    /// Number of recorded results that didn't succeed.
+    pub fn failure_count(&self) -> usize {
+        self.results.iter().filter(|r| !r.status.is_success()).count()
+    }
+
+    /// Attach an artifact to every recorded result named `benchmark`.
+    /// `path` is relative to `results/`. Returns how many results matched.
+    pub fn attach_artifact(&mut self, benchmark: &str, kind: &str, path: &str) -> usize {
+        let mut matched = 0;
+        for r in self.results.iter_mut().filter(|r| r.benchmark == benchmark) {
+            r.artifacts.push(Artifact {
+                kind: kind.to_string(),
+                path: path.to_string(),
+            });
+            matched += 1;
+        }
+        matched
+    }
+
+    /// Write all accumulated results to a JSON file in the results directory.
     ///
-    /// File naming: `<category>-<timestamp>-<commit>.json`
+    /// File naming: `<category>-<timestamp>-<commit>.json`. With `--profile`,
+    /// the flamegraph of the measured phases goes beside it as `.svg`.
     pub fn save(self) -> io::Result<PathBuf> {
+        let failures = self.failure_count();
         let report = BenchmarkReport {
             schema_version: 1,
             metadata: self.metadata.clone(),
             results: self.results,
+            resources: Some(self.sampler.finish()),
         };
 
         // Build filename
@@ -99,17 +203,45 @@ impl ResultRecorder {
         let ts = self.metadata.timestamp.replace(':', "-");
         let filename = format!("{}-{}-{}.json", self.category, ts, commit);
 
-        let results_dir = PathBuf::from("results");
+        let results_dir = results_dir();
         std::fs::create_dir_all(&results_dir)?;
         let path = results_dir.join(&filename);
 
         let json = serde_json::to_string_pretty(&report)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            .map_err(io::Error::other)?;
         std::fs::write(&path, json)?;
 
         eprintln!("Results saved to {}", path.display());
+        let svg = path.with_extension("svg");
+        match profile::write_flamegraph(&svg) {
+            Ok(true) => eprintln!("Flamegraph saved to {}", svg.display()),
+            Ok(false) => {}
+            Err(e) => eprintln!("Warning: flamegraph not written: {}", e),
+        }
+        if failures > 0 {
+            eprintln!("  {} of {} results did not succeed", failures, report.results.len());
+        }
         Ok(path)
     }
+
+    /// [`save`](Self::save), then exit with status 1 if any result didn't
+    /// succeed, so a failed validation fails the run only after its report
+    /// is written.
+    pub fn save_or_exit(self) {
+        let failed = self.failure_count() > 0;
+        let _ = self.save();
+        if failed {
+            std::process::exit(1);
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panicked".to_string())
 }
 
 // ---------------------------------------------------------------------------
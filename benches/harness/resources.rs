@@ -0,0 +1,180 @@
+//! Background sampling of the bench process's resource usage.
+//!
+//! A [`ResourceSampler`] thread wakes every interval and records RSS, CPU
+//! utilization and storage I/O for the interval just ended. Every
+//! [`ResultRecorder`](super::recorder::ResultRecorder) runs one from creation
+//! to `save()` and writes the series into the result file's `resources`, so a
+//! memory climb or an I/O burst can be lined up with the results around it.
+//!
+//! CPU and I/O come from `/proc/self` and are omitted elsewhere; RSS is
+//! also read on macOS. I/O counts what reached the storage layer
+//! (`read_bytes`/`write_bytes` in `/proc/self/io`), so page-cache hits don't
+//! count and writes show up when they're written back, not when issued.
+//!
+//! Sample timestamps come from a [`Clock`]. The thread waits on its stop
+//! channel rather than sleeping on the clock, so `finish()` returns at once
+//! instead of waiting out the interval.
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use strata_benchmarks::schema::{ResourceSample, ResourceUsage};
+
+use super::clock::{Clock, RealClock};
+use super::metrics::snapshot_process_metrics;
+use super::read_rss_bytes;
+
+/// Default time between samples.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A running sampler thread. [`ResourceSampler::finish`] stops it and
+/// returns the series.
+pub struct ResourceSampler {
+    interval: Duration,
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<Vec<ResourceSample>>,
+}
+
+impl ResourceSampler {
+    /// Start sampling every `interval`.
+    pub fn start(interval: Duration) -> Self {
+        Self::with_clock(RealClock::start(), interval)
+    }
+
+    /// Start sampling every `interval`, timestamped on `clock`.
+    pub fn with_clock(clock: impl Clock + Send + 'static, interval: Duration) -> Self {
+        let (stop, stopped) = mpsc::channel();
+        // Read before spawning so the series starts when this returns
+        let start = clock.elapsed();
+        let mut last = Counters::read(start);
+        let thread = std::thread::Builder::new()
+            .name("resource-sampler".into())
+            .spawn(move || {
+                let mut samples = Vec::new();
+                loop {
+                    let done = match stopped.recv_timeout(interval) {
+                        Err(RecvTimeoutError::Timeout) => false,
+                        Ok(()) | Err(RecvTimeoutError::Disconnected) => true,
+                    };
+                    let now = Counters::read(clock.elapsed());
+                    // A run that stops right after a sample adds no empty one
+                    if !done || now.at - last.at >= Duration::from_millis(1) {
+                        samples.push(now.since(&last, start));
+                    }
+                    if done {
+                        return samples;
+                    }
+                    last = now;
+                }
+            })
+            .expect("failed to spawn resource sampler");
+        ResourceSampler {
+            interval,
+            stop,
+            thread,
+        }
+    }
+
+    /// Take a last sample for the interval in progress, stop the thread, and
+    /// summarize the series.
+    pub fn finish(self) -> ResourceUsage {
+        let _ = self.stop.send(());
+        let samples = self.thread.join().unwrap_or_default();
+        ResourceUsage::from_samples(self.interval.as_millis() as u64, samples)
+    }
+}
+
+/// Cumulative counters at one point on the sampler's clock.
+struct Counters {
+    at: Duration,
+    rss_bytes: Option<u64>,
+    cpu_ms: Option<u64>,
+    io: Option<(u64, u64)>,
+}
+
+impl Counters {
+    fn read(at: Duration) -> Self {
+        let cpu = snapshot_process_metrics();
+        Counters {
+            at,
+            rss_bytes: read_rss_bytes(),
+            // Zero off Linux, where there's no /proc to read it from
+            cpu_ms: cfg!(target_os = "linux").then_some(cpu.user_time_ms + cpu.system_time_ms),
+            io: std::fs::read_to_string("/proc/self/io")
+                .ok()
+                .and_then(|s| parse_proc_io(&s)),
+        }
+    }
+
+    /// The interval from `prev` to `self`.
+    fn since(&self, prev: &Counters, start: Duration) -> ResourceSample {
+        let wall = self.at - prev.at;
+        let cpu_pct = match (prev.cpu_ms, self.cpu_ms) {
+            (Some(a), Some(b)) if !wall.is_zero() => {
+                Some(b.saturating_sub(a) as f64 / (wall.as_secs_f64() * 1000.0) * 100.0)
+            }
+            _ => None,
+        };
+        let io = prev
+            .io
+            .zip(self.io)
+            .map(|((r0, w0), (r1, w1))| (r1.saturating_sub(r0), w1.saturating_sub(w0)));
+        ResourceSample {
+            elapsed_ms: (self.at - start).as_millis() as u64,
+            interval_ms: wall.as_millis() as u64,
+            rss_bytes: self.rss_bytes,
+            cpu_pct,
+            read_bytes: io.map(|(r, _)| r),
+            write_bytes: io.map(|(_, w)| w),
+        }
+    }
+}
+
+/// `(read_bytes, write_bytes)` from the contents of `/proc/<pid>/io`.
+fn parse_proc_io(contents: &str) -> Option<(u64, u64)> {
+    let field = |name: &str| {
+        contents
+            .lines()
+            .find_map(|l| l.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|v| v.trim().parse().ok())
+    };
+    Some((field("read_bytes")?, field("write_bytes")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::clock::MockClock;
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_proc_io() {
+        let io = "rchar: 3291\nwchar: 512\nsyscr: 9\nsyscw: 2\nread_bytes: 4096\nwrite_bytes: 12288\ncancelled_write_bytes: 0\n";
+        assert_eq!(parse_proc_io(io), Some((4096, 12288)));
+        assert_eq!(parse_proc_io("rchar: 1\n"), None);
+    }
+
+    #[test]
+    fn test_finish_takes_a_final_sample() {
+        let sampler = ResourceSampler::start(Duration::from_secs(60));
+        std::thread::sleep(Duration::from_millis(20));
+        let usage = sampler.finish();
+        assert_eq!(usage.interval_ms, 60_000);
+        assert_eq!(usage.samples.len(), 1);
+        assert!(usage.samples[0].elapsed_ms >= 20);
+        #[cfg(target_os = "linux")]
+        assert!(usage.peak_rss_bytes.unwrap() > 0);
+    }
+
+    #[test]
+    fn test_final_sample_spans_the_clock() {
+        let clock = Arc::new(MockClock::new());
+        let sampler = ResourceSampler::with_clock(Arc::clone(&clock), Duration::from_secs(60));
+        clock.advance(Duration::from_millis(1500));
+        let usage = sampler.finish();
+        assert_eq!(usage.samples.len(), 1);
+        assert_eq!(usage.samples[0].elapsed_ms, 1500);
+        assert_eq!(usage.samples[0].interval_ms, 1500);
+    }
+}
@@ -2,9 +2,15 @@
 //!
 //! Provides the core `run_scaling_experiment` function that spawns N threads,
 //! coordinates warmup and measurement phases via barriers, and aggregates
-//! per-thread results into a single `ScalingResult`.
+//! per-thread results into a single `ScalingResult`. The warmup is fixed or
+//! runs until throughput is steady (see [`super::warmup`]).
 
+use super::affinity::pin_worker;
+use super::clock::{Clock, RealClock};
+use super::warmup::{self, Warmup, WarmupReport};
 use super::metrics::{delta_process_metrics, snapshot_process_metrics, ProcessMetrics};
+use super::profile;
+use strata_benchmarks::fmt::{fmt_duration, fmt_num, fmt_ops};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Barrier};
 use std::time::{Duration, Instant};
@@ -43,6 +49,7 @@ pub struct ScalingResult {
     pub p99: Duration,
     pub cpu: ProcessMetrics,
     pub wal: WalDelta,
+    pub warmup: WarmupReport,
 }
 
 /// Delta of WAL counters between before and after measurement.
@@ -167,7 +174,9 @@ impl ReservoirSampler {
 ///
 /// * `strata` - The Strata instance to benchmark. Per-thread handles are created via `new_handle()`.
 /// * `num_threads` - Number of worker threads to spawn.
-/// * `warmup_secs` - Warmup duration (ops run but aren't counted).
+/// * `warmup` - Warmup before measuring (ops run but aren't counted). A
+///   steady-state warmup runs the threads in short windows until throughput
+///   settles; how long it took is in `ScalingResult::warmup`.
 /// * `measure_secs` - Measurement duration.
 /// * `work_fn` - Closure called by each thread. Receives `(thread_id, Strata, stop_flag)`.
 ///   Each thread gets its own `Strata` instance created via `strata.new_handle()`.
@@ -175,7 +184,7 @@ impl ReservoirSampler {
 pub fn run_scaling_experiment<F>(
     strata: &Strata,
     num_threads: usize,
-    warmup_secs: u64,
+    warmup: &Warmup,
     measure_secs: u64,
     work_fn: F,
 ) -> ScalingResult
@@ -186,31 +195,10 @@ where
     let cores = physical_cores();
 
     // --- Warmup phase ---
-    {
-        let barrier = Arc::new(Barrier::new(num_threads + 1));
-        let stop = Arc::new(AtomicBool::new(false));
-        let mut handles = Vec::with_capacity(num_threads);
-
-        for tid in 0..num_threads {
-            let thread_strata = strata.new_handle().expect("failed to create Strata for thread");
-            let barrier = Arc::clone(&barrier);
-            let stop = Arc::clone(&stop);
-            let work_fn = Arc::clone(&work_fn);
-
-            handles.push(std::thread::spawn(move || {
-                barrier.wait();
-                work_fn(tid, thread_strata, stop)
-            }));
-        }
-
-        barrier.wait(); // release all threads
-        std::thread::sleep(Duration::from_secs(warmup_secs));
-        stop.store(true, Ordering::SeqCst);
-
-        for h in handles {
-            let _ = h.join();
-        }
-    }
+    let clock = RealClock::start();
+    let warmup = warmup::run(&clock, warmup, |window| {
+        run_warmup_window(&clock, strata, num_threads, window, &work_fn)
+    });
 
     // --- Measurement phase ---
     let barrier = Arc::new(Barrier::new(num_threads + 1));
@@ -228,6 +216,7 @@ where
         let work_fn = Arc::clone(&work_fn);
 
         handles.push(std::thread::spawn(move || {
+            pin_worker(tid);
             barrier.wait();
             work_fn(tid, thread_strata, stop)
         }));
@@ -235,8 +224,12 @@ where
 
     barrier.wait(); // release all threads
     let measure_start = Instant::now();
-    std::thread::sleep(Duration::from_secs(measure_secs));
+    let profile_section = profile::start();
+    clock.sleep(Duration::from_secs(measure_secs));
     stop.store(true, Ordering::SeqCst);
+    if let Some(section) = profile_section {
+        section.finish();
+    }
 
     let mut thread_results = Vec::with_capacity(num_threads);
     for h in handles {
@@ -300,9 +293,48 @@ where
         p99,
         cpu,
         wal,
+        warmup,
     }
 }
 
+/// Run the threads for `duration` of `clock` without measuring; returns
+/// their throughput over the window.
+fn run_warmup_window<F>(
+    clock: &impl Clock,
+    strata: &Strata,
+    num_threads: usize,
+    duration: Duration,
+    work_fn: &Arc<F>,
+) -> f64
+where
+    F: Fn(usize, Strata, Arc<AtomicBool>) -> ThreadResult + Send + Sync + 'static,
+{
+    let barrier = Arc::new(Barrier::new(num_threads + 1));
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut handles = Vec::with_capacity(num_threads);
+
+    for tid in 0..num_threads {
+        let thread_strata = strata.new_handle().expect("failed to create Strata for thread");
+        let barrier = Arc::clone(&barrier);
+        let stop = Arc::clone(&stop);
+        let work_fn = Arc::clone(work_fn);
+
+        handles.push(std::thread::spawn(move || {
+            pin_worker(tid);
+            barrier.wait();
+            work_fn(tid, thread_strata, stop)
+        }));
+    }
+
+    barrier.wait(); // release all threads
+    let start = clock.elapsed();
+    clock.sleep(duration);
+    stop.store(true, Ordering::SeqCst);
+
+    let ops: u64 = handles.into_iter().filter_map(|h| h.join().ok()).map(|r| r.ops).sum();
+    ops as f64 / (clock.elapsed() - start).as_secs_f64()
+}
+
 // ---------------------------------------------------------------------------
 // Percentile computation
 // ---------------------------------------------------------------------------
@@ -323,45 +355,6 @@ fn compute_percentiles(latencies: &mut [Duration]) -> (Duration, Duration, Durat
 // Display helpers
 // ---------------------------------------------------------------------------
 
-/// Format a Duration for table display (human-readable).
-pub fn fmt_duration(d: Duration) -> String {
-    let nanos = d.as_nanos();
-    if nanos < 1_000 {
-        format!("{} ns", nanos)
-    } else if nanos < 1_000_000 {
-        format!("{:.1} us", nanos as f64 / 1_000.0)
-    } else if nanos < 1_000_000_000 {
-        format!("{:.1} ms", nanos as f64 / 1_000_000.0)
-    } else {
-        format!("{:.2} s", nanos as f64 / 1_000_000_000.0)
-    }
-}
-
-/// Format a large number with comma separators.
-pub fn fmt_num(n: u64) -> String {
-    let s = n.to_string();
-    let bytes: Vec<u8> = s.bytes().rev().collect();
-    let chunks: Vec<String> = bytes
-        .chunks(3)
-        .map(|chunk| {
-            chunk
-                .iter()
-                .rev()
-                .map(|&b| b as char)
-                .collect::<String>()
-        })
-        .collect::<Vec<_>>()
-        .into_iter()
-        .rev()
-        .collect();
-    chunks.join(",")
-}
-
-/// Format ops/sec with comma separators.
-pub fn fmt_ops(ops: f64) -> String {
-    fmt_num(ops as u64)
-}
-
 /// Print the header row for a scaling result table.
 pub fn print_table_header() {
     eprintln!(
@@ -400,8 +393,7 @@ pub fn print_table_row(r: &ScalingResult) {
 #[allow(unused_imports)]
 mod tests {
     use super::{
-        compute_percentiles, fmt_duration, fmt_num, parse_thread_counts, thread_counts,
-        ReservoirSampler, RESERVOIR_SIZE,
+        compute_percentiles, parse_thread_counts, thread_counts, ReservoirSampler, RESERVOIR_SIZE,
     };
     use std::time::Duration;
 
@@ -461,20 +453,4 @@ mod tests {
         assert_eq!(p95, Duration::from_micros(42));
         assert_eq!(p99, Duration::from_micros(42));
     }
-
-    #[test]
-    fn test_fmt_num() {
-        assert_eq!(fmt_num(0), "0");
-        assert_eq!(fmt_num(999), "999");
-        assert_eq!(fmt_num(1_000), "1,000");
-        assert_eq!(fmt_num(1_000_000), "1,000,000");
-    }
-
-    #[test]
-    fn test_fmt_duration_ranges() {
-        assert!(fmt_duration(Duration::from_nanos(500)).contains("ns"));
-        assert!(fmt_duration(Duration::from_micros(50)).contains("us"));
-        assert!(fmt_duration(Duration::from_millis(50)).contains("ms"));
-        assert!(fmt_duration(Duration::from_secs(2)).contains("s"));
-    }
 }
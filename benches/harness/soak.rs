@@ -0,0 +1,541 @@
+//! Soak mode: run a workload for hours and stream per-window samples.
+//!
+//! A normal run reports one set of percentiles for the whole run, which
+//! averages away slow degradation such as fragmentation, WAL growth, or a
+//! leak. In soak mode a benchmark runs its workload until `--duration`
+//! runs out, and every `--soak-interval` (one minute by default) appends
+//! one row per operation, plus an `all` row, to a JSONL or CSV stream:
+//!
+//! ```text
+//! {"bench":"ycsb/workload-a/100K-standard","window":3,"elapsed_s":240.0,"op":"all",
+//!  "count":51234,"ops_per_sec":853.9,"p50_ns":...,"p99_ns":...,"max_ns":...,
+//!  "entries":100000,"rss_bytes":...,"disk_bytes":...}
+//! ```
+//!
+//! The stream is flushed after every window, so it can be tailed or plotted
+//! while the run is in progress and survives a run that is killed.
+//!
+//! Latencies go into the same HDR histograms as every other measured loop
+//! (see [`super::hdr`]), so memory stays constant however long the run is
+//! and window percentiles are within 0.1% of the true value, as in the
+//! benches' other results. `max_ns` is exact.
+//!
+//! Callers pass the gauges (RSS, on-disk size, entry count) sampled at the
+//! end of a window.
+//!
+//! The run length and window boundaries are kept on a [`Clock`], so a test
+//! can close windows on virtual time.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use strata_benchmarks::fmt::{fmt_ns, fmt_num};
+
+use super::clock::{Clock, RealClock};
+use super::hdr::LatencyHistogram;
+
+/// Window length when `--soak-interval` isn't given.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Parse a duration such as `2h`, `30m`, `90s`, `1h30m` or `45` (seconds).
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim().to_ascii_lowercase();
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(secs) = s.parse::<u64>() {
+        return (secs > 0).then(|| Duration::from_secs(secs));
+    }
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        total += digits.parse::<u64>().ok()? * unit;
+        digits.clear();
+    }
+    if !digits.is_empty() || total == 0 {
+        return None;
+    }
+    Some(Duration::from_secs(total))
+}
+
+/// Format a duration for logs: `"2h"`, `"1h30m"`, `"1m30s"`, `"0s"`.
+pub fn fmt_span(d: Duration) -> String {
+    let secs = d.as_secs();
+    let mut out = String::new();
+    for (n, unit) in [(secs / 3600, "h"), (secs % 3600 / 60, "m"), (secs % 60, "s")] {
+        if n > 0 {
+            out.push_str(&format!("{}{}", n, unit));
+        }
+    }
+    if out.is_empty() {
+        out.push_str("0s");
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Windows
+// ---------------------------------------------------------------------------
+
+/// Process and dataset state sampled at the end of a window.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gauges {
+    /// Entries in the dataset (records, events).
+    pub entries: u64,
+    pub rss_bytes: Option<u64>,
+    pub disk_bytes: Option<u64>,
+}
+
+/// One operation's numbers for one window.
+#[derive(Debug, Clone)]
+pub struct OpSample {
+    pub op: &'static str,
+    pub count: u64,
+    pub ops_per_sec: f64,
+    pub p50_ns: u64,
+    pub p95_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+}
+
+/// A completed window. `ops[0]` is the `all` row.
+#[derive(Debug, Clone)]
+pub struct Window {
+    pub index: u64,
+    /// Time from the start of the soak to the end of the window.
+    pub elapsed: Duration,
+    pub ops: Vec<OpSample>,
+    pub gauges: Gauges,
+}
+
+impl Window {
+    pub fn all(&self) -> &OpSample {
+        &self.ops[0]
+    }
+
+    /// The row for `op` (`"all"` included), if it ran in this window.
+    pub fn op(&self, op: &str) -> Option<&OpSample> {
+        self.ops.iter().find(|s| s.op == op)
+    }
+
+    /// One progress line: `[   12m] 85,390 ops/s  p50=... p99=... max=...`.
+    pub fn progress_line(&self) -> String {
+        let all = self.all();
+        let mb = |b: Option<u64>| b.map(|b| format!("{}MB", b >> 20)).unwrap_or_else(|| "-".into());
+        format!(
+            "  [{:>6}] {:>10} ops/s  p50={:<10} p99={:<10} max={:<10} entries={}  rss={}  disk={}",
+            fmt_span(Duration::from_secs(self.elapsed.as_secs())),
+            fmt_num(all.ops_per_sec as u64),
+            fmt_ns(all.p50_ns),
+            fmt_ns(all.p99_ns),
+            fmt_ns(all.max_ns),
+            fmt_num(self.gauges.entries),
+            mb(self.gauges.rss_bytes),
+            mb(self.gauges.disk_bytes),
+        )
+    }
+}
+
+enum Format {
+    Jsonl,
+    Csv,
+}
+
+/// Drives a soak run and writes its time series.
+pub struct Soak<C: Clock = RealClock> {
+    bench: String,
+    duration: Duration,
+    interval: Duration,
+    clock: C,
+    window_start: Duration,
+    all: LatencyHistogram,
+    by_op: Vec<(&'static str, LatencyHistogram)>,
+    windows: Vec<Window>,
+    format: Format,
+    out: BufWriter<File>,
+    path: PathBuf,
+}
+
+impl Soak {
+    /// Start a soak of `bench` lasting `duration`. The stream goes to `out`,
+    /// or to `results/soak-<bench>-<unix time>.jsonl`. A `.csv` extension
+    /// selects CSV, anything else JSONL.
+    pub fn create(
+        bench: &str,
+        duration: Duration,
+        interval: Duration,
+        out: Option<&Path>,
+    ) -> io::Result<Self> {
+        Soak::with_clock(RealClock::start(), bench, duration, interval, out)
+    }
+}
+
+impl<C: Clock> Soak<C> {
+    /// [`Soak::create`] timed on `clock`; the soak starts at the clock's zero.
+    pub fn with_clock(
+        clock: C,
+        bench: &str,
+        duration: Duration,
+        interval: Duration,
+        out: Option<&Path>,
+    ) -> io::Result<Self> {
+        let path = match out {
+            Some(p) => p.to_path_buf(),
+            None => {
+                let secs = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                super::cli::results_dir().join(format!("soak-{}-{}.jsonl", bench.replace('/', "-"), secs))
+            }
+        };
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => Format::Csv,
+            _ => Format::Jsonl,
+        };
+        let mut out = BufWriter::new(File::create(&path)?);
+        if let Format::Csv = format {
+            writeln!(
+                out,
+                "\"bench\",\"window\",\"elapsed_s\",\"op\",\"count\",\"ops_per_sec\",\"p50_ns\",\"p95_ns\",\"p99_ns\",\"max_ns\",\"entries\",\"rss_bytes\",\"disk_bytes\""
+            )?;
+        }
+        Ok(Soak {
+            bench: bench.to_string(),
+            duration,
+            interval: interval.max(Duration::from_secs(1)),
+            window_start: clock.elapsed(),
+            clock,
+            all: LatencyHistogram::new(),
+            by_op: Vec::new(),
+            windows: Vec::new(),
+            format,
+            out,
+            path,
+        })
+    }
+
+    /// Where the time series is written.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// True until `duration` has passed.
+    pub fn running(&self) -> bool {
+        self.clock.elapsed() < self.duration
+    }
+
+    /// Add one operation's latency to the current window.
+    pub fn record(&mut self, op: &'static str, latency: Duration) {
+        self.all.record(latency);
+        match self.by_op.iter_mut().find(|(name, _)| *name == op) {
+            Some((_, h)) => h.record(latency),
+            None => {
+                let mut h = LatencyHistogram::new();
+                h.record(latency);
+                self.by_op.push((op, h));
+            }
+        }
+    }
+
+    /// Close the current window if it has run for `interval`. `gauges` is
+    /// only called when it does. Returns the closed window.
+    pub fn tick(&mut self, gauges: impl FnOnce() -> Gauges) -> io::Result<Option<&Window>> {
+        if self.clock.elapsed() - self.window_start < self.interval {
+            return Ok(None);
+        }
+        self.close_window(gauges())?;
+        Ok(self.windows.last())
+    }
+
+    /// Close the last, possibly partial, window and flush the stream.
+    /// Returns every window of the run.
+    pub fn finish(mut self, gauges: Gauges) -> io::Result<Vec<Window>> {
+        if !self.all.is_empty() {
+            self.close_window(gauges)?;
+        }
+        self.out.flush()?;
+        Ok(self.windows)
+    }
+
+    fn close_window(&mut self, gauges: Gauges) -> io::Result<()> {
+        let now = self.clock.elapsed();
+        let span = (now - self.window_start).as_secs_f64().max(1e-9);
+        let ns = |d: Duration| d.as_nanos().min(u64::MAX as u128) as u64;
+        let sample = |op: &'static str, h: &LatencyHistogram| OpSample {
+            op,
+            count: h.len(),
+            ops_per_sec: h.len() as f64 / span,
+            p50_ns: ns(h.p50()),
+            p95_ns: ns(h.p95()),
+            p99_ns: ns(h.p99()),
+            max_ns: ns(h.max()),
+        };
+        let mut ops = vec![sample("all", &self.all)];
+        ops.extend(self.by_op.iter().map(|(op, h)| sample(op, h)));
+
+        let window = Window {
+            index: self.windows.len() as u64,
+            elapsed: now,
+            ops,
+            gauges,
+        };
+        self.write_window(&window)?;
+        self.windows.push(window);
+
+        self.window_start = now;
+        self.all = LatencyHistogram::new();
+        for (_, h) in &mut self.by_op {
+            *h = LatencyHistogram::new();
+        }
+        Ok(())
+    }
+
+    fn write_window(&mut self, w: &Window) -> io::Result<()> {
+        for s in &w.ops {
+            match self.format {
+                Format::Jsonl => {
+                    let row = serde_json::json!({
+                        "bench": self.bench,
+                        "window": w.index,
+                        "elapsed_s": w.elapsed.as_secs_f64(),
+                        "op": s.op,
+                        "count": s.count,
+                        "ops_per_sec": s.ops_per_sec,
+                        "p50_ns": s.p50_ns,
+                        "p95_ns": s.p95_ns,
+                        "p99_ns": s.p99_ns,
+                        "max_ns": s.max_ns,
+                        "entries": w.gauges.entries,
+                        "rss_bytes": w.gauges.rss_bytes,
+                        "disk_bytes": w.gauges.disk_bytes,
+                    });
+                    writeln!(self.out, "{}", row)?;
+                }
+                Format::Csv => {
+                    let opt = |v: Option<u64>| v.map(|v| v.to_string()).unwrap_or_default();
+                    writeln!(
+                        self.out,
+                        "\"{}\",{},{:.1},\"{}\",{},{:.2},{},{},{},{},{},{},{}",
+                        self.bench,
+                        w.index,
+                        w.elapsed.as_secs_f64(),
+                        s.op,
+                        s.count,
+                        s.ops_per_sec,
+                        s.p50_ns,
+                        s.p95_ns,
+                        s.p99_ns,
+                        s.max_ns,
+                        w.gauges.entries,
+                        opt(w.gauges.rss_bytes),
+                        opt(w.gauges.disk_bytes),
+                    )?;
+                }
+            }
+        }
+        // Readable while the run is still going
+        self.out.flush()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Summary
+// ---------------------------------------------------------------------------
+
+/// Windows that ran for a full interval. The final window is usually
+/// partial, so it is left out when there are at least three.
+fn full_windows(windows: &[Window]) -> &[Window] {
+    if windows.len() >= 3 {
+        &windows[..windows.len() - 1]
+    } else {
+        windows
+    }
+}
+
+/// The last full window: the state the run degraded to.
+pub fn last_full(windows: &[Window]) -> Option<&Window> {
+    full_windows(windows).last()
+}
+
+/// Percentage change of `metric` from the first to the last full window.
+pub fn drift_pct(windows: &[Window], metric: impl Fn(&Window) -> f64) -> Option<f64> {
+    let full = full_windows(windows);
+    if full.len() < 2 {
+        return None;
+    }
+    let base = metric(&full[0]);
+    (base > 0.0).then(|| (metric(&full[full.len() - 1]) - base) / base * 100.0)
+}
+
+/// Total operations and wall time over every window.
+pub fn totals(windows: &[Window]) -> (u64, Duration) {
+    let ops = windows.iter().map(|w| w.all().count).sum();
+    let elapsed = windows.last().map(|w| w.elapsed).unwrap_or_default();
+    (ops, elapsed)
+}
+
+/// Result parameters describing a soak: its length and how `op`'s
+/// throughput and tail latency, memory and disk moved between the first
+/// and last window. Pass `"all"` for the whole mix.
+pub fn summary_parameters(
+    windows: &[Window],
+    interval: Duration,
+    op: &str,
+) -> Vec<(&'static str, serde_json::Value)> {
+    let (ops, elapsed) = totals(windows);
+    let mut params = vec![
+        ("soak_duration_s", serde_json::json!(elapsed.as_secs())),
+        ("soak_interval_s", serde_json::json!(interval.as_secs())),
+        ("soak_windows", serde_json::json!(windows.len())),
+        ("soak_ops", serde_json::json!(ops)),
+    ];
+    let metric = |f: fn(&OpSample) -> f64| move |w: &Window| w.op(op).map(f).unwrap_or(0.0);
+    if let Some(d) = drift_pct(windows, metric(|s| s.ops_per_sec)) {
+        params.push(("throughput_drift_pct", serde_json::json!(d)));
+    }
+    if let Some(d) = drift_pct(windows, metric(|s| s.p99_ns as f64)) {
+        params.push(("p99_drift_pct", serde_json::json!(d)));
+    }
+    let growth = |gauge: fn(&Gauges) -> Option<u64>| {
+        let first = gauge(&windows.first()?.gauges)?;
+        let last = gauge(&windows.last()?.gauges)?;
+        Some(last as i64 - first as i64)
+    };
+    if let Some(g) = growth(|g| g.rss_bytes) {
+        params.push(("rss_growth_bytes", serde_json::json!(g)));
+    }
+    if let Some(g) = growth(|g| g.disk_bytes) {
+        params.push(("disk_growth_bytes", serde_json::json!(g)));
+    }
+    params
+}
+
+/// `path` as an artifact path, relative to the results directory when it is
+/// inside it.
+pub fn artifact_path(path: &Path) -> String {
+    path.strip_prefix(super::cli::results_dir())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::clock::MockClock;
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("30m"), Some(Duration::from_secs(1800)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("45"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration("2x"), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("10m5"), None);
+    }
+
+    #[test]
+    fn test_fmt_span() {
+        assert_eq!(fmt_span(Duration::from_secs(7200)), "2h");
+        assert_eq!(fmt_span(Duration::from_secs(5400)), "1h30m");
+        assert_eq!(fmt_span(Duration::from_secs(90)), "1m30s");
+        assert_eq!(fmt_span(Duration::from_secs(3605)), "1h5s");
+        assert_eq!(fmt_span(Duration::ZERO), "0s");
+    }
+
+    #[test]
+    fn test_window_percentiles_match_hdr() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("soak.jsonl");
+        let mut soak = Soak::create("test", Duration::from_secs(1), DEFAULT_INTERVAL, Some(&out)).unwrap();
+        let mut hdr = LatencyHistogram::new();
+        for us in 1..=1000u64 {
+            let op = if us.is_multiple_of(2) { "get" } else { "put" };
+            soak.record(op, Duration::from_micros(us));
+            hdr.record(Duration::from_micros(us));
+        }
+        let windows = soak.finish(Gauges::default()).unwrap();
+        let all = windows[0].all();
+        assert_eq!(all.count, 1000);
+        assert_eq!(all.p99_ns, hdr.p99().as_nanos() as u64);
+        assert!((all.p99_ns as f64 - 990_000.0).abs() / 990_000.0 <= 0.001);
+        assert_eq!(all.max_ns, 1_000_000);
+        assert_eq!(windows[0].op("get").unwrap().count, 500);
+        assert_eq!(std::fs::read_to_string(&out).unwrap().lines().count(), 3);
+    }
+
+    #[test]
+    fn test_windows_close_on_the_clock() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("soak.csv");
+        let clock = MockClock::new();
+        let interval = Duration::from_secs(60);
+        let mut soak =
+            Soak::with_clock(&clock, "test", Duration::from_secs(150), interval, Some(&out)).unwrap();
+        let mut closed = 0;
+        while soak.running() {
+            soak.record("get", Duration::from_micros(100));
+            clock.advance(Duration::from_millis(10));
+            if soak.tick(Gauges::default).unwrap().is_some() {
+                closed += 1;
+            }
+        }
+        assert_eq!(closed, 2);
+        let windows = soak.finish(Gauges::default()).unwrap();
+        let elapsed: Vec<u64> = windows.iter().map(|w| w.elapsed.as_secs()).collect();
+        assert_eq!(elapsed, [60, 120, 150]);
+        assert_eq!(windows[0].all().count, 6_000);
+        assert!((windows[0].all().ops_per_sec - 100.0).abs() < 1e-9);
+        assert_eq!(windows[2].all().count, 3_000);
+        assert_eq!(std::fs::read_to_string(&out).unwrap().lines().count(), 1 + 3 * 2);
+    }
+
+    #[test]
+    fn test_drift_skips_partial_window() {
+        let w = |i: u64, ops: f64| Window {
+            index: i,
+            elapsed: Duration::from_secs(60 * (i + 1)),
+            ops: vec![OpSample {
+                op: "all",
+                count: 0,
+                ops_per_sec: ops,
+                p50_ns: 0,
+                p95_ns: 0,
+                p99_ns: 0,
+                max_ns: 0,
+            }],
+            gauges: Gauges::default(),
+        };
+        let windows = [w(0, 100.0), w(1, 90.0), w(2, 80.0), w(3, 5.0)];
+        let drift = drift_pct(&windows, |w| w.all().ops_per_sec).unwrap();
+        assert!((drift + 20.0).abs() < 1e-9);
+        assert!(drift_pct(&windows[..1], |w| w.all().ops_per_sec).is_none());
+    }
+}
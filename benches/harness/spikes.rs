@@ -0,0 +1,174 @@
+//! Top-N slowest operations of a measured loop.
+//!
+//! Percentiles sort the timings and lose the order they came in, so
+//! `max_ns` can't say when the worst operation ran. The tracker keeps the
+//! N slowest operations with their position and start time as the loop
+//! runs, so a spike can be lined up against a compaction, a WAL sync, or
+//! the same spike in another benchmark of the run.
+//!
+//! Checking an operation against the fastest tracked spike is one
+//! comparison, so the tracker stays out of the timed region's way.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use strata_benchmarks::schema::LatencySpike;
+
+/// Spikes kept per measured loop.
+pub const TRACKED_SPIKES: usize = 10;
+
+/// A tracked operation: latency, worker, op index, and start offset.
+type Spike = (Duration, Option<usize>, usize, Duration);
+
+/// Collects the slowest operations of one measured loop.
+pub struct SpikeTracker {
+    capacity: usize,
+    start: Instant,
+    start_unix_ms: u64,
+    worker: Option<usize>,
+    // Min-heap on latency: the root is the first spike to be displaced.
+    heap: BinaryHeap<Reverse<Spike>>,
+}
+
+impl SpikeTracker {
+    /// Start tracking; offsets are measured from now.
+    pub fn new(capacity: usize) -> Self {
+        let start_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            capacity,
+            start: Instant::now(),
+            start_unix_ms,
+            worker: None,
+            heap: BinaryHeap::with_capacity(capacity + 1),
+        }
+    }
+
+    /// Record every spike as issued by `worker`, whose operations are
+    /// numbered from 0 on their own.
+    pub fn with_worker(mut self, worker: usize) -> Self {
+        self.worker = Some(worker);
+        self
+    }
+
+    /// Note operation `index`, which started at `started` and took `latency`.
+    pub fn observe(&mut self, index: usize, started: Instant, latency: Duration) {
+        self.insert(self.worker, index, started, latency);
+    }
+
+    /// Fold in spikes another tracker saw, e.g. another worker's in a
+    /// multi-threaded run. Offsets are moved onto this tracker's start;
+    /// each spike keeps the worker and op index it was recorded with.
+    pub fn merge(&mut self, other: SpikeTracker) {
+        for Reverse((latency, worker, index, offset)) in other.heap {
+            self.insert(worker, index, other.start + offset, latency);
+        }
+    }
+
+    fn insert(&mut self, worker: Option<usize>, index: usize, started: Instant, latency: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.heap.len() == self.capacity {
+            match self.heap.peek() {
+                Some(Reverse((fastest, ..))) if latency <= *fastest => return,
+                _ => {}
+            }
+            self.heap.pop();
+        }
+        let offset = started.saturating_duration_since(self.start);
+        self.heap.push(Reverse((latency, worker, index, offset)));
+    }
+
+    /// The tracked spikes, slowest first.
+    pub fn finish(self) -> Vec<LatencySpike> {
+        let start_unix_ms = self.start_unix_ms;
+        let mut spikes: Vec<Spike> = self.heap.into_iter().map(|Reverse(s)| s).collect();
+        spikes.sort_by(|a, b| b.0.cmp(&a.0).then(a.3.cmp(&b.3)));
+        spikes
+            .into_iter()
+            .map(|(latency, worker, index, offset)| LatencySpike {
+                latency_ns: latency.as_nanos() as u64,
+                op_index: index as u64,
+                worker: worker.map(|w| w as u64),
+                offset_ns: offset.as_nanos() as u64,
+                unix_ms: start_unix_ms + offset.as_millis() as u64,
+                context: None,
+            })
+            .collect()
+    }
+}
+
+impl Default for SpikeTracker {
+    fn default() -> Self {
+        Self::new(TRACKED_SPIKES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_keeps_slowest_in_order() {
+        let mut t = SpikeTracker::new(3);
+        let start = Instant::now();
+        for (i, lat) in [5, 1, 9, 3, 7, 2].into_iter().enumerate() {
+            t.observe(i, start + ms(i as u64), ms(lat));
+        }
+        let spikes = t.finish();
+        let got: Vec<_> = spikes.iter().map(|s| (s.op_index, s.latency_ns)).collect();
+        assert_eq!(got, vec![(2, 9_000_000), (4, 7_000_000), (0, 5_000_000)]);
+        assert!(spikes[0].offset_ns >= 2_000_000);
+        assert!(spikes[0].unix_ms >= spikes[2].unix_ms);
+    }
+
+    #[test]
+    fn test_ties_keep_earliest() {
+        let mut t = SpikeTracker::new(1);
+        let start = Instant::now();
+        t.observe(0, start, ms(4));
+        t.observe(1, start, ms(4));
+        assert_eq!(t.finish()[0].op_index, 0);
+    }
+
+    #[test]
+    fn test_merge_keeps_slowest_of_both() {
+        let mut a = SpikeTracker::new(2);
+        let mut b = SpikeTracker::new(2);
+        let start = Instant::now();
+        a.observe(0, start, ms(3));
+        a.observe(1, start, ms(8));
+        b.observe(0, start + ms(5), ms(6));
+        b.observe(1, start + ms(6), ms(1));
+        a.merge(b);
+        let got: Vec<_> = a.finish().iter().map(|s| (s.op_index, s.latency_ns)).collect();
+        assert_eq!(got, vec![(1, 8_000_000), (0, 6_000_000)]);
+    }
+
+    #[test]
+    fn test_merge_keeps_workers_apart() {
+        let start = Instant::now();
+        let mut a = SpikeTracker::new(2).with_worker(0);
+        let mut b = SpikeTracker::new(2).with_worker(1);
+        a.observe(0, start, ms(3));
+        b.observe(0, start + ms(1), ms(5));
+        a.merge(b);
+        let got: Vec<_> = a.finish().iter().map(|s| (s.worker, s.op_index)).collect();
+        assert_eq!(got, vec![(Some(1), 0), (Some(0), 0)]);
+    }
+
+    #[test]
+    fn test_zero_capacity_tracks_nothing() {
+        let mut t = SpikeTracker::new(0);
+        t.observe(0, Instant::now(), ms(1));
+        assert!(t.finish().is_empty());
+    }
+}
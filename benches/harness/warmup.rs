@@ -0,0 +1,267 @@
+//! Warm-up that ends once throughput has settled.
+//!
+//! A fixed warm-up is either too short (the first measured second still
+//! includes cache fills, memtable growth or the first compaction) or wastes
+//! time on workloads that settle at once. [`Warmup::Steady`] runs the
+//! workload in short windows instead and starts measuring once the last few
+//! windows' throughput agrees: their coefficient of variation (standard
+//! deviation over mean) is at most `max_cv`. A workload that never settles
+//! is measured after `max` anyway, and the report says so.
+//!
+//! `--warmup auto` selects it with the defaults; `--warmup 2` is a fixed
+//! two-second warm-up.
+//!
+//! [`run`] keeps time on a [`Clock`], so its stopping rules are tested on
+//! virtual time.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::clock::Clock;
+
+/// Default throughput window.
+const DEFAULT_WINDOW: Duration = Duration::from_millis(250);
+/// Default number of consecutive windows that must agree. With the default
+/// window the shortest warm-up is one second.
+const DEFAULT_WINDOWS: usize = 4;
+/// Default agreement threshold: 5% coefficient of variation.
+const DEFAULT_MAX_CV: f64 = 0.05;
+/// Default longest warm-up before measuring regardless.
+const DEFAULT_MAX: Duration = Duration::from_secs(10);
+
+/// How to warm a workload up before measuring it.
+#[derive(Debug, Clone, Copy)]
+pub enum Warmup {
+    /// Run for exactly this long.
+    Fixed(Duration),
+    /// Run until throughput is steady.
+    Steady(SteadyState),
+}
+
+/// Parameters of a [`Warmup::Steady`] warm-up.
+#[derive(Debug, Clone, Copy)]
+pub struct SteadyState {
+    /// Length of one throughput window.
+    pub window: Duration,
+    /// Consecutive windows whose throughput must agree.
+    pub windows: usize,
+    /// Largest coefficient of variation across those windows that counts
+    /// as steady.
+    pub max_cv: f64,
+    /// Longest warm-up; measurement starts after this even if unsteady.
+    pub max: Duration,
+}
+
+impl Default for SteadyState {
+    fn default() -> Self {
+        SteadyState {
+            window: DEFAULT_WINDOW,
+            windows: DEFAULT_WINDOWS,
+            max_cv: DEFAULT_MAX_CV,
+            max: DEFAULT_MAX,
+        }
+    }
+}
+
+impl Default for Warmup {
+    fn default() -> Self {
+        Warmup::Steady(SteadyState::default())
+    }
+}
+
+impl Warmup {
+    /// `auto` for a steady-state warm-up with the defaults, or a number of
+    /// seconds for a fixed one.
+    pub fn parse(s: &str) -> Option<Warmup> {
+        match s.trim() {
+            "auto" => Some(Warmup::default()),
+            secs => secs
+                .parse::<f64>()
+                .ok()
+                .filter(|s| s.is_finite() && *s >= 0.0)
+                .map(|s| Warmup::Fixed(Duration::from_secs_f64(s))),
+        }
+    }
+
+    /// Short description for the run banner.
+    pub fn describe(&self) -> String {
+        match self {
+            Warmup::Fixed(d) => format!("{}s warmup", d.as_secs_f64()),
+            Warmup::Steady(s) => format!(
+                "auto warmup ({} x {}ms windows within {}% CV, at most {}s)",
+                s.windows,
+                s.window.as_millis(),
+                s.max_cv * 100.0,
+                s.max.as_secs_f64()
+            ),
+        }
+    }
+}
+
+/// What a warm-up did, for the result's parameters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WarmupReport {
+    /// How long the warm-up ran.
+    pub duration: Duration,
+    /// Whether throughput settled: `None` for a fixed warm-up, `Some(false)`
+    /// if a steady-state warm-up hit its `max`.
+    pub steady: Option<bool>,
+}
+
+impl WarmupReport {
+    /// `(name, value)` pairs to add to a result's parameters.
+    pub fn parameters(&self) -> Vec<(&'static str, serde_json::Value)> {
+        let mut params = vec![("warmup_ms", serde_json::json!(self.duration.as_millis() as u64))];
+        if let Some(steady) = self.steady {
+            params.push(("warmup_steady", serde_json::json!(steady)));
+        }
+        params
+    }
+}
+
+/// Warm up with `warmup`, timed on `clock`. `window(d)` runs the workload
+/// for `d` and returns its throughput over that time. A fixed warm-up is
+/// one window of its whole length.
+pub fn run(
+    clock: &impl Clock,
+    warmup: &Warmup,
+    mut window: impl FnMut(Duration) -> f64,
+) -> WarmupReport {
+    let start = clock.elapsed();
+    let steady = match warmup {
+        Warmup::Fixed(duration) => {
+            window(*duration);
+            None
+        }
+        Warmup::Steady(params) => {
+            let mut detector = SteadyStateDetector::new(params);
+            loop {
+                if detector.observe(window(params.window)) {
+                    break Some(true);
+                }
+                if clock.elapsed() - start >= params.max {
+                    break Some(false);
+                }
+            }
+        }
+    };
+    WarmupReport {
+        duration: clock.elapsed() - start,
+        steady,
+    }
+}
+
+/// Decides when a series of window throughputs has settled.
+pub struct SteadyStateDetector {
+    windows: usize,
+    max_cv: f64,
+    recent: VecDeque<f64>,
+}
+
+impl SteadyStateDetector {
+    pub fn new(params: &SteadyState) -> Self {
+        let windows = params.windows.max(2);
+        SteadyStateDetector {
+            windows,
+            max_cv: params.max_cv,
+            recent: VecDeque::with_capacity(windows),
+        }
+    }
+
+    /// Add one window's throughput. Returns whether the last `windows`
+    /// windows are steady.
+    pub fn observe(&mut self, ops_per_sec: f64) -> bool {
+        if self.recent.len() == self.windows {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(ops_per_sec);
+        self.recent.len() == self.windows
+            && coefficient_of_variation(self.recent.iter().copied()) <= self.max_cv
+    }
+}
+
+/// Standard deviation over mean; infinite when the mean is zero, so a
+/// stalled workload never counts as steady.
+fn coefficient_of_variation(values: impl Iterator<Item = f64> + Clone) -> f64 {
+    let n = values.clone().count() as f64;
+    let mean = values.clone().sum::<f64>() / n;
+    if mean <= 0.0 {
+        return f64::INFINITY;
+    }
+    let variance = values.map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    variance.sqrt() / mean
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::clock::MockClock;
+
+    /// Runs `run` on virtual time; each window takes its full length and
+    /// reports the next throughput from `series` (the last one repeats).
+    fn warm_up(warmup: &Warmup, series: &[f64]) -> (WarmupReport, usize) {
+        let clock = MockClock::new();
+        let mut windows = 0;
+        let report = run(&clock, warmup, |d| {
+            clock.advance(d);
+            windows += 1;
+            series[(windows - 1).min(series.len() - 1)]
+        });
+        (report, windows)
+    }
+
+    #[test]
+    fn test_run_stops_once_steady() {
+        let series = [
+            10_000.0, 40_000.0, 80_000.0, 95_000.0, 99_000.0, 100_000.0, 101_000.0,
+        ];
+        let (report, windows) = warm_up(&Warmup::default(), &series);
+        assert_eq!(windows, 7);
+        assert_eq!(report.steady, Some(true));
+        assert_eq!(report.duration, 7 * DEFAULT_WINDOW);
+    }
+
+    #[test]
+    fn test_run_gives_up_at_max() {
+        let (report, windows) = warm_up(&Warmup::default(), &[0.0]);
+        assert_eq!(report.steady, Some(false));
+        assert_eq!(report.duration, DEFAULT_MAX);
+        assert_eq!(windows, (DEFAULT_MAX.as_millis() / DEFAULT_WINDOW.as_millis()) as usize);
+    }
+
+    #[test]
+    fn test_run_fixed_is_one_window() {
+        let (report, windows) = warm_up(&Warmup::Fixed(Duration::from_secs(2)), &[1.0]);
+        assert_eq!((windows, report.steady, report.duration), (1, None, Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_detector_waits_for_settled_windows() {
+        let mut d = SteadyStateDetector::new(&SteadyState::default());
+        // Ramping up: a full set of windows, but spread too wide
+        for ops in [10_000.0, 40_000.0, 80_000.0, 95_000.0] {
+            assert!(!d.observe(ops));
+        }
+        assert!(!d.observe(99_000.0));
+        assert!(!d.observe(100_000.0));
+        // 95k..101k: CV under 5%
+        assert!(d.observe(101_000.0));
+    }
+
+    #[test]
+    fn test_stalled_workload_is_not_steady() {
+        let mut d = SteadyStateDetector::new(&SteadyState::default());
+        for _ in 0..8 {
+            assert!(!d.observe(0.0));
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(matches!(Warmup::parse("auto"), Some(Warmup::Steady(_))));
+        assert!(matches!(Warmup::parse("2.5"), Some(Warmup::Fixed(d)) if d == Duration::from_millis(2500)));
+        assert!(matches!(Warmup::parse("0"), Some(Warmup::Fixed(Duration::ZERO))));
+        assert!(Warmup::parse("-1").is_none());
+        assert!(Warmup::parse("soon").is_none());
+    }
+}
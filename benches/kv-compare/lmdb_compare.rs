@@ -0,0 +1,557 @@
+//! Embedded KV Benchmark — Strata vs LMDB (heed) head-to-head
+//!
+//! Runs identical put / get / scan workloads against Strata and LMDB in the
+//! same process, on the same machine and disk, and reports them side by side.
+//! After loading, every key is read back from both engines and compared
+//! byte-for-byte, and both scans must return the same key set, so the
+//! numbers are only reported for engines that agree on the data.
+//!
+//! Follows the graph_bfs Strata-vs-petgraph pattern.
+//!
+//! Run:         `cargo bench --bench lmdb_compare`
+//! Quick:       `cargo bench --bench lmdb_compare -- --keys 10000 -q`
+//! Durability:  `cargo bench --bench lmdb_compare -- --durability always`
+//! CSV:         `cargo bench --bench lmdb_compare -- --csv`
+//! Strata only: `cargo bench --bench lmdb_compare -- --strata-only`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::spikes::{SpikeTracker, TRACKED_SPIKES};
+use harness::{create_db, db_temp_dir, print_hardware_info, BenchDb, DurabilityConfig};
+use heed::types::Bytes;
+use heed::{Database, Env, EnvFlags, EnvOpenOptions};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_us;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, LatencySpike, ResultStatus};
+use stratadb::Value;
+use tempfile::TempDir;
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_KEYS: usize = 100_000;
+const DEFAULT_VALUE_SIZE: usize = 100;
+const DEFAULT_SCAN_RUNS: usize = 10;
+
+/// LMDB map size. Sparse on disk; only needs to exceed the data set.
+const LMDB_MAP_SIZE: usize = 16 << 30;
+
+const KEY_PREFIX: &str = "k:";
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    keys: usize,
+    value_size: usize,
+    scan_runs: usize,
+    durability: Vec<DurabilityConfig>,
+    csv: bool,
+    quiet: bool,
+    strata_only: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        keys: DEFAULT_KEYS,
+        value_size: DEFAULT_VALUE_SIZE,
+        scan_runs: DEFAULT_SCAN_RUNS,
+        durability: DurabilityConfig::ALL.to_vec(),
+        csv: false,
+        quiet: false,
+        strata_only: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--keys" => config.keys = args.parse_value::<usize>().max(1),
+            "--value-size" => config.value_size = args.parse_value(),
+            "--scan-runs" => {
+                config.scan_runs = args.parse_value::<usize>().max(1);
+            }
+            "--strata-only" => config.strata_only = true,
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Workload data
+// ---------------------------------------------------------------------------
+
+fn key(i: u64) -> String {
+    format!("{}{:012}", KEY_PREFIX, i)
+}
+
+/// Value for key `i`: distinct per key so read-back validation catches
+/// swapped or stale values, not just missing ones.
+fn value(i: u64, size: usize) -> Vec<u8> {
+    let mut v = vec![0u8; size];
+    for (j, b) in v.iter_mut().enumerate() {
+        *b = (i.wrapping_mul(31).wrapping_add(j as u64) % 251) as u8;
+    }
+    v
+}
+
+/// Random read order, identical for both engines.
+fn read_order(n: usize) -> Vec<u64> {
+    let mut state = 0x1234_5678u64;
+    (0..n)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % n as u64
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// LMDB engine
+// ---------------------------------------------------------------------------
+
+struct Lmdb {
+    env: Env,
+    db: Database<Bytes, Bytes>,
+    _dir: TempDir,
+}
+
+impl Lmdb {
+    /// Durability mapping: `always` syncs every commit (LMDB's default);
+    /// `standard` and `cache` use `NO_SYNC`, leaving flushes to the OS.
+    fn open(mode: DurabilityConfig) -> heed::Result<Self> {
+        let dir = db_temp_dir();
+        let flags = match mode {
+            DurabilityConfig::Always => EnvFlags::empty(),
+            DurabilityConfig::Standard | DurabilityConfig::StandardSync { .. } | DurabilityConfig::Cache => EnvFlags::NO_SYNC,
+        };
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(LMDB_MAP_SIZE)
+                .max_dbs(1)
+                .flags(flags)
+                .open(dir.path())?
+        };
+        let mut wtxn = env.write_txn()?;
+        let db: Database<Bytes, Bytes> = env.create_database(&mut wtxn, None)?;
+        wtxn.commit()?;
+        Ok(Self { env, db, _dir: dir })
+    }
+
+    /// One write transaction per put, matching Strata's auto-commit.
+    fn put(&self, key: &str, value: &[u8]) -> heed::Result<()> {
+        let mut wtxn = self.env.write_txn()?;
+        self.db.put(&mut wtxn, key.as_bytes(), value)?;
+        wtxn.commit()
+    }
+
+    fn get(&self, key: &str) -> heed::Result<Option<Vec<u8>>> {
+        let rtxn = self.env.read_txn()?;
+        Ok(self.db.get(&rtxn, key.as_bytes())?.map(|v| v.to_vec()))
+    }
+
+    /// All (key, value) pairs under `prefix`, in key order.
+    fn scan(&self, prefix: &str) -> heed::Result<Vec<(String, Vec<u8>)>> {
+        let rtxn = self.env.read_txn()?;
+        let mut out = Vec::new();
+        for entry in self.db.prefix_iter(&rtxn, prefix.as_bytes())? {
+            let (k, v) = entry?;
+            out.push((String::from_utf8_lossy(k).into_owned(), v.to_vec()));
+        }
+        Ok(out)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Strata engine
+// ---------------------------------------------------------------------------
+
+fn strata_put(db: &BenchDb, key: &str, value: &[u8]) {
+    db.db.kv_put(key, Value::Bytes(value.to_vec())).unwrap();
+}
+
+fn strata_get(db: &BenchDb, key: &str) -> Option<Vec<u8>> {
+    match db.db.kv_get(key).unwrap() {
+        Some(Value::Bytes(b)) => Some(b),
+        _ => None,
+    }
+}
+
+/// Strata has no ordered iterator: list the keys, sort them, then read each
+/// value, so the result matches what an LMDB cursor returns.
+fn strata_scan(db: &BenchDb, prefix: &str) -> Vec<(String, Vec<u8>)> {
+    let mut keys = db.db.kv_list(Some(prefix)).unwrap();
+    keys.sort_unstable();
+    keys.into_iter()
+        .map(|k| {
+            let v = strata_get(db, &k).unwrap_or_default();
+            (k, v)
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Measurement
+// ---------------------------------------------------------------------------
+
+struct OpStats {
+    count: usize,
+    ops_per_sec: f64,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    /// The slowest operations, slowest first.
+    spikes: Vec<LatencySpike>,
+}
+
+fn op_stats(mut latencies: Vec<Duration>, wall: Duration, spikes: Vec<LatencySpike>) -> OpStats {
+    assert!(!latencies.is_empty(), "op_stats requires at least one sample");
+    latencies.sort_unstable();
+    let len = latencies.len();
+    let sum: Duration = latencies.iter().sum();
+    OpStats {
+        count: len,
+        ops_per_sec: len as f64 / wall.as_secs_f64(),
+        avg: sum / len as u32,
+        p50: latencies[len * 50 / 100],
+        p95: latencies[(len * 95 / 100).min(len - 1)],
+        p99: latencies[(len * 99 / 100).min(len - 1)],
+        min: latencies[0],
+        max: latencies[len - 1],
+        spikes,
+    }
+}
+
+fn time_each(n: usize, mut f: impl FnMut(usize)) -> OpStats {
+    let mut latencies = Vec::with_capacity(n);
+    let mut spikes = SpikeTracker::new(TRACKED_SPIKES);
+    let wall = Instant::now();
+    for i in 0..n {
+        let start = Instant::now();
+        f(i);
+        let elapsed = start.elapsed();
+        latencies.push(elapsed);
+        spikes.observe(i, start, elapsed);
+    }
+    op_stats(latencies, wall.elapsed(), spikes.finish())
+}
+
+/// Results for one engine: (op name, stats) in put/get/scan order.
+type EngineResults = Vec<(&'static str, OpStats)>;
+
+fn run_strata(db: &BenchDb, config: &Config, order: &[u64]) -> EngineResults {
+    let put = time_each(config.keys, |i| {
+        strata_put(db, &key(i as u64), &value(i as u64, config.value_size))
+    });
+    let get = time_each(order.len(), |i| {
+        std::hint::black_box(strata_get(db, &key(order[i])));
+    });
+    let scan = time_each(config.scan_runs, |_| {
+        std::hint::black_box(strata_scan(db, KEY_PREFIX));
+    });
+    vec![("put", put), ("get", get), ("scan", scan)]
+}
+
+fn run_lmdb(lmdb: &Lmdb, config: &Config, order: &[u64]) -> EngineResults {
+    let put = time_each(config.keys, |i| {
+        lmdb.put(&key(i as u64), &value(i as u64, config.value_size))
+            .expect("lmdb put failed")
+    });
+    let get = time_each(order.len(), |i| {
+        std::hint::black_box(lmdb.get(&key(order[i])).expect("lmdb get failed"));
+    });
+    let scan = time_each(config.scan_runs, |_| {
+        std::hint::black_box(lmdb.scan(KEY_PREFIX).expect("lmdb scan failed"));
+    });
+    vec![("put", put), ("get", get), ("scan", scan)]
+}
+
+// ---------------------------------------------------------------------------
+// Cross-validation: Strata vs LMDB
+// ---------------------------------------------------------------------------
+
+/// Read every key back from both engines and compare against the expected
+/// value, then compare full scans. Returns (pass, keys_checked, mismatches).
+fn cross_validate(db: &BenchDb, lmdb: &Lmdb, config: &Config) -> (bool, usize, usize) {
+    let mut mismatches = 0;
+    for i in 0..config.keys as u64 {
+        let k = key(i);
+        let expected = Some(value(i, config.value_size));
+        let s = strata_get(db, &k);
+        let l = lmdb.get(&k).expect("lmdb get failed");
+        if s != expected || l != expected {
+            mismatches += 1;
+        }
+    }
+
+    let strata_scan = strata_scan(db, KEY_PREFIX);
+    let lmdb_scan = lmdb.scan(KEY_PREFIX).expect("lmdb scan failed");
+    if strata_scan != lmdb_scan {
+        mismatches += 1;
+    }
+
+    (mismatches == 0, config.keys, mismatches)
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn print_table(strata: &EngineResults, lmdb: Option<&EngineResults>) {
+    eprintln!(
+        "  {:<6} {:>14} {:>10} {:>10}   {:>14} {:>10} {:>10}   {:>8}",
+        "op", "Strata ops/s", "p50", "p99", "LMDB ops/s", "p50", "p99", "ratio"
+    );
+    eprintln!("  {}", "-".repeat(96));
+    for (idx, (op, s)) in strata.iter().enumerate() {
+        match lmdb.map(|l| &l[idx].1) {
+            Some(l) => eprintln!(
+                "  {:<6} {:>14.0} {:>10} {:>10}   {:>14.0} {:>10} {:>10}   {:>7.2}x",
+                op,
+                s.ops_per_sec,
+                fmt_us(s.p50),
+                fmt_us(s.p99),
+                l.ops_per_sec,
+                fmt_us(l.p50),
+                fmt_us(l.p99),
+                l.ops_per_sec / s.ops_per_sec,
+            ),
+            None => eprintln!(
+                "  {:<6} {:>14.0} {:>10} {:>10}",
+                op,
+                s.ops_per_sec,
+                fmt_us(s.p50),
+                fmt_us(s.p99),
+            ),
+        }
+    }
+    eprintln!("  (ratio = LMDB ops/s ÷ Strata ops/s; scan ops are full scans of all keys)");
+    eprintln!();
+}
+
+fn print_csv_header() {
+    println!("\"engine\",\"durability\",\"op\",\"count\",\"ops_sec\",\"p50_us\",\"p95_us\",\"p99_us\"");
+}
+
+fn print_csv_rows(engine: &str, mode: DurabilityConfig, results: &EngineResults) {
+    for (op, s) in results {
+        println!(
+            "\"{}\",\"{}\",\"{}\",{},{:.2},{:.3},{:.3},{:.3}",
+            engine,
+            mode.label(),
+            op,
+            s.count,
+            s.ops_per_sec,
+            s.p50.as_nanos() as f64 / 1000.0,
+            s.p95.as_nanos() as f64 / 1000.0,
+            s.p99.as_nanos() as f64 / 1000.0,
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+fn record_open_failure(
+    recorder: &mut ResultRecorder,
+    engine: &str,
+    mode: DurabilityConfig,
+    config: &Config,
+    message: &str,
+) {
+    for op in ["put", "get", "scan"] {
+        let mut params = HashMap::new();
+        params.insert("engine".into(), serde_json::json!(engine));
+        params.insert("durability".into(), serde_json::json!(mode.label()));
+        params.insert("op".into(), serde_json::json!(op));
+        params.insert("keys".into(), serde_json::json!(config.keys));
+        params.insert("value_size".into(), serde_json::json!(config.value_size));
+        recorder.record_failure(
+            &format!("kv-compare/{}/{}/{}", op, engine, mode.label()),
+            params,
+            ResultStatus::Error {
+                message: message.to_string(),
+            },
+        );
+    }
+}
+
+fn record_results(
+    recorder: &mut ResultRecorder,
+    engine: &str,
+    mode: DurabilityConfig,
+    config: &Config,
+    results: &EngineResults,
+    cross_validation: Option<bool>,
+) {
+    for (op, s) in results {
+        let mut params = HashMap::new();
+        params.insert("engine".into(), serde_json::json!(engine));
+        params.insert("durability".into(), serde_json::json!(mode.label()));
+        params.insert("op".into(), serde_json::json!(op));
+        params.insert("keys".into(), serde_json::json!(config.keys));
+        params.insert("value_size".into(), serde_json::json!(config.value_size));
+        if let Some(pass) = cross_validation {
+            params.insert("cross_validation".into(), serde_json::json!(pass));
+        }
+        if *op == "scan" {
+            params.insert(
+                "entries_per_sec".into(),
+                serde_json::json!(s.ops_per_sec * config.keys as f64),
+            );
+        }
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!("kv-compare/{}/{}/{}", op, engine, mode.label()),
+            category: "kv-compare".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(s.ops_per_sec),
+                p50_ns: Some(s.p50.as_nanos() as u64),
+                p95_ns: Some(s.p95.as_nanos() as u64),
+                p99_ns: Some(s.p99.as_nanos() as u64),
+                min_ns: Some(s.min.as_nanos() as u64),
+                max_ns: Some(s.max.as_nanos() as u64),
+                avg_ns: Some(s.avg.as_nanos() as u64),
+                samples: Some(s.count as u64),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: s.spikes.clone(),
+            status: if cross_validation == Some(false) {
+                ResultStatus::ValidationFailed {
+                    message: "Strata and LMDB returned different values or scans".to_string(),
+                }
+            } else {
+                ResultStatus::Success
+            },
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== Embedded KV Benchmark: Strata vs LMDB ===");
+        eprintln!(
+            "Keys: {} | value: {}B | scans: {} full passes",
+            config.keys, config.value_size, config.scan_runs
+        );
+        if config.strata_only {
+            eprintln!("Mode: strata-only (LMDB comparison skipped)");
+        }
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let order = read_order(config.keys);
+    let mut recorder = ResultRecorder::new("kv-compare");
+
+    for &mode in &config.durability {
+        if !config.csv && !config.quiet {
+            eprintln!("--- durability: {} ---", mode.label());
+        }
+
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(mode.label()));
+        guard_params.insert("keys".into(), serde_json::json!(config.keys));
+        guard_params.insert("value_size".into(), serde_json::json!(config.value_size));
+        let guard_name = format!("kv-compare/{}", mode.label());
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            run_mode(recorder, mode, &config, &order)
+        });
+    }
+
+    recorder.save_or_exit();
+}
+
+/// Run every operation on Strata (and LMDB unless `--strata-only`) in
+/// `mode`, cross-validate the two, and record the results.
+fn run_mode(
+    recorder: &mut ResultRecorder,
+    mode: DurabilityConfig,
+    config: &Config,
+    order: &[u64],
+) {
+    let db = create_db(mode);
+    let strata = run_strata(&db, config, order);
+
+    let lmdb_state = if config.strata_only {
+        None
+    } else {
+        match Lmdb::open(mode) {
+            Ok(lmdb) => {
+                let results = run_lmdb(&lmdb, config, order);
+                Some((lmdb, results))
+            }
+            Err(e) => {
+                let message = format!("failed to open LMDB environment: {}", e);
+                eprintln!("{}", message);
+                record_open_failure(recorder, "lmdb", mode, config, &message);
+                None
+            }
+        }
+    };
+
+    let cross_validation = lmdb_state.as_ref().map(|(lmdb, _)| {
+        let (pass, checked, mismatches) = cross_validate(&db, lmdb, config);
+        if !config.csv {
+            if pass {
+                eprintln!("Cross-validation: PASS (values match on all {} keys, scans identical)", checked);
+            } else {
+                eprintln!(
+                    "Cross-validation: FAIL ({} mismatches out of {} keys + scan)",
+                    mismatches, checked
+                );
+            }
+        }
+        pass
+    });
+
+    let lmdb_results = lmdb_state.as_ref().map(|(_, r)| r);
+    if config.csv {
+        print_csv_rows("strata", mode, &strata);
+        if let Some(r) = lmdb_results {
+            print_csv_rows("lmdb", mode, r);
+        }
+    } else if config.quiet {
+        for (idx, (op, s)) in strata.iter().enumerate() {
+            let lmdb_part = lmdb_results
+                .map(|l| format!(", lmdb={:.0} ops/s", l[idx].1.ops_per_sec))
+                .unwrap_or_default();
+            eprintln!("{}/{}: strata={:.0} ops/s{}", op, mode.label(), s.ops_per_sec, lmdb_part);
+        }
+    } else {
+        print_table(&strata, lmdb_results);
+    }
+
+    record_results(recorder, "strata", mode, config, &strata, cross_validation);
+    if let Some(r) = lmdb_results {
+        record_results(recorder, "lmdb", mode, config, r, cross_validation);
+    }
+}
@@ -0,0 +1,556 @@
+//! Embedded Store Benchmark — Strata vs SQLite for KV and JSON workloads
+//!
+//! Maps Strata's KV and JSON primitives onto the way an embedded user would
+//! build them on SQLite, and runs both in the same process and report:
+//!
+//! | Strata                          | SQLite                                                 |
+//! |---------------------------------|--------------------------------------------------------|
+//! | `kv_put(k, v)`                  | `INSERT OR REPLACE INTO kv(key, value)` (PRIMARY KEY)  |
+//! | `kv_get(k)`                     | `SELECT value FROM kv WHERE key = ?`                   |
+//! | `json_set(k, "$", doc)`         | `INSERT OR REPLACE INTO docs(key, doc) VALUES (?, json(?))` |
+//! | `json_set(k, "$.a.b", v)`       | `UPDATE docs SET doc = json_set(doc, '$.a.b', ?)`      |
+//! | `json_get(k, "$.a.b")`          | `SELECT json_extract(doc, '$.a.b') FROM docs`          |
+//!
+//! Every statement runs in autocommit mode, matching Strata's per-call
+//! commit. Statements are prepared once and cached.
+//!
+//! Run:         `cargo bench --bench sqlite_compare`
+//! Quick:       `cargo bench --bench sqlite_compare -- --keys 10000 -q`
+//! Durability:  `cargo bench --bench sqlite_compare -- --durability always`
+//! CSV:         `cargo bench --bench sqlite_compare -- --csv`
+//! Strata only: `cargo bench --bench sqlite_compare -- --strata-only`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::spikes::{SpikeTracker, TRACKED_SPIKES};
+use harness::{create_db, db_temp_dir, json_document, print_hardware_info, BenchDb, DurabilityConfig};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_us;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, LatencySpike, ResultStatus};
+use stratadb::Value;
+use tempfile::TempDir;
+
+// ---------------------------------------------------------------------------
+// Default parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_KEYS: usize = 50_000;
+const DEFAULT_VALUE_SIZE: usize = 100;
+
+const SET_PATH: &str = "$.metadata.mid_score";
+const GET_PATH: &str = "$.name";
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    keys: usize,
+    value_size: usize,
+    durability: Vec<DurabilityConfig>,
+    csv: bool,
+    quiet: bool,
+    strata_only: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        keys: DEFAULT_KEYS,
+        value_size: DEFAULT_VALUE_SIZE,
+        durability: DurabilityConfig::ALL.to_vec(),
+        csv: false,
+        quiet: false,
+        strata_only: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--keys" => config.keys = args.parse_value::<usize>().max(1),
+            "--value-size" => config.value_size = args.parse_value(),
+            "--strata-only" => config.strata_only = true,
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Workload data
+// ---------------------------------------------------------------------------
+
+const OPS: [&str; 5] = ["kv_put", "kv_get", "json_set", "json_set_path", "json_get_path"];
+
+fn kv_key(i: u64) -> String {
+    format!("kv:{:012}", i)
+}
+
+fn doc_key(i: u64) -> String {
+    format!("doc:{:012}", i)
+}
+
+fn kv_bytes(i: u64, size: usize) -> Vec<u8> {
+    vec![(i % 251) as u8; size]
+}
+
+/// Random access order, identical for both engines.
+fn access_order(n: usize) -> Vec<u64> {
+    let mut state = 0x5EED_0001u64;
+    (0..n)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % n as u64
+        })
+        .collect()
+}
+
+fn to_serde(v: &Value) -> serde_json::Value {
+    match v {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::Int(i) => serde_json::json!(i),
+        Value::Float(f) => serde_json::Number::from_f64(*f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Value::String(s) => serde_json::Value::String(s.clone()),
+        Value::Bytes(b) => serde_json::json!(b),
+        Value::Array(a) => serde_json::Value::Array(a.iter().map(to_serde).collect()),
+        Value::Object(m) => serde_json::Value::Object(
+            m.iter().map(|(k, v)| (k.clone(), to_serde(v))).collect(),
+        ),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Engines
+// ---------------------------------------------------------------------------
+
+/// The five operations, driven identically for both engines.
+trait Engine {
+    fn kv_put(&mut self, key: &str, value: &[u8]);
+    fn kv_get(&mut self, key: &str) -> bool;
+    fn json_set(&mut self, key: &str, doc: &Value);
+    fn json_set_path(&mut self, key: &str, value: f64);
+    fn json_get_path(&mut self, key: &str) -> bool;
+}
+
+struct StrataEngine {
+    bench_db: BenchDb,
+}
+
+impl Engine for StrataEngine {
+    fn kv_put(&mut self, key: &str, value: &[u8]) {
+        self.bench_db
+            .db
+            .kv_put(key, Value::Bytes(value.to_vec()))
+            .unwrap();
+    }
+
+    fn kv_get(&mut self, key: &str) -> bool {
+        self.bench_db.db.kv_get(key).unwrap().is_some()
+    }
+
+    fn json_set(&mut self, key: &str, doc: &Value) {
+        self.bench_db.db.json_set(key, "$", doc.clone()).unwrap();
+    }
+
+    fn json_set_path(&mut self, key: &str, value: f64) {
+        self.bench_db
+            .db
+            .json_set(key, SET_PATH, Value::Float(value))
+            .unwrap();
+    }
+
+    fn json_get_path(&mut self, key: &str) -> bool {
+        self.bench_db.db.json_get(key, GET_PATH).unwrap().is_some()
+    }
+}
+
+/// SQLite through rusqlite. `cache` runs an in-memory database; the disk
+/// modes use WAL journaling with `synchronous` set to match Strata's policy.
+struct SqliteEngine {
+    conn: Connection,
+    _dir: Option<TempDir>,
+}
+
+impl SqliteEngine {
+    fn open(mode: DurabilityConfig) -> rusqlite::Result<Self> {
+        let (conn, dir) = match mode {
+            DurabilityConfig::Cache => (Connection::open_in_memory()?, None),
+            DurabilityConfig::Standard | DurabilityConfig::StandardSync { .. } | DurabilityConfig::Always => {
+                let dir = db_temp_dir();
+                let conn = Connection::open(dir.path().join("bench.sqlite"))?;
+                conn.pragma_update(None, "journal_mode", "WAL")?;
+                let sync = if mode == DurabilityConfig::Always { "FULL" } else { "NORMAL" };
+                conn.pragma_update(None, "synchronous", sync)?;
+                (conn, Some(dir))
+            }
+        };
+        conn.execute_batch(
+            "CREATE TABLE kv (key TEXT PRIMARY KEY, value BLOB NOT NULL);
+             CREATE TABLE docs (key TEXT PRIMARY KEY, doc TEXT NOT NULL);",
+        )?;
+        conn.set_prepared_statement_cache_capacity(16);
+        Ok(Self { conn, _dir: dir })
+    }
+}
+
+impl Engine for SqliteEngine {
+    fn kv_put(&mut self, key: &str, value: &[u8]) {
+        self.conn
+            .prepare_cached("INSERT OR REPLACE INTO kv (key, value) VALUES (?1, ?2)")
+            .and_then(|mut s| s.execute(params![key, value]))
+            .expect("sqlite kv_put failed");
+    }
+
+    fn kv_get(&mut self, key: &str) -> bool {
+        self.conn
+            .prepare_cached("SELECT value FROM kv WHERE key = ?1")
+            .and_then(|mut s| s.query_row(params![key], |r| r.get::<_, Vec<u8>>(0)).optional())
+            .expect("sqlite kv_get failed")
+            .is_some()
+    }
+
+    fn json_set(&mut self, key: &str, doc: &Value) {
+        let text = to_serde(doc).to_string();
+        self.conn
+            .prepare_cached("INSERT OR REPLACE INTO docs (key, doc) VALUES (?1, json(?2))")
+            .and_then(|mut s| s.execute(params![key, text]))
+            .expect("sqlite json_set failed");
+    }
+
+    fn json_set_path(&mut self, key: &str, value: f64) {
+        self.conn
+            .prepare_cached("UPDATE docs SET doc = json_set(doc, ?1, ?2) WHERE key = ?3")
+            .and_then(|mut s| s.execute(params![SET_PATH, value, key]))
+            .expect("sqlite json_set_path failed");
+    }
+
+    fn json_get_path(&mut self, key: &str) -> bool {
+        self.conn
+            .prepare_cached("SELECT json_extract(doc, ?1) FROM docs WHERE key = ?2")
+            .and_then(|mut s| {
+                s.query_row(params![GET_PATH, key], |r| r.get::<_, Option<String>>(0))
+                    .optional()
+            })
+            .expect("sqlite json_get_path failed")
+            .flatten()
+            .is_some()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Measurement
+// ---------------------------------------------------------------------------
+
+struct OpStats {
+    count: usize,
+    ops_per_sec: f64,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    /// The slowest operations, slowest first.
+    spikes: Vec<LatencySpike>,
+    /// Reads that found their key (read ops only).
+    found: Option<usize>,
+}
+
+fn op_stats(
+    mut latencies: Vec<Duration>,
+    wall: Duration,
+    found: Option<usize>,
+    spikes: Vec<LatencySpike>,
+) -> OpStats {
+    assert!(!latencies.is_empty(), "op_stats requires at least one sample");
+    latencies.sort_unstable();
+    let len = latencies.len();
+    let sum: Duration = latencies.iter().sum();
+    OpStats {
+        count: len,
+        ops_per_sec: len as f64 / wall.as_secs_f64(),
+        avg: sum / len as u32,
+        p50: latencies[len * 50 / 100],
+        p95: latencies[(len * 95 / 100).min(len - 1)],
+        p99: latencies[(len * 99 / 100).min(len - 1)],
+        min: latencies[0],
+        max: latencies[len - 1],
+        found,
+        spikes,
+    }
+}
+
+/// Time `f` once per index. `f` returns whether a read found its key;
+/// writes return `None`.
+fn time_each(n: usize, mut f: impl FnMut(usize) -> Option<bool>) -> OpStats {
+    let mut latencies = Vec::with_capacity(n);
+    let mut found = None;
+    let mut spikes = SpikeTracker::new(TRACKED_SPIKES);
+    let wall = Instant::now();
+    for i in 0..n {
+        let start = Instant::now();
+        let hit = f(i);
+        let elapsed = start.elapsed();
+        latencies.push(elapsed);
+        spikes.observe(i, start, elapsed);
+        if let Some(hit) = hit {
+            *found.get_or_insert(0) += hit as usize;
+        }
+    }
+    op_stats(latencies, wall.elapsed(), found, spikes.finish())
+}
+
+/// Results for one engine, in `OPS` order.
+type EngineResults = Vec<OpStats>;
+
+fn run_engine(engine: &mut dyn Engine, config: &Config, order: &[u64]) -> EngineResults {
+    let n = config.keys;
+    let kv_put = time_each(n, |i| {
+        engine.kv_put(&kv_key(i as u64), &kv_bytes(i as u64, config.value_size));
+        None
+    });
+    let kv_get = time_each(n, |i| Some(engine.kv_get(&kv_key(order[i]))));
+
+    // Documents are built outside the timed region for both engines
+    let docs: Vec<Value> = (0..n as u64).map(json_document).collect();
+    let json_set = time_each(n, |i| {
+        engine.json_set(&doc_key(i as u64), &docs[i]);
+        None
+    });
+    let json_set_path = time_each(n, |i| {
+        engine.json_set_path(&doc_key(order[i]), i as f64 * 2.5);
+        None
+    });
+    let json_get_path = time_each(n, |i| Some(engine.json_get_path(&doc_key(order[i]))));
+
+    vec![kv_put, kv_get, json_set, json_set_path, json_get_path]
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn print_table(strata: &EngineResults, sqlite: Option<&EngineResults>) {
+    eprintln!(
+        "  {:<14} {:>14} {:>10} {:>10}   {:>14} {:>10} {:>10}   {:>8}",
+        "op", "Strata ops/s", "p50", "p99", "SQLite ops/s", "p50", "p99", "ratio"
+    );
+    eprintln!("  {}", "-".repeat(104));
+    for (idx, s) in strata.iter().enumerate() {
+        match sqlite.map(|r| &r[idx]) {
+            Some(l) => eprintln!(
+                "  {:<14} {:>14.0} {:>10} {:>10}   {:>14.0} {:>10} {:>10}   {:>7.2}x",
+                OPS[idx],
+                s.ops_per_sec,
+                fmt_us(s.p50),
+                fmt_us(s.p99),
+                l.ops_per_sec,
+                fmt_us(l.p50),
+                fmt_us(l.p99),
+                l.ops_per_sec / s.ops_per_sec,
+            ),
+            None => eprintln!(
+                "  {:<14} {:>14.0} {:>10} {:>10}",
+                OPS[idx],
+                s.ops_per_sec,
+                fmt_us(s.p50),
+                fmt_us(s.p99),
+            ),
+        }
+    }
+    eprintln!("  (ratio = SQLite ops/s ÷ Strata ops/s)");
+    eprintln!();
+}
+
+fn print_csv_header() {
+    println!("\"engine\",\"durability\",\"op\",\"count\",\"ops_sec\",\"p50_us\",\"p95_us\",\"p99_us\",\"found\"");
+}
+
+fn print_csv_rows(engine: &str, mode: DurabilityConfig, results: &EngineResults) {
+    for (idx, s) in results.iter().enumerate() {
+        println!(
+            "\"{}\",\"{}\",\"{}\",{},{:.2},{:.3},{:.3},{:.3},{}",
+            engine,
+            mode.label(),
+            OPS[idx],
+            s.count,
+            s.ops_per_sec,
+            s.p50.as_nanos() as f64 / 1000.0,
+            s.p95.as_nanos() as f64 / 1000.0,
+            s.p99.as_nanos() as f64 / 1000.0,
+            s.found.map(|f| f.to_string()).unwrap_or_default(),
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+fn record_open_failure(
+    recorder: &mut ResultRecorder,
+    engine: &str,
+    mode: DurabilityConfig,
+    config: &Config,
+    message: &str,
+) {
+    for op in OPS {
+        let mut params = HashMap::new();
+        params.insert("engine".into(), serde_json::json!(engine));
+        params.insert("durability".into(), serde_json::json!(mode.label()));
+        params.insert("op".into(), serde_json::json!(op));
+        params.insert("keys".into(), serde_json::json!(config.keys));
+        params.insert("value_size".into(), serde_json::json!(config.value_size));
+        recorder.record_failure(
+            &format!("kv-compare/{}/{}/{}", op, engine, mode.label()),
+            params,
+            ResultStatus::Error {
+                message: message.to_string(),
+            },
+        );
+    }
+}
+
+fn record_results(
+    recorder: &mut ResultRecorder,
+    engine: &str,
+    mode: DurabilityConfig,
+    config: &Config,
+    results: &EngineResults,
+) {
+    for (idx, s) in results.iter().enumerate() {
+        let op = OPS[idx];
+        let mut params = HashMap::new();
+        params.insert("engine".into(), serde_json::json!(engine));
+        params.insert("durability".into(), serde_json::json!(mode.label()));
+        params.insert("op".into(), serde_json::json!(op));
+        params.insert("keys".into(), serde_json::json!(config.keys));
+        params.insert("value_size".into(), serde_json::json!(config.value_size));
+        if let Some(found) = s.found {
+            params.insert("found".into(), serde_json::json!(found));
+        }
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!("kv-compare/{}/{}/{}", op, engine, mode.label()),
+            category: "kv-compare".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(s.ops_per_sec),
+                p50_ns: Some(s.p50.as_nanos() as u64),
+                p95_ns: Some(s.p95.as_nanos() as u64),
+                p99_ns: Some(s.p99.as_nanos() as u64),
+                min_ns: Some(s.min.as_nanos() as u64),
+                max_ns: Some(s.max.as_nanos() as u64),
+                avg_ns: Some(s.avg.as_nanos() as u64),
+                samples: Some(s.count as u64),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: s.spikes.clone(),
+            status: ResultStatus::Success,
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== Embedded Store Benchmark: Strata vs SQLite ===");
+        eprintln!(
+            "Keys: {} | KV value: {}B | JSON: harness json_document, set {}, get {}",
+            config.keys, config.value_size, SET_PATH, GET_PATH
+        );
+        if config.strata_only {
+            eprintln!("Mode: strata-only (SQLite comparison skipped)");
+        }
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let order = access_order(config.keys);
+    let mut recorder = ResultRecorder::new("kv-compare");
+
+    for &mode in &config.durability {
+        if !config.csv && !config.quiet {
+            eprintln!("--- durability: {} ---", mode.label());
+        }
+
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(mode.label()));
+        guard_params.insert("keys".into(), serde_json::json!(config.keys));
+        guard_params.insert("value_size".into(), serde_json::json!(config.value_size));
+        let guard_name = format!("kv-compare/{}", mode.label());
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            let mut strata_engine = StrataEngine {
+                bench_db: create_db(mode),
+            };
+            let strata = run_engine(&mut strata_engine, &config, &order);
+
+            let sqlite = if config.strata_only {
+                None
+            } else {
+                match SqliteEngine::open(mode) {
+                    Ok(mut engine) => Some(run_engine(&mut engine, &config, &order)),
+                    Err(e) => {
+                        let message = format!("failed to open SQLite database: {}", e);
+                        eprintln!("{}", message);
+                        record_open_failure(recorder, "sqlite", mode, &config, &message);
+                        None
+                    }
+                }
+            };
+
+            if config.csv {
+                print_csv_rows("strata", mode, &strata);
+                if let Some(r) = &sqlite {
+                    print_csv_rows("sqlite", mode, r);
+                }
+            } else if config.quiet {
+                for (idx, s) in strata.iter().enumerate() {
+                    let sqlite_part = sqlite
+                        .as_ref()
+                        .map(|r| format!(", sqlite={:.0} ops/s", r[idx].ops_per_sec))
+                        .unwrap_or_default();
+                    eprintln!(
+                        "{}/{}: strata={:.0} ops/s{}",
+                        OPS[idx],
+                        mode.label(),
+                        s.ops_per_sec,
+                        sqlite_part
+                    );
+                }
+            } else {
+                print_table(&strata, sqlite.as_ref());
+            }
+
+            record_results(recorder, "strata", mode, &config, &strata);
+            if let Some(r) = &sqlite {
+                record_results(recorder, "sqlite", mode, &config, r);
+            }
+        });
+    }
+
+    recorder.save_or_exit();
+}
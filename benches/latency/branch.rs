@@ -10,7 +10,7 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 
-use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use criterion::{BenchmarkId, Criterion, Throughput};
 use harness::recorder::ResultRecorder;
 use harness::{
     counter_delta, create_db, measure_with_counters, report_counters, report_percentiles,
@@ -145,12 +145,18 @@ fn branch_delete(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, branch_create, branch_switch, branch_delete);
-
 fn main() {
     *RECORDER.lock().unwrap() = Some(ResultRecorder::new("latency"));
-    benches();
+    let mut criterion = Criterion::default().configure_from_args();
+    let targets: [(&str, fn(&mut Criterion)); 3] = [
+        ("branch/create", branch_create),
+        ("branch/switch", branch_switch),
+        ("branch/delete", branch_delete),
+    ];
+    for (name, target) in targets {
+        ResultRecorder::guard_shared(&RECORDER, name, HashMap::new(), || target(&mut criterion));
+    }
     if let Some(recorder) = RECORDER.lock().unwrap().take() {
-        let _ = recorder.save();
+        recorder.save_or_exit();
     }
 }
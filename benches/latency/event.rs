@@ -1,6 +1,10 @@
-//! Event primitive benchmarks: append, read, read_by_type
+//! Event primitive benchmarks: append, read, read_by_type, append_size
 //!
-//! All benchmarks report latency percentiles.
+//! All benchmarks report latency percentiles. `append_size` sweeps the
+//! payload from 100B to 1MB (tool outputs and transcripts are large) and also
+//! reports events/sec and MB/s. A payload size whose per-byte cost jumps
+//! above the previous size's is flagged as a cliff: per-byte cost should only
+//! fall as fixed per-event overhead is amortized.
 
 #[allow(unused)]
 #[path = "../harness/mod.rs"]
@@ -9,16 +13,40 @@ mod harness;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
+use std::time::Instant;
 
-use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use criterion::{BenchmarkId, Criterion, Throughput};
 use harness::recorder::ResultRecorder;
 use harness::{
     create_db, event_payload, measure_with_counters, report_counters, report_percentiles,
     DurabilityConfig, PERCENTILE_SAMPLES, WARMUP_COUNT,
 };
+use stratadb::Value;
 
 static RECORDER: Mutex<Option<ResultRecorder>> = Mutex::new(None);
 
+/// Payload sizes for append_size, in bytes.
+const PAYLOAD_SIZES: &[usize] = &[100, 1_000, 10_000, 100_000, 1_000_000];
+/// Bytes appended per size for percentiles; caps samples at large payloads.
+const SIZE_SWEEP_BYTES: usize = 64 << 20;
+/// Per-byte cost growth over the previous size that counts as a cliff.
+const CLIFF_FACTOR: f64 = 1.5;
+
+/// Event payload carrying `bytes` of data in a single field.
+fn event_payload_sized(bytes: usize) -> Value {
+    let mut map = HashMap::new();
+    map.insert("data".to_string(), Value::Bytes(vec![0x45; bytes]));
+    Value::Object(map)
+}
+
+fn size_label(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1_000_000 => format!("{}MB", b / 1_000_000),
+        b if b >= 1_000 => format!("{}KB", b / 1_000),
+        b => format!("{}B", b),
+    }
+}
+
 fn event_append(c: &mut Criterion) {
     let mut group = c.benchmark_group("event/append");
     group.throughput(Throughput::Elements(1));
@@ -101,7 +129,7 @@ fn event_read_by_type(c: &mut Criterion) {
     for mode in DurabilityConfig::ALL {
         let bench_db = create_db(mode);
         for i in 0..1000u64 {
-            let event_type = if i % 2 == 0 { "type_a" } else { "type_b" };
+            let event_type = if i.is_multiple_of(2) { "type_a" } else { "type_b" };
             bench_db
                 .db
                 .event_append(event_type, event_payload())
@@ -129,12 +157,86 @@ fn event_read_by_type(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, event_append, event_read, event_read_by_type);
+fn event_append_size(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event/append_size");
+    group.sample_size(20);
+
+    eprintln!("\n--- Latency Percentiles: event/append_size ---");
+    for mode in DurabilityConfig::ALL {
+        let bench_db = create_db(mode);
+        let mut prev: Option<(usize, f64)> = None;
+        for &size in PAYLOAD_SIZES {
+            let payload = event_payload_sized(size);
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_function(BenchmarkId::new(mode.label(), size_label(size)), |b| {
+                b.iter(|| {
+                    bench_db
+                        .db
+                        .event_append("bench_event", payload.clone())
+                        .unwrap();
+                });
+            });
+
+            let samples = (SIZE_SWEEP_BYTES / size).clamp(50, PERCENTILE_SAMPLES);
+            let label = format!("event/append_size/{}/{}", size_label(size), mode.label());
+            let start = Instant::now();
+            let (p, counters) = measure_with_counters(&bench_db, samples, || {
+                bench_db
+                    .db
+                    .event_append("bench_event", payload.clone())
+                    .unwrap();
+            });
+            let elapsed = start.elapsed().as_secs_f64();
+            let events_per_sec = samples as f64 / elapsed;
+            let mb_per_sec = (samples * size) as f64 / elapsed / 1e6;
+            let ns_per_byte = p.p50.as_nanos() as f64 / size as f64;
+            let cliff_from = prev.filter(|&(_, prev_npb)| ns_per_byte > prev_npb * CLIFF_FACTOR);
+
+            report_percentiles(&label, &p);
+            eprintln!(
+                "  {:<45} events/sec={:.0}  MB/s={:.1}  p50 ns/byte={:.2}",
+                label, events_per_sec, mb_per_sec, ns_per_byte
+            );
+            if let Some((prev_size, prev_npb)) = cliff_from {
+                eprintln!(
+                    "  {:<45} CLIFF: {:.2} ns/byte vs {:.2} at {}",
+                    label,
+                    ns_per_byte,
+                    prev_npb,
+                    size_label(prev_size)
+                );
+            }
+            report_counters(&label, &counters, samples as u64);
+
+            if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
+                let mut params = HashMap::new();
+                params.insert("durability".into(), serde_json::json!(mode.label()));
+                params.insert("payload_bytes".into(), serde_json::json!(size));
+                params.insert("events_per_sec".into(), serde_json::json!(events_per_sec));
+                params.insert("mb_per_sec".into(), serde_json::json!(mb_per_sec));
+                params.insert("p50_ns_per_byte".into(), serde_json::json!(ns_per_byte));
+                params.insert("cliff".into(), serde_json::json!(cliff_from.is_some()));
+                rec.record_latency(&label, params, &p, Some(&counters), samples as u64);
+            }
+            prev = Some((size, ns_per_byte));
+        }
+    }
+    group.finish();
+}
 
 fn main() {
     *RECORDER.lock().unwrap() = Some(ResultRecorder::new("latency"));
-    benches();
+    let mut criterion = Criterion::default().configure_from_args();
+    let targets: [(&str, fn(&mut Criterion)); 4] = [
+        ("event/append", event_append),
+        ("event/read", event_read),
+        ("event/read_by_type", event_read_by_type),
+        ("event/append_size", event_append_size),
+    ];
+    for (name, target) in targets {
+        ResultRecorder::guard_shared(&RECORDER, name, HashMap::new(), || target(&mut criterion));
+    }
     if let Some(recorder) = RECORDER.lock().unwrap().take() {
-        let _ = recorder.save();
+        recorder.save_or_exit();
     }
 }
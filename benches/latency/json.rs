@@ -1,25 +1,151 @@
-//! JSON primitive benchmarks: set_root, set_path, get, list
+//! JSON primitive benchmarks: set_root, set_path, get, list, and path
+//! set/get/delete at shallow and deep paths
 //!
-//! All benchmarks report latency percentiles.
+//! The path benchmarks run against the same documents at depth 1 (`$.score`)
+//! and depth 8 (`$.nest.….leaf`), so the gap between the two rows is the cost
+//! of path depth alone.
+//!
+//! All benchmarks report latency percentiles. Each operation also gets an
+//! in-memory baseline row (serde_json documents in a BTreeMap, no persistence)
+//! so the cost of durability and storage is visible next to the raw
+//! document-manipulation cost, the same way graph_bfs reports petgraph.
 
 #[allow(unused)]
 #[path = "../harness/mod.rs"]
 mod harness;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 
-use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use criterion::{BenchmarkId, Criterion, Throughput};
 use harness::recorder::ResultRecorder;
 use harness::{
-    create_db, json_document, measure_with_counters, report_counters, report_percentiles,
-    DurabilityConfig, PERCENTILE_SAMPLES, WARMUP_COUNT,
+    create_db, json_document, measure_percentiles, measure_with_counters, report_counters,
+    report_percentiles, BenchDb, DurabilityConfig, Percentiles, PERCENTILE_SAMPLES, WARMUP_COUNT,
 };
-use stratadb::Value;
+use stratadb::{Value, WalCounters};
 
 static RECORDER: Mutex<Option<ResultRecorder>> = Mutex::new(None);
 
+/// Documents pre-populated for the path benchmarks.
+const PATH_DOCS: u64 = 1000;
+
+/// (label, path, depth) for the path benchmarks. Both paths exist in every
+/// `nested_document`.
+const PATHS: [(&str, &str, usize); 2] = [
+    ("shallow", "$.score", 1),
+    ("deep", "$.nest.nest.nest.nest.nest.nest.nest.leaf", 8),
+];
+
+/// `json_document(i)` plus a chain of `nest` objects seven levels deep
+/// ending in a `leaf` field.
+fn nested_document(i: u64) -> Value {
+    let mut leaf = HashMap::new();
+    leaf.insert("leaf".to_string(), Value::Int(i as i64));
+    let mut nest = Value::Object(leaf);
+    for _ in 0..6 {
+        let mut m = HashMap::new();
+        m.insert("nest".to_string(), nest);
+        nest = Value::Object(m);
+    }
+
+    let Value::Object(mut doc) = json_document(i) else {
+        unreachable!("json_document returns an object");
+    };
+    doc.insert("nest".to_string(), nest);
+    Value::Object(doc)
+}
+
+// =============================================================================
+// In-memory baseline
+// =============================================================================
+
+/// Baseline document store: serde_json trees keyed by document id. Every
+/// operation converts from/to `stratadb::Value` just like the Strata API
+/// call it mirrors, so the difference is persistence, not conversion.
+#[derive(Default)]
+struct JsonBaseline {
+    docs: BTreeMap<String, serde_json::Value>,
+}
+
+impl JsonBaseline {
+    fn set_root(&mut self, key: &str, value: Value) {
+        self.docs.insert(key.to_string(), to_serde(&value));
+    }
+
+    /// Set a value at a `$.a.b` path (same syntax as `json_set`). A missing
+    /// last field is created, as `json_set` does.
+    fn set_path(&mut self, key: &str, path: &str, value: Value) {
+        let pointer = path.trim_start_matches('$').replace('.', "/");
+        let Some(doc) = self.docs.get_mut(key) else {
+            return;
+        };
+        if let Some(slot) = doc.pointer_mut(&pointer) {
+            *slot = to_serde(&value);
+        } else if let Some((parent, field)) = pointer.rsplit_once('/') {
+            if let Some(obj) = doc.pointer_mut(parent).and_then(|p| p.as_object_mut()) {
+                obj.insert(field.to_string(), to_serde(&value));
+            }
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<serde_json::Value> {
+        self.docs.get(key).cloned()
+    }
+
+    fn get_path(&self, key: &str, path: &str) -> Option<serde_json::Value> {
+        let pointer = path.trim_start_matches('$').replace('.', "/");
+        self.docs.get(key)?.pointer(&pointer).cloned()
+    }
+
+    fn delete_path(&mut self, key: &str, path: &str) -> Option<serde_json::Value> {
+        let pointer = path.trim_start_matches('$').replace('.', "/");
+        let (parent, field) = pointer.rsplit_once('/')?;
+        self.docs
+            .get_mut(key)?
+            .pointer_mut(parent)?
+            .as_object_mut()?
+            .remove(field)
+    }
+
+    fn list(&self, prefix: &str, limit: usize) -> Vec<String> {
+        self.docs
+            .range(prefix.to_string()..)
+            .take_while(|(k, _)| k.starts_with(prefix))
+            .take(limit)
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+}
+
+fn to_serde(v: &Value) -> serde_json::Value {
+    match v {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::Int(i) => serde_json::json!(i),
+        Value::Float(f) => serde_json::Number::from_f64(*f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Value::String(s) => serde_json::Value::String(s.clone()),
+        Value::Bytes(b) => serde_json::json!(b),
+        Value::Array(a) => serde_json::Value::Array(a.iter().map(to_serde).collect()),
+        Value::Object(m) => serde_json::Value::Object(
+            m.iter().map(|(k, v)| (k.clone(), to_serde(v))).collect(),
+        ),
+    }
+}
+
+fn record_baseline(label: &str, p: &Percentiles) {
+    report_percentiles(label, p);
+    if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
+        let mut params = HashMap::new();
+        params.insert("durability".into(), serde_json::json!("none"));
+        params.insert("baseline".into(), serde_json::json!("serde_json"));
+        rec.record_latency(label, params, p, None, PERCENTILE_SAMPLES as u64);
+    }
+}
+
 fn json_set_root(c: &mut Criterion) {
     let mut group = c.benchmark_group("json/set_root");
     group.throughput(Throughput::Elements(1));
@@ -56,6 +182,22 @@ fn json_set_root(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    let mut baseline = JsonBaseline::default();
+    let counter = AtomicU64::new(0);
+    group.bench_function(BenchmarkId::new("baseline", "serde_json"), |b| {
+        b.iter(|| {
+            let i = counter.fetch_add(1, Ordering::Relaxed);
+            baseline.set_root(&format!("doc:{}", i), json_document(i));
+        });
+    });
+    let mut baseline = JsonBaseline::default();
+    let pct_counter = AtomicU64::new(u64::MAX / 2);
+    let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+        let i = pct_counter.fetch_add(1, Ordering::Relaxed);
+        baseline.set_root(&format!("doc:{}", i), json_document(i));
+    });
+    record_baseline("json/set_root/baseline-serde_json", &p);
     group.finish();
 }
 
@@ -109,6 +251,32 @@ fn json_set_path(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    let mut baseline = JsonBaseline::default();
+    for i in 0..1000u64 {
+        baseline.set_root(&format!("doc:{}", i), json_document(i));
+    }
+    let counter = AtomicU64::new(0);
+    group.bench_function(BenchmarkId::new("baseline", "serde_json"), |b| {
+        b.iter(|| {
+            let i = counter.fetch_add(1, Ordering::Relaxed) % 1000;
+            baseline.set_path(
+                &format!("doc:{}", i),
+                "$.metadata.mid_score",
+                Value::Float(i as f64 * 2.5),
+            );
+        });
+    });
+    let pct_counter = AtomicU64::new(0);
+    let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+        let i = pct_counter.fetch_add(1, Ordering::Relaxed) % 1000;
+        baseline.set_path(
+            &format!("doc:{}", i),
+            "$.metadata.mid_score",
+            Value::Float(i as f64 * 2.5),
+        );
+    });
+    record_baseline("json/set_path/baseline-serde_json", &p);
     group.finish();
 }
 
@@ -148,6 +316,24 @@ fn json_get(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    let mut baseline = JsonBaseline::default();
+    for i in 0..WARMUP_COUNT {
+        baseline.set_root(&format!("doc:{}", i), json_document(i));
+    }
+    let counter = AtomicU64::new(0);
+    group.bench_function(BenchmarkId::new("baseline", "serde_json"), |b| {
+        b.iter(|| {
+            let i = counter.fetch_add(1, Ordering::Relaxed) % WARMUP_COUNT;
+            baseline.get(&format!("doc:{}", i)).unwrap();
+        });
+    });
+    let pct_counter = AtomicU64::new(0);
+    let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+        let i = pct_counter.fetch_add(1, Ordering::Relaxed) % WARMUP_COUNT;
+        baseline.get(&format!("doc:{}", i)).unwrap();
+    });
+    record_baseline("json/get/baseline-serde_json", &p);
     group.finish();
 }
 
@@ -189,15 +375,229 @@ fn json_list(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    let mut baseline = JsonBaseline::default();
+    for i in 0..1000u64 {
+        baseline.set_root(&format!("bench:{}", i), json_document(i));
+    }
+    group.bench_function(BenchmarkId::new("baseline", "serde_json"), |b| {
+        b.iter(|| {
+            baseline.list("bench:", 100);
+        });
+    });
+    let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+        baseline.list("bench:", 100);
+    });
+    record_baseline("json/list/baseline-serde_json", &p);
     group.finish();
 }
 
-criterion_group!(benches, json_set_root, json_set_path, json_get, json_list);
+fn record_path(
+    label: &str,
+    mode: DurabilityConfig,
+    path: &str,
+    depth: usize,
+    p: &Percentiles,
+    counters: &WalCounters,
+) {
+    report_percentiles(label, p);
+    report_counters(label, counters, PERCENTILE_SAMPLES as u64);
+    if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
+        let mut params = HashMap::new();
+        params.insert("durability".into(), serde_json::json!(mode.label()));
+        params.insert("path".into(), serde_json::json!(path));
+        params.insert("path_depth".into(), serde_json::json!(depth));
+        rec.record_latency(label, params, p, Some(counters), PERCENTILE_SAMPLES as u64);
+    }
+}
+
+fn populate_nested(bench_db: &BenchDb) {
+    for i in 0..PATH_DOCS {
+        bench_db
+            .db
+            .json_set(&format!("doc:{}", i), "$", nested_document(i))
+            .unwrap();
+    }
+}
+
+fn baseline_nested() -> JsonBaseline {
+    let mut baseline = JsonBaseline::default();
+    for i in 0..PATH_DOCS {
+        baseline.set_root(&format!("doc:{}", i), nested_document(i));
+    }
+    baseline
+}
+
+fn json_path_set(c: &mut Criterion) {
+    let mut group = c.benchmark_group("json/path_set");
+    group.throughput(Throughput::Elements(1));
+
+    eprintln!("\n--- Latency Percentiles: json/path_set ---");
+    for mode in DurabilityConfig::ALL {
+        let bench_db = create_db(mode);
+        populate_nested(&bench_db);
+        for (name, path, depth) in PATHS {
+            let counter = AtomicU64::new(0);
+            group.bench_function(BenchmarkId::new(name, mode.label()), |b| {
+                b.iter(|| {
+                    let i = counter.fetch_add(1, Ordering::Relaxed);
+                    bench_db
+                        .db
+                        .json_set(&format!("doc:{}", i % PATH_DOCS), path, Value::Int(i as i64))
+                        .unwrap();
+                });
+            });
+
+            let pct_counter = AtomicU64::new(0);
+            let label = format!("json/path_set/{}/{}", name, mode.label());
+            let (p, counters) = measure_with_counters(&bench_db, PERCENTILE_SAMPLES, || {
+                let i = pct_counter.fetch_add(1, Ordering::Relaxed);
+                bench_db
+                    .db
+                    .json_set(&format!("doc:{}", i % PATH_DOCS), path, Value::Int(i as i64))
+                    .unwrap();
+            });
+            record_path(&label, mode, path, depth, &p, &counters);
+        }
+    }
+
+    let mut baseline = baseline_nested();
+    for (name, path, _) in PATHS {
+        let counter = AtomicU64::new(0);
+        group.bench_function(BenchmarkId::new(name, "baseline-serde_json"), |b| {
+            b.iter(|| {
+                let i = counter.fetch_add(1, Ordering::Relaxed);
+                baseline.set_path(&format!("doc:{}", i % PATH_DOCS), path, Value::Int(i as i64));
+            });
+        });
+        let pct_counter = AtomicU64::new(0);
+        let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+            let i = pct_counter.fetch_add(1, Ordering::Relaxed);
+            baseline.set_path(&format!("doc:{}", i % PATH_DOCS), path, Value::Int(i as i64));
+        });
+        record_baseline(&format!("json/path_set/{}/baseline-serde_json", name), &p);
+    }
+    group.finish();
+}
+
+fn json_path_get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("json/path_get");
+    group.throughput(Throughput::Elements(1));
+
+    eprintln!("\n--- Latency Percentiles: json/path_get ---");
+    for mode in DurabilityConfig::ALL {
+        let bench_db = create_db(mode);
+        populate_nested(&bench_db);
+        for (name, path, depth) in PATHS {
+            let counter = AtomicU64::new(0);
+            group.bench_function(BenchmarkId::new(name, mode.label()), |b| {
+                b.iter(|| {
+                    let i = counter.fetch_add(1, Ordering::Relaxed) % PATH_DOCS;
+                    bench_db.db.json_get(&format!("doc:{}", i), path).unwrap();
+                });
+            });
+
+            let pct_counter = AtomicU64::new(0);
+            let label = format!("json/path_get/{}/{}", name, mode.label());
+            let (p, counters) = measure_with_counters(&bench_db, PERCENTILE_SAMPLES, || {
+                let i = pct_counter.fetch_add(1, Ordering::Relaxed) % PATH_DOCS;
+                assert!(bench_db.db.json_get(&format!("doc:{}", i), path).unwrap().is_some());
+            });
+            record_path(&label, mode, path, depth, &p, &counters);
+        }
+    }
+
+    let baseline = baseline_nested();
+    for (name, path, _) in PATHS {
+        let counter = AtomicU64::new(0);
+        group.bench_function(BenchmarkId::new(name, "baseline-serde_json"), |b| {
+            b.iter(|| {
+                let i = counter.fetch_add(1, Ordering::Relaxed) % PATH_DOCS;
+                baseline.get_path(&format!("doc:{}", i), path).unwrap();
+            });
+        });
+        let pct_counter = AtomicU64::new(0);
+        let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+            let i = pct_counter.fetch_add(1, Ordering::Relaxed) % PATH_DOCS;
+            baseline.get_path(&format!("doc:{}", i), path).unwrap();
+        });
+        record_baseline(&format!("json/path_get/{}/baseline-serde_json", name), &p);
+    }
+    group.finish();
+}
+
+/// Each iteration sets the field and deletes it again, like kv/delete's
+/// put + delete, so every delete removes a field that exists.
+fn json_path_delete(c: &mut Criterion) {
+    let mut group = c.benchmark_group("json/path_delete");
+    group.throughput(Throughput::Elements(1));
+
+    eprintln!("\n--- Latency Percentiles: json/path_delete ---");
+    for mode in DurabilityConfig::ALL {
+        let bench_db = create_db(mode);
+        populate_nested(&bench_db);
+        for (name, path, depth) in PATHS {
+            let counter = AtomicU64::new(0);
+            group.bench_function(BenchmarkId::new(name, mode.label()), |b| {
+                b.iter(|| {
+                    let i = counter.fetch_add(1, Ordering::Relaxed);
+                    let key = format!("doc:{}", i % PATH_DOCS);
+                    bench_db.db.json_set(&key, path, Value::Int(i as i64)).unwrap();
+                    bench_db.db.json_delete(&key, path).unwrap();
+                });
+            });
+
+            let pct_counter = AtomicU64::new(0);
+            let label = format!("json/path_delete/{}/{}", name, mode.label());
+            let (p, counters) = measure_with_counters(&bench_db, PERCENTILE_SAMPLES, || {
+                let i = pct_counter.fetch_add(1, Ordering::Relaxed);
+                let key = format!("doc:{}", i % PATH_DOCS);
+                bench_db.db.json_set(&key, path, Value::Int(i as i64)).unwrap();
+                bench_db.db.json_delete(&key, path).unwrap();
+            });
+            record_path(&label, mode, path, depth, &p, &counters);
+        }
+    }
+
+    let mut baseline = baseline_nested();
+    for (name, path, _) in PATHS {
+        let counter = AtomicU64::new(0);
+        group.bench_function(BenchmarkId::new(name, "baseline-serde_json"), |b| {
+            b.iter(|| {
+                let i = counter.fetch_add(1, Ordering::Relaxed);
+                let key = format!("doc:{}", i % PATH_DOCS);
+                baseline.set_path(&key, path, Value::Int(i as i64));
+                baseline.delete_path(&key, path).unwrap();
+            });
+        });
+        let pct_counter = AtomicU64::new(0);
+        let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+            let i = pct_counter.fetch_add(1, Ordering::Relaxed);
+            let key = format!("doc:{}", i % PATH_DOCS);
+            baseline.set_path(&key, path, Value::Int(i as i64));
+            baseline.delete_path(&key, path).unwrap();
+        });
+        record_baseline(&format!("json/path_delete/{}/baseline-serde_json", name), &p);
+    }
+    group.finish();
+}
 
 fn main() {
     *RECORDER.lock().unwrap() = Some(ResultRecorder::new("latency"));
-    benches();
+    let mut criterion = Criterion::default().configure_from_args();
+    let targets: [(&str, fn(&mut Criterion)); 7] = [
+        ("json/set_root", json_set_root),
+        ("json/set_path", json_set_path),
+        ("json/get", json_get),
+        ("json/list", json_list),
+        ("json/path_set", json_path_set),
+        ("json/path_get", json_path_get),
+        ("json/path_delete", json_path_delete),
+    ];
+    for (name, target) in targets {
+        ResultRecorder::guard_shared(&RECORDER, name, HashMap::new(), || target(&mut criterion));
+    }
     if let Some(recorder) = RECORDER.lock().unwrap().take() {
-        let _ = recorder.save();
+        recorder.save_or_exit();
     }
 }
@@ -1,24 +1,272 @@
-//! KV primitive benchmarks: put, get, delete, list_prefix
+//! KV primitive benchmarks: put, get, delete, list_prefix, put_type, get_type,
+//! multi_get
 //!
-//! All benchmarks report latency percentiles.
+//! All benchmarks report latency percentiles. Each operation also gets
+//! in-memory baseline rows (`std::collections::HashMap` and `BTreeMap`, same
+//! keys, values, and measurement loop, no persistence) so the per-operation
+//! cost of durability and storage is visible in the same results file.
+//!
+//! `put_type`/`get_type` repeat put and get for each `Value` variant at about
+//! the same encoded size, so differences between rows are the cost of
+//! encoding and decoding that type.
+//!
+//! Strata has no multi-key read call, so `multi_get` batches reads as one
+//! transaction of `KvGet` commands on a session and compares it against the
+//! same keys read with a plain `kv_get` loop. Rows report the latency of a
+//! whole batch; the effective per-key latency is in the parameters.
 
 #[allow(unused)]
 #[path = "../harness/mod.rs"]
 mod harness;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 
-use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use criterion::{BenchmarkId, Criterion, Throughput};
 use harness::recorder::ResultRecorder;
 use harness::{
-    create_db, kv_key, kv_key_with_prefix, kv_value, measure_with_counters, report_counters,
-    report_percentiles, DurabilityConfig, PERCENTILE_SAMPLES, WARMUP_COUNT,
+    create_db, kv_key, kv_key_with_prefix, kv_value, measure_percentiles, measure_with_counters,
+    report_counters, report_percentiles, DurabilityConfig, Percentiles, PERCENTILE_SAMPLES,
+    WARMUP_COUNT,
 };
+use stratadb::{Command, Session, Value, WalCounters};
 
 static RECORDER: Mutex<Option<ResultRecorder>> = Mutex::new(None);
 
+// =============================================================================
+// In-memory baselines
+// =============================================================================
+
+/// A std collection standing in for the database. Values are `stratadb::Value`
+/// built by the same generators, so only storage differs.
+enum Baseline {
+    Hash(HashMap<String, Value>),
+    BTree(BTreeMap<String, Value>),
+}
+
+impl Baseline {
+    const KINDS: [&'static str; 2] = ["hashmap", "btreemap"];
+
+    fn new(kind: &str) -> Self {
+        match kind {
+            "hashmap" => Baseline::Hash(HashMap::new()),
+            _ => Baseline::BTree(BTreeMap::new()),
+        }
+    }
+
+    fn put(&mut self, key: &str, value: Value) {
+        match self {
+            Baseline::Hash(m) => {
+                m.insert(key.to_string(), value);
+            }
+            Baseline::BTree(m) => {
+                m.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<Value> {
+        match self {
+            Baseline::Hash(m) => m.get(key).cloned(),
+            Baseline::BTree(m) => m.get(key).cloned(),
+        }
+    }
+
+    fn delete(&mut self, key: &str) {
+        match self {
+            Baseline::Hash(m) => {
+                m.remove(key);
+            }
+            Baseline::BTree(m) => {
+                m.remove(key);
+            }
+        }
+    }
+
+    /// Keys with `prefix`. The HashMap has no ordering, so this is a full
+    /// scan; the BTreeMap seeks to the prefix like a sorted store does.
+    fn list_prefix(&self, prefix: &str) -> Vec<String> {
+        match self {
+            Baseline::Hash(m) => m.keys().filter(|k| k.starts_with(prefix)).cloned().collect(),
+            Baseline::BTree(m) => m
+                .range(prefix.to_string()..)
+                .take_while(|(k, _)| k.starts_with(prefix))
+                .map(|(k, _)| k.clone())
+                .collect(),
+        }
+    }
+}
+
+fn record_baseline(label: &str, kind: &str, p: &Percentiles) {
+    report_percentiles(label, p);
+    if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
+        let mut params = HashMap::new();
+        params.insert("durability".into(), serde_json::json!("none"));
+        params.insert("baseline".into(), serde_json::json!(kind));
+        rec.record_latency(label, params, p, None, PERCENTILE_SAMPLES as u64);
+    }
+}
+
+// =============================================================================
+// Value-type matrix
+// =============================================================================
+
+/// Encoded size the variable-size types are built up to. Int and Float are
+/// fixed-size and can't grow; they are the floor of the matrix.
+const TYPED_VALUE_BYTES: usize = 1024;
+/// Keys per type for the get benchmark.
+const TYPED_KEYS: u64 = 100;
+
+const VALUE_TYPES: [&str; 7] = [
+    "int",
+    "float",
+    "string",
+    "bytes",
+    "small_object",
+    "large_object",
+    "nested_array",
+];
+
+/// Approximate JSON-encoded size of a value. Bytes count as their raw length.
+fn encoded_len(value: &Value) -> usize {
+    let commas = |n: usize| n.saturating_sub(1);
+    match value {
+        Value::Null => 4,
+        Value::Bool(true) => 4,
+        Value::Bool(false) => 5,
+        Value::Int(i) => i.to_string().len(),
+        Value::Float(f) => f.to_string().len(),
+        Value::String(s) => s.len() + 2,
+        Value::Bytes(b) => b.len(),
+        Value::Array(items) => 2 + commas(items.len()) + items.iter().map(encoded_len).sum::<usize>(),
+        Value::Object(fields) => {
+            2 + commas(fields.len())
+                + fields.iter().map(|(k, v)| k.len() + 3 + encoded_len(v)).sum::<usize>()
+        }
+        _ => 0,
+    }
+}
+
+/// The value used for `value_type`, built up to about `TYPED_VALUE_BYTES`.
+fn typed_value(value_type: &str) -> Value {
+    match value_type {
+        "int" => Value::Int(i64::MAX / 3),
+        "float" => Value::Float(std::f64::consts::PI),
+        "string" => Value::String("s".repeat(TYPED_VALUE_BYTES - 2)),
+        "bytes" => Value::Bytes(vec![0x42; TYPED_VALUE_BYTES]),
+        // Four string fields sharing the size: few fields, long values
+        "small_object" => {
+            let fields = 4;
+            let overhead = 2 + (fields - 1) + fields * ("field_0".len() + 3 + 2);
+            let len = (TYPED_VALUE_BYTES - overhead) / fields;
+            Value::Object(
+                (0..fields)
+                    .map(|f| (format!("field_{}", f), Value::String("o".repeat(len))))
+                    .collect(),
+            )
+        }
+        // Many short int fields: per-field overhead dominates
+        "large_object" => {
+            let mut fields = HashMap::new();
+            let mut value = Value::Object(HashMap::new());
+            while encoded_len(&value) < TYPED_VALUE_BYTES {
+                let n = fields.len();
+                fields.insert(format!("f{:03}", n), Value::Int(n as i64));
+                value = Value::Object(fields.clone());
+            }
+            value
+        }
+        // Four levels deep: [[[ [8 ints], [8 ints], ... ]]]
+        _ => {
+            let chunk = Value::Array((1000..1008).map(Value::Int).collect());
+            let wrap =
+                |leaves: Vec<Value>| Value::Array(vec![Value::Array(vec![Value::Array(leaves)])]);
+            let mut leaves = Vec::new();
+            while encoded_len(&wrap(leaves.clone())) < TYPED_VALUE_BYTES {
+                leaves.push(chunk.clone());
+            }
+            wrap(leaves)
+        }
+    }
+}
+
+fn record_typed(
+    label: &str,
+    mode: DurabilityConfig,
+    value_type: &str,
+    bytes: usize,
+    p: &Percentiles,
+    counters: &WalCounters,
+) {
+    report_percentiles(label, p);
+    report_counters(label, counters, PERCENTILE_SAMPLES as u64);
+    if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
+        let mut params = HashMap::new();
+        params.insert("durability".into(), serde_json::json!(mode.label()));
+        params.insert("value_type".into(), serde_json::json!(value_type));
+        params.insert("value_bytes".into(), serde_json::json!(bytes));
+        rec.record_latency(label, params, p, Some(counters), PERCENTILE_SAMPLES as u64);
+    }
+}
+
+/// Keys per `multi_get` call.
+const MULTI_GET_BATCHES: [usize; 3] = [10, 100, 1000];
+
+/// Stride between the keys of one batch, so a batch is scattered over the
+/// keyspace rather than one contiguous range.
+const MULTI_GET_STRIDE: u64 = 7919;
+
+/// The `j`th key of batch number `n`, over the `WARMUP_COUNT` pre-populated keys.
+fn multi_get_key(n: u64, batch: usize, j: usize) -> String {
+    let base = n.wrapping_mul(batch as u64);
+    kv_key((base + j as u64 * MULTI_GET_STRIDE) % WARMUP_COUNT)
+}
+
+/// Read one batch in a single transaction.
+fn multi_get_txn(session: &mut Session, n: u64, batch: usize) {
+    session
+        .execute(Command::TxnBegin { branch: None, options: None })
+        .unwrap();
+    for j in 0..batch {
+        session
+            .execute(Command::KvGet {
+                branch: None,
+                space: None,
+                key: multi_get_key(n, batch, j),
+                as_of: None,
+            })
+            .unwrap();
+    }
+    session.execute(Command::TxnCommit).unwrap();
+}
+
+fn record_multi_get(
+    label: &str,
+    mode: DurabilityConfig,
+    method: &str,
+    batch: usize,
+    p: &Percentiles,
+    counters: &WalCounters,
+) {
+    report_percentiles(label, p);
+    report_counters(label, counters, PERCENTILE_SAMPLES as u64);
+    let per_key_p50_ns = p.p50.as_nanos() as f64 / batch as f64;
+    eprintln!("  {:<40} {:>10.0} ns/key (p50)", label, per_key_p50_ns);
+    if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
+        let mut params = HashMap::new();
+        params.insert("durability".into(), serde_json::json!(mode.label()));
+        params.insert("method".into(), serde_json::json!(method));
+        params.insert("batch_size".into(), serde_json::json!(batch));
+        params.insert("per_key_p50_ns".into(), serde_json::json!(per_key_p50_ns));
+        params.insert(
+            "per_key_p99_ns".into(),
+            serde_json::json!(p.p99.as_nanos() as f64 / batch as f64),
+        );
+        rec.record_latency(label, params, p, Some(counters), PERCENTILE_SAMPLES as u64);
+    }
+}
+
 fn kv_put(c: &mut Criterion) {
     let mut group = c.benchmark_group("kv/put");
     group.throughput(Throughput::Elements(1));
@@ -40,6 +288,7 @@ fn kv_put(c: &mut Criterion) {
             let i = pct_counter.fetch_add(1, Ordering::Relaxed);
             bench_db.db.kv_put(&kv_key(i), kv_value()).unwrap();
         });
+        let p = p.with_context(|i| kv_key(u64::MAX / 2 + i as u64));
         report_percentiles(&label, &p);
         report_counters(&label, &counters, PERCENTILE_SAMPLES as u64);
 
@@ -49,6 +298,25 @@ fn kv_put(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    for kind in Baseline::KINDS {
+        let mut baseline = Baseline::new(kind);
+        let counter = AtomicU64::new(0);
+        group.bench_function(BenchmarkId::new("baseline", kind), |b| {
+            b.iter(|| {
+                let i = counter.fetch_add(1, Ordering::Relaxed);
+                baseline.put(&kv_key(i), kv_value());
+            });
+        });
+
+        let mut baseline = Baseline::new(kind);
+        let pct_counter = AtomicU64::new(u64::MAX / 2);
+        let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+            let i = pct_counter.fetch_add(1, Ordering::Relaxed);
+            baseline.put(&kv_key(i), kv_value());
+        });
+        record_baseline(&format!("kv/put/baseline-{}", kind), kind, &p);
+    }
     group.finish();
 }
 
@@ -76,6 +344,7 @@ fn kv_get(c: &mut Criterion) {
             let i = pct_counter.fetch_add(1, Ordering::Relaxed) % WARMUP_COUNT;
             bench_db.db.kv_get(&kv_key(i)).unwrap();
         });
+        let p = p.with_context(|i| kv_key(i as u64 % WARMUP_COUNT));
         report_percentiles(&label, &p);
         report_counters(&label, &counters, PERCENTILE_SAMPLES as u64);
 
@@ -85,6 +354,27 @@ fn kv_get(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    for kind in Baseline::KINDS {
+        let mut baseline = Baseline::new(kind);
+        for i in 0..WARMUP_COUNT {
+            baseline.put(&kv_key(i), kv_value());
+        }
+        let counter = AtomicU64::new(0);
+        group.bench_function(BenchmarkId::new("baseline", kind), |b| {
+            b.iter(|| {
+                let i = counter.fetch_add(1, Ordering::Relaxed) % WARMUP_COUNT;
+                baseline.get(&kv_key(i)).unwrap();
+            });
+        });
+
+        let pct_counter = AtomicU64::new(0);
+        let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+            let i = pct_counter.fetch_add(1, Ordering::Relaxed) % WARMUP_COUNT;
+            baseline.get(&kv_key(i)).unwrap();
+        });
+        record_baseline(&format!("kv/get/baseline-{}", kind), kind, &p);
+    }
     group.finish();
 }
 
@@ -122,6 +412,28 @@ fn kv_delete(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    for kind in Baseline::KINDS {
+        let mut baseline = Baseline::new(kind);
+        let counter = AtomicU64::new(0);
+        group.bench_function(BenchmarkId::new("baseline", kind), |b| {
+            b.iter(|| {
+                let i = counter.fetch_add(1, Ordering::Relaxed);
+                let key = kv_key(i);
+                baseline.put(&key, kv_value());
+                baseline.delete(&key);
+            });
+        });
+
+        let pct_counter = AtomicU64::new(u64::MAX / 2);
+        let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+            let i = pct_counter.fetch_add(1, Ordering::Relaxed);
+            let key = kv_key(i);
+            baseline.put(&key, kv_value());
+            baseline.delete(&key);
+        });
+        record_baseline(&format!("kv/delete/baseline-{}", kind), kind, &p);
+    }
     group.finish();
 }
 
@@ -161,15 +473,169 @@ fn kv_list_prefix(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    for kind in Baseline::KINDS {
+        let mut baseline = Baseline::new(kind);
+        for i in 0..1000u64 {
+            baseline.put(&kv_key_with_prefix("alpha:", i), kv_value());
+            baseline.put(&kv_key_with_prefix("beta:", i), kv_value());
+        }
+        group.bench_function(BenchmarkId::new("baseline", kind), |b| {
+            b.iter(|| {
+                baseline.list_prefix("alpha:");
+            });
+        });
+
+        let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+            baseline.list_prefix("alpha:");
+        });
+        record_baseline(&format!("kv/list_prefix/baseline-{}", kind), kind, &p);
+    }
+    group.finish();
+}
+
+fn kv_put_type(c: &mut Criterion) {
+    let mut group = c.benchmark_group("kv/put_type");
+    group.throughput(Throughput::Elements(1));
+
+    eprintln!("\n--- Latency Percentiles: kv/put_type ---");
+    for mode in DurabilityConfig::ALL {
+        let bench_db = create_db(mode);
+        for value_type in VALUE_TYPES {
+            let value = typed_value(value_type);
+            let prefix = format!("{}:", value_type);
+            let counter = AtomicU64::new(0);
+            group.bench_function(BenchmarkId::new(value_type, mode.label()), |b| {
+                b.iter(|| {
+                    let i = counter.fetch_add(1, Ordering::Relaxed);
+                    bench_db
+                        .db
+                        .kv_put(&kv_key_with_prefix(&prefix, i), value.clone())
+                        .unwrap();
+                });
+            });
+
+            let pct_counter = AtomicU64::new(u64::MAX / 2);
+            let label = format!("kv/put_type/{}/{}", value_type, mode.label());
+            let (p, counters) = measure_with_counters(&bench_db, PERCENTILE_SAMPLES, || {
+                let i = pct_counter.fetch_add(1, Ordering::Relaxed);
+                bench_db
+                    .db
+                    .kv_put(&kv_key_with_prefix(&prefix, i), value.clone())
+                    .unwrap();
+            });
+            record_typed(&label, mode, value_type, encoded_len(&value), &p, &counters);
+        }
+    }
+    group.finish();
+}
+
+fn kv_get_type(c: &mut Criterion) {
+    let mut group = c.benchmark_group("kv/get_type");
+    group.throughput(Throughput::Elements(1));
+
+    eprintln!("\n--- Latency Percentiles: kv/get_type ---");
+    for mode in DurabilityConfig::ALL {
+        let bench_db = create_db(mode);
+        for value_type in VALUE_TYPES {
+            let value = typed_value(value_type);
+            let prefix = format!("{}:", value_type);
+            for i in 0..TYPED_KEYS {
+                bench_db
+                    .db
+                    .kv_put(&kv_key_with_prefix(&prefix, i), value.clone())
+                    .unwrap();
+            }
+            let counter = AtomicU64::new(0);
+            group.bench_function(BenchmarkId::new(value_type, mode.label()), |b| {
+                b.iter(|| {
+                    let i = counter.fetch_add(1, Ordering::Relaxed) % TYPED_KEYS;
+                    bench_db.db.kv_get(&kv_key_with_prefix(&prefix, i)).unwrap();
+                });
+            });
+
+            let pct_counter = AtomicU64::new(0);
+            let label = format!("kv/get_type/{}/{}", value_type, mode.label());
+            let (p, counters) = measure_with_counters(&bench_db, PERCENTILE_SAMPLES, || {
+                let i = pct_counter.fetch_add(1, Ordering::Relaxed) % TYPED_KEYS;
+                assert!(bench_db.db.kv_get(&kv_key_with_prefix(&prefix, i)).unwrap().is_some());
+            });
+            record_typed(&label, mode, value_type, encoded_len(&value), &p, &counters);
+        }
+    }
     group.finish();
 }
 
-criterion_group!(benches, kv_put, kv_get, kv_delete, kv_list_prefix);
+fn kv_multi_get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("kv/multi_get");
+
+    eprintln!("\n--- Latency Percentiles: kv/multi_get ---");
+    for mode in DurabilityConfig::ALL {
+        let bench_db = create_db(mode);
+        for i in 0..WARMUP_COUNT {
+            bench_db.db.kv_put(&kv_key(i), kv_value()).unwrap();
+        }
+        let mut session = bench_db.db.session();
+
+        for batch in MULTI_GET_BATCHES {
+            group.throughput(Throughput::Elements(batch as u64));
+
+            let counter = AtomicU64::new(0);
+            group.bench_function(BenchmarkId::new(format!("txn/{}", batch), mode.label()), |b| {
+                b.iter(|| {
+                    let n = counter.fetch_add(1, Ordering::Relaxed);
+                    multi_get_txn(&mut session, n, batch);
+                });
+            });
+
+            let counter = AtomicU64::new(0);
+            group.bench_function(BenchmarkId::new(format!("loop/{}", batch), mode.label()), |b| {
+                b.iter(|| {
+                    let n = counter.fetch_add(1, Ordering::Relaxed);
+                    for j in 0..batch {
+                        bench_db.db.kv_get(&multi_get_key(n, batch, j)).unwrap();
+                    }
+                });
+            });
+
+            let pct_counter = AtomicU64::new(0);
+            let label = format!("kv/multi_get/txn/{}/{}", batch, mode.label());
+            let (p, counters) = measure_with_counters(&bench_db, PERCENTILE_SAMPLES, || {
+                let n = pct_counter.fetch_add(1, Ordering::Relaxed);
+                multi_get_txn(&mut session, n, batch);
+            });
+            record_multi_get(&label, mode, "txn", batch, &p, &counters);
+
+            let pct_counter = AtomicU64::new(0);
+            let label = format!("kv/multi_get/loop/{}/{}", batch, mode.label());
+            let (p, counters) = measure_with_counters(&bench_db, PERCENTILE_SAMPLES, || {
+                let n = pct_counter.fetch_add(1, Ordering::Relaxed);
+                for j in 0..batch {
+                    assert!(bench_db.db.kv_get(&multi_get_key(n, batch, j)).unwrap().is_some());
+                }
+            });
+            record_multi_get(&label, mode, "loop", batch, &p, &counters);
+        }
+    }
+    group.finish();
+}
 
 fn main() {
     *RECORDER.lock().unwrap() = Some(ResultRecorder::new("latency"));
-    benches();
+    let mut criterion = Criterion::default().configure_from_args();
+    let targets: [(&str, fn(&mut Criterion)); 7] = [
+        ("kv/put", kv_put),
+        ("kv/get", kv_get),
+        ("kv/delete", kv_delete),
+        ("kv/list_prefix", kv_list_prefix),
+        ("kv/put_type", kv_put_type),
+        ("kv/get_type", kv_get_type),
+        ("kv/multi_get", kv_multi_get),
+    ];
+    for (name, target) in targets {
+        ResultRecorder::guard_shared(&RECORDER, name, HashMap::new(), || target(&mut criterion));
+    }
     if let Some(recorder) = RECORDER.lock().unwrap().take() {
-        let _ = recorder.save();
+        recorder.save_or_exit();
     }
 }
@@ -1,6 +1,10 @@
-//! State primitive benchmarks: set, read, cas
+//! State primitive benchmarks: set, read, cas, cas_conflict
 //!
-//! All benchmarks report latency percentiles.
+//! All benchmarks report latency percentiles. `cas` is the success path (the
+//! expected version is always current); `cas_conflict` is the failure path
+//! (the expected version is always stale), so both sides of the compare are
+//! measured. Each operation also gets an in-memory baseline row (a versioned
+//! `HashMap`, no persistence), as in kv.rs.
 
 #[allow(unused)]
 #[path = "../harness/mod.rs"]
@@ -10,17 +14,64 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 
-use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use criterion::{BenchmarkId, Criterion, Throughput};
 use harness::recorder::ResultRecorder;
 use harness::{
-    create_db, measure_with_counters, report_counters, report_percentiles, state_value,
-    DurabilityConfig, PERCENTILE_SAMPLES,
+    create_db, measure_percentiles, measure_with_counters, report_counters, report_percentiles,
+    state_value, DurabilityConfig, Percentiles, PERCENTILE_SAMPLES,
 };
+use stratadb::Value;
 
 static RECORDER: Mutex<Option<ResultRecorder>> = Mutex::new(None);
 
 const CELL_POOL_SIZE: u64 = 100;
 
+/// Versions start at 1, so CAS expecting version 0 never succeeds.
+const STALE_VERSION: u64 = 0;
+
+// =============================================================================
+// In-memory baseline
+// =============================================================================
+
+/// Versioned cells in a `HashMap`, with the same CAS rule as `state_cas`:
+/// the write succeeds only if the cell's current version equals `expected`
+/// (`None` meaning the cell doesn't exist yet).
+#[derive(Default)]
+struct StateBaseline {
+    cells: HashMap<String, (u64, Value)>,
+}
+
+impl StateBaseline {
+    fn set(&mut self, cell: &str, value: Value) -> u64 {
+        let entry = self.cells.entry(cell.to_string()).or_insert((0, Value::Null));
+        entry.0 += 1;
+        entry.1 = value;
+        entry.0
+    }
+
+    fn get(&self, cell: &str) -> Option<Value> {
+        self.cells.get(cell).map(|(_, v)| v.clone())
+    }
+
+    fn cas(&mut self, cell: &str, expected: Option<u64>, value: Value) -> Option<u64> {
+        let current = self.cells.get(cell).map(|(version, _)| *version);
+        if current != expected {
+            return None;
+        }
+        Some(self.set(cell, value))
+    }
+}
+
+fn record_baseline(label: &str, p: &Percentiles) {
+    report_percentiles(label, p);
+    if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
+        let mut params = HashMap::new();
+        params.insert("durability".into(), serde_json::json!("none"));
+        params.insert("baseline".into(), serde_json::json!("hashmap"));
+        rec.record_latency(label, params, p, None, PERCENTILE_SAMPLES as u64);
+    }
+}
+
 fn state_set(c: &mut Criterion) {
     let mut group = c.benchmark_group("state/set");
     group.throughput(Throughput::Elements(1));
@@ -57,6 +108,21 @@ fn state_set(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    let mut baseline = StateBaseline::default();
+    let counter = AtomicU64::new(0);
+    group.bench_function(BenchmarkId::new("baseline", "hashmap"), |b| {
+        b.iter(|| {
+            let i = counter.fetch_add(1, Ordering::Relaxed) % CELL_POOL_SIZE;
+            baseline.set(&format!("cell_{}", i), state_value());
+        });
+    });
+    let pct_counter = AtomicU64::new(0);
+    let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+        let i = pct_counter.fetch_add(1, Ordering::Relaxed) % CELL_POOL_SIZE;
+        baseline.set(&format!("cell_{}", i), state_value());
+    });
+    record_baseline("state/set/baseline-hashmap", &p);
     group.finish();
 }
 
@@ -96,6 +162,24 @@ fn state_read(c: &mut Criterion) {
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    let mut baseline = StateBaseline::default();
+    for i in 0..CELL_POOL_SIZE {
+        baseline.set(&format!("cell_{}", i), state_value());
+    }
+    let counter = AtomicU64::new(0);
+    group.bench_function(BenchmarkId::new("baseline", "hashmap"), |b| {
+        b.iter(|| {
+            let i = counter.fetch_add(1, Ordering::Relaxed) % CELL_POOL_SIZE;
+            baseline.get(&format!("cell_{}", i)).unwrap();
+        });
+    });
+    let pct_counter = AtomicU64::new(0);
+    let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+        let i = pct_counter.fetch_add(1, Ordering::Relaxed) % CELL_POOL_SIZE;
+        baseline.get(&format!("cell_{}", i)).unwrap();
+    });
+    record_baseline("state/read/baseline-hashmap", &p);
     group.finish();
 }
 
@@ -111,26 +195,24 @@ fn state_cas(c: &mut Criterion) {
         group.bench_function(BenchmarkId::new("durability", mode.label()), |b| {
             b.iter(|| {
                 let expected = version_counter.load(Ordering::Relaxed);
-                let result = bench_db
+                let new_version = bench_db
                     .db
                     .state_cas("cas_cell", Some(expected), state_value())
-                    .unwrap();
-                if let Some(new_version) = result {
-                    version_counter.store(new_version, Ordering::Relaxed);
-                }
+                    .unwrap()
+                    .expect("CAS with the current version failed");
+                version_counter.store(new_version, Ordering::Relaxed);
             });
         });
 
         let label = format!("state/cas/{}", mode.label());
         let (p, counters) = measure_with_counters(&bench_db, PERCENTILE_SAMPLES, || {
             let expected = version_counter.load(Ordering::Relaxed);
-            let result = bench_db
+            let new_version = bench_db
                 .db
                 .state_cas("cas_cell", Some(expected), state_value())
-                .unwrap();
-            if let Some(new_version) = result {
-                version_counter.store(new_version, Ordering::Relaxed);
-            }
+                .unwrap()
+                .expect("CAS with the current version failed");
+            version_counter.store(new_version, Ordering::Relaxed);
         });
         report_percentiles(&label, &p);
         report_counters(&label, &counters, PERCENTILE_SAMPLES as u64);
@@ -138,18 +220,91 @@ fn state_cas(c: &mut Criterion) {
         if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
             let mut params = HashMap::new();
             params.insert("durability".into(), serde_json::json!(mode.label()));
+            params.insert("outcome".into(), serde_json::json!("success"));
             rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
         }
     }
+
+    let mut baseline = StateBaseline::default();
+    let mut version = baseline.set("cas_cell", state_value());
+    group.bench_function(BenchmarkId::new("baseline", "hashmap"), |b| {
+        b.iter(|| {
+            version = baseline.cas("cas_cell", Some(version), state_value()).unwrap();
+        });
+    });
+    let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+        version = baseline.cas("cas_cell", Some(version), state_value()).unwrap();
+    });
+    record_baseline("state/cas/baseline-hashmap", &p);
     group.finish();
 }
 
-criterion_group!(benches, state_set, state_read, state_cas);
+/// CAS against a stale version: the compare fails and nothing is written.
+/// Under contention this is the path losing writers take on every retry.
+fn state_cas_conflict(c: &mut Criterion) {
+    let mut group = c.benchmark_group("state/cas_conflict");
+    group.throughput(Throughput::Elements(1));
+
+    eprintln!("\n--- Latency Percentiles: state/cas_conflict ---");
+    for mode in DurabilityConfig::ALL {
+        let bench_db = create_db(mode);
+        bench_db.db.state_set("cas_cell", state_value()).unwrap();
+        group.bench_function(BenchmarkId::new("durability", mode.label()), |b| {
+            b.iter(|| {
+                let result = bench_db
+                    .db
+                    .state_cas("cas_cell", Some(STALE_VERSION), state_value())
+                    .unwrap();
+                assert!(result.is_none(), "CAS with a stale version succeeded");
+            });
+        });
+
+        let label = format!("state/cas_conflict/{}", mode.label());
+        let (p, counters) = measure_with_counters(&bench_db, PERCENTILE_SAMPLES, || {
+            let result = bench_db
+                .db
+                .state_cas("cas_cell", Some(STALE_VERSION), state_value())
+                .unwrap();
+            assert!(result.is_none(), "CAS with a stale version succeeded");
+        });
+        report_percentiles(&label, &p);
+        report_counters(&label, &counters, PERCENTILE_SAMPLES as u64);
+
+        if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
+            let mut params = HashMap::new();
+            params.insert("durability".into(), serde_json::json!(mode.label()));
+            params.insert("outcome".into(), serde_json::json!("conflict"));
+            rec.record_latency(&label, params, &p, Some(&counters), PERCENTILE_SAMPLES as u64);
+        }
+    }
+
+    let mut baseline = StateBaseline::default();
+    baseline.set("cas_cell", state_value());
+    group.bench_function(BenchmarkId::new("baseline", "hashmap"), |b| {
+        b.iter(|| {
+            assert!(baseline.cas("cas_cell", Some(STALE_VERSION), state_value()).is_none());
+        });
+    });
+    let p = measure_percentiles(PERCENTILE_SAMPLES, || {
+        assert!(baseline.cas("cas_cell", Some(STALE_VERSION), state_value()).is_none());
+    });
+    record_baseline("state/cas_conflict/baseline-hashmap", &p);
+    group.finish();
+}
 
 fn main() {
     *RECORDER.lock().unwrap() = Some(ResultRecorder::new("latency"));
-    benches();
+    let mut criterion = Criterion::default().configure_from_args();
+    let targets: [(&str, fn(&mut Criterion)); 4] = [
+        ("state/set", state_set),
+        ("state/read", state_read),
+        ("state/cas", state_cas),
+        ("state/cas_conflict", state_cas_conflict),
+    ];
+    for (name, target) in targets {
+        ResultRecorder::guard_shared(&RECORDER, name, HashMap::new(), || target(&mut criterion));
+    }
     if let Some(recorder) = RECORDER.lock().unwrap().take() {
-        let _ = recorder.save();
+        recorder.save_or_exit();
     }
 }
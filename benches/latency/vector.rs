@@ -1,6 +1,8 @@
-//! Vector primitive benchmarks: upsert, search, get
+//! Vector primitive benchmarks: upsert, search, get, list_collections
 //!
 //! Reduced sample_size because vector operations are inherently slower.
+//! list_collections runs in cache mode only: it reads collection metadata,
+//! so durability doesn't change what it measures.
 //! All benchmarks report latency percentiles.
 
 #[allow(unused)]
@@ -11,7 +13,7 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 
-use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use criterion::{BenchmarkId, Criterion, Throughput};
 use harness::recorder::ResultRecorder;
 use harness::{
     create_db, measure_with_counters, report_counters, report_percentiles, vector_128d,
@@ -21,6 +23,11 @@ use stratadb::DistanceMetric;
 
 static RECORDER: Mutex<Option<ResultRecorder>> = Mutex::new(None);
 
+/// Collection counts for list_collections. Per-collection cost should stay
+/// flat across them; growth points at quadratic listing.
+const COLLECTION_COUNTS: &[usize] = &[10, 100, 1_000, 10_000];
+const LIST_SAMPLES: usize = 100;
+
 fn vector_upsert(c: &mut Criterion) {
     let mut group = c.benchmark_group("vector/upsert");
     group.throughput(Throughput::Elements(1));
@@ -169,12 +176,61 @@ fn vector_get(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, vector_upsert, vector_search, vector_get);
+fn vector_list_collections(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vector/list_collections");
+    group.sample_size(10);
+
+    let metrics = [DistanceMetric::Cosine, DistanceMetric::Euclidean, DistanceMetric::DotProduct];
+    eprintln!("\n--- Latency Percentiles: vector/list_collections ---");
+    for &n in COLLECTION_COUNTS {
+        let bench_db = create_db(DurabilityConfig::Cache);
+        for i in 0..n {
+            bench_db
+                .db
+                .vector_create_collection(&format!("col_{}", i), (1 + i % 1024) as u64, metrics[i % 3])
+                .unwrap();
+        }
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_function(BenchmarkId::new("collections", n), |b| {
+            b.iter(|| bench_db.db.vector_list_collections().unwrap());
+        });
+
+        let label = format!("vector/list_collections/{}", n);
+        let (p, counters) = measure_with_counters(&bench_db, LIST_SAMPLES, || {
+            let listed = bench_db.db.vector_list_collections().unwrap();
+            assert_eq!(listed.len(), n);
+        });
+        report_percentiles(&label, &p);
+        eprintln!(
+            "  {:<45} p50/collection={:.1}ns",
+            label,
+            p.p50.as_nanos() as f64 / n as f64
+        );
+        report_counters(&label, &counters, LIST_SAMPLES as u64);
+
+        if let Some(rec) = RECORDER.lock().unwrap().as_mut() {
+            let mut params = HashMap::new();
+            params.insert("durability".into(), serde_json::json!(DurabilityConfig::Cache.label()));
+            params.insert("collections".into(), serde_json::json!(n));
+            rec.record_latency(&label, params, &p, Some(&counters), LIST_SAMPLES as u64);
+        }
+    }
+    group.finish();
+}
 
 fn main() {
     *RECORDER.lock().unwrap() = Some(ResultRecorder::new("latency"));
-    benches();
+    let mut criterion = Criterion::default().configure_from_args();
+    let targets: [(&str, fn(&mut Criterion)); 4] = [
+        ("vector/upsert", vector_upsert),
+        ("vector/search", vector_search),
+        ("vector/get", vector_get),
+        ("vector/list_collections", vector_list_collections),
+    ];
+    for (name, target) in targets {
+        ResultRecorder::guard_shared(&RECORDER, name, HashMap::new(), || target(&mut criterion));
+    }
     if let Some(recorder) = RECORDER.lock().unwrap().take() {
-        let _ = recorder.save();
+        recorder.save_or_exit();
     }
 }
@@ -0,0 +1,755 @@
+//! memtier_benchmark-style Mixed Workload for StrataDB
+//!
+//! Models the cache workload of `memtier_benchmark`: a configurable SET:GET
+//! ratio, a key access pattern (random, Gaussian, or sequential) over a key
+//! range, and a value-size distribution. Reports combined and per-command
+//! throughput and latency, with GET hits and misses counted separately, like
+//! memtier's totals table.
+//!
+//! Where redis_compare runs each command in isolation, this runs them
+//! interleaved against one keyspace, so reads see the writes' footprint.
+//!
+//! Run:      `cargo bench --bench memtier`
+//! Ratio:    `cargo bench --bench memtier -- --ratio 1:1`
+//! Gaussian: `cargo bench --bench memtier -- --key-pattern G:G --key-stddev 5000`
+//! Sizes:    `cargo bench --bench memtier -- --data-size-list 32:60,1024:30,16384:10`
+//! Clients:  `cargo bench --bench memtier -- -c 8 --prepopulate`
+//! Paced:    `cargo bench --bench memtier -- -c 4 --rate-limiting 10000`
+//! Quick:    `cargo bench --bench memtier -- --durability cache -q`
+//! CSV:      `cargo bench --bench memtier -- --csv`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::load::{self, Load, Recorder};
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::Duration;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{Strata, Value};
+
+// ---------------------------------------------------------------------------
+// Defaults (memtier_benchmark defaults where they make sense embedded)
+// ---------------------------------------------------------------------------
+
+const DEFAULT_REQUESTS: usize = 100_000;
+const DEFAULT_KEY_MAX: u64 = 100_000;
+const DEFAULT_DATA_SIZE: usize = 32;
+const DEFAULT_SET_RATIO: u32 = 1;
+const DEFAULT_GET_RATIO: u32 = 10;
+/// Request stream seed when `--seed` isn't given.
+const REQUEST_SEED: u64 = 0x3E3_7135;
+
+// ---------------------------------------------------------------------------
+// Random number generation
+// ---------------------------------------------------------------------------
+
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed ^ 0x5DEECE66D,
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.state
+    }
+
+    /// Uniform in [0.0, 1.0).
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal via Box-Muller.
+    fn next_gaussian(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Key pattern (memtier --key-pattern)
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum KeyPattern {
+    /// `R`: uniform over [key_min, key_max].
+    Random,
+    /// `G`: normal around `key_median` with `key_stddev`, clamped to the range.
+    Gaussian,
+    /// `S`: key_min, key_min+1, ... wrapping at key_max.
+    Sequential,
+}
+
+impl KeyPattern {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "R" => Some(KeyPattern::Random),
+            "G" => Some(KeyPattern::Gaussian),
+            "S" => Some(KeyPattern::Sequential),
+            _ => None,
+        }
+    }
+
+    fn letter(&self) -> char {
+        match self {
+            KeyPattern::Random => 'R',
+            KeyPattern::Gaussian => 'G',
+            KeyPattern::Sequential => 'S',
+        }
+    }
+}
+
+/// Generates key indices for one command type of one client.
+struct KeyChooser {
+    pattern: KeyPattern,
+    min: u64,
+    max: u64,
+    median: f64,
+    stddev: f64,
+    next_seq: u64,
+}
+
+impl KeyChooser {
+    fn new(pattern: KeyPattern, config: &Config, client: usize) -> Self {
+        let span = config.key_max - config.key_min + 1;
+        Self {
+            pattern,
+            min: config.key_min,
+            max: config.key_max,
+            median: config.key_median(),
+            stddev: config.key_stddev(),
+            // Sequential clients start at evenly spaced offsets so they don't
+            // walk the same keys in lockstep
+            next_seq: config.key_min + (client as u64 * span / config.clients as u64),
+        }
+    }
+
+    fn next(&mut self, rng: &mut Rng) -> u64 {
+        match self.pattern {
+            KeyPattern::Random => self.min + rng.next_u64() % (self.max - self.min + 1),
+            KeyPattern::Gaussian => {
+                let k = (self.median + rng.next_gaussian() * self.stddev).round();
+                k.clamp(self.min as f64, self.max as f64) as u64
+            }
+            KeyPattern::Sequential => {
+                let k = self.next_seq;
+                self.next_seq = if k >= self.max { self.min } else { k + 1 };
+                k
+            }
+        }
+    }
+}
+
+/// memtier's default key format: `memtier-<n>`.
+fn memtier_key(idx: u64) -> String {
+    format!("memtier-{}", idx)
+}
+
+// ---------------------------------------------------------------------------
+// Data size distribution (memtier --data-size / -range / -list)
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum DataSize {
+    Fixed(usize),
+    /// Uniform in [min, max].
+    Range(usize, usize),
+    /// Weighted choice of sizes: (size, weight).
+    List(Vec<(usize, u32)>),
+}
+
+impl DataSize {
+    fn parse_range(s: &str) -> Option<Self> {
+        let (lo, hi) = s.split_once('-')?;
+        let lo: usize = lo.trim().parse().ok()?;
+        let hi: usize = hi.trim().parse().ok()?;
+        (lo <= hi).then_some(DataSize::Range(lo, hi))
+    }
+
+    fn parse_list(s: &str) -> Option<Self> {
+        let entries: Option<Vec<(usize, u32)>> = s
+            .split(',')
+            .map(|e| {
+                let (size, weight) = e.split_once(':')?;
+                Some((size.trim().parse().ok()?, weight.trim().parse().ok()?))
+            })
+            .collect();
+        let entries = entries?;
+        let total: u32 = entries.iter().map(|(_, w)| w).sum();
+        (!entries.is_empty() && total > 0).then_some(DataSize::List(entries))
+    }
+
+    fn sample(&self, rng: &mut Rng) -> usize {
+        match self {
+            DataSize::Fixed(n) => *n,
+            DataSize::Range(lo, hi) => lo + (rng.next_u64() % (hi - lo + 1) as u64) as usize,
+            DataSize::List(entries) => {
+                let total: u32 = entries.iter().map(|(_, w)| w).sum();
+                let mut pick = (rng.next_u64() % total as u64) as u32;
+                for (size, weight) in entries {
+                    if pick < *weight {
+                        return *size;
+                    }
+                    pick -= weight;
+                }
+                entries[entries.len() - 1].0
+            }
+        }
+    }
+
+    fn max_size(&self) -> usize {
+        match self {
+            DataSize::Fixed(n) => *n,
+            DataSize::Range(_, hi) => *hi,
+            DataSize::List(entries) => entries.iter().map(|(s, _)| *s).max().unwrap_or(0),
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            DataSize::Fixed(n) => format!("{}", n),
+            DataSize::Range(lo, hi) => format!("{}-{}", lo, hi),
+            DataSize::List(entries) => entries
+                .iter()
+                .map(|(s, w)| format!("{}:{}", s, w))
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Measurement
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct ClientLatencies {
+    sets: Vec<Duration>,
+    get_hits: Vec<Duration>,
+    get_misses: Vec<Duration>,
+}
+
+/// Which row of the totals table one request lands in.
+enum Command {
+    Set,
+    GetHit,
+    GetMiss,
+}
+
+impl Recorder for ClientLatencies {
+    type Outcome = Command;
+
+    fn record(&mut self, command: Command, latency: Duration) {
+        match command {
+            Command::Set => self.sets.push(latency),
+            Command::GetHit => self.get_hits.push(latency),
+            Command::GetMiss => self.get_misses.push(latency),
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.sets.extend(other.sets);
+        self.get_hits.extend(other.get_hits);
+        self.get_misses.extend(other.get_misses);
+    }
+}
+
+struct RunResult {
+    latencies: ClientLatencies,
+    elapsed: Duration,
+}
+
+/// One client's request stream: each call issues the next request.
+fn client_requests<'a>(
+    db: Strata,
+    config: &'a Config,
+    client: usize,
+    payload: &'a [u8],
+) -> impl FnMut() -> Command + 'a {
+    let mut rng = Rng::new(config.seed ^ (client as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    let mut set_keys = KeyChooser::new(config.set_pattern, config, client);
+    let mut get_keys = KeyChooser::new(config.get_pattern, config, client);
+    let cycle = config.set_ratio + config.get_ratio;
+    let mut op = 0u32;
+
+    move || {
+        // Deterministic interleave in ratio order (memtier issues SETs then
+        // GETs within each ratio cycle)
+        let is_set = op % cycle < config.set_ratio;
+        op = op.wrapping_add(1);
+        if is_set {
+            let key = memtier_key(set_keys.next(&mut rng));
+            let size = config.data_size.sample(&mut rng);
+            db.kv_put(&key, Value::Bytes(payload[..size].to_vec())).unwrap();
+            Command::Set
+        } else {
+            let key = memtier_key(get_keys.next(&mut rng));
+            match db.kv_get(&key).unwrap() {
+                Some(_) => Command::GetHit,
+                None => Command::GetMiss,
+            }
+        }
+    }
+}
+
+fn run_workload(bench_db: &BenchDb, config: &Config) -> RunResult {
+    let payload = vec![b'x'; config.data_size.max_size()];
+    let handles: Vec<Strata> = (0..config.clients)
+        .map(|_| bench_db.db.new_handle().expect("failed to create Strata handle"))
+        .collect();
+    let load = Load {
+        ops: config.requests,
+        duration: None,
+        rate: config
+            .rate_limiting
+            .map(|per_client| per_client as f64 * config.clients as f64),
+    };
+
+    let run = load::run(&load, handles, |client, handle| {
+        client_requests(handle, config, client, &payload)
+    });
+    RunResult {
+        latencies: run.recorder,
+        elapsed: run.elapsed,
+    }
+}
+
+/// Fill every key in the range once, so GETs hit (memtier users typically
+/// run a `--ratio 1:0 --key-pattern P:P` pass first for the same effect).
+fn prepopulate(bench_db: &BenchDb, config: &Config) {
+    let payload = vec![b'x'; config.data_size.max_size()];
+    let mut rng = Rng::new(0x9E3);
+    for k in config.key_min..=config.key_max {
+        let size = config.data_size.sample(&mut rng);
+        bench_db
+            .db
+            .kv_put(&memtier_key(k), Value::Bytes(payload[..size].to_vec()))
+            .unwrap();
+    }
+}
+
+struct CommandStats {
+    count: usize,
+    ops_per_sec: f64,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    p999: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+/// Stats for one row. Throughput is the row's count over the whole run's
+/// wall time, so per-command rows sum to the totals row.
+fn command_stats(latencies: &[Duration], elapsed: Duration) -> Option<CommandStats> {
+    if latencies.is_empty() {
+        return None;
+    }
+    let mut sorted = latencies.to_vec();
+    sorted.sort_unstable();
+    let len = sorted.len();
+    let sum: Duration = sorted.iter().sum();
+    Some(CommandStats {
+        count: len,
+        ops_per_sec: len as f64 / elapsed.as_secs_f64(),
+        avg: sum / len as u32,
+        p50: sorted[len * 50 / 100],
+        p95: sorted[(len * 95 / 100).min(len - 1)],
+        p99: sorted[(len * 99 / 100).min(len - 1)],
+        p999: sorted[(len * 999 / 1000).min(len - 1)],
+        min: sorted[0],
+        max: sorted[len - 1],
+    })
+}
+
+/// (row name, latencies) in memtier's table order.
+fn rows(run: &RunResult) -> Vec<(&'static str, Vec<Duration>)> {
+    let l = &run.latencies;
+    let gets: Vec<Duration> = l.get_hits.iter().chain(&l.get_misses).copied().collect();
+    let totals: Vec<Duration> = l.sets.iter().chain(&gets).copied().collect();
+    vec![
+        ("Sets", l.sets.clone()),
+        ("Gets", gets),
+        ("Totals", totals),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+fn duration_ms(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000_000.0
+}
+
+fn print_table(run: &RunResult) {
+    let elapsed = run.elapsed;
+    eprintln!(
+        "  {:<8} {:>12} {:>12} {:>12} {:>10} {:>10} {:>10} {:>10}",
+        "Type", "Ops/sec", "Hits/sec", "Misses/sec", "Avg ms", "p50 ms", "p99 ms", "p99.9 ms"
+    );
+    eprintln!("  {}", "-".repeat(92));
+    let hits = run.latencies.get_hits.len() as f64 / elapsed.as_secs_f64();
+    let misses = run.latencies.get_misses.len() as f64 / elapsed.as_secs_f64();
+    for (name, lats) in rows(run) {
+        let Some(s) = command_stats(&lats, elapsed) else {
+            continue;
+        };
+        let (h, m) = match name {
+            "Sets" => ("---".to_string(), "---".to_string()),
+            _ => (format!("{:.2}", hits), format!("{:.2}", misses)),
+        };
+        eprintln!(
+            "  {:<8} {:>12.2} {:>12} {:>12} {:>10.3} {:>10.3} {:>10.3} {:>10.3}",
+            name,
+            s.ops_per_sec,
+            h,
+            m,
+            duration_ms(s.avg),
+            duration_ms(s.p50),
+            duration_ms(s.p99),
+            duration_ms(s.p999),
+        );
+    }
+    eprintln!();
+}
+
+fn print_quiet(run: &RunResult, mode: DurabilityConfig) {
+    let elapsed = run.elapsed;
+    let parts: Vec<String> = rows(run)
+        .into_iter()
+        .filter_map(|(name, lats)| {
+            command_stats(&lats, elapsed).map(|s| {
+                format!(
+                    "{}={:.0} ops/s p50={:.3}ms",
+                    name.to_lowercase(),
+                    s.ops_per_sec,
+                    duration_ms(s.p50)
+                )
+            })
+        })
+        .collect();
+    eprintln!("memtier/{}: {}", mode.label(), parts.join(", "));
+}
+
+fn print_csv_header() {
+    println!(
+        "\"durability\",\"type\",\"count\",\"ops_sec\",\"avg_ms\",\"p50_ms\",\"p99_ms\",\"p999_ms\",\"max_ms\""
+    );
+}
+
+fn print_csv_rows(run: &RunResult, mode: DurabilityConfig) {
+    for (name, lats) in rows(run) {
+        if let Some(s) = command_stats(&lats, run.elapsed) {
+            println!(
+                "\"{}\",\"{}\",{},{:.2},{:.3},{:.3},{:.3},{:.3},{:.3}",
+                mode.label(),
+                name.to_lowercase(),
+                s.count,
+                s.ops_per_sec,
+                duration_ms(s.avg),
+                duration_ms(s.p50),
+                duration_ms(s.p99),
+                duration_ms(s.p999),
+                duration_ms(s.max),
+            );
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+fn record_run(
+    recorder: &mut ResultRecorder,
+    run: &RunResult,
+    mode: DurabilityConfig,
+    config: &Config,
+) {
+    let ratio = format!("{}:{}", config.set_ratio, config.get_ratio);
+    let pattern = format!("{}:{}", config.set_pattern.letter(), config.get_pattern.letter());
+
+    for (name, lats) in rows(run) {
+        let Some(s) = command_stats(&lats, run.elapsed) else {
+            continue;
+        };
+        let mut params = HashMap::new();
+        params.insert("durability".into(), serde_json::json!(mode.label()));
+        params.insert("command".into(), serde_json::json!(name.to_lowercase()));
+        params.insert("ratio".into(), serde_json::json!(ratio));
+        params.insert("key_pattern".into(), serde_json::json!(pattern));
+        params.insert("key_min".into(), serde_json::json!(config.key_min));
+        params.insert("key_max".into(), serde_json::json!(config.key_max));
+        if config.set_pattern == KeyPattern::Gaussian || config.get_pattern == KeyPattern::Gaussian {
+            params.insert("key_median".into(), serde_json::json!(config.key_median()));
+            params.insert("key_stddev".into(), serde_json::json!(config.key_stddev()));
+        }
+        params.insert("data_size".into(), serde_json::json!(config.data_size.label()));
+        params.insert("requests".into(), serde_json::json!(config.requests));
+        params.insert("clients".into(), serde_json::json!(config.clients));
+        params.insert("prepopulate".into(), serde_json::json!(config.prepopulate));
+        params.insert("seed".into(), serde_json::json!(config.seed));
+        if let Some(rate) = config.rate_limiting {
+            params.insert("rate_limiting".into(), serde_json::json!(rate));
+        }
+        if name != "Sets" {
+            params.insert("get_hits".into(), serde_json::json!(run.latencies.get_hits.len()));
+            params.insert("get_misses".into(), serde_json::json!(run.latencies.get_misses.len()));
+        }
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!(
+                "memtier/{}/{}/{}/{}",
+                ratio,
+                pattern,
+                name.to_lowercase(),
+                mode.label()
+            ),
+            category: "memtier".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                ops_per_sec: Some(s.ops_per_sec),
+                p50_ns: Some(s.p50.as_nanos() as u64),
+                p95_ns: Some(s.p95.as_nanos() as u64),
+                p99_ns: Some(s.p99.as_nanos() as u64),
+                min_ns: Some(s.min.as_nanos() as u64),
+                max_ns: Some(s.max.as_nanos() as u64),
+                avg_ns: Some(s.avg.as_nanos() as u64),
+                samples: Some(s.count as u64),
+                threads: Some(config.clients),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::Success,
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct Config {
+    requests: usize,
+    clients: usize,
+    set_ratio: u32,
+    get_ratio: u32,
+    set_pattern: KeyPattern,
+    get_pattern: KeyPattern,
+    key_min: u64,
+    key_max: u64,
+    key_median: Option<f64>,
+    key_stddev: Option<f64>,
+    data_size: DataSize,
+    prepopulate: bool,
+    /// Requests per second per client (`--rate-limiting`); `None` runs
+    /// every client flat out.
+    rate_limiting: Option<u64>,
+    /// Seed for the request streams (`--seed`).
+    seed: u64,
+    durability: Vec<DurabilityConfig>,
+    csv: bool,
+    quiet: bool,
+}
+
+impl Config {
+    /// memtier default: the middle of the key range.
+    fn key_median(&self) -> f64 {
+        self.key_median
+            .unwrap_or((self.key_min as f64 + self.key_max as f64) / 2.0)
+    }
+
+    /// memtier default: a sixth of the key range.
+    fn key_stddev(&self) -> f64 {
+        self.key_stddev
+            .unwrap_or((self.key_max - self.key_min) as f64 / 6.0)
+    }
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        requests: DEFAULT_REQUESTS,
+        clients: 1,
+        set_ratio: DEFAULT_SET_RATIO,
+        get_ratio: DEFAULT_GET_RATIO,
+        set_pattern: KeyPattern::Random,
+        get_pattern: KeyPattern::Random,
+        key_min: 0,
+        key_max: DEFAULT_KEY_MAX,
+        key_median: None,
+        key_stddev: None,
+        data_size: DataSize::Fixed(DEFAULT_DATA_SIZE),
+        prepopulate: false,
+        rate_limiting: None,
+        seed: REQUEST_SEED,
+        durability: DurabilityConfig::ALL.to_vec(),
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "-n" | "--requests" => config.requests = args.parse_value(),
+            "-c" | "--clients" => config.clients = args.parse_value::<usize>().max(1),
+            "--ratio" => {
+                let v = args.value();
+                let ratio = v
+                    .split_once(':')
+                    .and_then(|(s, g)| Some((s.trim().parse::<u32>().ok()?, g.trim().parse::<u32>().ok()?)))
+                    .filter(|(s, g)| s + g > 0);
+                (config.set_ratio, config.get_ratio) = ratio.unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--ratio expects SET:GET like 1:10; got {}", v))
+                });
+            }
+            "--key-pattern" => {
+                let v = args.value();
+                let patterns = v
+                    .split_once(':')
+                    .and_then(|(s, g)| Some((KeyPattern::parse(s)?, KeyPattern::parse(g)?)));
+                (config.set_pattern, config.get_pattern) = patterns.unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--key-pattern expects SET:GET of R, G or S like R:G; got {}", v))
+                });
+            }
+            "--key-minimum" => config.key_min = args.parse_value(),
+            "--key-maximum" => config.key_max = args.parse_value(),
+            "--key-median" => config.key_median = Some(args.parse_value()),
+            "--key-stddev" => config.key_stddev = Some(args.parse_value()),
+            "-d" | "--data-size" => config.data_size = DataSize::Fixed(args.parse_value()),
+            "--data-size-range" => {
+                let v = args.value();
+                config.data_size = DataSize::parse_range(&v).unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--data-size-range expects MIN-MAX like 32-1024; got {}", v))
+                });
+            }
+            "--data-size-list" => {
+                let v = args.value();
+                config.data_size = DataSize::parse_list(&v).unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--data-size-list expects SIZE:WEIGHT,... like 32:9,1024:1; got {}", v))
+                });
+            }
+            "--prepopulate" => config.prepopulate = true,
+            "--rate-limiting" => {
+                config.rate_limiting = Some(args.parse_checked("requests per second above 0", |&r| r > 0));
+            }
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.seed = args.seed_or(REQUEST_SEED);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    if config.key_max < config.key_min {
+        std::mem::swap(&mut config.key_min, &mut config.key_max);
+    }
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv && !config.quiet {
+        eprintln!("=== StrataDB memtier-style Benchmark ===");
+        eprintln!(
+            "Parameters: {} requests, {} client(s), ratio {}:{} (SET:GET), key pattern {}:{}",
+            config.requests,
+            config.clients,
+            config.set_ratio,
+            config.get_ratio,
+            config.set_pattern.letter(),
+            config.get_pattern.letter(),
+        );
+        eprintln!(
+            "  keys [{}, {}], data size {}{}",
+            config.key_min,
+            config.key_max,
+            config.data_size.label(),
+            if config.prepopulate { ", prepopulated" } else { "" },
+        );
+        if let Some(rate) = config.rate_limiting {
+            eprintln!("  rate limited to {} requests/s per client", rate);
+        }
+        if config.set_pattern == KeyPattern::Gaussian || config.get_pattern == KeyPattern::Gaussian {
+            eprintln!(
+                "  gaussian median {:.0}, stddev {:.0}",
+                config.key_median(),
+                config.key_stddev()
+            );
+        }
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("memtier");
+    let ratio = format!("{}:{}", config.set_ratio, config.get_ratio);
+    let pattern = format!("{}:{}", config.set_pattern.letter(), config.get_pattern.letter());
+
+    for &mode in &config.durability {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(mode.label()));
+        guard_params.insert("ratio".into(), serde_json::json!(ratio));
+        guard_params.insert("key_pattern".into(), serde_json::json!(pattern));
+        let guard_name = format!("memtier/{}/{}/{}", ratio, pattern, mode.label());
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            let bench_db = create_db(mode);
+            if config.prepopulate {
+                if !config.csv && !config.quiet {
+                    eprint!("  Prepopulating {} keys...", config.key_max - config.key_min + 1);
+                }
+                prepopulate(&bench_db, &config);
+                if !config.csv && !config.quiet {
+                    eprintln!(" done");
+                }
+            }
+
+            let run = run_workload(&bench_db, &config);
+
+            if config.csv {
+                print_csv_rows(&run, mode);
+            } else if config.quiet {
+                print_quiet(&run, mode);
+            } else {
+                eprintln!(
+                    "--- durability: {} ({:.2}s) ---",
+                    mode.label(),
+                    run.elapsed.as_secs_f64()
+                );
+                print_table(&run);
+            }
+
+            record_run(recorder, &run, mode, &config);
+        });
+    }
+
+    if !config.csv && !config.quiet {
+        eprintln!("=== memtier benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,606 @@
+//! WAL Replay Benchmark for StrataDB
+//!
+//! How long does a crashed process take to come back, and how does that grow
+//! with the WAL it left behind? This writes WALs of 16 MB to 1 GB, simulates
+//! a crash, and times the reopen, so the result can be read as a replay
+//! throughput in MB/s for capacity planning ("a 2 GB WAL means ~N seconds of
+//! downtime after a crash").
+//!
+//! stratadb has no checkpoint call in its public API, and the only checkpoint
+//! this bench can trigger is a clean close. Each WAL size therefore stands
+//! for a checkpoint interval: the database is loaded with a checkpointed
+//! base dataset, then a child process writes the given amount of WAL and
+//! exits without closing the database, just before the next checkpoint
+//! would have been due. The parent then times:
+//!
+//! - `crash_open`: opening the crashed database, which replays the WAL
+//! - `clean_open`: closing it cleanly and opening it again, with nothing
+//!   left to replay
+//!
+//! WAL size is the `bytes_written` durability counter, not a guess from key
+//! and value sizes. After every size, the replay throughput is the WAL size
+//! divided by `crash_open`. After the sweep, a straight line is fitted through
+//! `crash_open` against WAL size: the slope is the marginal replay rate, the
+//! intercept the fixed cost of opening. If `clean_open` comes out close to
+//! `crash_open`, closing didn't checkpoint, the WAL sizes understate what was
+//! replayed, and the run says so.
+//!
+//! The WAL is read back from the page cache, as after a process crash. A
+//! crash that also lost the page cache (power loss, VM restart) reads it
+//! from the device, which is slower.
+//!
+//! Run:    `cargo bench --bench wal_replay`
+//! Quick:  `cargo bench --bench wal_replay -- -q`
+//! CSV:    `cargo bench --bench wal_replay -- --csv`
+//! Custom: `cargo bench --bench wal_replay -- --sizes 16,128,2048 --base-mb 256 --runs 5 --durability always`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{dir_size_bytes, kv_key, open_db_in, print_hardware_info, DurabilityConfig};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_ms, fmt_num};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{Strata, Value};
+use tempfile::TempDir;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_SIZES_MB: &[u64] = &[16, 64, 256, 1024];
+const DEFAULT_BASE_MB: u64 = 64;
+const DEFAULT_RUNS: usize = 3;
+const VALUE_SIZE: usize = 1024;
+const DISK_MODES: [DurabilityConfig; 2] = [DurabilityConfig::Standard, DurabilityConfig::Always];
+
+/// `kv_key` pads every key to 100 bytes.
+const KEY_BYTES: u64 = 100;
+/// Puts between reads of the durability counters while writing.
+const COUNTER_CHECK_EVERY: u64 = 64;
+/// Puts after which zero counters mean the build doesn't count WAL bytes.
+const COUNTER_GRACE_PUTS: u64 = 1_000;
+/// Most recent keys checked for after recovery.
+const TAIL_KEYS: u64 = 1_000;
+/// A clean reopen slower than this share of the crash reopen means closing
+/// didn't checkpoint.
+const NO_CHECKPOINT_RATIO: f64 = 0.5;
+
+// ---------------------------------------------------------------------------
+// Writing the WAL
+// ---------------------------------------------------------------------------
+
+fn incompressible_value(i: u64, size: usize) -> Value {
+    let mut x = i.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut bytes = Vec::with_capacity(size + 8);
+    while bytes.len() < size {
+        // xorshift64
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        bytes.extend_from_slice(&x.to_le_bytes());
+    }
+    bytes.truncate(size);
+    Value::Bytes(bytes)
+}
+
+/// What one write phase put in the WAL.
+struct Written {
+    /// Keys `first..first + keys` were written.
+    first: u64,
+    keys: u64,
+    wal_bytes: u64,
+    /// False when the durability counters stayed at zero and `wal_bytes` is
+    /// the logical size of the keys and values instead.
+    counted: bool,
+}
+
+/// Put fresh keys from `first` on until `bytes` of WAL have been written.
+fn write_wal(db: &Strata, first: u64, bytes: u64) -> Written {
+    let before = db.durability_counters().unwrap_or_default();
+    let logical = |keys: u64| keys * (KEY_BYTES + VALUE_SIZE as u64);
+    let mut keys = 0;
+    let mut counted = true;
+    let mut wal_bytes = 0;
+    while wal_bytes < bytes {
+        let i = first + keys;
+        db.kv_put(&kv_key(i), incompressible_value(i, VALUE_SIZE)).unwrap();
+        keys += 1;
+        if counted && keys.is_multiple_of(COUNTER_CHECK_EVERY) {
+            let after = db.durability_counters().unwrap_or_default();
+            wal_bytes = after.bytes_written - before.bytes_written;
+            counted = wal_bytes > 0 || keys < COUNTER_GRACE_PUTS;
+        }
+        if !counted {
+            wal_bytes = logical(keys);
+        }
+    }
+    Written {
+        first,
+        keys,
+        wal_bytes,
+        counted,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Child: write the WAL, then die
+// ---------------------------------------------------------------------------
+
+/// Runs in the child: write `size_mb` of WAL on top of the base dataset in
+/// `dir`, report it to `emit`, and exit without closing the database.
+fn child_main(config: &Config, size_mb: u64, dir: &str, emit: &str) {
+    let db = open_db_in(Path::new(dir), config.durability[0]);
+    let start = Instant::now();
+    let w = write_wal(&db, base_keys(config.base_mb), size_mb << 20);
+    let report = serde_json::json!({
+        "first": w.first,
+        "keys": w.keys,
+        "wal_bytes": w.wal_bytes,
+        "counted": w.counted,
+        "write_secs": start.elapsed().as_secs_f64(),
+    });
+    std::fs::write(emit, report.to_string()).expect("failed to write child results");
+
+    // No destructors run: the database is never closed or checkpointed, as
+    // if the process had crashed here.
+    std::process::exit(0);
+}
+
+/// Keys in a base dataset of `base_mb`.
+fn base_keys(base_mb: u64) -> u64 {
+    (base_mb << 20) / (KEY_BYTES + VALUE_SIZE as u64)
+}
+
+// ---------------------------------------------------------------------------
+// Parent: one crash and recovery per run
+// ---------------------------------------------------------------------------
+
+/// One crash and recovery.
+struct Run {
+    wal_bytes: u64,
+    keys: u64,
+    counted: bool,
+    disk_bytes: u64,
+    crash_open: Duration,
+    clean_open: Duration,
+    tail_lost: u64,
+    failures: Vec<String>,
+}
+
+fn timed_open(dir: &Path, mode: DurabilityConfig) -> (Duration, Strata) {
+    let start = Instant::now();
+    let db = open_db_in(dir, mode);
+    (start.elapsed(), db)
+}
+
+fn run_once(config: &Config, mode: DurabilityConfig, size_mb: u64) -> Result<Run, String> {
+    let dir = TempDir::new_in(harness::cli::db_root()).map_err(|e| format!("cannot create temp dir: {}", e))?;
+
+    // Base dataset, checkpointed by a clean close
+    let base = base_keys(config.base_mb);
+    if base > 0 {
+        let db = open_db_in(dir.path(), mode);
+        for i in 0..base {
+            db.kv_put(&kv_key(i), incompressible_value(i, VALUE_SIZE)).unwrap();
+        }
+    }
+
+    let emit = dir.path().with_extension("child.json");
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let mut child = Command::new(exe);
+    if let Some(spec) = harness::cli::strata_config_spec() {
+        child.args(["--strata-config", &spec]);
+    }
+    let status = child
+        .args([
+            "--child",
+            &size_mb.to_string(),
+            "--dir",
+            &dir.path().display().to_string(),
+            "--emit",
+            &emit.display().to_string(),
+            "--base-mb",
+            &config.base_mb.to_string(),
+            "--durability",
+            &mode.label(),
+        ])
+        .status()
+        .map_err(|e| format!("could not start child: {}", e))?;
+    let emitted = std::fs::read_to_string(&emit)
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok());
+    let _ = std::fs::remove_file(&emit);
+    let Some(v) = emitted else {
+        return Err(format!("child exited with {} and reported nothing", status));
+    };
+    let first = v["first"].as_u64().unwrap_or(base);
+    let keys = v["keys"].as_u64().unwrap_or(0);
+
+    let disk_bytes = dir_size_bytes(dir.path());
+    let (crash_open, db) = timed_open(dir.path(), mode);
+
+    let mut failures = Vec::new();
+    if base > 0 && db.kv_get(&kv_key(0)).unwrap().is_none() {
+        failures.push("base dataset lost in recovery".to_string());
+    }
+    let tail_start = (first + keys).saturating_sub(TAIL_KEYS).max(first);
+    let tail_lost = (tail_start..first + keys)
+        .filter(|&i| db.kv_get(&kv_key(i)).unwrap().is_none())
+        .count() as u64;
+    // Standard mode may lose writes that were not yet synced; always may not
+    if tail_lost > 0 && matches!(mode, DurabilityConfig::Always) {
+        failures.push(format!(
+            "{} of the last {} acknowledged writes lost in recovery",
+            tail_lost,
+            first + keys - tail_start
+        ));
+    }
+
+    drop(db);
+    let (clean_open, _db) = timed_open(dir.path(), mode);
+
+    Ok(Run {
+        wal_bytes: v["wal_bytes"].as_u64().unwrap_or(0),
+        keys,
+        counted: v["counted"].as_bool().unwrap_or(false),
+        disk_bytes,
+        crash_open,
+        clean_open,
+        tail_lost,
+        failures,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Results
+// ---------------------------------------------------------------------------
+
+/// All runs of one WAL size, summarized by their medians.
+struct SizeResult {
+    size_mb: u64,
+    runs: Vec<Run>,
+}
+
+fn median<T: Copy + Ord>(mut v: Vec<T>) -> T {
+    v.sort_unstable();
+    v[v.len() / 2]
+}
+
+impl SizeResult {
+    fn crash_open(&self) -> Duration {
+        median(self.runs.iter().map(|r| r.crash_open).collect())
+    }
+
+    fn clean_open(&self) -> Duration {
+        median(self.runs.iter().map(|r| r.clean_open).collect())
+    }
+
+    fn wal_bytes(&self) -> u64 {
+        median(self.runs.iter().map(|r| r.wal_bytes).collect())
+    }
+
+    fn disk_bytes(&self) -> u64 {
+        median(self.runs.iter().map(|r| r.disk_bytes).collect())
+    }
+
+    fn wal_mb(&self) -> f64 {
+        self.wal_bytes() as f64 / (1u64 << 20) as f64
+    }
+
+    /// WAL replayed per second of `crash_open`, fixed open cost included.
+    fn replay_mb_per_sec(&self) -> f64 {
+        self.wal_mb() / self.crash_open().as_secs_f64().max(1e-9)
+    }
+
+    fn tail_lost(&self) -> u64 {
+        self.runs.iter().map(|r| r.tail_lost).max().unwrap_or(0)
+    }
+
+    /// True unless a clean reopen took nearly as long as the crash reopen.
+    fn close_checkpoints(&self) -> bool {
+        self.clean_open().as_secs_f64() < NO_CHECKPOINT_RATIO * self.crash_open().as_secs_f64()
+    }
+
+    fn failures(&self) -> Vec<String> {
+        let mut all: Vec<String> = self.runs.iter().flat_map(|r| r.failures.clone()).collect();
+        all.dedup();
+        all
+    }
+}
+
+/// Least-squares line through `crash_open` (seconds) against WAL size (MB).
+/// Returns the marginal replay rate in MB/s and the fixed open cost.
+fn fit_replay(results: &[SizeResult]) -> Option<(f64, Duration)> {
+    if results.len() < 2 {
+        return None;
+    }
+    let points: Vec<(f64, f64)> = results
+        .iter()
+        .map(|r| (r.wal_mb(), r.crash_open().as_secs_f64()))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    if sxx <= 0.0 || sxy <= 0.0 {
+        return None;
+    }
+    let slope = sxy / sxx;
+    let intercept = (mean_y - slope * mean_x).max(0.0);
+    Some((1.0 / slope, Duration::from_secs_f64(intercept)))
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_size(
+    recorder: &mut ResultRecorder,
+    config: &Config,
+    mode: DurabilityConfig,
+    r: &SizeResult,
+    fit: Option<(f64, Duration)>,
+) {
+    let opens: Vec<u64> = r.runs.iter().map(|run| run.crash_open.as_nanos() as u64).collect();
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("wal_target_mb".into(), serde_json::json!(r.size_mb));
+    params.insert("wal_bytes".into(), serde_json::json!(r.wal_bytes()));
+    params.insert(
+        "wal_bytes_counted".into(),
+        serde_json::json!(r.runs.iter().all(|run| run.counted)),
+    );
+    params.insert("base_mb".into(), serde_json::json!(config.base_mb));
+    params.insert("value_size".into(), serde_json::json!(VALUE_SIZE));
+    params.insert("wal_keys".into(), serde_json::json!(median(r.runs.iter().map(|run| run.keys).collect())));
+    params.insert("clean_open_ns".into(), serde_json::json!(r.clean_open().as_nanos() as u64));
+    params.insert("replay_mb_per_sec".into(), serde_json::json!(r.replay_mb_per_sec()));
+    params.insert("tail_keys_lost".into(), serde_json::json!(r.tail_lost()));
+    params.insert("close_checkpoints".into(), serde_json::json!(r.close_checkpoints()));
+    if let Some((rate, fixed)) = fit {
+        params.insert("replay_mb_per_sec_fit".into(), serde_json::json!(rate));
+        params.insert("fixed_open_ns_fit".into(), serde_json::json!(fixed.as_nanos() as u64));
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("recovery/wal_replay/{}MB/{}", r.size_mb, mode.label()),
+        category: "recovery".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            p50_ns: Some(r.crash_open().as_nanos() as u64),
+            min_ns: opens.iter().min().copied(),
+            max_ns: opens.iter().max().copied(),
+            samples: Some(r.runs.len() as u64),
+            disk_bytes: Some(r.disk_bytes()),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::from_validation(&r.failures()),
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn fmt_mb(bytes: u64) -> String {
+    format!("{:.1}MB", bytes as f64 / (1u64 << 20) as f64)
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>8}  {:>10}  {:>10}  {:>10}  {:>11}  {:>11}  {:>11}  {:>6}",
+        "target", "WAL", "keys", "disk", "crash open", "clean open", "replay", "lost"
+    );
+}
+
+fn print_table_row(r: &SizeResult) {
+    eprintln!(
+        "  {:>6}MB  {:>10}  {:>10}  {:>10}  {:>11}  {:>11}  {:>6.0} MB/s  {:>6}",
+        r.size_mb,
+        fmt_mb(r.wal_bytes()),
+        fmt_num(median(r.runs.iter().map(|run| run.keys).collect())),
+        fmt_mb(r.disk_bytes()),
+        fmt_ms(r.crash_open()),
+        fmt_ms(r.clean_open()),
+        r.replay_mb_per_sec(),
+        r.tail_lost(),
+    );
+}
+
+fn print_quiet(r: &SizeResult, mode: DurabilityConfig) {
+    eprintln!(
+        "wal {}MB ({}): crash open {} ({:.0} MB/s)",
+        r.size_mb,
+        mode.label(),
+        fmt_ms(r.crash_open()),
+        r.replay_mb_per_sec(),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"durability\",\"wal_target_mb\",\"wal_bytes\",\"disk_bytes\",\"crash_open_ms\",\"clean_open_ms\",\"replay_mb_s\",\"tail_keys_lost\"");
+}
+
+fn print_csv_row(r: &SizeResult, mode: DurabilityConfig) {
+    println!(
+        "\"{}\",{},{},{},{:.3},{:.3},{:.1},{}",
+        mode.label(),
+        r.size_mb,
+        r.wal_bytes(),
+        r.disk_bytes(),
+        r.crash_open().as_secs_f64() * 1000.0,
+        r.clean_open().as_secs_f64() * 1000.0,
+        r.replay_mb_per_sec(),
+        r.tail_lost(),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: Vec<DurabilityConfig>,
+    sizes_mb: Vec<u64>,
+    base_mb: u64,
+    runs: usize,
+    csv: bool,
+    quiet: bool,
+    /// Set in child processes: write this many MB of WAL to `dir`, report to `emit`.
+    child: Option<(u64, String, String)>,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DISK_MODES.to_vec(),
+        sizes_mb: DEFAULT_SIZES_MB.to_vec(),
+        base_mb: DEFAULT_BASE_MB,
+        runs: DEFAULT_RUNS,
+        csv: false,
+        quiet: false,
+        child: None,
+    };
+    let mut child_size = None;
+    let mut dir = None;
+    let mut emit = None;
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--sizes" => config.sizes_mb = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            "--base-mb" => config.base_mb = args.parse_value(),
+            "--runs" => config.runs = args.parse_value::<usize>().max(1),
+            "--child" => child_size = Some(args.parse_value()),
+            "--dir" => dir = Some(args.value()),
+            "--emit" => emit = Some(args.value()),
+            _ => args.unknown_flag(),
+        }
+    }
+    if matches!(args.durability, Some(DurabilityConfig::Cache)) {
+        eprintln!("Cache mode has no WAL, so there is nothing to replay");
+        std::process::exit(1);
+    }
+    config.durability = args.durability_modes(&DISK_MODES);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config.sizes_mb.sort_unstable();
+    config.sizes_mb.dedup();
+    config.child = match (child_size, dir, emit) {
+        (Some(size), Some(dir), Some(emit)) => Some((size, dir, emit)),
+        _ => None,
+    };
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    if let Some((size_mb, dir, emit)) = &config.child {
+        child_main(&config, *size_mb, dir, emit);
+        return;
+    }
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB WAL Replay Benchmark ===");
+        eprintln!("Times reopening after a crash as the un-checkpointed WAL grows.");
+        eprintln!();
+        eprintln!(
+            "WAL sizes: {:?} MB on a {} MB base, {}B values, {} runs each",
+            config.sizes_mb, config.base_mb, VALUE_SIZE, config.runs
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("recovery");
+    let verbose = !config.csv && !config.quiet;
+
+    for &mode in &config.durability {
+        if verbose {
+            eprintln!("--- durability: {} ---", mode.label());
+            print_table_header();
+        }
+
+        let mut results = Vec::new();
+        for &size_mb in &config.sizes_mb {
+            let name = format!("recovery/wal_replay/{}MB/{}", size_mb, mode.label());
+            let mut params = HashMap::new();
+            params.insert("durability".into(), serde_json::json!(mode.label()));
+            params.insert("wal_target_mb".into(), serde_json::json!(size_mb));
+            let measured = recorder.guard(&name, params.clone(), |recorder| {
+                let mut runs = Vec::new();
+                for _ in 0..config.runs {
+                    match run_once(&config, mode, size_mb) {
+                        Ok(run) => runs.push(run),
+                        Err(message) => {
+                            eprintln!("  {}MB: {}", size_mb, message);
+                            recorder.record_failure(&name, params, ResultStatus::Error { message });
+                            return None;
+                        }
+                    }
+                }
+                let r = SizeResult { size_mb, runs };
+                if config.csv {
+                    print_csv_row(&r, mode);
+                } else if config.quiet {
+                    print_quiet(&r, mode);
+                } else {
+                    print_table_row(&r);
+                }
+                for failure in r.failures() {
+                    eprintln!("  Validation: FAIL ({})", failure);
+                }
+                Some(r)
+            });
+            results.extend(measured.flatten());
+        }
+
+        let fit = fit_replay(&results);
+        for r in &results {
+            record_size(&mut recorder, &config, mode, r, fit);
+        }
+        if !config.csv {
+            if let Some((rate, fixed)) = fit {
+                eprintln!(
+                    "  crash open ~ {} + WAL at {:.0} MB/s (1 GB of WAL ~ {:.1}s)",
+                    fmt_ms(fixed),
+                    rate,
+                    fixed.as_secs_f64() + 1024.0 / rate
+                );
+            }
+            if results.iter().any(|r| r.wal_mb() >= 64.0 && !r.close_checkpoints()) {
+                eprintln!(
+                    "  clean reopens took over {:.0}% of the crash reopen: closing doesn't appear to \
+                     checkpoint, so the base dataset's WAL was replayed as well",
+                    NO_CHECKPOINT_RATIO * 100.0
+                );
+            }
+            if results.iter().any(|r| r.runs.iter().any(|run| !run.counted)) {
+                eprintln!("  durability counters reported no WAL bytes; sizes are the logical size of the writes");
+            }
+            eprintln!();
+        }
+    }
+
+    if !config.csv {
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
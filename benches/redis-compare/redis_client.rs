@@ -0,0 +1,265 @@
+//! Real Redis backend for the redis_compare benchmark.
+//!
+//! Drives a locally running Redis server through redis-rs with the same
+//! operation mix, key generator, and payload as the Strata tests, so both
+//! engines' rows come out of one run. Only compiled with the `redis-client`
+//! feature.
+//!
+//! Strata-unique tests (STATE_SET, STATE_READ, EVENT_READ, KV_DELETE) have no
+//! Redis row; every other test issues the exact redis-benchmark command,
+//! including the list/set/sorted-set tests that Strata only emulates.
+
+use super::{pipelined, BenchResult, KeyGen, Load, LRANGE_LENGTHS};
+use redis::{Commands, Connection, Pipeline, RedisResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Open a synchronous connection and verify the server answers PING.
+pub fn connect(url: &str) -> Result<Connection, String> {
+    let client = redis::Client::open(url).map_err(|e| format!("invalid redis url: {}", e))?;
+    let mut con = client
+        .get_connection()
+        .map_err(|e| format!("cannot connect to {}: {}", url, e))?;
+    redis::cmd("PING")
+        .query::<String>(&mut con)
+        .map_err(|e| format!("PING failed: {}", e))?;
+    Ok(con)
+}
+
+/// Server-side persistence configuration, reported next to the Redis rows so
+/// the durability comparison is explicit.
+pub fn persistence_summary(con: &mut Connection) -> String {
+    let get = |con: &mut Connection, name: &str| -> String {
+        redis::cmd("CONFIG")
+            .arg("GET")
+            .arg(name)
+            .query::<Vec<String>>(con)
+            .ok()
+            .and_then(|v| v.get(1).cloned())
+            .unwrap_or_else(|| "?".to_string())
+    };
+    let appendonly = get(con, "appendonly");
+    let appendfsync = get(con, "appendfsync");
+    format!("appendonly {}, appendfsync {}", appendonly, appendfsync)
+}
+
+/// One connection per client, matching redis-benchmark's `-c`.
+fn connections(url: &str, clients: usize) -> Result<Vec<Connection>, String> {
+    (0..clients.max(1)).map(|_| connect(url)).collect()
+}
+
+/// [`super::run_bench`] for commands the server can reject. The first error
+/// any client sees is returned instead of a result, and every client skips
+/// its remaining requests, so a dropped connection doesn't spin out the run.
+fn run_bench(
+    name: &str,
+    redis_equiv: &str,
+    load: Load,
+    url: &str,
+    clients: usize,
+    bench_fn: impl Fn(&mut Connection, &mut KeyGen) -> RedisResult<()> + Sync,
+) -> Result<BenchResult, String> {
+    let failed = AtomicBool::new(false);
+    let error = Mutex::new(None);
+    let r = super::run_bench(name, redis_equiv, load, connections(url, clients)?, |c, kg| {
+        if failed.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Err(e) = bench_fn(c, kg) {
+            failed.store(true, Ordering::Relaxed);
+            error.lock().unwrap().get_or_insert(e);
+        }
+    });
+    match error.into_inner().unwrap() {
+        Some(e) => Err(format!("{} failed: {}", name, e)),
+        None => Ok(r),
+    }
+}
+
+/// A `-P` test: each call sends `pipeline` commands built by `add` as one
+/// redis-rs pipeline and waits for every reply, as redis-benchmark `-P` does.
+/// `load.ops` counts requests; latencies are per batch.
+fn run_pipelined(
+    name: &str,
+    url: &str,
+    clients: usize,
+    load: Load,
+    pipeline: usize,
+    add: impl Fn(&mut Pipeline, &mut KeyGen) + Sync,
+) -> Result<BenchResult, String> {
+    let batches = Load {
+        ops: load.ops.div_ceil(pipeline),
+        ..load
+    };
+    let r = run_bench(name, &format!("{} -P", name), batches, url, clients, |c, kg| {
+        let mut pipe = redis::pipe();
+        for _ in 0..pipeline {
+            add(&mut pipe, kg);
+        }
+        pipe.query::<()>(c)
+    })?;
+    Ok(pipelined(r, pipeline))
+}
+
+/// Hand every redis-benchmark test to `run_test`, in redis-benchmark's
+/// order, as its `--tests` name and a closure that runs it against the
+/// server. The database is flushed first so results don't depend on leftover
+/// state. With `pipeline` > 1, SET, GET and INCR are pipelined, matching the
+/// Strata side.
+pub fn run_all(
+    url: &str,
+    con: &mut Connection,
+    clients: usize,
+    load: Load,
+    pipeline: usize,
+    data: &[u8],
+    mut run_test: impl FnMut(&str, &mut dyn FnMut() -> Result<BenchResult, String>),
+) {
+    let _ = redis::cmd("FLUSHDB").query::<()>(con);
+
+    run_test("PING", &mut || {
+        run_bench("PING_INLINE", "PING_INLINE", load, url, clients, |c, _kg| {
+            redis::cmd("PING").query::<String>(c).map(drop)
+        })
+    });
+
+    run_test("PING_MBULK", &mut || {
+        run_bench("PING_MBULK", "PING_MBULK", load, url, clients, |c, _kg| {
+            redis::cmd("PING").query::<String>(c).map(drop)
+        })
+    });
+
+    run_test("SET", &mut || {
+        if pipeline > 1 {
+            run_pipelined("SET", url, clients, load, pipeline, |pipe, kg| {
+                pipe.set(kg.key("key"), data).ignore();
+            })
+        } else {
+            run_bench("SET", "SET", load, url, clients, |c, kg| {
+                let key = kg.key("key");
+                c.set::<_, _, ()>(key, data)
+            })
+        }
+    });
+
+    run_test("GET", &mut || {
+        if pipeline > 1 {
+            run_pipelined("GET", url, clients, load, pipeline, |pipe, kg| {
+                pipe.get(kg.key("key")).ignore();
+            })
+        } else {
+            run_bench("GET", "GET", load, url, clients, |c, kg| {
+                let key = kg.key("key");
+                c.get::<_, Option<Vec<u8>>>(key).map(drop)
+            })
+        }
+    });
+
+    run_test("INCR", &mut || {
+        if pipeline > 1 {
+            run_pipelined("INCR", url, clients, load, pipeline, |pipe, kg| {
+                pipe.incr(kg.key("counter"), 1).ignore();
+            })
+        } else {
+            run_bench("INCR", "INCR", load, url, clients, |c, kg| {
+                let key = kg.key("counter");
+                c.incr::<_, _, i64>(key, 1).map(drop)
+            })
+        }
+    });
+
+    run_test("LPUSH", &mut || {
+        run_bench("LPUSH", "LPUSH", load, url, clients, |c, _kg| {
+            c.lpush::<_, _, ()>("mylist", data)
+        })
+    });
+
+    run_test("RPUSH", &mut || {
+        run_bench("RPUSH", "RPUSH", load, url, clients, |c, _kg| {
+            c.rpush::<_, _, ()>("mylist", data)
+        })
+    });
+
+    run_test("LPOP", &mut || {
+        run_bench("LPOP", "LPOP", load, url, clients, |c, _kg| {
+            c.lpop::<_, Option<Vec<u8>>>("mylist", None).map(drop)
+        })
+    });
+
+    run_test("RPOP", &mut || {
+        run_bench("RPOP", "RPOP", load, url, clients, |c, _kg| {
+            c.rpop::<_, Option<Vec<u8>>>("mylist", None).map(drop)
+        })
+    });
+
+    run_test("SADD", &mut || {
+        run_bench("SADD", "SADD", load, url, clients, |c, kg| {
+            let member = kg.key("element");
+            c.sadd::<_, _, ()>("myset", member)
+        })
+    });
+
+    run_test("HSET", &mut || {
+        run_bench("HSET", "HSET", load, url, clients, |c, kg| {
+            let field = kg.key("element");
+            c.hset::<_, _, _, ()>("myhash", field, data)
+        })
+    });
+
+    run_test("SPOP", &mut || {
+        run_bench("SPOP", "SPOP", load, url, clients, |c, _kg| {
+            c.spop::<_, Option<String>>("myset").map(drop)
+        })
+    });
+
+    run_test("ZADD", &mut || {
+        run_bench("ZADD", "ZADD", load, url, clients, |c, kg| {
+            let score = kg.next_rand() % 1_000_000;
+            let member = kg.key("element");
+            c.zadd::<_, _, _, ()>("myzset", member, score)
+        })
+    });
+
+    run_test("ZPOPMIN", &mut || {
+        run_bench("ZPOPMIN", "ZPOPMIN", load, url, clients, |c, _kg| {
+            c.zpopmin::<_, Vec<String>>("myzset", 1).map(drop)
+        })
+    });
+
+    run_test("MSET", &mut || {
+        run_bench("MSET (10 keys)", "MSET (10 keys)", load, url, clients, |c, kg| {
+            let pairs: Vec<(String, &[u8])> = (0..10).map(|_| (kg.key("key"), data)).collect();
+            c.mset::<_, _, ()>(&pairs)
+        })
+    });
+
+    run_test("XADD", &mut || {
+        run_bench("XADD", "XADD", load, url, clients, |c, _kg| {
+            c.xadd::<_, _, _, _, String>("mystream", "*", &[("myfield", data)])
+                .map(drop)
+        })
+    });
+
+    for len in LRANGE_LENGTHS {
+        let stop = len as isize - 1;
+        run_test(&format!("LRANGE_{}", len), &mut || {
+            // A list of 600 elements, as redis-benchmark's LPUSH leaves it
+            let fill = |con: &mut Connection| -> RedisResult<()> {
+                con.del::<_, ()>("mylist")?;
+                for _ in 0..600 {
+                    con.lpush::<_, _, ()>("mylist", data)?;
+                }
+                Ok(())
+            };
+            fill(con).map_err(|e| format!("LRANGE_{} setup failed: {}", len, e))?;
+            run_bench(
+                &format!("LRANGE_{} (first {} elements)", len, len),
+                &format!("LRANGE_{}", len),
+                load,
+                url,
+                clients,
+                |c, _kg| c.lrange::<_, Vec<Vec<u8>>>("mylist", 0, stop).map(drop),
+            )
+        });
+    }
+}
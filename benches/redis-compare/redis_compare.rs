@@ -5,23 +5,33 @@
 //!
 //! By default, matches redis-benchmark's default behavior (no key randomization,
 //! all operations hit the same key). Use `-r <keyspace>` to enable random keys
-//! (equivalent to `redis-benchmark -r <keyspace>`).
+//! (equivalent to `redis-benchmark -r <keyspace>`). Use `--duration <secs>` to
+//! run each test for a fixed wall-clock time instead of a fixed request count.
 //!
 //! Run: `cargo bench --bench redis_compare`
 //! Random keys: `cargo bench --bench redis_compare -- -r 100000`
+//! Timed: `cargo bench --bench redis_compare -- -r 100000 --duration 10`
+//! Clients: `cargo bench --bench redis_compare -- -c 50 -r 100000`
+//! Pipeline: `cargo bench --bench redis_compare -- -P 16 -t set,get,incr`
 //! Quick: `cargo bench --bench redis_compare -- --durability cache -q`
 //! CSV:  `cargo bench --bench redis_compare -- --csv`
+//! Real Redis: `cargo bench --bench redis_compare --features redis-client -- --redis redis://127.0.0.1:6379`
 
 #[allow(unused)]
 #[path = "../harness/mod.rs"]
 mod harness;
 
+#[cfg(feature = "redis-client")]
+mod redis_client;
+
+use harness::load::Timings;
 use harness::recorder::ResultRecorder;
 use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
-use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult};
-use stratadb::{Command, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, LatencySpike, ResultStatus};
+use stratadb::{Command, Error, Output, Session, Strata, Value};
 
 // ---------------------------------------------------------------------------
 // Parameters (matching redis-benchmark defaults)
@@ -29,6 +39,8 @@ use stratadb::{Command, Value};
 
 const DEFAULT_REQUESTS: usize = 100_000;
 const DEFAULT_PAYLOAD_SIZE: usize = 3;
+/// Key stream seed when `--seed` isn't given.
+const KEY_SEED: u64 = 0xdeadbeef;
 
 // ---------------------------------------------------------------------------
 // Random data generator (matching redis-benchmark's genBenchmarkRandomData)
@@ -63,10 +75,13 @@ struct KeyGen {
 }
 
 impl KeyGen {
-    fn new(keyspace: u64) -> Self {
+    /// Independent key stream for client `client` of a multi-client run.
+    /// Client 0 starts from `seed` itself, so a single-client run's keys
+    /// depend on the seed alone.
+    fn for_client(keyspace: u64, seed: u64, client: usize) -> Self {
         Self {
             keyspace,
-            rng_state: 0xdeadbeef,
+            rng_state: seed ^ (client as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15),
         }
     }
 
@@ -93,6 +108,25 @@ impl KeyGen {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Load definition
+// ---------------------------------------------------------------------------
+
+/// How much work one test issues and over which keys.
+#[derive(Clone, Copy)]
+struct Load {
+    /// Total operations across all clients (`-n`). Ignored when `duration`
+    /// is set.
+    ops: usize,
+    /// Random key range (`-r`); 0 means every operation hits the same key.
+    keyspace: u64,
+    /// Seed for the key streams (`--seed`).
+    seed: u64,
+    /// Run each client until this much wall-clock time has passed
+    /// (`--duration`) instead of stopping after `ops`.
+    duration: Option<Duration>,
+}
+
 // ---------------------------------------------------------------------------
 // Benchmark result
 // ---------------------------------------------------------------------------
@@ -100,6 +134,12 @@ impl KeyGen {
 struct BenchResult {
     name: String,
     redis_equiv: String,
+    clients: usize,
+    /// Operations per transaction (`-P`); 1 when not pipelined.
+    pipeline: usize,
+    /// Pipelined batches whose commit aborted and were retried. Only
+    /// committed batches count toward `total_ops`.
+    aborts: u64,
     total_ops: usize,
     elapsed: Duration,
     ops_per_sec: f64,
@@ -107,55 +147,93 @@ struct BenchResult {
     p50: Duration,
     p95: Duration,
     p99: Duration,
+    p999: Duration,
+    p9999: Duration,
     min: Duration,
     max: Duration,
+    /// The slowest requests (batches when pipelined), slowest first.
+    spikes: Vec<LatencySpike>,
 }
 
 // ---------------------------------------------------------------------------
 // Core measurement function
 // ---------------------------------------------------------------------------
 
-/// Run a benchmark. No warmup phase — matches redis-benchmark which starts
-/// timing immediately (see benchmark() at line 946).
-fn run_bench(
+/// Run a benchmark across `contexts.len()` parallel clients (redis-benchmark's
+/// `-c`). Each client owns one context (a Strata handle or a server
+/// connection) and its own `KeyGen`, and issues its share of `load.ops`.
+/// No warmup phase — matches redis-benchmark which starts timing immediately
+/// (see benchmark() at line 946).
+///
+/// With `load.duration` set, every client keeps issuing operations until the
+/// deadline and the request count is whatever completed.
+///
+/// With a single client the operations run on the calling thread, exactly as
+/// before `-c` existed.
+fn run_bench<C: Send>(
     name: &str,
     redis_equiv: &str,
-    total_ops: usize,
-    mut bench_fn: impl FnMut(&mut KeyGen),
-    keygen: &mut KeyGen,
+    load: Load,
+    contexts: Vec<C>,
+    bench_fn: impl Fn(&mut C, &mut KeyGen) + Sync,
 ) -> BenchResult {
-    // Measure every operation
-    let mut latencies = Vec::with_capacity(total_ops);
-    let wall_start = Instant::now();
-
-    for _ in 0..total_ops {
-        let op_start = Instant::now();
-        bench_fn(keygen);
-        latencies.push(op_start.elapsed());
-    }
-
-    let elapsed = wall_start.elapsed();
+    let clients = contexts.len();
+    let (keyspace, seed) = (load.keyspace, load.seed);
+    let spec = harness::load::Load {
+        ops: load.ops,
+        duration: load.duration,
+        rate: None,
+    };
 
-    // Compute statistics
-    latencies.sort_unstable();
-    let len = latencies.len();
-    let sum: Duration = latencies.iter().sum();
+    let run: harness::load::LoadResult<Timings> =
+        harness::load::run(&spec, contexts, |client, mut ctx| {
+            let mut keygen = KeyGen::for_client(keyspace, seed, client);
+            let bench_fn = &bench_fn;
+            move || bench_fn(&mut ctx, &mut keygen)
+        });
+    let latencies = &run.recorder.latencies;
+    let len = run.ops as usize;
 
     BenchResult {
         name: name.to_string(),
         redis_equiv: redis_equiv.to_string(),
+        clients,
+        pipeline: 1,
+        aborts: 0,
         total_ops: len,
-        elapsed,
-        ops_per_sec: len as f64 / elapsed.as_secs_f64(),
-        avg_latency: sum / len as u32,
-        p50: latencies[len * 50 / 100],
-        p95: latencies[(len * 95 / 100).min(len - 1)],
-        p99: latencies[(len * 99 / 100).min(len - 1)],
-        min: latencies[0],
-        max: latencies[len - 1],
+        elapsed: run.elapsed,
+        ops_per_sec: run.ops_per_sec(),
+        avg_latency: latencies.mean(),
+        p50: latencies.p50(),
+        p95: latencies.p95(),
+        p99: latencies.p99(),
+        p999: latencies.p999(),
+        p9999: latencies.p9999(),
+        min: latencies.min(),
+        max: latencies.max(),
+        spikes: run.recorder.spikes.finish(),
     }
 }
 
+/// Convert a result measured per batch into per-request terms. Latencies stay
+/// per batch (what a pipelining client waits for, retries included), matching
+/// redis-benchmark `-P`, while request counts and RPS count every operation
+/// in the batch.
+fn pipelined(mut r: BenchResult, pipeline: usize) -> BenchResult {
+    r.pipeline = pipeline;
+    r.total_ops *= pipeline;
+    r.ops_per_sec *= pipeline as f64;
+    r
+}
+
+/// One Strata handle per client. Handles share the underlying database, like
+/// redis-benchmark's clients share one server.
+fn client_handles(db: &Strata, clients: usize) -> Vec<Strata> {
+    (0..clients.max(1))
+        .map(|_| db.new_handle().expect("failed to create Strata handle for client"))
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Output formatters
 // ---------------------------------------------------------------------------
@@ -174,8 +252,18 @@ fn print_verbose(r: &BenchResult, payload_size: usize) {
         r.total_ops,
         r.elapsed.as_secs_f64()
     );
-    eprintln!("  1 parallel client (embedded, no network)");
+    if r.clients == 1 {
+        eprintln!("  1 parallel client (embedded, no network)");
+    } else {
+        eprintln!("  {} parallel clients (one thread + handle each)", r.clients);
+    }
     eprintln!("  {} bytes payload", payload_size);
+    if r.pipeline > 1 {
+        eprintln!("  pipeline: {} requests per transaction (latency is per batch)", r.pipeline);
+        if r.aborts > 0 {
+            eprintln!("  {} batches aborted on commit and were retried", r.aborts);
+        }
+    }
     eprintln!();
     eprintln!(
         "  throughput summary: {:.2} requests per second",
@@ -183,15 +271,16 @@ fn print_verbose(r: &BenchResult, payload_size: usize) {
     );
     eprintln!("  latency summary (msec):");
     eprintln!(
-        "          avg       min       p50       p95       p99       max"
+        "          avg       min       p50       p95       p99     p99.9       max"
     );
     eprintln!(
-        "      {:>8.3}  {:>8.3}  {:>8.3}  {:>8.3}  {:>8.3}  {:>8.3}",
+        "      {:>8.3}  {:>8.3}  {:>8.3}  {:>8.3}  {:>8.3}  {:>8.3}  {:>8.3}",
         duration_ms(r.avg_latency),
         duration_ms(r.min),
         duration_ms(r.p50),
         duration_ms(r.p95),
         duration_ms(r.p99),
+        duration_ms(r.p999),
         duration_ms(r.max),
     );
     eprintln!();
@@ -230,10 +319,34 @@ fn print_csv_row(r: &BenchResult) {
 // Recording helper
 // ---------------------------------------------------------------------------
 
-fn record_redis_result(recorder: &mut ResultRecorder, r: &BenchResult, mode: &DurabilityConfig) {
+/// Parameters describing the load shape, shared by Strata and Redis rows.
+fn load_params(config: &Config) -> HashMap<String, serde_json::Value> {
     let mut params = HashMap::new();
+    params.insert("keyspace".into(), serde_json::json!(config.keyspace));
+    params.insert("seed".into(), serde_json::json!(config.seed));
+    match config.duration {
+        Some(d) => params.insert("duration_secs".into(), serde_json::json!(d.as_secs_f64())),
+        None => params.insert("requests".into(), serde_json::json!(config.requests)),
+    };
+    params.insert("payload_size".into(), serde_json::json!(config.payload_size));
+    params
+}
+
+fn record_redis_result(
+    recorder: &mut ResultRecorder,
+    r: &BenchResult,
+    mode: &DurabilityConfig,
+    config: &Config,
+) {
+    let mut params = load_params(config);
     params.insert("durability".into(), serde_json::json!(mode.label()));
     params.insert("redis_equiv".into(), serde_json::json!(r.redis_equiv));
+    params.insert("engine".into(), serde_json::json!("strata"));
+    params.insert("clients".into(), serde_json::json!(r.clients));
+    params.insert("pipeline".into(), serde_json::json!(r.pipeline));
+    if r.pipeline > 1 {
+        params.insert("aborted_batches".into(), serde_json::json!(r.aborts));
+    }
 
     recorder.record(BenchmarkResult {
         benchmark: format!("redis-compare/{}/{}", r.name, mode.label()),
@@ -244,12 +357,70 @@ fn record_redis_result(recorder: &mut ResultRecorder, r: &BenchResult, mode: &Du
             p50_ns: Some(r.p50.as_nanos() as u64),
             p95_ns: Some(r.p95.as_nanos() as u64),
             p99_ns: Some(r.p99.as_nanos() as u64),
+            p999_ns: Some(r.p999.as_nanos() as u64),
+            p9999_ns: Some(r.p9999.as_nanos() as u64),
+            min_ns: Some(r.min.as_nanos() as u64),
+            max_ns: Some(r.max.as_nanos() as u64),
+            avg_ns: Some(r.avg_latency.as_nanos() as u64),
+            samples: Some(r.total_ops as u64),
+            threads: Some(r.clients),
+            abort_rate_pct: (r.pipeline > 1).then(|| abort_rate_pct(r)),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: r.spikes.clone(),
+        status: ResultStatus::Success,
+    });
+}
+
+/// Aborted commits as a percentage of all pipelined commit attempts.
+fn abort_rate_pct(r: &BenchResult) -> f64 {
+    let committed = (r.total_ops / r.pipeline.max(1)) as u64;
+    let attempts = committed + r.aborts;
+    if attempts == 0 {
+        0.0
+    } else {
+        r.aborts as f64 / attempts as f64 * 100.0
+    }
+}
+
+/// Record a row measured against a real Redis server. `persistence` is the
+/// server's own appendonly/appendfsync setting, since Redis durability is
+/// configured server-side rather than per run.
+#[cfg(feature = "redis-client")]
+fn record_redis_server_result(
+    recorder: &mut ResultRecorder,
+    r: &BenchResult,
+    persistence: &str,
+    config: &Config,
+) {
+    let mut params = load_params(config);
+    params.insert("engine".into(), serde_json::json!("redis"));
+    params.insert("redis_persistence".into(), serde_json::json!(persistence));
+    params.insert("clients".into(), serde_json::json!(r.clients));
+    params.insert("pipeline".into(), serde_json::json!(r.pipeline));
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("redis-compare/{}/redis-server", r.name),
+        category: "redis-compare".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec),
+            p50_ns: Some(r.p50.as_nanos() as u64),
+            p95_ns: Some(r.p95.as_nanos() as u64),
+            p99_ns: Some(r.p99.as_nanos() as u64),
+            p999_ns: Some(r.p999.as_nanos() as u64),
+            p9999_ns: Some(r.p9999.as_nanos() as u64),
             min_ns: Some(r.min.as_nanos() as u64),
             max_ns: Some(r.max.as_nanos() as u64),
             avg_ns: Some(r.avg_latency.as_nanos() as u64),
             samples: Some(r.total_ops as u64),
+            threads: Some(r.clients),
             ..Default::default()
         },
+        artifacts: Vec::new(),
+        spikes: r.spikes.clone(),
+        status: ResultStatus::Success,
     });
 }
 
@@ -259,68 +430,209 @@ fn record_redis_result(recorder: &mut ResultRecorder, r: &BenchResult, mode: &Du
 // Each test matches the exact redis-benchmark default command.
 // Tests share the same database within a durability mode, just like
 // redis-benchmark shares the same Redis instance across all tests.
-// LRANGE_100 is the exception — it uses a fresh database because
+// The LRANGE_N tests are the exception — they use a fresh database because
 // kv_list prefix scan degrades with unrelated keys.
 // ---------------------------------------------------------------------------
 
 /// PING_INLINE: "PING\r\n" (redis-benchmark.c line 1880)
-fn bench_ping(db: &BenchDb, n: usize, keygen: &mut KeyGen) -> BenchResult {
-    run_bench("PING_INLINE", "PING_INLINE", n, |_kg| {
-        db.db.ping().unwrap();
-    }, keygen)
+fn bench_ping(db: &BenchDb, config: &Config) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("PING_INLINE", "PING_INLINE", config.load(), handles, |s, _kg| {
+        s.ping().unwrap();
+    })
 }
 
 /// SET: "SET key:__rand_int__ <data>" (redis-benchmark.c line 1889)
 /// Without -r: all writes go to the same key (hot-key benchmark).
-fn bench_set(db: &BenchDb, n: usize, data: &Value, keygen: &mut KeyGen) -> BenchResult {
-    run_bench("SET", "SET", n, |kg| {
+fn bench_set(db: &BenchDb, config: &Config, data: &Value) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("SET", "SET", config.load(), handles, |s, kg| {
         let key = kg.key("key");
-        db.db.kv_put(&key, data.clone()).unwrap();
-    }, keygen)
+        s.kv_put(&key, data.clone()).unwrap();
+    })
 }
 
 /// GET: "GET key:__rand_int__" (redis-benchmark.c line 1895)
 /// In redis-benchmark, GET runs after SET so the key already exists.
 /// Without -r: reads the same key SET wrote.
-fn bench_get(db: &BenchDb, n: usize, keygen: &mut KeyGen) -> BenchResult {
-    run_bench("GET", "GET", n, |kg| {
+fn bench_get(db: &BenchDb, config: &Config) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("GET", "GET", config.load(), handles, |s, kg| {
         let key = kg.key("key");
-        let _ = db.db.kv_get(&key);
-    }, keygen)
+        let _ = s.kv_get(&key);
+    })
 }
 
 /// INCR: "INCR counter:__rand_int__" (redis-benchmark.c line 1901)
 /// Redis INCR is a single atomic O(1) command.
 /// Strata equivalent requires state_read + state_set (2 operations).
-fn bench_incr(db: &BenchDb, n: usize, keygen: &mut KeyGen) -> BenchResult {
-    run_bench("INCR", "INCR (state_read+state_set)", n, |kg| {
+/// With `-c > 1` the read-modify-write is not atomic across clients, so the
+/// final counter may be lower than the request count; latency is unaffected.
+fn bench_incr(db: &BenchDb, config: &Config) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("INCR", "INCR (state_read+state_set)", config.load(), handles, |s, kg| {
         let cell = kg.key("counter");
-        let current = db.db.state_get(&cell).unwrap();
+        let current = s.state_get(&cell).unwrap();
         let val = match current {
             Some(Value::Int(v)) => v,
             _ => 0,
         };
-        db.db.state_set(&cell, Value::Int(val + 1)).unwrap();
-    }, keygen)
+        s.state_set(&cell, Value::Int(val + 1)).unwrap();
+    })
+}
+
+/// Load counted in batches: enough batches to issue `requests` operations
+/// `pipeline` at a time.
+fn batch_load(config: &Config) -> Load {
+    Load {
+        ops: config.requests.div_ceil(config.pipeline),
+        ..config.load()
+    }
+}
+
+/// Commit attempts per batch before a batch that keeps conflicting is given up on.
+const MAX_COMMIT_ATTEMPTS: u32 = 1_000;
+
+/// Run `batch` as one transaction, retrying while the commit aborts on a
+/// conflict (hot key, `-c` > 1), so an aborted batch isn't counted as served.
+/// Retries are added to `aborts`. Any other error is returned, and so is the
+/// last conflict once `MAX_COMMIT_ATTEMPTS` is reached; the callers panic on
+/// it, which fails the test through `run_test`'s guard.
+fn commit_batch(
+    session: &mut Session,
+    aborts: &AtomicU64,
+    mut batch: impl FnMut(&mut Session),
+) -> Result<(), Error> {
+    let mut attempts = 0;
+    loop {
+        session.execute(Command::TxnBegin { branch: None, options: None })?;
+        batch(session);
+        attempts += 1;
+        match session.execute(Command::TxnCommit) {
+            Ok(_) => return Ok(()),
+            Err(Error::TransactionConflict { .. }) if attempts < MAX_COMMIT_ATTEMPTS => {
+                aborts.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// SET with `-P`: each batch is one transaction of `pipeline` KvPut commands,
+/// the embedded analogue of sending `pipeline` SETs before reading replies.
+fn bench_set_pipelined(db: &BenchDb, config: &Config, data: &Value) -> BenchResult {
+    let p = config.pipeline;
+    let aborts = AtomicU64::new(0);
+    let handles = client_handles(&db.db, config.clients);
+    let r = run_bench("SET", "SET -P (txn of KvPut)", batch_load(config), handles, |s, kg| {
+        let keys: Vec<String> = (0..p).map(|_| kg.key("key")).collect();
+        commit_batch(&mut s.session(), &aborts, |session| {
+            for key in &keys {
+                session
+                    .execute(Command::KvPut {
+                        branch: None,
+                        space: None,
+                        key: key.clone(),
+                        value: data.clone(),
+                    })
+                    .unwrap();
+            }
+        })
+        .expect("SET -P batch failed");
+    });
+    BenchResult {
+        aborts: aborts.into_inner(),
+        ..pipelined(r, p)
+    }
+}
+
+/// GET with `-P`: one read-only transaction of `pipeline` KvGet commands.
+fn bench_get_pipelined(db: &BenchDb, config: &Config) -> BenchResult {
+    let p = config.pipeline;
+    let aborts = AtomicU64::new(0);
+    let handles = client_handles(&db.db, config.clients);
+    let r = run_bench("GET", "GET -P (txn of KvGet)", batch_load(config), handles, |s, kg| {
+        let keys: Vec<String> = (0..p).map(|_| kg.key("key")).collect();
+        commit_batch(&mut s.session(), &aborts, |session| {
+            for key in &keys {
+                session
+                    .execute(Command::KvGet {
+                        branch: None,
+                        space: None,
+                        key: key.clone(),
+                        as_of: None,
+                    })
+                    .unwrap();
+            }
+        })
+        .expect("GET -P batch failed");
+    });
+    BenchResult {
+        aborts: aborts.into_inner(),
+        ..pipelined(r, p)
+    }
+}
+
+/// INCR with `-P`: one transaction of `pipeline` read-modify-write pairs.
+/// `state_set` bypasses transactions, so the pipelined counter lives in KV
+/// (KvGet + KvPut) — labeled accordingly in the output.
+fn bench_incr_pipelined(db: &BenchDb, config: &Config) -> BenchResult {
+    let p = config.pipeline;
+    let aborts = AtomicU64::new(0);
+    let handles = client_handles(&db.db, config.clients);
+    let r = run_bench("INCR", "INCR -P (txn of kv_get+kv_put)", batch_load(config), handles, |s, kg| {
+        let keys: Vec<String> = (0..p).map(|_| kg.key("counter")).collect();
+        commit_batch(&mut s.session(), &aborts, |session| {
+            for key in &keys {
+                let current = session
+                    .execute(Command::KvGet {
+                        branch: None,
+                        space: None,
+                        key: key.clone(),
+                        as_of: None,
+                    })
+                    .unwrap();
+                let val = match current {
+                    Output::Maybe(Some(Value::Int(v))) => v,
+                    _ => 0,
+                };
+                session
+                    .execute(Command::KvPut {
+                        branch: None,
+                        space: None,
+                        key: key.clone(),
+                        value: Value::Int(val + 1),
+                    })
+                    .unwrap();
+            }
+        })
+        .expect("INCR -P batch failed");
+    });
+    BenchResult {
+        aborts: aborts.into_inner(),
+        ..pipelined(r, p)
+    }
 }
 
 /// HSET: "HSET myhash element:__rand_int__ <data>" (redis-benchmark.c line 1938)
 /// Redis HSET is O(1) hash field set. Strata has no native hash type.
 /// We use kv_put with composite key "myhash:element:X" which is the closest
 /// in terms of cost/complexity to Redis HSET.
-fn bench_hset(db: &BenchDb, n: usize, data: &Value, keygen: &mut KeyGen) -> BenchResult {
-    run_bench("HSET", "HSET (kv_put composite key)", n, |kg| {
+fn bench_hset(db: &BenchDb, config: &Config, data: &Value) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("HSET", "HSET (kv_put composite key)", config.load(), handles, |s, kg| {
         let key = kg.key("myhash:element");
-        db.db.kv_put(&key, data.clone()).unwrap();
-    }, keygen)
+        s.kv_put(&key, data.clone()).unwrap();
+    })
 }
 
 /// MSET (10 keys): "MSET key:__rand_int__ <data>" x10 (redis-benchmark.c line 2000)
 /// Redis MSET is a single atomic command. Without -r, all 10 keys are the same.
 /// Strata equivalent uses Session + TxnBegin + 10x KvPut + TxnCommit.
-fn bench_mset_10(db: &BenchDb, n: usize, data: &Value, keygen: &mut KeyGen) -> BenchResult {
-    run_bench("MSET (10 keys)", "MSET (10 keys) via txn", n, |kg| {
-        let mut session = db.db.session();
+fn bench_mset_10(db: &BenchDb, config: &Config, data: &Value) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("MSET (10 keys)", "MSET (10 keys) via txn", config.load(), handles, |s, kg| {
+        let mut session = s.session();
         session
             .execute(Command::TxnBegin {
                 branch: None,
@@ -332,77 +644,215 @@ fn bench_mset_10(db: &BenchDb, n: usize, data: &Value, keygen: &mut KeyGen) -> B
             session
                 .execute(Command::KvPut {
                     branch: None,
+                    space: None,
                     key,
                     value: data.clone(),
                 })
                 .unwrap();
         }
         session.execute(Command::TxnCommit).unwrap();
-    }, keygen)
+    })
 }
 
 /// XADD: "XADD mystream * myfield <data>" (redis-benchmark.c line 2015)
 /// Stream append with auto-generated ID. This is a close match.
-fn bench_xadd(db: &BenchDb, n: usize, data: &Value, keygen: &mut KeyGen) -> BenchResult {
+fn bench_xadd(db: &BenchDb, config: &Config, data: &Value) -> BenchResult {
     let mut payload_map = HashMap::new();
     payload_map.insert("myfield".to_string(), data.clone());
     let payload = Value::Object(payload_map);
 
-    run_bench("XADD", "XADD", n, |_kg| {
-        db.db.event_append("mystream", payload.clone()).unwrap();
-    }, keygen)
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("XADD", "XADD", config.load(), handles, |s, _kg| {
+        s.event_append("mystream", payload.clone()).unwrap();
+    })
 }
 
-/// LRANGE_100: "LRANGE mylist 0 99" (redis-benchmark.c line 1977)
+/// LRANGE_N: "LRANGE mylist 0 N-1" (redis-benchmark.c line 1977; N = 100,
+/// 300, 500, 600).
 /// Redis: indexed list access on a single pre-filled list, O(S+N).
-/// Strata: kv_list prefix scan returning 100 keys. NOT equivalent —
+/// Strata: kv_list prefix scan returning N keys. NOT equivalent —
 /// kv_list scans the key namespace, not an indexed list.
 /// Uses a fresh database to avoid scanning unrelated keys.
-fn bench_lrange_100(mode: DurabilityConfig, n: usize, data: &Value, keygen: &mut KeyGen) -> BenchResult {
+fn bench_lrange(mode: DurabilityConfig, config: &Config, data: &Value, len: u64) -> BenchResult {
     let bench_db = create_db(mode);
-    // Pre-populate 100 keys to scan (analogous to LPUSH filling the list)
-    for i in 0..100u64 {
+    // Pre-populate N keys to scan (analogous to LPUSH filling the list)
+    for i in 0..len {
         bench_db
             .db
             .kv_put(&format!("mylist:{:06}", i), data.clone())
             .unwrap();
     }
 
+    let handles = client_handles(&bench_db.db, config.clients);
     run_bench(
-        "LRANGE_100 (first 100 elements)",
-        "LRANGE_100 (kv_list prefix scan — NOT equivalent)",
-        n,
-        |_kg| {
-            let _ = bench_db.db.kv_list(Some("mylist:")).unwrap();
+        &format!("LRANGE_{} (first {} elements)", len, len),
+        &format!("LRANGE_{} (kv_list prefix scan — NOT equivalent)", len),
+        config.load(),
+        handles,
+        |s, _kg| {
+            let _ = s.kv_list(Some("mylist:")).unwrap();
         },
-        keygen,
     )
 }
 
+// --- Emulated list / set / sorted-set tests ---
+//
+// Strata has no list, set, or sorted-set type. These tests emulate the
+// redis-benchmark commands with sorted KV keys so the comparison table has a
+// row for them, but the redis_equiv label always names the emulation.
+
+/// A double-ended list stored as KV keys `mylist:<20-digit index>`, with the
+/// occupied index range `[head, tail)` tracked client-side. Starts in the
+/// middle of the index space so both ends can grow. Shared by LPUSH, RPUSH,
+/// LPOP, and RPOP within a durability mode, like redis-benchmark's single
+/// "mylist".
+struct KvDeque {
+    head: AtomicU64,
+    tail: AtomicU64,
+}
+
+impl KvDeque {
+    const MID: u64 = 1 << 62;
+
+    fn new() -> Self {
+        Self {
+            head: AtomicU64::new(Self::MID),
+            tail: AtomicU64::new(Self::MID),
+        }
+    }
+
+    fn key(idx: u64) -> String {
+        format!("mylist:{:020}", idx)
+    }
+}
+
+/// PING_MBULK: "*1\r\n$4\r\nPING\r\n" (redis-benchmark.c line 1883)
+/// The inline vs multi-bulk distinction is RESP parsing; embedded it is the
+/// same call as PING_INLINE.
+fn bench_ping_mbulk(db: &BenchDb, config: &Config) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("PING_MBULK", "PING_MBULK (same call as PING_INLINE)", config.load(), handles, |s, _kg| {
+        s.ping().unwrap();
+    })
+}
+
+/// LPUSH: "LPUSH mylist <data>" (redis-benchmark.c line 1907)
+fn bench_lpush(db: &BenchDb, config: &Config, data: &Value, list: &KvDeque) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("LPUSH", "LPUSH (kv_put at head of sorted-key deque)", config.load(), handles, |s, _kg| {
+        let idx = list.head.fetch_sub(1, Ordering::Relaxed) - 1;
+        s.kv_put(&KvDeque::key(idx), data.clone()).unwrap();
+    })
+}
+
+/// RPUSH: "RPUSH mylist <data>" (redis-benchmark.c line 1913)
+fn bench_rpush(db: &BenchDb, config: &Config, data: &Value, list: &KvDeque) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("RPUSH", "RPUSH (kv_put at tail of sorted-key deque)", config.load(), handles, |s, _kg| {
+        let idx = list.tail.fetch_add(1, Ordering::Relaxed);
+        s.kv_put(&KvDeque::key(idx), data.clone()).unwrap();
+    })
+}
+
+/// LPOP: "LPOP mylist" (redis-benchmark.c line 1919)
+/// Pops what LPUSH/RPUSH pushed; once the list is drained, pops are misses
+/// (Redis returns nil the same way).
+fn bench_lpop(db: &BenchDb, config: &Config, list: &KvDeque) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("LPOP", "LPOP (kv_get+kv_delete at head)", config.load(), handles, |s, _kg| {
+        let idx = list.head.fetch_add(1, Ordering::Relaxed);
+        let key = KvDeque::key(idx);
+        if s.kv_get(&key).unwrap().is_some() {
+            let _ = s.kv_delete(&key);
+        }
+    })
+}
+
+/// RPOP: "RPOP mylist" (redis-benchmark.c line 1925)
+fn bench_rpop(db: &BenchDb, config: &Config, list: &KvDeque) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("RPOP", "RPOP (kv_get+kv_delete at tail)", config.load(), handles, |s, _kg| {
+        let idx = list.tail.fetch_sub(1, Ordering::Relaxed) - 1;
+        let key = KvDeque::key(idx);
+        if s.kv_get(&key).unwrap().is_some() {
+            let _ = s.kv_delete(&key);
+        }
+    })
+}
+
+/// SADD: "SADD myset element:__rand_int__" (redis-benchmark.c line 1931)
+/// Set membership as a composite key with an empty value, like HSET.
+fn bench_sadd(db: &BenchDb, config: &Config) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("SADD", "SADD (kv_put composite key)", config.load(), handles, |s, kg| {
+        let key = kg.key("myset:element");
+        s.kv_put(&key, Value::Null).unwrap();
+    })
+}
+
+/// SPOP: "SPOP myset" (redis-benchmark.c line 1944)
+/// No O(1) random member: lists the set prefix and deletes the first member.
+fn bench_spop(db: &BenchDb, config: &Config) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("SPOP", "SPOP (kv_list+kv_delete — O(set size))", config.load(), handles, |s, _kg| {
+        let members = s.kv_list(Some("myset:")).unwrap();
+        if let Some(member) = members.first() {
+            let _ = s.kv_delete(member);
+        }
+    })
+}
+
+/// ZADD: "ZADD myzset <score> element:__rand_int__" (redis-benchmark.c line 1950)
+/// Score-ordered key `myzset:<12-digit score>:<member>`; lexicographic key
+/// order equals score order.
+fn bench_zadd(db: &BenchDb, config: &Config) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("ZADD", "ZADD (kv_put score-ordered key)", config.load(), handles, |s, kg| {
+        let score = kg.next_rand() % 1_000_000;
+        let member = kg.key("element");
+        s.kv_put(&format!("myzset:{:012}:{}", score, member), Value::Null)
+            .unwrap();
+    })
+}
+
+/// ZPOPMIN: "ZPOPMIN myzset" (redis-benchmark.c line 1957)
+/// Lists the sorted-set prefix, deletes the smallest key — O(set size).
+fn bench_zpopmin(db: &BenchDb, config: &Config) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("ZPOPMIN", "ZPOPMIN (kv_list+kv_delete min — O(set size))", config.load(), handles, |s, _kg| {
+        let members = s.kv_list(Some("myzset:")).unwrap();
+        if let Some(min) = members.iter().min() {
+            let _ = s.kv_delete(min);
+        }
+    })
+}
+
 // --- Strata-unique bonus tests ---
 
-fn bench_state_set(db: &BenchDb, n: usize, data: &Value, keygen: &mut KeyGen) -> BenchResult {
-    run_bench("STATE_SET", "(Strata unique)", n, |kg| {
+fn bench_state_set(db: &BenchDb, config: &Config, data: &Value) -> BenchResult {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("STATE_SET", "(Strata unique)", config.load(), handles, |s, kg| {
         let cell = kg.key("cell");
-        db.db.state_set(&cell, data.clone()).unwrap();
-    }, keygen)
+        s.state_set(&cell, data.clone()).unwrap();
+    })
 }
 
-fn bench_state_read(db: &BenchDb, n: usize, keygen: &mut KeyGen) -> BenchResult {
+fn bench_state_read(db: &BenchDb, config: &Config) -> BenchResult {
     // Pre-populate one cell so reads return data
     db.db
         .state_set("rcell:000000000000", Value::Int(42))
         .unwrap();
 
-    run_bench("STATE_READ", "(Strata unique)", n, |kg| {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("STATE_READ", "(Strata unique)", config.load(), handles, |s, kg| {
         let cell = kg.key("rcell");
-        let _ = db.db.state_get(&cell).unwrap();
-    }, keygen)
+        let _ = s.state_get(&cell).unwrap();
+    })
 }
 
-fn bench_event_read(db: &BenchDb, n: usize, keygen: &mut KeyGen) -> BenchResult {
+fn bench_event_read(db: &BenchDb, config: &Config) -> BenchResult {
     // Pre-populate events to read back (scale with n)
-    let event_count = (n as u64).min(10_000).max(1);
+    let event_count = (config.requests as u64).min(10_000).max(1);
     let payload = Value::Object(HashMap::from([(
         "data".to_string(),
         Value::Int(0),
@@ -411,35 +861,36 @@ fn bench_event_read(db: &BenchDb, n: usize, keygen: &mut KeyGen) -> BenchResult
         db.db.event_append("readstream", payload.clone()).unwrap();
     }
 
-    run_bench("EVENT_READ", "(Strata unique)", n, |kg| {
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("EVENT_READ", "(Strata unique)", config.load(), handles, |s, kg| {
         let seq = (kg.next_rand() % event_count) + 1;
-        let _ = db.db.event_get(seq).unwrap();
-    }, keygen)
+        let _ = s.event_get(seq).unwrap();
+    })
 }
 
-fn bench_kv_delete(db: &BenchDb, n: usize, data: &Value, keygen: &mut KeyGen) -> BenchResult {
-    // Pre-populate keys to delete (scale with n)
-    let keyspace = (n as u64).min(100_000).max(1);
-    for i in 0..keyspace {
+fn bench_kv_delete(db: &BenchDb, config: &Config, data: &Value) -> BenchResult {
+    // Pre-populate the keys that will be deleted: the whole -r keyspace, or
+    // the single fixed key without -r
+    for i in 0..config.keyspace.max(1) {
         db.db
             .kv_put(&format!("dkey:{:012}", i), data.clone())
             .unwrap();
     }
 
-    run_bench("KV_DELETE", "DEL (bonus)", n, |kg| {
-        if kg.keyspace == 0 {
-            let _ = db.db.kv_delete("dkey:000000000000");
-        } else {
-            let idx = kg.next_rand() % keyspace;
-            let key = format!("dkey:{:012}", idx);
-            let _ = db.db.kv_delete(&key);
-        }
-    }, keygen)
+    let handles = client_handles(&db.db, config.clients);
+    run_bench("KV_DELETE", "DEL (bonus)", config.load(), handles, |s, kg| {
+        let key = kg.key("dkey");
+        let _ = s.kv_delete(&key);
+    })
 }
 
-const SKIPPED_REDIS_TESTS: &[&str] = &[
-    "PING_MBULK", "LPUSH", "RPUSH", "LPOP", "RPOP", "SADD", "SPOP",
-    "LRANGE_300", "LRANGE_500", "LRANGE_600", "ZADD", "ZPOPMIN",
+/// LRANGE variants run by redis-benchmark's default suite.
+const LRANGE_LENGTHS: [u64; 4] = [100, 300, 500, 600];
+
+/// redis-benchmark tests that Strata can only emulate (see the emulation
+/// section above); listed after each mode so the table is read accordingly.
+const EMULATED_REDIS_TESTS: &[&str] = &[
+    "LPUSH", "RPUSH", "LPOP", "RPOP", "SADD", "SPOP", "ZADD", "ZPOPMIN", "LRANGE_*",
 ];
 
 // ---------------------------------------------------------------------------
@@ -451,62 +902,75 @@ struct Config {
     requests: usize,
     payload_size: usize,
     keyspace: u64,
+    seed: u64,
+    clients: usize,
+    pipeline: usize,
+    /// Per-test wall-clock budget; replaces the request count when set.
+    duration: Option<Duration>,
     durability: Vec<DurabilityConfig>,
     tests: Option<Vec<String>>,
     csv: bool,
     quiet: bool,
+    /// Redis server URL for the head-to-head run (`redis-client` feature).
+    redis_url: Option<String>,
+}
+
+impl Config {
+    fn load(&self) -> Load {
+        Load {
+            ops: self.requests,
+            keyspace: self.keyspace,
+            seed: self.seed,
+            duration: self.duration,
+        }
+    }
 }
 
 fn parse_args() -> Config {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args = harness::cli::Args::parse();
     let mut config = Config {
         requests: DEFAULT_REQUESTS,
         payload_size: DEFAULT_PAYLOAD_SIZE,
         keyspace: 0, // default: no randomization, same key every time (matches redis-benchmark)
+        seed: KEY_SEED,
+        clients: 1,
+        pipeline: 1,
+        duration: None,
         durability: DurabilityConfig::ALL.to_vec(),
         tests: None,
         csv: false,
         quiet: false,
+        redis_url: None,
     };
 
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
-            "-n" => {
-                i += 1;
-                config.requests = args[i].parse().unwrap_or(DEFAULT_REQUESTS);
-            }
-            "-d" => {
-                i += 1;
-                config.payload_size = args[i].parse().unwrap_or(DEFAULT_PAYLOAD_SIZE);
-            }
-            "-r" => {
-                i += 1;
-                config.keyspace = args[i].parse().unwrap_or(0);
-            }
-            "--durability" => {
-                i += 1;
-                config.durability = match args[i].as_str() {
-                    "cache" => vec![DurabilityConfig::Cache],
-                    "standard" => vec![DurabilityConfig::Standard],
-                    "always" => vec![DurabilityConfig::Always],
-                    _ => DurabilityConfig::ALL.to_vec(),
-                };
-            }
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "-n" => config.requests = args.parse_value(),
+            "-d" => config.payload_size = args.parse_value(),
+            "-r" => config.keyspace = args.parse_value(),
+            "-c" => config.clients = args.parse_value::<usize>().max(1),
+            "-P" => config.pipeline = args.parse_value::<usize>().max(1),
             "-t" => {
-                i += 1;
-                let names: Vec<String> = args[i]
+                let v = args.value();
+                let names: Vec<String> = v
                     .split(',')
                     .map(|s| s.trim().to_uppercase())
                     .collect();
                 config.tests = Some(names);
             }
-            "--csv" => config.csv = true,
-            "-q" => config.quiet = true,
-            _ => {}
+            "--redis" => {
+                // Optional URL; defaults to a local server on the standard port
+                let url = args.value_if(|v| v.starts_with("redis://"));
+                config.redis_url = Some(url.unwrap_or_else(|| "redis://127.0.0.1:6379".to_string()));
+            }
+            _ => args.unknown_flag(),
         }
-        i += 1;
     }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.duration = args.duration;
+    config.seed = args.seed_or(KEY_SEED);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
 
     config
 }
@@ -522,6 +986,29 @@ fn test_is_selected(name: &str, filter: &Option<Vec<String>>) -> bool {
     }
 }
 
+/// Run one test if `--tests` selects it. If it panics, the panic is
+/// recorded against the test and the remaining tests still run.
+fn run_test(
+    recorder: &mut ResultRecorder,
+    test: &str,
+    mode: &DurabilityConfig,
+    config: &Config,
+    bench: impl FnOnce() -> BenchResult,
+) {
+    if !test_is_selected(test, &config.tests) {
+        return;
+    }
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("engine".into(), serde_json::json!("strata"));
+    let name = format!("redis-compare/{}/{}", test, mode.label());
+    recorder.guard(&name, params, |recorder| {
+        let result = bench();
+        print_result(&result, config);
+        record_redis_result(recorder, &result, mode, config);
+    });
+}
+
 // ---------------------------------------------------------------------------
 // Main
 // ---------------------------------------------------------------------------
@@ -532,7 +1019,7 @@ fn main() {
 
     // Generate random payload data matching redis-benchmark's genBenchmarkRandomData
     let data_bytes = gen_benchmark_random_data(config.payload_size);
-    let data = Value::Bytes(data_bytes);
+    let data = Value::Bytes(data_bytes.clone());
 
     if !config.csv {
         eprintln!("=== StrataDB Redis-Comparison Benchmark ===");
@@ -549,8 +1036,23 @@ fn main() {
             eprintln!("  (use -r <keyspace> to enable random keys, e.g. -r 100000)");
         } else {
             eprintln!(
-                "Parameters: {} requests, {} bytes payload, keyspace {} (random keys)",
-                config.requests, config.payload_size, config.keyspace
+                "Parameters: {} requests, {} bytes payload, keyspace {} (random keys, seed {:#x})",
+                config.requests, config.payload_size, config.keyspace, config.seed
+            );
+        }
+        if let Some(d) = config.duration {
+            eprintln!(
+                "Duration: {:.1}s per test (request count ignored)",
+                d.as_secs_f64()
+            );
+        }
+        if config.clients > 1 {
+            eprintln!("Clients: {} (parallel threads, one Strata handle each)", config.clients);
+        }
+        if config.pipeline > 1 {
+            eprintln!(
+                "Pipeline: {} requests per transaction for SET/GET/INCR (other tests unpipelined)",
+                config.pipeline
             );
         }
         eprintln!();
@@ -566,7 +1068,7 @@ fn main() {
         if !config.csv {
             let redis_equiv = match mode {
                 DurabilityConfig::Cache => "Redis no persistence (save \"\", appendonly no)",
-                DurabilityConfig::Standard => "Redis appendfsync everysec (default)",
+                DurabilityConfig::Standard | DurabilityConfig::StandardSync { .. } => "Redis appendfsync everysec (default)",
                 DurabilityConfig::Always => "Redis appendfsync always",
             };
             eprintln!(
@@ -583,106 +1085,183 @@ fn main() {
 
         // --- Redis-equivalent tests (in redis-benchmark's exact order) ---
 
-        if test_is_selected("PING", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_ping(&bench_db, config.requests, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "PING", mode, &config, || bench_ping(&bench_db, &config));
 
-        if test_is_selected("SET", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_set(&bench_db, config.requests, &data, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "PING_MBULK", mode, &config, || {
+            bench_ping_mbulk(&bench_db, &config)
+        });
 
-        if test_is_selected("GET", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_get(&bench_db, config.requests, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "SET", mode, &config, || {
+            if config.pipeline > 1 {
+                bench_set_pipelined(&bench_db, &config, &data)
+            } else {
+                bench_set(&bench_db, &config, &data)
+            }
+        });
 
-        if test_is_selected("INCR", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_incr(&bench_db, config.requests, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "GET", mode, &config, || {
+            if config.pipeline > 1 {
+                bench_get_pipelined(&bench_db, &config)
+            } else {
+                bench_get(&bench_db, &config)
+            }
+        });
 
-        if test_is_selected("HSET", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_hset(&bench_db, config.requests, &data, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "INCR", mode, &config, || {
+            if config.pipeline > 1 {
+                bench_incr_pipelined(&bench_db, &config)
+            } else {
+                bench_incr(&bench_db, &config)
+            }
+        });
 
-        if test_is_selected("MSET", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_mset_10(&bench_db, config.requests, &data, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        let list = KvDeque::new();
 
-        if test_is_selected("XADD", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_xadd(&bench_db, config.requests, &data, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "LPUSH", mode, &config, || {
+            bench_lpush(&bench_db, &config, &data, &list)
+        });
+
+        run_test(&mut recorder, "RPUSH", mode, &config, || {
+            bench_rpush(&bench_db, &config, &data, &list)
+        });
+
+        run_test(&mut recorder, "LPOP", mode, &config, || bench_lpop(&bench_db, &config, &list));
+
+        run_test(&mut recorder, "RPOP", mode, &config, || bench_rpop(&bench_db, &config, &list));
+
+        run_test(&mut recorder, "SADD", mode, &config, || bench_sadd(&bench_db, &config));
+
+        run_test(&mut recorder, "HSET", mode, &config, || bench_hset(&bench_db, &config, &data));
 
-        if test_is_selected("LRANGE", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_lrange_100(*mode, config.requests, &data, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
+        run_test(&mut recorder, "SPOP", mode, &config, || bench_spop(&bench_db, &config));
+
+        run_test(&mut recorder, "ZADD", mode, &config, || bench_zadd(&bench_db, &config));
+
+        run_test(&mut recorder, "ZPOPMIN", mode, &config, || bench_zpopmin(&bench_db, &config));
+
+        run_test(&mut recorder, "MSET", mode, &config, || bench_mset_10(&bench_db, &config, &data));
+
+        run_test(&mut recorder, "XADD", mode, &config, || bench_xadd(&bench_db, &config, &data));
+
+        for len in LRANGE_LENGTHS {
+            run_test(&mut recorder, &format!("LRANGE_{}", len), mode, &config, || {
+                bench_lrange(*mode, &config, &data, len)
+            });
         }
 
         // --- Strata-unique bonus tests ---
 
-        if test_is_selected("STATE_SET", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_state_set(&bench_db, config.requests, &data, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "STATE_SET", mode, &config, || {
+            bench_state_set(&bench_db, &config, &data)
+        });
 
-        if test_is_selected("STATE_READ", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_state_read(&bench_db, config.requests, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "STATE_READ", mode, &config, || {
+            bench_state_read(&bench_db, &config)
+        });
 
-        if test_is_selected("EVENT_READ", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_event_read(&bench_db, config.requests, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "EVENT_READ", mode, &config, || {
+            bench_event_read(&bench_db, &config)
+        });
 
-        if test_is_selected("KV_DELETE", &config.tests) {
-            let mut kg = KeyGen::new(config.keyspace);
-            let result = bench_kv_delete(&bench_db, config.requests, &data, &mut kg);
-            print_result(&result, &config);
-            record_redis_result(&mut recorder, &result, mode);
-        }
+        run_test(&mut recorder, "KV_DELETE", mode, &config, || {
+            bench_kv_delete(&bench_db, &config, &data)
+        });
 
-        // List skipped Redis tests
+        // List emulated Redis tests
         if !config.csv && !config.quiet {
-            eprintln!("--- Skipped (no Strata equivalent) ---");
-            for name in SKIPPED_REDIS_TESTS {
-                eprintln!("  {}: N/A", name);
-            }
+            eprintln!("--- Emulated (no native Strata type; see redis equivalent labels) ---");
+            eprintln!("  {}", EMULATED_REDIS_TESTS.join(", "));
             eprintln!();
         }
     }
 
+    if let Some(url) = &config.redis_url {
+        run_redis_server(url, &config, &data_bytes, &mut recorder);
+    }
+
     if !config.csv {
         eprintln!("=== Benchmark complete ===");
     }
-    let _ = recorder.save();
+    recorder.save_or_exit();
+}
+
+/// Run the redis-benchmark tests against a real Redis server and emit its rows
+/// into the same output stream and result file as the Strata rows.
+#[cfg(feature = "redis-client")]
+fn run_redis_server(url: &str, config: &Config, data: &[u8], recorder: &mut ResultRecorder) {
+    let mut con = match redis_client::connect(url) {
+        Ok(con) => con,
+        Err(e) => {
+            eprintln!("WARNING: skipping Redis server run: {}", e);
+            let mut params = HashMap::new();
+            params.insert("engine".into(), serde_json::json!("redis"));
+            params.insert("url".into(), serde_json::json!(url));
+            recorder.record_failure(
+                "redis-compare/connect/redis-server",
+                params,
+                ResultStatus::Error { message: e },
+            );
+            return;
+        }
+    };
+    let persistence = redis_client::persistence_summary(&mut con);
+
+    if !config.csv {
+        eprintln!("--- engine: redis-server at {} ({}) ---", url, persistence);
+        eprintln!();
+    }
+
+    redis_client::run_all(
+        url,
+        &mut con,
+        config.clients,
+        config.load(),
+        config.pipeline,
+        data,
+        |test, bench| run_redis_test(recorder, test, &persistence, config, bench),
+    );
+}
+
+/// [`run_test`] for the Redis server side. A command the server rejects, or
+/// a connection it refuses or drops, fails the test like a panic does.
+#[cfg(feature = "redis-client")]
+fn run_redis_test(
+    recorder: &mut ResultRecorder,
+    test: &str,
+    persistence: &str,
+    config: &Config,
+    bench: &mut dyn FnMut() -> Result<BenchResult, String>,
+) {
+    if !test_is_selected(test, &config.tests) {
+        return;
+    }
+    let mut params = HashMap::new();
+    params.insert("engine".into(), serde_json::json!("redis"));
+    let name = format!("redis-compare/{}/redis-server", test);
+    recorder.guard(&name, params.clone(), |recorder| match bench() {
+        Ok(mut result) => {
+            record_redis_server_result(recorder, &result, persistence, config);
+            result.name = format!("{} [redis]", result.name);
+            print_result(&result, config);
+        }
+        Err(message) => recorder.record_failure(&name, params, ResultStatus::Error { message }),
+    });
+}
+
+#[cfg(not(feature = "redis-client"))]
+fn run_redis_server(url: &str, _config: &Config, _data: &[u8], recorder: &mut ResultRecorder) {
+    eprintln!("ERROR: --redis requires the redis-client feature:");
+    eprintln!("  cargo bench --bench redis_compare --features redis-client -- --redis");
+    let mut params = HashMap::new();
+    params.insert("engine".into(), serde_json::json!("redis"));
+    params.insert("url".into(), serde_json::json!(url));
+    recorder.record_failure(
+        "redis-compare/connect/redis-server",
+        params,
+        ResultStatus::Error {
+            message: "built without the redis-client feature".to_string(),
+        },
+    );
 }
 
 fn print_result(result: &BenchResult, config: &Config) {
@@ -0,0 +1,508 @@
+//! Chat-Memory Scenario Benchmark for StrataDB
+//!
+//! Models the memory layer of an LLM chat service. Every turn of every
+//! session does:
+//!
+//! - `event_append` of the user message and the assistant reply (history)
+//! - `state_set` of the running conversation summary
+//! - `kv_put` of an artifact produced during the turn
+//!
+//! and every `--replay-every` turns the session's full history is replayed
+//! with `event_get_by_type`, as when a context window is rebuilt.
+//!
+//! Sessions are isolated either by key prefix (one database namespace,
+//! `chat:<sid>` event types and `<kind>:<sid>:...` keys) or by branch (one
+//! branch per session, switched to before each turn). The same workload runs
+//! at increasing session counts and reports the per-turn latency
+//! distribution at each, so the effect of many live sessions is visible.
+//!
+//! Run:     `cargo bench --bench chat_memory`
+//! Quick:   `cargo bench --bench chat_memory -- --sessions 100,1000 --turns 5 -q`
+//! Branch:  `cargo bench --bench chat_memory -- --layout branch`
+//! CSV:     `cargo bench --bench chat_memory -- --csv`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, DurabilityConfig};
+use std::collections::HashMap;
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{Strata, Value};
+
+// ---------------------------------------------------------------------------
+// Defaults
+// ---------------------------------------------------------------------------
+
+const DEFAULT_SESSIONS: &[usize] = &[100, 1_000, 5_000];
+const DEFAULT_TURNS: usize = 10;
+const DEFAULT_THREADS: usize = 4;
+const DEFAULT_REPLAY_EVERY: usize = 5;
+const DEFAULT_MESSAGE_BYTES: usize = 256;
+const DEFAULT_SUMMARY_BYTES: usize = 512;
+const DEFAULT_ARTIFACT_BYTES: usize = 1_024;
+
+// ---------------------------------------------------------------------------
+// Session layout
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// All sessions share one branch; names carry the session id.
+    Prefix,
+    /// One branch per session; names are the same in every branch.
+    Branch,
+}
+
+impl Layout {
+    fn label(self) -> &'static str {
+        match self {
+            Layout::Prefix => "prefix",
+            Layout::Branch => "branch",
+        }
+    }
+}
+
+fn branch_name(sid: usize) -> String {
+    format!("session_{:06}", sid)
+}
+
+/// Event type, summary cell, and artifact key prefix for a session.
+fn session_names(layout: Layout, sid: usize) -> (String, String, String) {
+    match layout {
+        Layout::Prefix => (
+            format!("chat:{:06}", sid),
+            format!("summary:{:06}", sid),
+            format!("artifact:{:06}:", sid),
+        ),
+        Layout::Branch => ("chat".into(), "summary".into(), "artifact:".into()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Synthetic conversation
+// ---------------------------------------------------------------------------
+
+fn text(seed: &str, bytes: usize) -> String {
+    seed.chars().cycle().take(bytes).collect()
+}
+
+fn message(role: &str, sid: usize, turn: usize, bytes: usize) -> Value {
+    let body = format!("Session {} turn {} {} message. ", sid, turn, role);
+    Value::Object(HashMap::from([
+        ("role".to_string(), Value::String(role.to_string())),
+        ("turn".to_string(), Value::Int(turn as i64)),
+        ("text".to_string(), Value::String(text(&body, bytes))),
+    ]))
+}
+
+fn summary(sid: usize, turn: usize, bytes: usize) -> Value {
+    let body = format!("Summary of session {} through turn {}. ", sid, turn);
+    Value::String(text(&body, bytes))
+}
+
+fn artifact(turn: usize, bytes: usize) -> Value {
+    Value::Bytes(vec![(turn % 251) as u8; bytes])
+}
+
+// ---------------------------------------------------------------------------
+// One turn
+// ---------------------------------------------------------------------------
+
+/// Run turn `turn` of session `sid`. Returns (turn latency, replay latency
+/// if this turn replayed history, events seen by the replay).
+fn run_turn(
+    db: &mut Strata,
+    config: &Config,
+    layout: Layout,
+    sid: usize,
+    turn: usize,
+) -> (Duration, Option<Duration>, usize) {
+    let (event_type, summary_cell, artifact_prefix) = session_names(layout, sid);
+
+    let start = Instant::now();
+    if layout == Layout::Branch {
+        db.set_branch(&branch_name(sid)).unwrap();
+    }
+    db.event_append(&event_type, message("user", sid, turn, config.message_bytes))
+        .unwrap();
+    db.event_append(&event_type, message("assistant", sid, turn, config.message_bytes))
+        .unwrap();
+    db.state_set(&summary_cell, summary(sid, turn, config.summary_bytes))
+        .unwrap();
+    db.kv_put(
+        &format!("{}{:06}", artifact_prefix, turn),
+        artifact(turn, config.artifact_bytes),
+    )
+    .unwrap();
+
+    let mut replay = None;
+    let mut replayed = 0;
+    if config.replay_every > 0 && (turn + 1).is_multiple_of(config.replay_every) {
+        let replay_start = Instant::now();
+        replayed = db.event_get_by_type(&event_type).unwrap().len();
+        replay = Some(replay_start.elapsed());
+    }
+
+    (start.elapsed(), replay, replayed)
+}
+
+// ---------------------------------------------------------------------------
+// Statistics
+// ---------------------------------------------------------------------------
+
+struct Stats {
+    count: usize,
+    per_sec: f64,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+fn stats(mut latencies: Vec<Duration>, wall: Duration) -> Stats {
+    latencies.sort_unstable();
+    let len = latencies.len().max(1);
+    let at = |pct: usize| {
+        latencies
+            .get((len * pct / 100).min(len - 1))
+            .copied()
+            .unwrap_or_default()
+    };
+    let sum: Duration = latencies.iter().sum();
+    Stats {
+        count: latencies.len(),
+        per_sec: latencies.len() as f64 / wall.as_secs_f64(),
+        avg: sum / len as u32,
+        p50: at(50),
+        p95: at(95),
+        p99: at(99),
+        min: latencies.first().copied().unwrap_or_default(),
+        max: latencies.last().copied().unwrap_or_default(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Run at one session count
+// ---------------------------------------------------------------------------
+
+struct SessionRun {
+    turns: Stats,
+    replays: Stats,
+    /// Average history events returned per replay.
+    avg_replayed: f64,
+}
+
+/// Drive `sessions` sessions for `config.turns` turns each. Sessions are
+/// split across worker threads by `sid % threads`; each worker interleaves
+/// its sessions turn by turn so they are all live at once.
+fn run_sessions(db: &Strata, config: &Config, layout: Layout, sessions: usize) -> SessionRun {
+    if layout == Layout::Branch {
+        for sid in 0..sessions {
+            db.create_branch(&branch_name(sid)).unwrap();
+        }
+    }
+
+    let threads = config.threads.min(sessions).max(1);
+    let barrier = Barrier::new(threads + 1);
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|t| {
+                let mut handle = db.new_handle().expect("failed to create worker handle");
+                let barrier = &barrier;
+                scope.spawn(move || {
+                    let owned: Vec<usize> = (t..sessions).step_by(threads).collect();
+                    let mut turns = Vec::with_capacity(owned.len() * config.turns);
+                    let mut replays = Vec::new();
+                    let mut replayed = 0usize;
+                    barrier.wait();
+                    for turn in 0..config.turns {
+                        for &sid in &owned {
+                            let (lat, replay, seen) =
+                                run_turn(&mut handle, config, layout, sid, turn);
+                            turns.push(lat);
+                            if let Some(r) = replay {
+                                replays.push(r);
+                                replayed += seen;
+                            }
+                        }
+                    }
+                    (turns, replays, replayed)
+                })
+            })
+            .collect();
+
+        barrier.wait();
+        let start = Instant::now();
+        let mut turns = Vec::new();
+        let mut replays = Vec::new();
+        let mut replayed = 0usize;
+        for w in workers {
+            let (t, r, n) = w.join().expect("session worker panicked");
+            turns.extend(t);
+            replays.extend(r);
+            replayed += n;
+        }
+        let wall = start.elapsed();
+
+        let replay_count = replays.len().max(1);
+        SessionRun {
+            turns: stats(turns, wall),
+            replays: stats(replays, wall),
+            avg_replayed: replayed as f64 / replay_count as f64,
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+fn duration_ms(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000_000.0
+}
+
+fn print_header() {
+    eprintln!(
+        "  {:>9} {:>12} {:>10} {:>10} {:>10} {:>10}   {:>12} {:>12}",
+        "sessions", "turns/s", "p50 ms", "p95 ms", "p99 ms", "max ms", "replay p50", "replay p99"
+    );
+    eprintln!("  {}", "-".repeat(96));
+}
+
+fn print_row(sessions: usize, run: &SessionRun) {
+    eprintln!(
+        "  {:>9} {:>12.1} {:>10.3} {:>10.3} {:>10.3} {:>10.3}   {:>10.3}ms {:>10.3}ms",
+        sessions,
+        run.turns.per_sec,
+        duration_ms(run.turns.p50),
+        duration_ms(run.turns.p95),
+        duration_ms(run.turns.p99),
+        duration_ms(run.turns.max),
+        duration_ms(run.replays.p50),
+        duration_ms(run.replays.p99),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"layout\",\"durability\",\"sessions\",\"turns\",\"turns_per_sec\",\"p50_ms\",\"p95_ms\",\"p99_ms\",\"max_ms\",\"replay_p50_ms\",\"replay_p99_ms\"");
+}
+
+fn print_csv_row(layout: Layout, mode: DurabilityConfig, sessions: usize, run: &SessionRun) {
+    println!(
+        "\"{}\",\"{}\",{},{},{:.2},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3}",
+        layout.label(),
+        mode.label(),
+        sessions,
+        run.turns.count,
+        run.turns.per_sec,
+        duration_ms(run.turns.p50),
+        duration_ms(run.turns.p95),
+        duration_ms(run.turns.p99),
+        duration_ms(run.turns.max),
+        duration_ms(run.replays.p50),
+        duration_ms(run.replays.p99),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+fn result_name(layout: Layout, sessions: usize, mode: DurabilityConfig) -> String {
+    format!("scenario/chat_memory/{}/{}s/{}", layout.label(), sessions, mode.label())
+}
+
+fn record_run(
+    recorder: &mut ResultRecorder,
+    layout: Layout,
+    mode: DurabilityConfig,
+    config: &Config,
+    sessions: usize,
+    run: &SessionRun,
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("layout".into(), serde_json::json!(layout.label()));
+    params.insert("sessions".into(), serde_json::json!(sessions));
+    params.insert("turns_per_session".into(), serde_json::json!(config.turns));
+    params.insert("replay_every".into(), serde_json::json!(config.replay_every));
+    params.insert("message_bytes".into(), serde_json::json!(config.message_bytes));
+    params.insert("summary_bytes".into(), serde_json::json!(config.summary_bytes));
+    params.insert("artifact_bytes".into(), serde_json::json!(config.artifact_bytes));
+    params.insert("replays".into(), serde_json::json!(run.replays.count));
+    params.insert("replay_p50_ns".into(), serde_json::json!(run.replays.p50.as_nanos() as u64));
+    params.insert("replay_p99_ns".into(), serde_json::json!(run.replays.p99.as_nanos() as u64));
+    params.insert("avg_events_replayed".into(), serde_json::json!(run.avg_replayed));
+
+    let s = &run.turns;
+    recorder.record(BenchmarkResult {
+        benchmark: result_name(layout, sessions, mode),
+        category: "scenario".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(s.per_sec),
+            p50_ns: Some(s.p50.as_nanos() as u64),
+            p95_ns: Some(s.p95.as_nanos() as u64),
+            p99_ns: Some(s.p99.as_nanos() as u64),
+            min_ns: Some(s.min.as_nanos() as u64),
+            max_ns: Some(s.max.as_nanos() as u64),
+            avg_ns: Some(s.avg.as_nanos() as u64),
+            samples: Some(s.count as u64),
+            threads: Some(config.threads.min(sessions).max(1)),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    sessions: Vec<usize>,
+    turns: usize,
+    threads: usize,
+    replay_every: usize,
+    message_bytes: usize,
+    summary_bytes: usize,
+    artifact_bytes: usize,
+    layouts: Vec<Layout>,
+    durability: Vec<DurabilityConfig>,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        sessions: DEFAULT_SESSIONS.to_vec(),
+        turns: DEFAULT_TURNS,
+        threads: DEFAULT_THREADS,
+        replay_every: DEFAULT_REPLAY_EVERY,
+        message_bytes: DEFAULT_MESSAGE_BYTES,
+        summary_bytes: DEFAULT_SUMMARY_BYTES,
+        artifact_bytes: DEFAULT_ARTIFACT_BYTES,
+        layouts: vec![Layout::Prefix],
+        durability: vec![DurabilityConfig::Standard],
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--sessions" => config.sessions = args.list_checked("a list of numbers above 0", |&n| n > 0),
+            "--turns" => config.turns = args.parse_value::<usize>().max(1),
+            "--replay-every" => config.replay_every = args.parse_value(),
+            "--message-bytes" => config.message_bytes = args.parse_value(),
+            "--summary-bytes" => config.summary_bytes = args.parse_value(),
+            "--artifact-bytes" => config.artifact_bytes = args.parse_value(),
+            "--layout" => {
+                config.layouts = match args.value().as_str() {
+                    "prefix" => vec![Layout::Prefix],
+                    "branch" => vec![Layout::Branch],
+                    "all" => vec![Layout::Prefix, Layout::Branch],
+                    other => harness::cli::usage_error(&format!(
+                        "--layout expects prefix, branch or all; got {}",
+                        other
+                    )),
+                };
+            }
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.threads = args.thread_count_or(DEFAULT_THREADS);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let verbose = !config.csv && !config.quiet;
+    if verbose {
+        eprintln!("=== StrataDB Chat-Memory Scenario Benchmark ===");
+        eprintln!(
+            "Sessions: {:?} | {} turns each | {} threads | replay every {} turns",
+            config.sessions, config.turns, config.threads, config.replay_every,
+        );
+        eprintln!(
+            "Per turn: 2 x event_append ({}B) + state_set ({}B) + kv_put ({}B)",
+            config.message_bytes, config.summary_bytes, config.artifact_bytes,
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("scenario");
+
+    for &mode in &config.durability {
+        for &layout in &config.layouts {
+            if verbose {
+                eprintln!("--- durability: {} | layout: {} ---", mode.label(), layout.label());
+                print_header();
+            }
+
+            for &sessions in &config.sessions {
+                let mut guard_params = HashMap::new();
+                guard_params.insert("durability".into(), serde_json::json!(mode.label()));
+                guard_params.insert("layout".into(), serde_json::json!(layout.label()));
+                guard_params.insert("sessions".into(), serde_json::json!(sessions));
+                recorder.guard(&result_name(layout, sessions, mode), guard_params, |recorder| {
+                    // Fresh database per point so earlier session counts don't
+                    // inflate history for later ones
+                    let bench_db = create_db(mode);
+                    let run = run_sessions(&bench_db.db, &config, layout, sessions);
+
+                    if config.csv {
+                        print_csv_row(layout, mode, sessions, &run);
+                    } else if config.quiet {
+                        eprintln!(
+                            "chat_memory/{}/{}s/{}: {:.0} turns/s, p50={:.3}ms p99={:.3}ms, replay p99={:.3}ms",
+                            layout.label(),
+                            sessions,
+                            mode.label(),
+                            run.turns.per_sec,
+                            duration_ms(run.turns.p50),
+                            duration_ms(run.turns.p99),
+                            duration_ms(run.replays.p99),
+                        );
+                    } else {
+                        print_row(sessions, &run);
+                    }
+
+                    record_run(recorder, layout, mode, &config, sessions, &run);
+                });
+            }
+
+            if verbose {
+                eprintln!();
+            }
+        }
+    }
+
+    if verbose {
+        eprintln!("=== Chat-memory scenario complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,517 @@
+//! End-to-End RAG Scenario Benchmark for StrataDB
+//!
+//! Models the loop a retrieval-augmented agent runs: documents are stored
+//! with `json_set` and their (synthetic) embeddings with `vector_upsert`;
+//! each query is a `vector_search` followed by `json_get` of the top-k hits.
+//!
+//! Three phases, all against one database:
+//!
+//! 1. **Ingest**: load the corpus; reports docs/sec for json_set + upsert.
+//! 2. **Query**: query loop alone; end-to-end latency with a search/fetch
+//!    breakdown.
+//! 3. **Query under ingest**: query threads run while one writer keeps
+//!    ingesting new documents; reports query latency and the ingest rate the
+//!    writer sustained alongside it.
+//!
+//! Run:    `cargo bench --bench rag`
+//! Quick:  `cargo bench --bench rag -- --docs 2000 --queries 200 --duration 2 -q`
+//! Shape:  `cargo bench --bench rag -- --dim 768 --k 10 --doc-bytes 2048`
+//! CSV:    `cargo bench --bench rag -- --csv`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, DurabilityConfig};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::{DistanceMetric, Strata, Value};
+
+// ---------------------------------------------------------------------------
+// Defaults
+// ---------------------------------------------------------------------------
+
+const DEFAULT_DOCS: usize = 10_000;
+const DEFAULT_QUERIES: usize = 1_000;
+const DEFAULT_K: usize = 5;
+/// Common sentence-embedding width (e.g. MiniLM).
+const DEFAULT_DIM: usize = 384;
+const DEFAULT_DOC_BYTES: usize = 1_024;
+const DEFAULT_DURATION_SECS: u64 = 5;
+const DEFAULT_QUERY_THREADS: usize = 2;
+
+const COLLECTION: &str = "rag_chunks";
+
+// ---------------------------------------------------------------------------
+// Synthetic corpus
+// ---------------------------------------------------------------------------
+
+#[inline]
+fn lcg(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+/// Deterministic unit-length embedding for document or query `i`.
+fn embedding(i: u64, dim: usize) -> Vec<f32> {
+    let mut state = i.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ 0x5EED;
+    let mut v: Vec<f32> = (0..dim)
+        .map(|_| (lcg(&mut state) % 20_000) as f32 / 10_000.0 - 1.0)
+        .collect();
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::MIN_POSITIVE);
+    for x in &mut v {
+        *x /= norm;
+    }
+    v
+}
+
+/// A document chunk as a RAG pipeline stores it: text plus provenance.
+fn rag_document(i: u64, text_bytes: usize) -> Value {
+    let sentence = format!("Chunk {} of the synthetic corpus describes topic {}. ", i, i % 97);
+    let text: String = sentence.chars().cycle().take(text_bytes).collect();
+    Value::Object(HashMap::from([
+        ("text".to_string(), Value::String(text)),
+        ("source".to_string(), Value::String(format!("doc_{}.md", i / 8))),
+        ("chunk".to_string(), Value::Int((i % 8) as i64)),
+        ("topic".to_string(), Value::Int((i % 97) as i64)),
+    ]))
+}
+
+fn doc_key(i: u64) -> String {
+    format!("chunk:{:010}", i)
+}
+
+fn ingest_one(db: &Strata, i: u64, config: &Config) {
+    let key = doc_key(i);
+    db.json_set(&key, "$", rag_document(i, config.doc_bytes))
+        .unwrap();
+    db.vector_upsert(COLLECTION, &key, embedding(i, config.dim), None)
+        .unwrap();
+}
+
+/// One RAG query. Returns (search time, fetch time, documents fetched).
+fn query_one(db: &Strata, q: u64, config: &Config) -> (Duration, Duration, usize) {
+    // Query ids live above the corpus range so they never equal a document
+    let query = embedding(u64::MAX / 2 + q, config.dim);
+
+    let search_start = Instant::now();
+    let hits = db.vector_search(COLLECTION, query, config.k as u64).unwrap();
+    let search = search_start.elapsed();
+
+    let fetch_start = Instant::now();
+    let mut fetched = 0;
+    for hit in &hits {
+        if db.json_get(&hit.key, "$").unwrap().is_some() {
+            fetched += 1;
+        }
+    }
+    (search, fetch_start.elapsed(), fetched)
+}
+
+// ---------------------------------------------------------------------------
+// Statistics
+// ---------------------------------------------------------------------------
+
+struct Stats {
+    count: usize,
+    per_sec: f64,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+fn stats(mut latencies: Vec<Duration>, wall: Duration) -> Stats {
+    latencies.sort_unstable();
+    let len = latencies.len().max(1);
+    let at = |pct: usize| {
+        latencies
+            .get((len * pct / 100).min(len - 1))
+            .copied()
+            .unwrap_or_default()
+    };
+    let sum: Duration = latencies.iter().sum();
+    Stats {
+        count: latencies.len(),
+        per_sec: latencies.len() as f64 / wall.as_secs_f64(),
+        avg: sum / len as u32,
+        p50: at(50),
+        p95: at(95),
+        p99: at(99),
+        min: latencies.first().copied().unwrap_or_default(),
+        max: latencies.last().copied().unwrap_or_default(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Phases
+// ---------------------------------------------------------------------------
+
+fn run_ingest(db: &Strata, config: &Config) -> Stats {
+    let mut latencies = Vec::with_capacity(config.docs);
+    let start = Instant::now();
+    for i in 0..config.docs as u64 {
+        let op = Instant::now();
+        ingest_one(db, i, config);
+        latencies.push(op.elapsed());
+    }
+    stats(latencies, start.elapsed())
+}
+
+struct QueryPhase {
+    end_to_end: Stats,
+    search: Stats,
+    fetch: Stats,
+    /// Average documents fetched per query (≤ k).
+    avg_fetched: f64,
+}
+
+fn run_queries(db: &Strata, config: &Config) -> QueryPhase {
+    let mut total = Vec::with_capacity(config.queries);
+    let mut search = Vec::with_capacity(config.queries);
+    let mut fetch = Vec::with_capacity(config.queries);
+    let mut fetched = 0usize;
+
+    let start = Instant::now();
+    for q in 0..config.queries as u64 {
+        let (s, f, n) = query_one(db, q, config);
+        total.push(s + f);
+        search.push(s);
+        fetch.push(f);
+        fetched += n;
+    }
+    let wall = start.elapsed();
+
+    QueryPhase {
+        end_to_end: stats(total, wall),
+        search: stats(search, wall),
+        fetch: stats(fetch, wall),
+        avg_fetched: fetched as f64 / config.queries.max(1) as f64,
+    }
+}
+
+struct MixedPhase {
+    queries: Stats,
+    ingest: Stats,
+}
+
+/// `query_threads` readers and one writer run for `duration`. The writer
+/// continues the corpus from `config.docs` upward.
+fn run_query_under_ingest(db: &Strata, config: &Config) -> MixedPhase {
+    let stop = AtomicBool::new(false);
+    let next_doc = AtomicU64::new(config.docs as u64);
+    let barrier = Barrier::new(config.query_threads + 2);
+
+    std::thread::scope(|scope| {
+        let writer = {
+            let handle = db.new_handle().expect("failed to create writer handle");
+            let (stop, next_doc, barrier) = (&stop, &next_doc, &barrier);
+            scope.spawn(move || {
+                let mut latencies = Vec::new();
+                barrier.wait();
+                while !stop.load(Ordering::Relaxed) {
+                    let i = next_doc.fetch_add(1, Ordering::Relaxed);
+                    let op = Instant::now();
+                    ingest_one(&handle, i, config);
+                    latencies.push(op.elapsed());
+                }
+                latencies
+            })
+        };
+
+        let readers: Vec<_> = (0..config.query_threads)
+            .map(|t| {
+                let handle = db.new_handle().expect("failed to create reader handle");
+                let (stop, barrier) = (&stop, &barrier);
+                scope.spawn(move || {
+                    let mut latencies = Vec::new();
+                    let mut q = (t as u64) << 32;
+                    barrier.wait();
+                    while !stop.load(Ordering::Relaxed) {
+                        let (s, f, _) = query_one(&handle, q, config);
+                        latencies.push(s + f);
+                        q += 1;
+                    }
+                    latencies
+                })
+            })
+            .collect();
+
+        barrier.wait();
+        let start = Instant::now();
+        std::thread::sleep(config.duration);
+        stop.store(true, Ordering::Relaxed);
+
+        let query_latencies: Vec<Duration> = readers
+            .into_iter()
+            .flat_map(|r| r.join().expect("query thread panicked"))
+            .collect();
+        let ingest_latencies = writer.join().expect("ingest thread panicked");
+        let wall = start.elapsed();
+
+        MixedPhase {
+            queries: stats(query_latencies, wall),
+            ingest: stats(ingest_latencies, wall),
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+fn duration_ms(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000_000.0
+}
+
+fn print_row(name: &str, unit: &str, s: &Stats) {
+    eprintln!(
+        "  {:<22} {:>10.1} {:<8} p50={:>8.3}ms  p95={:>8.3}ms  p99={:>8.3}ms  max={:>8.3}ms",
+        name,
+        s.per_sec,
+        unit,
+        duration_ms(s.p50),
+        duration_ms(s.p95),
+        duration_ms(s.p99),
+        duration_ms(s.max),
+    );
+}
+
+fn print_csv_header() {
+    println!("\"phase\",\"durability\",\"count\",\"per_sec\",\"p50_ms\",\"p95_ms\",\"p99_ms\",\"max_ms\"");
+}
+
+fn print_csv_row(phase: &str, mode: DurabilityConfig, s: &Stats) {
+    println!(
+        "\"{}\",\"{}\",{},{:.2},{:.3},{:.3},{:.3},{:.3}",
+        phase,
+        mode.label(),
+        s.count,
+        s.per_sec,
+        duration_ms(s.p50),
+        duration_ms(s.p95),
+        duration_ms(s.p99),
+        duration_ms(s.max),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+fn record_stats(
+    recorder: &mut ResultRecorder,
+    phase: &str,
+    mode: DurabilityConfig,
+    config: &Config,
+    s: &Stats,
+    extra: Vec<(&str, serde_json::Value)>,
+    threads: usize,
+) {
+    let mut params = HashMap::new();
+    params.insert("durability".into(), serde_json::json!(mode.label()));
+    params.insert("phase".into(), serde_json::json!(phase));
+    params.insert("docs".into(), serde_json::json!(config.docs));
+    params.insert("dim".into(), serde_json::json!(config.dim));
+    params.insert("k".into(), serde_json::json!(config.k));
+    params.insert("doc_bytes".into(), serde_json::json!(config.doc_bytes));
+    for (key, value) in extra {
+        params.insert(key.into(), value);
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: format!("scenario/rag/{}/{}", phase, mode.label()),
+        category: "scenario".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(s.per_sec),
+            p50_ns: Some(s.p50.as_nanos() as u64),
+            p95_ns: Some(s.p95.as_nanos() as u64),
+            p99_ns: Some(s.p99.as_nanos() as u64),
+            min_ns: Some(s.min.as_nanos() as u64),
+            max_ns: Some(s.max.as_nanos() as u64),
+            avg_ns: Some(s.avg.as_nanos() as u64),
+            samples: Some(s.count as u64),
+            threads: Some(threads),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct Config {
+    docs: usize,
+    queries: usize,
+    k: usize,
+    dim: usize,
+    doc_bytes: usize,
+    duration: Duration,
+    query_threads: usize,
+    durability: Vec<DurabilityConfig>,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        docs: DEFAULT_DOCS,
+        queries: DEFAULT_QUERIES,
+        k: DEFAULT_K,
+        dim: DEFAULT_DIM,
+        doc_bytes: DEFAULT_DOC_BYTES,
+        duration: Duration::from_secs(DEFAULT_DURATION_SECS),
+        query_threads: DEFAULT_QUERY_THREADS,
+        durability: vec![DurabilityConfig::Standard],
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--docs" => config.docs = args.parse_value::<usize>().max(1),
+            "--queries" => config.queries = args.parse_value::<usize>().max(1),
+            "--k" | "-k" => config.k = args.parse_value::<usize>().max(1),
+            "--dim" => config.dim = args.parse_value::<usize>().max(1),
+            "--doc-bytes" => config.doc_bytes = args.parse_value(),
+            "--query-threads" => config.query_threads = args.parse_value::<usize>().max(1),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_modes(&DurabilityConfig::ALL);
+    config.duration = args.duration_or(Duration::from_secs(DEFAULT_DURATION_SECS));
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    let verbose = !config.csv && !config.quiet;
+    if verbose {
+        eprintln!("=== StrataDB RAG Scenario Benchmark ===");
+        eprintln!(
+            "Corpus: {} chunks x {}B text, {}-d embeddings | queries: {} (top-{}) | mixed phase: {:.1}s, {} query threads + 1 writer",
+            config.docs,
+            config.doc_bytes,
+            config.dim,
+            config.queries,
+            config.k,
+            config.duration.as_secs_f64(),
+            config.query_threads,
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("scenario");
+
+    for &mode in &config.durability {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(mode.label()));
+        guard_params.insert("docs".into(), serde_json::json!(config.docs));
+        guard_params.insert("dim".into(), serde_json::json!(config.dim));
+        recorder.guard(&format!("scenario/rag/{}", mode.label()), guard_params, |recorder| {
+            let bench_db = create_db(mode);
+            bench_db
+                .db
+                .vector_create_collection(COLLECTION, config.dim as u64, DistanceMetric::Cosine)
+                .unwrap();
+
+            if verbose {
+                eprintln!("--- durability: {} ---", mode.label());
+            }
+
+            let ingest = run_ingest(&bench_db.db, &config);
+            let query = run_queries(&bench_db.db, &config);
+            let mixed = run_query_under_ingest(&bench_db.db, &config);
+
+            if config.csv {
+                print_csv_row("ingest", mode, &ingest);
+                print_csv_row("query", mode, &query.end_to_end);
+                print_csv_row("query_search", mode, &query.search);
+                print_csv_row("query_fetch", mode, &query.fetch);
+                print_csv_row("query_under_ingest", mode, &mixed.queries);
+                print_csv_row("ingest_under_query", mode, &mixed.ingest);
+            } else if config.quiet {
+                eprintln!(
+                    "rag/{}: ingest={:.0} docs/s, query p50={:.3}ms p99={:.3}ms, under ingest p99={:.3}ms @ {:.0} docs/s",
+                    mode.label(),
+                    ingest.per_sec,
+                    duration_ms(query.end_to_end.p50),
+                    duration_ms(query.end_to_end.p99),
+                    duration_ms(mixed.queries.p99),
+                    mixed.ingest.per_sec,
+                );
+            } else {
+                print_row("ingest", "docs/s", &ingest);
+                print_row("query (end-to-end)", "qps", &query.end_to_end);
+                print_row("  vector_search", "", &query.search);
+                print_row(&format!("  json_get x{:.1}", query.avg_fetched), "", &query.fetch);
+                print_row("query under ingest", "qps", &mixed.queries);
+                print_row("ingest under query", "docs/s", &mixed.ingest);
+                eprintln!();
+            }
+
+            record_stats(recorder, "ingest", mode, &config, &ingest, vec![], 1);
+            record_stats(
+                recorder,
+                "query",
+                mode,
+                &config,
+                &query.end_to_end,
+                vec![
+                    ("search_p50_ns", serde_json::json!(query.search.p50.as_nanos() as u64)),
+                    ("search_p99_ns", serde_json::json!(query.search.p99.as_nanos() as u64)),
+                    ("fetch_p50_ns", serde_json::json!(query.fetch.p50.as_nanos() as u64)),
+                    ("fetch_p99_ns", serde_json::json!(query.fetch.p99.as_nanos() as u64)),
+                    ("avg_docs_fetched", serde_json::json!(query.avg_fetched)),
+                ],
+                1,
+            );
+            record_stats(
+                recorder,
+                "query_under_ingest",
+                mode,
+                &config,
+                &mixed.queries,
+                vec![
+                    ("ingest_docs_per_sec", serde_json::json!(mixed.ingest.per_sec)),
+                    ("ingest_p99_ns", serde_json::json!(mixed.ingest.p99.as_nanos() as u64)),
+                    ("duration_secs", serde_json::json!(config.duration.as_secs_f64())),
+                ],
+                config.query_threads + 1,
+            );
+        });
+    }
+
+    if verbose {
+        eprintln!("=== RAG scenario complete ===");
+    }
+    recorder.save_or_exit();
+}
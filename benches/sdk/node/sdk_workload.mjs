@@ -0,0 +1,182 @@
+#!/usr/bin/env node
+// SDK overhead workload for the StrataDB Node SDK.
+//
+// Runs the same operations as benches/sdk/sdk_overhead.rs (same keys, values,
+// documents, warmup, and percentile method) and writes one result file in the
+// shared schema (results/SCHEMA.md) with sdk = "node".
+//
+// Usage: node benches/sdk/node/sdk_workload.mjs --ops 10000 --out results/sdk-node.json
+//
+// Normally started by `cargo bench --bench sdk_overhead`. Exits non-zero if the
+// `stratadb` package can't be imported, so the runner skips this SDK.
+//
+// Methods are looked up by their snake_case name first, then camelCase
+// (`kv_put` or `kvPut`). If a call returns a promise, it is awaited inside the
+// timed region, since that is what a caller pays.
+
+import { execFileSync } from "node:child_process";
+import { readFileSync, writeFileSync } from "node:fs";
+import os from "node:os";
+
+const OPS = ["kv_put", "kv_get", "json_set", "json_get"];
+const WARMUP_DIVISOR = 10;
+const VALUE_BYTES = 100;
+
+// Node's names for these differ from Rust's std::env::consts.
+const RUST_OS = { darwin: "macos", win32: "windows" };
+const RUST_ARCH = { x64: "x86_64", arm64: "aarch64" };
+
+function workloadKey(op, i) {
+  const prefix = op.startsWith("json") ? "doc" : "key";
+  return `${prefix}:${String(i).padStart(8, "0")}`;
+}
+
+function workloadDocument(i) {
+  return { id: i, name: `doc_${i}`, active: i % 2 === 0 };
+}
+
+function method(db, name) {
+  const camel = name.replace(/_([a-z])/g, (_, c) => c.toUpperCase());
+  const fn = db[name] ?? db[camel];
+  if (typeof fn !== "function") {
+    throw new Error(`SDK has no ${name} or ${camel} method`);
+  }
+  return fn.bind(db);
+}
+
+function makeCall(db, op) {
+  const fn = method(db, op);
+  const value = "x".repeat(VALUE_BYTES);
+  switch (op) {
+    case "kv_put":
+      return (i) => fn(workloadKey(op, i), value);
+    case "json_set":
+      return (i) => fn(workloadKey(op, i), "$", workloadDocument(i));
+    case "kv_get":
+    case "json_get": {
+      const check = (key, v) => {
+        if (v === null || v === undefined) throw new Error(`${op} missed ${key}`);
+      };
+      return (i) => {
+        const key = workloadKey(op, i);
+        const r = op === "kv_get" ? fn(key) : fn(key, "$");
+        return r instanceof Promise ? r.then((v) => check(key, v)) : check(key, r);
+      };
+    }
+    default:
+      throw new Error(`unknown op ${op}`);
+  }
+}
+
+// Time each call individually; percentiles as in harness::measure_percentiles.
+async function measure(call, n) {
+  for (let i = 0; i < Math.max(Math.floor(n / WARMUP_DIVISOR), 1); i++) {
+    const r = call(i);
+    if (r instanceof Promise) await r;
+  }
+  const timings = new Array(n);
+  for (let i = 0; i < n; i++) {
+    const start = process.hrtime.bigint();
+    const r = call(i);
+    if (r instanceof Promise) await r;
+    timings[i] = Number(process.hrtime.bigint() - start);
+  }
+  timings.sort((a, b) => a - b);
+  const at = (pct) => timings[Math.floor((n * pct) / 100)];
+  return {
+    p50_ns: at(50),
+    p95_ns: at(95),
+    p99_ns: at(99),
+    min_ns: timings[0],
+    max_ns: timings[n - 1],
+    samples: n,
+  };
+}
+
+function git(...args) {
+  try {
+    return execFileSync("git", args, { encoding: "utf8", stdio: ["ignore", "pipe", "ignore"] }).trim();
+  } catch {
+    return null;
+  }
+}
+
+function metadata(sdkVersion) {
+  const meta = {
+    timestamp: new Date().toISOString().replace(/\.\d{3}Z$/, "Z"),
+    sdk: "node",
+    sdk_version: sdkVersion,
+    hardware: {
+      cpu: os.cpus()[0]?.model ?? "unknown",
+      cores: os.cpus().length,
+      ram_gb: Math.floor(os.totalmem() / 1024 ** 3),
+      os: RUST_OS[os.platform()] ?? os.platform(),
+      arch: RUST_ARCH[os.arch()] ?? os.arch(),
+    },
+  };
+  const commit = git("rev-parse", "--short", "HEAD");
+  if (commit !== null) {
+    meta.git_commit = commit;
+    meta.git_branch = git("rev-parse", "--abbrev-ref", "HEAD");
+    meta.git_dirty = git("status", "--porcelain") !== "";
+  }
+  return meta;
+}
+
+function sdkVersion() {
+  try {
+    const pkg = new URL("../../../node_modules/stratadb/package.json", import.meta.url);
+    return JSON.parse(readFileSync(pkg, "utf8")).version;
+  } catch {
+    return "unknown";
+  }
+}
+
+function parseArgs(argv) {
+  const args = { ops: 10000, out: null };
+  for (let i = 0; i < argv.length; i++) {
+    if (argv[i] === "--ops") args.ops = Math.max(parseInt(argv[++i], 10) || 10000, 100);
+    else if (argv[i] === "--out") args.out = argv[++i];
+  }
+  return args;
+}
+
+async function main() {
+  const args = parseArgs(process.argv.slice(2));
+  if (!args.out) {
+    console.error("Usage: node sdk_workload.mjs --ops N --out FILE");
+    return 1;
+  }
+
+  let stratadb;
+  try {
+    stratadb = await import("stratadb");
+  } catch (e) {
+    console.error(`stratadb Node SDK not installed: ${e.message}`);
+    return 1;
+  }
+  const Strata = stratadb.Strata ?? stratadb.default?.Strata;
+  let db = Strata.cache();
+  if (db instanceof Promise) db = await db;
+
+  const results = [];
+  for (const op of OPS) {
+    const metrics = await measure(makeCall(db, op), args.ops);
+    console.error(
+      `  node ${op.padEnd(40)} p50=${metrics.p50_ns}ns p95=${metrics.p95_ns}ns ` +
+        `p99=${metrics.p99_ns}ns (n=${metrics.samples})`,
+    );
+    results.push({
+      benchmark: `sdk/${op}`,
+      category: "sdk",
+      parameters: { ops: args.ops, durability: "cache" },
+      metrics,
+    });
+  }
+
+  const report = { schema_version: 1, metadata: metadata(sdkVersion()), results };
+  writeFileSync(args.out, JSON.stringify(report, null, 2));
+  return 0;
+}
+
+process.exitCode = await main();
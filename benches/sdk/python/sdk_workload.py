@@ -0,0 +1,171 @@
+#!/usr/bin/env python3
+"""SDK overhead workload for the StrataDB Python SDK.
+
+Runs the same operations as benches/sdk/sdk_overhead.rs (same keys, values,
+documents, warmup, and percentile method) and writes one result file in the
+shared schema (results/SCHEMA.md) with sdk = "python".
+
+Usage: python3 benches/sdk/python/sdk_workload.py --ops 10000 --out results/sdk-python.json
+
+Normally started by `cargo bench --bench sdk_overhead`. Exits non-zero if the
+`stratadb` package can't be imported, so the runner skips this SDK.
+"""
+
+import argparse
+import json
+import os
+import platform
+import subprocess
+import sys
+import time
+from datetime import datetime, timezone
+
+OPS = ["kv_put", "kv_get", "json_set", "json_get"]
+WARMUP_DIVISOR = 10
+VALUE_BYTES = 100
+
+# Python's names for these differ from Rust's std::env::consts.
+RUST_OS = {"darwin": "macos"}
+RUST_ARCH = {"amd64": "x86_64", "arm64": "aarch64"}
+
+
+def workload_key(op, i):
+    prefix = "doc" if op.startswith("json") else "key"
+    return f"{prefix}:{i:08}"
+
+
+def workload_document(i):
+    return {"id": i, "name": f"doc_{i}", "active": i % 2 == 0}
+
+
+def make_call(db, op):
+    value = "x" * VALUE_BYTES
+    if op == "kv_put":
+        return lambda i: db.kv_put(workload_key(op, i), value)
+    if op == "kv_get":
+        def kv_get(i):
+            key = workload_key(op, i)
+            if db.kv_get(key) is None:
+                raise AssertionError(f"kv_get missed {key}")
+        return kv_get
+    if op == "json_set":
+        return lambda i: db.json_set(workload_key(op, i), "$", workload_document(i))
+    if op == "json_get":
+        def json_get(i):
+            key = workload_key(op, i)
+            if db.json_get(key, "$") is None:
+                raise AssertionError(f"json_get missed {key}")
+        return json_get
+    raise ValueError(f"unknown op {op}")
+
+
+def measure(call, n):
+    """Time each call individually; percentiles as in harness::measure_percentiles."""
+    for i in range(max(n // WARMUP_DIVISOR, 1)):
+        call(i)
+    timings = []
+    clock = time.perf_counter_ns
+    for i in range(n):
+        start = clock()
+        call(i)
+        timings.append(clock() - start)
+    timings.sort()
+    size = len(timings)
+    return {
+        "p50_ns": timings[size * 50 // 100],
+        "p95_ns": timings[size * 95 // 100],
+        "p99_ns": timings[size * 99 // 100],
+        "min_ns": timings[0],
+        "max_ns": timings[-1],
+        "samples": size,
+    }
+
+
+def git(*args):
+    try:
+        out = subprocess.run(["git", *args], capture_output=True, text=True, check=True)
+        return out.stdout.strip()
+    except (OSError, subprocess.CalledProcessError):
+        return None
+
+
+def hardware():
+    cpu = platform.processor() or "unknown"
+    ram_gb = 0
+    try:
+        with open("/proc/cpuinfo") as f:
+            for line in f:
+                if line.startswith("model name"):
+                    cpu = line.split(":", 1)[1].strip()
+                    break
+        with open("/proc/meminfo") as f:
+            for line in f:
+                if line.startswith("MemTotal:"):
+                    ram_gb = int(line.split()[1]) // (1024 * 1024)
+                    break
+    except OSError:
+        pass
+    return {
+        "cpu": cpu,
+        "cores": os.cpu_count() or 0,
+        "ram_gb": ram_gb,
+        "os": RUST_OS.get(platform.system().lower(), platform.system().lower()),
+        "arch": RUST_ARCH.get(platform.machine().lower(), platform.machine()),
+    }
+
+
+def metadata(sdk_version):
+    meta = {
+        "timestamp": datetime.now(timezone.utc).strftime("%Y-%m-%dT%H:%M:%SZ"),
+        "sdk": "python",
+        "sdk_version": sdk_version,
+        "hardware": hardware(),
+    }
+    commit = git("rev-parse", "--short", "HEAD")
+    if commit is not None:
+        meta["git_commit"] = commit
+        meta["git_branch"] = git("rev-parse", "--abbrev-ref", "HEAD")
+        meta["git_dirty"] = git("status", "--porcelain") != ""
+    return meta
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("--ops", type=int, default=10_000)
+    parser.add_argument("--out", required=True)
+    args = parser.parse_args()
+
+    try:
+        import stratadb
+    except ImportError as e:
+        print(f"stratadb Python SDK not installed: {e}", file=sys.stderr)
+        return 1
+
+    db = stratadb.Strata.cache()
+    results = []
+    for op in OPS:
+        metrics = measure(make_call(db, op), args.ops)
+        print(
+            f"  python {op:<38} p50={metrics['p50_ns']}ns p95={metrics['p95_ns']}ns "
+            f"p99={metrics['p99_ns']}ns (n={metrics['samples']})",
+            file=sys.stderr,
+        )
+        results.append({
+            "benchmark": f"sdk/{op}",
+            "category": "sdk",
+            "parameters": {"ops": args.ops, "durability": "cache"},
+            "metrics": metrics,
+        })
+
+    report = {
+        "schema_version": 1,
+        "metadata": metadata(getattr(stratadb, "__version__", "unknown")),
+        "results": results,
+    }
+    with open(args.out, "w") as f:
+        json.dump(report, f, indent=2)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,482 @@
+//! SDK Overhead Benchmark for StrataDB
+//!
+//! Runs one small KV/JSON workload natively in Rust and, as subprocesses,
+//! through the Python and Node SDKs, then reports what each binding layer
+//! adds per call. Every side writes a result file in the shared schema
+//! (`sdk` = `rust`, `python`, `node`) with the same benchmark names, so the
+//! files are also fed to `bench-compare` for the usual delta table.
+//!
+//! The workload scripts live next to this file (`python/sdk_workload.py`,
+//! `node/sdk_workload.mjs`). An SDK whose interpreter or package is missing
+//! is skipped with a warning; the native run and the remaining SDKs still
+//! report. All sides use an in-memory (cache) database, so the difference is
+//! binding cost rather than storage cost.
+//!
+//! Run:    `cargo bench --bench sdk_overhead`
+//! Custom: `cargo bench --bench sdk_overhead -- --ops 50000 --sdks python`
+//! Interp: `cargo bench --bench sdk_overhead -- --python .venv/bin/python --node node20`
+//! Report: `cargo bench --bench sdk_overhead -- --format markdown > sdk.md`
+//! CSV:    `cargo bench --bench sdk_overhead -- --csv`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{
+    create_db, measure_percentiles, print_hardware_info, report_percentiles, DurabilityConfig,
+    Percentiles,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use strata_benchmarks::fmt::fmt_ns;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkReport, BenchmarkResult, ResultStatus};
+use stratadb::{Strata, Value};
+
+// ---------------------------------------------------------------------------
+// Defaults
+// ---------------------------------------------------------------------------
+
+const DEFAULT_OPS: usize = 10_000;
+const DEFAULT_SDKS: &[&str] = &["python", "node"];
+
+/// Operations of the shared workload. The SDK scripts run the same list in
+/// the same order and name their results `sdk/<op>`.
+const OPS: [&str; 4] = ["kv_put", "kv_get", "json_set", "json_get"];
+
+/// Calls run before timing starts, per operation.
+const WARMUP_DIVISOR: usize = 10;
+
+/// Length of the string stored by `kv_put`.
+const VALUE_BYTES: usize = 100;
+
+// ---------------------------------------------------------------------------
+// Shared workload (keep in sync with python/ and node/)
+// ---------------------------------------------------------------------------
+
+fn workload_key(op: &str, i: usize) -> String {
+    let prefix = if op.starts_with("json") { "doc" } else { "key" };
+    format!("{}:{:08}", prefix, i)
+}
+
+fn workload_value() -> Value {
+    Value::String("x".repeat(VALUE_BYTES))
+}
+
+/// `{"id": i, "name": "doc_<i>", "active": i is even}`.
+fn workload_document(i: usize) -> Value {
+    let mut map = HashMap::new();
+    map.insert("id".to_string(), Value::Int(i as i64));
+    map.insert("name".to_string(), Value::String(format!("doc_{}", i)));
+    map.insert("active".to_string(), Value::Bool(i.is_multiple_of(2)));
+    Value::Object(map)
+}
+
+/// Time `n` calls of `op` against `db`, one key per call. Puts run before
+/// gets in `OPS`, so every get hits.
+fn run_op(db: &Strata, op: &str, n: usize) -> Percentiles {
+    let call = |i: usize| match op {
+        "kv_put" => {
+            db.kv_put(&workload_key(op, i), workload_value()).unwrap();
+        }
+        "kv_get" => {
+            let key = workload_key(op, i);
+            assert!(db.kv_get(&key).unwrap().is_some(), "kv_get missed {}", key);
+        }
+        "json_set" => {
+            db.json_set(&workload_key(op, i), "$", workload_document(i))
+                .unwrap();
+        }
+        "json_get" => {
+            let key = workload_key(op, i);
+            assert!(db.json_get(&key, "$").unwrap().is_some(), "json_get missed {}", key);
+        }
+        _ => unreachable!("unknown op {}", op),
+    };
+
+    for i in 0..(n / WARMUP_DIVISOR).max(1) {
+        call(i);
+    }
+    let mut i = 0;
+    measure_percentiles(n, || {
+        call(i);
+        i += 1;
+    })
+    .with_context(|i| workload_key(op, i))
+}
+
+// ---------------------------------------------------------------------------
+// SDK subprocesses
+// ---------------------------------------------------------------------------
+
+/// An SDK run as a subprocess: `<interpreter> <script> --ops N --out FILE`.
+struct SdkRunner {
+    sdk: &'static str,
+    interpreter: String,
+    script: &'static str,
+}
+
+impl SdkRunner {
+    fn for_sdk(sdk: &str, config: &Config) -> Option<SdkRunner> {
+        match sdk {
+            "python" => Some(SdkRunner {
+                sdk: "python",
+                interpreter: config.python.clone(),
+                script: "benches/sdk/python/sdk_workload.py",
+            }),
+            "node" => Some(SdkRunner {
+                sdk: "node",
+                interpreter: config.node.clone(),
+                script: "benches/sdk/node/sdk_workload.mjs",
+            }),
+            _ => None,
+        }
+    }
+
+    /// Run the script and load the report it wrote. Any failure (missing
+    /// interpreter, missing package, bad output) is a warning, not an abort.
+    fn run(&self, ops: usize, out: &Path) -> Option<BenchmarkReport> {
+        let status = Command::new(&self.interpreter)
+            .arg(self.script)
+            .arg("--ops")
+            .arg(ops.to_string())
+            .arg("--out")
+            .arg(out)
+            .status();
+        match status {
+            Ok(s) if s.success() => {}
+            Ok(s) => {
+                eprintln!("Warning: {} workload exited with {}; skipping {}", self.sdk, s, self.sdk);
+                return None;
+            }
+            Err(e) => {
+                eprintln!(
+                    "Warning: could not run {} ({}); skipping {}",
+                    self.interpreter, e, self.sdk
+                );
+                return None;
+            }
+        }
+
+        let report = std::fs::read_to_string(out)
+            .map_err(|e| e.to_string())
+            .and_then(|s| serde_json::from_str::<BenchmarkReport>(&s).map_err(|e| e.to_string()));
+        match report {
+            Ok(r) if r.metadata.sdk == self.sdk => Some(r),
+            Ok(r) => {
+                eprintln!(
+                    "Warning: {} reports sdk \"{}\", expected \"{}\"; skipping",
+                    out.display(),
+                    r.metadata.sdk,
+                    self.sdk
+                );
+                None
+            }
+            Err(e) => {
+                eprintln!("Warning: could not read {}: {}; skipping {}", out.display(), e, self.sdk);
+                None
+            }
+        }
+    }
+}
+
+/// p50 of `sdk/<op>` in `report`, if it was recorded successfully.
+fn sdk_p50(report: &BenchmarkReport, op: &str) -> Option<u64> {
+    let name = format!("sdk/{}", op);
+    report
+        .results
+        .iter()
+        .find(|r| r.benchmark == name && r.status.is_success())
+        .and_then(|r| r.metrics.p50_ns)
+}
+
+// ---------------------------------------------------------------------------
+// Overhead
+// ---------------------------------------------------------------------------
+
+/// One operation through one SDK, against the native p50.
+struct Overhead {
+    op: &'static str,
+    sdk: &'static str,
+    rust_p50_ns: u64,
+    sdk_p50_ns: u64,
+    sdk_version: String,
+}
+
+impl Overhead {
+    /// Nanoseconds the binding adds per call (negative if it came out faster).
+    fn added_ns(&self) -> i64 {
+        self.sdk_p50_ns as i64 - self.rust_p50_ns as i64
+    }
+
+    fn ratio(&self) -> f64 {
+        self.sdk_p50_ns as f64 / self.rust_p50_ns.max(1) as f64
+    }
+}
+
+fn overheads(
+    native: &HashMap<&'static str, u64>,
+    sdk_reports: &[(&'static str, BenchmarkReport)],
+) -> Vec<Overhead> {
+    let mut rows = Vec::new();
+    for op in OPS {
+        let Some(&rust_p50_ns) = native.get(op) else {
+            continue;
+        };
+        for (sdk, report) in sdk_reports {
+            match sdk_p50(report, op) {
+                Some(sdk_p50_ns) => rows.push(Overhead {
+                    op,
+                    sdk,
+                    rust_p50_ns,
+                    sdk_p50_ns,
+                    sdk_version: report.metadata.sdk_version.clone(),
+                }),
+                None => eprintln!("Warning: {} report has no sdk/{} result", sdk, op),
+            }
+        }
+    }
+    rows
+}
+
+fn print_table(rows: &[Overhead]) {
+    eprintln!(
+        "\n  {:<10} {:<8} {:>12} {:>12} {:>12} {:>8}",
+        "op", "sdk", "rust p50", "sdk p50", "added", "ratio"
+    );
+    eprintln!("  {}", "-".repeat(67));
+    for r in rows {
+        let added = if r.added_ns() < 0 {
+            format!("-{}", fmt_ns(r.added_ns().unsigned_abs()))
+        } else {
+            format!("+{}", fmt_ns(r.added_ns() as u64))
+        };
+        eprintln!(
+            "  {:<10} {:<8} {:>12} {:>12} {:>12} {:>7.2}x",
+            r.op,
+            r.sdk,
+            fmt_ns(r.rust_p50_ns),
+            fmt_ns(r.sdk_p50_ns),
+            added,
+            r.ratio()
+        );
+    }
+}
+
+fn print_quiet(rows: &[Overhead]) {
+    for r in rows {
+        eprintln!("{}/{}: {:+}ns ({:.2}x)", r.sdk, r.op, r.added_ns(), r.ratio());
+    }
+}
+
+fn print_csv(rows: &[Overhead]) {
+    println!("op,sdk,sdk_version,rust_p50_ns,sdk_p50_ns,added_ns,ratio");
+    for r in rows {
+        println!(
+            "{},{},{},{},{},{},{:.3}",
+            r.op,
+            r.sdk,
+            r.sdk_version,
+            r.rust_p50_ns,
+            r.sdk_p50_ns,
+            r.added_ns(),
+            r.ratio()
+        );
+    }
+}
+
+fn record_overheads(recorder: &mut ResultRecorder, rows: &[Overhead], ops: usize) {
+    for r in rows {
+        let mut params = HashMap::new();
+        params.insert("op".into(), serde_json::json!(r.op));
+        params.insert("sdk".into(), serde_json::json!(r.sdk));
+        params.insert("sdk_version".into(), serde_json::json!(r.sdk_version));
+        params.insert("ops".into(), serde_json::json!(ops));
+        params.insert("rust_p50_ns".into(), serde_json::json!(r.rust_p50_ns));
+        params.insert("added_ns".into(), serde_json::json!(r.added_ns()));
+        params.insert("ratio".into(), serde_json::json!(r.ratio()));
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!("sdk/overhead/{}/{}", r.sdk, r.op),
+            category: "sdk-overhead".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                p50_ns: Some(r.sdk_p50_ns),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: ResultStatus::Success,
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// bench-compare
+// ---------------------------------------------------------------------------
+
+/// Compare the native report (baseline) against one SDK report (candidate)
+/// with `bench-compare`, rendering to stdout in `format`.
+fn run_compare(native: &Path, sdk: &str, sdk_path: &Path, format: &str) {
+    eprintln!("\n=== bench-compare: rust (baseline) vs {} (candidate) ===", sdk);
+    let status = Command::new(env!("CARGO_BIN_EXE_bench-compare"))
+        .arg("--format")
+        .arg(format)
+        .arg(native)
+        .arg(sdk_path)
+        .status();
+    match status {
+        Ok(s) if s.success() => {}
+        Ok(s) => eprintln!("Warning: bench-compare exited with {}", s),
+        Err(e) => eprintln!("Warning: could not run bench-compare: {}", e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CLI
+// ---------------------------------------------------------------------------
+
+struct Config {
+    ops: usize,
+    sdks: Vec<String>,
+    python: String,
+    node: String,
+    format: String,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        ops: DEFAULT_OPS,
+        sdks: DEFAULT_SDKS.iter().map(|s| s.to_string()).collect(),
+        python: "python3".to_string(),
+        node: "node".to_string(),
+        format: "text".to_string(),
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--ops" => config.ops = args.parse_value::<usize>().max(100),
+            "--sdks" => {
+                let v = args.value();
+                config.sdks = v
+                    .split(',')
+                    .map(|s| s.trim().to_lowercase())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+            }
+            "--python" => config.python = args.value(),
+            "--node" => config.node = args.value(),
+            "--format" => config.format = args.value(),
+            _ => args.unknown_flag(),
+        }
+    }
+    if !matches!(args.durability, None | Some(DurabilityConfig::Cache)) {
+        harness::cli::usage_error("sdk_overhead runs every side in cache mode to measure binding cost");
+    }
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    if !config.quiet {
+        print_hardware_info();
+        eprintln!(
+            "=== SDK overhead: {} ops per operation, sdks: {} ===\n",
+            config.ops,
+            config.sdks.join(",")
+        );
+    }
+
+    // Native run, saved on its own so bench-compare sees rust vs one SDK.
+    let mut native_recorder = ResultRecorder::new("sdk");
+    let mut native_p50 = HashMap::new();
+    let bench_db = create_db(DurabilityConfig::Cache);
+    for op in OPS {
+        let mut params = HashMap::new();
+        params.insert("ops".into(), serde_json::json!(config.ops));
+        params.insert("durability".into(), serde_json::json!("cache"));
+        let name = format!("sdk/{}", op);
+        let p = native_recorder.guard(&name, params.clone(), |_| {
+            run_op(&bench_db.db, op, config.ops)
+        });
+        if let Some(p) = p {
+            if !config.quiet {
+                report_percentiles(&format!("rust {}", op), &p);
+            }
+            native_p50.insert(op, p.p50.as_nanos() as u64);
+            native_recorder.record_latency(&name, params, &p, None, p.samples as u64);
+        }
+    }
+    drop(bench_db);
+    let native_failed = native_recorder.failure_count() > 0;
+    let native_path = match native_recorder.save() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Error saving native results: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // SDK runs, each writing next to the native file.
+    let mut sdk_reports = Vec::new();
+    let mut sdk_paths = Vec::new();
+    for name in &config.sdks {
+        let Some(runner) = SdkRunner::for_sdk(name, &config) else {
+            eprintln!("Warning: unknown sdk \"{}\" (expected python or node)", name);
+            continue;
+        };
+        let out = sdk_result_path(&native_path, runner.sdk);
+        if !config.quiet {
+            eprintln!("\nRunning {} workload: {} {}", runner.sdk, runner.interpreter, runner.script);
+        }
+        if let Some(report) = runner.run(config.ops, &out) {
+            sdk_reports.push((runner.sdk, report));
+            sdk_paths.push((runner.sdk, out));
+        }
+    }
+
+    let rows = overheads(&native_p50, &sdk_reports);
+    if config.csv {
+        print_csv(&rows);
+    } else if config.quiet {
+        print_quiet(&rows);
+    } else {
+        print_table(&rows);
+    }
+
+    let mut recorder = ResultRecorder::new("sdk-overhead");
+    record_overheads(&mut recorder, &rows, config.ops);
+    let _ = recorder.save();
+    if native_failed {
+        std::process::exit(1);
+    }
+
+    if !config.csv {
+        for (sdk, path) in &sdk_paths {
+            run_compare(&native_path, sdk, path, &config.format);
+        }
+    }
+}
+
+/// `results/sdk-<ts>-<commit>.json` -> `results/sdk-<sdk>-<ts>-<commit>.json`.
+fn sdk_result_path(native: &Path, sdk: &str) -> PathBuf {
+    let file = native
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or("sdk.json");
+    let rest = file.strip_prefix("sdk-").unwrap_or(file);
+    native.with_file_name(format!("sdk-{}-{}", sdk, rest))
+}
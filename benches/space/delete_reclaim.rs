@@ -0,0 +1,586 @@
+//! Delete-Heavy Reclamation Benchmark for StrataDB
+//!
+//! Inserts N keys, deletes 90% of them, then watches what the deletes leave
+//! behind. Tombstones that are never cleaned up keep the database directory
+//! at its pre-delete size and make reads and scans walk over dead entries.
+//!
+//! After the deletes, the database is left idle for `--observe-secs` while
+//! every `--interval-secs` it is probed:
+//!
+//! - on-disk size of the database directory;
+//! - `kv_get` of surviving keys;
+//! - `kv_get` of deleted keys (must miss);
+//! - `kv_list` over the whole prefix (must return exactly the survivors).
+//!
+//! A final probe runs after closing and reopening the database, since some
+//! engines only drop tombstones at checkpoint or recovery. Every probe is
+//! compared against a clean database that only ever held the surviving keys:
+//! the gap in disk size and latency is the cost of the tombstones. A final
+//! size more than 1.5x the clean database's is reported as not reclaimed.
+//!
+//! Cache mode has nothing on disk and is not run.
+//!
+//! Run:    `cargo bench --bench delete_reclaim`
+//! Quick:  `cargo bench --bench delete_reclaim -- --keys 100000 --observe-secs 10 -q`
+//! CSV:    `cargo bench --bench delete_reclaim -- --csv`
+//! Custom: `cargo bench --bench delete_reclaim -- --keys 2000000 --delete-pct 99 --durability standard`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, dir_size_bytes, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::fmt_num;
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+
+// ---------------------------------------------------------------------------
+// Parameters
+// ---------------------------------------------------------------------------
+
+const DEFAULT_KEYS: usize = 1_000_000;
+const DEFAULT_DELETE_PCT: usize = 90;
+const DEFAULT_OBSERVE_SECS: u64 = 30;
+const DEFAULT_INTERVAL_SECS: u64 = 5;
+const VALUE_SIZE: usize = 256;
+const PREFIX: &str = "del:";
+const DISK_MODES: &[DurabilityConfig] = &[DurabilityConfig::Standard, DurabilityConfig::Always];
+/// Gets of surviving and of deleted keys per probe.
+const GET_PROBES: usize = 1_000;
+/// Full-prefix `kv_list` calls per probe.
+const SCAN_PROBES: usize = 5;
+/// Final size relative to the clean database above which tombstones are
+/// reported as not reclaimed.
+const RECLAIM_SLACK: f64 = 1.5;
+
+// ---------------------------------------------------------------------------
+// Key layout
+// ---------------------------------------------------------------------------
+
+fn reclaim_key(i: usize) -> String {
+    format!("{}{:010}", PREFIX, i)
+}
+
+/// Which keys are deleted: `delete_pct` of every 100 consecutive keys, so the
+/// survivors are spread evenly across the key range.
+#[derive(Clone, Copy)]
+struct Layout {
+    keys: usize,
+    delete_pct: usize,
+}
+
+impl Layout {
+    fn deleted(&self, i: usize) -> bool {
+        i % 100 < self.delete_pct
+    }
+
+    fn survivors(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.keys).filter(|&i| !self.deleted(i))
+    }
+
+    fn survivor_count(&self) -> usize {
+        self.survivors().count()
+    }
+
+    fn logical_bytes(&self) -> u64 {
+        self.survivors()
+            .map(|i| (reclaim_key(i).len() + VALUE_SIZE) as u64)
+            .sum()
+    }
+
+    /// Up to `n` keys matching `deleted`, spread across the key range.
+    fn probe_keys(&self, deleted: bool, n: usize) -> Vec<String> {
+        let matching: Vec<usize> = (0..self.keys).filter(|&i| self.deleted(i) == deleted).collect();
+        let stride = (matching.len() / n.max(1)).max(1);
+        matching.iter().step_by(stride).take(n).map(|&i| reclaim_key(i)).collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Result types
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Default)]
+struct Latency {
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+    samples: usize,
+}
+
+impl Latency {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        if samples.is_empty() {
+            return Latency::default();
+        }
+        samples.sort_unstable();
+        let len = samples.len();
+        let sum: Duration = samples.iter().sum();
+        Latency {
+            avg: sum / len as u32,
+            p50: samples[len * 50 / 100],
+            p95: samples[(len * 95 / 100).min(len - 1)],
+            p99: samples[(len * 99 / 100).min(len - 1)],
+            min: samples[0],
+            max: samples[len - 1],
+            samples: len,
+        }
+    }
+}
+
+/// One probe of the database: size on disk and read latencies.
+struct Probe {
+    label: String,
+    disk_bytes: u64,
+    get: Latency,
+    get_deleted: Latency,
+    scan: Latency,
+    failures: Vec<String>,
+}
+
+const PROBE_OPS: [&str; 3] = ["get", "get_deleted", "scan"];
+
+impl Probe {
+    fn latency(&self, op: &str) -> Latency {
+        match op {
+            "get" => self.get,
+            "get_deleted" => self.get_deleted,
+            _ => self.scan,
+        }
+    }
+}
+
+struct ReclaimResult {
+    layout: Layout,
+    loaded_disk_bytes: u64,
+    delete_elapsed: Duration,
+    /// Probes after the deletes, in time order; the last is after reopen.
+    probes: Vec<Probe>,
+    clean: Probe,
+}
+
+impl ReclaimResult {
+    fn last(&self) -> &Probe {
+        self.probes.last().expect("at least one probe")
+    }
+
+    fn deletes_per_sec(&self) -> f64 {
+        (self.layout.keys - self.layout.survivor_count()) as f64 / self.delete_elapsed.as_secs_f64()
+    }
+
+    fn amplification(&self, disk_bytes: u64) -> f64 {
+        disk_bytes as f64 / self.layout.logical_bytes().max(1) as f64
+    }
+
+    /// Final on-disk size relative to the clean database.
+    fn vs_clean(&self) -> f64 {
+        self.last().disk_bytes as f64 / self.clean.disk_bytes.max(1) as f64
+    }
+
+    fn reclaimed(&self) -> bool {
+        self.vs_clean() <= RECLAIM_SLACK
+    }
+
+    fn failures(&self) -> Vec<String> {
+        self.probes
+            .iter()
+            .chain(std::iter::once(&self.clean))
+            .flat_map(|p| p.failures.iter().map(move |f| format!("{}: {}", p.label, f)))
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Measurement
+// ---------------------------------------------------------------------------
+
+fn disk_bytes(db: &BenchDb) -> u64 {
+    db.data_dir().map(dir_size_bytes).unwrap_or(0)
+}
+
+fn probe(db: &BenchDb, layout: &Layout, label: String) -> Probe {
+    let mut failures = Vec::new();
+    let disk_bytes = disk_bytes(db);
+
+    let mut get = Vec::with_capacity(GET_PROBES);
+    let mut missing = 0;
+    for key in layout.probe_keys(false, GET_PROBES) {
+        let start = Instant::now();
+        let found = db.db.kv_get(&key).unwrap().is_some();
+        get.push(start.elapsed());
+        missing += usize::from(!found);
+    }
+    if missing > 0 {
+        failures.push(format!("{} surviving keys not found", missing));
+    }
+
+    let mut get_deleted = Vec::with_capacity(GET_PROBES);
+    let mut resurrected = 0;
+    for key in layout.probe_keys(true, GET_PROBES) {
+        let start = Instant::now();
+        let found = db.db.kv_get(&key).unwrap().is_some();
+        get_deleted.push(start.elapsed());
+        resurrected += usize::from(found);
+    }
+    if resurrected > 0 {
+        failures.push(format!("{} deleted keys still found", resurrected));
+    }
+
+    let expected = layout.survivor_count();
+    let mut scan = Vec::with_capacity(SCAN_PROBES);
+    for _ in 0..SCAN_PROBES {
+        let start = Instant::now();
+        let listed = db.db.kv_list(Some(PREFIX)).unwrap();
+        scan.push(start.elapsed());
+        if listed.len() != expected {
+            failures.push(format!("kv_list returned {} keys, expected {}", listed.len(), expected));
+            break;
+        }
+    }
+
+    Probe {
+        label,
+        disk_bytes,
+        get: Latency::from_samples(get),
+        get_deleted: Latency::from_samples(get_deleted),
+        scan: Latency::from_samples(scan),
+        failures,
+    }
+}
+
+fn run_reclaim(
+    mode: DurabilityConfig,
+    layout: Layout,
+    observe: Duration,
+    interval: Duration,
+    quiet: bool,
+) -> ReclaimResult {
+    let value = Value::Bytes(vec![0x44; VALUE_SIZE]);
+
+    // Clean baseline: only the keys that will survive, never deleted.
+    let clean = {
+        let clean_db = create_db(mode);
+        for i in layout.survivors() {
+            clean_db.db.kv_put(&reclaim_key(i), value.clone()).unwrap();
+        }
+        probe(&clean_db, &layout, "clean".to_string())
+    };
+
+    let mut db = create_db(mode);
+    for i in 0..layout.keys {
+        db.db.kv_put(&reclaim_key(i), value.clone()).unwrap();
+    }
+    let loaded_disk_bytes = disk_bytes(&db);
+
+    let delete_start = Instant::now();
+    for i in (0..layout.keys).filter(|&i| layout.deleted(i)) {
+        db.db.kv_delete(&reclaim_key(i)).unwrap();
+    }
+    let delete_elapsed = delete_start.elapsed();
+
+    let mut probes = vec![probe(&db, &layout, "t+0s".to_string())];
+    if !quiet {
+        print_probe(&probes[0]);
+    }
+    let observe_start = Instant::now();
+    while observe_start.elapsed() + interval <= observe {
+        std::thread::sleep(interval);
+        let label = format!("t+{}s", observe_start.elapsed().as_secs());
+        probes.push(probe(&db, &layout, label));
+        if !quiet {
+            print_probe(probes.last().unwrap());
+        }
+    }
+
+    db = db.reopen();
+    probes.push(probe(&db, &layout, "reopened".to_string()));
+
+    ReclaimResult {
+        layout,
+        loaded_disk_bytes,
+        delete_elapsed,
+        probes,
+        clean,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recording helper
+// ---------------------------------------------------------------------------
+
+fn record_reclaim(recorder: &mut ResultRecorder, r: &ReclaimResult, mode: &DurabilityConfig) {
+    let last = r.last();
+    let status = ResultStatus::from_validation(&r.failures());
+
+    let mut base = HashMap::new();
+    base.insert("durability".into(), serde_json::json!(mode.label()));
+    base.insert("keys".into(), serde_json::json!(r.layout.keys));
+    base.insert("delete_pct".into(), serde_json::json!(r.layout.delete_pct));
+    base.insert("survivors".into(), serde_json::json!(r.layout.survivor_count()));
+    base.insert("value_size".into(), serde_json::json!(VALUE_SIZE));
+
+    // Disk size after the observation window and reopen
+    let mut params = base.clone();
+    let probe_labels: Vec<&str> = r.probes.iter().map(|p| p.label.as_str()).collect();
+    let probe_disk: Vec<u64> = r.probes.iter().map(|p| p.disk_bytes).collect();
+    params.insert("logical_bytes".into(), serde_json::json!(r.layout.logical_bytes()));
+    params.insert("loaded_disk_bytes".into(), serde_json::json!(r.loaded_disk_bytes));
+    params.insert("clean_disk_bytes".into(), serde_json::json!(r.clean.disk_bytes));
+    params.insert("vs_clean".into(), serde_json::json!(r.vs_clean()));
+    params.insert("tombstones_reclaimed".into(), serde_json::json!(r.reclaimed()));
+    params.insert("deletes_per_sec".into(), serde_json::json!(r.deletes_per_sec()));
+    params.insert("probe_labels".into(), serde_json::json!(probe_labels));
+    params.insert("probe_disk_bytes".into(), serde_json::json!(probe_disk));
+    recorder.record(BenchmarkResult {
+        benchmark: format!("space/delete_reclaim/{}keys/disk/{}", r.layout.keys, mode.label()),
+        category: "space".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            disk_bytes: Some(last.disk_bytes),
+            space_amplification: Some(r.amplification(last.disk_bytes)),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: status.clone(),
+    });
+
+    // Read latencies at the final probe, against the clean database
+    for op in PROBE_OPS {
+        let lat = last.latency(op);
+        let clean = r.clean.latency(op);
+        let trace: Vec<u64> = r.probes.iter().map(|p| p.latency(op).p50.as_nanos() as u64).collect();
+        let mut params = base.clone();
+        params.insert("op".into(), serde_json::json!(op));
+        params.insert("clean_p50_ns".into(), serde_json::json!(clean.p50.as_nanos() as u64));
+        params.insert(
+            "vs_clean".into(),
+            serde_json::json!(lat.p50.as_secs_f64() / clean.p50.as_secs_f64().max(1e-9)),
+        );
+        params.insert("probe_p50_ns".into(), serde_json::json!(trace));
+
+        recorder.record(BenchmarkResult {
+            benchmark: format!("space/delete_reclaim/{}keys/{}/{}", r.layout.keys, op, mode.label()),
+            category: "space".to_string(),
+            parameters: params,
+            metrics: BenchmarkMetrics {
+                p50_ns: Some(lat.p50.as_nanos() as u64),
+                p95_ns: Some(lat.p95.as_nanos() as u64),
+                p99_ns: Some(lat.p99.as_nanos() as u64),
+                min_ns: Some(lat.min.as_nanos() as u64),
+                max_ns: Some(lat.max.as_nanos() as u64),
+                avg_ns: Some(lat.avg.as_nanos() as u64),
+                samples: Some(lat.samples as u64),
+                disk_bytes: Some(last.disk_bytes),
+                ..Default::default()
+            },
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status: status.clone(),
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Output formatters
+// ---------------------------------------------------------------------------
+
+fn fmt_mb(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+}
+
+fn duration_us(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000.0
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:<10}  {:>10}  {:>11}  {:>14}  {:>11}",
+        "probe", "disk", "get p50", "deleted p50", "scan p50"
+    );
+}
+
+fn print_probe(p: &Probe) {
+    eprintln!(
+        "  {:<10}  {:>10}  {:>9.2}us  {:>12.2}us  {:>9.2}ms",
+        p.label,
+        fmt_mb(p.disk_bytes),
+        duration_us(p.get.p50),
+        duration_us(p.get_deleted.p50),
+        duration_us(p.scan.p50) / 1_000.0,
+    );
+}
+
+fn print_summary(r: &ReclaimResult) {
+    eprintln!();
+    eprintln!("  Logical live size:  {}", fmt_mb(r.layout.logical_bytes()));
+    eprintln!("  Before deletes:     {}", fmt_mb(r.loaded_disk_bytes));
+    eprintln!(
+        "  Final:              {} ({:.2}x live, {:.2}x clean){}",
+        fmt_mb(r.last().disk_bytes),
+        r.amplification(r.last().disk_bytes),
+        r.vs_clean(),
+        if r.reclaimed() { "" } else { "  <- tombstones not reclaimed" }
+    );
+    eprintln!("  Deletes:            {} ops/sec", fmt_num(r.deletes_per_sec() as u64));
+}
+
+fn print_quiet(r: &ReclaimResult, mode: &DurabilityConfig) {
+    eprintln!(
+        "delete_reclaim {} keys ({}): final disk {:.2}x clean, scan p50 {:.2}ms vs {:.2}ms clean",
+        fmt_num(r.layout.keys as u64),
+        mode.label(),
+        r.vs_clean(),
+        duration_us(r.last().scan.p50) / 1_000.0,
+        duration_us(r.clean.scan.p50) / 1_000.0,
+    );
+}
+
+fn print_csv_header() {
+    println!("\"durability\",\"keys\",\"probe\",\"disk_bytes\",\"get_p50_us\",\"get_deleted_p50_us\",\"scan_p50_us\"");
+}
+
+fn print_csv_rows(r: &ReclaimResult, mode: &DurabilityConfig) {
+    for p in std::iter::once(&r.clean).chain(&r.probes) {
+        println!(
+            "{},{},{},{},{:.3},{:.3},{:.3}",
+            mode.label(),
+            r.layout.keys,
+            p.label,
+            p.disk_bytes,
+            duration_us(p.get.p50),
+            duration_us(p.get_deleted.p50),
+            duration_us(p.scan.p50),
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    durability: Vec<DurabilityConfig>,
+    keys: usize,
+    delete_pct: usize,
+    observe_secs: u64,
+    interval_secs: u64,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        durability: DISK_MODES.to_vec(),
+        keys: DEFAULT_KEYS,
+        delete_pct: DEFAULT_DELETE_PCT,
+        observe_secs: DEFAULT_OBSERVE_SECS,
+        interval_secs: DEFAULT_INTERVAL_SECS,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--keys" => config.keys = args.parse_value::<usize>().max(100),
+            "--delete-pct" => {
+                config.delete_pct = args.parse_checked("a percentage from 1 to 99", |pct| (1..=99).contains(pct));
+            }
+            "--observe-secs" => config.observe_secs = args.parse_value(),
+            "--interval-secs" => config.interval_secs = args.parse_value::<u64>().max(1),
+            _ => args.unknown_flag(),
+        }
+    }
+    if matches!(args.durability, Some(DurabilityConfig::Cache)) {
+        eprintln!("Cache mode keeps nothing on disk, so there is no space to reclaim");
+        std::process::exit(1);
+    }
+    config.durability = args.durability_modes(DISK_MODES);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    print_hardware_info();
+
+    if !config.csv {
+        eprintln!("=== StrataDB Delete-Heavy Reclamation Benchmark ===");
+        eprintln!("Deletes most keys, then tracks disk size and read latency while tombstones are cleaned up.");
+        eprintln!();
+        eprintln!(
+            "Keys: {}, {}% deleted, {}B values, observed {}s every {}s",
+            fmt_num(config.keys as u64),
+            config.delete_pct,
+            VALUE_SIZE,
+            config.observe_secs,
+            config.interval_secs
+        );
+        eprintln!();
+    }
+
+    if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("space");
+    let layout = Layout {
+        keys: config.keys,
+        delete_pct: config.delete_pct,
+    };
+    let verbose = !config.csv && !config.quiet;
+
+    for mode in &config.durability {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("durability".into(), serde_json::json!(mode.label()));
+        guard_params.insert("keys".into(), serde_json::json!(config.keys));
+        guard_params.insert("delete_pct".into(), serde_json::json!(config.delete_pct));
+        let guard_name = format!("space/delete_reclaim/{}keys/{}", config.keys, mode.label());
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            if verbose {
+                eprintln!("--- durability: {} ---", mode.label());
+                print_table_header();
+            }
+            let r = run_reclaim(
+                *mode,
+                layout,
+                Duration::from_secs(config.observe_secs),
+                Duration::from_secs(config.interval_secs),
+                !verbose,
+            );
+            record_reclaim(recorder, &r, mode);
+
+            if config.csv {
+                print_csv_rows(&r, mode);
+            } else if config.quiet {
+                print_quiet(&r, mode);
+            } else {
+                print_probe(r.last());
+                print_probe(&r.clean);
+                print_summary(&r);
+                eprintln!();
+            }
+            for failure in r.failures() {
+                eprintln!("  Validation: FAIL ({})", failure);
+            }
+        });
+    }
+
+    if !config.csv {
+        eprintln!("=== Benchmark complete ===");
+    }
+    recorder.save_or_exit();
+}
@@ -0,0 +1,418 @@
+//! Read/Write Ratio Sweep for StrataDB
+//!
+//! YCSB's A (50% update), B (5%) and C (0%) are three points on one curve.
+//! This sweeps the update fraction from 0% to 100% in fixed steps over the
+//! same keyspace and key distribution, so the shape in between (where
+//! throughput falls off, where p99 jumps) is visible.
+//!
+//! Each point loads a fresh database with the same records, then replays the
+//! YCSB operation stream with that read/update mix.
+//!
+//! Run:    `cargo bench --bench rw_sweep`
+//! Quick:  `cargo bench --bench rw_sweep -- -q`
+//! Custom: `cargo bench --bench rw_sweep -- --step 5 --distribution uniform --records 1000000`
+//! CSV:    `cargo bench --bench rw_sweep -- --csv`
+
+#[allow(unused)]
+#[path = "../harness/mod.rs"]
+mod harness;
+
+#[allow(unused)]
+mod workloads;
+
+use harness::recorder::ResultRecorder;
+use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strata_benchmarks::fmt::{fmt_num, scale_label};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
+use stratadb::Value;
+use workloads::{ycsb_key, Distribution, OpStream, Operation, WorkloadSpec, RUN_SEED};
+
+// ---------------------------------------------------------------------------
+// Defaults
+// ---------------------------------------------------------------------------
+
+const DEFAULT_RECORDS: usize = 100_000;
+const DEFAULT_OPS: usize = 100_000;
+const DEFAULT_VALUE_SIZE: usize = 1000;
+const DEFAULT_STEP: u32 = 10;
+
+/// Width of the bars in the terminal plot.
+const PLOT_WIDTH: usize = 40;
+
+// ---------------------------------------------------------------------------
+// Sweep points
+// ---------------------------------------------------------------------------
+
+/// Update percentages from 0 to 100 inclusive.
+fn sweep_points(step: u32) -> Vec<u32> {
+    let mut points: Vec<u32> = (0..=100).step_by(step as usize).collect();
+    if points.last() != Some(&100) {
+        points.push(100);
+    }
+    points
+}
+
+/// A YCSB workload with only reads and updates.
+fn mix_workload(write_pct: u32, distribution: Distribution) -> WorkloadSpec {
+    let update = write_pct as f64 / 100.0;
+    WorkloadSpec {
+        name: "Read/Write Sweep",
+        label: 's',
+        description: "read/update mix at one point of the sweep",
+        read_proportion: 1.0 - update,
+        update_proportion: update,
+        insert_proportion: 0.0,
+        scan_proportion: 0.0,
+        rmw_proportion: 0.0,
+        distribution,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Measurement
+// ---------------------------------------------------------------------------
+
+struct LatencyStats {
+    count: usize,
+    avg: Duration,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+fn compute_stats(mut latencies: Vec<Duration>) -> Option<LatencyStats> {
+    if latencies.is_empty() {
+        return None;
+    }
+    latencies.sort_unstable();
+    let len = latencies.len();
+    let sum: Duration = latencies.iter().sum();
+    Some(LatencyStats {
+        count: len,
+        avg: sum / len as u32,
+        p50: latencies[len * 50 / 100],
+        p95: latencies[(len * 95 / 100).min(len - 1)],
+        p99: latencies[(len * 99 / 100).min(len - 1)],
+        min: latencies[0],
+        max: latencies[len - 1],
+    })
+}
+
+struct PointResult {
+    write_pct: u32,
+    ops_per_sec: f64,
+    overall: LatencyStats,
+    read: Option<LatencyStats>,
+    update: Option<LatencyStats>,
+}
+
+fn load(db: &BenchDb, records: usize, value_size: usize) {
+    let value = Value::Bytes(vec![0x42; value_size]);
+    for i in 0..records {
+        db.db.kv_put(&ycsb_key(i), value.clone()).unwrap();
+    }
+}
+
+fn run_point(db: &BenchDb, write_pct: u32, config: &Config) -> PointResult {
+    let workload = mix_workload(write_pct, config.distribution);
+    let update_value = Value::Bytes(vec![0x43; config.value_size]);
+    let mut reads = Vec::new();
+    let mut updates = Vec::new();
+
+    let wall_start = Instant::now();
+    for (op, idx) in OpStream::new(&workload, config.records, config.seed).take(config.ops) {
+        let key = ycsb_key(idx);
+        match op {
+            Operation::Read => {
+                let start = Instant::now();
+                let _ = db.db.kv_get(&key);
+                reads.push(start.elapsed());
+            }
+            Operation::Update => {
+                let start = Instant::now();
+                db.db.kv_put(&key, update_value.clone()).unwrap();
+                updates.push(start.elapsed());
+            }
+            other => unreachable!("sweep mixes only reads and updates, got {:?}", other),
+        }
+    }
+    let wall_elapsed = wall_start.elapsed();
+
+    let mut all = Vec::with_capacity(reads.len() + updates.len());
+    all.extend_from_slice(&reads);
+    all.extend_from_slice(&updates);
+    PointResult {
+        write_pct,
+        ops_per_sec: config.ops as f64 / wall_elapsed.as_secs_f64(),
+        overall: compute_stats(all).expect("sweep point ran no operations"),
+        read: compute_stats(reads),
+        update: compute_stats(updates),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+fn duration_us(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000.0
+}
+
+fn p99_us(s: &Option<LatencyStats>) -> String {
+    s.as_ref()
+        .map(|s| format!("{:.1}", duration_us(s.p99)))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn print_table_header() {
+    eprintln!(
+        "  {:>7}  {:>10}  {:>9}  {:>9}  {:>11}  {:>13}",
+        "write%", "ops/sec", "p50 (us)", "p99 (us)", "read p99", "update p99"
+    );
+    eprintln!("  {}", "-".repeat(68));
+}
+
+fn print_table_row(r: &PointResult) {
+    eprintln!(
+        "  {:>6}%  {:>10}  {:>9.1}  {:>9.1}  {:>11}  {:>13}",
+        r.write_pct,
+        fmt_num(r.ops_per_sec as u64),
+        duration_us(r.overall.p50),
+        duration_us(r.overall.p99),
+        p99_us(&r.read),
+        p99_us(&r.update),
+    );
+}
+
+/// One horizontal bar per sweep point, scaled to the largest value.
+fn print_plot(title: &str, unit: &str, results: &[PointResult], value: impl Fn(&PointResult) -> f64) {
+    let max = results.iter().map(&value).fold(0.0, f64::max);
+    eprintln!("  {}", title);
+    for r in results {
+        let v = value(r);
+        let len = if max > 0.0 {
+            ((v / max) * PLOT_WIDTH as f64).round() as usize
+        } else {
+            0
+        };
+        eprintln!(
+            "  {:>4}% |{:<width$}| {:.1} {}",
+            r.write_pct,
+            "#".repeat(len),
+            v,
+            unit,
+            width = PLOT_WIDTH
+        );
+    }
+    eprintln!();
+}
+
+fn print_quiet(r: &PointResult) {
+    eprintln!(
+        "write-{}%: {} ops/s, p50={:.1}us, p99={:.1}us",
+        r.write_pct,
+        fmt_num(r.ops_per_sec as u64),
+        duration_us(r.overall.p50),
+        duration_us(r.overall.p99),
+    );
+}
+
+fn print_csv_header() {
+    println!("write_pct,ops_sec,p50_us,p95_us,p99_us,max_us,read_p99_us,update_p99_us");
+}
+
+fn print_csv_row(r: &PointResult) {
+    let opt = |s: &Option<LatencyStats>| {
+        s.as_ref()
+            .map(|s| format!("{:.1}", duration_us(s.p99)))
+            .unwrap_or_default()
+    };
+    println!(
+        "{},{:.2},{:.1},{:.1},{:.1},{:.1},{},{}",
+        r.write_pct,
+        r.ops_per_sec,
+        duration_us(r.overall.p50),
+        duration_us(r.overall.p95),
+        duration_us(r.overall.p99),
+        duration_us(r.overall.max),
+        opt(&r.read),
+        opt(&r.update),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// JSON recording
+// ---------------------------------------------------------------------------
+
+fn benchmark_name(write_pct: u32, config: &Config) -> String {
+    format!(
+        "ycsb/rw_sweep/w{}/{}-{}-{}",
+        write_pct,
+        config.distribution.label(),
+        scale_label(config.records as u64),
+        config.durability.label()
+    )
+}
+
+fn record_point(recorder: &mut ResultRecorder, config: &Config, r: &PointResult) {
+    let mut params = HashMap::new();
+    params.insert("write_pct".into(), serde_json::json!(r.write_pct));
+    params.insert("record_count".into(), serde_json::json!(config.records));
+    params.insert("operation_count".into(), serde_json::json!(config.ops));
+    params.insert("value_size".into(), serde_json::json!(config.value_size));
+    params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    params.insert("distribution".into(), serde_json::json!(config.distribution.label()));
+    params.insert("seed".into(), serde_json::json!(config.seed));
+    for (name, stats) in [("read", &r.read), ("update", &r.update)] {
+        if let Some(s) = stats {
+            params.insert(format!("{}_count", name), serde_json::json!(s.count));
+            params.insert(format!("{}_p50_ns", name), serde_json::json!(s.p50.as_nanos() as u64));
+            params.insert(format!("{}_p99_ns", name), serde_json::json!(s.p99.as_nanos() as u64));
+        }
+    }
+
+    recorder.record(BenchmarkResult {
+        benchmark: benchmark_name(r.write_pct, config),
+        category: "ycsb".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(r.ops_per_sec),
+            p50_ns: Some(r.overall.p50.as_nanos() as u64),
+            p95_ns: Some(r.overall.p95.as_nanos() as u64),
+            p99_ns: Some(r.overall.p99.as_nanos() as u64),
+            min_ns: Some(r.overall.min.as_nanos() as u64),
+            max_ns: Some(r.overall.max.as_nanos() as u64),
+            avg_ns: Some(r.overall.avg.as_nanos() as u64),
+            samples: Some(r.overall.count as u64),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+}
+
+// ---------------------------------------------------------------------------
+// CLI parsing
+// ---------------------------------------------------------------------------
+
+struct Config {
+    records: usize,
+    ops: usize,
+    step: u32,
+    distribution: Distribution,
+    durability: DurabilityConfig,
+    value_size: usize,
+    seed: u64,
+    csv: bool,
+    quiet: bool,
+}
+
+fn parse_args() -> Config {
+    let mut args = harness::cli::Args::parse();
+    let mut config = Config {
+        records: DEFAULT_RECORDS,
+        ops: DEFAULT_OPS,
+        step: DEFAULT_STEP,
+        distribution: Distribution::Zipfian,
+        durability: DurabilityConfig::Standard,
+        value_size: DEFAULT_VALUE_SIZE,
+        seed: RUN_SEED,
+        csv: false,
+        quiet: false,
+    };
+
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--records" => config.records = args.parse_value::<usize>().max(1),
+            "--ops" => config.ops = args.parse_value::<usize>().max(1),
+            "--step" => config.step = args.parse_checked("a percentage from 1 to 100", |pct| (1..=100).contains(pct)),
+            "--distribution" => {
+                config.distribution = match args.value().as_str() {
+                    "uniform" => Distribution::Uniform,
+                    "latest" => Distribution::Latest,
+                    "zipfian" => Distribution::Zipfian,
+                    other => harness::cli::usage_error(&format!(
+                        "--distribution expects zipfian, uniform or latest; got {}",
+                        other
+                    )),
+                };
+            }
+            "--value-size" => config.value_size = args.parse_value(),
+            _ => args.unknown_flag(),
+        }
+    }
+    config.durability = args.durability_or(DurabilityConfig::Standard);
+    config.seed = args.seed_or(RUN_SEED);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    let verbose = !config.csv && !config.quiet;
+    print_hardware_info();
+
+    let points = sweep_points(config.step);
+    if verbose {
+        eprintln!("=== StrataDB Read/Write Ratio Sweep ===");
+        eprintln!(
+            "  records={}  ops={}  value={}B  distribution={}  durability={}  points={}",
+            fmt_num(config.records as u64),
+            fmt_num(config.ops as u64),
+            config.value_size,
+            config.distribution.label(),
+            config.durability.label(),
+            points.len(),
+        );
+        eprintln!();
+        print_table_header();
+    } else if config.csv {
+        print_csv_header();
+    }
+
+    let mut recorder = ResultRecorder::new("ycsb");
+    let mut results = Vec::new();
+
+    for &write_pct in &points {
+        let mut guard_params = HashMap::new();
+        guard_params.insert("write_pct".into(), serde_json::json!(write_pct));
+        guard_params.insert("records".into(), serde_json::json!(config.records));
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
+        let result = recorder.guard(&benchmark_name(write_pct, &config), guard_params, |recorder| {
+            let db = create_db(config.durability);
+            load(&db, config.records, config.value_size);
+            let r = run_point(&db, write_pct, &config);
+
+            if config.csv {
+                print_csv_row(&r);
+            } else if config.quiet {
+                print_quiet(&r);
+            } else {
+                print_table_row(&r);
+            }
+
+            record_point(recorder, &config, &r);
+            r
+        });
+        results.extend(result);
+    }
+
+    if verbose && !results.is_empty() {
+        eprintln!();
+        print_plot("Throughput", "ops/s", &results, |r| r.ops_per_sec);
+        print_plot("p99 latency", "us", &results, |r| duration_us(r.overall.p99));
+        eprintln!("=== Read/write sweep complete ===");
+    }
+    recorder.save_or_exit();
+}
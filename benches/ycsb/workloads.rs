@@ -374,6 +374,52 @@ impl KeyChooser {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Operation stream
+// ---------------------------------------------------------------------------
+
+/// Seed for the run phase, fixed so every machine replays the same operations.
+pub const RUN_SEED: u64 = 0xABCD_2026;
+
+/// The run phase's operation sequence: each item is an operation and the
+/// index of the key it targets. Inserts take the next unused index and widen
+/// the `Latest` distribution to cover it. Never ends; `take` what you need.
+pub struct OpStream<'a> {
+    workload: &'a WorkloadSpec,
+    rng: FastRng,
+    chooser: KeyChooser,
+    next_insert: usize,
+}
+
+impl<'a> OpStream<'a> {
+    pub fn new(workload: &'a WorkloadSpec, record_count: usize, seed: u64) -> Self {
+        Self {
+            workload,
+            rng: FastRng::new(seed),
+            chooser: KeyChooser::new(workload.distribution, record_count),
+            next_insert: record_count,
+        }
+    }
+}
+
+impl Iterator for OpStream<'_> {
+    type Item = (Operation, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let op = self.workload.choose_operation(self.rng.next_f64());
+        let idx = match op {
+            Operation::Insert => {
+                let idx = self.next_insert;
+                self.next_insert += 1;
+                self.chooser.set_max_key(self.next_insert);
+                idx
+            }
+            _ => self.chooser.next(&mut self.rng),
+        };
+        Some((op, idx))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -8,6 +8,14 @@
 //! Single: `cargo bench --bench ycsb -- --workload a`
 //! Custom: `cargo bench --bench ycsb -- --records 1000000 --ops 1000000`
 //! CSV:    `cargo bench --bench ycsb -- --csv`
+//! Soak:   `cargo bench --bench ycsb -- --workload a --duration 2h`
+//!
+//! With `--duration`, each workload's run phase repeats until the duration
+//! runs out instead of stopping after `--ops`, and per-minute throughput,
+//! percentiles, RSS and on-disk size are streamed to
+//! `results/soak-<bench>-<time>.jsonl` (see `harness/soak.rs`). Options:
+//! `--soak-interval 5m` for the window length, `--soak-out FILE` for the
+//! stream (`.csv` for CSV).
 
 #[allow(unused)]
 #[path = "../harness/mod.rs"]
@@ -15,15 +23,18 @@ mod harness;
 
 mod workloads;
 
+use harness::hdr::LatencyHistogram;
 use harness::recorder::ResultRecorder;
-use harness::{create_db, print_hardware_info, BenchDb, DurabilityConfig};
+use harness::soak::{self, Gauges, Soak, Window};
+use harness::{create_db, dir_size_bytes, print_hardware_info, read_rss_bytes, BenchDb, DurabilityConfig};
 use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
-use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult};
+use strata_benchmarks::fmt::{fmt_num, scale_label};
+use strata_benchmarks::schema::{BenchmarkMetrics, BenchmarkResult, ResultStatus};
 use stratadb::Value;
-use workloads::{
-    ycsb_key, FastRng, KeyChooser, Operation, WorkloadSpec, workload_by_label,
-};
+use workloads::{ycsb_key, workload_by_label, OpStream, Operation, WorkloadSpec, RUN_SEED};
 
 // ---------------------------------------------------------------------------
 // Defaults
@@ -32,6 +43,8 @@ use workloads::{
 const DEFAULT_RECORDS: usize = 100_000;
 const DEFAULT_OPS: usize = 100_000;
 const DEFAULT_VALUE_SIZE: usize = 1000;
+/// Operations between checks of the soak clock.
+const SOAK_CHECK_EVERY: u64 = 64;
 
 // ---------------------------------------------------------------------------
 // Per-operation latency collection
@@ -39,27 +52,39 @@ const DEFAULT_VALUE_SIZE: usize = 1000;
 
 #[derive(Default)]
 struct OpLatencies {
-    read: Vec<Duration>,
-    update: Vec<Duration>,
-    insert: Vec<Duration>,
-    scan: Vec<Duration>,
-    rmw: Vec<Duration>,
+    read: LatencyHistogram,
+    update: LatencyHistogram,
+    insert: LatencyHistogram,
+    scan: LatencyHistogram,
+    rmw: LatencyHistogram,
 }
 
 impl OpLatencies {
-    fn all(&self) -> Vec<Duration> {
-        let mut all = Vec::with_capacity(
-            self.read.len()
-                + self.update.len()
-                + self.insert.len()
-                + self.scan.len()
-                + self.rmw.len(),
-        );
-        all.extend_from_slice(&self.read);
-        all.extend_from_slice(&self.update);
-        all.extend_from_slice(&self.insert);
-        all.extend_from_slice(&self.scan);
-        all.extend_from_slice(&self.rmw);
+    fn push(&mut self, op: Operation, latency: Duration) {
+        match op {
+            Operation::Read => self.read.record(latency),
+            Operation::Update => self.update.record(latency),
+            Operation::Insert => self.insert.record(latency),
+            Operation::Scan => self.scan.record(latency),
+            Operation::ReadModifyWrite => self.rmw.record(latency),
+        }
+    }
+
+    fn by_op(&self) -> [(&'static str, &LatencyHistogram); 5] {
+        [
+            ("read", &self.read),
+            ("update", &self.update),
+            ("insert", &self.insert),
+            ("scan", &self.scan),
+            ("rmw", &self.rmw),
+        ]
+    }
+
+    fn all(&self) -> LatencyHistogram {
+        let mut all = LatencyHistogram::new();
+        for (_, h) in self.by_op() {
+            all.merge(h);
+        }
         all
     }
 }
@@ -71,28 +96,30 @@ struct LatencyStats {
     p50: Duration,
     p95: Duration,
     p99: Duration,
+    p999: Duration,
+    p9999: Duration,
     min: Duration,
     max: Duration,
 }
 
-fn compute_stats(mut latencies: Vec<Duration>, wall_elapsed: Option<Duration>) -> Option<LatencyStats> {
+fn compute_stats(latencies: &LatencyHistogram, wall_elapsed: Option<Duration>) -> Option<LatencyStats> {
     if latencies.is_empty() {
         return None;
     }
-    latencies.sort_unstable();
-    let len = latencies.len();
-    let sum: Duration = latencies.iter().sum();
-    let elapsed = wall_elapsed.unwrap_or(sum);
+    let len = latencies.len() as usize;
+    let elapsed = wall_elapsed.unwrap_or(latencies.total());
 
     Some(LatencyStats {
         count: len,
         ops_per_sec: len as f64 / elapsed.as_secs_f64(),
-        avg: sum / len as u32,
-        p50: latencies[len * 50 / 100],
-        p95: latencies[(len * 95 / 100).min(len - 1)],
-        p99: latencies[(len * 99 / 100).min(len - 1)],
-        min: latencies[0],
-        max: latencies[len - 1],
+        avg: latencies.mean(),
+        p50: latencies.p50(),
+        p95: latencies.p95(),
+        p99: latencies.p99(),
+        p999: latencies.p999(),
+        p9999: latencies.p9999(),
+        min: latencies.min(),
+        max: latencies.max(),
     })
 }
 
@@ -127,6 +154,53 @@ fn run_load_phase(db: &BenchDb, record_count: usize, value_size: usize) -> LoadR
 // Run phase
 // ---------------------------------------------------------------------------
 
+fn op_label(op: Operation) -> &'static str {
+    match op {
+        Operation::Read => "read",
+        Operation::Update => "update",
+        Operation::Insert => "insert",
+        Operation::Scan => "scan",
+        Operation::ReadModifyWrite => "rmw",
+    }
+}
+
+/// Run one operation and return how long it took.
+fn execute(db: &BenchDb, op: Operation, idx: usize, value: &Value, update_value: &Value) -> Duration {
+    match op {
+        Operation::Read => {
+            let key = ycsb_key(idx);
+            let start = Instant::now();
+            let _ = db.db.kv_get(&key);
+            start.elapsed()
+        }
+        Operation::Update => {
+            let key = ycsb_key(idx);
+            let start = Instant::now();
+            db.db.kv_put(&key, update_value.clone()).unwrap();
+            start.elapsed()
+        }
+        Operation::Insert => {
+            let key = ycsb_key(idx);
+            let start = Instant::now();
+            db.db.kv_put(&key, value.clone()).unwrap();
+            start.elapsed()
+        }
+        Operation::Scan => {
+            let prefix = format!("user{:010}", idx);
+            let start = Instant::now();
+            let _ = db.db.kv_list(Some(&prefix));
+            start.elapsed()
+        }
+        Operation::ReadModifyWrite => {
+            let key = ycsb_key(idx);
+            let start = Instant::now();
+            let _ = db.db.kv_get(&key);
+            db.db.kv_put(&key, update_value.clone()).unwrap();
+            start.elapsed()
+        }
+    }
+}
+
 struct RunResult {
     latencies: OpLatencies,
     wall_elapsed: Duration,
@@ -138,11 +212,8 @@ fn run_workload_phase(
     record_count: usize,
     operation_count: usize,
     value_size: usize,
+    seed: u64,
 ) -> RunResult {
-    let mut rng = FastRng::new(0xABCD_2026);
-    let mut key_chooser = KeyChooser::new(workload.distribution, record_count);
-    let mut insert_counter = record_count; // next key to insert
-
     let value = Value::Bytes(vec![0x42; value_size]);
     let update_value = Value::Bytes(vec![0x43; value_size]);
 
@@ -150,48 +221,8 @@ fn run_workload_phase(
 
     let wall_start = Instant::now();
 
-    for _ in 0..operation_count {
-        let op = workload.choose_operation(rng.next_f64());
-
-        match op {
-            Operation::Read => {
-                let idx = key_chooser.next(&mut rng);
-                let key = ycsb_key(idx);
-                let start = Instant::now();
-                let _ = db.db.kv_get(&key);
-                latencies.read.push(start.elapsed());
-            }
-            Operation::Update => {
-                let idx = key_chooser.next(&mut rng);
-                let key = ycsb_key(idx);
-                let start = Instant::now();
-                db.db.kv_put(&key, update_value.clone()).unwrap();
-                latencies.update.push(start.elapsed());
-            }
-            Operation::Insert => {
-                let key = ycsb_key(insert_counter);
-                insert_counter += 1;
-                key_chooser.set_max_key(insert_counter);
-                let start = Instant::now();
-                db.db.kv_put(&key, value.clone()).unwrap();
-                latencies.insert.push(start.elapsed());
-            }
-            Operation::Scan => {
-                let idx = key_chooser.next(&mut rng);
-                let prefix = format!("user{:010}", idx);
-                let start = Instant::now();
-                let _ = db.db.kv_list(Some(&prefix));
-                latencies.scan.push(start.elapsed());
-            }
-            Operation::ReadModifyWrite => {
-                let idx = key_chooser.next(&mut rng);
-                let key = ycsb_key(idx);
-                let start = Instant::now();
-                let _ = db.db.kv_get(&key);
-                db.db.kv_put(&key, update_value.clone()).unwrap();
-                latencies.rmw.push(start.elapsed());
-            }
-        }
+    for (op, idx) in OpStream::new(workload, record_count, seed).take(operation_count) {
+        latencies.push(op, execute(db, op, idx, &value, &update_value));
     }
 
     let wall_elapsed = wall_start.elapsed();
@@ -202,23 +233,64 @@ fn run_workload_phase(
 }
 
 // ---------------------------------------------------------------------------
-// Output helpers
+// Soak phase
 // ---------------------------------------------------------------------------
 
-fn duration_ms(d: Duration) -> f64 {
-    d.as_nanos() as f64 / 1_000_000.0
-}
+/// Run the workload until the soak's duration runs out. Inserts keep
+/// growing the keyspace, as in a long-lived deployment.
+fn run_soak_phase(db: &BenchDb, workload: &WorkloadSpec, config: &Config, mut soak: Soak) -> io::Result<Vec<Window>> {
+    let value = Value::Bytes(vec![0x42; config.value_size]);
+    let update_value = Value::Bytes(vec![0x43; config.value_size]);
+    let gauges = |inserted: u64| Gauges {
+        entries: config.records as u64 + inserted,
+        rss_bytes: read_rss_bytes(),
+        disk_bytes: db.data_dir().map(dir_size_bytes),
+    };
 
-fn fmt_num(n: u64) -> String {
-    let s = n.to_string();
-    let mut result = String::new();
-    for (i, c) in s.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
+    let mut stream = OpStream::new(workload, config.records, config.seed);
+    let mut inserted = 0u64;
+    let mut n = 0u64;
+    loop {
+        if n.is_multiple_of(SOAK_CHECK_EVERY) {
+            if !soak.running() {
+                break;
+            }
+            if let Some(w) = soak.tick(|| gauges(inserted))? {
+                if !config.csv && !config.quiet {
+                    eprintln!("{}", w.progress_line());
+                }
+            }
         }
-        result.push(c);
+        let (op, idx) = stream.next().expect("OpStream never ends");
+        soak.record(op_label(op), execute(db, op, idx, &value, &update_value));
+        inserted += (op == Operation::Insert) as u64;
+        n += 1;
     }
-    result.chars().rev().collect()
+    soak.finish(gauges(inserted))
+}
+
+/// Where the soak stream of workload `label` goes. With several workloads
+/// the label is added to `--soak-out`'s file name so they don't overwrite
+/// each other.
+fn soak_out(config: &Config, label: char) -> Option<PathBuf> {
+    let out = config.soak_out.as_ref()?;
+    if config.workloads.len() == 1 {
+        return Some(out.clone());
+    }
+    let stem = out.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let name = match out.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, label, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, label),
+    };
+    Some(out.with_file_name(name))
+}
+
+// ---------------------------------------------------------------------------
+// Output helpers
+// ---------------------------------------------------------------------------
+
+fn duration_ms(d: Duration) -> f64 {
+    d.as_nanos() as f64 / 1_000_000.0
 }
 
 fn print_workload_header(workload: &WorkloadSpec, config: &Config) {
@@ -229,10 +301,14 @@ fn print_workload_header(workload: &WorkloadSpec, config: &Config) {
         workload.name,
         workload.mix_label(),
     );
+    let run = match config.duration {
+        Some(d) => format!("duration={}", soak::fmt_span(d)),
+        None => format!("ops={}", fmt_num(config.ops as u64)),
+    };
     eprintln!(
-        "  records={}  ops={}  value={}B  durability={}",
+        "  records={}  {}  value={}B  durability={}",
         fmt_num(config.records as u64),
-        fmt_num(config.ops as u64),
+        run,
         config.value_size,
         config.durability.label(),
     );
@@ -251,48 +327,41 @@ fn print_load_result(load: &LoadResult) {
 
 fn print_run_table(latencies: &OpLatencies, wall_elapsed: Duration) {
     eprintln!(
-        "  {:<14} {:>8}  {:>10}  {:>9}  {:>9}  {:>9}  {:>9}",
-        "Operation", "count", "ops/sec", "p50", "p95", "p99", "max"
+        "  {:<14} {:>8}  {:>10}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}",
+        "Operation", "count", "ops/sec", "p50", "p95", "p99", "p99.9", "max"
     );
     eprintln!(
         "  {}",
-        "-".repeat(74)
+        "-".repeat(85)
     );
 
     // Overall
-    let all = latencies.all();
-    if let Some(s) = compute_stats(all, Some(wall_elapsed)) {
+    if let Some(s) = compute_stats(&latencies.all(), Some(wall_elapsed)) {
         eprintln!(
-            "  {:<14} {:>8}  {:>10}  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms",
+            "  {:<14} {:>8}  {:>10}  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms",
             "overall",
             fmt_num(s.count as u64),
             fmt_num(s.ops_per_sec as u64),
             duration_ms(s.p50),
             duration_ms(s.p95),
             duration_ms(s.p99),
+            duration_ms(s.p999),
             duration_ms(s.max),
         );
     }
 
     // Per-op breakdown
-    let ops: &[(&str, &[Duration])] = &[
-        ("read", &latencies.read),
-        ("update", &latencies.update),
-        ("insert", &latencies.insert),
-        ("scan", &latencies.scan),
-        ("rmw", &latencies.rmw),
-    ];
-
-    for (name, lats) in ops {
-        if let Some(s) = compute_stats(lats.to_vec(), None) {
+    for (name, lats) in latencies.by_op() {
+        if let Some(s) = compute_stats(lats, None) {
             eprintln!(
-                "  {:<14} {:>8}  {:>10}  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms",
+                "  {:<14} {:>8}  {:>10}  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms  {:>8.3}ms",
                 name,
                 fmt_num(s.count as u64),
                 "",
                 duration_ms(s.p50),
                 duration_ms(s.p95),
                 duration_ms(s.p99),
+                duration_ms(s.p999),
                 duration_ms(s.max),
             );
         }
@@ -313,20 +382,20 @@ fn print_quiet(workload: &WorkloadSpec, overall: &LatencyStats, load: &LoadResul
 
 fn print_csv_header() {
     println!(
-        "\"workload\",\"phase\",\"operation\",\"count\",\"ops_sec\",\"p50_ms\",\"p95_ms\",\"p99_ms\",\"max_ms\""
+        "\"workload\",\"phase\",\"operation\",\"count\",\"ops_sec\",\"p50_ms\",\"p95_ms\",\"p99_ms\",\"p999_ms\",\"max_ms\""
     );
 }
 
 fn print_csv_load(workload: &WorkloadSpec, load: &LoadResult) {
     println!(
-        "\"{}\",\"load\",\"insert\",{},{:.2},,,",
+        "\"{}\",\"load\",\"insert\",{},{:.2},,,,,",
         workload.label, load.record_count, load.ops_per_sec,
     );
 }
 
 fn print_csv_run(workload: &WorkloadSpec, name: &str, stats: &LatencyStats) {
     println!(
-        "\"{}\",\"run\",\"{}\",{},{:.2},{:.3},{:.3},{:.3},{:.3}",
+        "\"{}\",\"run\",\"{}\",{},{:.2},{:.3},{:.3},{:.3},{:.3},{:.3}",
         workload.label,
         name,
         stats.count,
@@ -334,6 +403,7 @@ fn print_csv_run(workload: &WorkloadSpec, name: &str, stats: &LatencyStats) {
         duration_ms(stats.p50),
         duration_ms(stats.p95),
         duration_ms(stats.p99),
+        duration_ms(stats.p999),
         duration_ms(stats.max),
     );
 }
@@ -349,8 +419,7 @@ fn record_workload_result(
     load: &LoadResult,
     run: &RunResult,
 ) {
-    let all_latencies = run.latencies.all();
-    let overall = match compute_stats(all_latencies, Some(run.wall_elapsed)) {
+    let overall = match compute_stats(&run.latencies.all(), Some(run.wall_elapsed)) {
         Some(s) => s,
         None => return,
     };
@@ -363,6 +432,7 @@ fn record_workload_result(
     params.insert("value_size".into(), serde_json::json!(config.value_size));
     params.insert("durability".into(), serde_json::json!(config.durability.label()));
     params.insert("distribution".into(), serde_json::json!(workload.distribution.label()));
+    params.insert("seed".into(), serde_json::json!(config.seed));
     params.insert("auto_embed".into(), serde_json::json!(!config.no_embed));
     params.insert(
         "load_ops_per_sec".into(),
@@ -374,15 +444,8 @@ fn record_workload_result(
     );
 
     // Per-op type stats in parameters
-    let op_vecs: &[(&str, &[Duration])] = &[
-        ("read", &run.latencies.read),
-        ("update", &run.latencies.update),
-        ("insert", &run.latencies.insert),
-        ("scan", &run.latencies.scan),
-        ("rmw", &run.latencies.rmw),
-    ];
-    for (name, lats) in op_vecs {
-        if let Some(s) = compute_stats(lats.to_vec(), None) {
+    for (name, lats) in run.latencies.by_op() {
+        if let Some(s) = compute_stats(lats, None) {
             params.insert(
                 format!("{}_count", name),
                 serde_json::json!(s.count),
@@ -399,6 +462,10 @@ fn record_workload_result(
                 format!("{}_p99_ns", name),
                 serde_json::json!(s.p99.as_nanos() as u64),
             );
+            params.insert(
+                format!("{}_p999_ns", name),
+                serde_json::json!(s.p999.as_nanos() as u64),
+            );
             params.insert(
                 format!("{}_avg_ns", name),
                 serde_json::json!(s.avg.as_nanos() as u64),
@@ -406,11 +473,7 @@ fn record_workload_result(
         }
     }
 
-    let record_label = if config.records >= 1_000_000 {
-        format!("{}m", config.records / 1_000_000)
-    } else {
-        format!("{}k", config.records / 1_000)
-    };
+    let record_label = scale_label(config.records as u64);
 
     recorder.record(BenchmarkResult {
         benchmark: format!(
@@ -426,15 +489,81 @@ fn record_workload_result(
             p50_ns: Some(overall.p50.as_nanos() as u64),
             p95_ns: Some(overall.p95.as_nanos() as u64),
             p99_ns: Some(overall.p99.as_nanos() as u64),
+            p999_ns: Some(overall.p999.as_nanos() as u64),
+            p9999_ns: Some(overall.p9999.as_nanos() as u64),
             min_ns: Some(overall.min.as_nanos() as u64),
             max_ns: Some(overall.max.as_nanos() as u64),
             avg_ns: Some(overall.avg.as_nanos() as u64),
             samples: Some(overall.count as u64),
             ..Default::default()
         },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
     });
 }
 
+/// Record a soak run. Throughput is over the whole run; percentiles are
+/// those of the last full window, where degradation would have led.
+fn record_soak_result(
+    recorder: &mut ResultRecorder,
+    workload: &WorkloadSpec,
+    config: &Config,
+    load: &LoadResult,
+    series: &std::path::Path,
+    windows: &[Window],
+) {
+    let Some(last) = soak::last_full(windows) else {
+        return;
+    };
+    let (ops, elapsed) = soak::totals(windows);
+    let name = soak_name(workload.label, config);
+
+    let mut params = HashMap::new();
+    params.insert("workload".into(), serde_json::json!(format!("{}", workload.label)));
+    params.insert("workload_name".into(), serde_json::json!(workload.name));
+    params.insert("record_count".into(), serde_json::json!(config.records));
+    params.insert("value_size".into(), serde_json::json!(config.value_size));
+    params.insert("durability".into(), serde_json::json!(config.durability.label()));
+    params.insert("distribution".into(), serde_json::json!(workload.distribution.label()));
+    params.insert("seed".into(), serde_json::json!(config.seed));
+    params.insert("auto_embed".into(), serde_json::json!(!config.no_embed));
+    params.insert("load_ops_per_sec".into(), serde_json::json!(load.ops_per_sec));
+    params.insert("final_entries".into(), serde_json::json!(last.gauges.entries));
+    for (key, value) in soak::summary_parameters(windows, config.soak_interval, "all") {
+        params.insert(key.into(), value);
+    }
+
+    let all = last.all();
+    recorder.record(BenchmarkResult {
+        benchmark: name.clone(),
+        category: "ycsb".to_string(),
+        parameters: params,
+        metrics: BenchmarkMetrics {
+            ops_per_sec: Some(ops as f64 / elapsed.as_secs_f64().max(1e-9)),
+            p50_ns: Some(all.p50_ns),
+            p95_ns: Some(all.p95_ns),
+            p99_ns: Some(all.p99_ns),
+            max_ns: Some(all.max_ns),
+            samples: Some(ops),
+            ..Default::default()
+        },
+        artifacts: Vec::new(),
+        spikes: Vec::new(),
+        status: ResultStatus::Success,
+    });
+    recorder.attach_artifact(&name, "soak-series", &soak::artifact_path(series));
+}
+
+fn soak_name(label: char, config: &Config) -> String {
+    format!(
+        "ycsb/soak/workload-{}/{}-{}",
+        label,
+        scale_label(config.records as u64),
+        config.durability.label()
+    )
+}
+
 // ---------------------------------------------------------------------------
 // CLI parsing
 // ---------------------------------------------------------------------------
@@ -446,73 +575,63 @@ struct Config {
     ops: usize,
     durability: DurabilityConfig,
     value_size: usize,
+    /// Seed for the operation stream (`--seed`).
+    seed: u64,
     no_embed: bool,
+    /// Soak mode: run each workload for this long (`--duration`).
+    duration: Option<Duration>,
+    soak_interval: Duration,
+    soak_out: Option<PathBuf>,
     csv: bool,
     quiet: bool,
 }
 
 fn parse_args() -> Config {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args = harness::cli::Args::parse();
     let mut config = Config {
         workloads: vec!['a', 'b', 'c', 'd', 'e', 'f'],
         records: DEFAULT_RECORDS,
         ops: DEFAULT_OPS,
         durability: DurabilityConfig::Standard,
         value_size: DEFAULT_VALUE_SIZE,
+        seed: RUN_SEED,
         no_embed: false,
+        duration: None,
+        soak_interval: soak::DEFAULT_INTERVAL,
+        soak_out: None,
         csv: false,
         quiet: false,
     };
 
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
             "--workload" | "-w" => {
-                i += 1;
-                if i < args.len() {
-                    config.workloads = args[i]
-                        .split(',')
-                        .filter_map(|s| s.trim().chars().next())
-                        .map(|c| c.to_ascii_lowercase())
-                        .collect();
-                }
+                let v = args.value();
+                config.workloads = v
+                    .split(',')
+                    .filter_map(|s| s.trim().chars().next())
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect();
             }
-            "--records" => {
-                i += 1;
-                if i < args.len() {
-                    config.records = args[i].parse().unwrap_or(DEFAULT_RECORDS);
-                }
-            }
-            "--ops" => {
-                i += 1;
-                if i < args.len() {
-                    config.ops = args[i].parse().unwrap_or(DEFAULT_OPS);
-                }
-            }
-            "--durability" => {
-                i += 1;
-                if i < args.len() {
-                    config.durability = match args[i].as_str() {
-                        "cache" => DurabilityConfig::Cache,
-                        "standard" => DurabilityConfig::Standard,
-                        "always" => DurabilityConfig::Always,
-                        _ => DurabilityConfig::Standard,
-                    };
-                }
-            }
-            "--value-size" => {
-                i += 1;
-                if i < args.len() {
-                    config.value_size = args[i].parse().unwrap_or(DEFAULT_VALUE_SIZE);
-                }
+            "--records" => config.records = args.parse_value(),
+            "--ops" => config.ops = args.parse_value(),
+            "--value-size" => config.value_size = args.parse_value(),
+            "--soak-interval" => {
+                let v = args.value();
+                config.soak_interval = soak::parse_duration(&v).unwrap_or_else(|| {
+                    harness::cli::usage_error(&format!("--soak-interval expects a span like 30s or 5m; got {}", v))
+                });
             }
+            "--soak-out" => config.soak_out = Some(PathBuf::from(args.value())),
             "--no-embed" | "--raw" => config.no_embed = true,
-            "--csv" => config.csv = true,
-            "-q" => config.quiet = true,
-            _ => {}
+            _ => args.unknown_flag(),
         }
-        i += 1;
     }
+    config.durability = args.durability_or(DurabilityConfig::Standard);
+    config.duration = args.duration;
+    config.seed = args.seed_or(RUN_SEED);
+    config.csv = args.csv;
+    config.quiet = args.quiet;
 
     config
 }
@@ -535,6 +654,13 @@ fn main() {
             config.durability.label(),
             if config.no_embed { ", auto_embed=off" } else { "" },
         );
+        if let Some(d) = config.duration {
+            eprintln!(
+                "Soak: {} per workload, {} windows",
+                soak::fmt_span(d),
+                soak::fmt_span(config.soak_interval)
+            );
+        }
     }
 
     if config.csv {
@@ -544,72 +670,107 @@ fn main() {
     let mut recorder = ResultRecorder::new("ycsb");
 
     for &label in &config.workloads {
+        let guard_name = if config.duration.is_some() {
+            soak_name(label, &config)
+        } else {
+            format!(
+                "ycsb/workload-{}/{}-{}",
+                label,
+                scale_label(config.records as u64),
+                config.durability.label()
+            )
+        };
+        let mut guard_params = HashMap::new();
+        guard_params.insert("workload".into(), serde_json::json!(label));
+        guard_params.insert("records".into(), serde_json::json!(config.records));
+        guard_params.insert("durability".into(), serde_json::json!(config.durability.label()));
         let workload = match workload_by_label(label) {
             Some(w) => w,
             None => {
-                eprintln!("Unknown workload: '{}', skipping", label);
+                recorder.record_failure(
+                    &guard_name,
+                    guard_params,
+                    ResultStatus::Error {
+                        message: format!("unknown workload '{}'", label),
+                    },
+                );
                 continue;
             }
         };
 
-        // Create a fresh database for each workload
-        let db = create_db(config.durability);
-
-        // Disable search features for pure KV benchmarking
-        if config.no_embed {
-            db.db.config_set("auto_embed", "false").unwrap();
-        }
-
-        // --- Load phase ---
-        if !config.csv && !config.quiet {
-            print_workload_header(workload, &config);
-            eprint!("  Loading {} records...", fmt_num(config.records as u64));
-        }
+        recorder.guard(&guard_name, guard_params, |recorder| {
+            // Create a fresh database for each workload
+            let db = create_db(config.durability);
 
-        let load = run_load_phase(&db, config.records, config.value_size);
+            // Disable search features for pure KV benchmarking
+            if config.no_embed {
+                db.db.config_set("auto_embed", "false").unwrap();
+            }
 
-        if !config.csv && !config.quiet {
-            eprintln!(" done");
-            print_load_result(&load);
-        }
+            // --- Load phase ---
+            if !config.csv && !config.quiet {
+                print_workload_header(workload, &config);
+                eprint!("  Loading {} records...", fmt_num(config.records as u64));
+            }
 
-        // --- Run phase ---
-        let run = run_workload_phase(&db, workload, config.records, config.ops, config.value_size);
+            let load = run_load_phase(&db, config.records, config.value_size);
 
-        // --- Output ---
-        if config.csv {
-            print_csv_load(workload, &load);
-            let all = run.latencies.all();
-            if let Some(s) = compute_stats(all, Some(run.wall_elapsed)) {
-                print_csv_run(workload, "overall", &s);
+            if !config.csv && !config.quiet {
+                eprintln!(" done");
+                print_load_result(&load);
             }
-            let ops: &[(&str, &[Duration])] = &[
-                ("read", &run.latencies.read),
-                ("update", &run.latencies.update),
-                ("insert", &run.latencies.insert),
-                ("scan", &run.latencies.scan),
-                ("rmw", &run.latencies.rmw),
-            ];
-            for (name, lats) in ops {
-                if let Some(s) = compute_stats(lats.to_vec(), None) {
-                    print_csv_run(workload, name, &s);
+
+            // --- Soak phase ---
+            if let Some(duration) = config.duration {
+                if config.csv {
+                    print_csv_load(workload, &load);
                 }
+                let name = soak_name(workload.label, &config);
+                let out = soak_out(&config, workload.label);
+                let soak = Soak::create(&name, duration, config.soak_interval, out.as_deref())
+                    .unwrap_or_else(|e| panic!("cannot create soak output: {}", e));
+                let series = soak.path().to_path_buf();
+                if !config.csv && !config.quiet {
+                    eprintln!("  Soaking for {}, series in {}", soak::fmt_span(duration), series.display());
+                }
+                let windows = run_soak_phase(&db, workload, &config, soak)
+                    .unwrap_or_else(|e| panic!("cannot write {}: {}", series.display(), e));
+                if let (true, Some(last)) = (config.quiet, windows.last()) {
+                    eprintln!("workload-{}: {}", workload.label.to_ascii_uppercase(), last.progress_line().trim());
+                }
+                record_soak_result(recorder, workload, &config, &load, &series, &windows);
+                return;
             }
-        } else if config.quiet {
-            let all = run.latencies.all();
-            if let Some(s) = compute_stats(all, Some(run.wall_elapsed)) {
-                print_quiet(workload, &s, &load);
+
+            // --- Run phase ---
+            let run = run_workload_phase(&db, workload, config.records, config.ops, config.value_size, config.seed);
+
+            // --- Output ---
+            if config.csv {
+                print_csv_load(workload, &load);
+                if let Some(s) = compute_stats(&run.latencies.all(), Some(run.wall_elapsed)) {
+                    print_csv_run(workload, "overall", &s);
+                }
+                for (name, lats) in run.latencies.by_op() {
+                    if let Some(s) = compute_stats(lats, None) {
+                        print_csv_run(workload, name, &s);
+                    }
+                }
+            } else if config.quiet {
+                if let Some(s) = compute_stats(&run.latencies.all(), Some(run.wall_elapsed)) {
+                    print_quiet(workload, &s, &load);
+                }
+            } else {
+                print_run_table(&run.latencies, run.wall_elapsed);
             }
-        } else {
-            print_run_table(&run.latencies, run.wall_elapsed);
-        }
 
-        // --- Record ---
-        record_workload_result(&mut recorder, workload, &config, &load, &run);
+            // --- Record ---
+            record_workload_result(recorder, workload, &config, &load, &run);
+        });
     }
 
     if !config.csv && !config.quiet {
         eprintln!("=== YCSB benchmark complete ===");
     }
-    let _ = recorder.save();
+    recorder.save_or_exit();
 }
@@ -1,17 +1,17 @@
-1 2
-1 3
-2 1
-2 4
-3 1
-3 4
-4 5
-4 6
-5 6
-5 7
-6 7
-6 8
-7 9
-8 9
-8 10
-9 10
-10 9
+1 2 0.5
+1 3 2.25
+2 1 1.0
+2 4 1.5
+3 1 0.75
+3 4 3.0
+4 5 1.25
+4 6 0.5
+5 6 2.0
+5 7 4.0
+6 7 0.25
+6 8 1.75
+7 9 3.5
+8 9 0.5
+8 10 2.5
+9 10 1.0
+10 9 0.75
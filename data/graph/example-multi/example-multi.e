@@ -0,0 +1,11 @@
+1 2
+2 1
+1 2
+2 3
+3 3
+3 4
+3 4
+4 5
+5 5
+5 5
+6 6
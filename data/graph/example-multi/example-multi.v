@@ -0,0 +1,6 @@
+1
+2
+3
+4
+5
+6
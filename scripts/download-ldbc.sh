@@ -1,29 +1,70 @@
 #!/usr/bin/env bash
-# Download and decompress LDBC Graphalytics datasets.
+# Download, verify, and decompress LDBC Graphalytics datasets.
 #
 # Usage:
-#   ./scripts/download-ldbc.sh [dataset-name...]
+#   ./scripts/download-ldbc.sh [--pin] [--list] [dataset-name...]
 #
 # Examples:
 #   ./scripts/download-ldbc.sh graph500-22              # S-scale only
 #   ./scripts/download-ldbc.sh graph500-22 graph500-23  # S + M scale
+#   ./scripts/download-ldbc.sh datagen-7_5-fb           # social-network graph
 #   ./scripts/download-ldbc.sh                          # defaults: S + M
+#   ./scripts/download-ldbc.sh --list                   # known datasets
+#   ./scripts/download-ldbc.sh --pin datagen-7_7-zf     # record its checksum
 #
 # Datasets are downloaded from https://datasets.ldbcouncil.org/graphalytics/
-# and decompressed into data/graph/<name>/.
+# and decompressed into data/graph/<name>/, where the graph benches load
+# them with `--dataset data/graph/<name>`.
+#
+# Each archive's SHA-256 is checked against scripts/ldbc-checksums.sha256
+# before it is unpacked. On a mismatch the archive is deleted and the script
+# fails. An archive with no pinned checksum is unpacked with a warning that
+# shows its hash. `--pin` appends that hash to the checksum file, so later
+# downloads of the dataset are verified.
 
 set -euo pipefail
 
 BASE_URL="https://datasets.ldbcouncil.org/graphalytics"
 REPO_ROOT="$(cd "$(dirname "$0")/.." && pwd)"
 DATA_DIR="$REPO_ROOT/data/graph"
+CHECKSUMS="$REPO_ROOT/scripts/ldbc-checksums.sha256"
 
 DEFAULTS=(graph500-22 graph500-23)
 
+# Graphalytics datasets by scale class. Any other name on the server also
+# works; these are the ones the graph benches are usually run against.
+KNOWN=(
+    "graph500-22     S    2.4M vertices,   64M edges, undirected"
+    "graph500-23     M    4.6M vertices,  129M edges, undirected"
+    "graph500-24     M    8.9M vertices,  260M edges, undirected"
+    "graph500-25     L   17.0M vertices,  524M edges, undirected"
+    "graph500-26     XL  32.8M vertices, 1.05B edges, undirected"
+    "datagen-7_5-fb  S    0.6M vertices,   34M edges, undirected"
+    "datagen-7_6-fb  S    0.8M vertices,   42M edges, undirected"
+    "datagen-7_7-zf  S   13.2M vertices,   32M edges, directed"
+    "datagen-7_8-zf  S   16.5M vertices,   40M edges, directed"
+    "datagen-7_9-fb  S    1.4M vertices,   85M edges, undirected"
+)
+
+PIN=0
+DATASETS=()
+for arg in "$@"; do
+    case "$arg" in
+        --pin) PIN=1 ;;
+        --list)
+            printf '%s\n' "${KNOWN[@]}"
+            exit 0
+            ;;
+        -*)
+            echo "ERROR: unknown option '$arg'" >&2
+            exit 1
+            ;;
+        *) DATASETS+=("$arg") ;;
+    esac
+done
+
 # Use arguments or defaults
-if [ $# -gt 0 ]; then
-    DATASETS=("$@")
-else
+if [ ${#DATASETS[@]} -eq 0 ]; then
     DATASETS=("${DEFAULTS[@]}")
 fi
 
@@ -35,6 +76,21 @@ for tool in curl zstd tar; do
     fi
 done
 
+if command -v sha256sum &>/dev/null; then
+    sha256() { sha256sum "$1" | cut -d' ' -f1; }
+elif command -v shasum &>/dev/null; then
+    sha256() { shasum -a 256 "$1" | cut -d' ' -f1; }
+else
+    echo "ERROR: 'sha256sum' or 'shasum' is required but not found. Install one and retry." >&2
+    exit 1
+fi
+
+# Pinned checksum for an archive file name, or nothing.
+pinned_checksum() {
+    [ -f "$CHECKSUMS" ] || return 0
+    awk -v file="$1" '$1 !~ /^#/ && $2 == file { print $1 }' "$CHECKSUMS"
+}
+
 mkdir -p "$DATA_DIR"
 
 for name in "${DATASETS[@]}"; do
@@ -50,6 +106,26 @@ for name in "${DATASETS[@]}"; do
     echo "==> Downloading $name..."
     curl --fail --location --progress-bar -o "$archive" "$url"
 
+    echo "==> Verifying $name..."
+    actual=$(sha256 "$archive")
+    expected=$(pinned_checksum "$name.tar.zst")
+    if [ -n "$expected" ]; then
+        if [ "$actual" != "$expected" ]; then
+            rm -f "$archive"
+            echo "ERROR: checksum mismatch for $name.tar.zst" >&2
+            echo "  expected: $expected" >&2
+            echo "  actual:   $actual" >&2
+            exit 1
+        fi
+        echo "    sha256 OK"
+    elif [ "$PIN" -eq 1 ]; then
+        echo "$actual  $name.tar.zst" >> "$CHECKSUMS"
+        echo "    pinned sha256 $actual"
+    else
+        echo "WARNING: no pinned checksum for $name.tar.zst (sha256 $actual)." >&2
+        echo "  Rerun with --pin to record it in scripts/ldbc-checksums.sha256." >&2
+    fi
+
     echo "==> Decompressing $name..."
     mkdir -p "$dest"
     zstd -d "$archive" --stdout | tar xf - -C "$dest" --strip-components=1
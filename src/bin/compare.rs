@@ -1,21 +1,173 @@
 //! Benchmark comparison tool.
 //!
 //! Compares two JSON result files and prints a table showing performance deltas.
+//! Optionally overlays reference runs from other machines (`--reference`) so
+//! a local result can be placed next to known hardware.
 //!
 //! Usage: `cargo run --bin bench-compare -- <baseline.json> <candidate.json>`
+//! Overlay: `cargo run --bin bench-compare -- --reference <file|dir> <candidate.json>`
+//! Report:  `cargo run --bin bench-compare -- --format markdown <baseline.json> <candidate.json> > diff.md`
+//! Gate:    `cargo run --bin bench-compare -- --gate <baseline.json> <candidate.json>`
+//!
+//! A change counts as faster/slower only outside its category's noise band,
+//! read from a tolerance profile file (`--tolerances`, default
+//! `results/tolerances.json`). `--gate` exits non-zero if any result regressed.
+//! A result whose `status` is not success is shown with its status instead of
+//! metrics; one that fails in the candidate but not the baseline is a regression.
+//!
+//! With `--format markdown` or `--format html`, each row links the artifacts
+//! (flamegraphs, heap profiles, notes) attached to either result. Artifact
+//! paths are resolved against the directory of the result file they came from.
+//!
+//! When both files record their OS environment (transparent huge pages, swap,
+//! filesystem of the database directory), any setting that differs is
+//! printed as a warning above the table.
 
-use strata_benchmarks::schema::{BenchmarkReport, BenchmarkResult};
+use strata_benchmarks::fmt::{fmt_ns, fmt_num};
+use strata_benchmarks::schema::{Artifact, BenchmarkReport, BenchmarkResult};
+use strata_benchmarks::tolerance::{Tolerance, ToleranceProfiles, Verdict};
 use std::collections::HashMap;
+use std::path::Path;
+
+const DEFAULT_TOLERANCES: &str = "results/tolerances.json";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Markdown,
+    Html,
+}
+
+/// One compared benchmark, ready to render.
+struct Row {
+    name: String,
+    base: String,
+    cand: String,
+    delta: String,
+    /// Slower than the category tolerance allows, recall dropped, or the
+    /// candidate failed where the baseline succeeded.
+    regressed: bool,
+    /// `(side, kind, href)`; side is `"base"` or `"new"`.
+    artifacts: Vec<(&'static str, String, String)>,
+}
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() != 3 {
-        eprintln!("Usage: {} <baseline.json> <candidate.json>", args[0]);
+    let mut positional = Vec::new();
+    let mut reference_paths = Vec::new();
+    let mut format = Format::Text;
+    let mut tolerance_path = None;
+    let mut gate = false;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--reference" => {
+                i += 1;
+                if i < args.len() {
+                    reference_paths.push(args[i].clone());
+                }
+            }
+            "--format" => {
+                i += 1;
+                format = match args.get(i).map(String::as_str) {
+                    Some("text") => Format::Text,
+                    Some("markdown") | Some("md") => Format::Markdown,
+                    Some("html") => Format::Html,
+                    other => {
+                        eprintln!("Unknown --format {:?} (expected text, markdown, or html)", other);
+                        std::process::exit(1);
+                    }
+                };
+            }
+            "--tolerances" => {
+                i += 1;
+                if i < args.len() {
+                    tolerance_path = Some(args[i].clone());
+                }
+            }
+            "--gate" => gate = true,
+            _ => positional.push(args[i].clone()),
+        }
+        i += 1;
+    }
+
+    let references: Vec<(String, BenchmarkReport)> = reference_paths
+        .iter()
+        .flat_map(|p| load_references(p))
+        .collect();
+
+    let tolerances = load_tolerances(tolerance_path.as_deref());
+
+    if format == Format::Html {
+        println!("<!DOCTYPE html>");
+        println!("<html><head><meta charset=\"utf-8\"><title>bench-compare</title></head><body>");
+    }
+
+    let mut regressions = 0;
+    match positional.len() {
+        1 if !references.is_empty() && !gate => {
+            print_reference_overlay(&references, &positional[0], format, &tolerances);
+        }
+        2 => {
+            regressions = compare_reports(&positional[0], &positional[1], format, &tolerances);
+            if !references.is_empty() {
+                print_reference_overlay(&references, &positional[1], format, &tolerances);
+            }
+        }
+        _ => {
+            eprintln!(
+                "Usage: {} [--format text|markdown|html] [--tolerances <file>] [--gate] [--reference <file|dir>]... <baseline.json> <candidate.json>",
+                args[0]
+            );
+            eprintln!(
+                "       {} --reference <file|dir> [--reference ...] <candidate.json>",
+                args[0]
+            );
+            std::process::exit(1);
+        }
+    }
+
+    if format == Format::Html {
+        println!("</body></html>");
+    }
+
+    if gate && regressions > 0 {
+        eprintln!("Gate failed: {} regression(s) outside tolerance", regressions);
         std::process::exit(1);
     }
+}
+
+/// Load the tolerance profile. An explicit path must exist; the default
+/// file is optional and falls back to the built-in ±1% band.
+fn load_tolerances(path: Option<&str>) -> ToleranceProfiles {
+    let (path, required) = match path {
+        Some(p) => (p, true),
+        None => (DEFAULT_TOLERANCES, false),
+    };
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) if !required => return ToleranceProfiles::default(),
+        Err(e) => {
+            eprintln!("Error reading {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+    ToleranceProfiles::from_json(&contents).unwrap_or_else(|e| {
+        eprintln!("Error parsing {}: {}", path, e);
+        std::process::exit(1);
+    })
+}
 
-    let baseline = load_report(&args[1]);
-    let candidate = load_report(&args[2]);
+/// Print the comparison table and return the number of regressions.
+fn compare_reports(
+    baseline_path: &str,
+    candidate_path: &str,
+    format: Format,
+    tolerances: &ToleranceProfiles,
+) -> usize {
+    let baseline = load_report(baseline_path);
+    let candidate = load_report(candidate_path);
 
     // Build lookup by benchmark name
     let base_map: HashMap<&str, &BenchmarkResult> = baseline
@@ -31,25 +183,30 @@ fn main() {
         .collect();
 
     // Header
-    eprintln!("Baseline: {} ({})", args[1], baseline.metadata.timestamp);
-    eprintln!("Candidate: {} ({})", args[2], candidate.metadata.timestamp);
+    eprintln!("Baseline: {} ({})", baseline_path, baseline.metadata.timestamp);
+    eprintln!("Candidate: {} ({})", candidate_path, candidate.metadata.timestamp);
+    if let (Some(base_env), Some(cand_env)) =
+        (&baseline.metadata.environment, &candidate.metadata.environment)
+    {
+        for (field, base, cand) in base_env.differences(cand_env) {
+            eprintln!("Warning: environment differs: {} {} -> {}", field, base, cand);
+        }
+    }
     eprintln!();
 
-    println!(
-        "{:<40} | {:>12} | {:>12} | {:>12}",
-        "Benchmark", "Base p50", "New p50", "Delta"
-    );
-    println!("{}", "-".repeat(84));
-
-    let mut matched = 0u32;
+    let mut rows = Vec::new();
     let mut only_base = 0u32;
     let mut only_cand = 0u32;
 
     // Iterate over candidate results to find matches
     for cand in &candidate.results {
         if let Some(base) = base_map.get(cand.benchmark.as_str()) {
-            matched += 1;
-            print_comparison(&cand.benchmark, &base.metrics, &cand.metrics);
+            let tol = tolerances.for_category(&base.category);
+            if let Some(mut row) = compare_results(base, cand, tol) {
+                row.artifacts = artifact_links("base", base, baseline_path);
+                row.artifacts.extend(artifact_links("new", cand, candidate_path));
+                rows.push(row);
+            }
         } else {
             only_cand += 1;
         }
@@ -62,11 +219,97 @@ fn main() {
         }
     }
 
-    println!("{}", "-".repeat(84));
-    println!(
-        "Compared: {} | Baseline only: {} | Candidate only: {}",
-        matched, only_base, only_cand
+    let regressions = rows.iter().filter(|r| r.regressed).count();
+    let title = format!("{} vs {}", baseline_path, candidate_path);
+    let summary = format!(
+        "Compared: {} | Regressions: {} | Baseline only: {} | Candidate only: {}",
+        rows.len(),
+        regressions,
+        only_base,
+        only_cand
     );
+    render_table(format, &title, ("Base p50", "New p50"), &rows, &summary);
+    regressions
+}
+
+/// Print one table per reference run, comparing the candidate against it.
+fn print_reference_overlay(
+    references: &[(String, BenchmarkReport)],
+    candidate_path: &str,
+    format: Format,
+    tolerances: &ToleranceProfiles,
+) {
+    let candidate = load_report(candidate_path);
+    for (path, reference) in references {
+        let rows = reference_rows(reference, &candidate, candidate_path, tolerances);
+        let hw = &reference.metadata.hardware;
+        let title = format!(
+            "Reference: {} ({}, {} cores, {} GB, {}/{})",
+            path, hw.cpu, hw.cores, hw.ram_gb, hw.os, hw.arch
+        );
+        let summary = format!(
+            "Matched: {} of {} reference results",
+            rows.len(),
+            reference.results.len()
+        );
+        render_table(format, &title, ("Ref p50", "Your p50"), &rows, &summary);
+    }
+}
+
+/// Rows for the candidate results that have a match in `reference`, by
+/// benchmark name. Deltas read as "your machine vs the reference machine".
+fn reference_rows(
+    reference: &BenchmarkReport,
+    candidate: &BenchmarkReport,
+    candidate_path: &str,
+    tolerances: &ToleranceProfiles,
+) -> Vec<Row> {
+    let ref_map: HashMap<&str, &BenchmarkResult> = reference
+        .results
+        .iter()
+        .map(|r| (r.benchmark.as_str(), r))
+        .collect();
+
+    let mut rows = Vec::new();
+    for cand in &candidate.results {
+        if let Some(r) = ref_map.get(cand.benchmark.as_str()) {
+            let tol = tolerances.for_category(&r.category);
+            if let Some(mut row) = compare_results(r, cand, tol) {
+                row.artifacts = artifact_links("new", cand, candidate_path);
+                rows.push(row);
+            }
+        }
+    }
+    rows
+}
+
+/// Load a reference file, or every `*.json` file in a reference directory
+/// (sorted by name for stable output).
+fn load_references(path: &str) -> Vec<(String, BenchmarkReport)> {
+    let p = Path::new(path);
+    if !p.is_dir() {
+        return vec![(path.to_string(), load_report(path))];
+    }
+
+    let mut files: Vec<String> = std::fs::read_dir(p)
+        .unwrap_or_else(|e| {
+            eprintln!("Error reading {}: {}", path, e);
+            std::process::exit(1);
+        })
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|x| x == "json").unwrap_or(false))
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|f| {
+            let report = load_report(&f);
+            (f, report)
+        })
+        .collect()
 }
 
 fn load_report(path: &str) -> BenchmarkReport {
@@ -80,82 +323,237 @@ fn load_report(path: &str) -> BenchmarkReport {
     })
 }
 
-fn print_comparison(
-    name: &str,
-    base: &strata_benchmarks::schema::BenchmarkMetrics,
-    cand: &strata_benchmarks::schema::BenchmarkMetrics,
-) {
+/// Resolve a result's artifacts against the directory of its result file.
+fn artifact_links(
+    side: &'static str,
+    result: &BenchmarkResult,
+    report_path: &str,
+) -> Vec<(&'static str, String, String)> {
+    let dir = Path::new(report_path).parent().unwrap_or(Path::new(""));
+    result
+        .artifacts
+        .iter()
+        .map(|Artifact { kind, path }| {
+            (side, kind.clone(), dir.join(path).to_string_lossy().into_owned())
+        })
+        .collect()
+}
+
+fn compare_results(base: &BenchmarkResult, cand: &BenchmarkResult, tol: Tolerance) -> Option<Row> {
+    // A failed side has no metrics to compare; newly failing counts as a regression
+    if !base.status.is_success() || !cand.status.is_success() {
+        let delta = match cand.status.message() {
+            Some(message) => message.to_string(),
+            None => "recovered".to_string(),
+        };
+        return Some(Row {
+            name: cand.benchmark.clone(),
+            base: base.status.label().to_string(),
+            cand: cand.status.label().to_string(),
+            delta,
+            regressed: base.status.is_success(),
+            artifacts: Vec::new(),
+        });
+    }
+
+    let (b, c) = (&base.metrics, &cand.metrics);
     // Compare p50 latency
-    if let (Some(base_p50), Some(cand_p50)) = (base.p50_ns, cand.p50_ns) {
+    let mut row = if let (Some(base_p50), Some(cand_p50)) = (b.p50_ns, c.p50_ns) {
         let delta_pct = if base_p50 > 0 {
             ((cand_p50 as f64 - base_p50 as f64) / base_p50 as f64) * 100.0
         } else {
             0.0
         };
+        let verdict = tol.latency(delta_pct);
 
-        let hint = if delta_pct < -1.0 {
-            "faster"
-        } else if delta_pct > 1.0 {
-            "slower"
-        } else {
-            "~same"
-        };
-
-        println!(
-            "{:<40} | {:>12} | {:>12} | {:>+.1}% ({})",
-            name,
-            format_ns(base_p50),
-            format_ns(cand_p50),
-            delta_pct,
-            hint,
-        );
-    } else if let (Some(base_ops), Some(cand_ops)) = (base.ops_per_sec, cand.ops_per_sec) {
+        Row {
+            name: cand.benchmark.clone(),
+            base: fmt_ns(base_p50),
+            cand: fmt_ns(cand_p50),
+            delta: format!("{:+.1}% ({})", delta_pct, verdict.label()),
+            regressed: verdict == Verdict::Slower,
+            artifacts: Vec::new(),
+        }
+    } else if let (Some(base_ops), Some(cand_ops)) = (b.ops_per_sec, c.ops_per_sec) {
         // Fallback to ops/sec comparison
         let delta_pct = if base_ops > 0.0 {
             ((cand_ops - base_ops) / base_ops) * 100.0
         } else {
             0.0
         };
+        let verdict = tol.throughput(delta_pct);
 
-        let hint = if delta_pct > 1.0 {
-            "faster"
-        } else if delta_pct < -1.0 {
-            "slower"
-        } else {
-            "~same"
-        };
+        Row {
+            name: cand.benchmark.clone(),
+            base: format!("{} ops/s", fmt_num(base_ops as u64)),
+            cand: format!("{} ops/s", fmt_num(cand_ops as u64)),
+            delta: format!("{:+.1}% ({})", delta_pct, verdict.label()),
+            regressed: verdict == Verdict::Slower,
+            artifacts: Vec::new(),
+        }
+    } else {
+        return None;
+    };
 
-        println!(
-            "{:<40} | {:>10} ops/s | {:>10} ops/s | {:>+.1}% ({})",
-            name,
-            format_num(base_ops as u64),
-            format_num(cand_ops as u64),
-            delta_pct,
-            hint,
-        );
+    // ANN results also carry recall, checked against an absolute tolerance
+    let recall = |r: &BenchmarkResult| r.parameters.get("recall").and_then(|v| v.as_f64());
+    if let (Some(base_recall), Some(cand_recall)) = (recall(base), recall(cand)) {
+        if tol.recall_abs.is_some() {
+            let dropped = tol.recall_regressed(base_recall, cand_recall);
+            row.delta.push_str(&format!(
+                ", recall {:+.4}{}",
+                cand_recall - base_recall,
+                if dropped { " (lower)" } else { "" }
+            ));
+            row.regressed |= dropped;
+        }
     }
+    Some(row)
 }
 
-fn format_ns(ns: u64) -> String {
-    if ns < 1_000 {
-        format!("{} ns", ns)
-    } else if ns < 1_000_000 {
-        format!("{:.2} us", ns as f64 / 1_000.0)
-    } else if ns < 1_000_000_000 {
-        format!("{:.2} ms", ns as f64 / 1_000_000.0)
-    } else {
-        format!("{:.2} s", ns as f64 / 1_000_000_000.0)
+fn render_table(format: Format, title: &str, cols: (&str, &str), rows: &[Row], summary: &str) {
+    match format {
+        Format::Text => {
+            println!();
+            println!("{}", title);
+            println!(
+                "{:<40} | {:>14} | {:>14} | {:>12}",
+                "Benchmark", cols.0, cols.1, "Delta"
+            );
+            println!("{}", "-".repeat(88));
+            for r in rows {
+                println!(
+                    "{:<40} | {:>14} | {:>14} | {}",
+                    r.name, r.base, r.cand, r.delta
+                );
+                for (side, kind, href) in &r.artifacts {
+                    println!("    {} {}: {}", side, kind, href);
+                }
+            }
+            println!("{}", "-".repeat(88));
+            println!("{}", summary);
+        }
+        Format::Markdown => {
+            println!();
+            println!("### {}", md_escape(title));
+            println!();
+            println!("| Benchmark | {} | {} | Delta | Artifacts |", cols.0, cols.1);
+            println!("|-----------|---:|---:|---:|-----------|");
+            for r in rows {
+                let links: Vec<String> = r
+                    .artifacts
+                    .iter()
+                    .map(|(side, kind, href)| {
+                        format!("[{} {}](<{}>)", side, md_escape(kind), md_escape(href))
+                    })
+                    .collect();
+                println!(
+                    "| `{}` | {} | {} | {} | {} |",
+                    md_escape(&r.name),
+                    md_escape(&r.base),
+                    md_escape(&r.cand),
+                    md_escape(&r.delta),
+                    links.join(" ")
+                );
+            }
+            println!();
+            println!("{}", summary);
+        }
+        Format::Html => {
+            println!("<h3>{}</h3>", html_escape(title));
+            println!("<table>");
+            println!(
+                "<tr><th>Benchmark</th><th>{}</th><th>{}</th><th>Delta</th><th>Artifacts</th></tr>",
+                cols.0, cols.1
+            );
+            for r in rows {
+                let links: Vec<String> = r
+                    .artifacts
+                    .iter()
+                    .map(|(side, kind, href)| {
+                        format!(
+                            "<a href=\"{}\">{} {}</a>",
+                            html_escape(href),
+                            side,
+                            html_escape(kind)
+                        )
+                    })
+                    .collect();
+                println!(
+                    "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    html_escape(&r.name),
+                    html_escape(&r.base),
+                    html_escape(&r.cand),
+                    html_escape(&r.delta),
+                    links.join(" ")
+                );
+            }
+            println!("</table>");
+            println!("<p>{}</p>", html_escape(summary));
+        }
     }
 }
 
-fn format_num(n: u64) -> String {
-    let s = n.to_string();
-    let mut result = String::new();
-    for (i, c) in s.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
-        }
-        result.push(c);
+/// Text for one markdown table cell: `|` would end the cell and a line
+/// break the row, which free-text status messages can contain.
+fn md_escape(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(cpu: &str, results: &[(&str, u64)]) -> String {
+        let results: Vec<serde_json::Value> = results
+            .iter()
+            .map(|(name, p50)| {
+                serde_json::json!({ "benchmark": name, "category": "latency", "metrics": { "p50_ns": p50 } })
+            })
+            .collect();
+        serde_json::json!({
+            "schema_version": 1,
+            "metadata": {
+                "timestamp": "2025-01-15T14:30:00Z", "sdk": "rust", "sdk_version": "0.1.0",
+                "hardware": { "cpu": cpu, "cores": 8, "ram_gb": 16, "os": "linux", "arch": "x86_64" }
+            },
+            "results": results,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_reference_directory_loads_json_files_in_name_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("b.json"), report("b", &[])).unwrap();
+        std::fs::write(dir.path().join("a.json"), report("a", &[])).unwrap();
+        std::fs::write(dir.path().join("notes.md"), "not a result").unwrap();
+
+        let refs = load_references(dir.path().to_str().unwrap());
+        let cpus: Vec<&str> = refs.iter().map(|(_, r)| r.metadata.hardware.cpu.as_str()).collect();
+        assert_eq!(cpus, ["a", "b"]);
+        assert!(refs[0].0.ends_with("a.json"));
+    }
+
+    #[test]
+    fn test_reference_rows_match_by_name() {
+        let reference: BenchmarkReport =
+            serde_json::from_str(&report("ref", &[("kv/put", 1000), ("kv/get", 500)])).unwrap();
+        let candidate: BenchmarkReport =
+            serde_json::from_str(&report("local", &[("kv/put", 2000), ("kv/scan", 9000)])).unwrap();
+
+        let rows = reference_rows(&reference, &candidate, "new.json", &ToleranceProfiles::default());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].name, "kv/put");
+        assert!(rows[0].delta.starts_with("+100.0%"));
+        assert!(rows[0].regressed);
     }
-    result.chars().rev().collect()
 }
@@ -0,0 +1,355 @@
+//! Graph500 Kronecker (R-MAT) graph generator.
+//!
+//! Writes a synthetic undirected graph in LDBC Graphalytics format, so the
+//! graph benches can run at 1M–100M+ edges without downloading a dataset.
+//! The graph has `2^scale` vertex slots and `edge_factor × 2^scale` generated
+//! edges, each placed by recursively picking a quadrant of the adjacency
+//! matrix with the Graph500 initiator probabilities (A=0.57, B=0.19, C=0.19,
+//! D=0.05).
+//!
+//! As in the LDBC `graph500-*` datasets:
+//!
+//! - vertex IDs are a random permutation, so ID order carries no locality;
+//! - self-loops and repeated edges are dropped, and each undirected edge is
+//!   written once, smaller ID first, in random order;
+//! - vertices with no edges are left out of the `.v` file.
+//!
+//! A BFS source is picked at random among the written vertices and its
+//! reference depths are written to `<name>-BFS`, so `graph_bfs` validates
+//! against it like any downloaded dataset.
+//!
+//! Usage:  `cargo run --release --bin gen-kronecker -- --scale 20 --edge-factor 16`
+//! Output: `data/graph/kronecker-<scale>-<edge_factor>/` (override with `--out <dir>`)
+//! Bench:  `cargo bench --bench graph_bfs -- --dataset data/graph/kronecker-20-16`
+//!
+//! Scale 16 gives about 1M edges, scale 20 about 16M, and scale 23 about 130M
+//! before duplicates are dropped.
+
+use std::collections::VecDeque;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use strata_benchmarks::fmt::{fmt_duration, fmt_num};
+
+const DEFAULT_SCALE: u32 = 16;
+const DEFAULT_EDGE_FACTOR: u64 = 16;
+const DEFAULT_SEED: u64 = 42;
+
+/// Graph500 initiator probabilities; D is the remainder.
+const A: f64 = 0.57;
+const B: f64 = 0.19;
+const C: f64 = 0.19;
+
+/// Depth written for vertices the BFS source can't reach.
+const UNREACHABLE: i64 = i64::MAX;
+
+// ---------------------------------------------------------------------------
+// Fast LCG RNG (same as ycsb/workloads.rs)
+// ---------------------------------------------------------------------------
+
+struct FastRng {
+    state: u64,
+}
+
+impl FastRng {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed ^ 0x5DEECE66D,
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.state
+    }
+
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uses the high bits; an LCG's low bits cycle with short periods.
+    #[inline]
+    fn next_usize(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+}
+
+fn shuffle<T>(items: &mut [T], rng: &mut FastRng) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, rng.next_usize(i + 1));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CLI configuration
+// ---------------------------------------------------------------------------
+
+struct Config {
+    scale: u32,
+    edge_factor: u64,
+    seed: u64,
+    out: Option<PathBuf>,
+}
+
+fn parse_args() -> Config {
+    let args: Vec<String> = std::env::args().collect();
+    let mut config = Config {
+        scale: DEFAULT_SCALE,
+        edge_factor: DEFAULT_EDGE_FACTOR,
+        seed: DEFAULT_SEED,
+        out: None,
+    };
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--scale" => {
+                i += 1;
+                if i < args.len() {
+                    config.scale = args[i].parse().unwrap_or(DEFAULT_SCALE);
+                }
+            }
+            "--edge-factor" => {
+                i += 1;
+                if i < args.len() {
+                    config.edge_factor = args[i].parse().unwrap_or(DEFAULT_EDGE_FACTOR);
+                }
+            }
+            "--seed" => {
+                i += 1;
+                if i < args.len() {
+                    config.seed = args[i].parse().unwrap_or(DEFAULT_SEED);
+                }
+            }
+            "--out" => {
+                i += 1;
+                if i < args.len() {
+                    config.out = Some(PathBuf::from(&args[i]));
+                }
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                eprintln!("Usage: gen-kronecker [--scale N] [--edge-factor N] [--seed N] [--out DIR]");
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    // Vertex IDs are stored as u32
+    if !(1..=32).contains(&config.scale) {
+        eprintln!("--scale must be between 1 and 32, got {}", config.scale);
+        std::process::exit(1);
+    }
+    if config.edge_factor == 0 {
+        eprintln!("--edge-factor must be at least 1");
+        std::process::exit(1);
+    }
+
+    config
+}
+
+// ---------------------------------------------------------------------------
+// Generation
+// ---------------------------------------------------------------------------
+
+/// One R-MAT edge: at each of `scale` levels, pick the quadrant and set the
+/// matching bit of the source and destination.
+fn rmat_edge(scale: u32, rng: &mut FastRng) -> (u32, u32) {
+    let (mut src, mut dst) = (0u32, 0u32);
+    for bit in 0..scale {
+        let r = rng.next_f64();
+        if r < A {
+        } else if r < A + B {
+            dst |= 1 << bit;
+        } else if r < A + B + C {
+            src |= 1 << bit;
+        } else {
+            src |= 1 << bit;
+            dst |= 1 << bit;
+        }
+    }
+    (src, dst)
+}
+
+/// Generate the edge list with permuted IDs, as `(min << 32) | max` keys.
+/// Self-loops and repeated edges are removed; the order is random.
+fn generate_edges(config: &Config, rng: &mut FastRng) -> Vec<u64> {
+    let n = 1usize << config.scale;
+    let mut perm: Vec<u32> = (0..n as u64).map(|v| v as u32).collect();
+    shuffle(&mut perm, rng);
+
+    let target = config.edge_factor as usize * n;
+    let mut edges = Vec::with_capacity(target);
+    for _ in 0..target {
+        let (s, d) = rmat_edge(config.scale, rng);
+        let (s, d) = (perm[s as usize], perm[d as usize]);
+        if s != d {
+            edges.push(((s.min(d) as u64) << 32) | s.max(d) as u64);
+        }
+    }
+
+    edges.sort_unstable();
+    edges.dedup();
+    shuffle(&mut edges, rng);
+    edges
+}
+
+fn unpack(edge: u64) -> (u32, u32) {
+    ((edge >> 32) as u32, edge as u32)
+}
+
+// ---------------------------------------------------------------------------
+// BFS reference
+// ---------------------------------------------------------------------------
+
+/// Undirected CSR adjacency over vertex slots `0..n`.
+fn csr(n: usize, edges: &[u64]) -> (Vec<usize>, Vec<u32>) {
+    let mut offsets = vec![0usize; n + 1];
+    for &e in edges {
+        let (s, d) = unpack(e);
+        offsets[s as usize + 1] += 1;
+        offsets[d as usize + 1] += 1;
+    }
+    for i in 0..n {
+        offsets[i + 1] += offsets[i];
+    }
+    let mut next = offsets.clone();
+    let mut targets = vec![0u32; offsets[n]];
+    for &e in edges {
+        let (s, d) = unpack(e);
+        targets[next[s as usize]] = d;
+        next[s as usize] += 1;
+        targets[next[d as usize]] = s;
+        next[d as usize] += 1;
+    }
+    (offsets, targets)
+}
+
+fn bfs_depths(offsets: &[usize], targets: &[u32], source: u32) -> Vec<i64> {
+    let mut depths = vec![UNREACHABLE; offsets.len() - 1];
+    let mut queue = VecDeque::new();
+    depths[source as usize] = 0;
+    queue.push_back(source);
+    while let Some(v) = queue.pop_front() {
+        let d = depths[v as usize];
+        for &w in &targets[offsets[v as usize]..offsets[v as usize + 1]] {
+            if depths[w as usize] == UNREACHABLE {
+                depths[w as usize] = d + 1;
+                queue.push_back(w);
+            }
+        }
+    }
+    depths
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+fn create(path: &Path) -> BufWriter<std::fs::File> {
+    let file = std::fs::File::create(path).unwrap_or_else(|e| {
+        eprintln!("Failed to create {}: {}", path.display(), e);
+        std::process::exit(1);
+    });
+    BufWriter::new(file)
+}
+
+fn write_dataset(dir: &Path, name: &str, config: &Config, edges: &[u64], rng: &mut FastRng) {
+    std::fs::create_dir_all(dir).unwrap_or_else(|e| {
+        eprintln!("Failed to create {}: {}", dir.display(), e);
+        std::process::exit(1);
+    });
+
+    let n = 1usize << config.scale;
+    let (offsets, targets) = csr(n, edges);
+    let vertices: Vec<u32> = (0..n as u64)
+        .map(|v| v as u32)
+        .filter(|&v| offsets[v as usize + 1] > offsets[v as usize])
+        .collect();
+
+    let mut out = create(&dir.join(format!("{}.v", name)));
+    for &v in &vertices {
+        writeln!(out, "{}", v).unwrap();
+    }
+    out.flush().unwrap();
+
+    let mut out = create(&dir.join(format!("{}.e", name)));
+    for &e in edges {
+        let (s, d) = unpack(e);
+        writeln!(out, "{} {}", s, d).unwrap();
+    }
+    out.flush().unwrap();
+
+    let source = (!vertices.is_empty()).then(|| vertices[rng.next_usize(vertices.len())]);
+
+    let mut out = create(&dir.join(format!("{}.properties", name)));
+    writeln!(out, "graph.name = {}", name).unwrap();
+    writeln!(out, "graph.directed = false").unwrap();
+    writeln!(out, "meta.vertices = {}", vertices.len()).unwrap();
+    writeln!(out, "meta.edges = {}", edges.len()).unwrap();
+    writeln!(out, "# gen-kronecker --scale {} --edge-factor {} --seed {}", config.scale, config.edge_factor, config.seed).unwrap();
+    if let Some(source) = source {
+        writeln!(out, "algorithms.bfs.source-vertex = {}", source).unwrap();
+    }
+    out.flush().unwrap();
+
+    if let Some(source) = source {
+        let depths = bfs_depths(&offsets, &targets, source);
+        let mut out = create(&dir.join(format!("{}-BFS", name)));
+        for &v in &vertices {
+            writeln!(out, "{} {}", v, depths[v as usize]).unwrap();
+        }
+        out.flush().unwrap();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Main
+// ---------------------------------------------------------------------------
+
+fn main() {
+    let config = parse_args();
+    let name = match &config.out {
+        Some(dir) => dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_else(|| {
+                eprintln!("--out must end in a directory name");
+                std::process::exit(1);
+            })
+            .to_string(),
+        None => format!("kronecker-{}-{}", config.scale, config.edge_factor),
+    };
+    let dir = config.out.clone().unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("data/graph")
+            .join(&name)
+    });
+
+    eprintln!(
+        "Generating scale {} (2^{} vertex slots), edge factor {}, seed {}",
+        config.scale, config.scale, config.edge_factor, config.seed
+    );
+    let start = Instant::now();
+    let mut rng = FastRng::new(config.seed);
+    let edges = generate_edges(&config, &mut rng);
+    eprintln!(
+        "Generated {} edges in {}",
+        fmt_num(edges.len() as u64),
+        fmt_duration(start.elapsed())
+    );
+
+    let start = Instant::now();
+    write_dataset(&dir, &name, &config, &edges, &mut rng);
+    eprintln!(
+        "Wrote {} in {}",
+        dir.display(),
+        fmt_duration(start.elapsed())
+    );
+}
@@ -0,0 +1,141 @@
+//! Number, duration, and scale formatting shared by every bench's tables.
+
+use std::time::Duration;
+
+/// Format an integer with comma separators: `1234567` → `"1,234,567"`.
+pub fn fmt_num(n: u64) -> String {
+    let s = n.to_string();
+    let mut result = String::with_capacity(s.len() + s.len() / 3);
+    for (i, c) in s.chars().enumerate() {
+        if i > 0 && (s.len() - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Format a rate (ops/sec, QPS) with comma separators, truncated to an
+/// integer. Negative and NaN rates show as `0`.
+pub fn fmt_ops(ops: f64) -> String {
+    fmt_num(ops.max(0.0) as u64)
+}
+
+/// Format a latency given in (possibly fractional) nanoseconds, picking the
+/// unit: `"850 ns"`, `"12.34 us"`, `"1.50 ms"`, `"2.00 s"`. Sub-nanosecond
+/// values (per-op averages of very cheap ops) keep two decimals.
+pub fn fmt_nanos(ns: f64) -> String {
+    if !ns.is_finite() || ns < 0.0 {
+        "-".to_string()
+    } else if ns < 1.0 {
+        format!("{:.2} ns", ns)
+    } else if ns < 1_000.0 {
+        format!("{:.0} ns", ns)
+    } else if ns < 1_000_000.0 {
+        format!("{:.2} us", ns / 1_000.0)
+    } else if ns < 1_000_000_000.0 {
+        format!("{:.2} ms", ns / 1_000_000.0)
+    } else {
+        format!("{:.2} s", ns / 1_000_000_000.0)
+    }
+}
+
+/// [`fmt_nanos`] for a `Duration`.
+pub fn fmt_duration(d: Duration) -> String {
+    fmt_nanos(d.as_nanos() as f64)
+}
+
+/// [`fmt_nanos`] for an integer nanosecond count, as stored in result files.
+pub fn fmt_ns(ns: u64) -> String {
+    fmt_nanos(ns as f64)
+}
+
+/// A duration as fixed-unit milliseconds: `"12.3ms"`.
+pub fn fmt_ms(d: Duration) -> String {
+    format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+}
+
+/// A duration as fixed-unit microseconds: `"12.34us"`.
+pub fn fmt_us(d: Duration) -> String {
+    format!("{:.2}us", d.as_nanos() as f64 / 1000.0)
+}
+
+/// Short label for a corpus or record count, used in benchmark names:
+/// `50_000` → `"50k"`, `2_000_000` → `"2m"`. Counts that aren't an exact
+/// multiple keep the next finer unit (`1_500_000` → `"1500k"`), so two
+/// different scales never share a label.
+pub fn scale_label(n: u64) -> String {
+    if n >= 1_000_000 && n.is_multiple_of(1_000_000) {
+        format!("{}m", n / 1_000_000)
+    } else if n >= 1_000 && n.is_multiple_of(1_000) {
+        format!("{}k", n / 1_000)
+    } else {
+        n.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt_num() {
+        assert_eq!(fmt_num(0), "0");
+        assert_eq!(fmt_num(999), "999");
+        assert_eq!(fmt_num(1_000), "1,000");
+        assert_eq!(fmt_num(12_345), "12,345");
+        assert_eq!(fmt_num(1_000_000), "1,000,000");
+        assert_eq!(fmt_num(u64::MAX), "18,446,744,073,709,551,615");
+    }
+
+    #[test]
+    fn test_fmt_ops() {
+        assert_eq!(fmt_ops(1_234_567.9), "1,234,567");
+        assert_eq!(fmt_ops(0.4), "0");
+        assert_eq!(fmt_ops(-5.0), "0");
+        assert_eq!(fmt_ops(f64::NAN), "0");
+        assert_eq!(fmt_ops(f64::INFINITY), fmt_num(u64::MAX));
+    }
+
+    #[test]
+    fn test_fmt_duration_ranges() {
+        assert!(fmt_duration(Duration::from_nanos(500)).contains("ns"));
+        assert!(fmt_duration(Duration::from_micros(50)).contains("us"));
+        assert!(fmt_duration(Duration::from_millis(50)).contains("ms"));
+        assert!(fmt_duration(Duration::from_secs(2)).contains("s"));
+        assert_eq!(fmt_duration(Duration::ZERO), "0.00 ns");
+        assert_eq!(fmt_duration(Duration::from_nanos(999)), "999 ns");
+        assert_eq!(fmt_duration(Duration::from_nanos(1_000)), "1.00 us");
+        assert_eq!(fmt_duration(Duration::from_nanos(1_500_000)), "1.50 ms");
+        assert!(fmt_duration(Duration::MAX).ends_with(" s"));
+    }
+
+    #[test]
+    fn test_fmt_nanos_sub_nanosecond_and_invalid() {
+        assert_eq!(fmt_nanos(0.25), "0.25 ns");
+        assert_eq!(fmt_nanos(0.0), "0.00 ns");
+        assert_eq!(fmt_nanos(-1.0), "-");
+        assert_eq!(fmt_nanos(f64::NAN), "-");
+        assert_eq!(fmt_ns(u64::MAX), format!("{:.2} s", u64::MAX as f64 / 1e9));
+    }
+
+    #[test]
+    fn test_fixed_unit_formats() {
+        assert_eq!(fmt_ms(Duration::from_micros(12_345)), "12.3ms");
+        assert_eq!(fmt_ms(Duration::ZERO), "0.0ms");
+        assert_eq!(fmt_us(Duration::from_nanos(1_234)), "1.23us");
+        assert_eq!(fmt_us(Duration::from_nanos(1)), "0.00us");
+    }
+
+    #[test]
+    fn test_scale_label() {
+        assert_eq!(scale_label(0), "0");
+        assert_eq!(scale_label(999), "999");
+        assert_eq!(scale_label(1_000), "1k");
+        assert_eq!(scale_label(50_000), "50k");
+        assert_eq!(scale_label(1_500), "1500");
+        assert_eq!(scale_label(1_000_000), "1m");
+        assert_eq!(scale_label(1_500_000), "1500k");
+        assert_eq!(scale_label(u64::MAX), "18446744073709551615");
+    }
+}
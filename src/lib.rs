@@ -3,6 +3,8 @@
 // This crate depends on `stratadb` as an external git dependency and exercises
 // only the public API. No internal crates are imported.
 
+pub mod fmt;
 pub mod schema;
+pub mod tolerance;
 
 pub use stratadb;
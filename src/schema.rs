@@ -15,6 +15,10 @@ pub struct BenchmarkReport {
     pub metadata: RunMetadata,
     /// Individual benchmark results.
     pub results: Vec<BenchmarkResult>,
+    /// Resource usage of the bench process over the whole run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub resources: Option<ResourceUsage>,
 }
 
 /// Metadata captured at the start of a benchmark run.
@@ -37,6 +41,10 @@ pub struct RunMetadata {
     pub sdk_version: String,
     /// Hardware information.
     pub hardware: HardwareInfo,
+    /// OS settings that change results on the same hardware.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub environment: Option<EnvironmentInfo>,
 }
 
 /// Hardware information for reproducibility.
@@ -54,12 +62,169 @@ pub struct HardwareInfo {
     pub arch: String,
 }
 
+/// OS-level settings captured alongside the hardware (Linux only; fields
+/// that can't be read are omitted).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentInfo {
+    /// Transparent huge pages mode (`always`, `madvise`, `never`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thp_enabled: Option<String>,
+    /// Transparent huge pages defrag mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thp_defrag: Option<String>,
+    /// `vm.swappiness`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swappiness: Option<u32>,
+    /// Total swap in MB; 0 means no swap.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_total_mb: Option<u64>,
+    /// Directory the on-disk databases were created in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_dir: Option<String>,
+    /// Mount point of the filesystem holding `db_dir`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_mount_point: Option<String>,
+    /// Filesystem type of that mount (e.g. `ext4`, `xfs`, `tmpfs`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_fs_type: Option<String>,
+    /// Mount options of that mount (e.g. `rw,relatime`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_mount_options: Option<String>,
+    /// `memory.max` of the process's cgroup in MB, when one is set. Page
+    /// cache counts against it, so results under a ceiling aren't comparable
+    /// with unconstrained ones.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit_mb: Option<u64>,
+    /// CPUs the bench process may run on, as a list like `0-3,8`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_affinity: Option<String>,
+    /// `--cpus` set the workers were pinned to one per core, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned_cpus: Option<String>,
+    /// Nice value the bench ran at (0 is the default priority).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+    /// `--strata-config` group-commit settings for standard mode, as
+    /// `key=value,key=value`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strata_config: Option<String>,
+}
+
+impl EnvironmentInfo {
+    /// `(field, self, other)` for every setting whose value differs. A field
+    /// missing on one side counts as different and is shown as `-`. Paths
+    /// are left out: the same filesystem under another path is no change.
+    pub fn differences(&self, other: &EnvironmentInfo) -> Vec<(&'static str, String, String)> {
+        fn show<T: ToString>(v: &Option<T>) -> String {
+            v.as_ref().map_or_else(|| "-".to_string(), T::to_string)
+        }
+        let fields = [
+            ("thp_enabled", show(&self.thp_enabled), show(&other.thp_enabled)),
+            ("thp_defrag", show(&self.thp_defrag), show(&other.thp_defrag)),
+            ("swappiness", show(&self.swappiness), show(&other.swappiness)),
+            ("swap_total_mb", show(&self.swap_total_mb), show(&other.swap_total_mb)),
+            ("db_fs_type", show(&self.db_fs_type), show(&other.db_fs_type)),
+            ("db_mount_options", show(&self.db_mount_options), show(&other.db_mount_options)),
+            ("memory_limit_mb", show(&self.memory_limit_mb), show(&other.memory_limit_mb)),
+            ("cpu_affinity", show(&self.cpu_affinity), show(&other.cpu_affinity)),
+            ("pinned_cpus", show(&self.pinned_cpus), show(&other.pinned_cpus)),
+            ("nice", show(&self.nice), show(&other.nice)),
+            ("strata_config", show(&self.strata_config), show(&other.strata_config)),
+        ];
+        fields.into_iter().filter(|(_, a, b)| a != b).collect()
+    }
+}
+
+/// Resource usage of the bench process, sampled in the background while it
+/// ran. Values that can't be read on the platform are omitted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// Target time between samples.
+    pub interval_ms: u64,
+    /// Highest resident set size seen.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub peak_rss_bytes: Option<u64>,
+    /// CPU time over wall time for the whole run; 100 is one core busy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub mean_cpu_pct: Option<f64>,
+    /// Busiest interval's CPU utilization.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub peak_cpu_pct: Option<f64>,
+    /// Bytes read from storage over the run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub read_bytes: Option<u64>,
+    /// Bytes written to storage over the run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub write_bytes: Option<u64>,
+    /// One entry per interval, oldest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub samples: Vec<ResourceSample>,
+}
+
+impl ResourceUsage {
+    /// Usage summarized from `samples`.
+    pub fn from_samples(interval_ms: u64, samples: Vec<ResourceSample>) -> Self {
+        fn total(v: impl Iterator<Item = Option<u64>>) -> Option<u64> {
+            v.flatten().reduce(|a, b| a + b)
+        }
+        let wall_ms: u64 = samples
+            .iter()
+            .filter(|s| s.cpu_pct.is_some())
+            .map(|s| s.interval_ms)
+            .sum();
+        let cpu_ms: f64 = samples
+            .iter()
+            .filter_map(|s| s.cpu_pct.map(|pct| pct * s.interval_ms as f64))
+            .sum();
+        Self {
+            interval_ms,
+            peak_rss_bytes: samples.iter().filter_map(|s| s.rss_bytes).max(),
+            mean_cpu_pct: (wall_ms > 0).then(|| cpu_ms / wall_ms as f64),
+            peak_cpu_pct: samples.iter().filter_map(|s| s.cpu_pct).reduce(f64::max),
+            read_bytes: total(samples.iter().map(|s| s.read_bytes)),
+            write_bytes: total(samples.iter().map(|s| s.write_bytes)),
+            samples,
+        }
+    }
+}
+
+/// One interval of [`ResourceUsage`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceSample {
+    /// Time from the start of the run to the end of the interval.
+    pub elapsed_ms: u64,
+    /// Length of the interval. The last one is usually short.
+    pub interval_ms: u64,
+    /// Resident set size at the end of the interval.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub rss_bytes: Option<u64>,
+    /// CPU time over the interval's wall time; 100 is one core busy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub cpu_pct: Option<f64>,
+    /// Bytes read from storage during the interval.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub read_bytes: Option<u64>,
+    /// Bytes written to storage during the interval.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub write_bytes: Option<u64>,
+}
+
 /// A single benchmark measurement.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchmarkResult {
     /// Benchmark name (e.g. "kv/put/128B/cache").
     pub benchmark: String,
-    /// Category (e.g. "latency", "concurrency", "redis-compare", "fill-level").
+    /// Category (e.g. "latency", "concurrency", "redis-compare", "fill-level", "space").
     pub category: String,
     /// Benchmark-specific parameters.
     #[serde(skip_serializing_if = "HashMap::is_empty")]
@@ -67,6 +232,137 @@ pub struct BenchmarkResult {
     pub parameters: HashMap<String, serde_json::Value>,
     /// Measured metrics.
     pub metrics: BenchmarkMetrics,
+    /// Evidence gathered while measuring this result.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub artifacts: Vec<Artifact>,
+    /// The slowest individual operations, slowest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub spikes: Vec<LatencySpike>,
+    /// How the configuration finished. Omitted when it succeeded.
+    #[serde(skip_serializing_if = "ResultStatus::is_success")]
+    #[serde(default)]
+    pub status: ResultStatus,
+}
+
+impl BenchmarkResult {
+    /// A configuration that didn't produce measurements. It's still
+    /// recorded so reports and trend analysis show the failure instead of a
+    /// missing row.
+    pub fn failed(
+        benchmark: String,
+        category: String,
+        parameters: HashMap<String, serde_json::Value>,
+        status: ResultStatus,
+    ) -> Self {
+        Self {
+            benchmark,
+            category,
+            parameters,
+            metrics: BenchmarkMetrics::default(),
+            artifacts: Vec::new(),
+            spikes: Vec::new(),
+            status,
+        }
+    }
+}
+
+/// Outcome of one benchmark configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ResultStatus {
+    /// Measured normally.
+    #[default]
+    Success,
+    /// Measured, but the output didn't match the reference or the other engine.
+    ValidationFailed { message: String },
+    /// Stopped at a time limit before finishing.
+    TimedOut { message: String },
+    /// Failed before producing measurements (a panic or engine error).
+    Error { message: String },
+}
+
+impl ResultStatus {
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success)
+    }
+
+    /// Short label (`success`, `validation_failed`, `timed_out`, `error`).
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::ValidationFailed { .. } => "validation_failed",
+            Self::TimedOut { .. } => "timed_out",
+            Self::Error { .. } => "error",
+        }
+    }
+
+    /// `Success` if no check failed, else `ValidationFailed` listing them.
+    pub fn from_validation(failures: &[String]) -> Self {
+        if failures.is_empty() {
+            Self::Success
+        } else {
+            Self::ValidationFailed {
+                message: failures.join("; "),
+            }
+        }
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Self::Success => None,
+            Self::ValidationFailed { message }
+            | Self::TimedOut { message }
+            | Self::Error { message } => Some(message),
+        }
+    }
+}
+
+impl std::fmt::Display for ResultStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.message() {
+            Some(message) => write!(f, "{}: {}", self.label(), message),
+            None => f.write_str(self.label()),
+        }
+    }
+}
+
+/// A file that explains a result: a flamegraph, heap profile, or notes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    /// What the file is (e.g. "flamegraph", "heap-profile", "notes").
+    pub kind: String,
+    /// Path relative to the directory containing the result file.
+    pub path: String,
+}
+
+/// One of the slowest operations of a measured loop.
+///
+/// `max_ns` says how bad the worst operation was; spikes say when it
+/// happened and which operation it was, so it can be lined up with
+/// compactions, syncs, or other spikes in the same run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencySpike {
+    /// How long the operation took.
+    pub latency_ns: u64,
+    /// Position of the operation in the measured loop (0-based). In a
+    /// multi-threaded run it counts within `worker`'s operations.
+    pub op_index: u64,
+    /// Worker (client thread) that issued the operation, in a multi-threaded
+    /// run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub worker: Option<u64>,
+    /// Time from the start of the measured loop to the start of the operation.
+    pub offset_ns: u64,
+    /// Wall-clock start of the operation, in milliseconds since the Unix epoch.
+    pub unix_ms: u64,
+    /// What the operation was working on (e.g. the key), when the benchmark
+    /// provides it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub context: Option<String>,
 }
 
 /// Metrics collected from a benchmark measurement.
@@ -84,6 +380,10 @@ pub struct BenchmarkMetrics {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub p99_ns: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub p999_ns: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p9999_ns: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub min_ns: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_ns: Option<u64>,
@@ -101,4 +401,183 @@ pub struct BenchmarkMetrics {
     pub abort_rate_pct: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fill_level: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocs_per_op: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alloc_bytes_per_op: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions_per_op: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cycles_per_op: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch_misses_per_op: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llc_misses_per_op: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub space_amplification: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_without_environment_parses() {
+        let json = r#"{
+            "timestamp": "2025-01-15T14:30:00Z",
+            "sdk": "rust",
+            "sdk_version": "0.1.0",
+            "hardware": { "cpu": "x", "cores": 8, "ram_gb": 16, "os": "linux", "arch": "x86_64" }
+        }"#;
+        let m: RunMetadata = serde_json::from_str(json).unwrap();
+        assert!(m.environment.is_none());
+    }
+
+    #[test]
+    fn test_result_without_status_is_success() {
+        let json = r#"{ "benchmark": "kv/put", "category": "latency", "metrics": { "p50_ns": 10 } }"#;
+        let r: BenchmarkResult = serde_json::from_str(json).unwrap();
+        assert_eq!(r.status, ResultStatus::Success);
+        // Success is left out again on write, so old readers see no change
+        assert!(!serde_json::to_string(&r).unwrap().contains("status"));
+    }
+
+    #[test]
+    fn test_failed_result_round_trips() {
+        let r = BenchmarkResult::failed(
+            "graph-bfs/strata/x".into(),
+            "graph-bfs".into(),
+            HashMap::new(),
+            ResultStatus::ValidationFailed { message: "3 mismatches".into() },
+        );
+        let json = serde_json::to_value(&r).unwrap();
+        assert_eq!(
+            json["status"],
+            serde_json::json!({ "kind": "validation_failed", "message": "3 mismatches" })
+        );
+        let back: BenchmarkResult = serde_json::from_value(json).unwrap();
+        assert_eq!(back.status, r.status);
+        assert_eq!(back.status.to_string(), "validation_failed: 3 mismatches");
+        assert!(back.metrics.p50_ns.is_none());
+    }
+
+    #[test]
+    fn test_spikes_round_trip() {
+        let json = r#"{ "benchmark": "kv/put", "category": "latency", "metrics": {},
+            "spikes": [{ "latency_ns": 900000, "op_index": 512, "offset_ns": 4100000, "unix_ms": 1736951400123 }] }"#;
+        let r: BenchmarkResult = serde_json::from_str(json).unwrap();
+        assert_eq!(r.spikes.len(), 1);
+        assert_eq!(r.spikes[0].op_index, 512);
+        assert!(r.spikes[0].context.is_none());
+        let out = serde_json::to_value(&r).unwrap();
+        assert!(out["spikes"][0].get("context").is_none());
+
+        let r = BenchmarkResult { spikes: Vec::new(), ..r };
+        assert!(!serde_json::to_string(&r).unwrap().contains("spikes"));
+    }
+
+    #[test]
+    fn test_report_without_resources_parses() {
+        let json = r#"{ "schema_version": 1, "results": [], "metadata": {
+            "timestamp": "2025-01-15T14:30:00Z", "sdk": "rust", "sdk_version": "0.1.0",
+            "hardware": { "cpu": "x", "cores": 8, "ram_gb": 16, "os": "linux", "arch": "x86_64" } } }"#;
+        let r: BenchmarkReport = serde_json::from_str(json).unwrap();
+        assert!(r.resources.is_none());
+        assert!(!serde_json::to_string(&r).unwrap().contains("resources"));
+    }
+
+    #[test]
+    fn test_resource_usage_summary() {
+        let sample = |elapsed_ms, interval_ms, rss, cpu, written| ResourceSample {
+            elapsed_ms,
+            interval_ms,
+            rss_bytes: Some(rss),
+            cpu_pct: Some(cpu),
+            read_bytes: Some(0),
+            write_bytes: Some(written),
+        };
+        let usage = ResourceUsage::from_samples(
+            1000,
+            vec![
+                sample(1000, 1000, 10 << 20, 100.0, 4096),
+                sample(2000, 1000, 30 << 20, 300.0, 0),
+                sample(2500, 500, 20 << 20, 50.0, 8192),
+            ],
+        );
+        assert_eq!(usage.peak_rss_bytes, Some(30 << 20));
+        assert_eq!(usage.peak_cpu_pct, Some(300.0));
+        // (1000 + 3000 + 250) ms of CPU over 2500 ms
+        assert_eq!(usage.mean_cpu_pct, Some(170.0));
+        assert_eq!((usage.read_bytes, usage.write_bytes), (Some(0), Some(12288)));
+
+        let empty = ResourceUsage::from_samples(1000, Vec::new());
+        assert_eq!((empty.peak_rss_bytes, empty.mean_cpu_pct, empty.write_bytes), (None, None, None));
+        let json = serde_json::to_value(&empty).unwrap();
+        assert_eq!(json, serde_json::json!({ "interval_ms": 1000 }));
+    }
+
+    #[test]
+    fn test_environment_differences() {
+        let a = EnvironmentInfo {
+            thp_enabled: Some("always".into()),
+            swappiness: Some(60),
+            db_dir: Some("/tmp".into()),
+            db_fs_type: Some("ext4".into()),
+            ..Default::default()
+        };
+        let b = EnvironmentInfo {
+            thp_enabled: Some("madvise".into()),
+            swappiness: Some(60),
+            db_dir: Some("/data".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            a.differences(&b),
+            vec![
+                ("thp_enabled", "always".to_string(), "madvise".to_string()),
+                ("db_fs_type", "ext4".to_string(), "-".to_string()),
+            ]
+        );
+        assert!(a.differences(&a).is_empty());
+    }
+
+    #[test]
+    fn test_environment_pinning_difference() {
+        let shared = EnvironmentInfo {
+            cpu_affinity: Some("0-15".into()),
+            nice: Some(0),
+            ..Default::default()
+        };
+        let pinned = EnvironmentInfo {
+            cpu_affinity: Some("2-5".into()),
+            pinned_cpus: Some("2-5".into()),
+            nice: Some(-10),
+            ..Default::default()
+        };
+        assert_eq!(
+            shared.differences(&pinned),
+            vec![
+                ("cpu_affinity", "0-15".to_string(), "2-5".to_string()),
+                ("pinned_cpus", "-".to_string(), "2-5".to_string()),
+                ("nice", "0".to_string(), "-10".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_environment_memory_limit_difference() {
+        let unconstrained = EnvironmentInfo::default();
+        let limited = EnvironmentInfo {
+            memory_limit_mb: Some(512),
+            ..Default::default()
+        };
+        assert_eq!(
+            unconstrained.differences(&limited),
+            vec![("memory_limit_mb", "-".to_string(), "512".to_string())]
+        );
+        let json = serde_json::to_value(&unconstrained).unwrap();
+        assert!(json.get("memory_limit_mb").is_none());
+    }
 }
@@ -0,0 +1,193 @@
+//! Per-category noise tolerances for comparing benchmark runs.
+//!
+//! Latency microbenchmarks are noisier than throughput macrobenchmarks, so a
+//! single global threshold either raises false alarms on one or misses real
+//! regressions on the other. A tolerance profile file sets the threshold per
+//! result category; fields a category leaves out fall back to `default`.
+//!
+//! ```json
+//! {
+//!   "default":    { "latency_pct": 1.0, "throughput_pct": 1.0 },
+//!   "categories": {
+//!     "latency": { "latency_pct": 5.0 },
+//!     "ycsb":    { "throughput_pct": 3.0 },
+//!     "ann":     { "recall_abs": 0.002 }
+//!   }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Thresholds for one category. Unset fields inherit from the default profile.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToleranceSpec {
+    /// Allowed p50 latency change, in percent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_pct: Option<f64>,
+    /// Allowed ops/sec change, in percent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throughput_pct: Option<f64>,
+    /// Allowed absolute change of the `recall` parameter (ANN results).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recall_abs: Option<f64>,
+}
+
+/// A tolerance profile file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToleranceProfiles {
+    #[serde(default)]
+    pub default: ToleranceSpec,
+    #[serde(default)]
+    pub categories: HashMap<String, ToleranceSpec>,
+}
+
+/// Resolved thresholds for one result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub latency_pct: f64,
+    pub throughput_pct: f64,
+    /// `None` means recall is not checked.
+    pub recall_abs: Option<f64>,
+}
+
+/// Threshold used when neither the category nor the default sets one.
+/// Matches bench-compare's historical ±1% "~same" band.
+pub const FALLBACK_PCT: f64 = 1.0;
+
+/// Whether a change is within tolerance, and which way it went if not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Faster,
+    Same,
+    Slower,
+}
+
+impl Verdict {
+    pub fn label(self) -> &'static str {
+        match self {
+            Verdict::Faster => "faster",
+            Verdict::Same => "~same",
+            Verdict::Slower => "slower",
+        }
+    }
+}
+
+impl ToleranceProfiles {
+    /// Parse a profile file.
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let profiles: Self = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        for (name, spec) in std::iter::once(("default", &profiles.default))
+            .chain(profiles.categories.iter().map(|(k, v)| (k.as_str(), v)))
+        {
+            for (field, v) in [
+                ("latency_pct", spec.latency_pct),
+                ("throughput_pct", spec.throughput_pct),
+                ("recall_abs", spec.recall_abs),
+            ] {
+                if v.is_some_and(|v| v.is_nan() || v < 0.0) {
+                    return Err(format!("{}.{} must be >= 0", name, field));
+                }
+            }
+        }
+        Ok(profiles)
+    }
+
+    /// Thresholds for `category`, falling back to the default profile and
+    /// then to [`FALLBACK_PCT`].
+    pub fn for_category(&self, category: &str) -> Tolerance {
+        let cat = self.categories.get(category);
+        let pick = |f: fn(&ToleranceSpec) -> Option<f64>| cat.and_then(f).or(f(&self.default));
+        Tolerance {
+            latency_pct: pick(|s| s.latency_pct).unwrap_or(FALLBACK_PCT),
+            throughput_pct: pick(|s| s.throughput_pct).unwrap_or(FALLBACK_PCT),
+            recall_abs: pick(|s| s.recall_abs),
+        }
+    }
+}
+
+impl Tolerance {
+    /// Classify a p50 latency change given in percent (positive = slower).
+    pub fn latency(&self, delta_pct: f64) -> Verdict {
+        if delta_pct > self.latency_pct {
+            Verdict::Slower
+        } else if delta_pct < -self.latency_pct {
+            Verdict::Faster
+        } else {
+            Verdict::Same
+        }
+    }
+
+    /// Classify an ops/sec change given in percent (positive = faster).
+    pub fn throughput(&self, delta_pct: f64) -> Verdict {
+        if delta_pct < -self.throughput_pct {
+            Verdict::Slower
+        } else if delta_pct > self.throughput_pct {
+            Verdict::Faster
+        } else {
+            Verdict::Same
+        }
+    }
+
+    /// Whether a recall change is a regression. Always `false` when recall
+    /// is not checked for this category.
+    pub fn recall_regressed(&self, base: f64, cand: f64) -> bool {
+        self.recall_abs.is_some_and(|tol| base - cand > tol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIPPED: &str = include_str!("../results/tolerances.json");
+
+    #[test]
+    fn shipped_profile_parses() {
+        let p = ToleranceProfiles::from_json(SHIPPED).unwrap();
+        assert!(p.for_category("latency").latency_pct > p.for_category("dbbench").latency_pct);
+        assert!(p.for_category("ann").recall_abs.is_some());
+    }
+
+    #[test]
+    fn category_overrides_inherit_from_default() {
+        let p = ToleranceProfiles::from_json(
+            r#"{"default": {"latency_pct": 2.0, "throughput_pct": 4.0},
+                "categories": {"latency": {"latency_pct": 5.0}}}"#,
+        )
+        .unwrap();
+        let t = p.for_category("latency");
+        assert_eq!(t.latency_pct, 5.0);
+        assert_eq!(t.throughput_pct, 4.0);
+        assert_eq!(t.recall_abs, None);
+        assert_eq!(p.for_category("unknown").latency_pct, 2.0);
+    }
+
+    #[test]
+    fn empty_profile_matches_historical_band() {
+        let t = ToleranceProfiles::default().for_category("latency");
+        assert_eq!(t.latency(1.0), Verdict::Same);
+        assert_eq!(t.latency(1.5), Verdict::Slower);
+        assert_eq!(t.latency(-1.5), Verdict::Faster);
+        assert_eq!(t.throughput(-1.5), Verdict::Slower);
+    }
+
+    #[test]
+    fn recall_uses_absolute_tolerance() {
+        let t = Tolerance {
+            latency_pct: 1.0,
+            throughput_pct: 1.0,
+            recall_abs: Some(0.002),
+        };
+        assert!(!t.recall_regressed(0.950, 0.9485));
+        assert!(t.recall_regressed(0.950, 0.947));
+        assert!(!t.recall_regressed(0.950, 0.990));
+    }
+
+    #[test]
+    fn rejects_negative_tolerance() {
+        let err = ToleranceProfiles::from_json(r#"{"categories": {"ycsb": {"throughput_pct": -3}}}"#)
+            .unwrap_err();
+        assert!(err.contains("ycsb.throughput_pct"));
+    }
+}
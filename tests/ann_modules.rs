@@ -0,0 +1,17 @@
+// Test harness for the self-contained modules of benches/ann/
+//
+// The ann bench is harness=false, so the #[cfg(test)] tests of its modules
+// never run via `cargo bench --bench ann`. This file re-includes the modules
+// that don't depend on the rest of the bench, so their tests run under
+// `cargo test`.
+
+#[allow(dead_code)]
+#[path = "../benches/ann/dataset.rs"]
+mod dataset;
+
+#[allow(dead_code)]
+#[path = "../benches/ann/gt_cache.rs"]
+mod gt_cache;
+
+#[path = "../benches/ann/operating_point.rs"]
+mod operating_point;
@@ -2,7 +2,54 @@
 //
 // The ldbc module lives inside a benchmark binary with harness=false,
 // so its #[cfg(test)] tests never run via `cargo test --bench graph_bfs`.
-// This file re-includes the module under the standard test harness.
+// This file re-includes the module under the standard test harness, and
+// checks that Strata and petgraph load the edge-semantics fixture the same way.
 
 #[path = "../benches/graph/ldbc.rs"]
 mod ldbc;
+
+use std::path::PathBuf;
+
+use ldbc::{petgraph_bfs, LdbcDataset};
+use stratadb::Strata;
+
+fn multi_dataset() -> LdbcDataset {
+    LdbcDataset::load(&PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data/graph/example-multi"))
+        .unwrap()
+}
+
+/// Load the dataset the way `graph_bfs` does: one node per vertex, one edge
+/// per entry in `dataset.edges`.
+fn load_strata(dataset: &LdbcDataset) -> Strata {
+    let db = Strata::cache().expect("failed to open temp db");
+    db.graph_create("ldbc").unwrap();
+    for &vid in &dataset.vertices {
+        db.graph_add_node("ldbc", &vid.to_string(), None, None).unwrap();
+    }
+    for &(src, dst) in &dataset.edges {
+        db.graph_add_edge("ldbc", &src.to_string(), &dst.to_string(), "E", None, None)
+            .unwrap();
+    }
+    db
+}
+
+#[test]
+fn strata_and_petgraph_agree_on_repeats_and_self_loops() {
+    let ds = multi_dataset();
+    let db = load_strata(&ds);
+    let (graph, id_map) = ds.to_petgraph();
+
+    let strata = db
+        .graph_bfs("ldbc", "1", usize::MAX, None, None, Some("both"))
+        .unwrap()
+        .depths;
+    let petgraph = petgraph_bfs(&graph, id_map[&1]);
+
+    for &vid in &ds.vertices {
+        let s = strata.get(&vid.to_string()).copied();
+        let p = petgraph.get(&id_map[&vid]).copied();
+        assert_eq!(s, p, "vertex {}: strata {:?}, petgraph {:?}", vid, s, p);
+    }
+    // Vertex 6 has only a self-loop
+    assert!(!strata.contains_key("6"));
+}
@@ -0,0 +1,147 @@
+// Test harness for benches/harness/
+//
+// The harness is compiled into every bench binary, and those are
+// harness=false, so the #[cfg(test)] tests of its modules (cli, clock, hdr,
+// load, soak, spikes, ...) never run via `cargo bench`. This file includes
+// the harness once under the standard test harness, which runs them, and
+// adds the durability checks below.
+//
+// Every durability-labeled benchmark result trusts that
+// `create_db(DurabilityConfig::X)` opens a database that really runs in mode
+// X. The durability tests check each mode through the WAL counters it
+// exposes after a fixed number of writes, so a mapping bug in `create_db`
+// fails here instead of silently mislabeling results.
+
+#[allow(unused)]
+#[path = "../benches/harness/mod.rs"]
+mod harness;
+
+use harness::{counter_delta, create_db, kv_key, kv_value, snapshot_counters, BenchDb, DurabilityConfig};
+use stratadb::WalCounters;
+
+const WRITES: u64 = 200;
+
+/// WAL counter delta across `WRITES` single-key puts.
+fn write_delta(bench_db: &BenchDb) -> WalCounters {
+    let before = snapshot_counters(bench_db);
+    for i in 0..WRITES {
+        bench_db.db.kv_put(&kv_key(i), kv_value()).unwrap();
+    }
+    let after = snapshot_counters(bench_db);
+    counter_delta(&before, &after)
+}
+
+// =============================================================================
+// Per-mode WAL behavior
+// =============================================================================
+
+#[test]
+fn cache_mode_writes_no_wal() {
+    let delta = write_delta(&create_db(DurabilityConfig::Cache));
+    assert_eq!(delta.wal_appends, 0, "cache mode should not append to a WAL");
+    assert_eq!(delta.sync_calls, 0, "cache mode should not sync");
+    assert_eq!(delta.bytes_written, 0, "cache mode should not write WAL bytes");
+}
+
+#[test]
+fn standard_mode_appends_every_write_but_batches_syncs() {
+    let delta = write_delta(&create_db(DurabilityConfig::Standard));
+    assert!(
+        delta.wal_appends >= WRITES,
+        "standard mode: expected at least {} WAL appends, got {}",
+        WRITES,
+        delta.wal_appends
+    );
+    assert!(delta.bytes_written > 0, "standard mode should write WAL bytes");
+    assert!(
+        delta.sync_calls < WRITES,
+        "standard mode should batch syncs: {} syncs for {} writes",
+        delta.sync_calls,
+        WRITES
+    );
+}
+
+#[test]
+fn always_mode_syncs_every_write() {
+    let delta = write_delta(&create_db(DurabilityConfig::Always));
+    assert!(
+        delta.wal_appends >= WRITES,
+        "always mode: expected at least {} WAL appends, got {}",
+        WRITES,
+        delta.wal_appends
+    );
+    assert!(
+        delta.sync_calls >= WRITES,
+        "always mode should sync every commit: {} syncs for {} writes",
+        delta.sync_calls,
+        WRITES
+    );
+}
+
+#[test]
+fn standard_sync_batch_of_one_syncs_every_write() {
+    let mode = DurabilityConfig::StandardSync {
+        interval_ms: 60_000,
+        batch_size: 1,
+    };
+    let delta = write_delta(&create_db(mode));
+    assert!(
+        delta.sync_calls >= WRITES,
+        "{}: a batch of one write should sync every write: {} syncs for {} writes",
+        mode,
+        delta.sync_calls,
+        WRITES
+    );
+}
+
+#[test]
+fn standard_sync_large_batch_batches_syncs() {
+    let mode = DurabilityConfig::StandardSync {
+        interval_ms: 60_000,
+        batch_size: WRITES * 10,
+    };
+    let delta = write_delta(&create_db(mode));
+    assert!(delta.wal_appends >= WRITES, "{} should append every write", mode);
+    assert!(
+        delta.sync_calls < WRITES / 2,
+        "{}: expected few syncs, got {} for {} writes",
+        mode,
+        delta.sync_calls,
+        WRITES
+    );
+}
+
+// =============================================================================
+// Modes are distinguishable from each other
+// =============================================================================
+
+#[test]
+fn each_mode_has_distinct_wal_behavior() {
+    let deltas: Vec<(DurabilityConfig, WalCounters)> = DurabilityConfig::ALL
+        .iter()
+        .map(|&mode| (mode, write_delta(&create_db(mode))))
+        .collect();
+
+    let [(_, cache), (_, standard), (_, always)] = deltas.as_slice() else {
+        panic!("expected three durability modes, got {}", deltas.len());
+    };
+    assert!(
+        cache.wal_appends < standard.wal_appends,
+        "standard should append to the WAL, cache should not"
+    );
+    assert!(
+        standard.sync_calls < always.sync_calls,
+        "always should sync more than standard ({} vs {})",
+        always.sync_calls,
+        standard.sync_calls
+    );
+}
+
+#[test]
+fn mode_labels_are_unique() {
+    let labels: Vec<String> = DurabilityConfig::ALL.iter().map(|m| m.label()).collect();
+    assert_eq!(labels, vec!["cache", "standard", "always"]);
+    for mode in DurabilityConfig::ALL {
+        assert_eq!(mode.to_string(), mode.label());
+    }
+}
@@ -233,3 +233,164 @@ fn collection_info_reflects_state() {
     let info = collections.iter().find(|c| c.name == "vecs").unwrap();
     assert_eq!(info.dimension, 128);
 }
+
+#[test]
+fn list_collections_at_scale() {
+    // 10K collections with distinct dimension/metric pairs: each must be
+    // listed exactly once with its own metadata.
+    const N: usize = 10_000;
+    let metrics = [DistanceMetric::Cosine, DistanceMetric::Euclidean, DistanceMetric::DotProduct];
+    let dim_of = |i: usize| 1 + i % 1024;
+
+    let db = db();
+    for i in 0..N {
+        db.vector_create_collection(&format!("col_{:05}", i), dim_of(i) as u64, metrics[i % 3])
+            .unwrap();
+    }
+
+    let collections = db.vector_list_collections().unwrap();
+    assert_eq!(collections.len(), N);
+
+    let by_name: HashMap<&str, _> = collections.iter().map(|c| (c.name.as_str(), c)).collect();
+    assert_eq!(by_name.len(), N, "a collection was listed more than once");
+    for i in 0..N {
+        let name = format!("col_{:05}", i);
+        let info = by_name.get(name.as_str()).unwrap_or_else(|| panic!("{} not listed", name));
+        assert_eq!(info.dimension as usize, dim_of(i), "dimension of {}", name);
+        assert_eq!(info.metric, metrics[i % 3], "metric of {}", name);
+    }
+}
+
+// =============================================================================
+// Concurrent upserts
+// =============================================================================
+
+#[test]
+fn upsert_same_value_is_idempotent() {
+    let db = db();
+    db.vector_create_collection("vecs", 3, DistanceMetric::Euclidean).unwrap();
+    for _ in 0..10 {
+        db.vector_upsert("vecs", "v1", vec![1.0, 2.0, 3.0], None).unwrap();
+    }
+    db.vector_upsert("vecs", "v2", vec![4.0, 5.0, 6.0], None).unwrap();
+
+    let data = db.vector_get("vecs", "v1").unwrap().unwrap();
+    assert_eq!(data.data.embedding, vec![1.0, 2.0, 3.0]);
+    let results = db.vector_search("vecs", vec![1.0, 2.0, 3.0], 10).unwrap();
+    assert_eq!(results.len(), 2, "repeated upserts must not add entries");
+}
+
+/// Writers tag every embedding as `[key, id, id, ...]`, where `id` is unique
+/// per (writer, round). A torn write shows up as a mix of ids, a misplaced
+/// one as the wrong key in slot 0.
+const STRESS_DIM: usize = 8;
+const STRESS_KEYS: usize = 16;
+const STRESS_WRITERS: usize = 4;
+const STRESS_ROUNDS: usize = 200;
+
+fn stress_embedding(key: usize, id: usize) -> Vec<f32> {
+    let mut e = vec![id as f32; STRESS_DIM];
+    e[0] = key as f32;
+    e
+}
+
+fn stress_write_id(writer: usize, round: usize) -> usize {
+    1 + writer * STRESS_ROUNDS + round
+}
+
+/// Panics unless `embedding` is exactly one write to `key`.
+fn assert_whole_write(key: usize, embedding: &[f32]) -> usize {
+    assert_eq!(embedding.len(), STRESS_DIM, "key {}: wrong dimension", key);
+    assert_eq!(embedding[0], key as f32, "key {}: embedding belongs to another key", key);
+    let id = embedding[1];
+    assert!(
+        embedding[1..].iter().all(|&x| x == id),
+        "key {}: torn embedding {:?}",
+        key,
+        embedding
+    );
+    let id = id as usize;
+    assert!(
+        (stress_write_id(0, 0)..=stress_write_id(STRESS_WRITERS - 1, STRESS_ROUNDS - 1)).contains(&id),
+        "key {}: id {} was never written",
+        key,
+        id
+    );
+    id
+}
+
+#[test]
+fn concurrent_upserts_same_keys() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let db = db();
+    db.vector_create_collection("vecs", STRESS_DIM as u64, DistanceMetric::Euclidean).unwrap();
+    let key_name = |k: usize| format!("k{:02}", k);
+    for k in 0..STRESS_KEYS {
+        db.vector_upsert("vecs", &key_name(k), stress_embedding(k, stress_write_id(0, 0)), None)
+            .unwrap();
+    }
+
+    let done = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        let writers: Vec<_> = (0..STRESS_WRITERS)
+            .map(|w| {
+                let db = &db;
+                scope.spawn(move || {
+                    for r in 0..STRESS_ROUNDS {
+                        // Writers walk the keys in different orders to collide more
+                        for i in 0..STRESS_KEYS {
+                            let k = (i + w * 5) % STRESS_KEYS;
+                            let e = stress_embedding(k, stress_write_id(w, r));
+                            db.vector_upsert("vecs", &key_name(k), e, None).unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let (db, done) = (&db, &done);
+        let reader = scope.spawn(move || {
+            let mut reads = 0;
+            while !done.load(Ordering::Acquire) {
+                let k = reads % STRESS_KEYS;
+                let data = db
+                    .vector_get("vecs", &key_name(k))
+                    .unwrap()
+                    .expect("key vanished mid-write");
+                assert_whole_write(k, &data.data.embedding);
+
+                // Every key exists throughout, so a full search must see each once
+                let results = db
+                    .vector_search("vecs", stress_embedding(k, 0), STRESS_KEYS as u64)
+                    .unwrap();
+                let mut seen: Vec<&str> = results.iter().map(|m| m.key.as_str()).collect();
+                seen.sort_unstable();
+                seen.dedup();
+                assert_eq!(seen.len(), STRESS_KEYS, "search returned {} distinct keys", seen.len());
+                reads += 1;
+            }
+            reads
+        });
+
+        for w in writers {
+            w.join().unwrap();
+        }
+        done.store(true, Ordering::Release);
+        assert!(reader.join().unwrap() > 0);
+    });
+
+    // Last writer wins: each key holds some writer's final write to it
+    let last_writes: Vec<usize> = (0..STRESS_WRITERS)
+        .map(|w| stress_write_id(w, STRESS_ROUNDS - 1))
+        .collect();
+    for k in 0..STRESS_KEYS {
+        let data = db.vector_get("vecs", &key_name(k)).unwrap().unwrap();
+        let id = assert_whole_write(k, &data.data.embedding);
+        assert!(last_writes.contains(&id), "key {}: holds id {}, not a final write", k, id);
+
+        // Search sees the final embedding, not an earlier one
+        let results = db.vector_search("vecs", data.data.embedding.clone(), 1).unwrap();
+        assert_eq!(results[0].key, key_name(k), "stale index entry for key {}", k);
+    }
+}
@@ -0,0 +1,108 @@
+// Golden hashes for the seeded workload generators.
+//
+// Results from two machines should differ only because of the hardware. This
+// pins the exact operation sequences the YCSB bench replays (ops and keys)
+// and the vectors the ANN bench generates, hashed with FNV-1a so the golden
+// values don't depend on std's hasher. The generators use libm functions
+// (`powf`, `ln`, `cos`), so a platform whose libm rounds differently fails
+// here instead of silently producing a different workload.
+//
+// If a generator change is intentional, update the hash and say so in the
+// commit: results recorded before and after it are not comparable.
+
+#[allow(dead_code)]
+#[path = "../benches/ycsb/workloads.rs"]
+mod workloads;
+
+#[allow(dead_code)]
+#[path = "../benches/ann/dataset.rs"]
+mod dataset;
+
+use dataset::{compute_ground_truth, generate_dataset};
+use workloads::{ycsb_key, OpStream, Operation, ALL_WORKLOADS, RUN_SEED};
+
+/// Seed of the synthetic ANN dataset (`SEED` in benches/ann/ann.rs).
+const ANN_SEED: u64 = 0xA00_2026;
+
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u64(&mut self, v: u64) {
+        self.write(&v.to_le_bytes());
+    }
+}
+
+fn op_tag(op: Operation) -> u8 {
+    match op {
+        Operation::Read => b'r',
+        Operation::Update => b'u',
+        Operation::Insert => b'i',
+        Operation::Scan => b's',
+        Operation::ReadModifyWrite => b'm',
+    }
+}
+
+#[test]
+fn ycsb_operation_sequences_match_golden() {
+    const GOLDEN: &[(char, u64)] = &[
+        ('a', 0xf5dc_50cd_f9b2_8b09),
+        ('b', 0x0bec_6401_35b7_ac8f),
+        ('c', 0x29b4_6891_2b0e_8ad5),
+        ('d', 0x810d_eb4a_4729_d25e),
+        ('e', 0x7633_9c08_5ad8_b017),
+        ('f', 0x67d5_6a96_fd82_2649),
+    ];
+
+    for workload in ALL_WORKLOADS {
+        let mut h = Fnv::new();
+        for (op, idx) in OpStream::new(workload, 1_000, RUN_SEED).take(10_000) {
+            h.write(&[op_tag(op)]);
+            h.write(ycsb_key(idx).as_bytes());
+        }
+        let golden = GOLDEN.iter().find(|(l, _)| *l == workload.label).unwrap().1;
+        assert_eq!(
+            h.0, golden,
+            "workload {} drifted: got {:#018x}",
+            workload.label, h.0
+        );
+    }
+}
+
+#[test]
+fn ann_synthetic_dataset_matches_golden() {
+    let d = generate_dataset(2_000, 100, 32, ANN_SEED);
+
+    let mut h = Fnv::new();
+    for (key, v) in d.train_keys.iter().zip(&d.train_vectors) {
+        h.write(key.as_bytes());
+        for x in v {
+            h.write(&x.to_bits().to_le_bytes());
+        }
+    }
+    for v in &d.query_vectors {
+        for x in v {
+            h.write(&x.to_bits().to_le_bytes());
+        }
+    }
+    assert_eq!(h.0, 0x5449_4147_3922_7ae7, "vectors drifted: got {:#018x}", h.0);
+
+    let gt = compute_ground_truth(&d, 10);
+    let mut h = Fnv::new();
+    for nn in &gt.neighbors {
+        for &i in nn {
+            h.write_u64(i as u64);
+        }
+    }
+    assert_eq!(h.0, 0xc23a_24ae_0ee1_2de0, "ground truth drifted: got {:#018x}", h.0);
+}